@@ -0,0 +1,204 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Opt-in usage statistics for the user's own dashboard: which features get used, how
+// often launches succeed and how fast downloads run. Events go to the local database
+// only; nothing here talks to the network, and nothing is recorded until
+// `usage_analytics_enabled` is switched on.
+
+const MAX_EVENTS: usize = 50_000;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Commands counted as use of a feature
+const FEATURES: &[(&str, &[&str])] = &[
+    ("launch", &["launch_model", "launch_model_with_preset", "launch_model_with_half_context", "queue_model_launch", "launch_model_batch"]),
+    ("launch_external", &["launch_model_external", "launch_model_with_preset_external"]),
+    ("model_download", &["download_model", "download_from_url", "download_hf_file"]),
+    ("llamacpp_install", &["download_llamacpp_asset", "download_llamacpp_asset_to_version", "install_local_llamacpp_zip"]),
+    ("model_pack", &["install_model_pack"]),
+    ("tracker", &["refresh_tracker_data", "classify_tracker_models"]),
+    ("mcp_tools", &["call_mcp_tool"]),
+    ("translation", &["translate_chat_message"]),
+    ("tunnel", &["start_tunnel"]),
+    ("energy_report", &["get_energy_report"]),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnalyticsEvent {
+    Feature { name: String },
+    /// `model` is the file name only
+    Launch { model: String, success: bool },
+    Download { bytes: u64, seconds: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEvent {
+    pub event: AnalyticsEvent,
+    pub recorded_at: DateTime<Utc>,
+}
+
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Store `event` in the background when analytics are on
+pub fn record(event: AnalyticsEvent) {
+    if !is_enabled() {
+        return;
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Err(e) = crate::storage::with(|db| db.analytics().record(&event, MAX_EVENTS)) {
+            eprintln!("[Analytics] {}", e);
+        }
+    });
+}
+
+/// Count an invoked command toward its feature, if it belongs to one
+fn record_command(command: &str) {
+    if let Some((feature, _)) = FEATURES.iter().find(|(_, commands)| commands.contains(&command)) {
+        record(AnalyticsEvent::Feature { name: feature.to_string() });
+    }
+}
+
+/// Wrap the command handler so feature use is counted as commands come in
+pub fn counting_commands<R: tauri::Runtime>(
+    handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        record_command(invoke.message.command());
+        handler(invoke)
+    }
+}
+
+pub fn record_launch(model_path: &str, success: bool) {
+    let model = Path::new(model_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| model_path.to_string());
+    record(AnalyticsEvent::Launch { model, success });
+}
+
+pub fn record_download(bytes: u64, seconds: i64) {
+    if bytes > 0 && seconds > 0 {
+        record(AnalyticsEvent::Download { bytes, seconds: seconds as f64 });
+    }
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct LaunchStats {
+    pub model: String,
+    pub total: u32,
+    pub succeeded: u32,
+    pub success_rate: f64,
+}
+
+impl LaunchStats {
+    fn add(&mut self, success: bool) {
+        self.total += 1;
+        self.succeeded += success as u32;
+        self.success_rate = self.succeeded as f64 / self.total as f64;
+    }
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct UsageSummary {
+    /// Feature name and use count, most used first
+    pub features: Vec<(String, u32)>,
+    pub launches: LaunchStats,
+    pub launches_by_model: Vec<LaunchStats>,
+    pub downloads: u32,
+    pub downloaded_bytes: u64,
+    pub average_download_bytes_per_sec: f64,
+}
+
+pub fn summarize(events: &[RecordedEvent], since: Option<DateTime<Utc>>) -> UsageSummary {
+    let mut features: BTreeMap<&str, u32> = BTreeMap::new();
+    let mut by_model: BTreeMap<&str, LaunchStats> = BTreeMap::new();
+    let mut summary = UsageSummary { launches: LaunchStats { model: "*".to_string(), ..Default::default() }, ..Default::default() };
+    let mut download_seconds = 0.0;
+
+    for recorded in events.iter().filter(|e| since.is_none_or(|since| e.recorded_at >= since)) {
+        match &recorded.event {
+            AnalyticsEvent::Feature { name } => *features.entry(name.as_str()).or_default() += 1,
+            AnalyticsEvent::Launch { model, success } => {
+                summary.launches.add(*success);
+                by_model
+                    .entry(model.as_str())
+                    .or_insert_with(|| LaunchStats { model: model.clone(), ..Default::default() })
+                    .add(*success);
+            }
+            AnalyticsEvent::Download { bytes, seconds } => {
+                summary.downloads += 1;
+                summary.downloaded_bytes += bytes;
+                download_seconds += seconds;
+            }
+        }
+    }
+
+    summary.features = features.into_iter().map(|(name, count)| (name.to_string(), count)).collect();
+    summary.features.sort_by_key(|feature| std::cmp::Reverse(feature.1));
+    summary.launches_by_model = by_model.into_values().collect();
+    summary.launches_by_model.sort_by_key(|model| std::cmp::Reverse(model.total));
+    if download_seconds > 0.0 {
+        summary.average_download_bytes_per_sec = summary.downloaded_bytes as f64 / download_seconds;
+    }
+    summary
+}
+
+pub fn usage_analytics(since_days: Option<u32>) -> Result<serde_json::Value, String> {
+    let since = since_days.map(|days| Utc::now() - chrono::Duration::days(days as i64));
+    let events = crate::storage::with(|db| db.analytics().events())?;
+    let summary = summarize(&events, since);
+    Ok(serde_json::json!({
+        "enabled": is_enabled(),
+        "local_only": true,
+        "since_days": since_days,
+        "first_recorded_at": events.first().map(|e| e.recorded_at),
+        "total_events": events.len(),
+        "summary": summary,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(event: AnalyticsEvent, hours_ago: i64) -> RecordedEvent {
+        RecordedEvent { event, recorded_at: Utc::now() - chrono::Duration::hours(hours_ago) }
+    }
+
+    #[test]
+    fn summarizes_features_launches_and_downloads() {
+        let launch = |model: &str, success| AnalyticsEvent::Launch { model: model.to_string(), success };
+        let feature = |name: &str| AnalyticsEvent::Feature { name: name.to_string() };
+        let events = vec![
+            at(feature("launch"), 1),
+            at(feature("tracker"), 1),
+            at(feature("launch"), 2),
+            at(launch("qwen.gguf", true), 1),
+            at(launch("qwen.gguf", false), 1),
+            at(launch("llama.gguf", true), 1),
+            at(AnalyticsEvent::Download { bytes: 3_000, seconds: 1.0 }, 1),
+            at(AnalyticsEvent::Download { bytes: 1_000, seconds: 3.0 }, 1),
+            at(feature("translation"), 72),
+        ];
+
+        let summary = summarize(&events, Some(Utc::now() - chrono::Duration::days(1)));
+        assert_eq!(summary.features, vec![("launch".to_string(), 2), ("tracker".to_string(), 1)]);
+        assert_eq!((summary.launches.total, summary.launches.succeeded), (3, 2));
+        assert_eq!(summary.launches_by_model[0].model, "qwen.gguf");
+        assert_eq!(summary.launches_by_model[0].success_rate, 0.5);
+        assert_eq!(summary.downloads, 2);
+        assert_eq!(summary.average_download_bytes_per_sec, 1_000.0);
+
+        assert_eq!(summarize(&events, None).features.len(), 3);
+    }
+}
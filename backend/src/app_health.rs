@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use crate::models::GlobalConfig;
+use crate::AppState;
+
+// Startup self-test. It runs in the background once the app is up and checks the data
+// directories, the database, whether any llama.cpp build is installed and whether the
+// proxy ports are free, so the UI can explain a degraded state up front instead of
+// commands failing later. `get_app_health` returns the last report.
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub id: String,
+    pub status: CheckStatus,
+    pub message: String,
+    /// Degraded-mode flag the UI can key on, e.g. `no_llamacpp`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flag: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct AppHealth {
+    pub checked_at: Option<DateTime<Utc>>,
+    pub checking: bool,
+    pub degraded: bool,
+    pub flags: Vec<String>,
+    pub checks: Vec<HealthCheck>,
+}
+
+static LAST_REPORT: Mutex<Option<AppHealth>> = Mutex::new(None);
+
+fn check(id: &str, status: CheckStatus, message: impl Into<String>, flag: Option<&str>) -> HealthCheck {
+    HealthCheck { id: id.to_string(), status, message: message.into(), flag: flag.map(str::to_string) }
+}
+
+fn summarize(checks: Vec<HealthCheck>) -> AppHealth {
+    let flags = checks
+        .iter()
+        .filter(|c| c.status != CheckStatus::Ok)
+        .filter_map(|c| c.flag.clone())
+        .collect();
+    AppHealth {
+        checked_at: Some(Utc::now()),
+        checking: false,
+        degraded: checks.iter().any(|c| c.status != CheckStatus::Ok),
+        flags,
+        checks,
+    }
+}
+
+fn writable(dir: &Path) -> bool {
+    let probe = dir.join(".arandu-write-test");
+    let ok = std::fs::create_dir_all(dir).is_ok() && std::fs::write(&probe, b"ok").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    ok
+}
+
+fn check_directories(config: &GlobalConfig) -> Vec<HealthCheck> {
+    let mut checks = Vec::new();
+    match crate::arandu_base_dir() {
+        Ok(base) if writable(&base) => checks.push(check("data_dir", CheckStatus::Ok, format!("{} is writable", base.display()), None)),
+        Ok(base) => checks.push(check("data_dir", CheckStatus::Error, format!("Cannot write to {}; settings and chats will not be saved", base.display()), Some("data_dir_readonly"))),
+        Err(e) => checks.push(check("data_dir", CheckStatus::Error, e, Some("data_dir_readonly"))),
+    }
+
+    let models_dir = Path::new(&config.models_directory);
+    if config.models_directory.trim().is_empty() || !models_dir.is_dir() {
+        checks.push(check("models_dir", CheckStatus::Warning, format!("Models folder '{}' does not exist", config.models_directory), Some("no_models_dir")));
+    } else {
+        checks.push(check("models_dir", CheckStatus::Ok, format!("Models folder {}", models_dir.display()), None));
+    }
+    let missing: Vec<&String> = config.additional_models_directories.iter().filter(|d| !Path::new(d).is_dir()).collect();
+    if !missing.is_empty() {
+        checks.push(check(
+            "extra_models_dirs",
+            CheckStatus::Warning,
+            format!("Additional models folders not found: {}", missing.iter().map(|d| d.as_str()).collect::<Vec<_>>().join(", ")),
+            None,
+        ));
+    }
+    checks
+}
+
+fn check_database() -> HealthCheck {
+    match crate::storage::check_integrity() {
+        Ok(()) => match crate::storage::rebuilt_notice() {
+            Some(notice) => check("database", CheckStatus::Warning, notice, Some("database_rebuilt")),
+            None => check("database", CheckStatus::Ok, "Database integrity ok", None),
+        },
+        Err(e) => check("database", CheckStatus::Error, format!("Database check failed: {}", e), Some("database_unavailable")),
+    }
+}
+
+/// The active build's server, else any build under `<executable_folder>/versions`
+pub fn find_llama_server(config: &GlobalConfig) -> Option<PathBuf> {
+    let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+    if let Some(active) = config.active_executable_folder.as_deref() {
+        let server = Path::new(active).join(exe_name);
+        if server.is_file() {
+            return Some(server);
+        }
+    }
+    let root = Path::new(&config.executable_folder);
+    let direct = root.join(exe_name);
+    if direct.is_file() {
+        return Some(direct);
+    }
+    // versions/<version>/<backend>/ or the older flat versions/<version>/
+    let versions = std::fs::read_dir(root.join("versions")).ok()?;
+    for version in versions.flatten().map(|e| e.path()).filter(|p| p.is_dir()) {
+        let flat = version.join(exe_name);
+        if flat.is_file() {
+            return Some(flat);
+        }
+        let Ok(backends) = std::fs::read_dir(&version) else { continue };
+        if let Some(nested) = backends.flatten().map(|e| e.path().join(exe_name)).find(|p| p.is_file()) {
+            return Some(nested);
+        }
+    }
+    None
+}
+
+fn check_executable(config: &GlobalConfig) -> HealthCheck {
+    match find_llama_server(config) {
+        Some(server) => check("llama_cpp", CheckStatus::Ok, format!("llama-server found at {}", server.display()), None),
+        None => check("llama_cpp", CheckStatus::Error, "No llama.cpp installed; install a build from the llama.cpp releases window to launch models", Some("no_llamacpp")),
+    }
+}
+
+async fn check_ports(state: &AppState, config: &GlobalConfig) -> Vec<HealthCheck> {
+    // Our own servers hold these ports once they are up
+    let ours = state.openai_proxy.lock().await.is_some();
+    [("network_port", "Network server", config.network_server_port), ("proxy_port", "OpenAI proxy", config.openai_proxy_port)]
+        .into_iter()
+        .filter(|(_, _, port)| *port != 0)
+        .map(|(id, label, port)| {
+            if ours {
+                check(id, CheckStatus::Ok, format!("{} is serving on port {}", label, port), None)
+            } else if crate::process::is_port_available(port) {
+                check(id, CheckStatus::Ok, format!("Port {} is free", port), None)
+            } else {
+                check(id, CheckStatus::Warning, format!("Port {} for the {} is used by another program", port, label), Some("port_in_use"))
+            }
+        })
+        .collect()
+}
+
+pub async fn run(state: &AppState) -> AppHealth {
+    let config = state.config.lock().await.clone();
+    let mut checks = {
+        let config = config.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut checks = check_directories(&config);
+            checks.push(check_database());
+            checks.push(check_executable(&config));
+            checks
+        })
+        .await
+        .unwrap_or_else(|e| vec![check("self_test", CheckStatus::Error, format!("Self-test failed: {}", e), None)])
+    };
+    if state.tracker_manager.lock().await.is_none() {
+        checks.push(check("tracker", CheckStatus::Error, "Tracker database could not be opened; the tracker is unavailable", Some("tracker_unavailable")));
+    }
+    checks.extend(check_ports(state, &config).await);
+
+    let report = summarize(checks);
+    if report.degraded {
+        println!("[Health] Degraded: {}", report.flags.join(", "));
+    }
+    *LAST_REPORT.lock().unwrap_or_else(|p| p.into_inner()) = Some(report.clone());
+    report
+}
+
+/// Last report; `checking` while the startup self-test has not finished
+pub fn current() -> AppHealth {
+    LAST_REPORT
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .clone()
+        .unwrap_or_else(|| AppHealth { checking: true, ..Default::default() })
+}
+
+/// Run the self-test in the background and emit `app-health` when done
+pub fn spawn(state: AppState, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let report = run(&state).await;
+        crate::events::emit(&app_handle, &report);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_nested_builds_and_flags_degraded_checks() {
+        let root = std::env::temp_dir().join(format!("arandu-health-{}", std::process::id()));
+        let build = root.join("versions").join("b5000").join("cuda");
+        std::fs::create_dir_all(&build).unwrap();
+        let config = GlobalConfig { executable_folder: root.to_string_lossy().to_string(), active_executable_folder: None, ..Default::default() };
+        assert_eq!(find_llama_server(&config), None);
+
+        let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+        std::fs::write(build.join(exe_name), b"").unwrap();
+        assert_eq!(find_llama_server(&config), Some(build.join(exe_name)));
+        let _ = std::fs::remove_dir_all(&root);
+
+        let report = summarize(vec![
+            check("database", CheckStatus::Ok, "ok", None),
+            check("llama_cpp", CheckStatus::Error, "missing", Some("no_llamacpp")),
+        ]);
+        assert!(report.degraded);
+        assert_eq!(report.flags, vec!["no_llamacpp"]);
+        assert!(!summarize(vec![check("database", CheckStatus::Ok, "ok", Some("unused"))]).degraded);
+    }
+}
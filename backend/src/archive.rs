@@ -0,0 +1,373 @@
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::{Component, Path, PathBuf};
+
+// Archive extraction for downloads and local installs: zip, tar.gz and 7z. Entry names
+// come from the archive and are not trusted — every one is checked to land inside the
+// destination (no `..`, absolute paths or drive letters), nothing is written through a
+// symlink an earlier entry created, and tar symlinks may only point inside the
+// destination, without going through a symlink an earlier entry created either. Entries are streamed to disk, never held in memory whole. This is
+// blocking I/O; async callers run it on `spawn_blocking`.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveKind {
+    Zip,
+    TarGz,
+    SevenZip,
+}
+
+impl ArchiveKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".7z") {
+            Some(Self::SevenZip)
+        } else {
+            None
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_name(&path.file_name()?.to_string_lossy())
+    }
+}
+
+/// Where extraction is, after each entry
+#[derive(Debug, Clone)]
+pub struct Progress {
+    pub percent: u8,
+    pub completed_files: usize,
+    /// 0 when the format does not say up front (tar.gz)
+    pub total_files: usize,
+    pub entry: String,
+}
+
+/// Path of the archive entry `name` under `destination`, or Err when it would land outside
+pub fn entry_path(destination: &Path, name: &str) -> Result<PathBuf, String> {
+    let unsafe_entry = || format!("Unsafe archive entry path blocked: {}", name);
+    if name.starts_with('/') || name.starts_with('\\') {
+        return Err(unsafe_entry());
+    }
+    let mut relative = PathBuf::new();
+    for part in name.split(['/', '\\']) {
+        match part {
+            "" | "." => {}
+            ".." => return Err(unsafe_entry()),
+            // Drive letters, and NTFS alternate data streams
+            _ if part.contains(':') => return Err(unsafe_entry()),
+            _ => relative.push(part),
+        }
+    }
+    Ok(destination.join(relative))
+}
+
+/// Whether a symlink at `link` (relative to `destination`) pointing to `target` stays inside
+/// it. `..` is only resolved lexically, so a target that passes through a symlink already
+/// extracted (whose `..` would go elsewhere) is refused.
+fn link_stays_inside(destination: &Path, link: &Path, target: &Path) -> bool {
+    let mut resolved: Vec<&std::ffi::OsStr> = link
+        .parent()
+        .map(|parent| parent.components().filter_map(|c| match c {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        }).collect())
+        .unwrap_or_default();
+    let mut components = target.components().peekable();
+    while let Some(component) = components.next() {
+        match component {
+            Component::Normal(part) => {
+                resolved.push(part);
+                let through = destination.join(resolved.iter().collect::<PathBuf>());
+                if components.peek().is_some() && fs::symlink_metadata(through).is_ok_and(|m| m.file_type().is_symlink()) {
+                    return false;
+                }
+            }
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if resolved.pop().is_none() {
+                    return false;
+                }
+            }
+            Component::RootDir | Component::Prefix(_) => return false,
+        }
+    }
+    true
+}
+
+/// Refuse to write `path` through a symlink an earlier entry created
+fn check_parents(destination: &Path, path: &Path) -> Result<(), String> {
+    let Ok(relative) = path.strip_prefix(destination) else {
+        return Err(format!("Unsafe archive entry path blocked: {}", path.display()));
+    };
+    let mut current = destination.to_path_buf();
+    let mut parts = relative.components().peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            break;
+        }
+        current.push(part);
+        if fs::symlink_metadata(&current).is_ok_and(|m| m.file_type().is_symlink()) {
+            return Err(format!("Archive entry {} goes through a symlink", relative.display()));
+        }
+    }
+    Ok(())
+}
+
+fn create_dir(destination: &Path, path: &Path) -> Result<(), String> {
+    check_parents(destination, path)?;
+    fs::create_dir_all(path).map_err(|e| format!("Failed to create directory '{}': {}", path.display(), e))
+}
+
+fn write_file(destination: &Path, path: &Path, contents: &mut dyn Read, mode: Option<u32>) -> Result<(), String> {
+    check_parents(destination, path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create parent directory '{}': {}", parent.display(), e))?;
+    }
+    // Replace rather than write into an existing file (or through an existing link)
+    if fs::symlink_metadata(path).is_ok() {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove existing file '{}': {}", path.display(), e))?;
+    }
+    let mut file = File::create(path).map_err(|e| format!("Failed to create '{}': {}", path.display(), e))?;
+    std::io::copy(contents, &mut file).map_err(|e| format!("Failed to extract '{}': {}", path.display(), e))?;
+    // Keep the executable bit of llama-server and friends
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o777))
+            .map_err(|e| format!("Failed to set permissions on '{}': {}", path.display(), e))?;
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    Ok(())
+}
+
+fn percent(done: u64, total: u64) -> u8 {
+    if total == 0 {
+        return 0;
+    }
+    (done.min(total) * 100 / total) as u8
+}
+
+/// Extract `archive` into `destination`, calling `on_progress` after every entry
+pub fn extract(archive: &Path, destination: &Path, on_progress: &mut dyn FnMut(&Progress)) -> Result<(), String> {
+    let kind = ArchiveKind::from_path(archive)
+        .ok_or_else(|| format!("{} is not a .zip, .tar.gz or .7z archive", archive.display()))?;
+    fs::create_dir_all(destination).map_err(|e| format!("Failed to create '{}': {}", destination.display(), e))?;
+    match kind {
+        ArchiveKind::Zip => extract_zip(archive, destination, on_progress),
+        ArchiveKind::TarGz => extract_tar_gz(archive, destination, on_progress),
+        ArchiveKind::SevenZip => extract_7z(archive, destination, on_progress),
+    }
+}
+
+fn extract_zip(archive: &Path, destination: &Path, on_progress: &mut dyn FnMut(&Progress)) -> Result<(), String> {
+    let file = File::open(archive).map_err(|e| format!("Failed to open zip file: {}", e))?;
+    let mut zip = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+    let total_files = zip.len();
+    for i in 0..total_files {
+        let mut entry = zip.by_index(i).map_err(|e| format!("Failed to read zip entry: {}", e))?;
+        let name = entry.name().to_string();
+        let path = entry_path(destination, &name)?;
+        if entry.is_dir() {
+            create_dir(destination, &path)?;
+        } else {
+            let mode = entry.unix_mode();
+            write_file(destination, &path, &mut entry, mode)?;
+        }
+        on_progress(&Progress {
+            percent: percent(i as u64 + 1, total_files as u64),
+            completed_files: i + 1,
+            total_files,
+            entry: name,
+        });
+    }
+    Ok(())
+}
+
+/// Counts the compressed bytes read, for progress through a tar.gz of unknown entry count
+struct CountingReader<R> {
+    inner: R,
+    read: std::rc::Rc<std::cell::Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read.set(self.read.get() + n as u64);
+        Ok(n)
+    }
+}
+
+fn extract_tar_gz(archive: &Path, destination: &Path, on_progress: &mut dyn FnMut(&Progress)) -> Result<(), String> {
+    let file = File::open(archive).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let read = std::rc::Rc::new(std::cell::Cell::new(0));
+    let counting = CountingReader { inner: BufReader::new(file), read: read.clone() };
+    let mut tar = tar::Archive::new(flate2::read::GzDecoder::new(counting));
+
+    let entries = tar.entries().map_err(|e| format!("Failed to read archive: {}", e))?;
+    let mut completed_files = 0;
+    for entry in entries {
+        let mut entry = entry.map_err(|e| format!("Failed to read archive entry: {}", e))?;
+        let name = entry.path().map_err(|e| format!("Invalid archive entry name: {}", e))?.to_string_lossy().to_string();
+        let path = entry_path(destination, &name)?;
+        let entry_type = entry.header().entry_type();
+
+        if entry_type.is_dir() {
+            create_dir(destination, &path)?;
+        } else if entry_type.is_file() {
+            let mode = entry.header().mode().ok();
+            write_file(destination, &path, &mut entry, mode)?;
+        } else if entry_type.is_symlink() || entry_type.is_hard_link() {
+            let target = entry
+                .link_name()
+                .map_err(|e| format!("Invalid link in archive: {}", e))?
+                .ok_or_else(|| format!("Link {} has no target", name))?
+                .into_owned();
+            let relative = path.strip_prefix(destination).unwrap_or(&path).to_path_buf();
+            if entry_type.is_hard_link() {
+                // Hard link targets name another entry of the archive
+                let source = entry_path(destination, &target.to_string_lossy())?;
+                check_parents(destination, &source)?;
+                write_file(destination, &path, &mut File::open(&source).map_err(|e| format!("Failed to link {}: {}", name, e))?, None)?;
+            } else if !link_stays_inside(destination, &relative, &target) {
+                return Err(format!("Archive link {} points outside the destination: {}", name, target.display()));
+            } else {
+                write_symlink(destination, &path, &target)?;
+            }
+        } else {
+            println!("[Archive] Skipping special entry {}", name);
+            continue;
+        }
+
+        completed_files += 1;
+        on_progress(&Progress {
+            percent: percent(read.get(), size),
+            completed_files,
+            total_files: 0,
+            entry: name,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn write_symlink(destination: &Path, path: &Path, target: &Path) -> Result<(), String> {
+    check_parents(destination, path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create parent directory '{}': {}", parent.display(), e))?;
+    }
+    if fs::symlink_metadata(path).is_ok() {
+        fs::remove_file(path).map_err(|e| format!("Failed to remove existing file '{}': {}", path.display(), e))?;
+    }
+    std::os::unix::fs::symlink(target, path).map_err(|e| format!("Failed to create link '{}': {}", path.display(), e))
+}
+
+/// Symlinks need privileges on Windows; the linked file is copied instead once it exists
+#[cfg(not(unix))]
+fn write_symlink(destination: &Path, path: &Path, target: &Path) -> Result<(), String> {
+    let source = path.parent().unwrap_or(destination).join(target);
+    match File::open(&source) {
+        Ok(mut file) => write_file(destination, path, &mut file, None),
+        Err(_) => {
+            println!("[Archive] Skipping link {} to {}", path.display(), target.display());
+            Ok(())
+        }
+    }
+}
+
+fn extract_7z(archive: &Path, destination: &Path, on_progress: &mut dyn FnMut(&Progress)) -> Result<(), String> {
+    let mut reader = sevenz_rust::SevenZReader::open(archive, sevenz_rust::Password::empty())
+        .map_err(|e| format!("Failed to read 7z archive: {}", e))?;
+    let total_files = reader.archive().files.len();
+    let mut completed_files = 0;
+    // The callback can only return the crate's error type; ours is kept aside
+    let mut failure = None;
+    reader
+        .for_each_entries(|entry, contents| {
+            let name = entry.name().to_string();
+            let result = entry_path(destination, &name).and_then(|path| {
+                if entry.is_directory() {
+                    create_dir(destination, &path)
+                } else {
+                    write_file(destination, &path, contents, None)
+                }
+            });
+            if let Err(e) = result {
+                failure = Some(e);
+                return Ok(false);
+            }
+            completed_files += 1;
+            on_progress(&Progress {
+                percent: percent(completed_files as u64, total_files as u64),
+                completed_files,
+                total_files,
+                entry: name,
+            });
+            Ok(true)
+        })
+        .map_err(|e| format!("Failed to extract 7z archive: {}", e))?;
+    failure.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_cannot_leave_the_destination() {
+        let destination = Path::new("/models/pack");
+        assert_eq!(entry_path(destination, "bin/llama-server").unwrap(), destination.join("bin").join("llama-server"));
+        assert_eq!(entry_path(destination, "./bin\\ggml.dll").unwrap(), destination.join("bin").join("ggml.dll"));
+        for name in ["../evil.sh", "bin/../../evil.sh", "/etc/passwd", "\\Windows\\evil.dll", "C:/evil.dll", "file.gguf:stream"] {
+            assert!(entry_path(destination, name).is_err(), "{} was allowed", name);
+        }
+
+        let inside = |link: &str, target: &str| link_stays_inside(destination, Path::new(link), Path::new(target));
+        assert!(inside("lib/libllama.so", "libllama.so.1"));
+        assert!(inside("bin/libggml.so", "../lib/libggml.so"));
+        assert!(!inside("libllama.so", "../outside.so"));
+        assert!(!inside("lib/x", "/usr/lib/libc.so"));
+
+        assert_eq!(ArchiveKind::from_name("llama-b6000-bin-ubuntu-x64.TAR.GZ"), Some(ArchiveKind::TarGz));
+        assert_eq!(ArchiveKind::from_name("model.7z"), Some(ArchiveKind::SevenZip));
+        assert_eq!(ArchiveKind::from_name("model.gguf"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_cannot_escape_through_earlier_symlinks() {
+        let root = std::env::temp_dir().join(format!("arandu-archive-{}", std::process::id()));
+        let destination = root.join("dest");
+        fs::create_dir_all(&destination).unwrap();
+        fs::write(root.join("secret"), b"outside").unwrap();
+
+        // d/l1 -> .. is the destination itself, but l2 -> d/l1/../secret is outside it
+        let archive = root.join("chain.tar.gz");
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(File::create(&archive).unwrap(), flate2::Compression::fast()));
+        let mut dir = tar::Header::new_gnu();
+        dir.set_entry_type(tar::EntryType::Directory);
+        dir.set_mode(0o755);
+        dir.set_size(0);
+        builder.append_data(&mut dir, "d", std::io::empty()).unwrap();
+        for (kind, link, target) in [
+            (tar::EntryType::Symlink, "d/l1", ".."),
+            (tar::EntryType::Symlink, "l2", "d/l1/../secret"),
+            (tar::EntryType::Link, "copied", "l2"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(kind);
+            header.set_size(0);
+            builder.append_link(&mut header, link, target).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let result = extract(&archive, &destination, &mut |_| {});
+        assert!(result.unwrap_err().contains("l2"));
+        assert!(!destination.join("copied").exists());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+use crate::models::{BackendBenchmarkResult, GlobalConfig};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, SystemTime};
+use tokio::process::Command as TokioCommand;
+use tokio_util::sync::CancellationToken;
+
+// Picks the fastest installed backend for a model by running a short llama-bench
+// per backend. Results are stored per model class so similar models reuse them
+// without benchmarking again.
+
+const BENCH_TIMEOUT: Duration = Duration::from_secs(300);
+const BENCH_PROMPT_TOKENS: &str = "128";
+const BENCH_GEN_TOKENS: &str = "32";
+
+fn bench_exe_name() -> &'static str {
+    if cfg!(windows) { "llama-bench.exe" } else { "llama-bench" }
+}
+
+/// Coarse class a benchmark result generalizes to: architecture plus a size bucket,
+/// since the relative speed of backends mostly depends on how much of the model fits in VRAM
+pub fn model_class(model_path: &str) -> String {
+    let path = Path::new(model_path);
+    let architecture = crate::scanner::extract_gguf_metadata(path)
+        .map(|m| m.architecture.to_lowercase())
+        .unwrap_or_else(|_| "unknown".to_string());
+    let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    format!("{}-{}", architecture, size_bucket(size))
+}
+
+fn size_bucket(bytes: u64) -> &'static str {
+    const GB: u64 = 1024 * 1024 * 1024;
+    match bytes {
+        b if b < 4 * GB => "xs",
+        b if b < 12 * GB => "s",
+        b if b < 32 * GB => "m",
+        b if b < 80 * GB => "l",
+        _ => "xl",
+    }
+}
+
+/// One build per backend to benchmark: the backend's active build when set,
+/// otherwise its newest install. Builds without llama-bench are skipped.
+pub fn benchmark_candidates(config: &GlobalConfig) -> Vec<(String, PathBuf)> {
+    let versions_dir = Path::new(&config.executable_folder).join("versions");
+    let mut newest: Vec<(String, PathBuf, Option<SystemTime>)> = Vec::new();
+
+    if let Ok(read_dir) = std::fs::read_dir(&versions_dir) {
+        for version_entry in read_dir.flatten() {
+            let Ok(backend_dir) = std::fs::read_dir(version_entry.path()) else { continue };
+            for backend_entry in backend_dir.flatten() {
+                let backend_path = backend_entry.path();
+                if !backend_path.join(bench_exe_name()).exists() {
+                    continue;
+                }
+                let backend = crate::detect_backend_type(&backend_entry.file_name().to_string_lossy());
+                let created = backend_entry.metadata().ok().and_then(|m| m.created().ok());
+                match newest.iter_mut().find(|(b, _, _)| *b == backend) {
+                    Some(existing) if existing.2 < created => *existing = (backend, backend_path, created),
+                    Some(_) => {}
+                    None => newest.push((backend, backend_path, created)),
+                }
+            }
+        }
+    }
+
+    let mut candidates: Vec<(String, PathBuf)> = newest.into_iter().map(|(b, p, _)| (b, p)).collect();
+    for (backend, active_path) in &config.active_backend_versions {
+        let active_path = PathBuf::from(active_path);
+        if !active_path.join(bench_exe_name()).exists() {
+            continue;
+        }
+        match candidates.iter_mut().find(|(b, _)| *b == *backend) {
+            Some(existing) => existing.1 = active_path,
+            None => candidates.push((backend.clone(), active_path)),
+        }
+    }
+    candidates.sort_by(|a, b| a.0.cmp(&b.0));
+    candidates
+}
+
+/// Run llama-bench from `build_path` against `model_path`; `cancel` kills it
+pub async fn run_benchmark(backend: &str, build_path: &Path, model_path: &str, cancel: &CancellationToken) -> BackendBenchmarkResult {
+    let mut result = BackendBenchmarkResult {
+        backend: backend.to_string(),
+        build_path: build_path.to_string_lossy().to_string(),
+        prompt_tps: None,
+        gen_tps: None,
+        error: None,
+        gpu_power: None,
+    };
+
+    let mut cmd = TokioCommand::new(build_path.join(bench_exe_name()));
+    cmd.args(["-m", &crate::long_path::extended_arg(model_path), "-p", BENCH_PROMPT_TOKENS, "-n", BENCH_GEN_TOKENS, "-r", "2", "-o", "json"])
+        .current_dir(build_path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if backend != "cpu" {
+        cmd.args(["-ngl", "99"]);
+    }
+
+    #[cfg(windows)]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let sampler = crate::system_monitor::PowerSampler::start();
+    let output = cancel.run_until_cancelled(tokio::time::timeout(BENCH_TIMEOUT, cmd.output())).await;
+    let gpu_power = sampler.finish().await;
+    let output = match output {
+        None => {
+            result.error = Some("llama-bench was cancelled".to_string());
+            return result;
+        }
+        Some(Ok(Ok(output))) => output,
+        Some(Ok(Err(e))) => {
+            result.error = Some(format!("Failed to run llama-bench: {}", e));
+            return result;
+        }
+        Some(Err(_)) => {
+            result.error = Some(format!("llama-bench timed out after {}s", BENCH_TIMEOUT.as_secs()));
+            return result;
+        }
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let last_line = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+        result.error = Some(format!("llama-bench exited with {}: {}", output.status, last_line));
+        return result;
+    }
+
+    let (prompt_tps, gen_tps) = parse_llama_bench_json(&String::from_utf8_lossy(&output.stdout));
+    if prompt_tps.is_none() && gen_tps.is_none() {
+        result.error = Some("llama-bench produced no results".to_string());
+    }
+    result.prompt_tps = prompt_tps;
+    result.gen_tps = gen_tps;
+    result.gpu_power = gpu_power;
+    result
+}
+
+/// `(prompt t/s, generation t/s)` from `llama-bench -o json`, which emits one
+/// object per test with `n_prompt`, `n_gen` and `avg_ts`
+pub fn parse_llama_bench_json(stdout: &str) -> (Option<f64>, Option<f64>) {
+    let start = stdout.find('[').unwrap_or(0);
+    let Ok(tests) = serde_json::from_str::<Vec<serde_json::Value>>(&stdout[start..]) else {
+        return (None, None);
+    };
+
+    let mut prompt_tps = None;
+    let mut gen_tps = None;
+    for test in &tests {
+        let n_prompt = test.get("n_prompt").and_then(|v| v.as_u64()).unwrap_or(0);
+        let n_gen = test.get("n_gen").and_then(|v| v.as_u64()).unwrap_or(0);
+        let avg_ts = test.get("avg_ts").and_then(|v| v.as_f64());
+        if n_prompt > 0 && n_gen == 0 {
+            prompt_tps = avg_ts.or(prompt_tps);
+        } else if n_gen > 0 && n_prompt == 0 {
+            gen_tps = avg_ts.or(gen_tps);
+        }
+    }
+    (prompt_tps, gen_tps)
+}
+
+/// Generation speed decides; prompt speed breaks ties. Failed runs never win.
+pub fn pick_winner(results: &[BackendBenchmarkResult]) -> Option<&BackendBenchmarkResult> {
+    results
+        .iter()
+        .filter(|r| r.error.is_none() && r.gen_tps.is_some())
+        .max_by(|a, b| {
+            a.gen_tps
+                .partial_cmp(&b.gen_tps)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.prompt_tps.partial_cmp(&b.prompt_tps).unwrap_or(std::cmp::Ordering::Equal))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(backend: &str, gen_tps: Option<f64>, error: Option<&str>) -> BackendBenchmarkResult {
+        BackendBenchmarkResult {
+            backend: backend.to_string(),
+            build_path: format!("/versions/b5000/{}", backend),
+            prompt_tps: Some(100.0),
+            gen_tps,
+            error: error.map(str::to_string),
+            gpu_power: None,
+        }
+    }
+
+    #[test]
+    fn parse_llama_bench_json_splits_prompt_and_generation() {
+        let stdout = r#"ggml_cuda_init: found 1 CUDA devices
+[
+  {"model_type": "llama 8B Q4_K - Medium", "n_prompt": 128, "n_gen": 0, "avg_ts": 2501.5},
+  {"model_type": "llama 8B Q4_K - Medium", "n_prompt": 0, "n_gen": 32, "avg_ts": 98.25}
+]"#;
+        assert_eq!(parse_llama_bench_json(stdout), (Some(2501.5), Some(98.25)));
+        assert_eq!(parse_llama_bench_json("error: failed to load model"), (None, None));
+    }
+
+    #[test]
+    fn pick_winner_prefers_fastest_successful_run() {
+        let results = vec![
+            result("cpu", Some(12.0), None),
+            result("vulkan", Some(80.0), None),
+            result("cuda", Some(120.0), Some("out of memory")),
+        ];
+        assert_eq!(pick_winner(&results).map(|r| r.backend.as_str()), Some("vulkan"));
+        assert!(pick_winner(&[result("cuda", None, Some("timeout"))]).is_none());
+        assert_eq!(size_bucket(5 * 1024 * 1024 * 1024), "s");
+    }
+}
@@ -0,0 +1,222 @@
+use std::fs;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use crate::models::{ChatLogMessage, ChatMessageMetadata};
+
+// Typed copy of a chat next to its markdown log. The markdown stays the file people
+// read; `<chat_id>.json` holds the same messages as records so the chat UI can reload
+// them without parsing headings back out of message text. The file uses the
+// `{"messages": [...]}` layout of legacy JSON chats, so `read_chat_markdown` reads
+// it too.
+
+pub fn path_for(chat_path: &Path) -> PathBuf {
+    chat_path.with_extension("json")
+}
+
+/// Legacy chats whose log already is the JSON file
+fn is_json_log(chat_path: &Path) -> bool {
+    chat_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Messages of a chat markdown log, with token counts and timings from its metadata
+pub fn from_markdown(markdown: &str, metadata: &[ChatMessageMetadata]) -> Vec<ChatLogMessage> {
+    crate::split_chat_message_sections(markdown)
+        .iter()
+        .enumerate()
+        .map(|(i, section)| {
+            let header = section.lines().next().unwrap_or("").trim_start_matches("## ");
+            let mut parts = header.splitn(3, " | ");
+            let role = parts.next().unwrap_or("").trim().to_lowercase();
+            let timestamp = parts.next().unwrap_or("").trim().to_string();
+            let model = parts.next().map(str::trim).filter(|m| !m.is_empty() && *m != "unknown").map(str::to_string);
+            let meta = metadata.iter().find(|m| m.message_index == i);
+            ChatLogMessage {
+                role,
+                content: crate::chat_section_body(section).trim().to_string(),
+                model,
+                timestamp,
+                duration_ms: meta.and_then(|m| m.duration_ms),
+                prompt_tokens: meta.and_then(|m| m.prompt_tokens),
+                completion_tokens: meta.and_then(|m| m.completion_tokens),
+            }
+        })
+        .collect()
+}
+
+/// Markdown sections in the format `append_chat_log_message` writes
+pub fn to_markdown(messages: &[ChatLogMessage]) -> String {
+    messages
+        .iter()
+        .map(|m| {
+            format!(
+                "## {} | {} | {}\n\n{}\n\n",
+                m.role.to_uppercase(),
+                m.timestamp,
+                m.model.as_deref().unwrap_or("unknown"),
+                m.content
+            )
+        })
+        .collect()
+}
+
+/// Stored messages, or `None` when the chat has no structured file yet
+fn load(chat_path: &Path) -> Option<Vec<ChatLogMessage>> {
+    let raw = fs::read_to_string(path_for(chat_path)).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&raw).ok()?;
+    let messages = value.get("messages").and_then(|v| v.as_array()).or_else(|| value.as_array())?;
+    Some(
+        messages
+            .iter()
+            .filter_map(|m| {
+                serde_json::from_value(m.clone()).ok().or_else(|| {
+                    // Legacy JSON chats may hold content as an array of parts
+                    Some(ChatLogMessage {
+                        role: m.get("role")?.as_str()?.to_lowercase(),
+                        content: crate::extract_json_message_content(m.get("content").or_else(|| m.get("text")))?,
+                        model: m.get("model").and_then(|v| v.as_str()).map(str::to_string),
+                        timestamp: m.get("timestamp").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                        duration_ms: None,
+                        prompt_tokens: None,
+                        completion_tokens: None,
+                    })
+                })
+            })
+            .collect(),
+    )
+}
+
+pub fn write(chat_path: &Path, chat_id: &str, messages: &[ChatLogMessage]) -> Result<(), String> {
+    let path = path_for(chat_path);
+    let content = serde_json::to_string_pretty(&serde_json::json!({ "chat_id": chat_id, "messages": messages }))
+        .map_err(|e| format!("Failed to serialize chat messages: {}", e))?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, &content).map_err(|e| format!("Failed to write chat messages temp file: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to rename chat messages temp file: {}", e))
+}
+
+/// Messages of a chat, built from the markdown (and saved) for chats logged before
+/// the structured file existed
+pub fn messages(chat_path: &Path, chat_id: &str) -> Result<Vec<ChatLogMessage>, String> {
+    if let Some(messages) = load(chat_path) {
+        return Ok(messages);
+    }
+    let markdown = crate::read_chat_markdown(chat_path)?;
+    let messages = from_markdown(&markdown, &crate::read_chat_message_metadata(chat_path));
+    if !is_json_log(chat_path) {
+        write(chat_path, chat_id, &messages)?;
+    }
+    Ok(messages)
+}
+
+/// Record message `message_index`, already appended to the markdown log
+pub fn append(chat_path: &Path, chat_id: &str, message_index: usize, message: ChatLogMessage) -> Result<(), String> {
+    // `append_json_chat_message` already wrote it into the log itself
+    if is_json_log(chat_path) {
+        return Ok(());
+    }
+    let mut messages = messages(chat_path, chat_id)?;
+    messages.truncate(message_index);
+    messages.push(message);
+    write(chat_path, chat_id, &messages)
+}
+
+/// Dataset and document formats for `export_chat_log`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExportFormat {
+    /// `{"messages": [{"role", "content"}]}` per line, the OpenAI fine-tuning layout
+    OpenaiJsonl,
+    /// `{"conversations": [{"from", "value"}]}` per line
+    Sharegpt,
+    Markdown,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::OpenaiJsonl | ExportFormat::Sharegpt => "jsonl",
+            ExportFormat::Markdown => "md",
+        }
+    }
+}
+
+/// One dataset line for a chat, `None` for markdown or a chat without messages
+pub fn dataset_line(messages: &[ChatLogMessage], format: ExportFormat) -> Option<String> {
+    let messages: Vec<&ChatLogMessage> = messages.iter().filter(|m| !m.content.trim().is_empty()).collect();
+    if messages.is_empty() {
+        return None;
+    }
+    let record = match format {
+        ExportFormat::OpenaiJsonl => serde_json::json!({
+            "messages": messages.iter().map(|m| serde_json::json!({ "role": m.role, "content": m.content })).collect::<Vec<_>>(),
+        }),
+        ExportFormat::Sharegpt => serde_json::json!({
+            "conversations": messages
+                .iter()
+                .map(|m| {
+                    let from = match m.role.as_str() {
+                        "user" => "human",
+                        "assistant" => "gpt",
+                        other => other,
+                    };
+                    serde_json::json!({ "from": from, "value": m.content })
+                })
+                .collect::<Vec<_>>(),
+        }),
+        ExportFormat::Markdown => return None,
+    };
+    Some(format!("{}\n", record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_round_trips_through_records() {
+        let markdown = "---\nchat_id: chat-1\ntitle: Test\n---\n\n\
+## USER | t0 | unknown\n\nhello\n\n\
+## ASSISTANT | t1 | qwen3-8b\n\n## Heading in the answer\n\nworld\n\n";
+        let metadata = vec![ChatMessageMetadata {
+            message_index: 1,
+            completion_tokens: Some(12),
+            duration_ms: Some(800),
+            ..ChatMessageMetadata::default()
+        }];
+
+        let messages = from_markdown(markdown, &metadata);
+        assert_eq!(messages.len(), 2);
+        assert_eq!((messages[0].role.as_str(), messages[0].model.clone()), ("user", None));
+        assert_eq!(messages[1].content, "## Heading in the answer\n\nworld");
+        assert_eq!((messages[1].completion_tokens, messages[1].duration_ms), (Some(12), Some(800)));
+
+        assert_eq!(from_markdown(&to_markdown(&messages), &metadata), messages);
+    }
+
+    #[test]
+    fn dataset_lines_map_roles_per_format() {
+        let message = |role: &str, content: &str| ChatLogMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            model: None,
+            timestamp: String::new(),
+            duration_ms: None,
+            prompt_tokens: None,
+            completion_tokens: None,
+        };
+        let chat = vec![message("system", "Be brief."), message("user", "Hi"), message("assistant", "Hello!"), message("user", " ")];
+
+        let openai = dataset_line(&chat, ExportFormat::OpenaiJsonl).unwrap();
+        assert!(openai.ends_with("}\n") && openai.lines().count() == 1);
+        let openai: serde_json::Value = serde_json::from_str(&openai).unwrap();
+        assert_eq!(openai["messages"].as_array().unwrap().len(), 3);
+        assert_eq!(openai["messages"][0], serde_json::json!({"role": "system", "content": "Be brief."}));
+        let sharegpt: serde_json::Value = serde_json::from_str(&dataset_line(&chat, ExportFormat::Sharegpt).unwrap()).unwrap();
+        assert_eq!(sharegpt["conversations"][1], serde_json::json!({"from": "human", "value": "Hi"}));
+        assert_eq!(sharegpt["conversations"][2]["from"], "gpt");
+        assert!(dataset_line(&[], ExportFormat::OpenaiJsonl).is_none());
+    }
+}
@@ -0,0 +1,222 @@
+// Tokenizer for the llama-server argument strings people type into model settings and
+// presets. Close to shell words, but Windows paths must survive unquoted, so a backslash
+// only escapes a quote character:
+//
+// - whitespace separates arguments; quoted parts and plain text next to each other
+//   form one argument (`--x="a b"` is `--x=a b`)
+// - single quotes are literal up to the next `'`, so JSON values can be quoted whole
+// - inside double quotes `\"` is a literal quote and every other character is kept
+// - outside quotes `\"` and `\'` are literal quotes, any other backslash is kept
+// - an unterminated quote runs to the end of the string
+//
+// `join` quotes so that `split(join(args)) == args` for any arguments.
+
+pub fn split(input: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    // An argument has started even when it is empty (`""`)
+    let mut started = false;
+    let mut chars = input.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            c if c.is_whitespace() => {
+                if started {
+                    args.push(std::mem::take(&mut current));
+                    started = false;
+                }
+            }
+            '\'' => {
+                started = true;
+                for c in chars.by_ref() {
+                    if c == '\'' {
+                        break;
+                    }
+                    current.push(c);
+                }
+            }
+            '"' => {
+                started = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' if chars.peek() == Some(&'"') => current.push(chars.next().unwrap_or('"')),
+                        c => current.push(c),
+                    }
+                }
+            }
+            '\\' if matches!(chars.peek(), Some('"') | Some('\'')) => {
+                started = true;
+                current.push(chars.next().unwrap_or('"'));
+            }
+            c => {
+                started = true;
+                current.push(c);
+            }
+        }
+    }
+    if started {
+        args.push(current);
+    }
+    args
+}
+
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty() && !arg.chars().any(|c| c.is_whitespace() || c == '"' || c == '\'');
+    if plain {
+        return arg.to_string();
+    }
+    // A backslash right before the closing `"` would escape it
+    if !arg.contains('"') && !arg.ends_with('\\') {
+        return format!("\"{}\"", arg);
+    }
+    // Single quotes keep everything; a `'` itself goes in double quotes in between
+    arg.split('\'').map(|part| format!("'{}'", part)).collect::<Vec<_>>().join("\"'\"")
+}
+
+pub fn join(args: &[String]) -> String {
+    args.iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" ")
+}
+
+/// Value of the last occurrence of any of `flags`, as `flag value` or `flag=value`
+pub fn flag_value(args: &[String], flags: &[&str]) -> Option<String> {
+    args.iter().enumerate().rev().find_map(|(i, arg)| {
+        flags.iter().find_map(|flag| {
+            if arg == flag {
+                args.get(i + 1).cloned()
+            } else {
+                arg.strip_prefix(&format!("{}=", flag)).map(str::to_string)
+            }
+        })
+    })
+}
+
+/// Replace every occurrence of a flag's value, or append the flag when missing
+pub fn set_flag(args: &mut Vec<String>, flags: &[&str], value: &str) {
+    let mut found = false;
+    let mut i = 0;
+    while i < args.len() {
+        if flags.contains(&args[i].as_str()) {
+            found = true;
+            if i + 1 < args.len() {
+                args[i + 1] = value.to_string();
+            } else {
+                args.push(value.to_string());
+            }
+            i += 2;
+        } else if let Some(flag) = flags.iter().find(|f| args[i].starts_with(&format!("{}=", f))) {
+            found = true;
+            args[i] = format!("{}={}", flag, value);
+            i += 1;
+        } else {
+            i += 1;
+        }
+    }
+    if !found {
+        args.push(flags[0].to_string());
+        args.push(value.to_string());
+    }
+}
+
+/// Drop every occurrence of a value-taking flag together with its value
+pub fn remove_flag(args: &mut Vec<String>, flags: &[&str]) {
+    let mut i = 0;
+    while i < args.len() {
+        if flags.contains(&args[i].as_str()) {
+            let end = (i + 2).min(args.len());
+            args.drain(i..end);
+        } else if flags.iter().any(|f| args[i].starts_with(&format!("{}=", f))) {
+            args.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// `--port` from the arguments, read the way `remove_flag` strips it
+pub fn port(args: &[String]) -> Option<u16> {
+    flag_value(args, &["--port"]).and_then(|p| p.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn splits_quotes_escapes_and_windows_paths() {
+        assert_eq!(
+            split(r#"-m C:\models\a.gguf --mmproj "C:\My Models\mm.gguf"  -c 4096"#),
+            strings(&["-m", r"C:\models\a.gguf", "--mmproj", r"C:\My Models\mm.gguf", "-c", "4096"])
+        );
+        assert_eq!(
+            split(r#"--chat-template-kwargs '{"enable_thinking": false}' --alias "say \"hi\"""#),
+            strings(&["--chat-template-kwargs", r#"{"enable_thinking": false}"#, "--alias", r#"say "hi""#])
+        );
+        assert_eq!(split(r#"--x="a b"c '' \\srv\share it\'s"#), strings(&["--x=a bc", "", r"\\srv\share", "it's"]));
+        assert_eq!(split("\t-ngl\n99 \"unterminated arg"), strings(&["-ngl", "99", "unterminated arg"]));
+        assert!(split("   ").is_empty());
+    }
+
+    #[test]
+    fn port_reading_and_filtering_agree() {
+        let mut args = split(r#"--alias "uses --port 1" --port=9000 -c 4096 --port 9001 --portable"#);
+        assert_eq!(port(&args), Some(9001));
+        remove_flag(&mut args, &["--port"]);
+        assert_eq!(args, strings(&["--alias", "uses --port 1", "-c", "4096", "--portable"]));
+        assert_eq!(port(&args), None);
+
+        let mut dangling = strings(&["-c", "8", "--port"]);
+        remove_flag(&mut dangling, &["--port"]);
+        assert_eq!(dangling, strings(&["-c", "8"]));
+    }
+
+    /// xorshift64*, enough to spread cases over the tricky characters
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    #[test]
+    fn join_round_trips_arbitrary_arguments() {
+        const ALPHABET: &[char] = &['a', 'Z', '0', '-', '=', ' ', '\t', '"', '\'', '\\', '/', ':', '{', '}', 'é', '模'];
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..5000 {
+            let args: Vec<String> = (0..rng.below(5))
+                .map(|_| (0..rng.below(8)).map(|_| ALPHABET[rng.below(ALPHABET.len())]).collect())
+                .collect();
+            let joined = join(&args);
+            assert_eq!(split(&joined), args, "joined as {}", joined);
+            // Stable once normalized
+            assert_eq!(join(&split(&joined)), joined);
+        }
+    }
+
+    #[test]
+    fn filtering_never_touches_quoted_paths() {
+        let mut rng = Rng(42);
+        for _ in 0..1000 {
+            let path: String = (0..1 + rng.below(12)).map(|_| ['x', ' ', '\\', '\'', '"', '-'][rng.below(6)]).collect();
+            let mut args = vec!["--mmproj".to_string(), path.clone(), "--port".to_string(), "8080".to_string(), "-c".to_string(), "4096".to_string()];
+            args.rotate_left(2 * rng.below(3));
+            let mut parsed = split(&join(&args));
+            assert_eq!(port(&parsed), Some(8080));
+            remove_flag(&mut parsed, &["--port"]);
+            assert_eq!(flag_value(&parsed, &["--mmproj"]), Some(path));
+            assert_eq!(parsed.len(), 4);
+        }
+    }
+}
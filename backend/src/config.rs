@@ -1,6 +1,5 @@
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
-use serde_json;
 use tokio::fs;
 use crate::models::*;
 use crate::AppState;
@@ -8,9 +7,8 @@ use crate::AppState;
 const SETTINGS_FILE: &str = "settings.json";
 
 pub async fn get_settings_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let mut path = dirs::home_dir()
-        .ok_or("Could not find home directory")?;
-    path.push(".Arandu");
+    dirs::home_dir().ok_or("Could not find home directory")?;
+    let mut path = default_arandu_base_dir();
     
     // Create directory if it doesn't exist
     fs::create_dir_all(&path).await?;
@@ -123,6 +121,7 @@ pub async fn load_settings(state: &AppState) -> Result<(), Box<dyn std::error::E
     
     if !settings_path.exists() {
         tracing::info!("Settings file does not exist, using defaults");
+        load_benchmarks(state, Vec::new()).await;
         return Ok(());
     }
     
@@ -140,13 +139,17 @@ pub async fn load_settings(state: &AppState) -> Result<(), Box<dyn std::error::E
     }
 
     let SettingsFile {
-        global_config,
+        mut global_config,
         model_configs: stored_model_configs,
     } = settings;
+    let legacy_benchmarks: Vec<BackendBenchmarkRecord> = std::mem::take(&mut global_config.backend_benchmarks).into_values().collect();
 
     // Get models directory for path conversion
     let models_dir = global_config.models_directory.clone();
     
+    crate::i18n::set_locale(&global_config.locale);
+    crate::analytics::set_enabled(global_config.usage_analytics_enabled);
+
     // Update global config
     {
         let mut config = state.config.lock().await;
@@ -166,11 +169,28 @@ pub async fn load_settings(state: &AppState) -> Result<(), Box<dyn std::error::E
         
         *model_configs = absolute_configs;
     }
+    load_benchmarks(state, legacy_benchmarks).await;
     
     tracing::info!("Settings loaded successfully from {:?}", settings_path);
     Ok(())
 }
 
+/// Benchmarks live in `storage`; ones found in an older settings file are moved there first
+async fn load_benchmarks(state: &AppState, legacy: Vec<BackendBenchmarkRecord>) {
+    let loaded = crate::storage::run(move |db| {
+        legacy.iter().try_for_each(|record| db.benchmarks().save(record))?;
+        db.benchmarks().all()
+    })
+    .await;
+    match loaded {
+        Ok(records) => {
+            state.config.lock().await.backend_benchmarks =
+                records.into_iter().map(|record| (record.model_class.clone(), record)).collect();
+        }
+        Err(e) => eprintln!("[Storage] Failed to load backend benchmarks: {}", e),
+    }
+}
+
 pub async fn save_settings(state: &AppState) -> Result<(), Box<dyn std::error::Error>> {
     let settings_path = get_settings_path().await?;
     
@@ -213,6 +233,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[cfg(windows)]
     fn test_make_path_relative() {
         // Test with Windows-style paths
         let absolute = "C:\\Users\\test\\models\\llama-3.gguf";
@@ -240,6 +261,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     fn test_make_path_absolute() {
         // Test with relative path
         let relative = "llama-3.gguf";
@@ -267,6 +289,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     fn test_remap_arandu_path_old_home_base() {
         let old_base = PathBuf::from(r"C:\Users\tester\.Arandu");
         let new_base = PathBuf::from(r"H:\Ardanu Fix\Arandu-maxi\.Arandu");
@@ -280,6 +303,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(windows)]
     fn test_remap_arandu_path_c_drive_marker_style() {
         let old_base = PathBuf::from(r"D:\Different\.Arandu");
         let new_base = PathBuf::from(r"H:\Ardanu Fix\Arandu-maxi\.Arandu");
@@ -0,0 +1,147 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use crate::llama_client::LlamaClient;
+use crate::AppState;
+
+// Dry run of a chat's next request: how much of the model's context the history plus a
+// new message takes, counted with the running model's tokenizer and chat template, and
+// what would have to go for it to fit. Nothing is sent for generation. The template's
+// own tokens (role markers, BOS) are the rendered prompt minus the messages' tokens;
+// the room a reply needs is the model's `max_tokens` default when one is set.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageUsage {
+    pub index: usize,
+    pub role: String,
+    pub tokens: u64,
+    /// Tokens of this message and every one before it, template included
+    pub cumulative: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextUsage {
+    pub model_name: String,
+    pub context_length: u64,
+    pub prompt_tokens: u64,
+    pub template_tokens: u64,
+    pub reserved_for_response: u64,
+    pub remaining: i64,
+    pub percent_used: f64,
+    pub fits: bool,
+    /// First message at which the history no longer fits
+    pub overflow_at: Option<usize>,
+    /// Oldest messages that would have to be dropped to fit; the first system message
+    /// and the new message are always kept
+    pub truncated: Vec<usize>,
+    /// The chat's messages followed by the new one
+    pub messages: Vec<MessageUsage>,
+}
+
+struct Plan {
+    cumulative: Vec<u64>,
+    overflow_at: Option<usize>,
+    truncated: Vec<usize>,
+}
+
+/// Where `tokens` (per message, the new one last) overflow `limit` after `fixed`
+/// template and response tokens, and which oldest messages dropping makes it fit
+fn plan(tokens: &[u64], keeps_system: bool, fixed: u64, limit: u64) -> Plan {
+    let mut cumulative = Vec::with_capacity(tokens.len());
+    let mut total = fixed;
+    for count in tokens {
+        total += count;
+        cumulative.push(total);
+    }
+    let overflow_at = cumulative.iter().position(|&used| used > limit);
+
+    let mut truncated = Vec::new();
+    if overflow_at.is_some() {
+        let first = usize::from(keeps_system);
+        let last = tokens.len().saturating_sub(1);
+        for (index, &count) in tokens.iter().enumerate().take(last).skip(first) {
+            if total <= limit {
+                break;
+            }
+            total -= count;
+            truncated.push(index);
+        }
+    }
+    Plan { cumulative, overflow_at, truncated }
+}
+
+/// Usage of the next request in the chat logged at `chat_path` if `new_message` were sent
+pub async fn simulate(state: &AppState, chat_path: &Path, chat_id: &str, model: &str, new_message: &str) -> Result<ContextUsage, String> {
+    let server = crate::model_routing::running_server(state, model).await?;
+    let client = LlamaClient::new(server.base_url.clone());
+
+    let mut messages: Vec<(String, String)> = crate::chat_store::messages(chat_path, chat_id)?
+        .into_iter()
+        .map(|m| (m.role, m.content))
+        .collect();
+    messages.push(("user".to_string(), new_message.to_string()));
+
+    let mut tokens = Vec::with_capacity(messages.len());
+    for (_, content) in &messages {
+        tokens.push(client.tokenize(content, false).await?.len() as u64);
+    }
+    let rendered: Vec<Value> = messages.iter().map(|(role, content)| json!({ "role": role, "content": content })).collect();
+    let prompt = client.apply_template(&Value::Array(rendered)).await?;
+    let prompt_tokens = client.tokenize(&prompt, true).await?.len() as u64;
+    let template_tokens = prompt_tokens.saturating_sub(tokens.iter().sum());
+    let context_length = client.context_length().await?;
+
+    let reserved_for_response = state
+        .parameter_controllers
+        .lock()
+        .await
+        .values()
+        .find(|c| c.model_path == server.model_path)
+        .and_then(|c| c.generation.max_tokens)
+        .map(|n| n.max(0) as u64)
+        .unwrap_or(0);
+
+    let keeps_system = messages.first().is_some_and(|(role, _)| role == "system");
+    let plan = plan(&tokens, keeps_system, template_tokens + reserved_for_response, context_length);
+    let used = prompt_tokens + reserved_for_response;
+    Ok(ContextUsage {
+        model_name: server.model_name,
+        context_length,
+        prompt_tokens,
+        template_tokens,
+        reserved_for_response,
+        remaining: context_length as i64 - used as i64,
+        percent_used: if context_length > 0 { used as f64 * 100.0 / context_length as f64 } else { 0.0 },
+        fits: plan.overflow_at.is_none(),
+        overflow_at: plan.overflow_at,
+        truncated: plan.truncated,
+        messages: messages
+            .into_iter()
+            .zip(tokens)
+            .zip(plan.cumulative)
+            .enumerate()
+            .map(|(index, (((role, _), tokens), cumulative))| MessageUsage { index, role, tokens, cumulative })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow_drops_the_oldest_messages_but_keeps_system_and_new() {
+        // system, four turns, new message; 20 template + 100 reply tokens in a 500 window
+        let tokens = [50, 100, 120, 80, 90, 60];
+        let plan = plan(&tokens, true, 120, 500);
+        assert_eq!(plan.cumulative, vec![170, 270, 390, 470, 560, 620]);
+        assert_eq!(plan.overflow_at, Some(4));
+        assert_eq!(plan.truncated, vec![1, 2]);
+
+        let plan = super::plan(&tokens, false, 120, 500);
+        assert_eq!(plan.truncated, vec![0, 1]);
+
+        let plan = super::plan(&tokens, true, 0, 1000);
+        assert!(plan.overflow_at.is_none() && plan.truncated.is_empty());
+    }
+}
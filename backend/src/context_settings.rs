@@ -0,0 +1,62 @@
+use crate::models::ContextSettings;
+use crate::openai_types::ChatCompletionRequest;
+use crate::cli_args::{self, set_flag};
+
+// Context overflow handling per chat. `--context-shift` and `--cache-reuse` are
+// server flags and go into the launch arguments; `cache_prompt` and `n_keep` are
+// request fields the proxy adds to each completion of the chat.
+
+/// Launch arguments with the chat's server-side context options merged in
+pub fn launch_args(custom_args: &str, settings: &ContextSettings) -> String {
+    let mut args = cli_args::split(custom_args);
+    args.retain(|arg| arg != "--context-shift" && arg != "--no-context-shift");
+    if settings.context_shift {
+        args.push("--context-shift".to_string());
+    }
+    if let Some(chunk) = settings.cache_reuse {
+        set_flag(&mut args, &["--cache-reuse"], &chunk.to_string());
+    }
+    cli_args::join(&args)
+}
+
+/// Request fields the client did not set come from the chat's settings
+pub fn apply(settings: &ContextSettings, request: &mut ChatCompletionRequest) {
+    request.cache_prompt = request.cache_prompt.or(Some(settings.cache_prompt));
+    request.n_keep = request.n_keep.or(settings.n_keep);
+}
+
+/// Settings stored on a chat's index entry, or the defaults
+pub fn for_chat(chat_id: &str) -> Result<ContextSettings, String> {
+    crate::read_chat_setting(chat_id, "context_settings")
+}
+
+pub fn save_for_chat(chat_id: &str, settings: &ContextSettings) -> Result<(), String> {
+    if settings.n_keep.is_some_and(|n| n < -1) {
+        return Err("n_keep must be -1 or greater".to_string());
+    }
+    crate::write_chat_setting(chat_id, "context_settings", settings)
+}
+
+/// Apply the settings of the request's chat, if it names one
+pub fn apply_for_request(request: &mut ChatCompletionRequest) {
+    let Some(chat_id) = request.chat_id.take() else { return };
+    match for_chat(&chat_id) {
+        Ok(settings) => apply(&settings, request),
+        Err(e) => eprintln!("[Context] No context settings for chat {}: {}", chat_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launch_args_carry_server_side_context_options() {
+        let settings = ContextSettings { cache_reuse: Some(256), ..ContextSettings::default() };
+        assert_eq!(launch_args("-c 8192 --no-context-shift", &settings), "-c 8192 --context-shift --cache-reuse 256");
+        assert_eq!(launch_args("--context-shift", &ContextSettings::default()), "--context-shift");
+
+        let off = ContextSettings { context_shift: false, ..ContextSettings::default() };
+        assert_eq!(launch_args("-c 8192 --context-shift", &off), "-c 8192");
+    }
+}
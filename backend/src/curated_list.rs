@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use crate::model_pack::{ModelPack, PackModel};
+use crate::models::{TrackerModel, WatchlistEntry};
+
+// Shareable "curated lists": hand-picked tracker models with the curator's attribution
+// and per-model notes. Importing adds them to the watchlist (source `curated`) and the
+// tracker's model table; entries that name GGUF files can be bulk-downloaded as a pack.
+
+pub const LIST_FORMAT: &str = "arandu-curated-list";
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Curator {
+    pub name: String,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CuratedEntry {
+    /// Hugging Face `author/model`
+    pub id: String,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// GGUF files to fetch on bulk download; entries without files are watch-only
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Tracker data at export time, so the list shows up before the next scrape
+    #[serde(default)]
+    pub model: Option<TrackerModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CuratedList {
+    #[serde(default = "default_format")]
+    pub format: String,
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub curator: Curator,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    pub models: Vec<CuratedEntry>,
+}
+
+fn default_format() -> String {
+    LIST_FORMAT.to_string()
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+/// Watchlist `collection_slug` for entries imported from a list
+pub fn slug(title: &str) -> String {
+    let slug: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+    format!("curated/{}", slug)
+}
+
+pub fn build(
+    title: &str,
+    description: Option<String>,
+    curator: Curator,
+    watchlist: &[WatchlistEntry],
+    tracked: &[TrackerModel],
+) -> CuratedList {
+    CuratedList {
+        format: default_format(),
+        version: default_version(),
+        title: title.trim().to_string(),
+        description,
+        curator,
+        created_at: Some(chrono::Utc::now().to_rfc3339()),
+        models: watchlist
+            .iter()
+            .map(|entry| CuratedEntry {
+                id: entry.model_id.clone(),
+                note: entry.note.clone(),
+                files: Vec::new(),
+                model: tracked.iter().find(|m| m.id == entry.model_id).cloned(),
+            })
+            .collect(),
+    }
+}
+
+pub fn parse(raw: &str) -> Result<CuratedList, String> {
+    let list: CuratedList = serde_json::from_str(raw).map_err(|e| format!("Failed to parse curated list: {}", e))?;
+    if list.format != LIST_FORMAT {
+        return Err(format!("Not a curated model list (format '{}')", list.format));
+    }
+    if list.title.trim().is_empty() || list.curator.name.trim().is_empty() {
+        return Err("Curated lists need a title and a curator name".to_string());
+    }
+    if let Some(bad) = list.models.iter().find(|m| m.id.split('/').filter(|p| !p.is_empty()).count() != 2) {
+        return Err(format!("Invalid model id '{}'", bad.id));
+    }
+    Ok(list)
+}
+
+/// Watchlist entries, each note carrying the attribution
+pub fn watchlist_entries(list: &CuratedList) -> Vec<WatchlistEntry> {
+    let added_at = chrono::Utc::now().to_rfc3339();
+    list.models
+        .iter()
+        .map(|entry| WatchlistEntry {
+            model_id: entry.id.clone(),
+            source: "curated".to_string(),
+            collection_slug: Some(slug(&list.title)),
+            note: Some(match &entry.note {
+                Some(note) => format!("{} (curated by {})", note, list.curator.name),
+                None => format!("Curated by {}", list.curator.name),
+            }),
+            added_at: added_at.clone(),
+        })
+        .collect()
+}
+
+/// Entries with files as a pack, so bulk download goes through `model_pack` validation
+pub fn as_pack(list: &CuratedList) -> Option<ModelPack> {
+    let models: Vec<PackModel> = list
+        .models
+        .iter()
+        .filter(|entry| !entry.files.is_empty())
+        .map(|entry| PackModel { repo: entry.id.clone(), files: entry.files.clone(), custom_args: None, presets: Vec::new() })
+        .collect();
+    if models.is_empty() {
+        return None;
+    }
+    Some(ModelPack {
+        format: Some(crate::model_pack::PACK_FORMAT.to_string()),
+        version: 1,
+        name: list.title.clone(),
+        description: list.description.clone(),
+        author: Some(list.curator.name.clone()),
+        models,
+        prompts: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_round_trip_with_attribution() {
+        let watchlist = vec![WatchlistEntry {
+            model_id: "Qwen/Qwen3-8B-GGUF".to_string(),
+            source: "manual".to_string(),
+            collection_slug: None,
+            note: Some("Great all-rounder".to_string()),
+            added_at: String::new(),
+        }];
+        let list = build("My Picks: Coding!", None, Curator { name: "alice".to_string(), url: None }, &watchlist, &[]);
+        let raw = serde_json::to_string(&list).unwrap();
+        let parsed = parse(&raw).unwrap();
+
+        let entries = watchlist_entries(&parsed);
+        assert_eq!(entries[0].collection_slug.as_deref(), Some("curated/my-picks-coding"));
+        assert_eq!(entries[0].note.as_deref(), Some("Great all-rounder (curated by alice)"));
+        assert!(as_pack(&parsed).is_none());
+
+        let with_files = raw.replace("\"files\":[]", "\"files\":[\"qwen3-8b-q4_k_m.gguf\"]");
+        let pack = as_pack(&parse(&with_files).unwrap()).unwrap();
+        assert_eq!(pack.models[0].files, vec!["qwen3-8b-q4_k_m.gguf"]);
+        assert!(parse(&raw.replace(LIST_FORMAT, "other")).is_err());
+    }
+}
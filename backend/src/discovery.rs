@@ -11,9 +11,6 @@ use tokio::time::interval;
 use tracing::{debug, error, info, warn};
 use tauri::Emitter;
 
-#[cfg(test)]
-use uuid::Uuid;
-
 use crate::peer_cache::PeerModelCache;
 
 const DISCOVERY_PROTOCOL: &str = "arandu-discovery";
@@ -200,6 +197,7 @@ impl DiscoveryService {
         by_endpoint.into_values().collect()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         port: u16,
         instance_id: String,
@@ -409,12 +407,12 @@ pub async fn start_listening(&mut self) -> Result<(), String> {
 
                                 let prev_last_fetch_attempt = existing_entry
                                     .as_ref()
-                                    .and_then(|entry| entry.last_fetch_attempt.clone());
+                                    .and_then(|entry| entry.last_fetch_attempt);
 
                                 let cooldown_elapsed = prev_last_fetch_attempt
                                     .as_ref()
                                     .map(|last| {
-                                        now.signed_duration_since(last.clone()).num_seconds()
+                                        now.signed_duration_since(*last).num_seconds()
                                             >= AUTO_FETCH_COOLDOWN_SECS
                                     })
                                     .unwrap_or(true);
@@ -0,0 +1,261 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use sysinfo::Disks;
+use tauri::Emitter;
+use crate::downloader::DownloadState;
+use crate::AppState;
+
+// Free-space watchdog for the models directories. When a disk drops below
+// `GlobalConfig.disk_space_min_free_gb` the downloads writing to it are paused and
+// `disk-space-low` is emitted with deletion suggestions; the downloads paused here
+// resume once the disk has room again.
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Extra room needed before paused downloads resume, so they do not flap
+const RESUME_MARGIN_GB: f64 = 2.0;
+const MAX_CANDIDATES: usize = 10;
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+/// Downloads paused by the watchdog (user pauses are left alone)
+static PAUSED_DOWNLOADS: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+/// Mount points already reported as low, so the event fires once per episode
+static LOW_DISKS: LazyLock<Mutex<HashSet<PathBuf>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskSpace {
+    pub directory: String,
+    pub mount_point: String,
+    pub free_gb: f64,
+    pub total_gb: f64,
+    pub low: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeletionCandidate {
+    pub path: String,
+    pub name: String,
+    pub size_gb: f64,
+    /// `duplicate` or `unused`
+    pub reason: String,
+}
+
+/// Mount point, free and total bytes of the disk holding `path`
+fn disk_for(path: &Path, disks: &[(PathBuf, u64, u64)]) -> Option<(PathBuf, u64, u64)> {
+    // Windows canonical paths start with `\\?\`, mount points do not
+    let path = path.canonicalize().map(|p| crate::long_path::strip_verbatim(&p)).unwrap_or_else(|_| path.to_path_buf());
+    disks
+        .iter()
+        .filter(|(mount, _, _)| path.starts_with(mount))
+        .max_by_key(|(mount, _, _)| mount.as_os_str().len())
+        .cloned()
+}
+
+fn list_disks() -> Vec<(PathBuf, u64, u64)> {
+    Disks::new_with_refreshed_list()
+        .iter()
+        .map(|disk| (disk.mount_point().to_path_buf(), disk.available_space(), disk.total_space()))
+        .collect()
+}
+
+async fn models_directories(state: &AppState) -> (Vec<String>, f64) {
+    let config = state.config.lock().await;
+    let mut dirs = vec![config.models_directory.clone()];
+    dirs.extend(config.additional_models_directories.clone());
+    dirs.retain(|dir| !dir.is_empty());
+    (dirs, config.disk_space_min_free_gb)
+}
+
+pub async fn disk_space_status(state: &AppState) -> Vec<DiskSpace> {
+    let (dirs, threshold_gb) = models_directories(state).await;
+    let disks = list_disks();
+    dirs.into_iter()
+        .filter_map(|dir| {
+            let (mount, free, total) = disk_for(Path::new(&dir), &disks)?;
+            let free_gb = free as f64 / GB;
+            Some(DiskSpace {
+                directory: dir,
+                mount_point: mount.to_string_lossy().to_string(),
+                free_gb,
+                total_gb: total as f64 / GB,
+                low: threshold_gb > 0.0 && free_gb < threshold_gb,
+            })
+        })
+        .collect()
+}
+
+/// Extra copies of the same file (same name and size), then models that have no
+/// saved settings and are not running, largest first
+pub fn deletion_candidates(
+    models: &[crate::models::ModelInfo],
+    configured: &HashSet<String>,
+    running: &HashSet<String>,
+) -> Vec<DeletionCandidate> {
+    let mut candidates = Vec::new();
+    let mut seen: HashMap<(String, u64), &str> = HashMap::new();
+    for model in models {
+        let key = (model.name.to_lowercase(), (model.size_gb * 1000.0).round() as u64);
+        match seen.get(&key) {
+            Some(original) => candidates.push(DeletionCandidate {
+                path: model.path.clone(),
+                name: model.name.clone(),
+                size_gb: model.size_gb,
+                reason: format!("duplicate of {}", original),
+            }),
+            None => {
+                seen.insert(key, model.path.as_str());
+            }
+        }
+    }
+
+    let mut unused: Vec<&crate::models::ModelInfo> = models
+        .iter()
+        .filter(|m| !configured.contains(&m.path) && !running.contains(&m.path))
+        .filter(|m| !candidates.iter().any(|c| c.path == m.path))
+        .collect();
+    unused.sort_by(|a, b| b.size_gb.total_cmp(&a.size_gb));
+    candidates.extend(unused.into_iter().map(|m| DeletionCandidate {
+        path: m.path.clone(),
+        name: m.name.clone(),
+        size_gb: m.size_gb,
+        reason: "unused".to_string(),
+    }));
+    candidates.truncate(MAX_CANDIDATES);
+    candidates
+}
+
+async fn candidates_for(state: &AppState, dirs: &[String]) -> Vec<DeletionCandidate> {
+    let models = crate::scanner::scan_models(dirs).await.unwrap_or_default();
+    let configured: HashSet<String> = state.model_configs.lock().await.keys().cloned().collect();
+    let running: HashSet<String> = state.running_processes.lock().await.values().map(|p| p.model_path.clone()).collect();
+    deletion_candidates(&models, &configured, &running)
+}
+
+async fn check_once(state: &AppState, app_handle: &tauri::AppHandle) {
+    let (dirs, threshold_gb) = models_directories(state).await;
+    let disks = list_disks();
+    let free_gb_at = |path: &Path| disk_for(path, &disks).map(|(mount, free, _)| (mount, free as f64 / GB));
+
+    // Resume what we paused once its disk has room (or the watchdog was turned off)
+    let paused: Vec<String> = PAUSED_DOWNLOADS.lock().unwrap_or_else(|p| p.into_inner()).iter().cloned().collect();
+    if !paused.is_empty() {
+        let mut manager = state.download_manager.lock().await;
+        for id in paused {
+            let destination = manager.downloads.get(&id).map(|d| d.destination.clone());
+            let has_room = destination
+                .and_then(|d| free_gb_at(Path::new(&d)))
+                .is_none_or(|(_, free_gb)| threshold_gb <= 0.0 || free_gb >= threshold_gb + RESUME_MARGIN_GB);
+            if has_room {
+                if manager.resume_download(&id).is_ok() {
+                    println!("[Disk] Resumed download {} after disk space recovered", id);
+                }
+                PAUSED_DOWNLOADS.lock().unwrap_or_else(|p| p.into_inner()).remove(&id);
+            }
+        }
+    }
+    if threshold_gb <= 0.0 {
+        LOW_DISKS.lock().unwrap_or_else(|p| p.into_inner()).clear();
+        return;
+    }
+
+    let mut low: HashMap<PathBuf, (f64, Vec<String>)> = HashMap::new();
+    for dir in &dirs {
+        if let Some((mount, free_gb)) = free_gb_at(Path::new(dir)).filter(|(_, free_gb)| *free_gb < threshold_gb) {
+            low.entry(mount).or_insert_with(|| (free_gb, Vec::new())).1.push(dir.clone());
+        }
+    }
+    LOW_DISKS.lock().unwrap_or_else(|p| p.into_inner()).retain(|mount| low.contains_key(mount));
+    if low.is_empty() {
+        return;
+    }
+
+    let mut paused_now: HashMap<PathBuf, Vec<String>> = HashMap::new();
+    {
+        let mut manager = state.download_manager.lock().await;
+        let active: Vec<(String, String)> = manager
+            .downloads
+            .values()
+            .filter(|d| matches!(d.status, DownloadState::Downloading))
+            .map(|d| (d.id.clone(), d.destination.clone()))
+            .collect();
+        for (id, destination) in active {
+            let Some((mount, _)) = free_gb_at(Path::new(&destination)).filter(|(mount, _)| low.contains_key(mount)) else {
+                continue;
+            };
+            if manager.pause_download(&id).is_ok() {
+                eprintln!("[Disk] Paused download {}: low disk space on {}", id, mount.display());
+                PAUSED_DOWNLOADS.lock().unwrap_or_else(|p| p.into_inner()).insert(id.clone());
+                paused_now.entry(mount).or_default().push(id);
+            }
+        }
+    }
+
+    for (mount, (free_gb, directories)) in low {
+        let newly_low = LOW_DISKS.lock().unwrap_or_else(|p| p.into_inner()).insert(mount.clone());
+        let paused = paused_now.remove(&mount).unwrap_or_default();
+        if !newly_low && paused.is_empty() {
+            continue;
+        }
+        let candidates = candidates_for(state, &directories).await;
+        let _ = app_handle.emit("disk-space-low", serde_json::json!({
+            "mount_point": mount.to_string_lossy(),
+            "directories": directories,
+            "free_gb": free_gb,
+            "threshold_gb": threshold_gb,
+            "paused_downloads": paused,
+            "candidates": candidates,
+        }));
+    }
+}
+
+pub fn spawn(state: AppState, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check_once(&state, &app_handle).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ModelInfo;
+
+    fn model(path: &str, name: &str, size_gb: f64) -> ModelInfo {
+        ModelInfo {
+            path: path.to_string(),
+            name: name.to_string(),
+            size_gb,
+            architecture: "llama".to_string(),
+            model_name: name.to_string(),
+            quantization: "Q4_K_M".to_string(),
+            date: 0,
+        }
+    }
+
+    #[test]
+    fn candidates_list_duplicates_then_unused_largest_first() {
+        let models = vec![
+            model("/a/qwen.gguf", "qwen", 4.5),
+            model("/b/qwen.gguf", "qwen", 4.5),
+            model("/a/small.gguf", "small", 1.0),
+            model("/a/big.gguf", "big", 40.0),
+            model("/a/used.gguf", "used", 8.0),
+        ];
+        let configured: HashSet<String> = ["/a/qwen.gguf".to_string(), "/a/used.gguf".to_string()].into();
+        let candidates = deletion_candidates(&models, &configured, &HashSet::new());
+
+        assert_eq!(candidates[0].path, "/b/qwen.gguf");
+        assert_eq!(candidates[0].reason, "duplicate of /a/qwen.gguf");
+        assert_eq!(candidates[1].path, "/a/big.gguf");
+        assert_eq!(candidates[2].path, "/a/small.gguf");
+        assert_eq!(candidates.len(), 3);
+
+        let disks = vec![(PathBuf::from("/"), 10, 100), (PathBuf::from("/mnt/models"), 5, 50)];
+        assert_eq!(disk_for(Path::new("/mnt/models/x"), &disks).unwrap().0, PathBuf::from("/mnt/models"));
+        assert_eq!(disk_for(Path::new("/home/x"), &disks).unwrap().0, PathBuf::from("/"));
+    }
+}
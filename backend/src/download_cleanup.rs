@@ -0,0 +1,214 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use crate::download_resume;
+use crate::downloader::DownloadState;
+use crate::AppState;
+
+// Partial downloads (`<name>.download` and their `.download.json` sidecars, for models and
+// archives alike) left in the models directories are removed only when asked, through
+// `clean_partial_downloads`. A `.download` file counts as a partial when its sidecar is one
+// the downloader wrote; `.gguf.download` files from before sidecars existed count as well.
+// A partial is removed once it has not been written to for `partial_download_retention_days`;
+// with `keep_resumable_downloads` set, partials that can still be resumed stay regardless
+// of age. Files of downloads still in the download manager are never touched, and a dry
+// run reports what would go without deleting anything.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PartialFile {
+    pub path: String,
+    /// The partial and its sidecar
+    pub bytes: u64,
+    pub age_days: u64,
+    pub resumable: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CleanupReport {
+    pub dry_run: bool,
+    pub removed: Vec<PartialFile>,
+    pub kept: Vec<PartialFile>,
+    pub reclaimed_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+/// When a partial goes, from the settings
+#[derive(Debug, Clone, Copy)]
+pub struct Policy {
+    pub retention_days: u32,
+    pub keep_resumable: bool,
+}
+
+impl Policy {
+    fn removes(&self, age: Duration, resumable: bool) -> bool {
+        !(resumable && self.keep_resumable) && age >= Duration::from_secs(u64::from(self.retention_days) * 86_400)
+    }
+}
+
+/// A `.download` name the downloader writes; other `.download` files may be the user's own,
+/// so the sidecar has to vouch for it unless it is a GGUF partial
+fn is_partial_name(path: &Path) -> bool {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    name.ends_with(".download") && (name.ends_with(".gguf.download") || download_resume::read_info(path).is_some())
+}
+
+fn is_partial(path: &Path) -> bool {
+    path.is_file() && is_partial_name(path)
+}
+
+/// The partial a sidecar without one belongs to
+fn orphaned_sidecar(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let partial = path.with_file_name(name.strip_suffix(".json")?);
+    (!partial.exists() && is_partial_name(&partial)).then_some(partial)
+}
+
+fn collect(dir: &Path, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect(&path, found);
+        } else if is_partial(&path) || orphaned_sidecar(&path).is_some() {
+            found.push(path);
+        }
+    }
+}
+
+fn size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn age(path: &Path, now: SystemTime) -> Duration {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| now.duration_since(modified).ok())
+        .unwrap_or_default()
+}
+
+fn sweep(directories: &[String], active: &HashSet<PathBuf>, policy: Policy, dry_run: bool) -> CleanupReport {
+    let mut report = CleanupReport { dry_run, removed: Vec::new(), kept: Vec::new(), reclaimed_bytes: 0, errors: Vec::new() };
+    let mut found = Vec::new();
+    for dir in directories.iter().filter(|dir| !dir.is_empty()) {
+        collect(Path::new(dir), &mut found);
+    }
+    let now = SystemTime::now();
+    for path in found.into_iter().filter(|path| !active.contains(path)) {
+        let orphan = orphaned_sidecar(&path).is_some();
+        let sidecar = (!orphan).then(|| download_resume::sidecar_path(&path)).filter(|s| s.exists());
+        let file_age = age(&path, now);
+        let file = PartialFile {
+            path: path.to_string_lossy().to_string(),
+            bytes: size(&path) + sidecar.as_deref().map_or(0, size),
+            age_days: file_age.as_secs() / 86_400,
+            resumable: !orphan && download_resume::has_resume_info(&path),
+        };
+        if !orphan && !policy.removes(file_age, file.resumable) {
+            report.kept.push(file);
+            continue;
+        }
+        if !dry_run {
+            if let Err(e) = std::fs::remove_file(&path) {
+                report.errors.push(format!("{}: {}", file.path, e));
+                continue;
+            }
+            if let Some(sidecar) = sidecar {
+                let _ = std::fs::remove_file(sidecar);
+            }
+        }
+        report.reclaimed_bytes += file.bytes;
+        report.removed.push(file);
+    }
+    report
+}
+
+/// Partial files of downloads the download manager still runs, queues or holds paused
+async fn active_partials(state: &AppState) -> HashSet<PathBuf> {
+    let download_manager = state.download_manager.lock().await;
+    download_manager
+        .downloads
+        .values()
+        .filter(|d| !matches!(d.status, DownloadState::Completed | DownloadState::Failed | DownloadState::Cancelled))
+        .flat_map(|d| {
+            d.files.iter().filter_map(|file| {
+                let name = Path::new(file).file_name()?.to_string_lossy().to_string();
+                Some(Path::new(&d.destination).join(format!("{}.download", name)))
+            })
+        })
+        .collect()
+}
+
+/// Clean partial downloads in the models directories Arandu may write to
+pub async fn clean(state: &AppState, dry_run: bool) -> Result<CleanupReport, String> {
+    let (directories, policy) = {
+        let config = state.config.lock().await;
+        let directories: Vec<String> = std::iter::once(config.models_directory.clone())
+            .chain(config.additional_models_directories.iter().cloned())
+            .filter(|dir| crate::model_files::read_only_directory(&config.read_only_models_directories, Path::new(dir)).is_none())
+            .collect();
+        let policy = Policy {
+            retention_days: config.partial_download_retention_days,
+            keep_resumable: config.keep_resumable_downloads,
+        };
+        (directories, policy)
+    };
+    let active = active_partials(state).await;
+    tokio::task::spawn_blocking(move || sweep(&directories, &active, policy, dry_run))
+        .await
+        .map_err(|e| format!("Cleanup failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleanup_keeps_resumable_and_active_partials() {
+        let dir = std::env::temp_dir().join(format!("arandu-cleanup-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("author")).unwrap();
+        let stale = dir.join("author").join("stale.gguf.download");
+        let resumable = dir.join("resumable.gguf.download");
+        let active = dir.join("active.gguf.download");
+        for path in [&stale, &resumable, &active] {
+            std::fs::write(path, vec![0u8; 10]).unwrap();
+        }
+        let info = download_resume::PartialInfo { url: "https://hf.co/m.gguf".to_string(), etag: None, last_modified: None, total_bytes: 100 };
+        download_resume::write_info(&resumable, &info).unwrap();
+        std::fs::write(dir.join("gone.gguf.download.json"), b"{}").unwrap();
+        std::fs::write(dir.join("model.gguf"), b"keep").unwrap();
+        std::fs::write(dir.join("notes.download"), b"keep").unwrap();
+        std::fs::write(dir.join("notes.download.json"), b"{}").unwrap();
+        // Archives are partials too once their sidecar says so
+        let archive = dir.join("llama-b5000-bin.zip.download");
+        std::fs::write(&archive, vec![0u8; 5]).unwrap();
+        download_resume::write_info(&archive, &download_resume::PartialInfo { total_bytes: 0, ..info.clone() }).unwrap();
+        std::fs::write(dir.join("gone.7z.download.json"), serde_json::to_string(&info).unwrap()).unwrap();
+
+        let directories = vec![dir.to_string_lossy().to_string()];
+        let active: HashSet<PathBuf> = [active].into_iter().collect();
+        let policy = Policy { retention_days: 0, keep_resumable: true };
+
+        let report = sweep(&directories, &active, policy, true);
+        assert_eq!(report.removed.len(), 3);
+        assert!(report.removed.iter().any(|f| f.path.ends_with("gone.7z.download.json")));
+        assert!(report.kept.iter().all(|f| f.resumable));
+        assert!(report.kept.iter().any(|f| f.path.ends_with("llama-b5000-bin.zip.download")));
+        assert!(stale.exists());
+
+        let report = sweep(&directories, &active, policy, false);
+        assert_eq!(report.removed.len(), 3);
+        assert!(!stale.exists() && resumable.exists() && dir.join("model.gguf").exists());
+        assert!(dir.join("notes.download").exists() && dir.join("notes.download.json").exists());
+
+        // Not old enough yet
+        let report = sweep(&directories, &HashSet::new(), Policy { retention_days: 7, keep_resumable: false }, true);
+        assert!(report.removed.is_empty());
+        let report = sweep(&directories, &HashSet::new(), Policy { retention_days: 0, keep_resumable: false }, false);
+        assert!(report.removed.iter().any(|f| f.path.ends_with("llama-b5000-bin.zip.download")));
+        assert!(!archive.exists() && !download_resume::sidecar_path(&archive).exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use crate::AppState;
+
+// Downloads wait in one queue and start in order while fewer than
+// `max_concurrent_downloads` are running; the order of waiting downloads can be changed.
+// All running downloads share `download_bandwidth_limit_kb_per_sec`: each chunk books
+// its share of the limit on one clock and the download sleeps until its share is due,
+// so the total rate stays under the cap however many downloads run. Both settings are
+// read as downloads progress, so changes apply without restarting them.
+
+/// How often a waiting download rechecks, in case the limit was raised
+const RECHECK: Duration = Duration::from_secs(2);
+
+static QUEUE: LazyLock<Mutex<VecDeque<String>>> = LazyLock::new(|| Mutex::new(VecDeque::new()));
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+static CHANGED: LazyLock<Notify> = LazyLock::new(Notify::new);
+/// When the bytes booked so far are paid off at the bandwidth limit
+static NEXT_FREE: LazyLock<Mutex<Option<Instant>>> = LazyLock::new(|| Mutex::new(None));
+
+fn queue() -> std::sync::MutexGuard<'static, VecDeque<String>> {
+    QUEUE.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+/// A running download's place among the `max_concurrent_downloads`; frees it on drop
+pub struct Slot(());
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::SeqCst);
+        CHANGED.notify_waiters();
+    }
+}
+
+/// Takes a download out of the queue when its wait ends, cancelled or not
+struct Waiting<'a>(&'a str);
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        queue().retain(|id| id != self.0);
+        CHANGED.notify_waiters();
+    }
+}
+
+/// Whether `download_id` may start: first in the queue with a slot free (0 is no limit)
+fn may_start(queue: &VecDeque<String>, download_id: &str, active: usize, max: usize) -> bool {
+    queue.front().is_some_and(|id| id == download_id) && (max == 0 || active < max)
+}
+
+/// Queue `download_id` and wait until it may start. Cancel by dropping the future.
+pub async fn acquire(download_id: &str, state: &AppState) -> Slot {
+    queue().push_back(download_id.to_string());
+    let _waiting = Waiting(download_id);
+    loop {
+        let notified = CHANGED.notified();
+        let max = state.config.lock().await.max_concurrent_downloads as usize;
+        {
+            let queue = queue();
+            if may_start(&queue, download_id, ACTIVE.load(Ordering::SeqCst), max) {
+                ACTIVE.fetch_add(1, Ordering::SeqCst);
+                return Slot(());
+            }
+        }
+        let _ = tokio::time::timeout(RECHECK, notified).await;
+    }
+}
+
+/// Ids of the downloads waiting to start, next first
+pub fn queued() -> Vec<String> {
+    queue().iter().cloned().collect()
+}
+
+/// Move a waiting download to `position` in the queue (0 starts next)
+pub fn move_to(download_id: &str, position: usize) -> Result<Vec<String>, String> {
+    let mut queue = queue();
+    let from = queue
+        .iter()
+        .position(|id| id == download_id)
+        .ok_or_else(|| format!("Download {} is not queued", download_id))?;
+    if let Some(id) = queue.remove(from) {
+        // Read the length before `insert` borrows the guarded queue mutably
+        let position = position.min(queue.len());
+        queue.insert(position, id);
+    }
+    CHANGED.notify_waiters();
+    Ok(queue.iter().cloned().collect())
+}
+
+/// Book `bytes` at `bytes_per_sec` after `next_free`: the new `next_free`, and how long
+/// to wait from `now` until then
+fn book(next_free: Option<Instant>, now: Instant, bytes: usize, bytes_per_sec: u64) -> (Instant, Duration) {
+    let start = next_free.filter(|at| *at > now).unwrap_or(now);
+    let done = start + Duration::from_secs_f64(bytes as f64 / bytes_per_sec as f64);
+    (done, done - now)
+}
+
+/// Sleep as long as `bytes` take at the global bandwidth limit; no wait without one
+pub async fn throttle(bytes: usize, state: &AppState) {
+    let limit = state.config.lock().await.download_bandwidth_limit_kb_per_sec * 1024;
+    if limit == 0 {
+        return;
+    }
+    let wait = {
+        let mut next_free = NEXT_FREE.lock().unwrap_or_else(|p| p.into_inner());
+        let (done, wait) = book(*next_free, Instant::now(), bytes, limit);
+        *next_free = Some(done);
+        wait
+    };
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_order_and_bandwidth_booking() {
+        let queue: VecDeque<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+        assert!(may_start(&queue, "a", 1, 2));
+        assert!(!may_start(&queue, "b", 0, 2));
+        assert!(!may_start(&queue, "a", 2, 2));
+        assert!(may_start(&queue, "a", 5, 0));
+
+        // 1 MB at 1 MB/s takes a second; a second download's chunk queues behind it
+        let now = Instant::now();
+        let (done, wait) = book(None, now, 1 << 20, 1 << 20);
+        assert_eq!(wait, Duration::from_secs(1));
+        let (_, wait) = book(Some(done), now, 512 << 10, 1 << 20);
+        assert_eq!(wait, Duration::from_millis(1500));
+        // Idle time is not saved up for a burst later
+        let (_, wait) = book(Some(now), now + Duration::from_secs(10), 1 << 20, 1 << 20);
+        assert_eq!(wait, Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,182 @@
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use crate::AppState;
+
+// Downloads survive interruptions. A file is written to `<name>.download` with a
+// `<name>.download.json` sidecar holding its URL and the server's validators (ETag,
+// Last-Modified). A later attempt at the same URL asks for the rest with `Range` and
+// `If-Range`: a 206 appends to the partial file, while a 200 (the file changed on the
+// server, or the server ignores ranges) starts it over. Downloads still running when
+// Arandu exits are kept in storage and started again at the next launch; files already
+// complete are skipped and partial ones pick up where they stopped.
+
+/// What a partial file was downloaded from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartialInfo {
+    pub url: String,
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// Full size of the file; 0 when the server did not say
+    #[serde(default)]
+    pub total_bytes: u64,
+}
+
+impl PartialInfo {
+    /// Validators of a response to a request for `url`
+    pub fn from_response(url: &str, headers: &HeaderMap, total_bytes: u64) -> Self {
+        let header = |name| headers.get(name).and_then(|v: &HeaderValue| v.to_str().ok()).map(str::to_string);
+        Self { url: url.to_string(), etag: header(ETAG), last_modified: header(LAST_MODIFIED), total_bytes }
+    }
+}
+
+pub fn sidecar_path(partial: &Path) -> PathBuf {
+    let mut name = partial.as_os_str().to_os_string();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+/// Whether `partial` has the sidecar a resume needs
+pub fn has_resume_info(partial: &Path) -> bool {
+    sidecar_path(partial).is_file()
+}
+
+/// The sidecar of `partial`, when there is a readable one
+pub fn read_info(partial: &Path) -> Option<PartialInfo> {
+    let raw = std::fs::read_to_string(sidecar_path(partial)).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+pub fn write_info(partial: &Path, info: &PartialInfo) -> Result<(), String> {
+    let raw = serde_json::to_string(info).map_err(|e| e.to_string())?;
+    std::fs::write(sidecar_path(partial), raw).map_err(|e| format!("Failed to save download progress: {}", e))
+}
+
+/// Delete a partial file and its sidecar
+pub async fn discard(partial: &Path) {
+    let _ = tokio::fs::remove_file(partial).await;
+    let _ = tokio::fs::remove_file(sidecar_path(partial)).await;
+}
+
+/// Bytes already in `partial` and what they came from, when they are from `url` and
+/// the file is not complete
+pub fn resumable(partial: &Path, url: &str) -> Option<(u64, PartialInfo)> {
+    let info = read_info(partial)?;
+    let offset = std::fs::metadata(partial).ok()?.len();
+    (info.url == url && offset > 0 && (info.total_bytes == 0 || offset < info.total_bytes)).then_some((offset, info))
+}
+
+/// `Range` asking for everything from `offset`, and `If-Range` so a changed file comes
+/// back whole. Weak ETags are not allowed in `If-Range`; Last-Modified stands in.
+pub fn range_headers(offset: u64, info: &PartialInfo) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if let Ok(range) = HeaderValue::from_str(&format!("bytes={}-", offset)) {
+        headers.insert(RANGE, range);
+    }
+    let validator = info.etag.as_ref().filter(|etag| !etag.starts_with("W/")).or(info.last_modified.as_ref());
+    if let Some(value) = validator.and_then(|v| HeaderValue::from_str(v).ok()) {
+        headers.insert(IF_RANGE, value);
+    }
+    headers
+}
+
+/// First byte of a `Content-Range: bytes <start>-<end>/<size>` header
+fn content_range_start(value: &str) -> Option<u64> {
+    value.trim().strip_prefix("bytes ")?.split('-').next()?.trim().parse().ok()
+}
+
+/// Where the response body starts in the file: `offset` for a 206 that continues the
+/// partial file, 0 when the server sent the whole file
+pub fn body_offset(status: StatusCode, headers: &HeaderMap, offset: u64) -> u64 {
+    let continues = status == StatusCode::PARTIAL_CONTENT
+        && headers
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(content_range_start)
+            == Some(offset);
+    if continues { offset } else { 0 }
+}
+
+/// Feed the bytes already downloaded into `hasher`, so a resumed file is still checked whole
+pub async fn hash_existing(partial: &Path, hasher: &mut Sha256) -> Result<(), String> {
+    use tokio::io::AsyncReadExt;
+    let mut file = tokio::fs::File::open(partial).await.map_err(|e| e.to_string())?;
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = file.read(&mut buffer).await.map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// Start the downloads that were still running when Arandu last exited
+pub fn spawn(state: AppState, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let pending = match crate::storage::run(|db| db.downloads().pending()).await {
+            Ok(pending) => pending,
+            Err(e) => {
+                eprintln!("[Download] Failed to read interrupted downloads: {}", e);
+                return;
+            }
+        };
+        for (id, config) in pending {
+            if let Err(e) = crate::storage::run({
+                let id = id.clone();
+                move |db| db.downloads().finish_pending(&id)
+            })
+            .await
+            {
+                eprintln!("[Download] Failed to forget interrupted download {}: {}", id, e);
+            }
+            match crate::downloader::start_download(config, &state, app_handle.clone()).await {
+                Ok(started) => println!("[Download] Resuming interrupted download {} as {}", id, started.download_id),
+                Err(e) => eprintln!("[Download] Failed to resume interrupted download {}: {}", id, e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_files_resume_only_from_their_own_url_and_offset() {
+        let dir = std::env::temp_dir().join(format!("arandu-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let partial = dir.join("model.gguf.download");
+        std::fs::write(&partial, vec![0u8; 100]).unwrap();
+        assert_eq!(resumable(&partial, "https://hf.co/model.gguf"), None);
+
+        let info = PartialInfo {
+            url: "https://hf.co/model.gguf".to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: Some("Wed, 01 Jan 2026 00:00:00 GMT".to_string()),
+            total_bytes: 1000,
+        };
+        write_info(&partial, &info).unwrap();
+        assert!(sidecar_path(&partial).ends_with("model.gguf.download.json"));
+        assert_eq!(resumable(&partial, "https://hf.co/model.gguf").map(|(offset, _)| offset), Some(100));
+        assert_eq!(resumable(&partial, "https://hf.co/other.gguf"), None);
+
+        let headers = range_headers(100, &info);
+        assert_eq!((headers[RANGE].to_str().unwrap(), headers[IF_RANGE].to_str().unwrap()), ("bytes=100-", "\"abc\""));
+        let weak = PartialInfo { etag: Some("W/\"abc\"".to_string()), ..info.clone() };
+        assert_eq!(range_headers(100, &weak)[IF_RANGE], "Wed, 01 Jan 2026 00:00:00 GMT");
+
+        let mut response = HeaderMap::new();
+        response.insert(CONTENT_RANGE, HeaderValue::from_static("bytes 100-999/1000"));
+        assert_eq!(body_offset(StatusCode::PARTIAL_CONTENT, &response, 100), 100);
+        assert_eq!(body_offset(StatusCode::PARTIAL_CONTENT, &response, 50), 0);
+        // The file changed or ranges are not supported: start over
+        assert_eq!(body_offset(StatusCode::OK, &HeaderMap::new(), 100), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+use url::Url;
+use crate::downloader::DownloadConfig;
+
+// Where a download's files come from. A source turns a `DownloadConfig` into file URLs,
+// the headers to send with them and, where the host publishes one, the SHA-256 each
+// file has to match; `downloader` only moves the bytes. A new host implements
+// `DownloadSource` and is picked in `for_config`.
+
+const USER_AGENT: &str = "Arandu-Tauri/1.0";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteFile {
+    pub url: String,
+    /// Path as requested, shown in the download's file list
+    pub path: String,
+    /// Name the file is saved under in the destination folder
+    pub file_name: String,
+}
+
+pub trait DownloadSource: Send + Sync {
+    /// Shown as the download's source
+    fn describe(&self) -> String;
+    fn resolve_files(&self) -> Result<Vec<RemoteFile>, String>;
+    /// Sent with every file request, credentials included
+    fn headers(&self) -> Vec<(String, String)>;
+    fn expected_sha256(&self, _file: &RemoteFile) -> Option<String> {
+        None
+    }
+}
+
+fn file_name_of(path: &str) -> Result<String, String> {
+    path.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| format!("Invalid file path: {}", path))
+}
+
+fn remote_file(url: String, path: &str) -> Result<RemoteFile, String> {
+    Ok(RemoteFile { url, path: path.to_string(), file_name: file_name_of(path)? })
+}
+
+fn bearer(token: Option<&str>) -> Option<(String, String)> {
+    token.filter(|t| !t.trim().is_empty()).map(|t| ("Authorization".to_string(), format!("Bearer {}", t.trim())))
+}
+
+/// Any HTTP(S) URL: the URL itself, or `files` under it as a base
+pub struct UrlSource {
+    pub base_url: String,
+    pub files: Vec<String>,
+}
+
+impl DownloadSource for UrlSource {
+    fn describe(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn resolve_files(&self) -> Result<Vec<RemoteFile>, String> {
+        if self.files.is_empty() {
+            let parsed = Url::parse(&self.base_url).map_err(|e| e.to_string())?;
+            let name = parsed
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .filter(|s| !s.is_empty())
+                .unwrap_or("download")
+                .to_string();
+            return Ok(vec![RemoteFile { url: self.base_url.clone(), path: name.clone(), file_name: name }]);
+        }
+        self.files
+            .iter()
+            .map(|file| remote_file(format!("{}/{}", self.base_url.trim_end_matches('/'), file.trim_start_matches('/')), file))
+            .collect()
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        vec![("User-Agent".to_string(), "Universal-Downloader/1.0".to_string())]
+    }
+}
+
+/// Files of a Hugging Face repo at a revision, from a `.../resolve/<revision>` URL
+pub struct HuggingFaceSource {
+    /// `author/name`, or `datasets/author/name`
+    pub repo: String,
+    pub revision: String,
+    pub files: Vec<String>,
+    pub token: Option<String>,
+    /// LFS SHA-256 by path in the repo
+    pub sha256: HashMap<String, String>,
+}
+
+impl HuggingFaceSource {
+    /// `None` unless `url` points into a repo's `resolve/` tree. A path after the
+    /// revision is the file itself when `files` is empty, otherwise their folder.
+    pub fn parse(url: &str, files: &[String]) -> Option<Self> {
+        let parsed = Url::parse(url).ok()?;
+        if !matches!(parsed.host_str()?, "huggingface.co" | "hf.co") {
+            return None;
+        }
+        let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let resolve = segments.iter().position(|s| *s == "resolve").filter(|i| *i >= 2)?;
+        let revision = segments.get(resolve + 1)?.to_string();
+        let rest = segments[resolve + 2..].join("/");
+        let files = match (files.is_empty(), rest.is_empty()) {
+            (true, true) => return None,
+            (true, false) => vec![rest],
+            (false, true) => files.to_vec(),
+            (false, false) => files.iter().map(|f| format!("{}/{}", rest, f.trim_start_matches('/'))).collect(),
+        };
+        Some(Self {
+            repo: segments[..resolve].join("/"),
+            revision,
+            files,
+            token: std::env::var("HF_TOKEN").or_else(|_| std::env::var("HUGGING_FACE_HUB_TOKEN")).ok(),
+            sha256: HashMap::new(),
+        })
+    }
+
+    /// Fill `sha256` from the repo tree; files without an LFS hash are not checked
+    pub async fn fetch_hashes(&mut self) {
+        let url = format!("https://huggingface.co/api/{}/tree/{}?recursive=true", self.api_kind_and_repo(), self.revision);
+        let entries = match crate::hf_client::get(&url).await {
+            Ok(response) if response.is_success() => response.json::<Vec<serde_json::Value>>().unwrap_or_default(),
+            Ok(response) => {
+                println!("[Download] No file hashes for {}: HTTP {}", self.repo, response.status());
+                return;
+            }
+            Err(e) => {
+                println!("[Download] No file hashes for {}: {}", self.repo, e);
+                return;
+            }
+        };
+        for entry in entries {
+            let path = entry.get("path").and_then(|v| v.as_str());
+            let oid = entry.get("lfs").and_then(|lfs| lfs.get("oid")).and_then(|v| v.as_str());
+            if let (Some(path), Some(oid)) = (path, oid) {
+                self.sha256.insert(path.to_string(), oid.to_lowercase());
+            }
+        }
+    }
+
+    fn api_kind_and_repo(&self) -> String {
+        match self.repo.split_once('/') {
+            Some(("datasets", repo)) => format!("datasets/{}", repo),
+            Some(("spaces", repo)) => format!("spaces/{}", repo),
+            _ => format!("models/{}", self.repo),
+        }
+    }
+}
+
+impl DownloadSource for HuggingFaceSource {
+    fn describe(&self) -> String {
+        format!("https://huggingface.co/{}/resolve/{}", self.repo, self.revision)
+    }
+
+    fn resolve_files(&self) -> Result<Vec<RemoteFile>, String> {
+        self.files
+            .iter()
+            .map(|file| {
+                let file = file.trim_start_matches('/');
+                remote_file(format!("https://huggingface.co/{}/resolve/{}/{}", self.repo, self.revision, file), file)
+            })
+            .collect()
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        let mut headers = vec![("User-Agent".to_string(), USER_AGENT.to_string())];
+        headers.extend(bearer(self.token.as_deref()));
+        headers
+    }
+
+    fn expected_sha256(&self, file: &RemoteFile) -> Option<String> {
+        self.sha256.get(&file.path).cloned()
+    }
+}
+
+/// A GitHub release asset (`github.com/<owner>/<repo>/releases/download/<tag>/<asset>`)
+pub struct GitHubSource {
+    pub url: String,
+    pub token: Option<String>,
+}
+
+impl GitHubSource {
+    pub fn parse(url: &str, files: &[String], token: Option<String>) -> Option<Self> {
+        let parsed = Url::parse(url).ok()?;
+        let segments: Vec<&str> = parsed.path_segments()?.collect();
+        let is_asset = parsed.host_str()? == "github.com" && segments.len() == 6 && segments[2..4] == ["releases", "download"];
+        (is_asset && files.is_empty()).then(|| Self { url: url.to_string(), token })
+    }
+}
+
+impl DownloadSource for GitHubSource {
+    fn describe(&self) -> String {
+        self.url.clone()
+    }
+
+    fn resolve_files(&self) -> Result<Vec<RemoteFile>, String> {
+        let name = file_name_of(&self.url)?;
+        Ok(vec![RemoteFile { url: self.url.clone(), path: name.clone(), file_name: name }])
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        // Authenticated requests get the higher rate limit and private repos
+        let mut headers = vec![("User-Agent".to_string(), USER_AGENT.to_string())];
+        headers.extend(bearer(self.token.as_deref()));
+        headers
+    }
+}
+
+/// A magnet link, fetched over HTTP from its web seeds (`ws=`, BEP 19). Peer-to-peer
+/// transfer is not supported, so a link without a web seed is rejected.
+pub struct TorrentSource {
+    pub magnet: String,
+    pub name: String,
+    pub web_seed: String,
+}
+
+impl TorrentSource {
+    /// `None` for anything but a magnet link, `Err` for a magnet link that cannot be fetched
+    pub fn parse(url: &str) -> Option<Result<Self, String>> {
+        if !url.starts_with("magnet:") {
+            return None;
+        }
+        let parsed = match Url::parse(url) {
+            Ok(parsed) => parsed,
+            Err(e) => return Some(Err(format!("Invalid magnet link: {}", e))),
+        };
+        let param = |key: &str| parsed.query_pairs().find(|(k, _)| k == key).map(|(_, v)| v.to_string());
+        let Some(name) = param("dn").filter(|n| !n.is_empty() && !n.contains(['/', '\\'])) else {
+            return Some(Err("Magnet link has no file name (dn=)".to_string()));
+        };
+        let Some(web_seed) = param("ws") else {
+            return Some(Err("Magnet link has no web seed (ws=); peer-to-peer downloads are not supported".to_string()));
+        };
+        Some(Ok(Self { magnet: url.to_string(), name, web_seed }))
+    }
+}
+
+impl DownloadSource for TorrentSource {
+    fn describe(&self) -> String {
+        self.magnet.clone()
+    }
+
+    fn resolve_files(&self) -> Result<Vec<RemoteFile>, String> {
+        // A seed ending in `/` is the folder holding the torrent's file
+        let url = if self.web_seed.ends_with('/') {
+            format!("{}{}", self.web_seed, urlencoding::encode(&self.name))
+        } else {
+            self.web_seed.clone()
+        };
+        Ok(vec![RemoteFile { url, path: self.name.clone(), file_name: self.name.clone() }])
+    }
+
+    fn headers(&self) -> Vec<(String, String)> {
+        vec![("User-Agent".to_string(), USER_AGENT.to_string())]
+    }
+}
+
+/// The source a download request is for, with Hugging Face file hashes looked up
+pub async fn for_config(config: &DownloadConfig, github_token: Option<String>) -> Result<Box<dyn DownloadSource>, String> {
+    if let Some(torrent) = TorrentSource::parse(&config.base_url) {
+        return Ok(Box::new(torrent?));
+    }
+    if let Some(mut hf) = HuggingFaceSource::parse(&config.base_url, &config.files) {
+        hf.fetch_hashes().await;
+        return Ok(Box::new(hf));
+    }
+    if let Some(github) = GitHubSource::parse(&config.base_url, &config.files, github_token) {
+        return Ok(Box::new(github));
+    }
+    Ok(Box::new(UrlSource { base_url: config.base_url.clone(), files: config.files.clone() }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn hugging_face_urls_resolve_per_file_with_hashes() {
+        let mut hf = HuggingFaceSource::parse("https://huggingface.co/Qwen/Qwen3-8B-GGUF/resolve/main", &strings(&["Q4_K_M/qwen3-8b-Q4_K_M.gguf"])).unwrap();
+        assert_eq!((hf.repo.as_str(), hf.revision.as_str()), ("Qwen/Qwen3-8B-GGUF", "main"));
+        hf.sha256.insert("Q4_K_M/qwen3-8b-Q4_K_M.gguf".to_string(), "abc".to_string());
+        let files = hf.resolve_files().unwrap();
+        assert_eq!(files[0].url, "https://huggingface.co/Qwen/Qwen3-8B-GGUF/resolve/main/Q4_K_M/qwen3-8b-Q4_K_M.gguf");
+        assert_eq!(files[0].file_name, "qwen3-8b-Q4_K_M.gguf");
+        assert_eq!(hf.expected_sha256(&files[0]).as_deref(), Some("abc"));
+
+        // The file in the URL itself, a dataset repo, and a folder the files sit in
+        let single = HuggingFaceSource::parse("https://hf.co/datasets/a/b/resolve/v1.0/data/x.gguf", &[]).unwrap();
+        assert_eq!((single.repo.as_str(), single.files.clone()), ("datasets/a/b", strings(&["data/x.gguf"])));
+        assert_eq!(single.api_kind_and_repo(), "datasets/a/b");
+        let nested = HuggingFaceSource::parse("https://huggingface.co/a/b/resolve/main/sub", &strings(&["m.gguf"])).unwrap();
+        assert_eq!(nested.files, strings(&["sub/m.gguf"]));
+        assert!(HuggingFaceSource::parse("https://huggingface.co/a/b", &[]).is_none());
+        assert!(HuggingFaceSource::parse("https://example.com/a/b/resolve/main/x", &[]).is_none());
+    }
+
+    #[test]
+    fn other_sources_resolve_without_network() {
+        let asset = "https://github.com/ggml-org/llama.cpp/releases/download/b6000/llama-b6000-bin-win-cuda-x64.zip";
+        let github = GitHubSource::parse(asset, &[], Some("ghp_x".to_string())).unwrap();
+        assert_eq!(github.resolve_files().unwrap()[0].file_name, "llama-b6000-bin-win-cuda-x64.zip");
+        assert!(github.headers().contains(&("Authorization".to_string(), "Bearer ghp_x".to_string())));
+        assert!(GitHubSource::parse("https://github.com/ggml-org/llama.cpp", &[], None).is_none());
+
+        let url = UrlSource { base_url: "https://example.com/models/".to_string(), files: strings(&["/a/m.gguf"]) };
+        assert_eq!(url.resolve_files().unwrap(), vec![RemoteFile {
+            url: "https://example.com/models/a/m.gguf".to_string(),
+            path: "/a/m.gguf".to_string(),
+            file_name: "m.gguf".to_string(),
+        }]);
+
+        let seeded = TorrentSource::parse("magnet:?xt=urn:btih:abcd&dn=model%20Q4.gguf&ws=https%3A%2F%2Fseed.example%2Ffiles%2F").unwrap().unwrap();
+        assert_eq!(seeded.resolve_files().unwrap()[0].url, "https://seed.example/files/model%20Q4.gguf");
+        assert!(TorrentSource::parse("magnet:?xt=urn:btih:abcd&dn=m.gguf").unwrap().is_err());
+        assert!(TorrentSource::parse("https://example.com/m.torrent").is_none());
+    }
+}
@@ -1,15 +1,16 @@
 use crate::AppState;
+use crate::download_source::{DownloadSource, RemoteFile};
+use crate::events::ExtractionProgress;
 use crate::models::DownloadStartResult;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tokio::sync::Mutex;
-use std::sync::Arc;
 use chrono::{DateTime, Utc};
 use std::path::Path;
-use tauri::{Emitter};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum DownloadState {
+    /// Waiting for a free slot, see `download_queue`
+    Queued,
     Starting,
     Downloading,
     Paused,
@@ -27,6 +28,9 @@ pub struct DownloadConfig {
     pub create_subfolder: Option<String>,
     pub files: Vec<String>, // List of files to download (for multi-file downloads)
     pub custom_headers: Option<HashMap<String, String>>,
+    /// SHA-256 of a single-file download, for sources that publish none
+    #[serde(default)]
+    pub expected_sha256: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -55,7 +59,6 @@ pub struct DownloadStatus {
 pub struct DownloadManager {
     pub downloads: HashMap<String, DownloadStatus>,
     pub download_history: Vec<DownloadStatus>,
-    cancellation_tokens: HashMap<String, Arc<Mutex<bool>>>,
 }
 
 impl DownloadManager {
@@ -63,13 +66,11 @@ impl DownloadManager {
         Self {
             downloads: HashMap::new(),
             download_history: Vec::new(),
-            cancellation_tokens: HashMap::new(),
         }
     }
 
     pub fn add_download(&mut self, id: String, status: DownloadStatus) {
-        self.downloads.insert(id.clone(), status);
-        self.cancellation_tokens.insert(id, Arc::new(Mutex::new(false)));
+        self.downloads.insert(id, status);
     }
 
     pub fn get_status(&self, id: &str) -> Option<&DownloadStatus> {
@@ -83,10 +84,10 @@ impl DownloadManager {
                 status.pause_start_time = Some(chrono::Utc::now());
                 Ok(())
             } else {
-                Err("Download is not in a state that can be paused".to_string())
+                Err(crate::i18n::t("download.not_pausable", &[]))
             }
         } else {
-            Err("Download not found".to_string())
+            Err(crate::i18n::t("download.not_found", &[]))
         }
     }
 
@@ -101,26 +102,21 @@ impl DownloadManager {
                 status.status = DownloadState::Downloading;
                 Ok(())
             } else {
-                Err("Download is not paused".to_string())
+                Err(crate::i18n::t("download.not_paused", &[]))
             }
         } else {
-            Err("Download not found".to_string())
+            Err(crate::i18n::t("download.not_found", &[]))
         }
     }
 
     pub fn cancel_download(&mut self, id: &str) -> Result<(), String> {
         if let Some(status) = self.downloads.get_mut(id) {
             status.status = DownloadState::Cancelled;
-            if let Some(token) = self.cancellation_tokens.get(id) {
-                let token = token.clone();
-                tokio::spawn(async move {
-                    let mut token_guard = token.lock().await;
-                    *token_guard = true;
-                });
-            }
+            // Already finished when nothing is registered
+            let _ = crate::task_manager::cancel(id);
             Ok(())
         } else {
-            Err("Download not found".to_string())
+            Err(crate::i18n::t("download.not_found", &[]))
         }
     }
 
@@ -133,15 +129,29 @@ impl DownloadManager {
 }
 
 
+/// Runs once a download has finished and every archive in it was extracted
+pub type OnInstalled = Box<dyn FnOnce() + Send + 'static>;
+
 // Universal download function
 pub async fn start_download(
     config: DownloadConfig,
     state: &AppState,
     app_handle: tauri::AppHandle,
+) -> Result<DownloadStartResult, Box<dyn std::error::Error>> {
+    start_download_then(config, state, app_handle, None).await
+}
+
+/// `start_download`, calling `on_installed` only when the files landed and extracted
+pub async fn start_download_then(
+    config: DownloadConfig,
+    state: &AppState,
+    app_handle: tauri::AppHandle,
+    on_installed: Option<OnInstalled>,
 ) -> Result<DownloadStartResult, Box<dyn std::error::Error>> {
     use tokio::fs;
 
     let download_id = generate_download_id(&config);
+    crate::model_files::ensure_writable(state, Path::new(&config.destination_folder), "download to").await?;
 
     // Create destination folder if it doesn't exist
     let final_destination = if let Some(subfolder) = &config.create_subfolder {
@@ -153,22 +163,26 @@ pub async fn start_download(
         config.destination_folder.clone()
     };
 
-    // Determine files to download
-    let files_to_download = if config.files.is_empty() {
-        // Single file download - extract filename from URL
-        let filename = extract_filename_from_url(&config.base_url)?;
-        vec![filename]
-    } else {
-        config.files.clone()
-    };
+    let github_token = crate::github_token::load(state).await;
+    let source = crate::download_source::for_config(&config, github_token).await?;
+    let remote_files = source.resolve_files()?;
+    if let Some(expected) = &config.expected_sha256 {
+        if remote_files.len() != 1 {
+            return Err("An expected SHA-256 applies to single-file downloads only".into());
+        }
+        if expected.len() != 64 || !expected.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Expected SHA-256 is not 64 hex digits: {}", expected).into());
+        }
+    }
+    let files_to_download: Vec<String> = remote_files.iter().map(|f| f.path.clone()).collect();
 
     // Add to download manager
     {
         let mut download_manager = state.download_manager.lock().await;
         let download_status = DownloadStatus {
             id: download_id.clone(),
-            status: DownloadState::Starting,
-            source_url: config.base_url.clone(),
+            status: DownloadState::Queued,
+            source_url: source.describe(),
             destination: final_destination.clone(),
             files: files_to_download.clone(),
             total_files: files_to_download.len(),
@@ -183,72 +197,118 @@ pub async fn start_download(
             total_paused_time: 0,
             pause_start_time: None,
             error: None,
-            message: Some(format!("Starting download from {}", config.base_url)),
+            message: Some(crate::i18n::t("download.starting", &[("url", &config.base_url)])),
         };
 
         download_manager.add_download(download_id.clone(), download_status);
     }
+    // Started again at the next launch if Arandu exits first, see `download_resume`
+    {
+        let (id, config) = (download_id.clone(), config.clone());
+        if let Err(e) = crate::storage::run(move |db| db.downloads().track_pending(&id, &config)).await {
+            eprintln!("[Download] Failed to remember download {}: {}", download_id, e);
+        }
+    }
 
     // Start the download task in the background
+    let task = crate::task_manager::register("download", Some(download_id.clone()), source.describe());
     let state_clone = state.clone();
     let download_id_for_task = download_id.clone();
     let config_clone = config.clone();
     let app_handle_clone = app_handle.clone();
 
     tokio::spawn(async move {
-        if let Err(e) = execute_download(
-            download_id_for_task.clone(),
-            config_clone,
-            final_destination,
-            files_to_download,
-            &state_clone,
-            app_handle,
-        ).await {
-            // Update download status to failed
+        let result = match task.run(crate::download_queue::acquire(&download_id_for_task, &state_clone)).await {
+            Ok(_slot) => {
+                if let Some(status) = state_clone.download_manager.lock().await.downloads.get_mut(&download_id_for_task) {
+                    status.status = DownloadState::Starting;
+                    status.start_time = chrono::Utc::now();
+                }
+                execute_download(
+                    download_id_for_task.clone(),
+                    config_clone,
+                    source,
+                    remote_files,
+                    final_destination,
+                    &task,
+                    &state_clone,
+                    app_handle,
+                ).await
+            }
+            Err(e) => Err(e),
+        };
+        if let (Ok(true), Some(on_installed)) = (&result, on_installed) {
+            on_installed();
+        }
+        let finished = {
             let mut download_manager = state_clone.download_manager.lock().await;
-            if let Some(status) = download_manager.downloads.get_mut(&download_id_for_task) {
-                status.status = DownloadState::Failed;
-                status.error = Some(e.to_string());
+            let status = download_manager.downloads.get_mut(&download_id_for_task);
+            match (&result, status) {
+                (Err(_), Some(status)) if task.is_cancelled() => status.status = DownloadState::Cancelled,
+                (Err(e), Some(status)) => {
+                    status.status = DownloadState::Failed;
+                    status.error = Some(e.to_string());
+                }
+                _ => {}
+            }
+            download_manager.downloads.get(&download_id_for_task).cloned()
+        };
+        let id = download_id_for_task.clone();
+        if let Err(e) = crate::storage::run(move |db| db.downloads().finish_pending(&id)).await {
+            eprintln!("[Download] Failed to forget finished download {}: {}", download_id_for_task, e);
+        }
+        // Kept so the download manager still lists it after a restart
+        if let Some(finished) = finished {
+            if matches!(finished.status, DownloadState::Completed) {
+                crate::analytics::record_download(finished.downloaded_bytes, finished.elapsed_time);
+            }
+            if let Err(e) = crate::storage::run(move |db| db.downloads().record(&finished)).await {
+                eprintln!("[Download] Failed to record download history: {}", e);
             }
         }
     });
 
     // Emit an event to open the download manager window
-    let _ = app_handle_clone.emit("open-download-manager", ());
+    crate::events::emit(&app_handle_clone, &crate::events::OpenDownloadManager);
 
     Ok(DownloadStartResult {
         download_id,
-        message: format!("Download started from {}", config.base_url),
+        message: crate::i18n::t("download.started", &[("url", &config.base_url)]),
     })
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn execute_download(
     download_id: String,
     config: DownloadConfig,
+    source: Box<dyn DownloadSource>,
+    files: Vec<RemoteFile>,
     destination_folder: String,
-    files: Vec<String>,
+    task: &crate::task_manager::Task,
     state: &AppState,
     app_handle: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<bool, String> {
     use tokio::fs::File;
     use tokio::io::AsyncWriteExt;
     use std::path::Path;
     use futures_util::StreamExt;
-    use tauri::Emitter;
-    use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, USER_AGENT};
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT};
+    use sha2::{Digest, Sha256};
 
     let client = reqwest::Client::new();
     let mut last_emit_time = std::time::Instant::now();
     let mut last_progress = 0u8;
+    let mut extracted = true;
 
-    for (file_index, file_path) in files.iter().enumerate() {
+    for (file_index, remote) in files.iter().enumerate() {
+        let file_path = &remote.path;
         // Check if download was cancelled before starting each file
-        if check_cancellation_status(&download_id, state).await? {
-            return Err("Download cancelled by user".to_string());
+        if task.is_cancelled() {
+            return Err(crate::i18n::t("download.cancelled", &[]));
         }
 
         // Wait if paused
-        wait_if_paused(&download_id, state).await?;
+        wait_if_paused(&download_id, task, state).await?;
 
         // Update current file
         {
@@ -259,21 +319,9 @@ async fn execute_download(
             }
         }
 
-        // Construct download URL
-        let download_url = if files.len() == 1 && config.files.is_empty() {
-            // Single file download from direct URL
-            config.base_url.clone()
-        } else {
-            // Multi-file download or specific file from base URL
-            format!("{}/{}", config.base_url.trim_end_matches('/'), file_path.trim_start_matches('/'))
-        };
-
-        let file_name = Path::new(file_path).file_name()
-            .ok_or("Invalid file path")?
-            .to_string_lossy()
-            .to_string();
-        let final_path = Path::new(&destination_folder).join(&file_name);
-        let temp_path = Path::new(&destination_folder).join(format!("{}.download", file_name));
+        let file_name = remote.file_name.clone();
+        let final_path = crate::long_path::extended(&Path::new(&destination_folder).join(&file_name));
+        let temp_path = crate::long_path::extended(&Path::new(&destination_folder).join(format!("{}.download", file_name)));
 
         // Check if final file already exists
         if final_path.exists() {
@@ -285,41 +333,57 @@ async fn execute_download(
             continue;
         }
 
-        // Create request with headers (avoid duplicate User-Agent)
+        // The source's headers, then the caller's on top
         let mut headers_map = HeaderMap::new();
         // Always send a generic Accept to play nice with CDNs
         headers_map.insert(ACCEPT, HeaderValue::from_static("*/*"));
-
-        if let Some(custom) = &config.custom_headers {
-            for (key, value) in custom {
-                if let (Ok(name), Ok(val)) = (
-                    HeaderName::from_bytes(key.as_bytes()),
-                    HeaderValue::from_str(value),
-                ) {
-                    headers_map.insert(name, val);
-                }
+        let custom = config.custom_headers.iter().flatten().map(|(k, v)| (k.clone(), v.clone()));
+        for (key, value) in source.headers().into_iter().chain(custom) {
+            if let (Ok(name), Ok(val)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                headers_map.insert(name, val);
             }
-        } else {
-            // Default UA only if caller didn't supply one
-            headers_map.insert(
-                USER_AGENT,
-                HeaderValue::from_static("Universal-Downloader/1.0"),
-            );
         }
 
-        let request = client.get(&download_url).headers(headers_map);
+        // Continue a partial file from an earlier attempt
+        let resume = crate::download_resume::resumable(&temp_path, &remote.url);
+        let mut request = client.get(&remote.url).headers(headers_map.clone());
+        if let Some((offset, info)) = &resume {
+            request = request.headers(crate::download_resume::range_headers(*offset, info));
+        }
 
         // Start downloading to temp file
-        let response = request
-            .send()
+        let mut response = task
+            .run(request.send())
             .await
+            .map_err(|_| crate::i18n::t("download.cancelled", &[]))?
             .map_err(|e| e.to_string())?;
 
+        if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && resume.is_some() {
+            crate::download_resume::discard(&temp_path).await;
+            response = task
+                .run(client.get(&remote.url).headers(headers_map).send())
+                .await
+                .map_err(|_| crate::i18n::t("download.cancelled", &[]))?
+                .map_err(|e| e.to_string())?;
+        }
+
         if !response.status().is_success() {
             return Err(format!("Failed to download {}: {}", file_path, response.status()));
         }
 
-        let total_size = response.content_length().unwrap_or(0);
+        let offset = crate::download_resume::body_offset(
+            response.status(),
+            response.headers(),
+            resume.as_ref().map_or(0, |(offset, _)| *offset),
+        );
+        let total_size = response.content_length().map_or(0, |length| offset + length);
+        let info = crate::download_resume::PartialInfo::from_response(&remote.url, response.headers(), total_size);
+        if let Err(e) = crate::download_resume::write_info(&temp_path, &info) {
+            eprintln!("[Download] {}; {} will not resume if interrupted", e, file_path);
+        }
 
         // Update total bytes
         {
@@ -329,31 +393,53 @@ async fn execute_download(
             }
         }
 
-        // Create the temp file
-        let mut file = File::create(&temp_path).await
-            .map_err(|e| e.to_string())?;
-        let mut downloaded = 0u64;
+        // Append to the partial file when the server continues it, otherwise start over
+        let mut file = if offset > 0 {
+            tokio::fs::OpenOptions::new().append(true).open(&temp_path).await
+        } else {
+            File::create(&temp_path).await
+        }
+        .map_err(|e| e.to_string())?;
+        let mut downloaded = offset;
+        let expected_sha256 = config.expected_sha256.clone().or_else(|| source.expected_sha256(remote));
+        let mut hasher = expected_sha256.as_ref().map(|_| Sha256::new());
+        if let (Some(hasher), true) = (hasher.as_mut(), offset > 0) {
+            crate::download_resume::hash_existing(&temp_path, hasher).await?;
+        }
         let mut stream = response.bytes_stream();
         let start_time = std::time::Instant::now();
 
-        while let Some(chunk) = stream.next().await {
-            // Check for cancellation during download
-            if check_cancellation_status(&download_id, state).await? {
-                let _ = tokio::fs::remove_file(&temp_path).await;
-                return Err("Download cancelled by user".to_string());
-            }
+        loop {
+            // A stalled stream still gives way to a cancel
+            let chunk = match task.run(stream.next()).await {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => break,
+                Err(_) => {
+                    drop(file);
+                    crate::download_resume::discard(&temp_path).await;
+                    return Err(crate::i18n::t("download.cancelled", &[]));
+                }
+            };
 
             // Handle pause
-            wait_if_paused(&download_id, state).await?;
+            if let Err(e) = wait_if_paused(&download_id, task, state).await {
+                drop(file);
+                crate::download_resume::discard(&temp_path).await;
+                return Err(e);
+            }
 
             let chunk = chunk.map_err(|e| e.to_string())?;
+            crate::download_queue::throttle(chunk.len(), state).await;
             file.write_all(&chunk).await
                 .map_err(|e| e.to_string())?;
+            if let Some(hasher) = hasher.as_mut() {
+                hasher.update(&chunk);
+            }
             downloaded += chunk.len() as u64;
 
-            // Calculate speed and elapsed time
+            // Calculate speed and elapsed time; bytes from an earlier attempt do not count
             let elapsed = start_time.elapsed().as_secs_f64();
-            let speed = if elapsed > 0.0 { downloaded as f64 / elapsed } else { 0.0 };
+            let speed = if elapsed > 0.0 { (downloaded - offset) as f64 / elapsed } else { 0.0 };
 
             // Update progress
             {
@@ -395,12 +481,25 @@ async fn execute_download(
                 // Emit directly without spawning a new task
                 let download_manager = state.download_manager.lock().await;
                 if let Some(status) = download_manager.downloads.get(&download_id) {
-                    let _ = app_handle.emit("download-progress", status.clone());
+                    crate::events::emit(&app_handle, status);
                 }
+                task.report(Some(current_progress), Some(file_path.clone()));
+            }
+        }
+
+        file.flush().await.map_err(|e| e.to_string())?;
+        drop(file);
+        if let (Some(expected), Some(hasher)) = (&expected_sha256, hasher) {
+            let actual = format!("{:x}", hasher.finalize());
+            if !actual.eq_ignore_ascii_case(expected) {
+                crate::download_resume::discard(&temp_path).await;
+                return Err(format!(
+                    "Checksum mismatch for {}: expected SHA-256 {}, got {}. The download is corrupt or the file changed; it was deleted",
+                    file_path, expected, actual
+                ));
             }
         }
 
-        
                 // Move temp file to final location
                 // First, try to remove the final path if it exists (in case of resumed download)
                 if final_path.exists() {
@@ -424,8 +523,9 @@ async fn execute_download(
                         }
                     }
                 }
-        // Extract if requested and file is a zip
-        if config.auto_extract && file_name.to_lowercase().ends_with(".zip") {
+                let _ = tokio::fs::remove_file(crate::download_resume::sidecar_path(&temp_path)).await;
+        // Extract if requested and file is an archive
+        if config.auto_extract && crate::archive::ArchiveKind::from_name(&file_name).is_some() {
             // Update status to extracting
             {
                 let mut download_manager = state.download_manager.lock().await;
@@ -439,19 +539,20 @@ async fn execute_download(
             //println!("Emitting extraction start event for {}", download_id);
             let download_manager = state.download_manager.lock().await;
             if let Some(status) = download_manager.downloads.get(&download_id) {
-                let _ = app_handle.emit("download-progress", status.clone());
+                crate::events::emit(&app_handle, status);
             }
             
-            if let Err(e) = extract_zip(&final_path, &destination_folder, &download_id, &app_handle).await {
+            if let Err(e) = extract_archive(&final_path, &destination_folder, &download_id, &app_handle).await {
                 // Don't fail the download, just log the extraction error
+                extracted = false;
                 let mut download_manager = state.download_manager.lock().await;
                 if let Some(status) = download_manager.downloads.get_mut(&download_id) {
                     status.message = Some(format!("Downloaded but extraction failed: {}", e));
                 }
             } else {
-                // Remove the zip file after successful extraction
+                // Remove the archive after successful extraction
                 if let Err(e) = tokio::fs::remove_file(&final_path).await {
-                    eprintln!("Warning: Failed to remove zip file after extraction: {}", e);
+                    eprintln!("Warning: Failed to remove archive after extraction: {}", e);
                 }
             }
         }
@@ -472,14 +573,14 @@ async fn execute_download(
         if let Some(status) = download_manager.downloads.get_mut(&download_id) {
             status.status = DownloadState::Completed;
             status.progress = 100;
-            status.message = Some(format!("Download completed from {}", config.base_url));
+            status.message = Some(crate::i18n::t("download.completed", &[("url", &config.base_url)]));
         }
     }
 
     // Emit event to frontend
-    app_handle.emit("download-complete", ()).unwrap();
+    crate::events::emit(&app_handle, &crate::events::DownloadComplete);
 
-    Ok(())
+    Ok(extracted)
 }
 
 
@@ -508,7 +609,7 @@ fn extract_filename_from_url(url: &str) -> Result<String, String> {
     
     let parsed_url = Url::parse(url).map_err(|e| e.to_string())?;
     let filename = parsed_url.path_segments()
-        .and_then(|segments| segments.last())
+        .and_then(|mut segments| segments.next_back())
         .filter(|s| !s.is_empty())
         .unwrap_or("download")
         .to_string();
@@ -516,21 +617,15 @@ fn extract_filename_from_url(url: &str) -> Result<String, String> {
     Ok(filename)
 }
 
-async fn check_cancellation_status(download_id: &str, state: &AppState) -> Result<bool, String> {
-    let download_manager = state.download_manager.lock().await;
-    if let Some(status) = download_manager.downloads.get(download_id) {
-        Ok(matches!(status.status, DownloadState::Cancelled))
-    } else {
-        Err("Download not found".to_string())
-    }
-}
-
-async fn wait_if_paused(download_id: &str, state: &AppState) -> Result<(), String> {
+async fn wait_if_paused(download_id: &str, task: &crate::task_manager::Task, state: &AppState) -> Result<(), String> {
     loop {
+        if task.is_cancelled() {
+            return Err(crate::i18n::t("download.cancelled", &[]));
+        }
         let download_manager = state.download_manager.lock().await;
         if let Some(status) = download_manager.downloads.get(download_id) {
             if matches!(status.status, DownloadState::Cancelled) {
-                return Err("Download cancelled by user".to_string());
+                return Err(crate::i18n::t("download.cancelled", &[]));
             }
             if matches!(status.status, DownloadState::Paused) {
                 drop(download_manager); // Release the lock
@@ -539,66 +634,58 @@ async fn wait_if_paused(download_id: &str, state: &AppState) -> Result<(), Strin
             }
             break; // Not paused, continue with download
         } else {
-            return Err("Download not found".to_string());
+            return Err(crate::i18n::t("download.not_found", &[]));
         }
     }
     Ok(())
 }
 
-async fn extract_zip(zip_path: &Path, destination: &str, download_id: &str, app_handle: &tauri::AppHandle) -> Result<(), String> {
-    use std::fs::File;
-    use std::io::BufReader;
-    use zip::ZipArchive;
+/// Unpack a downloaded archive next to it on a blocking thread, reporting each entry
+async fn extract_archive(archive_path: &Path, destination: &str, download_id: &str, app_handle: &tauri::AppHandle) -> Result<(), String> {
+    // Emit extraction start event
+    crate::events::emit(app_handle, &ExtractionProgress {
+        download_id: download_id.to_string(),
+        extraction_progress: 0,
+        extraction_total_files: 0,
+        extraction_completed_files: 0,
+        current_extracting_file: "Starting extraction...".to_string(),
+    });
 
-    let file = File::open(zip_path).map_err(|e| format!("Failed to open zip file: {}", e))?;
-    let reader = BufReader::new(file);
-    let mut archive = ZipArchive::new(reader).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+    let archive_path = archive_path.to_path_buf();
+    let destination = std::path::PathBuf::from(destination);
+    let download_id = download_id.to_string();
+    let app_handle = app_handle.clone();
+    tokio::task::spawn_blocking(move || {
+        crate::archive::extract(&archive_path, &destination, &mut |progress: &crate::archive::Progress| {
+            crate::events::emit(&app_handle, &ExtractionProgress {
+                download_id: download_id.clone(),
+                extraction_progress: progress.percent,
+                extraction_total_files: progress.total_files,
+                extraction_completed_files: progress.completed_files,
+                current_extracting_file: progress.entry.clone(),
+            });
+        })?;
+        hoist_server_binaries(&destination)
+    })
+    .await
+    .map_err(|e| format!("Extraction task failed: {}", e))?
+}
 
-    let total_files = archive.len();
-    
-    // Emit extraction start event with total file count
-    let _ = app_handle.emit("extraction-progress", serde_json::json!({
-        "download_id": download_id,
-        "extraction_progress": 0,
-        "extraction_total_files": total_files,
-        "extraction_completed_files": 0,
-        "current_extracting_file": "Starting extraction..."
-    }));
-
-    for i in 0..total_files {
-        let mut file = archive.by_index(i).map_err(|e| format!("Failed to read zip entry: {}", e))?;
-        let outpath = Path::new(destination).join(file.name());
-
-        if file.name().ends_with('/') {
-            // Directory
-            std::fs::create_dir_all(&outpath).map_err(|e| format!("Failed to create directory: {}", e))?;
-        } else {
-            // File
-            if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    std::fs::create_dir_all(p).map_err(|e| format!("Failed to create parent directory: {}", e))?;
-                }
-            }
-            // Ensure the destination file doesn't exist before extraction to avoid conflicts
-            if outpath.exists() {
-                std::fs::remove_file(&outpath).map_err(|e| format!("Failed to remove existing file before extraction: {}", e))?;
-            }
-            let mut outfile = File::create(&outpath).map_err(|e| format!("Failed to create output file: {}", e))?;
-            std::io::copy(&mut file, &mut outfile).map_err(|e| format!("Failed to extract file: {}", e))?;
+/// macOS and Linux release zips keep their binaries in `build/bin/`; move them up so the
+/// version folder has llama-server at its root like the Windows builds
+fn hoist_server_binaries(destination: &Path) -> Result<(), String> {
+    let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+    if destination.join(exe_name).is_file() {
+        return Ok(());
+    }
+    let Ok(server_root) = crate::find_server_root_dir(destination, exe_name) else { return Ok(()) };
+    let entries = std::fs::read_dir(&server_root).map_err(|e| format!("Failed to read {}: {}", server_root.display(), e))?;
+    for entry in entries.flatten() {
+        let target = destination.join(entry.file_name());
+        if !target.exists() {
+            std::fs::rename(entry.path(), &target).map_err(|e| format!("Failed to move {}: {}", target.display(), e))?;
         }
-
-        // Calculate and emit progress
-        let completed_files = i + 1;
-        let progress = ((completed_files as f64 / total_files as f64) * 100.0) as u8;
-        
-        let _ = app_handle.emit("extraction-progress", serde_json::json!({
-            "download_id": download_id,
-            "extraction_progress": progress,
-            "extraction_total_files": total_files,
-            "extraction_completed_files": completed_files,
-            "current_extracting_file": file.name()
-        }));
     }
-
+    println!("Moved llama.cpp binaries from {} to {}", server_root.display(), destination.display());
     Ok(())
 }
\ No newline at end of file
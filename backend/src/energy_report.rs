@@ -0,0 +1,160 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::AppState;
+
+// Approximate energy use per model and backend, from GPU power sampled during
+// generations (proxy streams and chat messages with `gpu_power` metadata). Only GPU
+// board power is counted, so CPU-only runs and the rest of the system are missing.
+
+const MAX_SAMPLES: usize = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnergySample {
+    pub model: String,
+    #[serde(default)]
+    pub backend: Option<String>,
+    /// `proxy` or `chat`
+    pub source: String,
+    pub tokens: u64,
+    pub energy_wh: f64,
+    pub avg_power_watts: f32,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EnergyReportRow {
+    pub model: String,
+    pub backend: String,
+    pub samples: u32,
+    pub tokens: u64,
+    pub energy_wh: f64,
+    pub wh_per_1k_tokens: f64,
+    pub avg_power_watts: f32,
+}
+
+/// Where samples were kept before they moved into `storage`
+fn legacy_samples_path() -> Result<PathBuf, String> {
+    Ok(crate::arandu_base_dir()?.join("energy_samples.json"))
+}
+
+/// Move `energy_samples.json` into the store the first time it is seen
+fn import_legacy_samples(db: &crate::storage::Db) -> Result<(), String> {
+    let path = legacy_samples_path()?;
+    let Ok(raw) = fs::read_to_string(&path) else { return Ok(()) };
+    let samples: Vec<EnergySample> = serde_json::from_str(&raw).unwrap_or_default();
+    db.usage().record(&samples, MAX_SAMPLES)?;
+    fs::rename(&path, path.with_extension("json.imported"))
+        .map_err(|e| format!("Failed to retire energy_samples.json: {}", e))
+}
+
+fn read_samples() -> Vec<EnergySample> {
+    crate::storage::with(|db| {
+        import_legacy_samples(db)?;
+        db.usage().samples()
+    })
+    .unwrap_or_else(|e| {
+        eprintln!("[Energy] {}", e);
+        Vec::new()
+    })
+}
+
+/// Store a sample, keeping the newest `MAX_SAMPLES`
+pub fn record(sample: EnergySample) {
+    if sample.tokens == 0 || sample.energy_wh <= 0.0 {
+        return;
+    }
+    let result = crate::storage::with(|db| {
+        import_legacy_samples(db)?;
+        db.usage().record(&[sample], MAX_SAMPLES)
+    });
+    if let Err(e) = result {
+        eprintln!("[Energy] {}", e);
+    }
+}
+
+/// Backend of the process serving `server_url`, from its llama.cpp build folder name
+pub async fn served_backend(state: &AppState, server_url: &str) -> Option<String> {
+    let port = url::Url::parse(server_url).ok()?.port_or_known_default()?;
+    let running = state.running_processes.lock().await;
+    let process = running.values().find(|p| p.port == port && !p.model_path.starts_with("tunnel:"))?;
+    let executable = PathBuf::from(process.command.first()?);
+    let folder = executable.parent()?.file_name()?.to_string_lossy().to_string();
+    Some(crate::gpu_runtime::backend_from_name(&folder))
+}
+
+/// Rows per model and backend, most efficient first
+pub fn build_report(samples: &[EnergySample], since: Option<DateTime<Utc>>) -> Vec<EnergyReportRow> {
+    let mut groups: BTreeMap<(String, String), Vec<&EnergySample>> = BTreeMap::new();
+    for sample in samples.iter().filter(|s| since.is_none_or(|since| s.recorded_at >= since)) {
+        let backend = sample.backend.clone().unwrap_or_else(|| "unknown".to_string());
+        groups.entry((sample.model.clone(), backend)).or_default().push(sample);
+    }
+
+    let mut rows: Vec<EnergyReportRow> = groups
+        .into_iter()
+        .map(|((model, backend), group)| {
+            let tokens: u64 = group.iter().map(|s| s.tokens).sum();
+            let energy_wh: f64 = group.iter().map(|s| s.energy_wh).sum();
+            EnergyReportRow {
+                model,
+                backend,
+                samples: group.len() as u32,
+                tokens,
+                energy_wh,
+                wh_per_1k_tokens: if tokens > 0 { energy_wh * 1000.0 / tokens as f64 } else { 0.0 },
+                avg_power_watts: group.iter().map(|s| s.avg_power_watts).sum::<f32>() / group.len() as f32,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| a.wh_per_1k_tokens.total_cmp(&b.wh_per_1k_tokens));
+    rows
+}
+
+pub fn energy_report(since_days: Option<u32>) -> serde_json::Value {
+    let since = since_days.map(|days| Utc::now() - chrono::Duration::days(days as i64));
+    let samples = read_samples();
+    let rows = build_report(&samples, since);
+    serde_json::json!({
+        "rows": rows,
+        "total_samples": samples.len(),
+        "note": "Approximate: GPU board power only (NVIDIA), CPU and the rest of the system are not measured",
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(model: &str, backend: Option<&str>, tokens: u64, energy_wh: f64) -> EnergySample {
+        EnergySample {
+            model: model.to_string(),
+            backend: backend.map(str::to_string),
+            source: "proxy".to_string(),
+            tokens,
+            energy_wh,
+            avg_power_watts: 200.0,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn report_groups_by_model_and_backend() {
+        let samples = vec![
+            sample("qwen-q8", Some("cuda"), 1000, 2.0),
+            sample("qwen-q8", Some("cuda"), 1000, 1.0),
+            sample("qwen-q4", Some("vulkan"), 2000, 1.0),
+            sample("qwen-q4", None, 500, 1.0),
+        ];
+        let rows = build_report(&samples, None);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].model, "qwen-q4");
+        assert_eq!(rows[0].wh_per_1k_tokens, 0.5);
+        assert_eq!(rows[1].wh_per_1k_tokens, 1.5);
+        assert_eq!(rows[2].backend, "unknown");
+
+        assert!(build_report(&samples, Some(Utc::now() + chrono::Duration::hours(1))).is_empty());
+    }
+}
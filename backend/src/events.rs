@@ -0,0 +1,364 @@
+use serde::Serialize;
+use std::borrow::Cow;
+use tauri::Emitter;
+use crate::app_health::AppHealth;
+use crate::downloader::DownloadStatus;
+use crate::generation_metrics::GenerationMetrics;
+use crate::models::BackendBenchmarkResult;
+
+// Events the backend emits with a stable payload. Each payload type names its event,
+// so an event cannot go out with the wrong shape, and `list_event_schemas` describes
+// them for the frontend and external consumers. A payload change that would break a
+// listener bumps `VERSION`, which moves the event to `<name>.v<version>`; additions
+// of fields do not. Events not listed here are internal and may change freely.
+
+pub trait AppEvent: Serialize + Clone {
+    const NAME: &'static str;
+    const VERSION: u32 = 1;
+    const DESCRIPTION: &'static str;
+    /// Payload fields and their JSON types; empty for events without a payload
+    const FIELDS: &'static [(&'static str, &'static str)];
+}
+
+pub fn event_name<E: AppEvent>() -> Cow<'static, str> {
+    match E::VERSION {
+        1 => Cow::Borrowed(E::NAME),
+        version => Cow::Owned(format!("{}.v{}", E::NAME, version)),
+    }
+}
+
+pub fn emit<E: AppEvent>(app_handle: &tauri::AppHandle, payload: &E) {
+    let _ = app_handle.emit(event_name::<E>().as_ref(), payload);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventSchema {
+    pub name: String,
+    pub version: u32,
+    pub description: &'static str,
+    pub fields: Vec<EventField>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventField {
+    pub key: &'static str,
+    #[serde(rename = "type")]
+    pub value_type: &'static str,
+}
+
+fn schema<E: AppEvent>() -> EventSchema {
+    EventSchema {
+        name: event_name::<E>().into_owned(),
+        version: E::VERSION,
+        description: E::DESCRIPTION,
+        fields: E::FIELDS.iter().map(|(key, value_type)| EventField { key, value_type }).collect(),
+    }
+}
+
+pub fn schemas() -> Vec<EventSchema> {
+    vec![
+        schema::<DownloadStatus>(),
+        schema::<DownloadComplete>(),
+        schema::<OpenDownloadManager>(),
+        schema::<ExtractionProgress>(),
+        schema::<FileDeleted>(),
+        schema::<ModelReady>(),
+        schema::<ModelRestarting>(),
+        schema::<ModelUnloadedIdle>(),
+        schema::<BackendBenchmarkProgress>(),
+        schema::<TaskProgress>(),
+        schema::<GenerationMetrics>(),
+        schema::<AppHealth>(),
+    ]
+}
+
+impl AppEvent for DownloadStatus {
+    const NAME: &'static str = "download-progress";
+    const DESCRIPTION: &'static str = "Progress of a download, at most every 500ms or 1%";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("id", "string"),
+        ("status", "string"),
+        ("source_url", "string"),
+        ("destination", "string"),
+        ("files", "string[]"),
+        ("total_files", "integer"),
+        ("files_completed", "integer"),
+        ("current_file", "string"),
+        ("progress", "integer"),
+        ("downloaded_bytes", "integer"),
+        ("total_bytes", "integer"),
+        ("speed", "number"),
+        ("start_time", "string"),
+        ("elapsed_time", "integer"),
+        ("total_paused_time", "integer"),
+        ("pause_start_time", "string|null"),
+        ("error", "string|null"),
+        ("message", "string|null"),
+    ];
+}
+
+/// A download finished or a model was added to the library; the model list should refresh
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DownloadComplete;
+
+impl AppEvent for DownloadComplete {
+    const NAME: &'static str = "download-complete";
+    const DESCRIPTION: &'static str = "A download finished or a model was added; refresh the model list";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[];
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct OpenDownloadManager;
+
+impl AppEvent for OpenDownloadManager {
+    const NAME: &'static str = "open-download-manager";
+    const DESCRIPTION: &'static str = "A download started; show the download manager";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractionProgress {
+    pub download_id: String,
+    pub extraction_progress: u8,
+    pub extraction_total_files: usize,
+    pub extraction_completed_files: usize,
+    pub current_extracting_file: String,
+}
+
+impl AppEvent for ExtractionProgress {
+    const NAME: &'static str = "extraction-progress";
+    const DESCRIPTION: &'static str = "Extracting a downloaded archive, once per entry; the file total is 0 for tar.gz";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("download_id", "string"),
+        ("extraction_progress", "integer"),
+        ("extraction_total_files", "integer"),
+        ("extraction_completed_files", "integer"),
+        ("current_extracting_file", "string"),
+    ];
+}
+
+/// A model file was deleted, moved or renamed
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct FileDeleted;
+
+impl AppEvent for FileDeleted {
+    const NAME: &'static str = "file-deleted";
+    const DESCRIPTION: &'static str = "A model file was deleted, moved or renamed; refresh the model list";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelReady {
+    pub process_id: String,
+    pub model_path: String,
+    pub model_name: String,
+    pub host: String,
+    pub port: u16,
+    pub load_ms: u64,
+}
+
+impl AppEvent for ModelReady {
+    const NAME: &'static str = "model-ready";
+    const DESCRIPTION: &'static str = "A launched model answered /health and takes requests";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("process_id", "string"),
+        ("model_path", "string"),
+        ("model_name", "string"),
+        ("host", "string"),
+        ("port", "integer"),
+        ("load_ms", "integer"),
+    ];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelRestarting {
+    pub process_id: String,
+    pub model_path: String,
+    pub model_name: String,
+    /// -1 when the server was killed by a signal
+    pub exit_code: i32,
+    pub attempt: u32,
+    pub max_retries: u32,
+    pub delay_ms: u64,
+}
+
+impl AppEvent for ModelRestarting {
+    const NAME: &'static str = "model-restarting";
+    const DESCRIPTION: &'static str = "A model exited and its restart policy relaunches it after delay_ms";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("process_id", "string"),
+        ("model_path", "string"),
+        ("model_name", "string"),
+        ("exit_code", "integer"),
+        ("attempt", "integer"),
+        ("max_retries", "integer"),
+        ("delay_ms", "integer"),
+    ];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelUnloadedIdle {
+    pub process_id: String,
+    pub model_path: String,
+    pub model_name: String,
+    pub idle_minutes: u32,
+}
+
+impl AppEvent for ModelUnloadedIdle {
+    const NAME: &'static str = "model-unloaded-idle";
+    const DESCRIPTION: &'static str = "A model's server was stopped after idle_minutes without requests";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("process_id", "string"),
+        ("model_path", "string"),
+        ("model_name", "string"),
+        ("idle_minutes", "integer"),
+    ];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendBenchmarkProgress {
+    pub model_path: String,
+    pub backend: String,
+    pub index: usize,
+    pub total: usize,
+    /// `running`, `done` or `failed`
+    pub status: &'static str,
+    /// Once the backend finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<BackendBenchmarkResult>,
+}
+
+impl AppEvent for BackendBenchmarkProgress {
+    const NAME: &'static str = "backend-benchmark-progress";
+    const DESCRIPTION: &'static str = "One llama.cpp backend started or finished benchmarking a model";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("model_path", "string"),
+        ("backend", "string"),
+        ("index", "integer"),
+        ("total", "integer"),
+        ("status", "string"),
+        ("result", "object|absent"),
+    ];
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskProgress {
+    pub task_id: String,
+    /// `download`, `scan`, `benchmark`, `batch_launch` or `mcp_discovery`
+    pub kind: String,
+    pub label: String,
+    pub progress: Option<u8>,
+    pub message: Option<String>,
+    pub cancelled: bool,
+    /// Last event of the task; it no longer shows in `list_background_tasks`
+    pub done: bool,
+}
+
+impl AppEvent for TaskProgress {
+    const NAME: &'static str = "task-progress";
+    const DESCRIPTION: &'static str = "A background task started, made progress or ended, see list_background_tasks";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("task_id", "string"),
+        ("kind", "string"),
+        ("label", "string"),
+        ("progress", "integer|null"),
+        ("message", "string|null"),
+        ("cancelled", "boolean"),
+        ("done", "boolean"),
+    ];
+}
+
+impl AppEvent for GenerationMetrics {
+    const NAME: &'static str = "generation-metrics";
+    const DESCRIPTION: &'static str = "Live tokens per second of a proxied generation, and its final numbers";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("request_id", "string"),
+        ("model", "string"),
+        ("tokens", "integer"),
+        ("tokens_per_second", "number"),
+        ("average_tokens_per_second", "number"),
+        ("eta_seconds", "number|null"),
+        ("max_tokens", "integer|null"),
+        ("elapsed_ms", "integer"),
+        ("done", "boolean"),
+        ("gpu_power", "object|null"),
+    ];
+}
+
+impl AppEvent for AppHealth {
+    const NAME: &'static str = "app-health";
+    const DESCRIPTION: &'static str = "Startup self-test results";
+    const FIELDS: &'static [(&'static str, &'static str)] = &[
+        ("checked_at", "string|null"),
+        ("checking", "boolean"),
+        ("degraded", "boolean"),
+        ("flags", "string[]"),
+        ("checks", "object[]"),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keys of the payload as serialized, in order
+    fn keys<E: AppEvent>(payload: &E) -> Vec<String> {
+        match serde_json::to_value(payload).unwrap() {
+            serde_json::Value::Object(map) => map.keys().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn declared<E: AppEvent>() -> Vec<String> {
+        let mut fields: Vec<String> = E::FIELDS.iter().filter(|(_, t)| !t.ends_with("|absent")).map(|(k, _)| k.to_string()).collect();
+        fields.sort();
+        fields
+    }
+
+    fn assert_fields<E: AppEvent>(payload: E) {
+        let mut actual = keys(&payload);
+        actual.sort();
+        assert_eq!(actual, declared::<E>(), "fields of {}", E::NAME);
+    }
+
+    #[test]
+    fn declared_fields_match_the_payloads() {
+        assert_fields(ExtractionProgress {
+            download_id: String::new(),
+            extraction_progress: 0,
+            extraction_total_files: 0,
+            extraction_completed_files: 0,
+            current_extracting_file: String::new(),
+        });
+        assert_fields(ModelReady { process_id: String::new(), model_path: String::new(), model_name: String::new(), host: String::new(), port: 0, load_ms: 0 });
+        assert_fields(ModelRestarting {
+            process_id: String::new(),
+            model_path: String::new(),
+            model_name: String::new(),
+            exit_code: -1,
+            attempt: 1,
+            max_retries: 3,
+            delay_ms: 0,
+        });
+        assert_fields(ModelUnloadedIdle { process_id: String::new(), model_path: String::new(), model_name: String::new(), idle_minutes: 30 });
+        assert_fields(BackendBenchmarkProgress { model_path: String::new(), backend: String::new(), index: 0, total: 1, status: "running", result: None });
+        assert_fields(TaskProgress {
+            task_id: String::new(),
+            kind: "scan".to_string(),
+            label: String::new(),
+            progress: None,
+            message: None,
+            cancelled: false,
+            done: true,
+        });
+        assert_fields(AppHealth::default());
+        assert_eq!(serde_json::to_value(FileDeleted).unwrap(), serde_json::Value::Null);
+
+        let names: Vec<String> = schemas().into_iter().map(|s| s.name).collect();
+        let mut unique = names.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(names.len(), unique.len());
+        assert!(names.iter().all(|n| !n.contains(".v1")));
+    }
+}
@@ -0,0 +1,191 @@
+use serde::Serialize;
+use std::time::Duration;
+use crate::llama_client::LlamaClient;
+use crate::models::{ProcessInfo, ProcessStatus};
+use crate::openai_types::ChatCompletionRequest;
+use crate::AppState;
+
+// Fallback chain for proxied chat completions. A request first goes to the server it
+// was routed to (by its `model`, its API key's launch profile or the default upstream);
+// when that errors or times out it moves on through `GlobalConfig.fallback_chain`, a
+// list of model paths tried in order. Chain models that are not loaded are skipped
+// rather than launched.
+
+/// Per-model budget before moving on to the next model in the chain
+const ATTEMPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub model_path: String,
+    pub model_name: String,
+    pub base_url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Attempt {
+    pub model: String,
+    pub error: String,
+}
+
+fn candidate(process: &ProcessInfo) -> Candidate {
+    Candidate {
+        model_path: process.model_path.clone(),
+        model_name: process.model_name.clone(),
+        base_url: crate::suspend::local_base_url(&process.host, process.port),
+    }
+}
+
+/// The routed server, then the running chain models after it in chain order, plus
+/// attempts for chain entries not loaded
+fn plan<'a>(
+    routed_url: &str,
+    requested_model: &str,
+    chain: &[String],
+    running: impl Iterator<Item = &'a ProcessInfo> + Clone,
+) -> (Vec<Candidate>, Vec<Attempt>) {
+    let serving = running.filter(|p| matches!(p.status, ProcessStatus::Running) && !p.model_path.starts_with("tunnel:"));
+    let routed = serving
+        .clone()
+        .filter(|p| crate::suspend::local_base_url(&p.host, p.port) == routed_url)
+        .min_by_key(|p| p.instance)
+        .map(candidate)
+        .unwrap_or_else(|| Candidate {
+            model_path: String::new(),
+            model_name: requested_model.to_string(),
+            base_url: routed_url.to_string(),
+        });
+    let mut skipped = Vec::new();
+    let routed_path = routed.model_path.clone();
+    let mut found = vec![routed];
+    for model_path in chain.iter().filter(|path| **path != routed_path) {
+        match serving.clone().filter(|p| p.model_path == *model_path).min_by_key(|p| p.instance) {
+            Some(p) => found.push(candidate(p)),
+            None => skipped.push(Attempt { model: model_path.clone(), error: "not loaded".to_string() }),
+        }
+    }
+    (found, skipped)
+}
+
+/// Servers to try for a request routed to `routed_url`, see `plan`
+pub async fn candidates(routed_url: &str, requested_model: &str, chain: &[String], state: &AppState) -> (Vec<Candidate>, Vec<Attempt>) {
+    let running = state.running_processes.lock().await;
+    plan(routed_url, requested_model, chain, running.values())
+}
+
+/// Which model answered, added to non-streaming responses as `arandu`
+pub fn annotation(answered: &Candidate, attempts: &[Attempt]) -> serde_json::Value {
+    serde_json::json!({
+        "answered_by": answered.model_name,
+        "model_path": answered.model_path,
+        "fallback_used": !attempts.is_empty(),
+        "attempts": attempts,
+    })
+}
+
+pub async fn chat_completion(
+    candidates: &[Candidate],
+    mut attempts: Vec<Attempt>,
+    request: &ChatCompletionRequest,
+) -> Result<(serde_json::Value, Candidate, Vec<Attempt>), Vec<Attempt>> {
+    for candidate in candidates {
+        let client = LlamaClient::new(candidate.base_url.clone());
+        let error = match tokio::time::timeout(ATTEMPT_TIMEOUT, client.chat_completion(request)).await {
+            Ok(Ok(mut response)) => {
+                response["arandu"] = annotation(candidate, &attempts);
+                return Ok((response, candidate.clone(), attempts));
+            }
+            Ok(Err(e)) => e,
+            Err(_) => format!("timed out after {}s", ATTEMPT_TIMEOUT.as_secs()),
+        };
+        println!("[Fallback] {} failed ({}), trying next model", candidate.model_name, error);
+        attempts.push(Attempt { model: candidate.model_name.clone(), error });
+    }
+    Err(attempts)
+}
+
+/// Open a stream on the first model that accepts the request. Once tokens flow there
+/// is no switching models mid-answer.
+pub async fn chat_completion_stream(
+    candidates: &[Candidate],
+    mut attempts: Vec<Attempt>,
+    request: &ChatCompletionRequest,
+) -> Result<(reqwest::Response, Candidate, Vec<Attempt>), Vec<Attempt>> {
+    for candidate in candidates {
+        let client = LlamaClient::new(candidate.base_url.clone());
+        let error = match tokio::time::timeout(ATTEMPT_TIMEOUT, client.chat_completion_stream(request)).await {
+            Ok(Ok(response)) => return Ok((response, candidate.clone(), attempts)),
+            Ok(Err(e)) => e,
+            Err(_) => format!("timed out after {}s", ATTEMPT_TIMEOUT.as_secs()),
+        };
+        println!("[Fallback] {} failed ({}), trying next model", candidate.model_name, error);
+        attempts.push(Attempt { model: candidate.model_name.clone(), error });
+    }
+    Err(attempts)
+}
+
+pub fn exhausted_message(attempts: &[Attempt]) -> String {
+    if attempts.is_empty() {
+        return "The fallback chain is empty".to_string();
+    }
+    let details: Vec<String> = attempts.iter().map(|a| format!("{}: {}", a.model, a.error)).collect();
+    format!("Every model in the fallback chain failed ({})", details.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn annotation_reports_fallback_use() {
+        let answered = Candidate {
+            model_path: "/models/small.gguf".into(),
+            model_name: "small".into(),
+            base_url: "http://127.0.0.1:8081".into(),
+        };
+        let attempts = vec![Attempt { model: "big".into(), error: "timed out after 120s".into() }];
+        let note = annotation(&answered, &attempts);
+        assert_eq!(note["answered_by"], "small");
+        assert_eq!(note["fallback_used"], true);
+        assert!(exhausted_message(&attempts).contains("big: timed out"));
+        assert_eq!(annotation(&answered, &[])["fallback_used"], false);
+    }
+
+    fn process(model_path: &str, port: u16) -> ProcessInfo {
+        ProcessInfo {
+            id: format!("p{}", port),
+            model_path: model_path.to_string(),
+            model_name: model_path.trim_start_matches("/models/").to_string(),
+            host: "127.0.0.1".to_string(),
+            port,
+            command: Vec::new(),
+            status: ProcessStatus::Running,
+            output: Vec::new(),
+            created_at: chrono::Utc::now(),
+            last_sent_line: None,
+            preset_id: None,
+            instance: 1,
+            restart_count: 0,
+        }
+    }
+
+    #[test]
+    fn routed_server_answers_first_and_the_chain_follows() {
+        let running = [process("/models/chat.gguf", 8081), process("/models/coder.gguf", 8082), process("/models/small.gguf", 8083)];
+        let chain = vec!["/models/chat.gguf".to_string(), "/models/coder.gguf".to_string(), "/models/big.gguf".to_string()];
+        let order = |candidates: &[Candidate]| candidates.iter().map(|c| c.model_name.clone()).collect::<Vec<_>>();
+
+        // Asked for the coder: it answers, the rest of the chain backs it up
+        let (found, skipped) = plan("http://127.0.0.1:8082", "coder.gguf", &chain, running.iter());
+        assert_eq!(order(&found), ["coder.gguf", "chat.gguf"]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].model, "/models/big.gguf");
+
+        // A model outside the chain still answers first
+        let (found, _) = plan("http://127.0.0.1:8083", "small.gguf", &chain, running.iter());
+        assert_eq!(order(&found), ["small.gguf", "chat.gguf", "coder.gguf"]);
+
+        // An upstream Arandu does not manage is tried under the requested name
+        let (found, _) = plan("http://127.0.0.1:9000", "gpt-4", &chain, running.iter());
+        assert_eq!((found[0].model_name.as_str(), found[0].base_url.as_str()), ("gpt-4", "http://127.0.0.1:9000"));
+    }
+}
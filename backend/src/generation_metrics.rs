@@ -0,0 +1,216 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use crate::energy_report::EnergySample;
+use crate::models::GpuPowerSummary;
+use crate::system_monitor::PowerSampler;
+
+// Live speed of streamed completions. The proxy feeds every SSE chunk to a
+// `GenerationMeter`, which counts tokens and emits `generation-metrics` a couple of
+// times per second plus once when the stream ends, with the GPU power sampled
+// during the generation.
+
+const EMIT_INTERVAL: Duration = Duration::from_millis(500);
+/// Rolling tok/s is measured over this much recent history
+const RATE_WINDOW: Duration = Duration::from_secs(3);
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// Called once from setup; without it meters count but emit nothing
+pub fn set_app_handle(app_handle: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationMetrics {
+    pub request_id: String,
+    pub model: String,
+    pub tokens: u64,
+    pub tokens_per_second: f64,
+    pub average_tokens_per_second: f64,
+    /// Time left to reach `max_tokens` at the current speed
+    pub eta_seconds: Option<f64>,
+    pub max_tokens: Option<i32>,
+    pub elapsed_ms: u128,
+    pub done: bool,
+    /// Only on the final event
+    pub gpu_power: Option<GpuPowerSummary>,
+}
+
+#[derive(Debug)]
+pub struct GenerationMeter {
+    request_id: String,
+    model: String,
+    max_tokens: Option<i32>,
+    started: Instant,
+    first_token: Option<Instant>,
+    tokens: u64,
+    samples: VecDeque<(Instant, u64)>,
+    last_emit: Option<Instant>,
+    power: Option<PowerSampler>,
+    backend: Option<String>,
+}
+
+/// Tokens a chunk carries: one per content delta, or the server's own count when it reports one
+fn chunk_tokens(chunk: &serde_json::Value) -> (u64, Option<u64>) {
+    let reported = chunk
+        .pointer("/usage/completion_tokens")
+        .or_else(|| chunk.pointer("/timings/predicted_n"))
+        .and_then(|v| v.as_u64());
+    let produced = chunk
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|choice| {
+            ["/delta/content", "/delta/reasoning_content", "/text"]
+                .iter()
+                .any(|path| choice.pointer(path).and_then(|v| v.as_str()).is_some_and(|s| !s.is_empty()))
+        })
+        .count() as u64;
+    (produced, reported)
+}
+
+impl GenerationMeter {
+    pub fn new(model: &str, max_tokens: Option<i32>) -> Self {
+        Self {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            model: model.to_string(),
+            max_tokens: max_tokens.filter(|m| *m > 0),
+            started: Instant::now(),
+            first_token: None,
+            tokens: 0,
+            samples: VecDeque::new(),
+            last_emit: None,
+            power: None,
+            backend: None,
+        }
+    }
+
+    /// Backend of the serving build, for the energy report
+    pub fn with_backend(mut self, backend: Option<String>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Also sample GPU power until the meter finishes
+    pub fn with_power_sampling(mut self) -> Self {
+        self.power = Some(PowerSampler::start());
+        self
+    }
+
+    /// Count the tokens in one SSE `data:` payload
+    pub fn observe(&mut self, data: &str) {
+        let Ok(chunk) = serde_json::from_str::<serde_json::Value>(data) else { return };
+        self.observe_at(&chunk, Instant::now());
+        if self.last_emit.is_none_or(|last| last.elapsed() >= EMIT_INTERVAL) {
+            self.last_emit = Some(Instant::now());
+            emit(self.snapshot(false));
+        }
+    }
+
+    fn observe_at(&mut self, chunk: &serde_json::Value, now: Instant) {
+        let (produced, reported) = chunk_tokens(chunk);
+        let total = reported.unwrap_or(0).max(self.tokens + produced);
+        if total > self.tokens && self.first_token.is_none() {
+            self.first_token = Some(now);
+        }
+        self.tokens = total;
+        self.samples.push_back((now, total));
+        while self.samples.front().is_some_and(|(at, _)| now.duration_since(*at) > RATE_WINDOW) {
+            self.samples.pop_front();
+        }
+    }
+
+    fn rolling_rate(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some((first_at, first)), Some((last_at, last))) if last_at > first_at => {
+                (last - first) as f64 / last_at.duration_since(*first_at).as_secs_f64()
+            }
+            _ => self.average_rate(),
+        }
+    }
+
+    /// Over the time since the first token, so prompt processing does not drag it down
+    fn average_rate(&self) -> f64 {
+        let Some(first) = self.first_token else { return 0.0 };
+        let elapsed = self.samples.back().map(|(at, _)| at.duration_since(first)).unwrap_or_default();
+        if elapsed.is_zero() {
+            return 0.0;
+        }
+        self.tokens.saturating_sub(1) as f64 / elapsed.as_secs_f64()
+    }
+
+    pub fn snapshot(&self, done: bool) -> GenerationMetrics {
+        let rate = self.rolling_rate();
+        let eta_seconds = match self.max_tokens {
+            Some(max) if !done && rate > 0.0 => Some((max as u64).saturating_sub(self.tokens) as f64 / rate),
+            _ => None,
+        };
+        GenerationMetrics {
+            request_id: self.request_id.clone(),
+            model: self.model.clone(),
+            tokens: self.tokens,
+            tokens_per_second: rate,
+            average_tokens_per_second: self.average_rate(),
+            eta_seconds,
+            max_tokens: self.max_tokens,
+            elapsed_ms: self.started.elapsed().as_millis(),
+            done,
+            gpu_power: None,
+        }
+    }
+
+    pub async fn finish(mut self) {
+        let mut metrics = self.snapshot(true);
+        if let Some(power) = self.power.take() {
+            metrics.gpu_power = power.finish().await;
+        }
+        if let Some(power) = &metrics.gpu_power {
+            crate::energy_report::record(EnergySample {
+                model: self.model.clone(),
+                backend: self.backend.take(),
+                source: "proxy".to_string(),
+                tokens: metrics.tokens,
+                energy_wh: power.energy_wh,
+                avg_power_watts: power.avg_power_watts,
+                recorded_at: chrono::Utc::now(),
+            });
+        }
+        emit(metrics);
+    }
+}
+
+fn emit(metrics: GenerationMetrics) {
+    if let Some(app_handle) = APP_HANDLE.get() {
+        crate::events::emit(app_handle, &metrics);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn meter_counts_deltas_and_prefers_reported_usage() {
+        let mut meter = GenerationMeter::new("qwen", Some(100));
+        let start = Instant::now();
+        let delta = json!({"choices": [{"delta": {"content": "Hi"}}]});
+        for i in 0..10 {
+            meter.observe_at(&delta, start + Duration::from_millis(100 * i));
+        }
+        meter.observe_at(&json!({"choices": [{"delta": {}}]}), start + Duration::from_millis(1000));
+
+        let metrics = meter.snapshot(false);
+        assert_eq!(metrics.tokens, 10);
+        assert!((metrics.tokens_per_second - 9.0).abs() < 0.01);
+        assert!(metrics.eta_seconds.unwrap() > 9.0);
+
+        meter.observe_at(&json!({"choices": [], "usage": {"completion_tokens": 12}}), start + Duration::from_millis(1100));
+        let done = meter.snapshot(true);
+        assert_eq!(done.tokens, 12);
+        assert!(done.eta_seconds.is_none());
+    }
+}
@@ -1,6 +1,186 @@
 use crate::models::GgufMetadata;
+use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
 
+// One reader for the GGUF header, over anything that implements `Read`: a local file or
+// the first bytes of a remote one from a range request. Reads stop cleanly where the
+// input ends, so a truncated header still yields the keys read so far.
+
+/// Longest key or string value accepted; anything longer means a corrupt header
+const MAX_STRING_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GgufValue {
+    /// Unsigned, bool, or a signed value that is not negative
+    Int(u64),
+    Text(String),
+    /// Arrays keep only their length
+    Array(u64),
+    Other,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GgufHeader {
+    pub tensor_count: u64,
+    pub values: HashMap<String, GgufValue>,
+    /// False when the input ended inside the key/value section
+    pub complete: bool,
+}
+
+impl GgufHeader {
+    pub fn int(&self, key: &str) -> Option<u64> {
+        match self.values.get(key)? {
+            GgufValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn text(&self, key: &str) -> Option<&str> {
+        match self.values.get(key)? {
+            GgufValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn array_len(&self, key: &str) -> Option<u64> {
+        match self.values.get(key)? {
+            GgufValue::Array(len) => Some(*len),
+            _ => None,
+        }
+    }
+
+    pub fn architecture(&self) -> Option<&str> {
+        self.text("general.architecture")
+    }
+
+    /// `<architecture>.<suffix>`, e.g. `block_count`
+    pub fn arch_int(&self, suffix: &str) -> Option<u64> {
+        self.int(&format!("{}.{}", self.architecture()?, suffix))
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> Option<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes).ok()?;
+    Some(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> Option<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes).ok()?;
+    Some(u64::from_le_bytes(bytes))
+}
+
+fn skip(reader: &mut impl Read, len: u64) -> Option<()> {
+    let skipped = std::io::copy(&mut reader.take(len), &mut std::io::sink()).ok()?;
+    (skipped == len).then_some(())
+}
+
+fn read_string(reader: &mut impl Read) -> Option<String> {
+    let len = read_u64(reader)?;
+    if len > MAX_STRING_BYTES {
+        return None;
+    }
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes).ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Byte width of fixed-size value types
+fn scalar_width(value_type: u32) -> Option<u64> {
+    match value_type {
+        0 | 1 | 7 => Some(1),
+        2 | 3 => Some(2),
+        4..=6 => Some(4),
+        10..=12 => Some(8),
+        _ => None,
+    }
+}
+
+fn read_value(reader: &mut impl Read, value_type: u32) -> Option<GgufValue> {
+    match value_type {
+        8 => read_string(reader).map(GgufValue::Text),
+        9 => {
+            let item_type = read_u32(reader)?;
+            let count = read_u64(reader)?;
+            match scalar_width(item_type) {
+                Some(width) => skip(reader, width.checked_mul(count)?)?,
+                // Arrays of strings (the tokenizer vocabulary) have to be walked
+                None if item_type == 8 => {
+                    for _ in 0..count {
+                        let len = read_u64(reader)?;
+                        skip(reader, len)?;
+                    }
+                }
+                None => return None,
+            }
+            Some(GgufValue::Array(count))
+        }
+        // f32 and f64
+        6 | 12 => skip(reader, scalar_width(value_type)?).map(|_| GgufValue::Other),
+        _ => {
+            let width = scalar_width(value_type)? as usize;
+            let mut bytes = [0u8; 8];
+            reader.read_exact(&mut bytes[..width]).ok()?;
+            let value = u64::from_le_bytes(bytes);
+            // i8, i16, i32, i64: negative values have the sign bit of their width set
+            let signed = matches!(value_type, 1 | 3 | 5 | 11);
+            let negative = signed && (value >> (width * 8 - 1)) & 1 == 1;
+            Some(if negative { GgufValue::Other } else { GgufValue::Int(value) })
+        }
+    }
+}
+
+/// Read the header key/values. `None` when the input is not GGUF or ends before the counts.
+pub fn read_header(reader: &mut impl Read) -> Option<GgufHeader> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic).ok()?;
+    if &magic != b"GGUF" {
+        return None;
+    }
+    let _version = read_u32(reader)?;
+    let tensor_count = read_u64(reader)?;
+    let kv_count = read_u64(reader)?;
+
+    let mut header = GgufHeader { tensor_count, values: HashMap::new(), complete: false };
+    for _ in 0..kv_count {
+        let Some(key) = read_string(reader) else { return Some(header) };
+        let Some(value_type) = read_u32(reader) else { return Some(header) };
+        let Some(value) = read_value(reader, value_type) else { return Some(header) };
+        header.values.insert(key, value);
+    }
+    header.complete = true;
+    Some(header)
+}
+
+/// Total elements over the tensor table that follows a complete header; `None` when
+/// the input ends inside it or the file has no tensors
+pub fn parameter_count(reader: &mut impl Read, header: &GgufHeader) -> Option<u64> {
+    if !header.complete || header.tensor_count == 0 {
+        return None;
+    }
+    let mut total: u64 = 0;
+    for _ in 0..header.tensor_count {
+        read_string(reader)?;
+        let n_dims = read_u32(reader)?;
+        let mut elements: u64 = 1;
+        for _ in 0..n_dims {
+            elements = elements.saturating_mul(read_u64(reader)?);
+        }
+        read_u32(reader)?; // tensor type
+        read_u64(reader)?; // data offset
+        total = total.saturating_add(elements);
+    }
+    Some(total)
+}
+
+/// Header of a local file; `None` for unreadable or non-GGUF files
+pub fn read_file_header(path: &Path) -> Option<GgufHeader> {
+    let file = std::fs::File::open(crate::long_path::extended(path)).ok()?;
+    read_header(&mut std::io::BufReader::new(file))
+}
+
 /// Parse GGUF file metadata
 /// For now, uses the existing scanner implementation for reliability
 pub fn parse_gguf_metadata(path: &str) -> Result<GgufMetadata, String> {
@@ -10,7 +190,7 @@ pub fn parse_gguf_metadata(path: &str) -> Result<GgufMetadata, String> {
         return Err("File not found".to_string());
     }
     
-    if !path.extension().map_or(false, |ext| ext == "gguf") {
+    if path.extension().is_none_or(|ext| ext != "gguf") {
         return Err("Not a GGUF file".to_string());
     }
     
@@ -78,6 +258,37 @@ mod tests {
         std::fs::remove_file(&temp_file).ok();
     }
 
+    #[test]
+    fn test_read_header_from_file_and_truncated_bytes() {
+        let mut buf = b"GGUF".to_vec();
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&0u64.to_le_bytes());
+        buf.extend_from_slice(&3u64.to_le_bytes());
+        for (key, value_type, value) in [
+            ("general.architecture", 8u32, [5u64.to_le_bytes().as_slice(), b"llama"].concat()),
+            ("llama.block_count", 4, 32u32.to_le_bytes().to_vec()),
+            ("llama.rope.offset", 5, (-4i32).to_le_bytes().to_vec()),
+        ] {
+            buf.extend_from_slice(&(key.len() as u64).to_le_bytes());
+            buf.extend_from_slice(key.as_bytes());
+            buf.extend_from_slice(&value_type.to_le_bytes());
+            buf.extend_from_slice(&value);
+        }
+
+        let temp_file = std::env::temp_dir().join(format!("test_gguf_header_{}.gguf", std::process::id()));
+        std::fs::write(&temp_file, &buf).unwrap();
+        let header = read_file_header(&temp_file).unwrap();
+        std::fs::remove_file(&temp_file).ok();
+        assert!(header.complete);
+        assert_eq!(header.arch_int("block_count"), Some(32));
+        assert_eq!(header.values.get("llama.rope.offset"), Some(&GgufValue::Other));
+
+        let truncated = read_header(&mut &buf[..buf.len() - 2]).unwrap();
+        assert!(!truncated.complete);
+        assert_eq!(truncated.architecture(), Some("llama"));
+        assert!(read_header(&mut &b"GGML"[..]).is_none());
+    }
+
     #[test]
     fn test_parse_nonexistent_file() {
         let result = parse_gguf_metadata("/nonexistent/file.gguf");
@@ -0,0 +1,75 @@
+use crate::AppState;
+
+// The GitHub token used for release lookups and GitHub downloads lives in the OS keyring,
+// like MCP tokens, never in settings.json. Settings written by earlier versions may still
+// carry it as `github_token`; `migrate` moves it into the keyring at startup and saves the
+// settings without it; if the keyring is unavailable the token is kept in memory for the
+// session only. The UI only learns whether a token is set.
+
+const KEYRING_SERVICE: &str = "arandu-github";
+const KEYRING_USER: &str = "api-token";
+
+fn keyring_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).map_err(|e| format!("Failed to open keyring: {}", e))
+}
+
+fn read() -> Result<Option<String>, String> {
+    match keyring_entry()?.get_password() {
+        Ok(token) => Ok(Some(token).filter(|t| !t.is_empty())),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read GitHub token from keyring: {}", e)),
+    }
+}
+
+fn write(token: &str) -> Result<(), String> {
+    keyring_entry()?
+        .set_password(token)
+        .map_err(|e| format!("Failed to store GitHub token in keyring: {}", e))
+}
+
+fn forget() -> Result<(), String> {
+    match keyring_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove GitHub token from keyring: {}", e)),
+    }
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tokio::task::spawn_blocking(f).await.map_err(|e| format!("Keyring task failed: {}", e))?
+}
+
+/// The stored token, or one `migrate` could not move yet; a keyring that cannot be read
+/// counts as none
+pub async fn load(state: &AppState) -> Option<String> {
+    let stored = blocking(read).await.unwrap_or_else(|e| {
+        eprintln!("[GitHub] {}", e);
+        None
+    });
+    match stored {
+        Some(token) => Some(token),
+        None => state.config.lock().await.github_token.clone(),
+    }
+}
+
+/// Store (or with an empty `token`, remove) the token
+pub async fn set(token: String) -> Result<(), String> {
+    let token = token.trim().to_string();
+    blocking(move || if token.is_empty() { forget() } else { write(&token) }).await
+}
+
+pub async fn clear() -> Result<(), String> {
+    blocking(forget).await
+}
+
+/// Move a token left in settings.json by an earlier version into the keyring
+pub async fn migrate(state: &AppState) {
+    let Some(token) = state.config.lock().await.github_token.take() else { return };
+    if let Err(e) = set(token.clone()).await {
+        eprintln!("[GitHub] Keeping the token from settings for now: {}", e);
+        state.config.lock().await.github_token = Some(token);
+        return;
+    }
+    if let Err(e) = crate::config::save_settings(state).await {
+        eprintln!("[GitHub] Failed to save settings after moving the token: {}", e);
+    }
+}
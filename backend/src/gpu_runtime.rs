@@ -0,0 +1,750 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::{LazyLock, Mutex};
+
+// Host platform and GPU detection shared by the release browser and anything else
+// that needs to know which llama.cpp builds this machine can run. Asset names follow
+// the upstream pattern `llama-<tag>-bin-<os>-<backend>[-<version>]-<arch>.zip`.
+
+/// What the host looks like from the point of view of choosing a llama.cpp build
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HostPlatform {
+    /// "windows", "macos" or "linux"
+    pub os: String,
+    /// "x64", "arm64" or "arm32"
+    pub arch: String,
+    /// "nvidia", "amd", "intel" or "apple"
+    pub gpu_vendors: Vec<String>,
+    /// Highest CUDA version the installed NVIDIA driver supports, as "major.minor"
+    pub cuda_driver_version: Option<String>,
+    /// ROCm/HIP runtime version, when one was found
+    #[serde(default)]
+    pub rocm_version: Option<String>,
+    /// `None` when the Vulkan probe could not tell
+    #[serde(default)]
+    pub vulkan_available: Option<bool>,
+    /// Board name from the device tree, e.g. "Raspberry Pi 5 Model B Rev 1.0"
+    #[serde(default)]
+    pub device_model: Option<String>,
+    #[serde(default)]
+    pub total_memory_gb: f64,
+}
+
+/// One GPU runtime as seen by `probe_gpu_runtime`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RuntimeInfo {
+    pub available: bool,
+    pub version: Option<String>,
+    /// Installed driver version, where it differs from the runtime version
+    pub driver_version: Option<String>,
+    pub devices: Vec<String>,
+    /// How it was detected: "nvml", "nvidia-smi", "rocminfo", "vulkaninfo", "library", ...
+    pub source: Option<String>,
+}
+
+/// Installed GPU runtimes, cached after the first probe
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GpuRuntimeProbe {
+    pub cuda: RuntimeInfo,
+    pub rocm: RuntimeInfo,
+    pub vulkan: RuntimeInfo,
+    pub probed_at: Option<String>,
+}
+
+/// Where an asset is meant to run, parsed from its file name
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AssetTarget {
+    pub os: Option<String>,
+    pub arch: Option<String>,
+    pub backend: String,
+    /// CUDA toolkit version the asset was built against, e.g. "12.4"
+    pub cuda_version: Option<String>,
+    /// CUDA runtime DLL bundles that complement a CUDA build rather than run on their own
+    pub is_runtime_bundle: bool,
+}
+
+/// How well an asset fits the host
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AssetCompatibility {
+    #[serde(flatten)]
+    pub target: AssetTarget,
+    /// Built for this OS and architecture
+    pub platform_match: bool,
+    /// Platform matches and no missing GPU or driver was detected
+    pub compatible: bool,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Best compatible build of its release for this host, set by `mark_recommended`
+    #[serde(default)]
+    pub recommended: bool,
+}
+
+/// Memory at or below which an ARM board gets the low-RAM profile
+const LOW_RAM_GB: f64 = 8.5;
+
+static DISPLAY_VENDORS: LazyLock<Vec<String>> = LazyLock::new(detect_display_vendors);
+
+static RUNTIME_PROBE: Mutex<Option<GpuRuntimeProbe>> = Mutex::new(None);
+
+/// Probe installed GPU runtimes. Spawns vendor tools, so call it off the async runtime.
+/// The result is cached until `force` is set.
+pub fn probe_gpu_runtime(force: bool) -> GpuRuntimeProbe {
+    if !force {
+        if let Some(cached) = RUNTIME_PROBE.lock().ok().and_then(|p| p.clone()) {
+            return cached;
+        }
+    }
+
+    let probe = GpuRuntimeProbe {
+        cuda: probe_cuda(),
+        rocm: probe_rocm(),
+        vulkan: probe_vulkan(),
+        probed_at: Some(chrono::Utc::now().to_rfc3339()),
+    };
+    println!(
+        "[GPU] Runtimes: CUDA={:?} ROCm={:?} Vulkan={}",
+        probe.cuda.version, probe.rocm.version, probe.vulkan.available
+    );
+    if let Ok(mut cached) = RUNTIME_PROBE.lock() {
+        *cached = Some(probe.clone());
+    }
+    probe
+}
+
+/// Host platform built from the cached runtime probe
+pub fn host_platform() -> HostPlatform {
+    detect_host_platform(&probe_gpu_runtime(false))
+}
+
+fn current_os() -> &'static str {
+    match std::env::consts::OS {
+        "windows" => "windows",
+        "macos" => "macos",
+        _ => "linux",
+    }
+}
+
+fn current_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "aarch64" => "arm64",
+        "arm" => "arm32",
+        _ if running_under_rosetta() => "arm64",
+        _ => "x64",
+    }
+}
+
+/// An x64 build on Apple Silicon still wants the arm64 (Metal) llama.cpp builds
+#[cfg(target_os = "macos")]
+fn running_under_rosetta() -> bool {
+    static TRANSLATED: LazyLock<bool> =
+        LazyLock::new(|| run_tool("sysctl", &["-n", "sysctl.proc_translated"]).is_some_and(|out| out.trim() == "1"));
+    *TRANSLATED
+}
+
+#[cfg(not(target_os = "macos"))]
+fn running_under_rosetta() -> bool {
+    false
+}
+
+fn detect_host_platform(probe: &GpuRuntimeProbe) -> HostPlatform {
+    let mut gpu_vendors = Vec::new();
+
+    if probe.cuda.available {
+        gpu_vendors.push("nvidia".to_string());
+    }
+    if probe.rocm.available {
+        gpu_vendors.push("amd".to_string());
+    }
+    if current_os() == "macos" && current_arch() == "arm64" {
+        gpu_vendors.push("apple".to_string());
+    }
+
+    for vendor in DISPLAY_VENDORS.iter() {
+        if !gpu_vendors.contains(vendor) {
+            gpu_vendors.push(vendor.clone());
+        }
+    }
+
+    HostPlatform {
+        os: current_os().to_string(),
+        arch: current_arch().to_string(),
+        gpu_vendors,
+        cuda_driver_version: probe.cuda.version.clone(),
+        rocm_version: probe.rocm.version.clone(),
+        vulkan_available: if probe.vulkan.source.is_some() { Some(probe.vulkan.available) } else { None },
+        device_model: device_model(),
+        total_memory_gb: total_memory_gb(),
+    }
+}
+
+/// Single-board computers name themselves in the device tree; PCs have no such file
+fn device_model() -> Option<String> {
+    let raw = std::fs::read_to_string("/proc/device-tree/model").ok()?;
+    let model = raw.trim_matches(char::from(0)).trim();
+    (!model.is_empty()).then(|| model.to_string())
+}
+
+pub(crate) fn total_memory_gb() -> f64 {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    sys.total_memory() as f64 / (1024.0 * 1024.0 * 1024.0)
+}
+
+/// ARM boards with little memory, where GPU builds and big contexts are out of reach
+pub fn is_low_ram_arm(host: &HostPlatform) -> bool {
+    host.os == "linux" && host.arch.starts_with("arm") && host.total_memory_gb > 0.0 && host.total_memory_gb <= LOW_RAM_GB
+}
+
+/// Launch arguments for CPU-only serving on a small ARM board: every core, a context
+/// that fits next to the weights and a small batch to keep compute buffers down
+pub fn low_ram_cpu_args(threads: usize, total_memory_gb: f64) -> String {
+    let context = match total_memory_gb {
+        m if m <= 2.5 => 512,
+        m if m <= 4.5 => 1024,
+        m if m <= 8.5 => 2048,
+        _ => 4096,
+    };
+    let threads = threads.max(1);
+    format!("-ngl 0 -t {t} -tb {t} -c {c} -b 256 -ub 128 -np 1 --cache-type-k q8_0", t = threads, c = context)
+}
+
+/// stdout of a tool, or `None` when it is missing or fails
+fn run_tool(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn probe_cuda() -> RuntimeInfo {
+    if let Ok(nvml) = nvml_wrapper::Nvml::init() {
+        let count = nvml.device_count().unwrap_or(0);
+        if count > 0 {
+            let devices = (0..count)
+                .filter_map(|i| nvml.device_by_index(i).ok().and_then(|d| d.name().ok()))
+                .collect();
+            return RuntimeInfo {
+                available: true,
+                version: nvml.sys_cuda_driver_version().ok().map(format_cuda_version),
+                driver_version: nvml.sys_driver_version().ok(),
+                devices,
+                source: Some("nvml".to_string()),
+            };
+        }
+    }
+
+    // NVML can be missing from PATH while nvidia-smi is still installed
+    match run_tool("nvidia-smi", &[]) {
+        Some(output) => {
+            let (version, driver_version) = parse_nvidia_smi_header(&output);
+            RuntimeInfo {
+                available: true,
+                version,
+                driver_version,
+                devices: Vec::new(),
+                source: Some("nvidia-smi".to_string()),
+            }
+        }
+        None => RuntimeInfo::default(),
+    }
+}
+
+/// `Driver Version: 551.86 ... CUDA Version: 12.4` from the nvidia-smi banner
+fn parse_nvidia_smi_header(output: &str) -> (Option<String>, Option<String>) {
+    let value_after = |label: &str| {
+        output.find(label).map(|idx| {
+            output[idx + label.len()..]
+                .trim_start()
+                .chars()
+                .take_while(|c| c.is_ascii_digit() || *c == '.')
+                .collect::<String>()
+        })
+        .filter(|v| !v.is_empty())
+    };
+    (value_after("CUDA Version:"), value_after("Driver Version:"))
+}
+
+fn probe_rocm() -> RuntimeInfo {
+    if let Some(output) = run_tool("hipconfig", &["--version"]) {
+        let version = leading_version(output.trim());
+        if version.is_some() {
+            return RuntimeInfo {
+                available: true,
+                version,
+                devices: rocminfo_devices(),
+                source: Some("hipconfig".to_string()),
+                ..Default::default()
+            };
+        }
+    }
+
+    // Linux installs record the version file; Windows HIP SDK exports HIP_PATH
+    let version_file = std::path::Path::new("/opt/rocm/.info/version");
+    if let Ok(content) = std::fs::read_to_string(version_file) {
+        return RuntimeInfo {
+            available: true,
+            version: leading_version(content.trim()),
+            devices: rocminfo_devices(),
+            source: Some("rocm".to_string()),
+            ..Default::default()
+        };
+    }
+    if let Ok(hip_path) = std::env::var("HIP_PATH") {
+        let version = std::path::Path::new(hip_path.trim_end_matches(['\\', '/']))
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(leading_version);
+        return RuntimeInfo {
+            available: true,
+            version,
+            source: Some("HIP_PATH".to_string()),
+            ..Default::default()
+        };
+    }
+
+    RuntimeInfo::default()
+}
+
+fn rocminfo_devices() -> Vec<String> {
+    run_tool("rocminfo", &[])
+        .map(|output| {
+            output
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("Marketing Name:"))
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty() && !name.to_lowercase().contains("cpu"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// "6.1.40093-..." -> "6.1.40093"
+fn leading_version(raw: &str) -> Option<String> {
+    let version: String = raw
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit())
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let version = version.trim_end_matches('.');
+    if version.is_empty() { None } else { Some(version.to_string()) }
+}
+
+fn probe_vulkan() -> RuntimeInfo {
+    if let Some(output) = run_tool("vulkaninfo", &["--summary"]) {
+        let (version, devices) = parse_vulkaninfo_summary(&output);
+        return RuntimeInfo {
+            available: !devices.is_empty(),
+            version,
+            devices,
+            source: Some("vulkaninfo".to_string()),
+            ..Default::default()
+        };
+    }
+
+    // No SDK tools: the loader library being present is the best signal we have
+    let loader_paths: &[&str] = if cfg!(windows) {
+        &["C:\\Windows\\System32\\vulkan-1.dll"]
+    } else if cfg!(target_os = "macos") {
+        &["/usr/local/lib/libvulkan.1.dylib", "/opt/homebrew/lib/libvulkan.1.dylib"]
+    } else {
+        &[
+            "/usr/lib/x86_64-linux-gnu/libvulkan.so.1",
+            "/usr/lib/aarch64-linux-gnu/libvulkan.so.1",
+            "/usr/lib64/libvulkan.so.1",
+            "/usr/lib/libvulkan.so.1",
+        ]
+    };
+    match loader_paths.iter().find(|p| std::path::Path::new(p).exists()) {
+        Some(path) => RuntimeInfo {
+            available: true,
+            source: Some(format!("library:{}", path)),
+            ..Default::default()
+        },
+        None => RuntimeInfo::default(),
+    }
+}
+
+/// Highest `apiVersion` and every `deviceName` from `vulkaninfo --summary`
+fn parse_vulkaninfo_summary(output: &str) -> (Option<String>, Vec<String>) {
+    let mut version: Option<String> = None;
+    let mut devices = Vec::new();
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = value.trim();
+        match key.trim() {
+            "apiVersion" => {
+                let parsed = leading_version(value);
+                if parsed.as_deref().and_then(parse_major_minor) > version.as_deref().and_then(parse_major_minor) {
+                    version = parsed;
+                }
+            }
+            "deviceName" if !value.to_lowercase().contains("llvmpipe") => devices.push(value.to_string()),
+            _ => {}
+        }
+    }
+    (version, devices)
+}
+
+/// NVML reports the CUDA version as `major * 1000 + minor * 10`
+fn format_cuda_version(raw: i32) -> String {
+    format!("{}.{}", raw / 1000, (raw % 1000) / 10)
+}
+
+#[cfg(target_os = "windows")]
+fn vendor_from_name(name: &str) -> Option<&'static str> {
+    let lower = name.to_lowercase();
+    if lower.contains("nvidia") || lower.contains("geforce") || lower.contains("quadro") {
+        Some("nvidia")
+    } else if lower.contains("amd") || lower.contains("radeon") || lower.contains("ati ") {
+        Some("amd")
+    } else if lower.contains("intel") {
+        Some("intel")
+    } else if lower.contains("apple") {
+        Some("apple")
+    } else {
+        None
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect_display_vendors() -> Vec<String> {
+    let mut vendors = Vec::new();
+    let Ok(entries) = std::fs::read_dir("/sys/class/drm") else { return vendors };
+    for entry in entries.flatten() {
+        let vendor_id = std::fs::read_to_string(entry.path().join("device").join("vendor")).unwrap_or_default();
+        let vendor = match vendor_id.trim() {
+            "0x10de" => "nvidia",
+            "0x1002" => "amd",
+            "0x8086" => "intel",
+            _ => continue,
+        };
+        if !vendors.iter().any(|v| v == vendor) {
+            vendors.push(vendor.to_string());
+        }
+    }
+    vendors
+}
+
+#[cfg(target_os = "windows")]
+fn detect_display_vendors() -> Vec<String> {
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "Get-CimInstance Win32_VideoController | Select-Object -ExpandProperty Name",
+        ])
+        .output();
+    let Ok(output) = output else { return Vec::new() };
+
+    let mut vendors: Vec<String> = Vec::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(vendor) = vendor_from_name(line) {
+            if !vendors.iter().any(|v| v == vendor) {
+                vendors.push(vendor.to_string());
+            }
+        }
+    }
+    vendors
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn detect_display_vendors() -> Vec<String> {
+    Vec::new()
+}
+
+/// Backend a build is for, from an asset or version folder name
+pub fn backend_from_name(name: &str) -> String {
+    let lower = name.to_lowercase();
+    let backend = if lower.contains("cuda") || lower.contains("cudart") {
+        "cuda"
+    } else if lower.contains("rocm") || lower.contains("hip") || lower.contains("radeon") {
+        "rocm"
+    } else if lower.contains("vulkan") {
+        "vulkan"
+    } else if lower.contains("opencl") {
+        "opencl"
+    } else if lower.contains("sycl") {
+        "sycl"
+    } else if lower.contains("metal") || (is_macos_name(&lower) && !lower.contains("cpu")) {
+        // macOS builds ship with Metal enabled and carry no backend suffix
+        "metal"
+    } else {
+        "cpu"
+    };
+    backend.to_string()
+}
+
+fn is_macos_name(lower: &str) -> bool {
+    lower.contains("macos") || lower.contains("darwin") || lower.contains("osx") || lower.contains("xcframework")
+}
+
+fn os_from_name(lower: &str) -> Option<&'static str> {
+    if is_macos_name(lower) {
+        Some("macos")
+    } else if lower.contains("-win-") || lower.contains("windows") || lower.contains("win64") || lower.contains("win32") {
+        Some("windows")
+    } else if ["ubuntu", "linux", "debian", "fedora", "raspi", "rpi", "raspbian"].iter().any(|n| lower.contains(n)) {
+        Some("linux")
+    } else {
+        None
+    }
+}
+
+fn arch_from_name(lower: &str) -> Option<&'static str> {
+    if lower.contains("arm64") || lower.contains("aarch64") {
+        Some("arm64")
+    } else if lower.contains("armv7") || lower.contains("armhf") || lower.contains("arm32") {
+        Some("arm32")
+    } else if lower.contains("x64") || lower.contains("x86_64") || lower.contains("amd64") {
+        Some("x64")
+    } else {
+        None
+    }
+}
+
+/// CUDA version in names like `cuda-12.4`, `cuda-cu12.2.0` or `cu11.7`
+fn cuda_version_from_name(lower: &str) -> Option<String> {
+    let idx = lower.rfind("cuda").map(|i| i + 4).or_else(|| lower.find("-cu").map(|i| i + 3))?;
+    let rest = lower[idx..].trim_start_matches(['-', '_']).trim_start_matches("cu");
+    let version: String = rest.chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+    let mut parts = version.split('.').filter(|p| !p.is_empty());
+    let major = parts.next()?;
+    let minor = parts.next().unwrap_or("0");
+    Some(format!("{}.{}", major, minor))
+}
+
+pub fn classify_asset(name: &str) -> AssetTarget {
+    let lower = name.to_lowercase();
+    let backend = backend_from_name(&lower);
+    let cuda_version = if backend == "cuda" { cuda_version_from_name(&lower) } else { None };
+    AssetTarget {
+        os: os_from_name(&lower).map(str::to_string),
+        // Apple Silicon builds are the only macOS builds without an arch suffix
+        arch: arch_from_name(&lower)
+            .or_else(|| if lower.contains("xcframework") { Some("arm64") } else { None })
+            .map(str::to_string),
+        backend,
+        cuda_version,
+        is_runtime_bundle: lower.starts_with("cudart"),
+    }
+}
+
+fn parse_major_minor(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().and_then(|m| m.parse().ok()).unwrap_or(0);
+    Some((major, minor))
+}
+
+pub fn assess_asset(name: &str, host: &HostPlatform) -> AssetCompatibility {
+    let target = classify_asset(name);
+    let mut warnings = Vec::new();
+
+    let os_match = target.os.as_deref().map(|os| os == host.os).unwrap_or(false);
+    // Assets without an arch suffix have historically been x64
+    let arch_match = target.arch.as_deref().unwrap_or("x64") == host.arch;
+    let platform_match = os_match && arch_match;
+    let mut compatible = platform_match;
+
+    let has_vendor = |vendor: &str| host.gpu_vendors.iter().any(|v| v == vendor);
+    match target.backend.as_str() {
+        "cuda" => {
+            if !has_vendor("nvidia") {
+                compatible = false;
+                warnings.push("No NVIDIA GPU detected".to_string());
+            } else if let (Some(required), Some(driver)) = (
+                target.cuda_version.as_deref().and_then(parse_major_minor),
+                host.cuda_driver_version.as_deref().and_then(parse_major_minor),
+            ) {
+                if required > driver {
+                    compatible = false;
+                    warnings.push(format!(
+                        "Built for CUDA {}.{} but the installed driver supports up to CUDA {}.{}",
+                        required.0, required.1, driver.0, driver.1
+                    ));
+                }
+            }
+        }
+        "rocm" if !has_vendor("amd") => {
+            compatible = false;
+            warnings.push("No AMD GPU detected".to_string());
+        }
+        "rocm" if host.rocm_version.is_none() && host.os == "linux" => {
+            warnings.push("ROCm runtime not found; install ROCm before using this build".to_string());
+        }
+        "sycl" if !has_vendor("intel") => {
+            compatible = false;
+            warnings.push("No Intel GPU detected".to_string());
+        }
+        "metal" if host.os != "macos" => {
+            compatible = false;
+        }
+        "vulkan" if host.vulkan_available == Some(false) => {
+            compatible = false;
+            warnings.push("No Vulkan driver or device found".to_string());
+        }
+        "vulkan" if host.gpu_vendors.is_empty() => {
+            warnings.push("No GPU detected; Vulkan will fall back to a software device if any".to_string());
+        }
+        _ => {}
+    }
+
+    if !platform_match {
+        warnings.insert(
+            0,
+            format!(
+                "Built for {} {}",
+                target.os.as_deref().unwrap_or("another platform"),
+                target.arch.as_deref().unwrap_or("x64")
+            ),
+        );
+    }
+
+    if name.to_lowercase().contains("xcframework") {
+        compatible = false;
+        warnings.push("Library framework for app developers; it contains no llama-server".to_string());
+    }
+
+    AssetCompatibility { target, platform_match, compatible, warnings, recommended: false }
+}
+
+/// Order in which backends are worth trying on `host`; lower is better
+fn backend_rank(backend: &str, host: &HostPlatform) -> u8 {
+    let has_vendor = |vendor: &str| host.gpu_vendors.iter().any(|v| v == vendor);
+    match backend {
+        "metal" if host.os == "macos" => 0,
+        "cuda" if has_vendor("nvidia") => 0,
+        "rocm" if has_vendor("amd") => 1,
+        "vulkan" if !host.gpu_vendors.is_empty() => 2,
+        "sycl" if has_vendor("intel") => 3,
+        "cpu" => 4,
+        _ => 5,
+    }
+}
+
+/// Flag the best compatible asset (never a runtime bundle) among one release's assets
+pub fn mark_recommended(assets: &mut [&mut AssetCompatibility], host: &HostPlatform) {
+    let best = assets
+        .iter()
+        .enumerate()
+        .filter(|(_, a)| a.compatible && !a.target.is_runtime_bundle)
+        .min_by_key(|(_, a)| backend_rank(&a.target.backend, host))
+        .map(|(i, _)| i);
+    for (i, asset) in assets.iter_mut().enumerate() {
+        asset.recommended = Some(i) == best;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(os: &str, arch: &str, vendors: &[&str], cuda: Option<&str>) -> HostPlatform {
+        HostPlatform {
+            os: os.to_string(),
+            arch: arch.to_string(),
+            gpu_vendors: vendors.iter().map(|v| v.to_string()).collect(),
+            cuda_driver_version: cuda.map(str::to_string),
+            rocm_version: None,
+            vulkan_available: None,
+            device_model: None,
+            total_memory_gb: 0.0,
+        }
+    }
+
+    #[test]
+    fn classify_asset_reads_os_arch_backend_and_cuda_version() {
+        let cuda = classify_asset("llama-b5000-bin-win-cuda-12.4-x64.zip");
+        assert_eq!(cuda.os.as_deref(), Some("windows"));
+        assert_eq!(cuda.arch.as_deref(), Some("x64"));
+        assert_eq!(cuda.backend, "cuda");
+        assert_eq!(cuda.cuda_version.as_deref(), Some("12.4"));
+
+        assert!(classify_asset("cudart-llama-bin-win-cuda-12.4-x64.zip").is_runtime_bundle);
+        assert_eq!(classify_asset("llama-b5000-bin-win-cuda-cu11.7-x64.zip").cuda_version.as_deref(), Some("11.7"));
+        assert_eq!(classify_asset("llama-b5000-bin-win-hip-radeon-x64.zip").backend, "rocm");
+        assert_eq!(classify_asset("llama-b5000-bin-win-sycl-x64.zip").backend, "sycl");
+
+        let mac = classify_asset("llama-b5000-bin-macos-arm64.zip");
+        assert_eq!((mac.os.as_deref(), mac.backend.as_str()), (Some("macos"), "metal"));
+        assert_eq!(classify_asset("llama-b5000-bin-ubuntu-vulkan-x64.zip").os.as_deref(), Some("linux"));
+        assert_eq!(backend_from_name("b5000"), "cpu");
+    }
+
+    #[test]
+    fn assess_asset_flags_driver_and_vendor_mismatches() {
+        let old_driver = host("windows", "x64", &["nvidia"], Some("11.8"));
+        let cuda12 = assess_asset("llama-b5000-bin-win-cuda-12.4-x64.zip", &old_driver);
+        assert!(cuda12.platform_match);
+        assert!(!cuda12.compatible);
+        assert!(cuda12.warnings[0].contains("CUDA 12.4"));
+        assert!(assess_asset("llama-b5000-bin-win-cuda-11.7-x64.zip", &old_driver).compatible);
+
+        let amd_linux = host("linux", "x64", &["amd"], None);
+        assert!(!assess_asset("llama-b5000-bin-win-vulkan-x64.zip", &amd_linux).platform_match);
+        assert!(assess_asset("llama-b5000-bin-ubuntu-vulkan-x64.zip", &amd_linux).compatible);
+        assert!(!assess_asset("llama-b5000-bin-ubuntu-cuda-12.4-x64.zip", &amd_linux).compatible);
+
+        let mac = host("macos", "arm64", &["apple"], None);
+        assert!(assess_asset("llama-b5000-bin-macos-arm64.zip", &mac).compatible);
+        assert!(!assess_asset("llama-b5000-bin-macos-x64.zip", &mac).platform_match);
+    }
+
+    #[test]
+    fn arm_boards_get_arm_builds_and_a_small_cpu_profile() {
+        let pi = HostPlatform { total_memory_gb: 4.0, ..host("linux", "arm64", &[], None) };
+        assert!(assess_asset("llama-b5000-bin-ubuntu-arm64.zip", &pi).compatible);
+        assert!(!assess_asset("llama-b5000-bin-ubuntu-x64.zip", &pi).platform_match);
+        assert!(!assess_asset("llama-b5000-bin-macos-arm64.zip", &pi).platform_match);
+        assert_eq!(classify_asset("llama-b5000-bin-raspi-armv7.zip").arch.as_deref(), Some("arm32"));
+        assert!(is_low_ram_arm(&pi));
+        assert!(!is_low_ram_arm(&HostPlatform { total_memory_gb: 32.0, ..pi.clone() }));
+
+        assert_eq!(low_ram_cpu_args(4, 4.0), "-ngl 0 -t 4 -tb 4 -c 1024 -b 256 -ub 128 -np 1 --cache-type-k q8_0");
+        assert!(low_ram_cpu_args(0, 8.0).contains("-t 1 ") && low_ram_cpu_args(0, 8.0).contains("-c 2048"));
+    }
+
+    #[test]
+    fn recommends_metal_on_apple_silicon() {
+        let mac = host("macos", "arm64", &["apple"], None);
+        let mut assets: Vec<AssetCompatibility> = ["llama-b5000-xcframework.zip", "llama-b5000-bin-macos-x64.zip", "llama-b5000-bin-macos-arm64.zip"]
+            .iter()
+            .map(|name| assess_asset(name, &mac))
+            .collect();
+        assert!(!assets[0].compatible);
+        mark_recommended(&mut assets.iter_mut().collect::<Vec<_>>(), &mac);
+        assert_eq!(assets.iter().map(|a| a.recommended).collect::<Vec<_>>(), vec![false, false, true]);
+
+        let nvidia = host("windows", "x64", &["nvidia"], Some("12.4"));
+        let mut assets: Vec<AssetCompatibility> = ["cudart-llama-bin-win-cuda-12.4-x64.zip", "llama-b5000-bin-win-cpu-x64.zip", "llama-b5000-bin-win-cuda-12.4-x64.zip"]
+            .iter()
+            .map(|name| assess_asset(name, &nvidia))
+            .collect();
+        mark_recommended(&mut assets.iter_mut().collect::<Vec<_>>(), &nvidia);
+        assert!(assets[2].recommended && !assets[0].recommended);
+    }
+
+    #[test]
+    fn runtime_tool_output_parsers() {
+        let smi = "| NVIDIA-SMI 551.86   Driver Version: 551.86   CUDA Version: 12.4     |";
+        assert_eq!(
+            parse_nvidia_smi_header(smi),
+            (Some("12.4".to_string()), Some("551.86".to_string()))
+        );
+
+        let vulkan = "Devices:\n  apiVersion = 1.3.277\n  deviceName = NVIDIA GeForce RTX 4090\n  apiVersion = 1.3.255\n  deviceName = llvmpipe (LLVM 17.0.6, 256 bits)\n";
+        let (version, devices) = parse_vulkaninfo_summary(vulkan);
+        assert_eq!(version.as_deref(), Some("1.3.277"));
+        assert_eq!(devices, vec!["NVIDIA GeForce RTX 4090".to_string()]);
+
+        assert_eq!(leading_version("6.1.40093-1f2b3c").as_deref(), Some("6.1.40093"));
+    }
+}
@@ -0,0 +1,285 @@
+use bytes::Bytes;
+use reqwest::header::{HeaderMap, ETAG, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+// Single entry point for Hugging Face API traffic. Every caller shares one client,
+// so connections are reused, 429/5xx responses are retried with Retry-After
+// respected, and JSON responses are revalidated with ETags instead of refetched.
+
+const USER_AGENT: &str = "Arandu-Tauri/1.0";
+const MAX_RETRIES: u32 = 3;
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+const MAX_CACHE_ENTRIES: usize = 256;
+
+static CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+});
+
+static CACHE: LazyLock<Mutex<HashMap<String, CachedResponse>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static METRICS: LazyLock<Mutex<HfRateMetrics>> =
+    LazyLock::new(|| Mutex::new(HfRateMetrics::default()));
+
+struct CachedResponse {
+    etag: String,
+    body: Bytes,
+    stored_at: Instant,
+}
+
+/// Counters and the last rate budget reported by the Hub
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HfRateMetrics {
+    pub requests: u64,
+    pub cache_hits: u64,
+    pub retries: u64,
+    pub rate_limited: u64,
+    pub server_errors: u64,
+    /// Requests left in the current window, from the `RateLimit` headers
+    pub remaining: Option<u64>,
+    pub limit: Option<u64>,
+    /// Seconds until the window resets
+    pub reset_secs: Option<u64>,
+    pub last_updated: Option<String>,
+}
+
+/// A fully read response. Bodies are buffered so callers can inspect the status
+/// before deciding how to decode.
+#[derive(Debug, Clone)]
+pub struct HfResponse {
+    pub status: StatusCode,
+    pub body: Bytes,
+}
+
+impl HfResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.status.is_success()
+    }
+
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+fn lock_or_recover<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Shared client for requests that need custom handling (range requests, streaming)
+pub fn client() -> &'static Client {
+    &CLIENT
+}
+
+pub fn metrics() -> HfRateMetrics {
+    lock_or_recover(&METRICS).clone()
+}
+
+/// GET a Hub API URL with retries and ETag revalidation
+pub async fn get(url: &str) -> Result<HfResponse, String> {
+    let cached_etag = lock_or_recover(&CACHE).get(url).map(|c| c.etag.clone());
+
+    let response = send_with_retry(|| {
+        let request = CLIENT.get(url).header("Accept", "application/json");
+        match &cached_etag {
+            Some(etag) => request.header(IF_NONE_MATCH, etag.as_str()),
+            None => request,
+        }
+    })
+    .await?;
+
+    let status = response.status();
+    if status == StatusCode::NOT_MODIFIED {
+        let mut cache = lock_or_recover(&CACHE);
+        if let Some(entry) = cache.get_mut(url) {
+            entry.stored_at = Instant::now();
+            lock_or_recover(&METRICS).cache_hits += 1;
+            return Ok(HfResponse { status: StatusCode::OK, body: entry.body.clone() });
+        }
+    }
+
+    let etag = response
+        .headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read Hugging Face response: {}", e))?;
+
+    if status.is_success() {
+        if let Some(etag) = etag {
+            store_cached(url, etag, body.clone());
+        }
+    }
+
+    Ok(HfResponse { status, body })
+}
+
+/// Send a request, retrying on 429, 5xx and transport errors. `build` is called once
+/// per attempt because a `RequestBuilder` cannot be reused.
+pub async fn send_with_retry<F>(build: F) -> Result<reqwest::Response, String>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        lock_or_recover(&METRICS).requests += 1;
+
+        match build().send().await {
+            Ok(response) => {
+                record_rate_headers(response.headers());
+                let status = response.status();
+                let retryable = status == StatusCode::TOO_MANY_REQUESTS || is_retryable_server_error(status);
+                if !retryable || attempt >= MAX_RETRIES {
+                    return Ok(response);
+                }
+
+                {
+                    let mut metrics = lock_or_recover(&METRICS);
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        metrics.rate_limited += 1;
+                    } else {
+                        metrics.server_errors += 1;
+                    }
+                    metrics.retries += 1;
+                }
+
+                let delay = retry_after(response.headers()).unwrap_or_else(|| backoff_delay(attempt));
+                println!("[HF] HTTP {} from {}, retrying in {:?}", status, response.url(), delay);
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => {
+                if attempt >= MAX_RETRIES || e.is_builder() {
+                    return Err(format!("Hugging Face request failed: {}", e));
+                }
+                lock_or_recover(&METRICS).retries += 1;
+                tokio::time::sleep(backoff_delay(attempt)).await;
+            }
+        }
+        attempt += 1;
+    }
+}
+
+fn is_retryable_server_error(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 500 | 502 | 503 | 504)
+}
+
+fn backoff_delay(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.min(5)).min(MAX_RETRY_DELAY)
+}
+
+/// `Retry-After` as delta seconds or an HTTP date, capped so a bad header cannot stall the UI
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    let delay = if let Ok(secs) = value.parse::<u64>() {
+        Duration::from_secs(secs)
+    } else {
+        let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        let secs = (date.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds().max(0);
+        Duration::from_secs(secs as u64)
+    };
+    Some(delay.min(MAX_RETRY_DELAY))
+}
+
+/// Parse the IETF `RateLimit` header the Hub sends (`"api";r=499;t=120`) together
+/// with `RateLimit-Policy` (`"fixed window";"api";q=500;w=300`).
+fn parse_rate_limit(headers: &HeaderMap) -> (Option<u64>, Option<u64>, Option<u64>) {
+    let param = |header: &str, key: &str| {
+        headers
+            .get(header)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| {
+                v.split(';').find_map(|part| {
+                    part.trim()
+                        .strip_prefix(key)
+                        .and_then(|rest| rest.strip_prefix('='))
+                        .and_then(|n| n.trim().parse::<u64>().ok())
+                })
+            })
+    };
+    let legacy = |header: &str| {
+        headers
+            .get(header)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+    };
+
+    let remaining = param("ratelimit", "r").or_else(|| legacy("x-ratelimit-remaining"));
+    let reset = param("ratelimit", "t").or_else(|| legacy("x-ratelimit-reset"));
+    let limit = param("ratelimit-policy", "q").or_else(|| legacy("x-ratelimit-limit"));
+    (remaining, limit, reset)
+}
+
+fn record_rate_headers(headers: &HeaderMap) {
+    let (remaining, limit, reset) = parse_rate_limit(headers);
+    if remaining.is_none() && limit.is_none() {
+        return;
+    }
+
+    let mut metrics = lock_or_recover(&METRICS);
+    metrics.remaining = remaining.or(metrics.remaining);
+    metrics.limit = limit.or(metrics.limit);
+    metrics.reset_secs = reset;
+    metrics.last_updated = Some(chrono::Utc::now().to_rfc3339());
+}
+
+fn store_cached(url: &str, etag: String, body: Bytes) {
+    let mut cache = lock_or_recover(&CACHE);
+    if cache.len() >= MAX_CACHE_ENTRIES && !cache.contains_key(url) {
+        if let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, entry)| entry.stored_at)
+            .map(|(key, _)| key.clone())
+        {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(url.to_string(), CachedResponse { etag, body, stored_at: Instant::now() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn parse_rate_limit_reads_ietf_and_legacy_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("ratelimit", HeaderValue::from_static("\"api\";r=499;t=120"));
+        headers.insert("ratelimit-policy", HeaderValue::from_static("\"fixed window\";\"api\";q=500;w=300"));
+        assert_eq!(parse_rate_limit(&headers), (Some(499), Some(500), Some(120)));
+
+        let mut legacy = HeaderMap::new();
+        legacy.insert("x-ratelimit-remaining", HeaderValue::from_static("12"));
+        assert_eq!(parse_rate_limit(&legacy), (Some(12), None, None));
+    }
+
+    #[test]
+    fn retry_after_is_capped_and_backoff_grows() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("5"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(5)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert_eq!(retry_after(&headers), Some(MAX_RETRY_DELAY));
+
+        assert!(backoff_delay(0) < backoff_delay(2));
+    }
+}
@@ -0,0 +1,146 @@
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+// Shared HTTP clients for llama-server and MCP endpoints. One client is kept per
+// origin so keep-alive connections survive between requests instead of being
+// dropped with a throwaway `reqwest::Client` on every call.
+
+const USER_AGENT: &str = "Arandu-Tauri/1.0";
+const MAX_CLIENTS: usize = 64;
+
+static CLIENTS: LazyLock<Mutex<HashMap<String, Client>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Timeout profile for a request. Streams get no total timeout; the client's read
+/// timeout still catches a server that stops sending.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RequestClass {
+    Health,
+    Control,
+    Completion,
+    Stream,
+}
+
+impl RequestClass {
+    pub fn timeout(self) -> Option<Duration> {
+        match self {
+            RequestClass::Health => Some(Duration::from_secs(5)),
+            RequestClass::Control => Some(Duration::from_secs(30)),
+            RequestClass::Completion => Some(Duration::from_secs(300)),
+            RequestClass::Stream => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// A couple of quick retries, enough to ride out a server that is still loading
+    pub const DEFAULT: RetryPolicy = RetryPolicy { max_retries: 2, base_delay: Duration::from_millis(250) };
+
+    fn delay(&self, attempt: u32) -> Duration {
+        self.base_delay * (1u32 << attempt.min(4))
+    }
+}
+
+fn build_client() -> Client {
+    Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(Duration::from_secs(10))
+        .read_timeout(Duration::from_secs(300))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(8)
+        .tcp_keepalive(Duration::from_secs(60))
+        .build()
+        .unwrap_or_else(|_| Client::new())
+}
+
+/// `scheme://host:port` of a URL, the key clients are pooled under
+fn origin(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => format!(
+            "{}://{}:{}",
+            parsed.scheme(),
+            parsed.host_str().unwrap_or(""),
+            parsed.port_or_known_default().unwrap_or(0)
+        ),
+        Err(_) => url.to_string(),
+    }
+}
+
+/// Pooled client for the origin of `url`. Cloning a `Client` is cheap; the clone
+/// shares the connection pool.
+pub fn client_for(url: &str) -> Client {
+    let key = origin(url);
+    let mut clients = match CLIENTS.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(client) = clients.get(&key) {
+        return client.clone();
+    }
+    if clients.len() >= MAX_CLIENTS {
+        clients.clear();
+    }
+    let client = build_client();
+    clients.insert(key, client.clone());
+    client
+}
+
+/// Apply a request class's timeout to a builder from any pooled client
+pub fn with_timeout(request: RequestBuilder, class: RequestClass) -> RequestBuilder {
+    match class.timeout() {
+        Some(timeout) => request.timeout(timeout),
+        None => request,
+    }
+}
+
+pub fn get(url: &str, class: RequestClass) -> RequestBuilder {
+    with_timeout(client_for(url).get(url), class)
+}
+
+/// 503 is what llama-server answers while the model is still loading
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+/// Send a request, retrying connection failures and 503s. Both mean the server did not
+/// process the request, so retrying is safe even for completions. `build` is called
+/// per attempt because a `RequestBuilder` cannot be reused.
+pub async fn send_with_retry<F>(build: F, policy: RetryPolicy) -> Result<Response, reqwest::Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let mut attempt = 0;
+    loop {
+        match build().send().await {
+            Ok(response) if is_retryable_status(response.status()) && attempt < policy.max_retries => {}
+            Ok(response) => return Ok(response),
+            Err(e) if e.is_connect() && attempt < policy.max_retries => {}
+            Err(e) => return Err(e),
+        }
+        tokio::time::sleep(policy.delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clients_are_pooled_per_origin() {
+        assert_eq!(origin("http://127.0.0.1:8080/v1/chat/completions"), "http://127.0.0.1:8080");
+        assert_eq!(origin("https://example.com/mcp"), "https://example.com:443");
+        assert_eq!(origin("not a url"), "not a url");
+        assert!(RetryPolicy::DEFAULT.delay(0) < RetryPolicy::DEFAULT.delay(1));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+    }
+}
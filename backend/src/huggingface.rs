@@ -2,109 +2,14 @@ use crate::models::*;
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use serde_json::{json, Value};
 use std::collections::HashMap;
-use std::path::Path;
-use tokio::fs;
 
 const MODEL_DATE_CUTOFF: &str = "2025-01-01T00:00:00Z";
 
-/// Cleanup leftover .download files from interrupted downloads during startup
-pub async fn cleanup_leftover_downloads(models_directory: &str) -> Result<usize, Box<dyn std::error::Error>> {
-    if models_directory.is_empty() {
-        return Ok(0);
-    }
-    
-    let models_path = Path::new(models_directory);
-    if !models_path.exists() {
-        return Ok(0);
-    }
-    
-    let mut cleaned_count = 0;
-    
-    // Recursively walk through the models directory
-    let mut entries = fs::read_dir(models_path).await?;
-    while let Some(entry) = entries.next_entry().await? {
-        let path = entry.path();
-        
-        if path.is_dir() {
-            // Recursively clean subdirectories (author folders, model folders)
-            if let Ok(count) = cleanup_directory_downloads(&path).await {
-                cleaned_count += count;
-            }
-        } else if path.is_file() {
-            // Check if it's a .gguf.download file
-            if is_gguf_download_file(&path) {
-                match fs::remove_file(&path).await {
-                    Ok(_) => {
-                        println!("Cleaned up leftover download file: {:?}", path);
-                        cleaned_count += 1;
-                    },
-                    Err(e) => {
-                        eprintln!("Failed to remove download file {:?}: {}", path, e);
-                    }
-                }
-            }
-        }
-    }
-    
-    if cleaned_count > 0 {
-        println!("Startup cleanup: removed {} leftover .gguf.download files", cleaned_count);
-    }
-    
-    Ok(cleaned_count)
-}
-
-/// Recursively clean .gguf.download files from a directory
-fn cleanup_directory_downloads(dir_path: &Path) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<usize, Box<dyn std::error::Error>>> + Send + '_>> {
-    Box::pin(async move {
-        let mut cleaned_count = 0;
-        
-        let mut entries = fs::read_dir(dir_path).await?;
-        while let Some(entry) = entries.next_entry().await? {
-            let path = entry.path();
-            
-            if path.is_dir() {
-                // Recursively clean subdirectories
-                if let Ok(count) = cleanup_directory_downloads(&path).await {
-                    cleaned_count += count;
-                }
-            } else if path.is_file() {
-                // Check if it's a .gguf.download file
-                if is_gguf_download_file(&path) {
-                    match fs::remove_file(&path).await {
-                        Ok(_) => {
-                            println!("Cleaned up leftover download file: {:?}", path);
-                            cleaned_count += 1;
-                        },
-                        Err(e) => {
-                            eprintln!("Failed to remove download file {:?}: {}", path, e);
-                        }
-                    }
-                }
-            }
-        }
-        
-        Ok(cleaned_count)
-    })
-}
-
-/// Strictly check if a file is a .gguf.download file
-/// Only files ending with exactly ".gguf.download" will be considered for removal
-fn is_gguf_download_file(path: &Path) -> bool {
-    if let Some(file_name) = path.file_name() {
-        if let Some(file_str) = file_name.to_str() {
-            // Must end with exactly ".gguf.download" - case insensitive for safety
-            return file_str.to_lowercase().ends_with(".gguf.download");
-        }
-    }
-    false
-}
-
 pub async fn search_models(
     query: String,
     limit: usize,
     sort_by: String,
 ) -> Result<SearchResult, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
     let cutoff_date = parse_cutoff_date();
     
     // Build search URL with parameters - filter for GGUF models (includes both conversational and text-to-image)
@@ -123,17 +28,13 @@ pub async fn search_models(
     println!("Searching with URL: {}", url);
     println!("Query: {}, Sort: {}, Limit: {}", query, sort_by, limit);
     
-    let response = client
-        .get(&url)
-        .header("User-Agent", "Arandu-Tauri/1.0")
-        .send()
-        .await?;
+    let response = crate::hf_client::get(&url).await?;
     
-    if !response.status().is_success() {
+    if !response.is_success() {
         return Err(format!("API request failed with status: {}", response.status()).into());
     }
     
-    let models_data: Value = response.json().await?;
+    let models_data: Value = response.json()?;
     let models_array = models_data.as_array()
         .ok_or("Invalid response format: expected array")?;
     
@@ -173,6 +74,8 @@ fn parse_model_basic(data: &Value) -> Option<ModelBasic> {
     let id = data.get("id")?.as_str()?.to_string();
     let name = id.clone(); // Use ID as name for now
     let author = id.split('/').next().unwrap_or("unknown").to_string();
+    let vram_estimate_gb = crate::vram_estimate::estimate_remote(&id, None, None, None, &Default::default())
+        .map(|estimate| estimate.total_gb);
     
     // Try multiple field names for last modified date
     let last_modified = data.get("lastModified")
@@ -195,6 +98,7 @@ fn parse_model_basic(data: &Value) -> Option<ModelBasic> {
         downloads: data.get("downloads").and_then(|v| v.as_u64()).unwrap_or(0),
         likes: data.get("likes").and_then(|v| v.as_u64()).unwrap_or(0),
         last_modified,
+        vram_estimate_gb,
     })
 }
 
@@ -230,32 +134,22 @@ fn parse_hf_datetime(value: &str) -> Option<DateTime<Utc>> {
 pub async fn get_huggingface_model_details(
     model_id: String,
 ) -> Result<ModelDetails, Box<dyn std::error::Error>> {
-    let client = reqwest::Client::new();
-    
     // Get model info
     let model_url = format!("https://huggingface.co/api/models/{}", model_id);
-    let model_response = client
-        .get(&model_url)
-        .header("User-Agent", "Arandu-Tauri/1.0")
-        .send()
-        .await?;
+    let model_response = crate::hf_client::get(&model_url).await?;
     
-    if !model_response.status().is_success() {
+    if !model_response.is_success() {
         return Err(format!("Failed to fetch model info: {}", model_response.status()).into());
     }
     
-    let model_data: Value = model_response.json().await?;
+    let model_data: Value = model_response.json()?;
     
     // Get file tree to find GGUF files
     let files_url = format!("https://huggingface.co/api/models/{}/tree/main?recursive=true", model_id);
-    let files_response = client
-        .get(&files_url)
-        .header("User-Agent", "Arandu-Tauri/1.0")
-        .send()
-        .await?;
+    let files_response = crate::hf_client::get(&files_url).await?;
     
-    let files_data: Value = if files_response.status().is_success() {
-        files_response.json().await?
+    let files_data: Value = if files_response.is_success() {
+        files_response.json()?
     } else {
         json!([])
     };
@@ -281,7 +175,7 @@ pub async fn get_huggingface_model_details(
 // Process GGUF files specifically
                 if file_path.to_lowercase().ends_with(".gguf") {
                     let size = file.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
-                    let filename = file_path.split('/').last().unwrap_or(file_path).to_string();
+                    let filename = file_path.split('/').next_back().unwrap_or(file_path).to_string();
                     let quantization_type = extract_quantization_type(file_path).unwrap_or_else(|| "Unknown".to_string());
                     
                     gguf_files.insert(filename.clone(), GgufFileInfo {
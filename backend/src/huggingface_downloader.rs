@@ -13,6 +13,22 @@ pub struct HfFileInfo {
     pub commit_date: Option<String>,
 }
 
+/// Architecture details gathered from a GGUF header range request or the repo's `config.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GgufPreview {
+    pub architecture: Option<String>,
+    pub parameter_count: Option<u64>,
+    pub size_label: Option<String>,
+    pub context_length: Option<u64>,
+    pub layer_count: Option<u64>,
+    /// "gguf_header" or "config_json"
+    pub source: String,
+}
+
+// Enough for the metadata block of most GGUF files; the tokenizer vocab that follows
+// can run to several MB and is not needed for the preview.
+const GGUF_PREVIEW_RANGE_BYTES: u64 = 2 * 1024 * 1024;
+
 /// Model information from HuggingFace API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelCardInfo {
@@ -71,15 +87,11 @@ pub fn parse_model_id(input: &str) -> Result<String, String> {
 pub async fn fetch_model_info(model_id: &str) -> Result<ModelCardInfo, String> {
     let url = format!("https://huggingface.co/api/models/{}", model_id);
     
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "Arandu-Tauri/1.0")
-        .send()
+    let response = crate::hf_client::get(&url)
         .await
         .map_err(|e| format!("Failed to fetch model info: {}", e))?;
     
-    if !response.status().is_success() {
+    if !response.is_success() {
         let status = response.status();
         if status == reqwest::StatusCode::NOT_FOUND {
             return Err("Model not found".to_string());
@@ -93,7 +105,6 @@ pub async fn fetch_model_info(model_id: &str) -> Result<ModelCardInfo, String> {
     
     let data: serde_json::Value = response
         .json()
-        .await
         .map_err(|e| format!("Failed to parse response: {}", e))?;
     
     // Extract description from cardData if available
@@ -128,15 +139,11 @@ pub async fn fetch_model_info(model_id: &str) -> Result<ModelCardInfo, String> {
 pub async fn fetch_model_files(model_id: &str) -> Result<Vec<HfFileInfo>, String> {
     let url = format!("https://huggingface.co/api/models/{}/tree/main", model_id);
     
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&url)
-        .header("User-Agent", "Arandu-Tauri/1.0")
-        .send()
+    let response = crate::hf_client::get(&url)
         .await
         .map_err(|e| format!("Failed to fetch file list: {}", e))?;
     
-    if !response.status().is_success() {
+    if !response.is_success() {
         let status = response.status();
         if status == reqwest::StatusCode::NOT_FOUND {
             return Err("Model repository not found".to_string());
@@ -146,7 +153,6 @@ pub async fn fetch_model_files(model_id: &str) -> Result<Vec<HfFileInfo>, String
     
     let files: Vec<serde_json::Value> = response
         .json()
-        .await
         .map_err(|e| format!("Failed to parse file list: {}", e))?;
     
     let mut gguf_files: Vec<HfFileInfo> = files
@@ -169,7 +175,7 @@ pub async fn fetch_model_files(model_id: &str) -> Result<Vec<HfFileInfo>, String
         })
         .map(|file| {
             let path = file.get("path").and_then(|p| p.as_str()).unwrap_or("").to_string();
-            let filename = path.split('/').last().unwrap_or(&path).to_string();
+            let filename = path.split('/').next_back().unwrap_or(&path).to_string();
             let size = file.get("size").and_then(|s| s.as_u64()).unwrap_or(0);
             
             // Extract quantization from filename
@@ -195,6 +201,138 @@ pub async fn fetch_model_files(model_id: &str) -> Result<Vec<HfFileInfo>, String
     Ok(gguf_files)
 }
 
+fn is_projector_file(filename: &str) -> bool {
+    filename.to_lowercase().contains("mmproj")
+}
+
+/// Read architecture details for a repo without downloading a model file, when the user
+/// asks for them. Tries the header of the smallest GGUF first (a 2 MB range request),
+/// then falls back to `config.json`.
+pub async fn fetch_model_preview(
+    model_id: &str,
+    files: &[HfFileInfo],
+) -> Option<GgufPreview> {
+    let candidate = files.iter().find(|f| {
+        !is_projector_file(&f.filename) && !f.path.to_lowercase().ends_with(".download")
+            && (!f.filename.contains("-of-") || f.filename.contains("-00001-of-"))
+    });
+
+    if let Some(file) = candidate {
+        match fetch_gguf_header_preview(model_id, &file.path).await {
+            Ok(Some(preview)) => return Some(preview),
+            Ok(None) => {}
+            Err(e) => eprintln!("GGUF preview failed for {}/{}: {}", model_id, file.path, e),
+        }
+    }
+
+    match fetch_config_json_preview(model_id).await {
+        Ok(preview) => preview,
+        Err(e) => {
+            eprintln!("config.json preview failed for {}: {}", model_id, e);
+            None
+        }
+    }
+}
+
+async fn fetch_gguf_header_preview(
+    model_id: &str,
+    path: &str,
+) -> Result<Option<GgufPreview>, String> {
+    let url = format!("https://huggingface.co/{}/resolve/main/{}", model_id, path);
+    let response = crate::hf_client::send_with_retry(|| {
+        crate::hf_client::client()
+            .get(&url)
+            .header("Range", format!("bytes=0-{}", GGUF_PREVIEW_RANGE_BYTES - 1))
+            .timeout(std::time::Duration::from_secs(15))
+    })
+    .await
+    .map_err(|e| format!("Failed to fetch GGUF header: {}", e))?;
+
+    // A server that ignores Range would stream the whole file; bail out instead
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(format!("Range request not honoured (HTTP {})", response.status()));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read GGUF header: {}", e))?;
+    Ok(parse_gguf_header_preview(&bytes))
+}
+
+async fn fetch_config_json_preview(model_id: &str) -> Result<Option<GgufPreview>, String> {
+    let url = format!("https://huggingface.co/{}/resolve/main/config.json", model_id);
+    let response = crate::hf_client::get(&url)
+        .await
+        .map_err(|e| format!("Failed to fetch config.json: {}", e))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.is_success() {
+        return Err(format!("Failed to fetch config.json (HTTP {})", response.status()));
+    }
+
+    let config: serde_json::Value = response
+        .json()
+        .map_err(|e| format!("Failed to parse config.json: {}", e))?;
+    Ok(preview_from_config_json(&config))
+}
+
+/// Map a transformers `config.json` onto the preview fields. Multimodal configs keep
+/// the language model settings under `text_config`.
+fn preview_from_config_json(config: &serde_json::Value) -> Option<GgufPreview> {
+    let text_config = config.get("text_config").unwrap_or(config);
+    let lookup = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| text_config.get(*key).or_else(|| config.get(*key)))
+            .and_then(|v| v.as_u64())
+    };
+
+    let preview = GgufPreview {
+        architecture: config
+            .get("model_type")
+            .and_then(|v| v.as_str())
+            .or_else(|| {
+                config
+                    .get("architectures")
+                    .and_then(|v| v.as_array())
+                    .and_then(|arr| arr.first())
+                    .and_then(|v| v.as_str())
+            })
+            .map(|s| s.to_string()),
+        parameter_count: None,
+        size_label: None,
+        context_length: lookup(&["max_position_embeddings", "n_positions", "seq_length"]),
+        layer_count: lookup(&["num_hidden_layers", "n_layer", "num_layers"]),
+        source: "config_json".to_string(),
+    };
+
+    if preview.architecture.is_none() && preview.context_length.is_none() && preview.layer_count.is_none() {
+        None
+    } else {
+        Some(preview)
+    }
+}
+
+/// Extract the preview fields from the first bytes of a GGUF file. Parameter count is
+/// summed from the tensor table when it fits in the range and the file is not split.
+fn parse_gguf_header_preview(bytes: &[u8]) -> Option<GgufPreview> {
+    let mut reader = bytes;
+    let header = crate::gguf_parser::read_header(&mut reader)?;
+    let split_count = header.int("split.count").unwrap_or(1);
+    let parameter_count = if split_count <= 1 { crate::gguf_parser::parameter_count(&mut reader, &header) } else { None };
+
+    Some(GgufPreview {
+        context_length: header.arch_int("context_length"),
+        layer_count: header.arch_int("block_count"),
+        architecture: header.architecture().map(str::to_string),
+        parameter_count,
+        size_label: header.text("general.size_label").map(str::to_string),
+        source: "gguf_header".to_string(),
+    })
+}
+
 /// Extract quantization from filename
 /// e.g., "model-Q4_K_M.gguf" -> "Q4_K_M"
 fn extract_quantization(filename: &str) -> Option<String> {
@@ -361,6 +499,80 @@ mod tests {
         assert!(path.to_string_lossy().contains("model"));
     }
 
+    fn push_string(buf: &mut Vec<u8>, value: &str) {
+        buf.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        buf.extend_from_slice(value.as_bytes());
+    }
+
+    fn sample_gguf_header() -> Vec<u8> {
+        let mut buf = b"GGUF".to_vec();
+        buf.extend_from_slice(&3u32.to_le_bytes());
+        buf.extend_from_slice(&2u64.to_le_bytes()); // tensors
+        buf.extend_from_slice(&4u64.to_le_bytes()); // kv pairs
+
+        push_string(&mut buf, "general.architecture");
+        buf.extend_from_slice(&8u32.to_le_bytes());
+        push_string(&mut buf, "qwen2");
+
+        push_string(&mut buf, "qwen2.context_length");
+        buf.extend_from_slice(&4u32.to_le_bytes());
+        buf.extend_from_slice(&32768u32.to_le_bytes());
+
+        push_string(&mut buf, "tokenizer.ggml.scores");
+        buf.extend_from_slice(&9u32.to_le_bytes());
+        buf.extend_from_slice(&6u32.to_le_bytes());
+        buf.extend_from_slice(&2u64.to_le_bytes());
+        buf.extend_from_slice(&0.5f32.to_le_bytes());
+        buf.extend_from_slice(&0.25f32.to_le_bytes());
+
+        push_string(&mut buf, "qwen2.block_count");
+        buf.extend_from_slice(&4u32.to_le_bytes());
+        buf.extend_from_slice(&28u32.to_le_bytes());
+
+        for (name, dims) in [("token_embd.weight", vec![896u64, 1000]), ("output_norm.weight", vec![896u64])] {
+            push_string(&mut buf, name);
+            buf.extend_from_slice(&(dims.len() as u32).to_le_bytes());
+            for dim in dims {
+                buf.extend_from_slice(&dim.to_le_bytes());
+            }
+            buf.extend_from_slice(&0u32.to_le_bytes());
+            buf.extend_from_slice(&0u64.to_le_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn test_parse_gguf_header_preview() {
+        let preview = parse_gguf_header_preview(&sample_gguf_header()).unwrap();
+        assert_eq!(preview.architecture.as_deref(), Some("qwen2"));
+        assert_eq!(preview.context_length, Some(32768));
+        assert_eq!(preview.layer_count, Some(28));
+        assert_eq!(preview.parameter_count, Some(896 * 1000 + 896));
+    }
+
+    #[test]
+    fn test_parse_gguf_header_preview_truncated() {
+        let header = sample_gguf_header();
+        // Cut inside the tensor table: metadata survives, parameter count does not
+        let preview = parse_gguf_header_preview(&header[..header.len() - 10]).unwrap();
+        assert_eq!(preview.layer_count, Some(28));
+        assert_eq!(preview.parameter_count, None);
+
+        assert!(parse_gguf_header_preview(b"NOPE").is_none());
+    }
+
+    #[test]
+    fn test_preview_from_config_json() {
+        let config = serde_json::json!({
+            "model_type": "gemma3",
+            "text_config": { "num_hidden_layers": 34, "max_position_embeddings": 131072 }
+        });
+        let preview = preview_from_config_json(&config).unwrap();
+        assert_eq!(preview.architecture.as_deref(), Some("gemma3"));
+        assert_eq!(preview.layer_count, Some(34));
+        assert_eq!(preview.context_length, Some(131072));
+    }
+
     #[test]
     fn test_extract_filename_from_url() {
         let url = "https://huggingface.co/author/model/blob/main/model-Q4_K_M.gguf";
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+// Translations for messages the backend hands to the UI (download, launch and common
+// errors). Each message has a code such as `download.started` with `{name}`
+// placeholders. English, Portuguese (Brazil) and Spanish catalogs are built in; a
+// `~/.Arandu/locales/<locale>.json` file adds a language or overrides built-in strings.
+// Lookups fall back from `pt-BR` to `pt` to English, then to the code itself.
+
+const BUILT_IN: [(&str, &str); 3] = [
+    ("en", include_str!("../locales/en.json")),
+    ("pt-BR", include_str!("../locales/pt-BR.json")),
+    ("es", include_str!("../locales/es.json")),
+];
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+type Catalog = HashMap<String, String>;
+
+struct Catalogs {
+    locale: String,
+    by_locale: HashMap<String, Catalog>,
+}
+
+static CATALOGS: RwLock<Option<Catalogs>> = RwLock::new(None);
+
+fn parse_catalog(raw: &str, source: &str) -> Catalog {
+    serde_json::from_str(raw).unwrap_or_else(|e| {
+        eprintln!("[i18n] Ignoring catalog {}: {}", source, e);
+        Catalog::new()
+    })
+}
+
+fn load_catalogs() -> HashMap<String, Catalog> {
+    let mut by_locale: HashMap<String, Catalog> =
+        BUILT_IN.iter().map(|(locale, raw)| (locale.to_string(), parse_catalog(raw, locale))).collect();
+    let Ok(dir) = crate::arandu_base_dir().map(|base| base.join("locales")) else { return by_locale };
+    let Ok(entries) = std::fs::read_dir(&dir) else { return by_locale };
+    for path in entries.flatten().map(|e| e.path()).filter(|p| p.extension().is_some_and(|ext| ext == "json")) {
+        let Some(locale) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else { continue };
+        let Ok(raw) = std::fs::read_to_string(&path) else { continue };
+        by_locale.entry(locale).or_default().extend(parse_catalog(&raw, &path.display().to_string()));
+    }
+    by_locale
+}
+
+fn with_catalogs<T>(f: impl FnOnce(&Catalogs) -> T) -> T {
+    if let Some(catalogs) = CATALOGS.read().unwrap_or_else(|p| p.into_inner()).as_ref() {
+        return f(catalogs);
+    }
+    let mut slot = CATALOGS.write().unwrap_or_else(|p| p.into_inner());
+    let catalogs = slot.get_or_insert_with(|| Catalogs { locale: DEFAULT_LOCALE.to_string(), by_locale: load_catalogs() });
+    f(catalogs)
+}
+
+/// `pt-BR`, then `pt`, then English
+fn lookup<'a>(by_locale: &'a HashMap<String, Catalog>, locale: &str, code: &str) -> Option<&'a String> {
+    let language = locale.split(['-', '_']).next().unwrap_or(locale);
+    [locale, language, DEFAULT_LOCALE]
+        .into_iter()
+        .find_map(|candidate| by_locale.get(candidate).and_then(|catalog| catalog.get(code)))
+}
+
+fn fill(template: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(template.to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), value))
+}
+
+/// Message `code` in the current locale with `{name}` placeholders filled from `args`
+pub fn t(code: &str, args: &[(&str, &str)]) -> String {
+    with_catalogs(|catalogs| match lookup(&catalogs.by_locale, &catalogs.locale, code) {
+        Some(template) => fill(template, args),
+        None => code.to_string(),
+    })
+}
+
+/// Locales with a catalog, built-in or from the user's locales folder
+pub fn available_locales() -> Vec<String> {
+    let mut locales: Vec<String> = with_catalogs(|catalogs| catalogs.by_locale.keys().cloned().collect());
+    locales.sort();
+    locales
+}
+
+pub fn is_known(locale: &str) -> bool {
+    let language = locale.split(['-', '_']).next().unwrap_or(locale);
+    with_catalogs(|catalogs| catalogs.by_locale.contains_key(locale) || catalogs.by_locale.contains_key(language))
+}
+
+/// Switch the locale, re-reading catalogs so edited files take effect
+pub fn set_locale(locale: &str) {
+    let locale = if locale.trim().is_empty() { DEFAULT_LOCALE } else { locale.trim() };
+    *CATALOGS.write().unwrap_or_else(|p| p.into_inner()) = Some(Catalogs { locale: locale.to_string(), by_locale: load_catalogs() });
+}
+
+/// Every message of `locale`, English filling the gaps, for the frontend
+pub fn catalog(locale: &str) -> Catalog {
+    with_catalogs(|catalogs| {
+        let codes = catalogs.by_locale.get(DEFAULT_LOCALE).map(|en| en.keys().cloned().collect::<Vec<_>>()).unwrap_or_default();
+        codes
+            .into_iter()
+            .filter_map(|code| lookup(&catalogs.by_locale, locale, &code).map(|text| (code, text.clone())))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalogs_share_codes_and_fall_back_by_language() {
+        let en = parse_catalog(BUILT_IN[0].1, "en");
+        for (locale, raw) in &BUILT_IN[1..] {
+            let catalog = parse_catalog(raw, locale);
+            let mut missing: Vec<&String> = en.keys().filter(|code| !catalog.contains_key(*code)).collect();
+            missing.sort();
+            assert!(missing.is_empty(), "{} is missing {:?}", locale, missing);
+        }
+
+        let by_locale: HashMap<String, Catalog> = BUILT_IN.iter().map(|(l, raw)| (l.to_string(), parse_catalog(raw, l))).collect();
+        let text = |locale: &str| lookup(&by_locale, locale, "download.start_failed").map(|t| fill(t, &[("error", "timeout")]));
+        assert_eq!(text("pt-BR").as_deref(), Some("Falha ao iniciar o download: timeout"));
+        assert_eq!(text("es-MX").as_deref(), Some("Error al iniciar la descarga: timeout"));
+        assert_eq!(text("de").as_deref(), Some("Failed to start download: timeout"));
+        assert!(lookup(&by_locale, "en", "no.such.code").is_none());
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use crate::http_pool::{self, RequestClass};
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+// Stops servers nobody uses, so a large model does not hold its VRAM overnight.
+// A model opts in with `ModelConfig.idle_timeout_minutes`. Activity comes from two
+// places: requests through the proxy (`touch`), and llama-server's `/slots`, polled
+// every `CHECK_INTERVAL`, which also catches clients talking to the server directly —
+// a slot processing or a new task id since the last poll counts as a request.
+// Paused models are left alone.
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Activity {
+    last_active: Instant,
+    last_task: Option<i64>,
+}
+
+/// Per process id
+static ACTIVITY: LazyLock<Mutex<HashMap<String, Activity>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+/// Last proxied request per server port
+static PROXIED: LazyLock<Mutex<HashMap<u16, Instant>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Note a request the proxy sends to `server_url`
+pub fn touch(server_url: &str) {
+    let Some(port) = url::Url::parse(server_url).ok().and_then(|u| u.port_or_known_default()) else { return };
+    PROXIED.lock().unwrap_or_else(|p| p.into_inner()).insert(port, Instant::now());
+}
+
+/// Whether `/slots` shows work since `last_task`, and the newest task id it lists
+fn slots_activity(slots: &serde_json::Value, last_task: Option<i64>) -> (bool, Option<i64>) {
+    let Some(slots) = slots.as_array() else { return (false, last_task) };
+    let processing = slots.iter().any(|s| s.get("is_processing").and_then(|v| v.as_bool()).unwrap_or(false));
+    let newest = slots.iter().filter_map(|s| s.get("id_task").and_then(|v| v.as_i64())).max();
+    let new_task = matches!((newest, last_task), (Some(newest), Some(last)) if newest != last);
+    (processing || new_task, newest.or(last_task))
+}
+
+async fn fetch_slots(host: &str, port: u16) -> Option<serde_json::Value> {
+    let url = format!("{}/slots", crate::suspend::local_base_url(host, port));
+    let response = http_pool::get(&url, RequestClass::Control).send().await.ok()?;
+    response.json().await.ok()
+}
+
+async fn check_once(state: &AppState, app_handle: &tauri::AppHandle) {
+    let running: Vec<(String, String, String, String, u16)> = state
+        .running_processes
+        .lock()
+        .await
+        .values()
+        .filter(|p| !p.model_path.starts_with("tunnel:") && matches!(p.status, ProcessStatus::Running))
+        .map(|p| (p.id.clone(), p.model_path.clone(), p.model_name.clone(), p.host.clone(), p.port))
+        .collect();
+    let timeouts: HashMap<String, u32> = {
+        let model_configs = state.model_configs.lock().await;
+        running
+            .iter()
+            .filter_map(|(_, path, ..)| Some((path.clone(), model_configs.get(path)?.idle_timeout_minutes)))
+            .filter(|(_, minutes)| *minutes > 0)
+            .collect()
+    };
+    ACTIVITY
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .retain(|id, _| running.iter().any(|(running_id, path, ..)| running_id == id && timeouts.contains_key(path)));
+
+    for (process_id, model_path, model_name, host, port) in running {
+        let Some(&minutes) = timeouts.get(&model_path) else { continue };
+        let slots = fetch_slots(&host, port).await;
+        let proxied = PROXIED.lock().unwrap_or_else(|p| p.into_inner()).get(&port).copied();
+
+        let idle_for = {
+            let mut activity = ACTIVITY.lock().unwrap_or_else(|p| p.into_inner());
+            // The clock starts when a model is first seen running with a timeout
+            let entry = activity.entry(process_id.clone()).or_insert(Activity { last_active: Instant::now(), last_task: None });
+            if let Some(slots) = &slots {
+                let (active, newest) = slots_activity(slots, entry.last_task);
+                entry.last_task = newest;
+                if active {
+                    entry.last_active = Instant::now();
+                }
+            }
+            if let Some(proxied) = proxied.filter(|at| *at > entry.last_active) {
+                entry.last_active = proxied;
+            }
+            entry.last_active.elapsed()
+        };
+        if idle_for < Duration::from_secs(minutes as u64 * 60) {
+            continue;
+        }
+
+        println!("[Idle] Stopping {} after {} idle minute(s)", model_name, idle_for.as_secs() / 60);
+        if let Err(e) = crate::process::terminate_process(process_id.clone(), state).await {
+            eprintln!("[Idle] Failed to stop {}: {}", model_name, e);
+            continue;
+        }
+        ACTIVITY.lock().unwrap_or_else(|p| p.into_inner()).remove(&process_id);
+        crate::events::emit(app_handle, &crate::events::ModelUnloadedIdle {
+            process_id,
+            model_path,
+            model_name,
+            idle_minutes: minutes,
+        });
+    }
+}
+
+pub fn spawn(state: AppState, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            check_once(&state, &app_handle).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_show_activity_by_processing_or_new_tasks() {
+        let slots = |processing: bool, task: i64| serde_json::json!([
+            { "id": 0, "id_task": task, "is_processing": processing },
+            { "id": 1, "id_task": -1, "is_processing": false },
+        ]);
+        assert_eq!(slots_activity(&slots(false, 7), None), (false, Some(7)));
+        assert_eq!(slots_activity(&slots(false, 7), Some(7)), (false, Some(7)));
+        assert_eq!(slots_activity(&slots(false, 9), Some(7)), (true, Some(9)));
+        assert_eq!(slots_activity(&slots(true, 9), Some(9)), (true, Some(9)));
+        assert_eq!(slots_activity(&serde_json::json!({"error": "disabled"}), Some(3)), (false, Some(3)));
+    }
+}
@@ -0,0 +1,363 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::Emitter;
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+// Launches that would not fit in free VRAM wait here until a running model stops
+// (manually or through an idle unload). A single worker drains the queue in order
+// and emits `launch-queue-updated` whenever an entry moves.
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Headroom left for the driver and other applications
+const VRAM_MARGIN_GB: f32 = 0.5;
+/// KV cache and compute buffers on top of the weights when the GGUF header is unreadable
+const CONTEXT_OVERHEAD_GB: f32 = 0.75;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueuedLaunchStatus {
+    Queued,
+    Starting,
+    Launched,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedLaunch {
+    pub id: String,
+    pub model_path: String,
+    pub preset_id: Option<String>,
+    pub status: QueuedLaunchStatus,
+    /// Why the launch is waiting, shown to the user
+    pub reason: String,
+    pub required_vram_gb: f32,
+    pub free_vram_gb: Option<f32>,
+    pub queued_at: DateTime<Utc>,
+    pub process_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct LaunchQueue {
+    pub entries: Vec<QueuedLaunch>,
+    worker_running: bool,
+}
+
+impl LaunchQueue {
+    /// Entries still waiting, in launch order
+    pub fn pending(&self) -> Vec<&QueuedLaunch> {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.status, QueuedLaunchStatus::Queued | QueuedLaunchStatus::Starting))
+            .collect()
+    }
+
+    /// Drop finished entries beyond the most recent few so the list stays short
+    fn trim_finished(&mut self) {
+        const KEEP_FINISHED: usize = 20;
+        let finished = self
+            .entries
+            .iter()
+            .filter(|e| !matches!(e.status, QueuedLaunchStatus::Queued | QueuedLaunchStatus::Starting))
+            .count();
+        let mut to_drop = finished.saturating_sub(KEEP_FINISHED);
+        self.entries.retain(|e| {
+            let finished = !matches!(e.status, QueuedLaunchStatus::Queued | QueuedLaunchStatus::Starting);
+            if finished && to_drop > 0 {
+                to_drop -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+fn has_zero_gpu_layers(custom_args: &str) -> bool {
+    let tokens: Vec<&str> = custom_args.split_whitespace().collect();
+    tokens.windows(2).any(|pair| {
+        matches!(pair[0], "-ngl" | "--n-gpu-layers" | "--gpu-layers") && pair[1] == "0"
+    }) || tokens.iter().any(|t| *t == "--n-gpu-layers=0" || *t == "-ngl=0")
+}
+
+/// VRAM a launch needs for its context size, cache types and offloaded layers.
+/// CPU-only launches (`-ngl 0`) need none.
+pub fn estimate_required_vram_gb(model_path: &str, custom_args: &str) -> f32 {
+    if has_zero_gpu_layers(custom_args) {
+        return 0.0;
+    }
+    let options = crate::vram_estimate::EstimateOptions::from_args(custom_args);
+    if let Some(estimate) = crate::vram_estimate::estimate_file(std::path::Path::new(model_path), &options) {
+        return estimate.total_gb as f32;
+    }
+    let size = std::fs::metadata(model_path).map(|m| m.len()).unwrap_or(0);
+    size as f32 / (1024.0 * 1024.0 * 1024.0) + CONTEXT_OVERHEAD_GB
+}
+
+/// `None` when the launch fits now, otherwise the reason it has to wait
+fn wait_reason(required_gb: f32, free_gb: Option<f32>, running_models: usize) -> Option<String> {
+    let free_gb = free_gb?;
+    // With nothing running, waiting cannot free anything; let llama.cpp try
+    if running_models == 0 || required_gb + VRAM_MARGIN_GB <= free_gb {
+        return None;
+    }
+    Some(format!(
+        "Needs ~{:.1} GB VRAM, {:.1} GB free; waiting for a running model to stop",
+        required_gb, free_gb
+    ))
+}
+
+async fn running_model_count(state: &AppState) -> usize {
+    let processes = state.running_processes.lock().await;
+    processes
+        .values()
+        .filter(|p| !p.model_path.starts_with("tunnel:"))
+        .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running | ProcessStatus::Suspended))
+        .count()
+}
+
+async fn free_vram_gb() -> Option<f32> {
+    tokio::task::spawn_blocking(crate::system_monitor::gpu_memory_gb)
+        .await
+        .ok()
+        .flatten()
+        .map(|(total, used)| (total - used).max(0.0))
+}
+
+async fn custom_args_for(state: &AppState, model_path: &str, preset_id: Option<&str>) -> String {
+    let model_configs = state.model_configs.lock().await;
+    let Some(config) = model_configs.get(model_path) else { return String::new() };
+    let preset_id = preset_id.or(config.default_preset_id.as_deref());
+    preset_id
+        .and_then(|id| config.presets.iter().find(|p| p.id == id))
+        .map(|p| p.custom_args.clone())
+        .unwrap_or_else(|| config.custom_args.clone())
+}
+
+pub async fn snapshot(state: &AppState) -> serde_json::Value {
+    let queue = state.launch_queue.lock().await;
+    let pending = queue.pending();
+    serde_json::json!({
+        "entries": queue.entries,
+        "pending": pending.len(),
+        "positions": pending.iter().map(|e| e.id.clone()).collect::<Vec<_>>(),
+    })
+}
+
+/// The right to run launches in queue order, held by one task at a time. Dropping it without
+/// `release` (a panic while launching) still frees it so later launches are not stuck.
+struct WorkerSlot {
+    state: Option<AppState>,
+}
+
+impl WorkerSlot {
+    /// Take the slot when nobody holds it
+    fn take(queue: &mut LaunchQueue, state: &AppState) -> Option<Self> {
+        if queue.worker_running {
+            return None;
+        }
+        queue.worker_running = true;
+        Some(Self { state: Some(state.clone()) })
+    }
+
+    fn release(mut self, queue: &mut LaunchQueue) {
+        queue.worker_running = false;
+        self.state = None;
+    }
+}
+
+impl Drop for WorkerSlot {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            tokio::spawn(async move {
+                state.launch_queue.lock().await.worker_running = false;
+            });
+        }
+    }
+}
+
+fn spawn_worker(state: &AppState, app_handle: tauri::AppHandle, slot: WorkerSlot) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        run_worker(state, app_handle, slot).await;
+    });
+}
+
+async fn emit_update(state: &AppState, app_handle: &tauri::AppHandle) {
+    let payload = snapshot(state).await;
+    let _ = app_handle.emit("launch-queue-updated", payload);
+}
+
+/// Launch now when the model fits, otherwise queue it behind earlier requests
+pub async fn queue_model_launch(
+    model_path: String,
+    preset_id: Option<String>,
+    state: &AppState,
+    app_handle: tauri::AppHandle,
+) -> Result<QueuedLaunch, String> {
+    if !std::path::Path::new(&model_path).exists() {
+        return Err("Model file not found".into());
+    }
+
+    let custom_args = custom_args_for(state, &model_path, preset_id.as_deref()).await;
+    let required_vram_gb = estimate_required_vram_gb(&model_path, &custom_args);
+    // Nobody waiting and no worker: hold the slot through the VRAM check and the launch so
+    // a launch requested meanwhile queues behind this one instead of racing it
+    let slot = {
+        let mut queue = state.launch_queue.lock().await;
+        if queue.pending().is_empty() { WorkerSlot::take(&mut queue, state) } else { None }
+    };
+    let free_vram_gb = free_vram_gb().await;
+    let reason = wait_reason(required_vram_gb, free_vram_gb, running_model_count(state).await);
+
+    let mut entry = QueuedLaunch {
+        id: format!("launch-{}", uuid::Uuid::new_v4()),
+        model_path: model_path.clone(),
+        preset_id: preset_id.clone(),
+        status: QueuedLaunchStatus::Queued,
+        reason: reason.clone().unwrap_or_else(|| "Waiting for earlier launches".to_string()),
+        required_vram_gb,
+        free_vram_gb,
+        queued_at: Utc::now(),
+        process_id: None,
+        error: None,
+    };
+
+    // Fits and nobody is ahead of us: launch right away
+    let slot = match (reason.is_none(), slot) {
+        (true, Some(slot)) => {
+            let result = crate::launch_with_preset(model_path, preset_id, state).await;
+            // Launches queued while this one started
+            spawn_worker(state, app_handle, slot);
+            entry.status = QueuedLaunchStatus::Launched;
+            entry.reason = String::new();
+            entry.process_id = Some(result?.process_id);
+            return Ok(entry);
+        }
+        (_, slot) => slot,
+    };
+
+    println!("[Queue] Queued {}: {}", entry.model_path, entry.reason);
+    let slot = {
+        let mut queue = state.launch_queue.lock().await;
+        queue.entries.push(entry.clone());
+        slot.or_else(|| WorkerSlot::take(&mut queue, state))
+    };
+    emit_update(state, &app_handle).await;
+
+    if let Some(slot) = slot {
+        spawn_worker(state, app_handle, slot);
+    }
+    Ok(entry)
+}
+
+pub async fn cancel_queued_launch(id: &str, state: &AppState, app_handle: &tauri::AppHandle) -> Result<(), String> {
+    {
+        let mut queue = state.launch_queue.lock().await;
+        let entry = queue
+            .entries
+            .iter_mut()
+            .find(|e| e.id == id)
+            .ok_or_else(|| "Queued launch not found".to_string())?;
+        if entry.status != QueuedLaunchStatus::Queued {
+            return Err("Launch already started".into());
+        }
+        entry.status = QueuedLaunchStatus::Cancelled;
+        entry.reason = "Cancelled".to_string();
+    }
+    emit_update(state, app_handle).await;
+    Ok(())
+}
+
+async fn run_worker(state: AppState, app_handle: tauri::AppHandle, slot: WorkerSlot) {
+    loop {
+        let head = {
+            let mut queue = state.launch_queue.lock().await;
+            let head = queue.pending().first().map(|e| (*e).clone());
+            match head {
+                Some(head) => head,
+                None => {
+                    slot.release(&mut queue);
+                    queue.trim_finished();
+                    return;
+                }
+            }
+        };
+
+        let free = free_vram_gb().await;
+        let reason = wait_reason(head.required_vram_gb, free, running_model_count(&state).await);
+        if let Some(reason) = reason {
+            let changed = {
+                let mut queue = state.launch_queue.lock().await;
+                match queue.entries.iter_mut().find(|e| e.id == head.id) {
+                    Some(entry) if entry.reason != reason => {
+                        entry.reason = reason;
+                        entry.free_vram_gb = free;
+                        true
+                    }
+                    _ => false,
+                }
+            };
+            if changed {
+                emit_update(&state, &app_handle).await;
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+            continue;
+        }
+
+        set_status(&state, &head.id, QueuedLaunchStatus::Starting, |_| {}).await;
+        emit_update(&state, &app_handle).await;
+
+        println!("[Queue] Starting queued launch of {}", head.model_path);
+        match crate::launch_with_preset(head.model_path.clone(), head.preset_id.clone(), &state).await {
+            Ok(result) => {
+                set_status(&state, &head.id, QueuedLaunchStatus::Launched, |entry| {
+                    entry.reason = String::new();
+                    entry.process_id = Some(result.process_id.clone());
+                })
+                .await;
+            }
+            Err(e) => {
+                eprintln!("[Queue] Queued launch of {} failed: {}", head.model_path, e);
+                set_status(&state, &head.id, QueuedLaunchStatus::Failed, |entry| entry.error = Some(e.clone())).await;
+            }
+        }
+        emit_update(&state, &app_handle).await;
+    }
+}
+
+async fn set_status<F>(state: &AppState, id: &str, status: QueuedLaunchStatus, update: F)
+where
+    F: FnOnce(&mut QueuedLaunch),
+{
+    let mut queue = state.launch_queue.lock().await;
+    if let Some(entry) = queue.entries.iter_mut().find(|e| e.id == id) {
+        entry.status = status;
+        update(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_reason_only_blocks_when_something_can_be_freed() {
+        assert!(wait_reason(6.0, Some(10.0), 1).is_none());
+        assert!(wait_reason(12.0, Some(10.0), 1).is_some());
+        // Nothing running to wait for, or no GPU memory reported
+        assert!(wait_reason(12.0, Some(10.0), 0).is_none());
+        assert!(wait_reason(12.0, None, 2).is_none());
+    }
+
+    #[test]
+    fn cpu_only_launches_need_no_vram() {
+        assert!(has_zero_gpu_layers("-c 4096 -ngl 0"));
+        assert!(has_zero_gpu_layers("--n-gpu-layers=0"));
+        assert!(!has_zero_gpu_layers("-ngl 99"));
+        assert_eq!(estimate_required_vram_gb("/does/not/exist.gguf", "-ngl 0"), 0.0);
+    }
+}
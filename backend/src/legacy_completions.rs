@@ -0,0 +1,168 @@
+use serde_json::{json, Map, Value};
+use crate::openai_types::{ChatCompletionRequest, CompletionRequest};
+
+// Legacy `/v1/completions` support. llama-server answers it natively, so the proxy
+// passes requests through; servers that only speak chat get the prompt as a single
+// user message and the chat answer converted back to `text_completion` objects.
+
+/// Fields of the legacy API that have no chat equivalent
+const COMPLETION_ONLY_FIELDS: &[&str] = &["suffix", "best_of"];
+
+fn prompt_text(prompt: &Value) -> Result<String, String> {
+    match prompt {
+        Value::String(text) => Ok(text.clone()),
+        Value::Array(items) if items.len() == 1 && items[0].is_string() => Ok(items[0].as_str().unwrap_or_default().to_string()),
+        Value::Array(_) => Err("Batched or tokenized prompts need a server with /v1/completions".to_string()),
+        _ => Err("prompt must be a string".to_string()),
+    }
+}
+
+pub fn to_chat_request(request: &CompletionRequest) -> Result<ChatCompletionRequest, String> {
+    let mut body: Map<String, Value> = request
+        .extra
+        .iter()
+        .filter(|(key, _)| !COMPLETION_ONLY_FIELDS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    body.insert("model".into(), json!(request.model));
+    body.insert("messages".into(), json!([{ "role": "user", "content": prompt_text(&request.prompt)? }]));
+    if let Some(max_tokens) = request.max_tokens {
+        body.insert("max_tokens".into(), json!(max_tokens));
+    }
+    if let Some(stream) = request.stream {
+        body.insert("stream".into(), json!(stream));
+    }
+    if let Some(top) = request.logprobs {
+        body.insert("logprobs".into(), json!(true));
+        body.insert("top_logprobs".into(), json!(top));
+    }
+    serde_json::from_value(Value::Object(body)).map_err(|e| format!("Failed to build chat request: {}", e))
+}
+
+/// Chat `logprobs.content` in the legacy `{tokens, token_logprobs, top_logprobs, text_offset}` shape
+fn legacy_logprobs(chat_logprobs: &Value) -> Value {
+    let Some(entries) = chat_logprobs.get("content").and_then(|c| c.as_array()) else {
+        return Value::Null;
+    };
+    let mut tokens = Vec::new();
+    let mut token_logprobs = Vec::new();
+    let mut top_logprobs = Vec::new();
+    let mut text_offset = Vec::new();
+    let mut offset = 0;
+    for entry in entries {
+        let token = entry.get("token").and_then(|t| t.as_str()).unwrap_or_default();
+        text_offset.push(json!(offset));
+        offset += token.chars().count();
+        tokens.push(json!(token));
+        token_logprobs.push(entry.get("logprob").cloned().unwrap_or(Value::Null));
+        let alternatives: Map<String, Value> = entry
+            .get("top_logprobs")
+            .and_then(|t| t.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|alt| Some((alt.get("token")?.as_str()?.to_string(), alt.get("logprob")?.clone())))
+            .collect();
+        top_logprobs.push(Value::Object(alternatives));
+    }
+    json!({
+        "tokens": tokens,
+        "token_logprobs": token_logprobs,
+        "top_logprobs": top_logprobs,
+        "text_offset": text_offset,
+    })
+}
+
+fn convert_choice(choice: &Value, text: &str) -> Value {
+    json!({
+        "index": choice.get("index").cloned().unwrap_or(json!(0)),
+        "text": text,
+        "finish_reason": choice.get("finish_reason").cloned().unwrap_or(Value::Null),
+        "logprobs": choice.get("logprobs").map(legacy_logprobs).unwrap_or(Value::Null),
+    })
+}
+
+fn completion_object(chat: &Value, choices: Vec<Value>) -> Value {
+    let mut completion = json!({
+        "id": chat.get("id").cloned().unwrap_or(json!("cmpl-arandu")),
+        "object": "text_completion",
+        "created": chat.get("created").cloned().unwrap_or(json!(chrono::Utc::now().timestamp())),
+        "model": chat.get("model").cloned().unwrap_or(json!("")),
+        "choices": choices,
+    });
+    if let Some(usage) = chat.get("usage").filter(|u| !u.is_null()) {
+        completion["usage"] = usage.clone();
+    }
+    completion
+}
+
+pub fn completion_from_chat(chat: &Value) -> Value {
+    let choices = chat
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .map(|choice| {
+            let text = choice.pointer("/message/content").and_then(|c| c.as_str()).unwrap_or_default();
+            convert_choice(choice, text)
+        })
+        .collect();
+    completion_object(chat, choices)
+}
+
+/// One SSE `data:` payload of a chat stream as a completion chunk; `[DONE]` and
+/// anything unparseable pass through unchanged
+pub fn completion_chunk_from_chat(data: &str) -> String {
+    let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+        return data.to_string();
+    };
+    if chunk.get("error").is_some() {
+        return data.to_string();
+    }
+    let choices = chunk
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .map(|choice| {
+            let text = choice.pointer("/delta/content").and_then(|c| c.as_str()).unwrap_or_default();
+            convert_choice(choice, text)
+        })
+        .collect();
+    completion_object(&chunk, choices).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chat_answers_convert_to_text_completions() {
+        let request: CompletionRequest = serde_json::from_value(json!({
+            "model": "local", "prompt": "Once upon a time", "max_tokens": 16, "logprobs": 2, "echo": false, "temperature": 0.5
+        }))
+        .unwrap();
+        let chat = to_chat_request(&request).unwrap();
+        assert_eq!(chat.messages[0].content, "Once upon a time");
+        assert_eq!(chat.temperature, Some(0.5));
+        assert_eq!(chat.extra["top_logprobs"], 2);
+
+        let answer = json!({
+            "id": "chatcmpl-1", "created": 1, "model": "local",
+            "choices": [{"index": 0, "finish_reason": "length", "message": {"role": "assistant", "content": " there"},
+                "logprobs": {"content": [{"token": " there", "logprob": -0.5, "top_logprobs": [{"token": " there", "logprob": -0.5}]}]}}],
+            "usage": {"prompt_tokens": 4, "completion_tokens": 1}
+        });
+        let completion = completion_from_chat(&answer);
+        assert_eq!(completion["object"], "text_completion");
+        assert_eq!(completion["choices"][0]["text"], " there");
+        assert_eq!(completion["choices"][0]["logprobs"]["token_logprobs"][0], -0.5);
+        assert_eq!(completion["choices"][0]["logprobs"]["top_logprobs"][0][" there"], -0.5);
+
+        assert_eq!(completion_chunk_from_chat("[DONE]"), "[DONE]");
+        let chunk = completion_chunk_from_chat(r#"{"choices":[{"index":0,"delta":{"content":"Hi"}}]}"#);
+        assert!(chunk.contains("\"text\":\"Hi\""));
+
+        let tokens: CompletionRequest = serde_json::from_value(json!({"prompt": [[1, 2, 3]]})).unwrap();
+        assert!(to_chat_request(&tokens).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 // Arandu Backend - Main Library
 // AI AGENTS: Check nowledge-mem memory for file locations and patterns before modifying
 // Search: "Arandu Complete File Location Reference" | "Arandu Common Development Patterns"
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::fs;
 use chrono::Utc;
@@ -14,21 +14,87 @@ use tokio::process::Command as TokioCommand;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::time::{timeout, Duration, Instant};
 
+mod archive;
+mod long_path;
 mod models;
 mod config;
 mod process;
+mod cli_args;
+mod task_manager;
 mod scanner;
 mod huggingface;
 mod downloader;
+mod download_cleanup;
+mod download_queue;
+mod download_resume;
+mod download_source;
 mod llamacpp_manager;
 mod system_monitor;
+mod gpu_runtime;
+mod backend_bench;
 mod gguf_parser;
 mod update_checker;
 mod huggingface_downloader;
+mod hf_client;
+mod http_pool;
 mod tracker_scraper;
 mod tracker_manager;
+mod events;
 mod openai_types;
 mod openai_proxy;
+mod openapi_spec;
+mod proxy_guard;
+mod port_mapping;
+mod tunnels;
+mod launch_queue;
+mod log_classifier;
+mod process_summary;
+mod process_registry;
+mod suspend;
+mod parameter_controller;
+mod sampling_profiles;
+mod scheduler;
+mod idle_unload;
+mod context_settings;
+mod chat_store;
+mod memory_mode;
+mod multi_gpu;
+mod translation;
+mod logprobs;
+mod tool_call_test;
+mod fallback;
+mod model_routing;
+mod prompt_compare;
+mod replay;
+mod context_budget;
+mod route_policy;
+mod legacy_completions;
+mod generation_metrics;
+mod energy_report;
+mod disk_watchdog;
+mod memory_watchdog;
+mod power;
+mod model_files;
+mod model_reconcile;
+mod model_identity;
+mod model_pack;
+mod model_verify;
+mod curated_list;
+mod vram_estimate;
+mod tracker_classify;
+mod storage;
+mod settings_schema;
+mod app_health;
+mod i18n;
+mod analytics;
+mod mcp_import;
+mod mcp_catalog;
+mod mcp_auth;
+mod github_token;
+mod mcp_health;
+mod structured_fixer;
+mod terminal;
+mod macos;
 mod llama_client;
 mod discovery;
 mod peer_cache;
@@ -39,12 +105,12 @@ use process::launch_model_external as launch_model_external_impl;
 use scanner::*;
 use huggingface::*;
 use huggingface_downloader::*;
-use models::{GlobalConfig, ModelConfig, ModelPreset, ProcessInfo, SessionState, WindowState, ProcessOutput, SearchResult, ModelDetails, DownloadStartResult, UpdateCheckResult, UpdateStatus, InitialScanResult, HFLinkResult, HFFileInfo, HfMetadata, GgufMetadata, TrackerModel, TrackerConfig, TrackerStats, WeeklyReport, McpServerConfig, McpToolsResult, McpToolInfo, McpTestResult, McpTransport, McpToolCallRequest, McpToolCallResult, SupermemoryNativeCallRequest, SupermemoryNativeCallResult, DiscoveredPeer, DiscoveryStatus, ActiveModel};
+use models::{GlobalConfig, ModelConfig, ModelPreset, ProcessInfo, SessionState, WindowState, ProcessOutput, SearchResult, ModelDetails, DownloadStartResult, UpdateCheckResult, UpdateStatus, InitialScanResult, HFLinkResult, HFFileInfo, HfMetadata, GgufMetadata, TrackerModel, TrackerConfig, TrackerStats, TrackerFileList, TrackerDbInfo, WeeklyReport, HfCollection, WatchlistEntry, McpServerConfig, McpToolsResult, McpToolInfo, McpTestResult, McpTransport, McpToolCallRequest, McpToolCallResult, SupermemoryNativeCallRequest, SupermemoryNativeCallResult, ChatMessageMetadata, ChatLogMessage, DiscoveredPeer, DiscoveryStatus, ActiveModel, BackendBenchmarkRecord, LaunchResult, BatchLaunchItem, BatchLaunchEntry, RestartMode, RestartPolicy, MemorySettings, GpuDevices, PowerPolicy, ModelSchedule, SamplingProfile, ContextSettings, TranslationSettings, MessageTranslation};
 use downloader::{DownloadManager, DownloadStatus};
 use llamacpp_manager::{LlamaCppReleaseFrontend as LlamaCppRelease, LlamaCppAssetFrontend as LlamaCppAsset};
 use system_monitor::*;
 use tracker_scraper::TrackerScraper;
-use tracker_manager::TrackerManager;
+use tracker_manager::TrackerDb;
 
 // Import ProcessHandle from process module
 use process::ProcessHandle;
@@ -54,8 +120,8 @@ use discovery::DiscoveryService;
 use peer_cache::PeerModelCache;
 
 fn arandu_base_dir() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or_else(|| "Unable to resolve home directory".to_string())?;
-    Ok(home.join(".Arandu"))
+    dirs::home_dir().ok_or_else(|| "Unable to resolve home directory".to_string())?;
+    Ok(models::default_arandu_base_dir())
 }
 
 fn chats_dir() -> Result<PathBuf, String> {
@@ -109,10 +175,16 @@ fn read_chats_index() -> Result<Vec<serde_json::Value>, String> {
                 eprintln!("[Arandu] Chats index recovered successfully. Backup saved to .bak");
                 data
             } else {
-                // Recovery failed - back up corrupt file and return empty
+                // Recovery failed - back up the corrupt file and fall back to the mirror
                 let backup = index_path.with_extension("json.bak");
                 let _ = fs::copy(&index_path, &backup);
                 let _ = fs::remove_file(&index_path);
+                let mirrored = storage::with(|db| db.chats().index()).unwrap_or_default();
+                if !mirrored.is_empty() {
+                    eprintln!("[Arandu] Chats index rebuilt from storage ({} chats). Backup saved to .bak", mirrored.len());
+                    let _ = write_chats_index(&mirrored);
+                    return Ok(mirrored);
+                }
                 eprintln!("[Arandu] Warning: Could not recover chats index. Backup saved to .bak, starting fresh.");
                 return Ok(Vec::new());
             }
@@ -210,7 +282,12 @@ fn write_chats_index(index: &[serde_json::Value]) -> Result<(), String> {
     fs::write(&tmp_path, &content)
         .map_err(|e| format!("Failed to write chats index temp file: {}", e))?;
     fs::rename(&tmp_path, &index_path)
-        .map_err(|e| format!("Failed to rename chats index temp file: {}", e))
+        .map_err(|e| format!("Failed to rename chats index temp file: {}", e))?;
+    // Mirror kept in the shared store so a corrupt index can be rebuilt
+    if let Err(e) = storage::with(|db| db.chats().replace_index(index)) {
+        eprintln!("[Arandu] Warning: Failed to mirror chats index: {}", e);
+    }
+    Ok(())
 }
 
 fn push_path_candidate(candidates: &mut Vec<PathBuf>, candidate: PathBuf) {
@@ -525,6 +602,23 @@ fn find_chat_entry_index(index: &[serde_json::Value], chat_id: &str) -> Option<u
     index.iter().position(|item| chat_index_matches_query(item, chat_id))
 }
 
+/// A settings object stored under `key` on a chat's index entry; defaults when absent
+fn read_chat_setting<T: serde::de::DeserializeOwned + Default>(chat_id: &str, key: &str) -> Result<T, String> {
+    let index = read_chats_index()?;
+    let idx = find_chat_entry_index(&index, chat_id).ok_or_else(|| "Chat not found".to_string())?;
+    Ok(index[idx]
+        .get(key)
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default())
+}
+
+fn write_chat_setting<T: serde::Serialize>(chat_id: &str, key: &str, value: &T) -> Result<(), String> {
+    let mut index = read_chats_index()?;
+    let idx = find_chat_entry_index(&index, chat_id).ok_or_else(|| "Chat not found".to_string())?;
+    index[idx][key] = serde_json::to_value(value).map_err(|e| format!("Failed to serialize chat settings: {}", e))?;
+    write_chats_index(&index)
+}
+
 fn chat_markdown_path(chat_id: &str) -> Result<PathBuf, String> {
     Ok(chats_dir()?.join(format!("{}.md", chat_id)))
 }
@@ -555,12 +649,193 @@ fn sanitize_chat_model_label(raw: &str) -> String {
 
     normalized
         .split('/')
-        .filter(|segment| !segment.is_empty())
-        .last()
+        .rfind(|segment| !segment.is_empty())
         .unwrap_or("")
         .to_string()
 }
 
+fn chat_markdown_frontmatter(entry: &serde_json::Value) -> String {
+    format!(
+        "---\nchat_id: {}\ntitle: {}\ncreated_at: {}\nlast_used_at: {}\nmodels_used: {}\n---\n\n",
+        entry["chat_id"].as_str().unwrap_or(""),
+        entry["title"].as_str().unwrap_or("Untitled Chat"),
+        entry["created_at"].as_str().unwrap_or(""),
+        entry["last_used_at"].as_str().unwrap_or(""),
+        entry["models_used"].as_array()
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(", "))
+            .unwrap_or_default()
+    )
+}
+
+fn chat_metadata_path(chat_path: &Path) -> PathBuf {
+    chat_path.with_extension("meta.json")
+}
+
+fn read_chat_message_metadata(chat_path: &Path) -> Vec<ChatMessageMetadata> {
+    let meta_path = chat_metadata_path(chat_path);
+    if !meta_path.exists() {
+        return Vec::new();
+    }
+
+    match fs::read_to_string(&meta_path)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| serde_json::from_str::<Vec<ChatMessageMetadata>>(&raw).map_err(|e| e.to_string()))
+    {
+        Ok(entries) => entries,
+        Err(e) => {
+            eprintln!("[Arandu] Warning: Failed to read chat metadata '{}': {}", meta_path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+fn write_chat_message_metadata(chat_path: &Path, entries: &[ChatMessageMetadata]) -> Result<(), String> {
+    let meta_path = chat_metadata_path(chat_path);
+    let content = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Failed to serialize chat metadata: {}", e))?;
+    let tmp_path = meta_path.with_extension("json.tmp");
+    fs::write(&tmp_path, &content)
+        .map_err(|e| format!("Failed to write chat metadata temp file: {}", e))?;
+    fs::rename(&tmp_path, &meta_path)
+        .map_err(|e| format!("Failed to rename chat metadata temp file: {}", e))
+}
+
+fn is_chat_message_header(line: &str) -> bool {
+    let Some(rest) = line.strip_prefix("## ") else {
+        return false;
+    };
+    let mut parts = rest.split(" | ");
+    let role = parts.next().unwrap_or("");
+    matches!(role, "USER" | "ASSISTANT" | "SYSTEM") && parts.next().is_some()
+}
+
+fn strip_chat_frontmatter(markdown: &str) -> &str {
+    if let Some(rest) = markdown.strip_prefix("---\n") {
+        if let Some(end) = rest.find("\n---\n") {
+            return rest[end + 5..].trim_start_matches('\n');
+        }
+    }
+    markdown
+}
+
+/// Split a chat markdown body into one section per `## ROLE | timestamp | model` message.
+/// Anything before the first message header is dropped.
+fn split_chat_message_sections(markdown: &str) -> Vec<String> {
+    let body = strip_chat_frontmatter(markdown);
+    let mut sections = Vec::new();
+    let mut current = String::new();
+    let mut in_message = false;
+
+    for line in body.split_inclusive('\n') {
+        if is_chat_message_header(line.trim_end_matches(['\r', '\n'])) {
+            if in_message {
+                sections.push(std::mem::take(&mut current));
+            }
+            in_message = true;
+        }
+        if in_message {
+            current.push_str(line);
+        }
+    }
+
+    if in_message {
+        sections.push(current);
+    }
+    sections
+}
+
+fn chat_section_body(section: &str) -> &str {
+    match section.split_once('\n') {
+        Some((_, body)) => body,
+        None => "",
+    }
+}
+
+/// Collect fenced (``` or ~~~) code blocks from markdown, keeping the info-string language.
+/// An unterminated fence runs to the end of the text.
+fn extract_fenced_code_blocks(markdown: &str) -> Vec<serde_json::Value> {
+    let mut blocks = Vec::new();
+    let mut open_fence: Option<(String, String)> = None;
+    let mut code = String::new();
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_start();
+        match &open_fence {
+            None => {
+                let fence_char = if trimmed.starts_with("```") {
+                    '`'
+                } else if trimmed.starts_with("~~~") {
+                    '~'
+                } else {
+                    continue;
+                };
+                let fence: String = trimmed.chars().take_while(|c| *c == fence_char).collect();
+                let language = trimmed[fence.len()..]
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or("")
+                    .to_lowercase();
+                open_fence = Some((fence, language));
+                code.clear();
+            }
+            Some((fence, language)) => {
+                let closing = trimmed.trim_end();
+                if closing.starts_with(fence.as_str()) && closing.chars().all(|c| c == fence.chars().next().unwrap_or('`')) {
+                    blocks.push(serde_json::json!({
+                        "index": blocks.len(),
+                        "language": language,
+                        "code": code.trim_end_matches('\n'),
+                    }));
+                    open_fence = None;
+                } else {
+                    code.push_str(line);
+                    code.push('\n');
+                }
+            }
+        }
+    }
+
+    if let Some((_, language)) = open_fence {
+        blocks.push(serde_json::json!({
+            "index": blocks.len(),
+            "language": language,
+            "code": code.trim_end_matches('\n'),
+        }));
+    }
+    blocks
+}
+
+fn validate_code_block_target_path(file_path: &str, overwrite: bool) -> Result<PathBuf, String> {
+    let trimmed = file_path.trim();
+    if trimmed.is_empty() {
+        return Err("File path is required".to_string());
+    }
+
+    let path = PathBuf::from(trimmed);
+    if !path.is_absolute() {
+        return Err("File path must be absolute".to_string());
+    }
+    if path.components().any(|c| matches!(c, std::path::Component::ParentDir)) {
+        return Err("File path must not contain '..' segments".to_string());
+    }
+    if path.file_name().is_none() {
+        return Err("File path must include a file name".to_string());
+    }
+
+    let parent = path.parent().ok_or_else(|| "File path has no parent directory".to_string())?;
+    if !parent.is_dir() {
+        return Err(format!("Directory does not exist: {}", parent.display()));
+    }
+    if path.is_dir() {
+        return Err("File path points to a directory".to_string());
+    }
+    if path.exists() && !overwrite {
+        return Err("File already exists".to_string());
+    }
+
+    Ok(path)
+}
+
 #[tauri::command]
 async fn list_chat_logs() -> Result<Vec<serde_json::Value>, String> {
     let mut index = read_chats_index()?;
@@ -593,17 +868,9 @@ async fn create_chat_log(model: String) -> Result<serde_json::Value, String> {
     });
 
     let chat_path = chat_markdown_path(entry.get("chat_id").and_then(|v| v.as_str()).unwrap_or(""))?;
-    let md = format!(
-        "---\nchat_id: {}\ntitle: {}\ncreated_at: {}\nlast_used_at: {}\nmodels_used: {}\n---\n\n",
-        entry["chat_id"].as_str().unwrap_or(""),
-        entry["title"].as_str().unwrap_or("Untitled Chat"),
-        entry["created_at"].as_str().unwrap_or(""),
-        entry["last_used_at"].as_str().unwrap_or(""),
-        entry["models_used"].as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(", "))
-            .unwrap_or_default()
-    );
+    let md = chat_markdown_frontmatter(&entry);
     fs::write(&chat_path, md).map_err(|e| format!("Failed to create chat file: {}", e))?;
+    chat_store::write(&chat_path, &chat_id, &[])?;
 
     index.push(entry.clone());
     write_chats_index(&index)?;
@@ -611,7 +878,14 @@ async fn create_chat_log(model: String) -> Result<serde_json::Value, String> {
 }
 
 #[tauri::command]
-async fn append_chat_log_message(chat_id: String, role: String, content: String, model: String) -> Result<serde_json::Value, String> {
+async fn append_chat_log_message(
+    chat_id: String,
+    role: String,
+    content: String,
+    model: String,
+    metadata: Option<ChatMessageMetadata>,
+    logprobs: Option<serde_json::Value>,
+) -> Result<serde_json::Value, String> {
     let role_norm = role.trim().to_lowercase();
     if role_norm != "user" && role_norm != "assistant" && role_norm != "system" {
         return Err("Invalid chat role".to_string());
@@ -630,6 +904,7 @@ async fn append_chat_log_message(chat_id: String, role: String, content: String,
     }
 
     let model_label = sanitize_chat_model_label(&model);
+    let message_index = split_chat_message_sections(&read_chat_markdown(&path)?).len();
     let section = format!(
         "## {} | {} | {}\n\n{}\n\n",
         role_norm.to_uppercase(),
@@ -657,6 +932,59 @@ async fn append_chat_log_message(chat_id: String, role: String, content: String,
         fs::write(&path, existing).map_err(|e| format!("Failed to append chat file: {}", e))?;
     }
 
+    let mut message = ChatLogMessage {
+        role: role_norm.clone(),
+        content: content.trim().to_string(),
+        model: (!model_label.is_empty()).then(|| model_label.clone()),
+        timestamp: now.clone(),
+        duration_ms: None,
+        prompt_tokens: None,
+        completion_tokens: None,
+    };
+
+    if let Some(mut meta) = metadata {
+        message.duration_ms = meta.duration_ms;
+        message.prompt_tokens = meta.prompt_tokens;
+        message.completion_tokens = meta.completion_tokens;
+        meta.message_index = message_index;
+        meta.role = role_norm.clone();
+        meta.timestamp = now.clone();
+        if meta.model.as_deref().is_none_or(|m| m.trim().is_empty()) && !model_label.is_empty() {
+            meta.model = Some(model_label.clone());
+        }
+        if meta.seed.is_none() {
+            meta.seed = replay::recorded_seed(meta.params.as_ref());
+        }
+        if let (Some(power), Some(tokens)) = (&meta.gpu_power, meta.completion_tokens) {
+            energy_report::record(energy_report::EnergySample {
+                model: meta.model.clone().unwrap_or_default(),
+                backend: None,
+                source: "chat".to_string(),
+                tokens,
+                energy_wh: power.energy_wh,
+                avg_power_watts: power.avg_power_watts,
+                recorded_at: Utc::now(),
+            });
+        }
+        let mut entries = read_chat_message_metadata(&path);
+        entries.retain(|entry| entry.message_index != message_index);
+        entries.push(meta);
+        write_chat_message_metadata(&path, &entries)?;
+    }
+
+    // The markdown already has the message; a failed structured copy is rebuilt from it later
+    if let Err(e) = chat_store::append(&path, &chat_id, message_index, message) {
+        eprintln!("[Arandu] Warning: Failed to store structured chat message: {}", e);
+    }
+
+    // Response (or stream chunks) of a request sent with `n_probs` / `logprobs`
+    if let Some(raw) = logprobs.filter(|_| role_norm == "assistant") {
+        let tokens = logprobs::extract(&raw);
+        if !tokens.is_empty() {
+            logprobs::store(&path, message_index, tokens)?;
+        }
+    }
+
     let message_count = index[idx].get("message_count").and_then(|v| v.as_i64()).unwrap_or(0) + 1;
     index[idx]["message_count"] = serde_json::json!(message_count);
     index[idx]["last_used_at"] = serde_json::json!(now);
@@ -677,6 +1005,73 @@ async fn append_chat_log_message(chat_id: String, role: String, content: String,
     Ok(index[idx].clone())
 }
 
+#[tauri::command]
+async fn get_chat_context_settings(chat_id: String) -> Result<ContextSettings, String> {
+    context_settings::for_chat(&chat_id)
+}
+
+/// `cache_prompt` / `n_keep` apply to the chat's next request through the proxy;
+/// `context_shift` / `cache_reuse` need the model launched for the chat
+#[tauri::command]
+async fn set_chat_context_settings(chat_id: String, settings: ContextSettings) -> Result<ContextSettings, String> {
+    context_settings::save_for_chat(&chat_id, &settings)?;
+    Ok(settings)
+}
+
+#[tauri::command]
+async fn detect_message_language(text: String) -> Result<translation::DetectedLanguage, String> {
+    Ok(translation::detect_language(&text))
+}
+
+#[tauri::command]
+async fn get_chat_translation_settings(chat_id: String) -> Result<TranslationSettings, String> {
+    translation::for_chat(&chat_id)
+}
+
+#[tauri::command]
+async fn set_chat_translation_settings(chat_id: String, settings: TranslationSettings) -> Result<TranslationSettings, String> {
+    translation::save_for_chat(&chat_id, &settings)?;
+    Ok(settings)
+}
+
+/// Translate a message for the chat. User messages go to the chat's target language;
+/// pass `target_language` to translate a reply back (e.g. to the detected user language).
+/// The returned `translation` is meant for `append_chat_log_message` metadata so the log
+/// keeps both texts.
+#[tauri::command]
+async fn translate_chat_message(
+    chat_id: String,
+    text: String,
+    target_language: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let settings = translation::for_chat(&chat_id)?;
+    let detected = translation::detect_language(&text);
+    let target = target_language.unwrap_or_else(|| settings.target_language.clone());
+
+    if detected.code == "und" || detected.code.eq_ignore_ascii_case(&target) {
+        return Ok(serde_json::json!({
+            "translated": false,
+            "detected": detected,
+            "text": text,
+        }));
+    }
+
+    let translated = translation::translate(&state, &settings, &text, &detected.code, &target).await?;
+    let translation = MessageTranslation {
+        source_language: detected.code.clone(),
+        target_language: target,
+        original: text,
+        translated: translated.clone(),
+    };
+    Ok(serde_json::json!({
+        "translated": true,
+        "detected": detected,
+        "text": translated,
+        "translation": translation,
+    }))
+}
+
 #[tauri::command]
 async fn rename_chat_log(chat_id: String, title: String) -> Result<serde_json::Value, String> {
     let mut index = read_chats_index()?;
@@ -690,6 +1085,76 @@ async fn rename_chat_log(chat_id: String, title: String) -> Result<serde_json::V
     Ok(index[idx].clone())
 }
 
+/// Copy a chat into a new log, optionally keeping only messages up to and including
+/// the zero-based `up_to_message` index.
+#[tauri::command]
+async fn duplicate_chat_log(chat_id: String, up_to_message: Option<usize>) -> Result<serde_json::Value, String> {
+    let mut index = read_chats_index()?;
+    let source_entry = find_chat_entry_index(&index, &chat_id)
+        .and_then(|i| index.get(i))
+        .cloned()
+        .ok_or_else(|| "Chat not found".to_string())?;
+
+    let chats_dir = chats_dir()?;
+    let source_path = resolve_chat_file_path(&chat_id, &index)
+        .or_else(|| resolve_chat_file_path_for_entry(&source_entry, &chats_dir))
+        .ok_or_else(|| "Chat file not found".to_string())?;
+    let markdown = read_chat_markdown(&source_path)?;
+
+    let mut sections = split_chat_message_sections(&markdown);
+    if let Some(last) = up_to_message {
+        if last >= sections.len() {
+            return Err(format!(
+                "Message index {} is out of range (chat has {} messages)",
+                last,
+                sections.len()
+            ));
+        }
+        sections.truncate(last + 1);
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let mut millis = Utc::now().timestamp_millis();
+    let mut new_chat_id = format!("chat-{}", millis);
+    while find_chat_entry_index(&index, &new_chat_id).is_some() || chat_markdown_path(&new_chat_id)?.exists() {
+        millis += 1;
+        new_chat_id = format!("chat-{}", millis);
+    }
+
+    let source_title = source_entry.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled Chat");
+    let entry = serde_json::json!({
+        "chat_id": new_chat_id,
+        "file_path": format!("{}.md", new_chat_id),
+        "title": sanitize_chat_title(&format!("{} (copy)", source_title)),
+        "created_at": now,
+        "last_used_at": now,
+        "last_model": source_entry.get("last_model").cloned().unwrap_or_else(|| serde_json::json!("")),
+        "models_used": source_entry.get("models_used").cloned().unwrap_or_else(|| serde_json::json!([])),
+        "message_count": sections.len(),
+        "forked_from": source_entry.get("chat_id").cloned().unwrap_or_else(|| serde_json::json!(chat_id))
+    });
+
+    let mut md = chat_markdown_frontmatter(&entry);
+    md.push_str(&sections.concat());
+    let new_path = chat_markdown_path(&new_chat_id)?;
+    fs::write(&new_path, md)
+        .map_err(|e| format!("Failed to create chat file: {}", e))?;
+
+    let metadata: Vec<ChatMessageMetadata> = read_chat_message_metadata(&source_path)
+        .into_iter()
+        .filter(|meta| meta.message_index < sections.len())
+        .collect();
+    if !metadata.is_empty() {
+        write_chat_message_metadata(&new_path, &metadata)?;
+    }
+    let messages = chat_store::from_markdown(&sections.concat(), &metadata);
+    chat_store::write(&new_path, &new_chat_id, &messages)?;
+
+    index.push(entry.clone());
+    write_chats_index(&index)?;
+    Ok(entry)
+}
+
 #[tauri::command]
 async fn get_chat_log(chat_id: String) -> Result<serde_json::Value, String> {
     let index = read_chats_index()?;
@@ -709,173 +1174,355 @@ async fn get_chat_log(chat_id: String) -> Result<serde_json::Value, String> {
         return Err("Chat file not found".to_string());
     }
     let markdown = read_chat_markdown(&path)?;
+    let message_metadata = read_chat_message_metadata(&path);
 
     Ok(serde_json::json!({
         "entry": entry,
-        "markdown": markdown
+        "markdown": markdown,
+        "message_metadata": message_metadata
     }))
 }
 
-#[tauri::command]
-async fn delete_chat_log(chat_id: String) -> Result<serde_json::Value, String> {
-    let normalized_chat_id = chat_id.trim();
-    if normalized_chat_id.is_empty() {
-        return Err("chat_id is required".to_string());
-    }
+/// Index id and log file of a chat
+fn resolve_chat(chat_id: &str, index: &[serde_json::Value]) -> Result<(String, PathBuf), String> {
+    let entry = find_chat_entry_index(index, chat_id)
+        .and_then(|i| index.get(i))
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({"chat_id": chat_id}));
+    let chats_dir = chats_dir()?;
+    let path = resolve_chat_file_path(chat_id, index)
+        .or_else(|| resolve_chat_file_path_for_entry(&entry, &chats_dir))
+        .ok_or_else(|| "Chat file not found".to_string())?;
+    let chat_id = entry.get("chat_id").and_then(|v| v.as_str()).unwrap_or(chat_id).to_string();
+    Ok((chat_id, path))
+}
 
-    let index = read_chats_index()?;
-    let mut index_with_paths = index.clone();
+/// Typed messages of a chat for reloading it into the chat UI
+#[tauri::command]
+async fn get_chat_messages(chat_id: String) -> Result<Vec<ChatLogMessage>, String> {
+    let (chat_id, path) = resolve_chat(&chat_id, &read_chats_index()?)?;
+    chat_store::messages(&path, &chat_id)
+}
 
-    let matched_entry = index_with_paths
-        .iter()
-        .find(|entry| chat_index_matches_query(entry, normalized_chat_id))
-        .cloned();
+/// Content of a chat in a fine-tuning dataset format (one JSONL line) or as its markdown log
+fn export_chat_content(chat_id: &str, path: &Path, format: chat_store::ExportFormat) -> Result<String, String> {
+    match format {
+        chat_store::ExportFormat::Markdown => read_chat_markdown(path),
+        _ => chat_store::dataset_line(&chat_store::messages(path, chat_id)?, format)
+            .ok_or_else(|| "Chat has no messages".to_string()),
+    }
+}
 
-    let chats_dir = chats_dir()?;
-    let chat_file_path = resolve_chat_file_path(normalized_chat_id, &index_with_paths)
-        .or_else(|| matched_entry.as_ref().and_then(|entry| resolve_chat_file_path_for_entry(entry, &chats_dir)))
-        .or_else(|| resolve_chat_file_path_from_query(normalized_chat_id));
+/// Chat as `openai-jsonl`, `sharegpt` or `markdown`, with a file name for saving it
+#[tauri::command]
+async fn export_chat_log(chat_id: String, format: chat_store::ExportFormat) -> Result<serde_json::Value, String> {
+    let (chat_id, path) = resolve_chat(&chat_id, &read_chats_index()?)?;
+    let content = export_chat_content(&chat_id, &path, format)?;
+    Ok(serde_json::json!({
+        "chat_id": chat_id,
+        "file_name": format!("{}.{}", export_file_stem(&chat_id), format.extension()),
+        "content": content,
+    }))
+}
 
-    let removed_count = {
-        let before_len = index_with_paths.len();
-        index_with_paths.retain(|entry| !chat_index_matches_query(entry, normalized_chat_id));
-        before_len - index_with_paths.len()
-    };
+fn export_file_stem(chat_id: &str) -> String {
+    chat_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
 
-    if removed_count == 0 && chat_file_path.is_none() {
-        return Err("Chat not found".to_string());
+/// Write every chat into `folder`, one file per chat; chats without messages are skipped
+#[tauri::command]
+async fn export_all_chats(folder: String, format: chat_store::ExportFormat) -> Result<serde_json::Value, String> {
+    let folder = PathBuf::from(folder.trim());
+    if !folder.is_absolute() || !folder.is_dir() {
+        return Err(format!("Not an existing folder: {}", folder.display()));
     }
 
-    let mut file_deleted = false;
-    if let Some(path) = chat_file_path {
-        if path.exists() {
-            fs::remove_file(&path)
-                .map_err(|e| format!("Failed to delete chat file '{}': {}", path.display(), e))?;
-            file_deleted = true;
+    let index = read_chats_index()?;
+    let mut written = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in &index {
+        let Some(id) = entry.get("chat_id").and_then(|v| v.as_str()) else { continue };
+        let content = resolve_chat(id, &index).and_then(|(id, path)| export_chat_content(&id, &path, format));
+        match content {
+            Ok(content) => {
+                let target = folder.join(format!("{}.{}", export_file_stem(id), format.extension()));
+                fs::write(&target, content).map_err(|e| format!("Failed to write '{}': {}", target.display(), e))?;
+                written.push(target.to_string_lossy().to_string());
+            }
+            Err(e) => skipped.push(serde_json::json!({ "chat_id": id, "reason": e })),
         }
     }
 
-    write_chats_index(&index_with_paths)?;
-
-    let removed_chat_id = matched_entry
-        .as_ref()
-        .and_then(|entry| entry.get("chat_id").and_then(|v| v.as_str()))
-        .map(str::to_string)
-        .filter(|value| !value.trim().is_empty())
-        .unwrap_or_else(|| normalized_chat_id.to_string());
-
     Ok(serde_json::json!({
-        "chat_id": removed_chat_id,
-        "file_deleted": file_deleted,
-        "removed_count": removed_count
+        "folder": folder.to_string_lossy(),
+        "written": written,
+        "skipped": skipped,
     }))
 }
 
+/// Create a chat from markdown in the `## ROLE | timestamp | model` log format, e.g. an
+/// exported or hand-edited log. A frontmatter `title:` names the chat unless `title` is given.
 #[tauri::command]
-async fn search_chat_logs(term: String) -> Result<Vec<serde_json::Value>, String> {
-    let needle = term.trim().to_lowercase();
-    if needle.is_empty() {
-        return list_chat_logs().await;
+async fn import_chat_markdown(markdown: String, title: Option<String>) -> Result<serde_json::Value, String> {
+    let markdown = markdown.replace("\r\n", "\n");
+    let mut messages = chat_store::from_markdown(&markdown, &[]);
+    if messages.is_empty() {
+        return Err("No chat messages found; expected \"## USER | timestamp | model\" headers".to_string());
+    }
+
+    let frontmatter_title = markdown
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+        .and_then(|(front, _)| front.lines().find_map(|line| line.strip_prefix("title:")))
+        .map(|t| t.trim().to_string());
+    let now = Utc::now().to_rfc3339();
+    for message in messages.iter_mut().filter(|m| m.timestamp.is_empty()) {
+        message.timestamp = now.clone();
     }
 
-    let index = read_chats_index()?;
-    let chats_dir = chats_dir()?;
-    let mut matches = Vec::new();
-
-    for item in index.iter() {
-        let chat_id = item
-            .get("chat_id")
-            .and_then(|v| v.as_str())
-            .or_else(|| item.get("id").and_then(|v| v.as_str()))
-            .unwrap_or("");
-        let title = item.get("title").and_then(|v| v.as_str()).unwrap_or("");
-        let mut is_match = title.to_lowercase().contains(&needle);
-        if !is_match {
-            let path = resolve_chat_file_path(&chat_id, &index)
-                .or_else(|| resolve_chat_file_path_for_entry(&item, &chats_dir));
-
-            if let Some(path) = path {
-                if let Ok(md) = read_chat_markdown(&path) {
-                    if md.to_lowercase().contains(&needle) {
-                        is_match = true;
-                    }
-                }
-            }
-        }
+    let mut index = read_chats_index()?;
+    let mut millis = Utc::now().timestamp_millis();
+    let mut chat_id = format!("chat-{}", millis);
+    while find_chat_entry_index(&index, &chat_id).is_some() || chat_markdown_path(&chat_id)?.exists() {
+        millis += 1;
+        chat_id = format!("chat-{}", millis);
+    }
 
-        if is_match {
-            matches.push(item.clone());
+    let mut models_used: Vec<String> = Vec::new();
+    for model in messages.iter().filter_map(|m| m.model.as_deref()).map(sanitize_chat_model_label) {
+        if !model.is_empty() && !models_used.contains(&model) {
+            models_used.push(model);
         }
     }
-
-    matches.sort_by(|a, b| {
-        let a_ts = a.get("last_used_at").and_then(|v| v.as_str()).unwrap_or("");
-        let b_ts = b.get("last_used_at").and_then(|v| v.as_str()).unwrap_or("");
-        b_ts.cmp(a_ts)
+    let entry = serde_json::json!({
+        "chat_id": chat_id,
+        "file_path": format!("{}.md", chat_id),
+        "title": sanitize_chat_title(&title.or(frontmatter_title).unwrap_or_else(|| format!("Imported chat {}", Utc::now().format("%Y-%m-%d %H:%M")))),
+        "created_at": messages.first().map(|m| m.timestamp.clone()).unwrap_or_else(|| now.clone()),
+        "last_used_at": now,
+        "last_model": models_used.last().cloned().unwrap_or_default(),
+        "models_used": models_used,
+        "message_count": messages.len(),
+        "imported": true
     });
-    Ok(matches)
+
+    let path = chat_markdown_path(&chat_id)?;
+    let mut md = chat_markdown_frontmatter(&entry);
+    md.push_str(&chat_store::to_markdown(&messages));
+    fs::write(&path, md).map_err(|e| format!("Failed to create chat file: {}", e))?;
+    chat_store::write(&path, &chat_id, &messages)?;
+
+    index.push(entry.clone());
+    write_chats_index(&index)?;
+    Ok(entry)
 }
 
-/// Detect backend type from asset name
-fn detect_backend_type(asset_name: &str) -> String {
-    let name_lower = asset_name.to_lowercase();
-    
-    if name_lower.contains("cuda") || name_lower.contains("cudart") {
-        "cuda".to_string()
-    } else if name_lower.contains("rocm") || name_lower.contains("hip") {
-        "rocm".to_string()
-    } else if name_lower.contains("vulkan") {
-        "vulkan".to_string()
-    } else if name_lower.contains("opencl") {
-        "opencl".to_string()
-    } else if name_lower.contains("metal") {
-        "metal".to_string()
-    } else if name_lower.contains("cpu") || (!name_lower.contains("cuda") && !name_lower.contains("vulkan") && !name_lower.contains("opencl") && !name_lower.contains("metal") && !name_lower.contains("rocm") && !name_lower.contains("hip")) {
-        "cpu".to_string()
-    } else {
-        "unknown".to_string()
-    }
+/// Token logprobs captured for an assistant message, for highlighting low-confidence spans
+#[tauri::command]
+async fn get_message_logprobs(chat_id: String, message_index: usize) -> Result<Option<Vec<logprobs::TokenLogprob>>, String> {
+    let index = read_chats_index()?;
+    let path = resolve_chat_file_path(&chat_id, &index)
+        .or_else(|| chat_markdown_path(&chat_id).ok())
+        .ok_or_else(|| "Chat file not found".to_string())?;
+    Ok(logprobs::load(&path, message_index))
+}
+
+/// Send an assistant message's prompt again with its recorded seed and parameters, to
+/// check that the running model still produces the same output
+#[tauri::command]
+async fn repeat_chat_message(
+    chat_id: String,
+    message_index: usize,
+    state: tauri::State<'_, AppState>,
+) -> Result<replay::ReplayResult, String> {
+    let index = read_chats_index()?;
+    let path = resolve_chat_file_path(&chat_id, &index)
+        .or_else(|| chat_markdown_path(&chat_id).ok())
+        .ok_or_else(|| "Chat file not found".to_string())?;
+    replay::repeat(&state, &path, &chat_id, message_index).await
+}
+
+/// How much of the model's context the chat's next request would use with `new_message`
+/// added, and what would be cut, without sending it. `model` defaults to the chat's last model.
+#[tauri::command]
+async fn simulate_context_usage(
+    chat_id: String,
+    new_message: String,
+    model: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<context_budget::ContextUsage, String> {
+    let index = read_chats_index()?;
+    let path = resolve_chat_file_path(&chat_id, &index)
+        .or_else(|| chat_markdown_path(&chat_id).ok())
+        .ok_or_else(|| "Chat file not found".to_string())?;
+    let model = model
+        .filter(|m| !m.trim().is_empty())
+        .or_else(|| {
+            find_chat_entry_index(&index, &chat_id)
+                .and_then(|i| index[i].get("last_model").and_then(|v| v.as_str()).map(str::to_string))
+                .filter(|m| !m.is_empty())
+        })
+        .ok_or_else(|| "The chat has no model yet; choose one to simulate with".to_string())?;
+    context_budget::simulate(&state, &path, &chat_id, &model, &new_message).await
 }
 
-fn extract_zip_safely_to_directory(zip_path: &Path, destination: &Path) -> Result<(), String> {
-    use std::fs::File;
-    use std::io::BufReader;
-    use zip::ZipArchive;
+#[tauri::command]
+async fn extract_code_blocks(chat_id: String, message_index: usize) -> Result<Vec<serde_json::Value>, String> {
+    let index = read_chats_index()?;
+    let entry = find_chat_entry_index(&index, &chat_id)
+        .and_then(|i| index.get(i))
+        .cloned()
+        .unwrap_or_else(|| serde_json::json!({"chat_id": chat_id}));
+    let chats_dir = chats_dir()?;
+    let path = resolve_chat_file_path(&chat_id, &index)
+        .or_else(|| resolve_chat_file_path_for_entry(&entry, &chats_dir))
+        .or_else(|| resolve_chat_file_path_from_query(&chat_id))
+        .ok_or_else(|| "Chat file not found".to_string())?;
 
-    let file = File::open(zip_path).map_err(|e| format!("Failed to open zip file: {}", e))?;
-    let reader = BufReader::new(file);
-    let mut archive = ZipArchive::new(reader).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+    let sections = split_chat_message_sections(&read_chat_markdown(&path)?);
+    let section = sections.get(message_index).ok_or_else(|| {
+        format!(
+            "Message index {} is out of range (chat has {} messages)",
+            message_index,
+            sections.len()
+        )
+    })?;
 
-    for i in 0..archive.len() {
-        let mut entry = archive
-            .by_index(i)
-            .map_err(|e| format!("Failed to read zip entry: {}", e))?;
+    Ok(extract_fenced_code_blocks(chat_section_body(section)))
+}
 
-        let enclosed = entry
-            .enclosed_name()
-            .ok_or_else(|| format!("Unsafe zip entry path blocked: {}", entry.name()))?
-            .to_owned();
+#[tauri::command]
+async fn save_code_block_to_file(
+    file_path: String,
+    content: String,
+    overwrite: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let path = validate_code_block_target_path(&file_path, overwrite.unwrap_or(false))?;
+    fs::write(&path, content.as_bytes())
+        .map_err(|e| format!("Failed to save code block to '{}': {}", path.display(), e))?;
 
-        let outpath = destination.join(enclosed);
+    Ok(serde_json::json!({
+        "success": true,
+        "path": path.to_string_lossy(),
+        "bytes_written": content.len()
+    }))
+}
 
-        if entry.name().ends_with('/') {
-            fs::create_dir_all(&outpath)
-                .map_err(|e| format!("Failed to create directory during extraction: {}", e))?;
-            continue;
+#[tauri::command]
+async fn delete_chat_log(chat_id: String) -> Result<serde_json::Value, String> {
+    let normalized_chat_id = chat_id.trim();
+    if normalized_chat_id.is_empty() {
+        return Err("chat_id is required".to_string());
+    }
+
+    let index = read_chats_index()?;
+    let mut index_with_paths = index.clone();
+
+    let matched_entry = index_with_paths
+        .iter()
+        .find(|entry| chat_index_matches_query(entry, normalized_chat_id))
+        .cloned();
+
+    let chats_dir = chats_dir()?;
+    let chat_file_path = resolve_chat_file_path(normalized_chat_id, &index_with_paths)
+        .or_else(|| matched_entry.as_ref().and_then(|entry| resolve_chat_file_path_for_entry(entry, &chats_dir)))
+        .or_else(|| resolve_chat_file_path_from_query(normalized_chat_id));
+
+    let removed_count = {
+        let before_len = index_with_paths.len();
+        index_with_paths.retain(|entry| !chat_index_matches_query(entry, normalized_chat_id));
+        before_len - index_with_paths.len()
+    };
+
+    if removed_count == 0 && chat_file_path.is_none() {
+        return Err("Chat not found".to_string());
+    }
+
+    let mut file_deleted = false;
+    if let Some(path) = chat_file_path {
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Failed to delete chat file '{}': {}", path.display(), e))?;
+            file_deleted = true;
         }
+        let meta_path = chat_metadata_path(&path);
+        if meta_path.exists() {
+            let _ = fs::remove_file(&meta_path);
+        }
+        let messages_path = chat_store::path_for(&path);
+        if messages_path.exists() {
+            let _ = fs::remove_file(&messages_path);
+        }
+        logprobs::remove_all(&path);
+    }
+
+    write_chats_index(&index_with_paths)?;
+
+    let removed_chat_id = matched_entry
+        .as_ref()
+        .and_then(|entry| entry.get("chat_id").and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| normalized_chat_id.to_string());
+
+    Ok(serde_json::json!({
+        "chat_id": removed_chat_id,
+        "file_deleted": file_deleted,
+        "removed_count": removed_count
+    }))
+}
+
+#[tauri::command]
+async fn search_chat_logs(term: String) -> Result<Vec<serde_json::Value>, String> {
+    let needle = term.trim().to_lowercase();
+    if needle.is_empty() {
+        return list_chat_logs().await;
+    }
+
+    let index = read_chats_index()?;
+    let chats_dir = chats_dir()?;
+    let mut matches = Vec::new();
+
+    for item in index.iter() {
+        let chat_id = item
+            .get("chat_id")
+            .and_then(|v| v.as_str())
+            .or_else(|| item.get("id").and_then(|v| v.as_str()))
+            .unwrap_or("");
+        let title = item.get("title").and_then(|v| v.as_str()).unwrap_or("");
+        let mut is_match = title.to_lowercase().contains(&needle);
+        if !is_match {
+            let path = resolve_chat_file_path(chat_id, &index)
+                .or_else(|| resolve_chat_file_path_for_entry(item, &chats_dir));
 
-        if let Some(parent) = outpath.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create parent directory during extraction: {}", e))?;
+            if let Some(path) = path {
+                if let Ok(md) = read_chat_markdown(&path) {
+                    if md.to_lowercase().contains(&needle) {
+                        is_match = true;
+                    }
+                }
+            }
         }
 
-        let mut outfile = File::create(&outpath)
-            .map_err(|e| format!("Failed to create extracted file '{}': {}", outpath.display(), e))?;
-        std::io::copy(&mut entry, &mut outfile)
-            .map_err(|e| format!("Failed to extract file '{}': {}", outpath.display(), e))?;
+        if is_match {
+            matches.push(item.clone());
+        }
     }
 
-    Ok(())
+    matches.sort_by(|a, b| {
+        let a_ts = a.get("last_used_at").and_then(|v| v.as_str()).unwrap_or("");
+        let b_ts = b.get("last_used_at").and_then(|v| v.as_str()).unwrap_or("");
+        b_ts.cmp(a_ts)
+    });
+    Ok(matches)
+}
+
+/// Detect backend type from asset name
+fn detect_backend_type(asset_name: &str) -> String {
+    gpu_runtime::backend_from_name(asset_name)
 }
 
 fn find_server_root_dir(root: &Path, server_binary_name: &str) -> Result<PathBuf, String> {
@@ -984,13 +1631,18 @@ pub struct AppState {
     pub child_processes: Arc<Mutex<HashMap<String, Arc<Mutex<ProcessHandle>>>>>, // Simplified process tracking
     pub session_state: Arc<Mutex<SessionState>>,
     pub download_manager: Arc<Mutex<DownloadManager>>,
-    pub tracker_manager: Arc<Mutex<Option<TrackerManager>>>,
+    pub tracker_manager: Arc<Mutex<Option<TrackerDb>>>,
     pub openai_proxy: Arc<Mutex<Option<openai_proxy::ProxyServer>>>,
     pub discovery_service: Arc<Mutex<Option<DiscoveryService>>>,
     pub python_processes: Arc<Mutex<Vec<u32>>>, // Track Python process PIDs spawned by this app
     pub active_models: Arc<Mutex<HashMap<String, ActiveModel>>>, // Track models launched remotely
     pub peer_model_cache: Option<Arc<PeerModelCache>>, // Persistent cache for peer models
     pub fake_discovery_model_enabled: Arc<Mutex<bool>>,
+    pub tunnels: Arc<Mutex<HashMap<String, tunnels::TunnelInfo>>>, // Tunnels exposing the network server
+    pub launch_queue: Arc<Mutex<launch_queue::LaunchQueue>>, // Launches waiting for VRAM
+    pub suspended_processes: Arc<Mutex<HashMap<String, suspend::SuspendedProcess>>>, // Paused or unloaded models
+    pub parameter_controllers: Arc<Mutex<HashMap<String, parameter_controller::ParameterController>>>, // Keyed by process id
+    pub restarting_ports: Arc<Mutex<HashSet<u16>>>, // Servers being restarted; the proxy holds their requests
 }
 
 // Implement Clone manually to avoid derive issues with Child
@@ -1010,10 +1662,21 @@ impl Clone for AppState {
             active_models: self.active_models.clone(),
             peer_model_cache: self.peer_model_cache.clone(),
             fake_discovery_model_enabled: self.fake_discovery_model_enabled.clone(),
+            tunnels: self.tunnels.clone(),
+            launch_queue: self.launch_queue.clone(),
+            suspended_processes: self.suspended_processes.clone(),
+            parameter_controllers: self.parameter_controllers.clone(),
+            restarting_ports: self.restarting_ports.clone(),
         }
     }
 }
 
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AppState {
     pub fn new() -> Self {
         Self {
@@ -1030,6 +1693,11 @@ impl AppState {
             active_models: Arc::new(Mutex::new(HashMap::new())),
             peer_model_cache: None,
             fake_discovery_model_enabled: Arc::new(Mutex::new(false)),
+            tunnels: Arc::new(Mutex::new(HashMap::new())),
+            launch_queue: Arc::new(Mutex::new(launch_queue::LaunchQueue::default())),
+            suspended_processes: Arc::new(Mutex::new(HashMap::new())),
+            parameter_controllers: Arc::new(Mutex::new(HashMap::new())),
+            restarting_ports: Arc::new(Mutex::new(HashSet::new())),
         }
     }
     
@@ -1131,7 +1799,7 @@ impl AppState {
             #[cfg(not(windows))]
             {
                 // On Unix systems, use kill -9
-                for (process_id, handle_arc) in child_processes.iter() {
+                for (_process_id, handle_arc) in child_processes.iter() {
                     if let Ok(handle_guard) = handle_arc.try_lock() {
                         if let Some(pid) = handle_guard.get_child_id() {
                             let _ = std::process::Command::new("kill")
@@ -1259,6 +1927,11 @@ impl AppState {
         
         // Finally, kill tracked Python processes only (not all Python on system)
         self.kill_tracked_python_processes().await;
+
+        // Close the router port rather than leave it open until the lease runs out
+        if let Err(e) = port_mapping::remove_active_mapping().await {
+            eprintln!("[PortMapping] Failed to remove port mapping: {}", e);
+        }
         
         println!("Comprehensive cleanup completed");
     }
@@ -1291,9 +1964,88 @@ impl Drop for AppState {
 
 // Tauri commands
 #[tauri::command]
-async fn get_config(state: tauri::State<'_, AppState>) -> Result<GlobalConfig, String> {
+async fn get_config(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let has_github_token = github_token::load(&state).await.is_some();
+    let config = state.config.lock().await;
+    let mut value = serde_json::to_value(&*config).map_err(|e| format!("Failed to serialize config: {}", e))?;
+    value["has_github_token"] = serde_json::Value::Bool(has_github_token);
+    Ok(value)
+}
+
+/// Last startup self-test report, or a fresh one with `refresh`
+#[tauri::command]
+async fn get_app_health(refresh: Option<bool>, state: tauri::State<'_, AppState>) -> Result<app_health::AppHealth, String> {
+    if refresh.unwrap_or(false) {
+        return Ok(app_health::run(&state).await);
+    }
+    Ok(app_health::current())
+}
+
+/// Current message locale and every locale with a catalog
+#[tauri::command]
+async fn get_locales(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
     let config = state.config.lock().await;
-    Ok(config.clone())
+    Ok(serde_json::json!({
+        "locale": config.locale,
+        "available": i18n::available_locales(),
+    }))
+}
+
+#[tauri::command]
+async fn set_locale(locale: String, state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let locale = locale.trim().to_string();
+    if !i18n::is_known(&locale) {
+        return Err(i18n::t("locale.unknown", &[("locale", &locale)]));
+    }
+    state.config.lock().await.locale = locale.clone();
+    i18n::set_locale(&locale);
+    save_settings(&state)
+        .await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))?;
+    println!("[i18n] Locale set to {}", locale);
+    Ok(serde_json::json!({ "success": true, "locale": locale }))
+}
+
+/// Messages of `locale` (the configured one by default) keyed by code
+#[tauri::command]
+async fn get_message_catalog(locale: Option<String>, state: tauri::State<'_, AppState>) -> Result<HashMap<String, String>, String> {
+    let locale = match locale {
+        Some(locale) => locale,
+        None => state.config.lock().await.locale.clone(),
+    };
+    Ok(i18n::catalog(&locale))
+}
+
+/// Mark a configured models directory read-only (scanned and launched, never written to) or writable
+#[tauri::command]
+async fn set_models_directory_read_only(
+    directory: String,
+    read_only: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let directory = directory.trim().to_string();
+    let read_only_dirs = {
+        let mut config = state.config.lock().await;
+        let configured = std::iter::once(&config.models_directory)
+            .chain(config.additional_models_directories.iter())
+            .any(|dir| dir.trim() == directory);
+        if !configured {
+            return Err(format!("{} is not a configured models directory", directory));
+        }
+        config.read_only_models_directories.retain(|dir| dir.trim() != directory);
+        if read_only {
+            config.read_only_models_directories.push(directory);
+        }
+        config.read_only_models_directories.clone()
+    };
+    save_settings(&state).await.map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))?;
+    Ok(read_only_dirs)
+}
+
+/// Keys, types, defaults and ranges of the global and per-model settings
+#[tauri::command]
+async fn describe_settings_schema() -> Result<serde_json::Value, String> {
+    settings_schema::settings_schema()
 }
 
 #[tauri::command]
@@ -1314,7 +2066,7 @@ async fn save_config(
         existing_active_path, existing_active_version, existing_proxy_enabled, existing_proxy_port,
         existing_network_host, existing_network_port, existing_mcp_servers,
         existing_discovery_enabled, existing_discovery_port, existing_discovery_interval,
-        existing_discovery_name, existing_discovery_id
+        existing_discovery_name, existing_discovery_id, existing_config
     ) = {
         let cfg = state.config.lock().await;
         (
@@ -1330,6 +2082,7 @@ async fn save_config(
             cfg.discovery_broadcast_interval,
             cfg.discovery_instance_name.clone(),
             cfg.discovery_instance_id.clone(),
+            cfg.clone(),
         )
     };
     
@@ -1359,6 +2112,8 @@ async fn save_config(
         discovery_broadcast_interval: existing_discovery_interval,
         discovery_instance_name: existing_discovery_name,
         discovery_instance_id: existing_discovery_id,
+        // Settings this dialog does not edit
+        ..existing_config
     };
     
     // Update global config
@@ -1372,21 +2127,14 @@ async fn save_config(
         println!("Failed to save settings: {}", e);
         return Ok(serde_json::json!({
             "success": false,
-            "error": format!("Failed to save settings: {}", e)
+            "error": i18n::t("settings.save_failed", &[("error", &e.to_string())])
         }));
     }
     
-    // Build list of all directories to scan and cleanup
+    // Build list of all directories to scan
     let mut all_directories = vec![models_directory.clone()];
     all_directories.extend(additional_dirs);
     
-    // Cleanup leftover download files in all models directories
-    for dir in &all_directories {
-        if let Err(e) = huggingface::cleanup_leftover_downloads(dir).await {
-            eprintln!("Warning: Failed to cleanup leftover downloads in {}: {}", dir, e);
-        }
-    }
-    
     // Scan models from all directories
     match scan_models(&all_directories).await {
         Ok(models) => {
@@ -1408,16 +2156,28 @@ async fn save_config(
 
 #[tauri::command]
 async fn scan_models_command(
+    task_id: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
-    let config = state.config.lock().await;
-    
-    // Build list of all directories to scan
-    let mut all_directories = vec![config.models_directory.clone()];
-    all_directories.extend(config.additional_models_directories.clone());
+    let all_directories = {
+        let config = state.config.lock().await;
+        // Build list of all directories to scan
+        let mut all_directories = vec![config.models_directory.clone()];
+        all_directories.extend(config.additional_models_directories.clone());
+        all_directories
+    };
     
-    let models = scan_models(&all_directories).await
+    let task = task_manager::register("scan", task_id, "Scan models");
+    let models = scan_models_until_cancelled(&all_directories, &task.token()).await
         .map_err(|e| format!("Failed to scan models: {}", e))?;
+
+    // Settings follow files renamed or moved outside the app
+    let identity_state = state.inner().clone();
+    tokio::spawn(async move {
+        if let Err(e) = model_identity::reattach(&identity_state).await {
+            eprintln!("[Models] Identity reattach failed: {}", e);
+        }
+    });
     
     Ok(serde_json::json!({
         "success": true,
@@ -1467,7 +2227,7 @@ async fn update_model_settings(
     }
     
     save_settings(&state).await
-        .map_err(|e| format!("Failed to save settings: {}", e))
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
 }
 
 #[tauri::command]
@@ -1504,7 +2264,7 @@ async fn update_model_presets(
     
     // Save to disk (this will acquire its own locks)
     if let Err(e) = save_settings(&state).await {
-        return Err(format!("Failed to save settings: {}", e));
+        return Err(i18n::t("settings.save_failed", &[("error", &e.to_string())]));
     }
     
     Ok(serde_json::json!({
@@ -1556,7 +2316,7 @@ async fn save_model_preset(
     
     println!("Calling save_settings...");
     let result = save_settings(&state).await
-        .map_err(|e| format!("Failed to save settings: {}", e));
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]));
     
     if result.is_ok() {
         println!("Settings saved successfully");
@@ -1590,7 +2350,7 @@ async fn delete_model_preset(
     }
     
     save_settings(&state).await
-        .map_err(|e| format!("Failed to save settings: {}", e))
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
 }
 
 #[tauri::command]
@@ -1615,45 +2375,34 @@ async fn set_default_preset(
     }
     
     save_settings(&state).await
-        .map_err(|e| format!("Failed to save settings: {}", e))
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
 }
 
-#[tauri::command]
-async fn launch_model_with_preset(
+/// Launch with a preset's args and env vars (or the default preset / current args),
+/// restoring the model's own settings afterwards
+async fn launch_with_preset(
     model_path: String,
     preset_id: Option<String>,
-    state: tauri::State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    // Get the preset arguments and env vars
-    let (custom_args, env_vars) = {
+    state: &AppState,
+) -> Result<LaunchResult, String> {
+    // Get the preset arguments and env vars, plus the preset actually applied
+    let (custom_args, env_vars, applied_preset_id) = {
         let model_configs = state.model_configs.lock().await;
         let config = model_configs.get(&model_path)
             .cloned()
             .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
         
-        if let Some(pid) = preset_id {
-            // Find the preset
-            config.presets.iter()
-                .find(|p| p.id == pid)
-                .map(|p| {
-                    let mut envs = config.env_vars.clone();
-                    envs.extend(p.env_vars.clone());
-                    (p.custom_args.clone(), envs)
-                })
-                .unwrap_or_else(|| (config.custom_args.clone(), config.env_vars.clone()))
-        } else if let Some(default_id) = config.default_preset_id {
-            // Use default preset
-            config.presets.iter()
-                .find(|p| p.id == default_id)
-                .map(|p| {
-                    let mut envs = config.env_vars.clone();
-                    envs.extend(p.env_vars.clone());
-                    (p.custom_args.clone(), envs)
-                })
-                .unwrap_or_else(|| (config.custom_args.clone(), config.env_vars.clone()))
-        } else {
-            // Use current custom_args
-            (config.custom_args.clone(), config.env_vars.clone())
+        // An explicit preset wins, then the default preset, then the current custom_args
+        let preset = preset_id
+            .or(config.default_preset_id.clone())
+            .and_then(|pid| config.presets.iter().find(|p| p.id == pid));
+        match preset {
+            Some(p) => {
+                let mut envs = config.env_vars.clone();
+                envs.extend(p.env_vars.clone());
+                (p.custom_args.clone(), envs, Some(p.id.clone()))
+            }
+            None => (config.custom_args.clone(), config.env_vars.clone(), None),
         }
     };
     
@@ -1679,10 +2428,10 @@ async fn launch_model_with_preset(
     } // Release the lock here
     
     // Launch the model (this may acquire locks internally)
-    let result = launch_model_server(model_path.clone(), &state, None).await
-        .map_err(|e| format!("Failed to launch model: {}", e))?;
+    let result = launch_model_server(model_path.clone(), state, None).await
+        .map_err(|e| i18n::t("launch.failed", &[("error", &e.to_string())]));
 
-    // Restore original args
+    // Restore original args, also when the launch failed
     {
         let mut model_configs = state.model_configs.lock().await;
         let mut config = model_configs.get(&model_path)
@@ -1692,42 +2441,329 @@ async fn launch_model_with_preset(
         config.env_vars = original_env_vars;
         model_configs.insert(model_path, config);
     }
+
+    // Remember the preset so same-model instances can be told apart
+    if let Ok(launched) = &result {
+        let mut processes = state.running_processes.lock().await;
+        if let Some(process) = processes.get_mut(&launched.process_id) {
+            process.preset_id = applied_preset_id;
+        }
+    }
     
-    Ok(serde_json::json!({
-        "success": true,
-        "process_id": result.process_id,
-        "model_name": result.model_name,
-        "server_host": result.server_host,
-        "server_port": result.server_port
-    }))
+    result
 }
 
-fn append_half_context_arg(custom_args: &str) -> String {
-    let trimmed_args = custom_args.trim();
-    if trimmed_args.is_empty() {
-        "--context-shift".to_string()
-    } else if trimmed_args
-        .split_whitespace()
-        .any(|token| token == "--context-shift")
-    {
-        trimmed_args.to_string()
-    } else {
-        format!("{} {}", trimmed_args, "--context-shift")
+/// Resolve a launch's exclusive flag (falling back to the model's default) and, when set,
+/// stop every other running model first. Returns the ids of the stopped processes.
+async fn prepare_exclusive_launch(
+    model_path: &str,
+    exclusive: Option<bool>,
+    state: &AppState,
+) -> Vec<String> {
+    let exclusive = match exclusive {
+        Some(exclusive) => exclusive,
+        None => {
+            let model_configs = state.model_configs.lock().await;
+            model_configs.get(model_path).map(|c| c.exclusive_launch).unwrap_or(false)
+        }
+    };
+    if !exclusive {
+        return Vec::new();
+    }
+    stop_other_models(model_path, state).await
+}
+
+#[tauri::command]
+async fn launch_model_with_preset(
+    model_path: String,
+    preset_id: Option<String>,
+    exclusive: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let stopped_processes = prepare_exclusive_launch(&model_path, exclusive, &state).await;
+    let result = launch_with_preset(model_path, preset_id, &state).await?;
+    let instance = instance_details(&result.process_id, &state).await;
+    
+    Ok(serde_json::json!({
+        "success": true,
+        "process_id": result.process_id,
+        "model_name": result.model_name,
+        "server_host": result.server_host,
+        "server_port": result.server_port,
+        "stopped_processes": stopped_processes,
+        "instance": instance
+    }))
+}
+
+/// Index of the batch item `reference` (a model path or file name) points at
+fn batch_item_index(items: &[BatchLaunchItem], reference: &str) -> Option<usize> {
+    let reference = reference.trim();
+    items.iter().position(|item| item.model_path == reference).or_else(|| {
+        items.iter().position(|item| {
+            Path::new(&item.model_path)
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().eq_ignore_ascii_case(reference))
+        })
+    })
+}
+
+/// Launch order with dependencies first, otherwise keeping the given order
+fn batch_launch_order(items: &[BatchLaunchItem]) -> Result<Vec<usize>, String> {
+    let mut dependencies = Vec::with_capacity(items.len());
+    for item in items {
+        let indexes = item
+            .depends_on
+            .iter()
+            .map(|reference| {
+                batch_item_index(items, reference)
+                    .ok_or_else(|| format!("{} depends on {}, which is not part of the batch", item.model_path, reference))
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+        dependencies.push(indexes);
+    }
+
+    let mut order: Vec<usize> = Vec::with_capacity(items.len());
+    while order.len() < items.len() {
+        let next = (0..items.len())
+            .find(|i| !order.contains(i) && dependencies[*i].iter().all(|d| order.contains(d)))
+            .ok_or_else(|| "Batch dependencies form a cycle".to_string())?;
+        order.push(next);
+    }
+    Ok(order)
+}
+
+/// Launch several models one after another (e.g. draft, main and embedding models),
+/// dependencies first, each on a free port. A model waits until the servers it depends
+/// on answer `/health` and is skipped when one of them did not come up.
+#[tauri::command]
+async fn launch_model_batch(
+    items: Vec<BatchLaunchItem>,
+    task_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    if items.is_empty() {
+        return Err("At least one model is required".to_string());
+    }
+    let order = batch_launch_order(&items)?;
+    // Cancelling skips the models not launched yet; started ones keep running
+    let task = task_manager::register("batch_launch", task_id, format!("Launch {} model(s)", items.len()));
+
+    let mut entries: Vec<Option<BatchLaunchEntry>> = vec![None; items.len()];
+    let mut base_urls: HashMap<usize, String> = HashMap::new();
+    let mut ready: HashSet<usize> = HashSet::new();
+    for (step, index) in order.into_iter().enumerate() {
+        let item = &items[index];
+        task.report_steps(step, items.len(), item.model_path.clone());
+        let entry = |status: &str, message: String, launched: Option<&LaunchResult>| BatchLaunchEntry {
+            model_path: item.model_path.clone(),
+            preset_id: item.preset_id.clone(),
+            status: status.to_string(),
+            message,
+            process_id: launched.map(|r| r.process_id.clone()),
+            server_port: launched.map(|r| r.server_port),
+        };
+
+        let mut blocked = task.check().err();
+        for dependency in item.depends_on.iter().filter_map(|reference| batch_item_index(&items, reference)) {
+            if blocked.is_some() {
+                break;
+            }
+            let Some(base_url) = base_urls.get(&dependency) else {
+                blocked = Some(format!("{} did not start", items[dependency].model_path));
+                break;
+            };
+            if ready.contains(&dependency) {
+                continue;
+            }
+            match task.run(suspend::wait_until_healthy(base_url)).await.and_then(|healthy| healthy) {
+                Ok(()) => {
+                    ready.insert(dependency);
+                }
+                Err(e) => {
+                    blocked = Some(format!("{} is not ready: {}", items[dependency].model_path, e));
+                    break;
+                }
+            }
+        }
+
+        entries[index] = Some(match blocked {
+            Some(reason) => entry("skipped", reason, None),
+            None => match launch_with_preset(item.model_path.clone(), item.preset_id.clone(), &state).await {
+                Ok(result) => {
+                    base_urls.insert(index, suspend::local_base_url(&result.server_host, result.server_port));
+                    entry("launched", result.message.clone(), Some(&result))
+                }
+                Err(e) => entry("failed", e, None),
+            },
+        });
+    }
+
+    let results: Vec<BatchLaunchEntry> = entries.into_iter().flatten().collect();
+    let launched = results.iter().filter(|e| e.status == "launched").count();
+    println!("[Arandu] Batch launch: {} of {} model(s) started", launched, results.len());
+    Ok(serde_json::json!({
+        "success": launched == results.len(),
+        "launched": launched,
+        "failed": results.len() - launched,
+        "results": results
+    }))
+}
+
+/// Run one prompt on several models in turn with a fixed seed and parameters, for
+/// comparing outputs and timing. Models that were not running are stopped afterwards.
+#[tauri::command]
+async fn test_prompt_across_models(
+    prompt: String,
+    model_paths: Vec<String>,
+    params: Option<parameter_controller::GenerationParams>,
+    seed: Option<u64>,
+    task_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<prompt_compare::PromptComparison, String> {
+    if prompt.trim().is_empty() {
+        return Err("Prompt is required".to_string());
+    }
+    if model_paths.is_empty() {
+        return Err("At least one model is required".to_string());
+    }
+    let task = task_manager::register("prompt_test", task_id, format!("Test prompt on {} model(s)", model_paths.len()));
+    Ok(prompt_compare::compare(&state, prompt, model_paths, params, seed, &task).await)
+}
+
+/// Launch now if the model fits in free VRAM, otherwise queue it until a running model
+/// stops. Progress is reported through `launch-queue-updated` events.
+#[tauri::command]
+async fn queue_model_launch(
+    model_path: String,
+    preset_id: Option<String>,
+    exclusive: Option<bool>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<launch_queue::QueuedLaunch, String> {
+    // Stopping the other models first lets an exclusive launch fit without waiting
+    prepare_exclusive_launch(&model_path, exclusive, &state).await;
+    launch_queue::queue_model_launch(model_path, preset_id, &state, app_handle).await
+}
+
+#[tauri::command]
+async fn get_launch_queue(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    Ok(launch_queue::snapshot(&state).await)
+}
+
+#[tauri::command]
+async fn cancel_queued_launch(
+    queue_id: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    launch_queue::cancel_queued_launch(&queue_id, &state, &app_handle).await
+}
+
+/// One running model process as an instance: its preset, instance number and label
+fn instance_json(process: &ProcessInfo, config: Option<&ModelConfig>) -> serde_json::Value {
+    let preset_name = process
+        .preset_id
+        .as_deref()
+        .and_then(|id| config?.presets.iter().find(|p| p.id == id))
+        .map(|p| p.name.clone());
+    serde_json::json!({
+        "process_id": process.id,
+        "model_path": process.model_path,
+        "model_name": process.model_name,
+        "preset_id": process.preset_id,
+        "preset_name": preset_name,
+        "instance": process.instance,
+        "label": instance_label(process.instance, preset_name.as_deref()),
+        "host": process.host,
+        "port": process.port,
+        "status": process.status,
+        "created_at": process.created_at,
+    })
+}
+
+async fn instance_details(process_id: &str, state: &AppState) -> serde_json::Value {
+    let Some(process) = state.running_processes.lock().await.get(process_id).cloned() else {
+        return serde_json::Value::Null;
+    };
+    let model_configs = state.model_configs.lock().await;
+    instance_json(&process, model_configs.get(&process.model_path))
+}
+
+/// Running model instances, optionally only those of one model, ordered by model then instance
+#[tauri::command]
+async fn list_model_instances(
+    model_path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let mut processes: Vec<ProcessInfo> = {
+        let running = state.running_processes.lock().await;
+        running
+            .values()
+            .filter(|p| !p.model_path.starts_with("tunnel:"))
+            .filter(|p| model_path.as_ref().is_none_or(|m| &p.model_path == m))
+            .cloned()
+            .collect()
+    };
+    processes.sort_by(|a, b| a.model_path.cmp(&b.model_path).then(a.instance.cmp(&b.instance)));
+
+    let model_configs = state.model_configs.lock().await;
+    Ok(processes
+        .iter()
+        .map(|p| instance_json(p, model_configs.get(&p.model_path)))
+        .collect())
+}
+
+/// Stop the instances of a model: all of them, those launched with `preset_id`, or the
+/// single `instance` number. Returns the stopped process ids.
+#[tauri::command]
+async fn kill_model_instances(
+    model_path: String,
+    preset_id: Option<String>,
+    instance: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let targets: Vec<String> = {
+        let running = state.running_processes.lock().await;
+        running
+            .values()
+            .filter(|p| p.model_path == model_path)
+            .filter(|p| preset_id.is_none() || p.preset_id == preset_id)
+            .filter(|p| instance.is_none_or(|n| p.instance == n))
+            .map(|p| p.id.clone())
+            .collect()
+    };
+    if targets.is_empty() {
+        return Err("No matching model instance is running".to_string());
     }
+
+    let mut stopped = Vec::new();
+    for process_id in targets {
+        terminate_process(process_id.clone(), &state).await
+            .map_err(|e| format!("Failed to kill process: {}", e))?;
+        stopped.push(process_id);
+    }
+    Ok(stopped)
 }
 
+/// Launch with context shifting (and the chat's cache-reuse setting when `chat_id` is
+/// given) so a full context drops old tokens instead of failing requests
 #[tauri::command]
 async fn launch_model_with_half_context(
     model_path: String,
+    chat_id: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
+    let settings = match chat_id {
+        Some(chat_id) => context_settings::for_chat(&chat_id)?,
+        None => ContextSettings::default(),
+    };
     let custom_args_with_half_context = {
         let model_configs = state.model_configs.lock().await;
         let config = model_configs.get(&model_path)
             .cloned()
             .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
 
-        append_half_context_arg(&config.custom_args)
+        context_settings::launch_args(&config.custom_args, &ContextSettings { context_shift: true, ..settings })
     };
 
     let (original_args, original_env_vars) = {
@@ -1750,7 +2786,7 @@ async fn launch_model_with_half_context(
 
     let result = launch_model_server(model_path.clone(), &state, None)
         .await
-        .map_err(|e| format!("Failed to launch model with half context: {}", e))?;
+        .map_err(|e| i18n::t("launch.failed_half_context", &[("error", &e.to_string())]))?;
 
     {
         let mut model_configs = state.model_configs.lock().await;
@@ -1774,17 +2810,22 @@ async fn launch_model_with_half_context(
 #[tauri::command]
 async fn launch_model(
     model_path: String,
+    exclusive: Option<bool>,
     state: tauri::State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
+    let stopped_processes = prepare_exclusive_launch(&model_path, exclusive, &state).await;
     let result = launch_model_server(model_path, &state, None).await
-        .map_err(|e| format!("Failed to launch model: {}", e))?;
+        .map_err(|e| i18n::t("launch.failed", &[("error", &e.to_string())]))?;
+    let instance = instance_details(&result.process_id, &state).await;
     
     Ok(serde_json::json!({
         "success": true,
         "process_id": result.process_id,
         "model_name": result.model_name,
         "server_host": result.server_host,
-        "server_port": result.server_port
+        "server_port": result.server_port,
+        "stopped_processes": stopped_processes,
+        "instance": instance
     }))
 }
 
@@ -1794,7 +2835,7 @@ async fn launch_model_external(
     state: tauri::State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
     let result = launch_model_external_impl(model_path, &state).await
-        .map_err(|e| format!("Failed to launch model externally: {}", e))?;
+        .map_err(|e| i18n::t("launch.failed_external", &[("error", &e.to_string())]))?;
     
     Ok(serde_json::json!({
         "success": true,
@@ -1864,7 +2905,7 @@ async fn launch_model_with_preset_external(
     
     // Launch the model externally (this may acquire locks internally)
     let result = launch_model_external_impl(model_path.clone(), &state).await
-        .map_err(|e| format!("Failed to launch model externally: {}", e))?;
+        .map_err(|e| i18n::t("launch.failed_external", &[("error", &e.to_string())]))?;
     
     // Restore original args
     {
@@ -1925,6 +2966,13 @@ async fn delete_model_file(
             "error": "Only .gguf files can be deleted"
         }));
     }
+
+    if let Err(e) = model_files::ensure_writable(&state, &model_file, "delete").await {
+        return Ok(serde_json::json!({
+            "success": false,
+            "error": e
+        }));
+    }
     
     // Delete the file
     match fs::remove_file(&model_path) {
@@ -1939,7 +2987,7 @@ async fn delete_model_file(
             if let Err(e) = save_settings(&state).await {
                 return Ok(serde_json::json!({
                     "success": false,
-                    "error": format!("Failed to save settings: {}", e)
+                    "error": i18n::t("settings.save_failed", &[("error", &e.to_string())])
                 }));
             }
             
@@ -1947,8 +2995,7 @@ async fn delete_model_file(
             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
             
             // Emit file deletion event to frontend
-            use tauri::Emitter;
-            let _ = app_handle.emit("file-deleted", ());
+            events::emit(&app_handle, &events::FileDeleted);
             
             Ok(serde_json::json!({
                 "success": true
@@ -1972,12 +3019,286 @@ async fn kill_process(
         .map_err(|e| format!("Failed to kill process: {}", e))
 }
 
+/// Pause a model to free the GPU for a while. `unload` also stops the server after saving
+/// its KV cache so VRAM is released; otherwise the process is only frozen.
+#[tauri::command]
+async fn suspend_process(
+    process_id: String,
+    unload: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<suspend::SuspendedProcess, String> {
+    suspend::suspend_process(&process_id, unload.unwrap_or(false), &state).await
+}
+
+#[tauri::command]
+async fn resume_process(
+    process_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let resumed_id = suspend::resume_process(&process_id, &state).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "process_id": resumed_id,
+        "relaunched": resumed_id != process_id,
+    }))
+}
+
+#[tauri::command]
+async fn get_suspended_processes(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<suspend::SuspendedProcess>, String> {
+    Ok(suspend::list_suspended(&state).await)
+}
+
+/// Every tracked process with status, uptime, speed, memory and a log tail, in one call
+#[tauri::command]
+async fn get_all_processes_summary(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<process_summary::ProcessSummary>, String> {
+    Ok(process_summary::all_processes_summary(&state).await)
+}
+
+/// Cancel a running download, scan, benchmark, batch launch or MCP tool discovery by
+/// its task id (a download's id, or the `task_id` passed to the command)
+#[tauri::command]
+async fn cancel_task(task_id: String) -> Result<(), String> {
+    task_manager::cancel(&task_id).map(|_| ())
+}
+
+/// Running downloads, scans, benchmarks, batch launches and MCP tool discoveries
+#[tauri::command]
+async fn list_background_tasks() -> Result<Vec<task_manager::BackgroundTask>, String> {
+    Ok(task_manager::list())
+}
+
+/// Servers from a previous run that are still alive, see `process_registry`
+#[tauri::command]
+async fn list_orphan_processes() -> Result<Vec<process_registry::OrphanProcess>, String> {
+    Ok(process_registry::orphans())
+}
+
+#[tauri::command]
+async fn adopt_orphan_process(
+    process_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<ProcessInfo, String> {
+    process_registry::adopt(&process_id, &state).await
+}
+
+#[tauri::command]
+async fn kill_orphan_process(process_id: String) -> Result<(), String> {
+    process_registry::kill(&process_id).await
+}
+
+async fn with_parameter_controller<T>(
+    process_id: &str,
+    state: &AppState,
+    f: impl FnOnce(&mut parameter_controller::ParameterController) -> T,
+) -> Result<T, String> {
+    let mut controllers = state.parameter_controllers.lock().await;
+    controllers
+        .get_mut(process_id)
+        .map(f)
+        .ok_or_else(|| format!("No parameter controller for process {}", process_id))
+}
+
+/// Token count of `text` with the tokenizer of a running model, given by process id,
+/// model path or model name
+#[tauri::command]
+async fn count_tokens(
+    target: String,
+    text: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let server = model_routing::running_server(&state, &target).await?;
+    let tokens = llama_client::LlamaClient::new(server.base_url).tokenize(&text, false).await?;
+    Ok(serde_json::json!({
+        "tokens": tokens.len(),
+        "model_path": server.model_path,
+        "model_name": server.model_name,
+    }))
+}
+
+#[tauri::command]
+async fn get_generation_params(
+    process_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<parameter_controller::GenerationParams, String> {
+    with_parameter_controller(&process_id, &state, |c| c.generation.clone()).await
+}
+
+/// Defaults the proxy fills into requests for this process; takes effect on the next request
+#[tauri::command]
+async fn set_generation_params(
+    process_id: String,
+    params: parameter_controller::GenerationParams,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    with_parameter_controller(&process_id, &state, |c| c.set_generation_params(params)).await
+}
+
+/// Queue a new context length. It is applied the next time the model launches.
+#[tauri::command]
+async fn set_context_length(
+    process_id: String,
+    context_length: u32,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<parameter_controller::PendingChange>, String> {
+    if context_length == 0 {
+        return Err("Context length must be greater than zero".to_string());
+    }
+    with_parameter_controller(&process_id, &state, |c| {
+        c.set_context_length(context_length);
+        c.pending_changes()
+    })
+    .await
+}
+
+#[tauri::command]
+async fn set_gpu_layers(
+    process_id: String,
+    gpu_layers: i32,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<parameter_controller::PendingChange>, String> {
+    with_parameter_controller(&process_id, &state, |c| {
+        c.set_gpu_layers(gpu_layers);
+        c.pending_changes()
+    })
+    .await
+}
+
+#[tauri::command]
+async fn get_pending_changes(
+    process_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<parameter_controller::PendingChange>, String> {
+    with_parameter_controller(&process_id, &state, |c| c.pending_changes()).await
+}
+
+/// One-click apply for pending context / GPU-layer changes: the server restarts on the
+/// same port while API clients of the proxy wait instead of getting errors
+#[tauri::command]
+async fn restart_model_with_pending_changes(
+    process_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<parameter_controller::RestartResult, String> {
+    parameter_controller::restart_with_pending_changes(&process_id, &state).await
+}
+
+#[tauri::command]
+async fn get_sampling_profiles(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<SamplingProfile>, String> {
+    Ok(state.config.lock().await.sampling_profiles.clone())
+}
+
+/// Create or update a sampling profile (matched by id)
+#[tauri::command]
+async fn save_sampling_profile(
+    profile: SamplingProfile,
+    state: tauri::State<'_, AppState>,
+) -> Result<SamplingProfile, String> {
+    let profile = sampling_profiles::normalize(profile)?;
+    {
+        let mut config = state.config.lock().await;
+        if config
+            .sampling_profiles
+            .iter()
+            .any(|p| p.id != profile.id && p.name.eq_ignore_ascii_case(&profile.name))
+        {
+            return Err(format!("A sampling profile named '{}' already exists", profile.name));
+        }
+        match config.sampling_profiles.iter().position(|p| p.id == profile.id) {
+            Some(index) => config.sampling_profiles[index] = profile.clone(),
+            None => config.sampling_profiles.push(profile.clone()),
+        }
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save sampling profiles: {}", e))?;
+    Ok(profile)
+}
+
+#[tauri::command]
+async fn delete_sampling_profile(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().await;
+        let original_len = config.sampling_profiles.len();
+        config.sampling_profiles.retain(|p| p.id != id);
+        if config.sampling_profiles.len() == original_len {
+            return Err("Sampling profile not found".to_string());
+        }
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save sampling profiles: {}", e))
+}
+
+#[tauri::command]
+async fn get_model_schedules(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ModelSchedule>, String> {
+    Ok(state.config.lock().await.model_schedules.clone())
+}
+
+/// Create or update a model schedule (matched by id)
+#[tauri::command]
+async fn save_model_schedule(
+    schedule: ModelSchedule,
+    state: tauri::State<'_, AppState>,
+) -> Result<ModelSchedule, String> {
+    let schedule = scheduler::normalize(schedule)?;
+    {
+        let mut config = state.config.lock().await;
+        match config.model_schedules.iter().position(|s| s.id == schedule.id) {
+            Some(index) => config.model_schedules[index] = schedule.clone(),
+            None => config.model_schedules.push(schedule.clone()),
+        }
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save model schedules: {}", e))?;
+    Ok(schedule)
+}
+
+#[tauri::command]
+async fn delete_model_schedule(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().await;
+        let original_len = config.model_schedules.len();
+        config.model_schedules.retain(|s| s.id != id);
+        if config.model_schedules.len() == original_len {
+            return Err("Model schedule not found".to_string());
+        }
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save model schedules: {}", e))
+}
+
+/// Scheduled starts and stops of the next week, soonest first
+#[tauri::command]
+async fn get_upcoming_scheduled_runs(
+    limit: Option<usize>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<scheduler::ScheduledRun>, String> {
+    let schedules = state.config.lock().await.model_schedules.clone();
+    Ok(scheduler::upcoming(&schedules, chrono::Local::now(), limit.unwrap_or(20)))
+}
+
+/// `min_severity` is one of debug, info, progress, warning or error
 #[tauri::command]
 async fn get_process_output(
     process_id: String,
+    min_severity: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<ProcessOutput, String> {
-    get_process_logs(process_id, &state).await
+    let min_severity = match min_severity.as_deref().filter(|s| !s.trim().is_empty()) {
+        Some(value) => Some(
+            log_classifier::LogSeverity::parse(value)
+                .ok_or_else(|| format!("Unknown severity: {}", value))?,
+        ),
+        None => None,
+    };
+    get_process_logs(process_id, min_severity, &state).await
         .map_err(|e| format!("Failed to get process output: {}", e))
 }
 
@@ -2048,7 +3369,7 @@ async fn pick_llamacpp_zip_file(
     use tauri_plugin_dialog::DialogExt;
 
     let dialog = app.dialog();
-    let mut file_dialog = dialog.file().add_filter("ZIP Archives", &["zip"]);
+    let mut file_dialog = dialog.file().add_filter("Archives", &["zip", "gz", "tgz", "7z"]);
 
     if let Some(initial) = initial_dir {
         file_dialog = file_dialog.set_directory(initial);
@@ -2111,19 +3432,14 @@ async fn install_local_llamacpp_zip(
         };
 
         let detected_server_root = if source_path.is_file() {
-            let is_zip = source_path
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| ext.eq_ignore_ascii_case("zip"))
-                .unwrap_or(false);
-            if !is_zip {
-                return Err("Selected file must be a .zip archive".to_string());
+            if archive::ArchiveKind::from_path(&source_path).is_none() {
+                return Err("Selected file must be a .zip, .tar.gz or .7z archive".to_string());
             }
 
-            extract_zip_safely_to_directory(&source_path, &temp_extract_dir)?;
+            archive::extract(&source_path, &temp_extract_dir, &mut |_| {})?;
             find_server_root_dir(&temp_extract_dir, server_binary_name)?
         } else {
-            return Err("Source path must be a .zip file".to_string());
+            return Err("Source path must be an archive file".to_string());
         };
 
         if backend_dir.exists() {
@@ -2142,7 +3458,21 @@ async fn install_local_llamacpp_zip(
             ));
         }
 
-        Ok(LocalLlamaInstallResult {
+        if let Some(key) = llamacpp_manager::version_metadata_key(&versions_root, &backend_dir) {
+            let metadata = llamacpp_manager::InstalledVersionMetadata {
+                tag_name: Some(version_name.clone()),
+                backend_type: Some(backend_type.clone()),
+                asset_name: Some(archive_name.to_string()),
+                source: Some("local_zip".to_string()),
+                installed_at: Some(Utc::now().to_rfc3339()),
+                ..Default::default()
+            };
+            if let Err(e) = llamacpp_manager::update_version_metadata(&versions_root, &key, |entry| *entry = Some(metadata)) {
+                eprintln!("Failed to record llama.cpp version metadata: {}", e);
+            }
+        }
+
+        Ok(LocalLlamaInstallResult {
             version_name,
             backend_type,
             install_path: backend_dir.to_string_lossy().to_string(),
@@ -2188,19 +3518,14 @@ async fn install_local_llamacpp_cuda_dlls_zip(
 
     let install_result = (|| -> Result<LocalLlamaDllInstallResult, String> {
         let dll_files = if source_path.is_file() {
-            let is_zip = source_path
-                .extension()
-                .and_then(|ext| ext.to_str())
-                .map(|ext| ext.eq_ignore_ascii_case("zip"))
-                .unwrap_or(false);
-            if !is_zip {
-                return Err("Selected file must be a .zip archive".to_string());
+            if archive::ArchiveKind::from_path(&source_path).is_none() {
+                return Err("Selected file must be a .zip, .tar.gz or .7z archive".to_string());
             }
 
-            extract_zip_safely_to_directory(&source_path, &temp_extract_dir)?;
+            archive::extract(&source_path, &temp_extract_dir, &mut |_| {})?;
             collect_files_with_extension(&temp_extract_dir, "dll")?
         } else {
-            return Err("Source path must be a .zip file".to_string());
+            return Err("Source path must be an archive file".to_string());
         };
 
         if dll_files.is_empty() {
@@ -2316,11 +3641,12 @@ async fn download_model(
             headers.insert("User-Agent".to_string(), "Arandu-Tauri/1.0".to_string());
             headers
         }),
+        expected_sha256: None,
     };
     
     start_download(config, &state, app_handle)
         .await
-        .map_err(|e| format!("Failed to start download: {}", e))
+        .map_err(|e| i18n::t("download.start_failed", &[("error", &e.to_string())]))
 }
 
 #[tauri::command]
@@ -2329,9 +3655,8 @@ async fn get_download_status(
     state: tauri::State<'_, AppState>,
 ) -> Result<DownloadStatus, String> {
     let download_manager = state.download_manager.lock().await;
-    download_manager.get_status(&download_id)
-        .map(|status| status.clone())
-        .ok_or_else(|| "Download not found".to_string())
+    download_manager.get_status(&download_id).cloned()
+        .ok_or_else(|| i18n::t("download.not_found", &[]))
 }
 
 #[tauri::command]
@@ -2348,7 +3673,7 @@ async fn cancel_download(
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<DownloadStatus>, String> {
     let mut download_manager = state.download_manager.lock().await;
-    download_manager.cancel_download(&download_id).map_err(|e| format!("Failed to cancel download: {}", e))?;
+    download_manager.cancel_download(&download_id).map_err(|e| i18n::t("download.cancel_failed", &[("error", &e)]))?;
     Ok(download_manager.downloads.values().cloned().collect())
 }
 
@@ -2358,7 +3683,7 @@ async fn pause_download(
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<DownloadStatus>, String> {
     let mut download_manager = state.download_manager.lock().await;
-    download_manager.pause_download(&download_id).map_err(|e| format!("Failed to pause download: {}", e))?;
+    download_manager.pause_download(&download_id).map_err(|e| i18n::t("download.pause_failed", &[("error", &e)]))?;
     Ok(download_manager.downloads.values().cloned().collect())
 }
 
@@ -2368,10 +3693,43 @@ async fn resume_download(
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<DownloadStatus>, String> {
     let mut download_manager = state.download_manager.lock().await;
-    download_manager.resume_download(&download_id).map_err(|e| format!("Failed to resume download: {}", e))?;
+    download_manager.resume_download(&download_id).map_err(|e| i18n::t("download.resume_failed", &[("error", &e)]))?;
     Ok(download_manager.downloads.values().cloned().collect())
 }
 
+/// Remove partial downloads the cleanup settings let go; `dry_run` only reports them
+#[tauri::command]
+async fn clean_partial_downloads(
+    dry_run: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<download_cleanup::CleanupReport, String> {
+    download_cleanup::clean(&state, dry_run).await
+}
+
+/// Hash a model file and compare it with the SHA-256 its Hugging Face repo publishes
+#[tauri::command]
+async fn verify_model_file(
+    model_path: String,
+    task_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<model_verify::FileVerification, String> {
+    let name = Path::new(&model_path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let task = task_manager::register("verify", task_id, format!("Verify {}", name));
+    model_verify::verify(&state, &model_path, &task).await
+}
+
+/// Ids of the downloads waiting for a free slot, next first
+#[tauri::command]
+async fn get_download_queue() -> Result<Vec<String>, String> {
+    Ok(download_queue::queued())
+}
+
+/// Move a waiting download to `position` in the queue; 0 starts it next
+#[tauri::command]
+async fn move_queued_download(download_id: String, position: usize) -> Result<Vec<String>, String> {
+    download_queue::move_to(&download_id, position)
+}
+
 #[tauri::command]
 async fn get_all_downloads_and_history(
     state: tauri::State<'_, AppState>,
@@ -2386,6 +3744,7 @@ async fn get_all_downloads_and_history(
 async fn clear_download_history(
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<DownloadStatus>, String> {
+    storage::run(|db| db.downloads().clear()).await?;
     let mut download_manager = state.download_manager.lock().await;
     download_manager.clear_download_history();
     let mut all_downloads = download_manager.downloads.values().cloned().collect::<Vec<_>>();
@@ -2417,6 +3776,8 @@ async fn delete_model(
     if !model_path.to_lowercase().ends_with(".gguf") {
         return Err("Only .gguf files can be deleted".to_string());
     }
+    drop(config);
+    model_files::ensure_writable(&state, &model_file, "delete").await?;
     
     // Delete the file
     fs::remove_file(&model_path).map_err(|e| format!("Failed to delete file: {}", e))?;
@@ -2426,7 +3787,7 @@ async fn delete_model(
     model_configs.remove(&model_path);
     
     // Save settings
-    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    save_settings(&state).await.map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))?;
     
     Ok(())
 }
@@ -2435,6 +3796,7 @@ async fn delete_model(
 
 #[tauri::command]
 async fn initial_scan_models(
+    task_id: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<InitialScanResult, String> {
     use std::time::SystemTime;
@@ -2456,8 +3818,10 @@ async fn initial_scan_models(
         dirs
     };
     
+    let task = task_manager::register("scan", task_id, "Link models to Hugging Face");
+
     // Process each directory
-    for directory in all_directories {
+    'directories: for directory in all_directories {
         if directory.is_empty() || !Path::new(&directory).is_dir() {
             continue;
         }
@@ -2473,6 +3837,12 @@ async fn initial_scan_models(
         };
         
         for entry in entries {
+            // Models processed so far are still saved
+            if let Err(e) = task.check() {
+                result.success = false;
+                result.errors.push(e);
+                break 'directories;
+            }
             let path = match entry {
                 Ok(path) => path,
                 Err(e) => {
@@ -2519,12 +3889,13 @@ async fn initial_scan_models(
             }
             
             result.models_processed += 1;
+            task.report(None, Some(format!("{} model(s) processed", result.models_processed)));
         }
     }
     
     // Save settings
     if let Err(e) = save_settings(&state).await {
-        result.errors.push(format!("Failed to save settings: {}", e));
+        result.errors.push(i18n::t("settings.save_failed", &[("error", &e.to_string())]));
     }
     
     Ok(result)
@@ -2563,12 +3934,9 @@ fn parse_hf_date(date_str: &str) -> Option<i64> {
 async fn get_hf_model_files(
     hf_model_id: String,
 ) -> Result<HFLinkResult, String> {
-    use reqwest;
-    
     let url = format!("https://huggingface.co/api/models/{}/tree/main", hf_model_id);
     
-    let client = reqwest::Client::new();
-    let response = match client.get(&url).send().await {
+    let response = match hf_client::get(&url).await {
         Ok(resp) => resp,
         Err(e) => {
             return Ok(HFLinkResult {
@@ -2587,7 +3955,7 @@ async fn get_hf_model_files(
         });
     }
     
-    let data: serde_json::Value = match response.json().await {
+    let data: serde_json::Value = match response.json() {
         Ok(data) => data,
         Err(e) => {
             return Ok(HFLinkResult {
@@ -2880,6 +4248,7 @@ async fn download_from_url(
     url: String,
     destination_folder: String,
     extract: bool,
+    expected_sha256: Option<String>,
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<DownloadStartResult, String> {
@@ -2893,27 +4262,124 @@ async fn download_from_url(
         create_subfolder: None,
         files: Vec::new(), // Single file download
         custom_headers: None,
+        expected_sha256: expected_sha256.map(|hash| hash.trim().to_lowercase()).filter(|hash| !hash.is_empty()),
     };
     
     start_download(config, &state, app_handle)
         .await
-        .map_err(|e| format!("Failed to start download: {}", e))
+        .map_err(|e| i18n::t("download.start_failed", &[("error", &e.to_string())]))
+}
+
+#[tauri::command]
+async fn get_llamacpp_releases(
+    force_refresh: Option<bool>,
+    platform_only: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<LlamaCppRelease>, String> {
+    let github_token = github_token::load(&state).await;
+    let options = {
+        let config = state.config.lock().await;
+        llamacpp_manager::ReleaseFetchOptions {
+            github_token,
+            max_age: Duration::from_secs(u64::from(config.llamacpp_releases_refresh_hours.max(1)) * 3600),
+            force_refresh: force_refresh.unwrap_or(false),
+        }
+    };
+
+    let mut releases = llamacpp_manager::fetch_llamacpp_releases(&options)
+        .await
+        .map_err(|e| format!("Failed to fetch llama.cpp releases: {}", e))?;
+
+    let host = tokio::task::spawn_blocking(gpu_runtime::host_platform)
+        .await
+        .map_err(|e| format!("Failed to detect host platform: {}", e))?;
+    llamacpp_manager::annotate_asset_compatibility(&mut releases, &host, platform_only.unwrap_or(false));
+    Ok(releases)
+}
+
+#[tauri::command]
+async fn get_host_platform() -> Result<gpu_runtime::HostPlatform, String> {
+    tokio::task::spawn_blocking(gpu_runtime::host_platform)
+        .await
+        .map_err(|e| format!("Failed to detect host platform: {}", e))
+}
+
+/// Detect installed CUDA/ROCm/Vulkan runtimes. Cached; pass `force` to probe again.
+#[tauri::command]
+async fn probe_gpu_runtime(force: Option<bool>) -> Result<gpu_runtime::GpuRuntimeProbe, String> {
+    let force = force.unwrap_or(false);
+    tokio::task::spawn_blocking(move || gpu_runtime::probe_gpu_runtime(force))
+        .await
+        .map_err(|e| format!("Failed to probe GPU runtime: {}", e))
+}
+
+/// Checked before a download starts so the UI can warn about builds this machine cannot run
+#[tauri::command]
+async fn check_llamacpp_asset_compatibility(asset_name: String) -> Result<gpu_runtime::AssetCompatibility, String> {
+    let host = tokio::task::spawn_blocking(gpu_runtime::host_platform)
+        .await
+        .map_err(|e| format!("Failed to detect host platform: {}", e))?;
+    Ok(gpu_runtime::assess_asset(&asset_name, &host))
 }
 
+/// CPU-only preset for small ARM boards; `recommended` when this machine is one
 #[tauri::command]
-async fn get_llamacpp_releases() -> Result<Vec<LlamaCppRelease>, String> {
-    llamacpp_manager::fetch_llamacpp_releases()
+async fn get_low_ram_cpu_preset() -> Result<serde_json::Value, String> {
+    let host = tokio::task::spawn_blocking(gpu_runtime::host_platform)
         .await
-        .map_err(|e| format!("Failed to fetch llama.cpp releases: {}", e))
+        .map_err(|e| format!("Failed to detect host platform: {}", e))?;
+    let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    Ok(serde_json::json!({
+        "name": "CPU low-RAM (ARM)",
+        "custom_args": gpu_runtime::low_ram_cpu_args(threads, host.total_memory_gb),
+        "recommended": gpu_runtime::is_low_ram_arm(&host),
+        "device_model": host.device_model,
+    }))
 }
 
 #[tauri::command]
-async fn get_llamacpp_commit_info(tag_name: String) -> Result<llamacpp_manager::CommitInfo, String> {
-    llamacpp_manager::fetch_commit_info(&tag_name)
+async fn get_llamacpp_commit_info(
+    tag_name: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<llamacpp_manager::CommitInfo, String> {
+    let github_token = github_token::load(&state).await;
+    llamacpp_manager::fetch_commit_info(&tag_name, github_token.as_deref())
         .await
         .map_err(|e| format!("Failed to fetch commit info: {}", e))
 }
 
+/// Store the release refresh interval; the token itself goes through `set_github_token`
+#[tauri::command]
+async fn save_github_settings(
+    releases_refresh_hours: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    if let Some(hours) = releases_refresh_hours {
+        state.config.lock().await.llamacpp_releases_refresh_hours = hours.max(1);
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save config: {}", e))?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "has_token": github_token::load(&state).await.is_some(),
+    }))
+}
+
+/// Store the GitHub token in the OS keyring (an empty token removes it)
+#[tauri::command]
+async fn set_github_token(token: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    github_token::set(token).await?;
+    state.config.lock().await.github_token = None;
+    Ok(())
+}
+
+#[tauri::command]
+async fn clear_github_token(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    github_token::clear().await?;
+    state.config.lock().await.github_token = None;
+    Ok(())
+}
+
 #[tauri::command]
 async fn download_llamacpp_asset(
     asset: LlamaCppAsset,
@@ -2940,6 +4406,7 @@ async fn download_llamacpp_asset(
             headers.insert("User-Agent".to_string(), "Arandu-Tauri/1.0".to_string());
             headers
         }),
+        expected_sha256: None,
     };
     
     start_download(config, &state, app_handle)
@@ -2951,23 +4418,23 @@ async fn download_llamacpp_asset(
 async fn download_llamacpp_asset_to_version(
     asset: LlamaCppAsset,
     version_folder: String,
+    tag_name: Option<String>,
     state: tauri::State<'_, AppState>,
     app_handle: tauri::AppHandle,
 ) -> Result<DownloadStartResult, String> {
-    use crate::downloader::{DownloadConfig, start_download};
+    use crate::downloader::{DownloadConfig, start_download_then};
 
     // Base executable folder from config
+    let github_token = github_token::load(&state).await;
     let base_exec = {
         let config = state.config.lock().await;
         config.executable_folder.clone()
     };
 
     // Destination: <exec>/versions/<version_folder>
-    let destination_folder = std::path::Path::new(&base_exec)
-        .join("versions")
-        .join(&version_folder)
-        .to_string_lossy()
-        .to_string();
+    let versions_root = std::path::Path::new(&base_exec).join("versions");
+    let destination_path = versions_root.join(&version_folder);
+    let destination_folder = destination_path.to_string_lossy().to_string();
 
     // Create download configuration
     let config = DownloadConfig {
@@ -2981,13 +4448,75 @@ async fn download_llamacpp_asset_to_version(
             headers.insert("User-Agent".to_string(), "Arandu-Tauri/1.0".to_string());
             headers
         }),
+        expected_sha256: None,
     };
 
-    start_download(config, &state, app_handle)
+    // Only a build that downloaded and extracted gets an entry in versions.json
+    let asset_name = asset.name.clone();
+    let on_installed: downloader::OnInstalled = Box::new(move || {
+        record_llamacpp_install_metadata(&versions_root, &destination_path, &asset_name, tag_name, github_token);
+    });
+    start_download_then(config, &state, app_handle, Some(on_installed))
         .await
         .map_err(|e| format!("Failed to download llama.cpp asset: {}", e))
 }
 
+/// Persist what is known about a build once it is extracted. The commit hash
+/// needs two more GitHub calls, so it is filled in from a background task.
+fn record_llamacpp_install_metadata(
+    versions_root: &Path,
+    version_path: &Path,
+    asset_name: &str,
+    tag_name: Option<String>,
+    github_token: Option<String>,
+) {
+    let Some(key) = llamacpp_manager::version_metadata_key(versions_root, version_path) else {
+        return;
+    };
+    let tag_name = tag_name
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .or_else(|| key.split('/').next().map(|s| s.to_string()));
+    let release = tag_name.as_deref().and_then(llamacpp_manager::cached_release);
+
+    let metadata = llamacpp_manager::InstalledVersionMetadata {
+        tag_name: release.as_ref().map(|r| r.tag_name.clone()).or(tag_name),
+        commit_sha: release.as_ref().and_then(|r| r.commit_info.as_ref().map(|c| c.sha.clone())),
+        backend_type: Some(detect_backend_type(asset_name)),
+        asset_name: Some(asset_name.to_string()),
+        published_at: release.as_ref().map(|r| r.published_at.clone()),
+        release_notes: release.as_ref().and_then(|r| r.body.clone()),
+        release_url: release.as_ref().map(|r| r.html_url.clone()),
+        source: Some("github".to_string()),
+        installed_at: Some(Utc::now().to_rfc3339()),
+    };
+    let lookup_tag = metadata.tag_name.clone().filter(|_| metadata.commit_sha.is_none());
+
+    if let Err(e) = llamacpp_manager::update_version_metadata(versions_root, &key, |entry| *entry = Some(metadata)) {
+        eprintln!("Failed to record llama.cpp version metadata: {}", e);
+        return;
+    }
+
+    if let Some(tag) = lookup_tag {
+        let versions_root = versions_root.to_path_buf();
+        tauri::async_runtime::spawn(async move {
+            match llamacpp_manager::fetch_commit_info(&tag, github_token.as_deref()).await {
+                Ok(commit) => {
+                    let result = llamacpp_manager::update_version_metadata(&versions_root, &key, |entry| {
+                        if let Some(entry) = entry.as_mut() {
+                            entry.commit_sha = Some(commit.sha);
+                        }
+                    });
+                    if let Err(e) = result {
+                        eprintln!("Failed to record commit for {}: {}", key, e);
+                    }
+                }
+                Err(e) => eprintln!("Failed to look up commit for {}: {}", tag, e),
+            }
+        });
+    }
+}
+
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
 struct LlamaCppInstalledVersion {
     name: String,
@@ -2995,7 +4524,12 @@ struct LlamaCppInstalledVersion {
     has_server: bool,
     created: Option<i64>,
     is_active: bool,
+    /// Active build for its backend type, see `GlobalConfig::active_backend_versions`
+    is_backend_active: bool,
     backend_type: Option<String>,
+    /// Details recorded at install time; `None` for versions installed before metadata existed
+    #[serde(default)]
+    metadata: Option<llamacpp_manager::InstalledVersionMetadata>,
 }
 
 #[tauri::command]
@@ -3004,12 +4538,13 @@ async fn list_llamacpp_versions(state: tauri::State<'_, AppState>) -> Result<Vec
     use std::collections::HashSet;
     use std::time::SystemTime;
 
-    let (base_exec, active_path, active_version) = {
+    let (base_exec, active_path, active_version, active_backend_versions) = {
         let cfg = state.config.lock().await;
         (
             cfg.executable_folder.clone(),
             cfg.active_executable_folder.clone(),
             cfg.active_executable_version.clone(),
+            cfg.active_backend_versions.clone(),
         )
     };
     let current_versions_dir = std::path::Path::new(&base_exec).join("versions");
@@ -3028,6 +4563,11 @@ async fn list_llamacpp_versions(state: tauri::State<'_, AppState>) -> Result<Vec
         if !versions_dir.exists() {
             continue;
         }
+        let versions_metadata = llamacpp_manager::load_versions_metadata(&versions_dir);
+        let metadata_for = |path: &Path| {
+            llamacpp_manager::version_metadata_key(&versions_dir, path)
+                .and_then(|key| versions_metadata.get(&key).cloned())
+        };
         if let Ok(read_dir) = fs::read_dir(&versions_dir) {
             for entry in read_dir.flatten() {
                 let path = entry.path();
@@ -3074,7 +4614,9 @@ async fn list_llamacpp_versions(state: tauri::State<'_, AppState>) -> Result<Vec
                                     has_server, 
                                     created, 
                                     is_active,
+                                    is_backend_active: false,
                                     backend_type: Some(backend_type),
+                                    metadata: metadata_for(&backend_path),
                                 });
                             }
                         }
@@ -3116,7 +4658,9 @@ async fn list_llamacpp_versions(state: tauri::State<'_, AppState>) -> Result<Vec
                             has_server: true, 
                             created, 
                             is_active,
+                            is_backend_active: false,
                             backend_type: Some(backend_type),
+                            metadata: metadata_for(&path),
                         });
                     }
                 }
@@ -3124,10 +4668,19 @@ async fn list_llamacpp_versions(state: tauri::State<'_, AppState>) -> Result<Vec
         }
     }
     
+    for version in out.iter_mut() {
+        version.is_backend_active = version
+            .backend_type
+            .as_ref()
+            .and_then(|backend| active_backend_versions.get(&detect_backend_type(backend)))
+            .map(|p| llamacpp_manager::same_version_path(p, &version.path))
+            .unwrap_or(false);
+    }
+    
     // If there is exactly one installed version and none is active, set it active automatically
     let has_active = out.iter().any(|v| v.is_active);
     if out.len() == 1 && !has_active {
-        if let Some(only) = out.get(0) {
+        if let Some(only) = out.first() {
             // Update config with this single version as active
             {
                 let mut cfg = state.config.lock().await;
@@ -3156,147 +4709,900 @@ async fn set_active_llamacpp_version(path: String, state: tauri::State<'_, AppSt
             .file_name()
             .and_then(|s| s.to_str())
             .map(|s| s.to_string());
+        // The default build is also the active one for its backend
+        if let Some(backend) = version_name.as_deref().map(detect_backend_type) {
+            cfg.active_backend_versions.insert(backend, path.clone());
+        }
         cfg.active_executable_folder = Some(path);
         cfg.active_executable_version = version_name;
     }
-    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
+    save_settings(&state).await.map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
 }
 
+/// Make `path` the active build for its backend type without changing the default build.
+/// With `path` set to `None`, clears the selection for `backend_type`.
 #[tauri::command]
-async fn delete_llamacpp_version(path: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
-    use std::fs;
-    use std::path::Path;
-
-    let base_exec = {
-        let cfg = state.config.lock().await;
-        cfg.executable_folder.clone()
-    };
-
-    let versions_root = Path::new(&base_exec).join("versions");
-    let legacy_versions_root = dirs::home_dir()
-        .map(|h| h.join(".Arandu").join("llama.cpp").join("versions"));
-    let path_buf = Path::new(&path).to_path_buf();
-
-    if !path_buf.exists() {
-        return Err("Version path does not exist".into());
-    }
-
-    let versions_root_canon = fs::canonicalize(&versions_root)
-        .unwrap_or(versions_root.clone());
-    let legacy_versions_root_canon = legacy_versions_root
-        .as_ref()
-        .and_then(|p| fs::canonicalize(p).ok());
-    let target_canon = fs::canonicalize(&path_buf)
-        .map_err(|e| format!("Failed to resolve version path: {}", e))?;
-
-    // Ensure deletion target resolves under allowed versions roots
-    let in_primary = target_canon.starts_with(&versions_root_canon);
-    let in_legacy = legacy_versions_root_canon
-        .as_ref()
-        .map(|root| target_canon.starts_with(root))
-        .unwrap_or(false);
-    if !in_primary && !in_legacy {
-        return Err("Cannot delete outside versions directory".into());
+async fn set_backend_active_llamacpp_version(
+    backend_type: String,
+    path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if backend_type.trim().is_empty() {
+        return Err("Backend type is required".into());
     }
+    let backend = detect_backend_type(backend_type.trim());
 
-    if target_canon.exists() {
-        fs::remove_dir_all(&target_canon).map_err(|e| format!("Failed to delete version: {}", e))?;
-        
-        // If we deleted a backend folder, check if the parent version folder is now empty
-        if let Some(parent) = target_canon.parent() {
-            let is_root_parent = parent == versions_root_canon
-                || legacy_versions_root_canon
-                    .as_ref()
-                    .map(|root| parent == root)
-                    .unwrap_or(false);
-            if !is_root_parent && parent.exists() {
-                if let Ok(entries) = fs::read_dir(parent) {
-                    if entries.count() == 0 {
-                        // Parent version folder is empty, remove it too
-                        let _ = fs::remove_dir(parent);
-                    }
+    {
+        let mut cfg = state.config.lock().await;
+        match path {
+            Some(path) => {
+                let server_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+                if !Path::new(&path).join(server_name).exists() {
+                    return Err("llama-server not found in the selected version folder".into());
                 }
+                cfg.active_backend_versions.insert(backend, path);
+            }
+            None => {
+                cfg.active_backend_versions.remove(&backend);
             }
         }
     }
-    
-    // Clear active if it pointed here
+    save_settings(&state).await.map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+/// Choose which backend's active build a model launches with, or `None` for the default build
+#[tauri::command]
+async fn set_model_backend_preference(
+    model_path: String,
+    backend_type: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let backend_type = backend_type
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty())
+        .map(|b| detect_backend_type(&b));
+
     {
-        let mut cfg = state.config.lock().await;
-        if cfg.active_executable_folder.as_deref() == Some(&path) {
-            cfg.active_executable_folder = None;
-            cfg.active_executable_version = None;
-        }
+        let mut model_configs = state.model_configs.lock().await;
+        let mut config = model_configs.get(&model_path)
+            .cloned()
+            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
+        config.preferred_backend = backend_type.clone();
+        model_configs.insert(model_path, config);
     }
-    save_settings(&state).await.map_err(|e| format!("Failed to save settings: {}", e))
-}
 
-// ==================== HuggingFace Direct Link Download Commands ====================
+    if let Err(e) = save_settings(&state).await {
+        return Err(i18n::t("settings.save_failed", &[("error", &e.to_string())]));
+    }
 
-#[tauri::command]
-async fn parse_hf_url(url: String) -> Result<String, String> {
-    huggingface_downloader::parse_model_id(&url)
+    Ok(serde_json::json!({
+        "success": true,
+        "preferred_backend": backend_type,
+    }))
 }
 
+/// Make a model stop all other running models whenever it launches
 #[tauri::command]
-async fn fetch_hf_model_info(model_id: String) -> Result<ModelCardInfo, String> {
-    huggingface_downloader::fetch_model_info(&model_id).await
+async fn set_model_exclusive_launch(
+    model_path: String,
+    exclusive: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut model_configs = state.model_configs.lock().await;
+        let mut config = model_configs.get(&model_path)
+            .cloned()
+            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
+        config.exclusive_launch = exclusive;
+        model_configs.insert(model_path, config);
+    }
+
+    save_settings(&state).await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
 }
 
+/// Set whether a model's server is relaunched after it exits on its own; applies
+/// from the next exit, also for servers already running
 #[tauri::command]
-async fn fetch_hf_model_files(model_id: String) -> Result<Vec<HfFileInfo>, String> {
-    huggingface_downloader::fetch_model_files(&model_id).await
+async fn set_model_restart_policy(
+    model_path: String,
+    policy: RestartPolicy,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    if policy.mode != RestartMode::Never && policy.max_retries == 0 {
+        return Err("Restart policy needs at least one retry".to_string());
+    }
+    {
+        let mut model_configs = state.model_configs.lock().await;
+        let mut config = model_configs.get(&model_path)
+            .cloned()
+            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
+        config.restart_policy = policy;
+        model_configs.insert(model_path, config);
+    }
+
+    save_settings(&state).await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
 }
 
+/// Set a model's `--mlock`, `--no-mmap` and `--numa` options; used from its next launch
 #[tauri::command]
-async fn get_default_download_path(
-    model_id: String,
+async fn set_model_memory_settings(
+    model_path: String,
+    settings: MemorySettings,
     state: tauri::State<'_, AppState>,
-) -> Result<String, String> {
-    let config = state.config.lock().await;
-    let base_dir = &config.models_directory;
-    let path = huggingface_downloader::build_destination_path(base_dir, &model_id);
-    Ok(path.to_string_lossy().to_string())
+) -> Result<(), String> {
+    {
+        let mut model_configs = state.model_configs.lock().await;
+        let mut config = model_configs.get(&model_path)
+            .cloned()
+            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
+        config.memory = settings;
+        model_configs.insert(model_path, config);
+    }
+
+    save_settings(&state).await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
 }
 
 #[tauri::command]
-async fn download_hf_file(
-    model_id: String,
-    filename: String,
-    destination: String,
+async fn set_model_gpu_devices(
+    model_path: String,
+    settings: GpuDevices,
     state: tauri::State<'_, AppState>,
-    app_handle: tauri::AppHandle,
-) -> Result<DownloadStartResult, String> {
-    use downloader::{DownloadConfig, start_download};
-    use std::path::Path;
-    
-    // Construct download URL
-    let download_url = format!(
-        "https://huggingface.co/{}/resolve/main/{}",
-        model_id, filename
-    );
-    
-    // Ensure destination directory exists
-    let dest_path = Path::new(&destination);
-    if let Some(parent) = dest_path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create directory: {}", e))?;
+) -> Result<(), String> {
+    multi_gpu::validate(&settings)?;
+    {
+        let mut model_configs = state.model_configs.lock().await;
+        let mut config = model_configs.get(&model_path)
+            .cloned()
+            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
+        config.gpu_devices = settings;
+        model_configs.insert(model_path, config);
+    }
+
+    save_settings(&state).await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+/// Minutes without requests before the model's server is stopped; 0 turns it off
+#[tauri::command]
+async fn set_model_idle_timeout(
+    model_path: String,
+    minutes: u32,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut model_configs = state.model_configs.lock().await;
+        let mut config = model_configs.get(&model_path)
+            .cloned()
+            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
+        config.idle_timeout_minutes = minutes;
+        model_configs.insert(model_path, config);
+    }
+
+    save_settings(&state).await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+/// Low memory mode state, with whether this machine's RAM suggests turning it on
+#[tauri::command]
+async fn get_low_memory_mode(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let (enabled, context) = {
+        let config = state.config.lock().await;
+        (config.low_memory_mode, config.low_memory_context)
+    };
+    let total_memory_gb = gpu_runtime::total_memory_gb();
+    Ok(serde_json::json!({
+        "enabled": enabled,
+        "context": context,
+        "total_memory_gb": total_memory_gb,
+        "recommended": total_memory_gb > 0.0 && total_memory_gb <= memory_mode::LOW_MEMORY_RECOMMENDED_GB,
+    }))
+}
+
+#[tauri::command]
+async fn set_low_memory_mode(enabled: bool, context: Option<u32>, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if context.is_some_and(|c| c < 512) {
+        return Err("Low memory context must be at least 512 tokens".to_string());
+    }
+    {
+        let mut config = state.config.lock().await;
+        config.low_memory_mode = enabled;
+        if let Some(context) = context {
+            config.low_memory_context = context;
+        }
+    }
+    println!("[Launch] Low memory mode {}", if enabled { "enabled" } else { "disabled" });
+    save_settings(&state)
+        .await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+/// Wh per 1k tokens for each model and backend, optionally over the last `since_days`
+#[tauri::command]
+async fn get_energy_report(since_days: Option<u32>) -> Result<serde_json::Value, String> {
+    tokio::task::spawn_blocking(move || energy_report::energy_report(since_days))
+        .await
+        .map_err(|e| format!("Failed to build energy report: {}", e))
+}
+
+/// The user's own usage statistics, optionally over the last `since_days`; local only
+#[tauri::command]
+async fn get_usage_analytics(since_days: Option<u32>) -> Result<serde_json::Value, String> {
+    tokio::task::spawn_blocking(move || analytics::usage_analytics(since_days))
+        .await
+        .map_err(|e| format!("Failed to build usage analytics: {}", e))?
+}
+
+#[tauri::command]
+async fn set_usage_analytics_enabled(enabled: bool, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.config.lock().await.usage_analytics_enabled = enabled;
+    analytics::set_enabled(enabled);
+    println!("[Analytics] Local usage statistics {}", if enabled { "enabled" } else { "disabled" });
+    save_settings(&state)
+        .await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+/// Known terminal emulators of this platform, for the External terminal setting
+#[tauri::command]
+async fn get_terminal_options(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let current = state.config.lock().await.external_terminal.clone();
+    Ok(serde_json::json!({ "current": current, "options": terminal::options() }))
+}
+
+#[tauri::command]
+async fn set_external_terminal(template: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let template = template.trim().to_string();
+    if !template.is_empty() {
+        terminal::expand(&template, "llama-server", &[], None)?;
+    }
+    state.config.lock().await.external_terminal = template;
+    save_settings(&state)
+        .await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+#[tauri::command]
+async fn clear_usage_analytics() -> Result<(), String> {
+    storage::run(|db| db.analytics().clear()).await
+}
+
+/// Move a model (and its split shards, mmproj and LoRA files) to another models directory
+#[tauri::command]
+async fn move_model(
+    model_path: String,
+    target_directory: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let result = model_files::move_model(&state, &app_handle, &model_path, &target_directory).await?;
+    // Same refresh the desktop does after a delete
+    events::emit(&app_handle, &events::FileDeleted);
+    Ok(result)
+}
+
+/// Rename a model file (all shards for split models) and carry its settings over
+#[tauri::command]
+async fn rename_model_file(
+    old_path: String,
+    new_name: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let result = model_files::rename_model_file(&state, &old_path, &new_name).await?;
+    events::emit(&app_handle, &events::FileDeleted);
+    Ok(result)
+}
+
+/// Report model settings whose files are gone, optionally relinking some
+/// (missing path -> found file) and removing confirmed orphans
+#[tauri::command]
+async fn reconcile_model_configs(
+    relink: Option<HashMap<String, String>>,
+    remove: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    model_reconcile::reconcile(&state, relink.unwrap_or_default(), remove.unwrap_or_default()).await
+}
+
+/// SHA-256 the given models (every configured one when omitted) so their settings can
+/// follow them across renames and moves
+#[tauri::command]
+async fn compute_model_hashes(
+    model_paths: Option<Vec<String>>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<HashMap<String, String>, String> {
+    model_identity::compute_hashes(&state, &app_handle, model_paths).await
+}
+
+/// Add a GGUF from elsewhere on disk to the library, e.g. one dropped onto the window.
+/// `copy_or_link` is "copy" or "link".
+#[tauri::command]
+async fn register_external_model(
+    path: String,
+    copy_or_link: String,
+    target_directory: Option<String>,
+    hf_model_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let result = model_files::register_external_model(&state, &app_handle, &path, &copy_or_link, target_directory, hf_model_id).await?;
+    events::emit(&app_handle, &events::DownloadComplete);
+    Ok(result)
+}
+
+/// Install an Arandu pack (manifest JSON or a path to it). Without `confirmed` only the
+/// summary to confirm is returned.
+#[tauri::command]
+async fn install_model_pack(
+    manifest: String,
+    confirmed: Option<bool>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let pack = model_pack::load_manifest(&manifest)?;
+    model_pack::install_if_confirmed(&state, app_handle, &pack, confirmed.unwrap_or(false)).await
+}
+
+/// VRAM estimate for a local file (`model_path`, using its configured arguments unless
+/// `custom_args` is given) or a remote model by name, parameter count and quantization
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn estimate_vram(
+    state: tauri::State<'_, AppState>,
+    model_path: Option<String>,
+    model_name: Option<String>,
+    quantization: Option<String>,
+    parameters_b: Option<f64>,
+    context_length: Option<u32>,
+    kv_cache_type: Option<String>,
+    custom_args: Option<String>,
+) -> Result<vram_estimate::VramEstimate, String> {
+    let args = match (&custom_args, &model_path) {
+        (Some(args), _) => args.clone(),
+        (None, Some(path)) => state.model_configs.lock().await.get(path).map(|c| c.custom_args.clone()).unwrap_or_default(),
+        (None, None) => String::new(),
+    };
+    let mut options = vram_estimate::EstimateOptions::from_args(&args);
+    if let Some(context) = context_length {
+        options.context_length = context;
+    }
+    if let Some(kind) = kv_cache_type {
+        options.cache_type_k = kind.to_lowercase();
+        options.cache_type_v = kind.to_lowercase();
+    }
+
+    if let Some(path) = model_path {
+        return tokio::task::spawn_blocking(move || vram_estimate::estimate_file(Path::new(&path), &options))
+            .await
+            .map_err(|e| format!("Failed to estimate VRAM: {}", e))?
+            .ok_or_else(|| "Model file not found".to_string());
+    }
+    let name = model_name.unwrap_or_default();
+    vram_estimate::estimate_remote(&name, parameters_b, quantization.as_deref(), None, &options)
+        .ok_or_else(|| format!("No parameter count for '{}'; pass parameters_b", name))
+}
+
+#[derive(serde::Serialize, Debug, Clone)]
+struct LaunchArgsSuggestion {
+    /// The model's arguments with the suggested `-ngl`
+    custom_args: String,
+    /// `-ngl` the arguments already set, which launches keep
+    current_gpu_layers: Option<String>,
+    suggestion: vram_estimate::GpuLayerSuggestion,
+}
+
+/// Recommended GPU layers for a local model with its saved arguments and the VRAM free now
+#[tauri::command]
+async fn suggest_launch_args(state: tauri::State<'_, AppState>, model_path: String) -> Result<LaunchArgsSuggestion, String> {
+    let custom_args = state.model_configs.lock().await.get(&model_path).map(|c| c.custom_args.clone()).unwrap_or_default();
+    let mut suggestion = process::suggest_gpu_layers(&model_path, &custom_args).await?;
+    let mut args = cli_args::split(&custom_args);
+    let current_gpu_layers = cli_args::flag_value(&args, process::GPU_LAYER_FLAGS);
+    if let Some(current) = &current_gpu_layers {
+        suggestion.reasoning.push(format!("The arguments set -ngl {}, which launches keep until it is changed", current));
+    }
+    cli_args::set_flag(&mut args, process::GPU_LAYER_FLAGS, &suggestion.gpu_layers.to_string());
+    Ok(LaunchArgsSuggestion { custom_args: cli_args::join(&args), current_gpu_layers, suggestion })
+}
+
+/// Events with a stable payload, their versions and payload fields
+#[tauri::command]
+async fn list_event_schemas() -> Result<Vec<events::EventSchema>, String> {
+    Ok(events::schemas())
+}
+
+/// Free space of every models directory's disk against the watchdog threshold
+#[tauri::command]
+async fn get_disk_space_status(state: tauri::State<'_, AppState>) -> Result<Vec<disk_watchdog::DiskSpace>, String> {
+    Ok(disk_watchdog::disk_space_status(&state).await)
+}
+
+/// RAM, swap and commit charge as of the memory watchdog's last check
+#[tauri::command]
+async fn get_memory_pressure() -> Result<Option<memory_watchdog::MemoryPressure>, String> {
+    Ok(memory_watchdog::latest())
+}
+
+/// Battery state and what the power policy holds back right now
+#[tauri::command]
+async fn get_power_status(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let policy = state.config.lock().await.power_policy.clone();
+    let (status, throttle) = power::current();
+    Ok(serde_json::json!({ "status": status, "throttle": throttle, "policy": policy }))
+}
+
+/// Takes effect at the power watchdog's next check
+#[tauri::command]
+async fn set_power_policy(policy: PowerPolicy, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    if policy.battery_threshold_percent > 100 {
+        return Err("Battery threshold must be a percentage".to_string());
+    }
+    state.config.lock().await.power_policy = policy;
+    save_settings(&state)
+        .await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+#[tauri::command]
+async fn get_fallback_chain(state: tauri::State<'_, AppState>) -> Result<Vec<String>, String> {
+    Ok(state.config.lock().await.fallback_chain.clone())
+}
+
+/// Primary model first; an empty chain turns fallback off
+#[tauri::command]
+async fn set_fallback_chain(
+    chain: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for model_path in chain.into_iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()) {
+        if !cleaned.contains(&model_path) {
+            cleaned.push(model_path);
+        }
+    }
+    state.config.lock().await.fallback_chain = cleaned.clone();
+    save_settings(&state).await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))?;
+    Ok(cleaned)
+}
+
+#[tauri::command]
+async fn get_route_policies(
+    state: tauri::State<'_, AppState>,
+) -> Result<HashMap<String, models::RoutePolicy>, String> {
+    Ok(state.config.lock().await.route_policies.clone())
+}
+
+/// `key` is a model path, the alias clients send as `model`, or `*`; no policy removes it
+#[tauri::command]
+async fn set_route_policy(
+    key: String,
+    policy: Option<models::RoutePolicy>,
+    state: tauri::State<'_, AppState>,
+) -> Result<HashMap<String, models::RoutePolicy>, String> {
+    let key = key.trim().to_string();
+    if key.is_empty() {
+        return Err("Policy key is required".to_string());
+    }
+    let policies = {
+        let mut config = state.config.lock().await;
+        match policy.filter(|p| *p != models::RoutePolicy::default()) {
+            Some(policy) => {
+                if policy.max_concurrent == Some(0) || policy.max_tokens_cap.is_some_and(|c| c <= 0) {
+                    return Err("Limits must be greater than zero".to_string());
+                }
+                config.route_policies.insert(key, policy);
+            }
+            None => {
+                config.route_policies.remove(&key);
+            }
+        }
+        config.route_policies.clone()
+    };
+    save_settings(&state).await
+        .map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))?;
+    Ok(policies)
+}
+
+#[tauri::command]
+async fn delete_llamacpp_version(path: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    use std::fs;
+    use std::path::Path;
+
+    let base_exec = {
+        let cfg = state.config.lock().await;
+        cfg.executable_folder.clone()
+    };
+
+    let versions_root = Path::new(&base_exec).join("versions");
+    let legacy_versions_root = dirs::home_dir()
+        .map(|h| h.join(".Arandu").join("llama.cpp").join("versions"));
+    let path_buf = Path::new(&path).to_path_buf();
+
+    if !path_buf.exists() {
+        return Err("Version path does not exist".into());
+    }
+
+    let versions_root_canon = fs::canonicalize(&versions_root)
+        .unwrap_or(versions_root.clone());
+    let legacy_versions_root_canon = legacy_versions_root
+        .as_ref()
+        .and_then(|p| fs::canonicalize(p).ok());
+    let target_canon = fs::canonicalize(&path_buf)
+        .map_err(|e| format!("Failed to resolve version path: {}", e))?;
+
+    // Ensure deletion target resolves under allowed versions roots
+    let in_primary = target_canon.starts_with(&versions_root_canon);
+    let in_legacy = legacy_versions_root_canon
+        .as_ref()
+        .map(|root| target_canon.starts_with(root))
+        .unwrap_or(false);
+    if !in_primary && !in_legacy {
+        return Err("Cannot delete outside versions directory".into());
+    }
+
+    if target_canon.exists() {
+        fs::remove_dir_all(&target_canon).map_err(|e| format!("Failed to delete version: {}", e))?;
+
+        let metadata_root = if in_primary { &versions_root_canon } else { legacy_versions_root_canon.as_ref().unwrap_or(&versions_root_canon) };
+        if let Some(key) = llamacpp_manager::version_metadata_key(metadata_root, &target_canon) {
+            let prefix = format!("{}/", key);
+            let metadata = llamacpp_manager::load_versions_metadata(metadata_root);
+            for stale in metadata.keys().filter(|k| **k == key || k.starts_with(&prefix)) {
+                let _ = llamacpp_manager::update_version_metadata(metadata_root, stale, |entry| *entry = None);
+            }
+        }
+        
+        // If we deleted a backend folder, check if the parent version folder is now empty
+        if let Some(parent) = target_canon.parent() {
+            let is_root_parent = parent == versions_root_canon
+                || legacy_versions_root_canon
+                    .as_ref()
+                    .map(|root| parent == root)
+                    .unwrap_or(false);
+            if !is_root_parent && parent.exists() {
+                if let Ok(entries) = fs::read_dir(parent) {
+                    if entries.count() == 0 {
+                        // Parent version folder is empty, remove it too
+                        let _ = fs::remove_dir(parent);
+                    }
+                }
+            }
+        }
+    }
+    
+    // Clear active if it pointed here
+    {
+        let mut cfg = state.config.lock().await;
+        if cfg.active_executable_folder.as_deref() == Some(&path) {
+            cfg.active_executable_folder = None;
+            cfg.active_executable_version = None;
+        }
+        cfg.active_backend_versions
+            .retain(|_, active| !llamacpp_manager::same_version_path(active, &path));
+    }
+    save_settings(&state).await.map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+/// Benchmark every installed backend with `model_path` and remember the fastest for
+/// its model class. Later launches of similar models without a backend preference use it.
+#[tauri::command]
+async fn auto_select_backend(
+    model_path: String,
+    task_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<BackendBenchmarkRecord, String> {
+    if !Path::new(&model_path).exists() {
+        return Err("Model file not found".into());
+    }
+
+    let config = state.config.lock().await.clone();
+    let candidates = backend_bench::benchmark_candidates(&config);
+    if candidates.is_empty() {
+        return Err("No installed llama.cpp build includes llama-bench".into());
+    }
+
+    let class_path = model_path.clone();
+    let model_class = tokio::task::spawn_blocking(move || backend_bench::model_class(&class_path))
+        .await
+        .map_err(|e| format!("Failed to read model metadata: {}", e))?;
+
+    let task = task_manager::register("benchmark", task_id, format!("Benchmark backends with {}", model_path));
+    let total = candidates.len();
+    let mut results = Vec::with_capacity(total);
+    for (index, (backend, build_path)) in candidates.into_iter().enumerate() {
+        task.check()?;
+        task.report_steps(index, total, backend.clone());
+        events::emit(&app_handle, &events::BackendBenchmarkProgress {
+            model_path: model_path.clone(),
+            backend: backend.clone(),
+            index,
+            total,
+            status: "running",
+            result: None,
+        });
+        println!("[Bench] Running llama-bench for {} on {}", model_path, backend);
+        let result = backend_bench::run_benchmark(&backend, &build_path, &model_path, &task.token()).await;
+        events::emit(&app_handle, &events::BackendBenchmarkProgress {
+            model_path: model_path.clone(),
+            backend: backend.clone(),
+            index,
+            total,
+            status: if result.error.is_some() { "failed" } else { "done" },
+            result: Some(result.clone()),
+        });
+        results.push(result);
+    }
+    // A cancelled last run would otherwise count as a failed backend
+    task.check()?;
+
+    let winner = backend_bench::pick_winner(&results).cloned();
+    let record = BackendBenchmarkRecord {
+        model_class: model_class.clone(),
+        winner_backend: winner.as_ref().map(|w| w.backend.clone()),
+        winner_path: winner.as_ref().map(|w| w.build_path.clone()),
+        results,
+        model_path,
+        measured_at: Utc::now(),
+    };
+
+    if let Some(winner) = &winner {
+        println!("[Bench] {} is fastest for {} ({:?} t/s)", winner.backend, model_class, winner.gen_tps);
+        let stored = record.clone();
+        storage::run(move |db| db.benchmarks().save(&stored)).await?;
+        let mut cfg = state.config.lock().await;
+        cfg.backend_benchmarks.insert(model_class, record.clone());
+        // Make sure the winner resolves at launch even if its backend had no active build
+        cfg.active_backend_versions
+            .entry(winner.backend.clone())
+            .or_insert_with(|| winner.build_path.clone());
+    }
+    save_settings(&state).await.map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))?;
+
+    Ok(record)
+}
+
+#[tauri::command]
+async fn get_backend_benchmarks(state: tauri::State<'_, AppState>) -> Result<Vec<BackendBenchmarkRecord>, String> {
+    let cfg = state.config.lock().await;
+    let mut records: Vec<_> = cfg.backend_benchmarks.values().cloned().collect();
+    records.sort_by(|a, b| a.model_class.cmp(&b.model_class));
+    Ok(records)
+}
+
+#[tauri::command]
+async fn clear_backend_benchmarks(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    storage::run(|db| db.benchmarks().clear()).await?;
+    state.config.lock().await.backend_benchmarks.clear();
+    Ok(())
+}
+
+/// Remove all but the newest `keep_per_backend` builds of each backend type. Active builds
+/// (default and per backend) and builds pinned by a model are never touched. With `dry_run` nothing is deleted.
+#[tauri::command]
+async fn prune_llamacpp_versions(
+    dry_run: bool,
+    keep_per_backend: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    use std::fs;
+    use std::time::SystemTime;
+
+    let (base_exec, active_paths, configured_keep) = {
+        let cfg = state.config.lock().await;
+        let mut active_paths: Vec<String> = cfg.active_backend_versions.values().cloned().collect();
+        active_paths.extend(cfg.active_executable_folder.clone());
+        (
+            cfg.executable_folder.clone(),
+            active_paths,
+            cfg.llamacpp_keep_versions_per_backend,
+        )
+    };
+    let keep = keep_per_backend.unwrap_or(configured_keep).max(1);
+    let pinned: Vec<String> = {
+        let model_configs = state.model_configs.lock().await;
+        model_configs
+            .values()
+            .filter_map(|c| c.pinned_llamacpp_version.clone())
+            .collect()
+    };
+
+    let versions_root = Path::new(&base_exec).join("versions");
+    let versions_metadata = llamacpp_manager::load_versions_metadata(&versions_root);
+    let mut candidates = Vec::new();
+
+    // Only the nested `<version>/<backend>` layout is pruned; legacy flat folders predate
+    // backend tracking and are left for manual deletion
+    if let Ok(read_dir) = fs::read_dir(&versions_root) {
+        for version_entry in read_dir.flatten() {
+            let version_path = version_entry.path();
+            if !version_path.is_dir() {
+                continue;
+            }
+            let version_name = version_path.file_name().and_then(|s| s.to_str()).unwrap_or("").to_string();
+            for backend_path in llamacpp_manager::backend_dirs(&versions_root, &version_path, &versions_metadata) {
+                let backend_name = backend_path.file_name().and_then(|s| s.to_str()).unwrap_or("").to_string();
+                let path_string = backend_path.to_string_lossy().to_string();
+                let created = fs::metadata(&backend_path).ok()
+                    .and_then(|m| m.created().ok())
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64);
+                let metadata = llamacpp_manager::version_metadata_key(&versions_root, &backend_path)
+                    .and_then(|key| versions_metadata.get(&key));
+
+                let is_active = active_paths
+                    .iter()
+                    .any(|a| llamacpp_manager::overlapping_version_paths(a, &path_string));
+                let is_pinned = pinned.iter().any(|p| llamacpp_manager::overlapping_version_paths(p, &path_string));
+
+                candidates.push(llamacpp_manager::PruneCandidate {
+                    name: format!("{}-{}", version_name, backend_name),
+                    backend_type: metadata
+                        .and_then(|m| m.backend_type.clone())
+                        .unwrap_or(backend_name),
+                    sort_key: llamacpp_manager::version_sort_key(metadata, created),
+                    protected: is_active || is_pinned,
+                    size_bytes: llamacpp_manager::directory_size(&backend_path),
+                    path: path_string,
+                });
+            }
+        }
+    }
+
+    let to_remove = llamacpp_manager::select_versions_to_prune(&candidates, keep as usize);
+    let protected: Vec<&llamacpp_manager::PruneCandidate> = candidates.iter().filter(|c| c.protected).collect();
+    let mut removed = Vec::new();
+    let mut errors = Vec::new();
+
+    for candidate in &to_remove {
+        if dry_run {
+            removed.push(candidate.clone());
+            continue;
+        }
+        let backend_path = Path::new(&candidate.path);
+        match fs::remove_dir_all(backend_path) {
+            Ok(()) => {
+                println!("Pruned llama.cpp version {} ({} bytes)", candidate.name, candidate.size_bytes);
+                if let Some(key) = llamacpp_manager::version_metadata_key(&versions_root, backend_path) {
+                    let _ = llamacpp_manager::update_version_metadata(&versions_root, &key, |entry| *entry = None);
+                }
+                if let Some(parent) = backend_path.parent() {
+                    if parent != versions_root && fs::read_dir(parent).map(|mut e| e.next().is_none()).unwrap_or(false) {
+                        let _ = fs::remove_dir(parent);
+                    }
+                }
+                removed.push(candidate.clone());
+            }
+            Err(e) => errors.push(format!("Failed to delete {}: {}", candidate.name, e)),
+        }
+    }
+
+    let freed_bytes: u64 = removed.iter().map(|c| c.size_bytes).sum();
+    Ok(serde_json::json!({
+        "dry_run": dry_run,
+        "keep_per_backend": keep,
+        "removed": removed,
+        "protected": protected,
+        "freed_bytes": freed_bytes,
+        "errors": errors,
+    }))
+}
+
+#[tauri::command]
+async fn save_llamacpp_retention(keep_per_backend: u32, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    {
+        let mut cfg = state.config.lock().await;
+        cfg.llamacpp_keep_versions_per_backend = keep_per_backend.max(1);
+    }
+    save_settings(&state).await.map_err(|e| i18n::t("settings.save_failed", &[("error", &e.to_string())]))
+}
+
+/// Pin a model to a specific llama.cpp build folder, or clear the pin with `None`
+#[tauri::command]
+async fn set_model_llamacpp_version(
+    model_path: String,
+    version_path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let version_path = version_path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(path) = &version_path {
+        if !Path::new(path).is_dir() {
+            return Err("llama.cpp version folder does not exist".into());
+        }
+    }
+
+    {
+        let mut model_configs = state.model_configs.lock().await;
+        let mut config = model_configs.get(&model_path)
+            .cloned()
+            .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
+        config.pinned_llamacpp_version = version_path.clone();
+        model_configs.insert(model_path, config);
+    }
+
+    if let Err(e) = save_settings(&state).await {
+        return Err(i18n::t("settings.save_failed", &[("error", &e.to_string())]));
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "pinned_llamacpp_version": version_path,
+    }))
+}
+
+// ==================== HuggingFace Direct Link Download Commands ====================
+
+#[tauri::command]
+async fn parse_hf_url(url: String) -> Result<String, String> {
+    huggingface_downloader::parse_model_id(&url)
+}
+
+#[tauri::command]
+async fn fetch_hf_model_info(model_id: String) -> Result<ModelCardInfo, String> {
+    huggingface_downloader::fetch_model_info(&model_id).await
+}
+
+#[tauri::command]
+async fn fetch_hf_model_files(model_id: String) -> Result<Vec<HfFileInfo>, String> {
+    huggingface_downloader::fetch_model_files(&model_id).await
+}
+
+/// Architecture, parameters, layers and context of a repo, fetched only when asked for
+#[tauri::command]
+async fn fetch_hf_model_preview(model_id: String) -> Result<Option<GgufPreview>, String> {
+    let files = huggingface_downloader::fetch_model_files(&model_id).await?;
+    Ok(huggingface_downloader::fetch_model_preview(&model_id, &files).await)
+}
+
+#[tauri::command]
+async fn get_default_download_path(
+    model_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let config = state.config.lock().await;
+    let base_dir = &config.models_directory;
+    let path = huggingface_downloader::build_destination_path(base_dir, &model_id);
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+async fn download_hf_file(
+    model_id: String,
+    filename: String,
+    destination: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<DownloadStartResult, String> {
+    use downloader::{DownloadConfig, start_download};
+    use std::path::Path;
+    
+    // Ensure destination directory exists
+    let dest_path = Path::new(&destination);
+    if let Some(parent) = dest_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory: {}", e))?;
     }
     
     // Build download configuration
     let config = DownloadConfig {
-        base_url: download_url.clone(),
+        base_url: format!("https://huggingface.co/{}/resolve/main", model_id),
         destination_folder: destination.clone(),
         auto_extract: false,
         create_subfolder: None,
         files: vec![filename.clone()],
         custom_headers: None,
+        expected_sha256: None,
     };
     
     // Use existing download infrastructure
     match start_download(config, &state, app_handle).await {
         Ok(result) => Ok(result),
-        Err(e) => Err(format!("Failed to start download: {}", e))
+        Err(e) => Err(i18n::t("download.start_failed", &[("error", &e.to_string())]))
     }
 }
 
@@ -3308,7 +5614,7 @@ async fn initialize_app_state(app_data_dir: std::path::PathBuf) -> Result<AppSta
     // Initialize tracker manager
     {
         let tracker_dir = app_data_dir.join("tracker");
-        match TrackerManager::new(tracker_dir) {
+        match TrackerDb::open(tracker_dir) {
             Ok(manager) => {
                 let mut tracker = state.tracker_manager.lock().await;
                 *tracker = Some(manager);
@@ -3321,6 +5627,13 @@ async fn initialize_app_state(app_data_dir: std::path::PathBuf) -> Result<AppSta
     }
     
     load_settings(&state).await?;
+    github_token::migrate(&state).await;
+
+    // Finished downloads from earlier sessions
+    match storage::run(|db| db.downloads().history(200)).await {
+        Ok(history) => state.download_manager.lock().await.download_history = history,
+        Err(e) => eprintln!("[Storage] Failed to load download history: {}", e),
+    }
 
     // Create models and executable directories if they don't exist
     {
@@ -3356,21 +5669,6 @@ async fn initialize_app_state(app_data_dir: std::path::PathBuf) -> Result<AppSta
         }
     }
     
-    // Cleanup leftover download files from previous sessions
-    {
-        let config = state.config.lock().await;
-        let mut all_directories = vec![config.models_directory.clone()];
-        all_directories.extend(config.additional_models_directories.clone());
-        
-        for dir in &all_directories {
-            if !dir.is_empty() {
-                if let Err(e) = huggingface::cleanup_leftover_downloads(dir).await {
-                    eprintln!("Warning: Failed to cleanup leftover downloads in '{}': {}", dir, e);
-                }
-            }
-        }
-    }
-    
     // Initialize peer model cache for persistent storage of discovered peer models
     {
         let cache = Arc::new(PeerModelCache::new(app_data_dir.clone()).await);
@@ -3382,61 +5680,261 @@ async fn initialize_app_state(app_data_dir: std::path::PathBuf) -> Result<AppSta
 }
 
 #[tauri::command]
-async fn get_tracker_models(
-    vram_limit: Option<f64>,
-    categories: Option<Vec<String>>,
-    chinese_only: bool,
-    gguf_only: bool,
-    file_types: Option<Vec<String>>,
-    quantizations: Option<Vec<String>>,
-    search: Option<String>,
-    sort_by: Option<String>,
-    sort_desc: Option<bool>,
-    state: tauri::State<'_, AppState>,
-) -> Result<Vec<TrackerModel>, String> {
-    let tracker = state.tracker_manager.lock().await;
-    let manager = tracker.as_ref().ok_or("Tracker not initialized")?;
-    
-    manager.get_models(
-        vram_limit,
-        categories,
-        chinese_only,
-        gguf_only,
-        file_types,
-        quantizations,
-        search,
-        &sort_by.unwrap_or_else(|| "downloads".to_string()),
-        sort_desc.unwrap_or(true),
-    )
+#[allow(clippy::too_many_arguments)]
+async fn get_tracker_models(
+    vram_limit: Option<f64>,
+    categories: Option<Vec<String>>,
+    chinese_only: bool,
+    gguf_only: bool,
+    file_types: Option<Vec<String>>,
+    quantizations: Option<Vec<String>>,
+    search: Option<String>,
+    sort_by: Option<String>,
+    sort_desc: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<TrackerModel>, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+    
+    tracker.run(move |manager| manager.get_models(
+        vram_limit,
+        categories,
+        chinese_only,
+        gguf_only,
+        file_types,
+        quantizations,
+        search,
+        &sort_by.unwrap_or_else(|| "downloads".to_string()),
+        sort_desc.unwrap_or(true),
+    )).await
+}
+
+#[tauri::command]
+async fn refresh_tracker_data(
+    state: tauri::State<'_, AppState>,
+    _app_handle: tauri::AppHandle,
+) -> Result<TrackerStats, String> {
+    let scraper = TrackerScraper::new();
+    
+    let models = scraper.fetch_trending_models(100).await?;
+    
+    let tracker = TrackerDb::from_state(&state).await?;
+    let limit = models.len();
+    
+    // Models that dropped out of the last few refreshes are pruned so counts stay accurate
+    let (stats, config) = tracker.run(move |manager| {
+        manager.save_models(&models)?;
+        manager.record_scrape(&models)?;
+        let config = manager.get_config()?;
+        if let Err(e) = manager.maintain(config.keep_scrapes, false) {
+            eprintln!("[Tracker] Maintenance failed: {}", e);
+        }
+        Ok((manager.get_stats()?, config))
+    }).await?;
+
+    if config.auto_classify {
+        let classify_state = state.inner().clone();
+        tokio::spawn(async move {
+            if let Err(e) = tracker_classify::classify(&classify_state, None, limit).await {
+                eprintln!("[Tracker] Auto-classification skipped: {}", e);
+            }
+        });
+    }
+    
+    Ok(stats)
+}
+
+#[tauri::command]
+async fn export_tracker_json(
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+    
+    tracker.run(|manager| manager.export_json()).await
+}
+
+#[tauri::command]
+async fn import_tracker_json(
+    json: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<usize, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+
+    tracker.run(move |manager| manager.import_json(&json)).await
+}
+
+/// Classify unclassified tracker models with a running model (the first one unless
+/// `model_path` names another)
+#[tauri::command]
+async fn classify_tracker_models(
+    limit: Option<u32>,
+    model_path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    tracker_classify::classify(&state, model_path.as_deref(), limit.unwrap_or(50) as usize).await
+}
+
+/// Manually set a tracker model's category; None clears it for re-classification
+#[tauri::command]
+async fn set_tracker_model_category(
+    model_id: String,
+    category: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    let category = match category.as_deref().filter(|c| !c.trim().is_empty()) {
+        Some(raw) => Some(tracker_classify::normalize_category(raw).ok_or_else(|| {
+            format!("Unknown category '{}'; use one of {}", raw, tracker_classify::CATEGORIES.join(", "))
+        })?),
+        None => None,
+    };
+    let tracker = TrackerDb::from_state(&state).await?;
+    tracker.run(move |manager| manager.set_manual_category(&model_id, category)).await
+}
+
+#[tauri::command]
+async fn get_tracker_db_info(state: tauri::State<'_, AppState>) -> Result<TrackerDbInfo, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+    tracker.run(|manager| manager.db_info()).await
+}
+
+/// Drop models missing from the last `keep_scrapes` refreshes (the configured count by default)
+#[tauri::command]
+async fn prune_tracker_models(
+    keep_scrapes: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<TrackerDbInfo, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+    tracker.run(move |manager| {
+        let keep_scrapes = match keep_scrapes {
+            Some(keep) => keep,
+            None => manager.get_config()?.keep_scrapes,
+        };
+        let pruned = manager.prune_unseen(keep_scrapes)?;
+        let orphans_removed = manager.remove_orphans()?;
+        Ok(TrackerDbInfo { pruned, orphans_removed, ..manager.db_info()? })
+    }).await
+}
+
+#[tauri::command]
+async fn dedupe_tracker_models(state: tauri::State<'_, AppState>) -> Result<TrackerDbInfo, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+    tracker.run(|manager| {
+        let deduplicated = manager.dedupe()?;
+        let orphans_removed = manager.remove_orphans()?;
+        Ok(TrackerDbInfo { deduplicated, orphans_removed, ..manager.db_info()? })
+    }).await
+}
+
+#[tauri::command]
+async fn vacuum_tracker_db(state: tauri::State<'_, AppState>) -> Result<TrackerDbInfo, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+    tracker.run(|manager| {
+        manager.vacuum()?;
+        Ok(TrackerDbInfo { vacuumed: true, ..manager.db_info()? })
+    }).await
 }
 
+/// A tracker model's real GGUF files and quantizations, fetched on first expand and
+/// cached for a day (or until `refresh`)
 #[tauri::command]
-async fn refresh_tracker_data(
+async fn get_tracker_model_files(
+    model_id: String,
+    refresh: Option<bool>,
     state: tauri::State<'_, AppState>,
-    _app_handle: tauri::AppHandle,
-) -> Result<TrackerStats, String> {
-    let scraper = TrackerScraper::new();
-    
-    let models = scraper.fetch_trending_models(100).await?;
-    
-    let tracker = state.tracker_manager.lock().await;
-    let manager = tracker.as_ref().ok_or("Tracker not initialized")?;
-    
-    // Clear existing models before saving new ones to ensure counts are accurate
-    manager.clear_models()?;
-    manager.save_models(&models)?;
-    
-    manager.get_stats()
+) -> Result<TrackerFileList, String> {
+    const MAX_AGE_HOURS: i64 = 24;
+    let tracker = TrackerDb::from_state(&state).await?;
+    if !refresh.unwrap_or(false) {
+        let id = model_id.clone();
+        if let Some(cached) = tracker.run(move |manager| manager.get_model_files(&id)).await? {
+            let fresh = chrono::DateTime::parse_from_rfc3339(&cached.fetched_at)
+                .map(|at| chrono::Utc::now().signed_duration_since(at).num_hours() < MAX_AGE_HOURS)
+                .unwrap_or(false);
+            if fresh {
+                return Ok(cached);
+            }
+        }
+    }
+
+    let files = TrackerScraper::new().fetch_file_list(&model_id).await?;
+    tracker.run(move |manager| manager.save_model_files(&model_id, &files)).await
 }
 
+/// Curated list JSON of the given models (the whole watchlist when omitted)
 #[tauri::command]
-async fn export_tracker_json(
+async fn export_curated_list(
+    title: String,
+    description: Option<String>,
+    curator_name: String,
+    curator_url: Option<String>,
+    model_ids: Option<Vec<String>>,
     state: tauri::State<'_, AppState>,
 ) -> Result<String, String> {
-    let tracker = state.tracker_manager.lock().await;
-    let manager = tracker.as_ref().ok_or("Tracker not initialized")?;
-    
-    manager.export_json()
+    if title.trim().is_empty() || curator_name.trim().is_empty() {
+        return Err("Title and curator name are required".to_string());
+    }
+    let tracker = TrackerDb::from_state(&state).await?;
+
+    let (mut watchlist, tracked) = tracker.run(|manager| {
+        Ok((manager.get_watchlist()?, manager.get_models(None, None, false, false, None, None, None, "downloads", true)?))
+    }).await?;
+    if let Some(ids) = model_ids {
+        let known: HashSet<String> = watchlist.iter().map(|e| e.model_id.clone()).collect();
+        watchlist.retain(|e| ids.contains(&e.model_id));
+        watchlist.extend(ids.into_iter().filter(|id| !known.contains(id)).map(|model_id| WatchlistEntry {
+            model_id,
+            source: "manual".to_string(),
+            collection_slug: None,
+            note: None,
+            added_at: String::new(),
+        }));
+    }
+    let curator = curated_list::Curator { name: curator_name.trim().to_string(), url: curator_url };
+    let list = curated_list::build(&title, description, curator, &watchlist, &tracked);
+    serde_json::to_string_pretty(&list).map_err(|e| format!("Failed to serialize curated list: {}", e))
+}
+
+/// Add a curated list to the watchlist; with `download` also fetch the files it names, once
+/// `confirmed` after the same pack summary `install_model_pack` shows
+#[tauri::command]
+async fn import_curated_list(
+    list: String,
+    download: Option<bool>,
+    confirmed: Option<bool>,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<serde_json::Value, String> {
+    let list = curated_list::parse(&list)?;
+    let entries = curated_list::watchlist_entries(&list);
+    let entry_count = entries.len();
+    let added = {
+        let tracker = TrackerDb::from_state(&state).await?;
+        let snapshots: Vec<TrackerModel> = list.models.iter().filter_map(|m| m.model.clone()).collect();
+        tracker.run(move |manager| {
+            manager.save_models(&snapshots)?;
+            manager.add_to_watchlist(&entries)
+        }).await?
+    };
+
+    let downloads = match curated_list::as_pack(&list).filter(|_| download.unwrap_or(false)) {
+        Some(pack) => {
+            let manifest = serde_json::to_string(&pack).map_err(|e| format!("Failed to build download pack: {}", e))?;
+            let pack = model_pack::load_manifest(&manifest)?;
+            // Same confirmation as `install_model_pack`; the manifest lets the UI confirm there
+            let mut result = model_pack::install_if_confirmed(&state, app_handle, &pack, confirmed.unwrap_or(false)).await?;
+            result["manifest"] = serde_json::Value::String(manifest);
+            Some(result)
+        }
+        None => None,
+    };
+
+    Ok(serde_json::json!({
+        "success": true,
+        "title": list.title,
+        "curator": list.curator,
+        "added": added,
+        "already_watched": entry_count - added,
+        "downloads": downloads,
+    }))
 }
 
 #[tauri::command]
@@ -3455,20 +5953,18 @@ async fn get_tracker_live_results(
 async fn get_tracker_stats(
     state: tauri::State<'_, AppState>,
 ) -> Result<TrackerStats, String> {
-    let tracker = state.tracker_manager.lock().await;
-    let manager = tracker.as_ref().ok_or("Tracker not initialized")?;
+    let tracker = TrackerDb::from_state(&state).await?;
     
-    manager.get_stats()
+    tracker.run(|manager| manager.get_stats()).await
 }
 
 #[tauri::command]
 async fn get_tracker_config(
     state: tauri::State<'_, AppState>,
 ) -> Result<TrackerConfig, String> {
-    let tracker = state.tracker_manager.lock().await;
-    let manager = tracker.as_ref().ok_or("Tracker not initialized")?;
+    let tracker = TrackerDb::from_state(&state).await?;
     
-    manager.get_config()
+    tracker.run(|manager| manager.get_config()).await
 }
 
 #[tauri::command]
@@ -3476,43 +5972,157 @@ async fn update_tracker_config(
     config: TrackerConfig,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
-    let tracker = state.tracker_manager.lock().await;
-    let manager = tracker.as_ref().ok_or("Tracker not initialized")?;
+    let tracker = TrackerDb::from_state(&state).await?;
     
-    manager.save_config(&config)
+    tracker.run(move |manager| manager.save_config(&config)).await
 }
 
 #[tauri::command]
 async fn get_weekly_reports(
     state: tauri::State<'_, AppState>,
 ) -> Result<Vec<WeeklyReport>, String> {
-    let tracker = state.tracker_manager.lock().await;
-    let manager = tracker.as_ref().ok_or("Tracker not initialized")?;
+    let tracker = TrackerDb::from_state(&state).await?;
     
-    manager.get_weekly_reports(4)
+    tracker.run(|manager| manager.get_weekly_reports(4)).await
 }
 
 #[tauri::command]
 async fn generate_weekly_report(
     state: tauri::State<'_, AppState>,
 ) -> Result<WeeklyReport, String> {
-    let tracker = state.tracker_manager.lock().await;
-    let manager = tracker.as_ref().ok_or("Tracker not initialized")?;
+    let tracker = TrackerDb::from_state(&state).await?;
     
-    manager.generate_weekly_report()
+    tracker.run(|manager| manager.generate_weekly_report()).await
+}
+
+#[tauri::command]
+async fn get_hf_rate_metrics() -> Result<hf_client::HfRateMetrics, String> {
+    Ok(hf_client::metrics())
+}
+
+#[tauri::command]
+async fn list_hf_collections(owner: String) -> Result<Vec<HfCollection>, String> {
+    TrackerScraper::new().fetch_collections(&owner).await
 }
 
 #[tauri::command]
+async fn get_hf_collection(slug: String) -> Result<HfCollection, String> {
+    TrackerScraper::new().fetch_collection(&slug).await
+}
+
+/// Add every model in a collection to the tracker watchlist. Datasets, spaces and
+/// papers in the collection are reported as skipped.
+#[tauri::command]
+async fn import_hf_collection_to_watchlist(
+    slug: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let collection = TrackerScraper::new().fetch_collection(&slug).await?;
+
+    let added_at = Utc::now().to_rfc3339();
+    let (models, skipped): (Vec<_>, Vec<_>) = collection
+        .items
+        .iter()
+        .partition(|item| item.item_type == "model");
+    let entries: Vec<WatchlistEntry> = models
+        .iter()
+        .map(|item| WatchlistEntry {
+            model_id: item.id.clone(),
+            source: "collection".to_string(),
+            collection_slug: Some(collection.slug.clone()),
+            note: item.note.clone(),
+            added_at: added_at.clone(),
+        })
+        .collect();
+
+    let tracker = TrackerDb::from_state(&state).await?;
+    let entry_count = entries.len();
+    let added = tracker.run(move |manager| manager.add_to_watchlist(&entries)).await?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "collection": collection.title,
+        "added": added,
+        "already_watched": entry_count - added,
+        "skipped": skipped.iter().map(|item| serde_json::json!({
+            "id": item.id,
+            "type": item.item_type,
+        })).collect::<Vec<_>>(),
+    }))
+}
+
+#[tauri::command]
+async fn get_tracker_watchlist(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<WatchlistEntry>, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+
+    tracker.run(|manager| manager.get_watchlist()).await
+}
+
+#[tauri::command]
+async fn add_to_tracker_watchlist(
+    model_id: String,
+    note: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, String> {
+    let model_id = model_id.trim().to_string();
+    if model_id.is_empty() {
+        return Err("Model id is required".to_string());
+    }
+
+    let tracker = TrackerDb::from_state(&state).await?;
+
+    let entry = WatchlistEntry {
+        model_id,
+        source: "manual".to_string(),
+        collection_slug: None,
+        note,
+        added_at: Utc::now().to_rfc3339(),
+    };
+    let added = tracker.run(move |manager| manager.add_to_watchlist(&[entry])).await?;
+    Ok(added > 0)
+}
+
+#[tauri::command]
+async fn remove_from_tracker_watchlist(
+    model_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<bool, String> {
+    let tracker = TrackerDb::from_state(&state).await?;
+
+    tracker.run(move |manager| manager.remove_from_watchlist(&model_id)).await
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 async fn save_network_config(
     address: String,
     port: u16,
     proxy_port: u16,
+    rate_limit_per_minute: Option<u32>,
+    max_body_mb: Option<u32>,
+    read_timeout_secs: Option<u64>,
+    write_timeout_secs: Option<u64>,
     state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     let mut config = state.config.lock().await;
     config.network_server_host = address.clone();
     config.network_server_port = port;
     config.openai_proxy_port = proxy_port;
+    // Limits take effect the next time the network server is activated
+    if let Some(value) = rate_limit_per_minute {
+        config.network_rate_limit_per_minute = value;
+    }
+    if let Some(value) = max_body_mb {
+        config.network_max_body_mb = value.max(1);
+    }
+    if let Some(value) = read_timeout_secs {
+        config.network_read_timeout_secs = value.max(1);
+    }
+    if let Some(value) = write_timeout_secs {
+        config.network_write_timeout_secs = value.max(1);
+    }
     
     drop(config);
     
@@ -3534,6 +6144,14 @@ async fn get_network_config(
         "port": config.network_server_port,
         "proxy_port": config.openai_proxy_port,
         "enabled": config.openai_proxy_enabled,
+        "limits": {
+            "rate_limit_per_minute": config.network_rate_limit_per_minute,
+            "max_body_mb": config.network_max_body_mb,
+            "read_timeout_secs": config.network_read_timeout_secs,
+            "write_timeout_secs": config.network_write_timeout_secs,
+        },
+        "allowed_cidrs": config.network_allowed_cidrs,
+        "api_keys_required": !config.network_api_keys.is_empty(),
     }))
 }
 
@@ -3555,13 +6173,13 @@ async fn get_network_interfaces() -> Result<serde_json::Value, String> {
     ];
     
     // Try to get local IP addresses using socket connection trick
-    match tokio::task::spawn_blocking(|| {
+    if let Ok(Some(ip)) = tokio::task::spawn_blocking(|| {
         let socket = std::net::UdpSocket::bind("0.0.0.0:0");
         if let Ok(socket) = socket {
             // Connect to a public DNS server to determine our outbound interface
             if socket.connect("8.8.8.8:80").is_ok() {
                 if let Ok(local_addr) = socket.local_addr() {
-                    if let Some(ip) = local_addr.ip().to_string().parse::<Ipv4Addr>().ok() {
+                    if let Ok(ip) = local_addr.ip().to_string().parse::<Ipv4Addr>() {
                         // Don't add loopback again
                         if !ip.is_loopback() {
                             return Some(ip.to_string());
@@ -3572,14 +6190,11 @@ async fn get_network_interfaces() -> Result<serde_json::Value, String> {
         }
         None
     }).await {
-        Ok(Some(ip)) => {
-            interfaces.push(serde_json::json!({
-                "address": ip,
-                "name": "Primary Network Interface",
-                "type": "primary"
-            }));
-        }
-        _ => {}
+        interfaces.push(serde_json::json!({
+            "address": ip,
+            "name": "Primary Network Interface",
+            "type": "primary"
+        }));
     }
     
     // The primary interface detection above should catch most cases
@@ -3653,12 +6268,17 @@ async fn ensure_network_server_running_for_discovery(
         active_models: state.active_models.clone(),
         peer_model_cache: state.peer_model_cache.clone(),
         fake_discovery_model_enabled: state.fake_discovery_model_enabled.clone(),
+        tunnels: state.tunnels.clone(),
+        launch_queue: state.launch_queue.clone(),
+        suspended_processes: state.suspended_processes.clone(),
+        parameter_controllers: state.parameter_controllers.clone(),
+        restarting_ports: state.restarting_ports.clone(),
     });
 
     new_proxy
         .start(app_state_arc)
         .await
-        .map_err(|e| format!("Failed to start proxy: {}", e))?;
+        .map_err(|e| i18n::t("proxy.start_failed", &[("error", &e.to_string())]))?;
 
     {
         let mut proxy = state.openai_proxy.lock().await;
@@ -3673,7 +6293,7 @@ async fn ensure_network_server_running_for_discovery(
         config.openai_proxy_port = api_port;
     }
 
-    if let Err(e) = save_settings(&state).await {
+    if let Err(e) = save_settings(state).await {
         eprintln!("[Discovery] Warning: failed to save settings after auto-start network server: {}", e);
     }
 
@@ -3751,7 +6371,7 @@ async fn start_discovery_service(
         config.discovery_instance_id = instance_id;
     }
 
-    if let Err(e) = save_settings(&state).await {
+    if let Err(e) = save_settings(state).await {
         eprintln!("[Discovery] Warning: failed to save settings after auto-start discovery: {}", e);
     }
 
@@ -3888,6 +6508,11 @@ let mut new_proxy = openai_proxy::ProxyServer::new(
         active_models: state.active_models.clone(),
         peer_model_cache: state.peer_model_cache.clone(),
         fake_discovery_model_enabled: state.fake_discovery_model_enabled.clone(),
+        tunnels: state.tunnels.clone(),
+        launch_queue: state.launch_queue.clone(),
+        suspended_processes: state.suspended_processes.clone(),
+        parameter_controllers: state.parameter_controllers.clone(),
+        restarting_ports: state.restarting_ports.clone(),
     });
 
     match new_proxy.start(app_state_arc).await {
@@ -3909,7 +6534,7 @@ let mut new_proxy = openai_proxy::ProxyServer::new(
                 "message": format!("OpenAI proxy server activated on port {}", proxy_port)
             }))
         }
-        Err(e) => Err(format!("Failed to start proxy: {}", e)),
+        Err(e) => Err(i18n::t("proxy.start_failed", &[("error", &e.to_string())])),
     }
 }
 
@@ -3922,6 +6547,11 @@ async fn deactivate_network_server(
     if let Some(ref mut p) = *proxy {
         p.stop().await;
         *proxy = None;
+
+        if let Err(e) = port_mapping::remove_active_mapping().await {
+            eprintln!("[PortMapping] Failed to remove port mapping: {}", e);
+        }
+        tunnels::stop_all_tunnels(&state).await;
         
         let mut config = state.config.lock().await;
         config.openai_proxy_enabled = false;
@@ -3957,6 +6587,310 @@ async fn get_network_server_status(
     }))
 }
 
+#[tauri::command]
+async fn get_openapi_document(
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let config = state.config.lock().await;
+    let server_url = format!("http://{}:{}", config.network_server_host, config.openai_proxy_port);
+    Ok(openapi_spec::build_openapi_document(Some(&server_url)))
+}
+
+#[tauri::command]
+async fn get_network_clients(
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let clients = {
+        let proxy = state.openai_proxy.lock().await;
+        proxy
+            .as_ref()
+            .and_then(|server| server.guard())
+            .map(|guard| guard.clients.snapshot())
+            .unwrap_or_default()
+    };
+    let blocked = state.config.lock().await.network_blocked_clients.clone();
+
+    Ok(serde_json::json!({
+        "clients": clients,
+        "blocked": blocked,
+    }))
+}
+
+#[tauri::command]
+async fn set_network_client_blocked(
+    ip: String,
+    blocked: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let parsed: std::net::IpAddr = ip
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid IP address: {}", ip))?;
+    let ip_str = parsed.to_string();
+
+    {
+        let mut config = state.config.lock().await;
+        config.network_blocked_clients.retain(|existing| existing != &ip_str);
+        if blocked {
+            config.network_blocked_clients.push(ip_str.clone());
+        }
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save config: {}", e))?;
+
+    // Apply to the running server immediately
+    if let Some(guard) = state.openai_proxy.lock().await.as_ref().and_then(|server| server.guard()) {
+        guard.clients.set_blocked(parsed, blocked);
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "ip": ip_str,
+        "blocked": blocked,
+    }))
+}
+
+#[tauri::command]
+async fn save_network_allowlist(
+    cidrs: Vec<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let networks = proxy_guard::parse_allowlist(&cidrs)?;
+    let normalized: Vec<String> = networks.iter().map(|network| network.to_string()).collect();
+
+    {
+        let mut config = state.config.lock().await;
+        config.network_allowed_cidrs = normalized.clone();
+    }
+    save_settings(&state).await.map_err(|e| format!("Failed to save config: {}", e))?;
+
+    if let Some(guard) = state.openai_proxy.lock().await.as_ref().and_then(|server| server.guard()) {
+        guard.set_allowlist(networks);
+    }
+
+    let mut warnings = Vec::new();
+    if normalized.is_empty() {
+        warnings.push("Allowlist is empty: any address that can reach this machine may use the network server".to_string());
+    } else if normalized.iter().any(|cidr| cidr.ends_with("/0")) {
+        warnings.push("Allowlist contains a /0 network, which admits every address".to_string());
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "cidrs": normalized,
+        "warnings": warnings,
+    }))
+}
+
+/// Save the API key list and hand it to the running network server
+async fn store_network_api_keys(
+    state: &tauri::State<'_, AppState>,
+    update: impl FnOnce(&mut Vec<models::ProxyApiKey>) -> Result<(), String>,
+) -> Result<Vec<models::ProxyApiKey>, String> {
+    let api_keys = {
+        let mut config = state.config.lock().await;
+        update(&mut config.network_api_keys)?;
+        config.network_api_keys.clone()
+    };
+    save_settings(state).await.map_err(|e| format!("Failed to save config: {}", e))?;
+    if let Some(guard) = state.openai_proxy.lock().await.as_ref().and_then(|server| server.guard()) {
+        guard.set_api_keys(api_keys.clone());
+    }
+    Ok(api_keys)
+}
+
+#[tauri::command]
+async fn get_network_api_keys(state: tauri::State<'_, AppState>) -> Result<Vec<models::ProxyApiKey>, String> {
+    Ok(state.config.lock().await.network_api_keys.clone())
+}
+
+/// Create an API key; the key is returned only here
+#[tauri::command]
+async fn create_network_api_key(
+    name: String,
+    requests_per_minute: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    if name.trim().is_empty() {
+        return Err("API key name is required".to_string());
+    }
+    let (key, entry) = proxy_guard::new_api_key(&name, requests_per_minute.unwrap_or(0));
+    let stored = entry.clone();
+    let api_keys = store_network_api_keys(&state, move |keys| {
+        keys.push(stored);
+        Ok(())
+    })
+    .await?;
+
+    let mut warnings = Vec::new();
+    if api_keys.len() == 1 {
+        warnings.push("The network server now requires an API key; clients without one are refused".to_string());
+    }
+    Ok(serde_json::json!({
+        "success": true,
+        "key": key,
+        "api_key": entry,
+        "warnings": warnings,
+    }))
+}
+
+#[tauri::command]
+async fn update_network_api_key(
+    id: String,
+    name: Option<String>,
+    requests_per_minute: Option<u32>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<models::ProxyApiKey>, String> {
+    store_network_api_keys(&state, |keys| {
+        let key = keys.iter_mut().find(|k| k.id == id).ok_or_else(|| format!("API key {} not found", id))?;
+        if let Some(name) = name.filter(|n| !n.trim().is_empty()) {
+            key.name = name.trim().to_string();
+        }
+        if let Some(limit) = requests_per_minute {
+            key.requests_per_minute = limit;
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Bind an API key to a model and preset the proxy loads for that key's client; no model clears the binding
+#[tauri::command]
+async fn set_network_api_key_profile(
+    id: String,
+    model_path: Option<String>,
+    preset_id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<models::ProxyApiKey>, String> {
+    let profile = match model_path.filter(|p| !p.trim().is_empty()) {
+        Some(model_path) => {
+            if !std::path::Path::new(&model_path).is_file() {
+                return Err(format!("Model file not found: {}", model_path));
+            }
+            if let Some(preset_id) = &preset_id {
+                let model_configs = state.model_configs.lock().await;
+                let known = model_configs
+                    .get(&model_path)
+                    .is_some_and(|config| config.presets.iter().any(|p| &p.id == preset_id));
+                if !known {
+                    return Err(format!("Preset {} not found for {}", preset_id, model_path));
+                }
+            }
+            Some(models::KeyLaunchProfile { model_path, preset_id })
+        }
+        None => None,
+    };
+    store_network_api_keys(&state, |keys| {
+        let key = keys.iter_mut().find(|k| k.id == id).ok_or_else(|| format!("API key {} not found", id))?;
+        key.launch_profile = profile;
+        Ok(())
+    })
+    .await
+}
+
+/// Revoke an API key; removing the last one leaves the network server open
+#[tauri::command]
+async fn delete_network_api_key(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<models::ProxyApiKey>, String> {
+    store_network_api_keys(&state, |keys| {
+        let before = keys.len();
+        keys.retain(|k| k.id != id);
+        if keys.len() == before {
+            return Err(format!("API key {} not found", id));
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Opt-in: ask the router to forward the proxy port so the server is reachable from the internet
+#[tauri::command]
+async fn enable_port_forwarding(
+    confirm_internet_exposure: bool,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    if !confirm_internet_exposure {
+        return Err("Port forwarding exposes the network server to the internet and must be explicitly confirmed".to_string());
+    }
+    if state.openai_proxy.lock().await.is_none() {
+        return Err("Activate the network server before enabling port forwarding".to_string());
+    }
+    if state.config.lock().await.network_api_keys.is_empty() {
+        return Err("Create an API key for the network server before enabling port forwarding; without one anyone who finds the address can use it".to_string());
+    }
+
+    let proxy_port = state.config.lock().await.openai_proxy_port;
+    let mapping = port_mapping::request_port_mapping(proxy_port).await?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "external_address": mapping.external_address(),
+        "warnings": port_mapping::security_warnings(&mapping),
+        "mapping": mapping,
+    }))
+}
+
+#[tauri::command]
+async fn disable_port_forwarding() -> Result<serde_json::Value, String> {
+    let removed = port_mapping::remove_active_mapping().await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "removed": removed,
+    }))
+}
+
+#[tauri::command]
+async fn get_port_forwarding_status() -> Result<serde_json::Value, String> {
+    let mapping = port_mapping::active_mapping().await;
+    Ok(serde_json::json!({
+        "active": mapping.is_some(),
+        "external_address": mapping.as_ref().and_then(|m| m.external_address()),
+        "warnings": mapping.as_ref().map(port_mapping::security_warnings).unwrap_or_default(),
+        "mapping": mapping,
+    }))
+}
+
+/// Expose the network server through a `cloudflared` quick tunnel or `tailscale serve`
+#[tauri::command]
+async fn start_tunnel(
+    provider: String,
+    executable_path: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let provider = tunnels::TunnelProvider::parse(&provider)?;
+    if state.openai_proxy.lock().await.is_none() {
+        return Err("Activate the network server before starting a tunnel".to_string());
+    }
+
+    let proxy_port = state.config.lock().await.openai_proxy_port;
+    let tunnel = tunnels::start_tunnel(provider, executable_path, proxy_port, &state).await?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "tunnel": tunnel,
+    }))
+}
+
+#[tauri::command]
+async fn stop_tunnel(
+    tunnel_id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let tunnel = tunnels::stop_tunnel(&tunnel_id, &state).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "tunnel": tunnel,
+    }))
+}
+
+#[tauri::command]
+async fn list_tunnels(state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let mut list: Vec<tunnels::TunnelInfo> = state.tunnels.lock().await.values().cloned().collect();
+    list.sort_by_key(|tunnel| std::cmp::Reverse(tunnel.started_at));
+    Ok(serde_json::json!({ "tunnels": list }))
+}
+
 // ==================== Network Discovery Commands ====================
 
 #[tauri::command]
@@ -4012,7 +6946,7 @@ async fn enable_discovery(
     // Without this, beacons advertise port api_port but nothing listens there,
     // so peers get connection refused when fetching models.
     ensure_network_server_running_for_discovery(
-        &*state,
+        &state,
         bind_ip.clone(),
         chat_port,
         api_port,
@@ -4305,6 +7239,13 @@ async fn save_mcp_connection(
 
     match position {
         Some(index) => {
+            // The edit form does not send discovered resources and prompts
+            let previous = &config.mcp_servers[index];
+            if connection.resources.is_empty() && connection.prompts.is_empty() {
+                connection.resources = previous.resources.clone();
+                connection.prompts = previous.prompts.clone();
+                connection.catalog_last_refresh_at = previous.catalog_last_refresh_at.clone();
+            }
             config.mcp_servers[index] = connection.clone();
         }
         None => {
@@ -4321,6 +7262,30 @@ async fn save_mcp_connection(
     Ok(connection)
 }
 
+/// Add connections from a Claude Desktop style `mcpServers` JSON; one result per entry
+#[tauri::command]
+async fn import_mcp_servers(
+    json: String,
+    overwrite: Option<bool>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<mcp_import::ImportResult>, String> {
+    let mut config = state.config.lock().await;
+    let results = mcp_import::import_servers(&json, &mut config.mcp_servers, overwrite.unwrap_or(false))?;
+    drop(config);
+
+    let changed = results
+        .iter()
+        .filter(|r| matches!(r.status, mcp_import::ImportStatus::Imported | mcp_import::ImportStatus::Updated))
+        .count();
+    if changed > 0 {
+        save_settings(&state)
+            .await
+            .map_err(|e| format!("Failed to save MCP connections: {}", e))?;
+    }
+    println!("[MCP] Imported {} of {} server(s) from JSON", changed, results.len());
+    Ok(results)
+}
+
 fn validate_mcp_connection_payload(connection: &McpServerConfig) -> Result<(), String> {
     if connection.name.trim().is_empty() {
         return Err("MCP connection name is required".to_string());
@@ -4572,6 +7537,8 @@ fn parse_mcp_tools_from_response(response: &serde_json::Value) -> Result<Vec<Mcp
                     .get("outputSchema")
                     .or_else(|| tool.get("output_schema"))
                     .cloned(),
+                enabled: true,
+                argument_presets: serde_json::Map::new(),
             })
         })
         .collect::<Vec<_>>();
@@ -4579,6 +7546,37 @@ fn parse_mcp_tools_from_response(response: &serde_json::Value) -> Result<Vec<Mcp
     Ok(parsed)
 }
 
+/// Carry the user's enabled flags and presets over to freshly discovered tools
+fn keep_mcp_tool_settings(previous: &[McpToolInfo], discovered: Vec<McpToolInfo>) -> Vec<McpToolInfo> {
+    discovered
+        .into_iter()
+        .map(|tool| match previous.iter().find(|old| old.name == tool.name) {
+            Some(old) => McpToolInfo { enabled: old.enabled, argument_presets: old.argument_presets.clone(), ..tool },
+            None => tool,
+        })
+        .collect()
+}
+
+/// Arguments for a call of `tool`, presets filling the gaps; refused when the tool is disabled
+fn mcp_tool_call_arguments(tool: Option<&McpToolInfo>, arguments: serde_json::Value) -> Result<serde_json::Value, String> {
+    let Some(tool) = tool else { return Ok(arguments) };
+    if !tool.enabled {
+        return Err(format!("Tool '{}' is disabled for this connection", tool.name));
+    }
+    if tool.argument_presets.is_empty() {
+        return Ok(arguments);
+    }
+    let mut merged = match arguments {
+        serde_json::Value::Object(map) => map,
+        serde_json::Value::Null => serde_json::Map::new(),
+        _ => return Err("Tool arguments must be a JSON object".to_string()),
+    };
+    for (key, value) in &tool.argument_presets {
+        merged.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+    Ok(serde_json::Value::Object(merged))
+}
+
 fn parse_mcp_json_error_message(response: &serde_json::Value) -> Option<String> {
     let error = response.get("error")?;
     if let Some(message) = error.get("message").and_then(|value| value.as_str()) {
@@ -4697,6 +7695,8 @@ async fn execute_stdio_mcp_request(
             command = shell_command;
         }
     }
+    // Reassigned by the Windows fallbacks below
+    #[cfg_attr(not(windows), allow(unused_mut))]
     let mut spawned = command.spawn();
 
     #[cfg(windows)]
@@ -4835,6 +7835,22 @@ fn mcp_accept_header(transport: &McpTransport) -> &'static str {
     }
 }
 
+/// The connection's headers (including its `Authorization`), skipping blank ones
+fn with_mcp_headers(
+    mut request: reqwest::RequestBuilder,
+    headers: &std::collections::HashMap<String, String>,
+) -> reqwest::RequestBuilder {
+    for (key, value) in headers {
+        let header_name = key.trim();
+        let header_value = value.trim();
+        if header_name.is_empty() || header_value.is_empty() {
+            continue;
+        }
+        request = request.header(header_name, header_value);
+    }
+    request
+}
+
 async fn post_mcp_request(
     client: &reqwest::Client,
     transport: &McpTransport,
@@ -4843,19 +7859,11 @@ async fn post_mcp_request(
     headers: &std::collections::HashMap<String, String>,
     timeout_duration: Duration,
 ) -> Result<reqwest::Response, String> {
-    let mut request = client
+    let request = client
         .post(url)
         .header("accept", mcp_accept_header(transport))
         .json(&payload);
-
-    for (key, value) in headers {
-        let header_name = key.trim();
-        let header_value = value.trim();
-        if header_name.is_empty() || header_value.is_empty() {
-            continue;
-        }
-        request = request.header(header_name, header_value);
-    }
+    let request = with_mcp_headers(request, headers);
 
     timeout(timeout_duration, request.send())
         .await
@@ -4870,7 +7878,7 @@ async fn run_mcp_tool_discovery(
     timeout_duration: Duration,
 ) -> McpToolsResult {
     let start_time = Instant::now();
-    let client = reqwest::Client::new();
+    let client = http_pool::client_for(&url);
 
     let initialize_payload = default_mcp_initialize_payload();
     let tools_payload = serde_json::json!({
@@ -4895,22 +7903,19 @@ async fn run_mcp_tool_discovery(
         }
     };
 
-    match initialize_response.status().is_success() {
-        false => {
-            return McpToolsResult {
-                success: false,
-                latency_ms: start_time.elapsed().as_millis() as i64,
-                message: format!(
-                    "Initialize request returned HTTP {}",
-                    initialize_response.status()
-                ),
-                tool_count: 0,
-                tools: Vec::new(),
-                status_code: Some(initialize_response.status().as_u16()),
-                error: Some("initialize_failed".to_string()),
-            };
-        }
-        true => {}
+    if !initialize_response.status().is_success() {
+        return McpToolsResult {
+            success: false,
+            latency_ms: start_time.elapsed().as_millis() as i64,
+            message: format!(
+                "Initialize request returned HTTP {}",
+                initialize_response.status()
+            ),
+            tool_count: 0,
+            tools: Vec::new(),
+            status_code: Some(initialize_response.status().as_u16()),
+            error: Some("initialize_failed".to_string()),
+        };
     }
 
     let tools_response = match post_mcp_request(&client, &transport, &url, tools_payload, &headers, timeout_duration).await {
@@ -5000,6 +8005,11 @@ async fn delete_mcp_connection(
     }
 
     drop(config);
+    let token_id = id.clone();
+    if let Err(e) = tokio::task::spawn_blocking(move || mcp_auth::forget(&token_id)).await.unwrap_or_else(|e| Err(e.to_string())) {
+        eprintln!("[MCP] {}", e);
+    }
+    mcp_health::forget(&id).await;
 
     if let Err(e) = save_settings(&state).await {
         return Err(format!("Failed to save MCP connections: {}", e));
@@ -5011,6 +8021,7 @@ async fn delete_mcp_connection(
 #[tauri::command]
 async fn list_mcp_tools(
     id: String,
+    task_id: Option<String>,
     state: tauri::State<'_, AppState>,
 ) -> Result<McpToolsResult, String> {
     let start_time = Instant::now();
@@ -5043,22 +8054,25 @@ async fn list_mcp_tools(
     } else {
         connection.clone()
     };
+    let effective_connection = mcp_auth::authorized(effective_connection).await?;
     let stdio_timeout = if matches!(effective_connection.transport, McpTransport::Stdio) {
         Duration::from_secs(connection.timeout_seconds.max(1).max(45))
     } else {
         timeout_duration
     };
 
+    // A cancelled discovery leaves the last refresh status as it was
+    let task = task_manager::register("mcp_discovery", task_id, format!("List tools of {}", connection.name));
     if matches!(effective_connection.transport, McpTransport::Stdio) {
-        let stdio_result = execute_stdio_mcp_request(
+        let stdio_result = task.run(execute_stdio_mcp_request(
             &effective_connection,
             "tools/list",
             serde_json::json!({}),
             stdio_timeout,
-        )
-        .await;
+        ))
+        .await?;
 
-        let result = match stdio_result {
+        let mut result = match stdio_result {
             Ok(body) => match parse_mcp_tools_from_response(&body) {
                 Ok(tools) => McpToolsResult {
                     success: true,
@@ -5096,7 +8110,10 @@ async fn list_mcp_tools(
             conn.tools_last_status = Some(if result.success { "ok".to_string() } else { "error".to_string() });
             conn.tools_last_message = Some(result.message.clone());
             conn.tools_last_error = result.error.clone();
-            conn.tools = result.tools.clone();
+            if result.success {
+                result.tools = keep_mcp_tool_settings(&conn.tools, std::mem::take(&mut result.tools));
+                conn.tools = result.tools.clone();
+            }
         }
         drop(config);
         let _ = save_settings(&state).await;
@@ -5119,12 +8136,12 @@ async fn list_mcp_tools(
 
     let url = resolved_url.unwrap_or_default();
 
-    let result = run_mcp_tool_discovery(
+    let mut result = task.run(run_mcp_tool_discovery(
         effective_connection.transport.clone(),
         url,
         effective_connection.headers.clone(),
         timeout_duration,
-    ).await;
+    )).await?;
 
     let mut config = state.config.lock().await;
     if let Some(conn) = config.mcp_servers.iter_mut().find(|item| item.id == id) {
@@ -5132,7 +8149,10 @@ async fn list_mcp_tools(
         conn.tools_last_status = Some(if result.success { "ok".to_string() } else { "error".to_string() });
         conn.tools_last_message = Some(result.message.clone());
         conn.tools_last_error = result.error.clone();
-        conn.tools = result.tools.clone();
+        if result.success {
+            result.tools = keep_mcp_tool_settings(&conn.tools, std::mem::take(&mut result.tools));
+            conn.tools = result.tools.clone();
+        }
     }
 
     drop(config);
@@ -5141,6 +8161,111 @@ async fn list_mcp_tools(
     Ok(result)
 }
 
+/// Enable or disable one discovered tool and save its argument presets
+#[tauri::command]
+async fn set_mcp_tool_settings(
+    id: String,
+    tool_name: String,
+    enabled: bool,
+    argument_presets: Option<serde_json::Map<String, serde_json::Value>>,
+    state: tauri::State<'_, AppState>,
+) -> Result<McpToolInfo, String> {
+    let tool = {
+        let mut config = state.config.lock().await;
+        let connection = config
+            .mcp_servers
+            .iter_mut()
+            .find(|item| item.id == id)
+            .ok_or_else(|| "MCP connection not found".to_string())?;
+        let tool = connection
+            .tools
+            .iter_mut()
+            .find(|tool| tool.name == tool_name)
+            .ok_or_else(|| format!("Tool '{}' not found; refresh the tools list first", tool_name))?;
+        tool.enabled = enabled;
+        if let Some(presets) = argument_presets {
+            tool.argument_presets = presets;
+        }
+        tool.clone()
+    };
+
+    if let Err(e) = save_settings(&state).await {
+        return Err(format!("Failed to save MCP connections: {}", e));
+    }
+    Ok(tool)
+}
+
+/// Save (or with an empty token, remove) a connection's bearer token in the keyring
+#[tauri::command]
+async fn set_mcp_auth_token(id: String, token: String) -> Result<(), String> {
+    mcp_auth::set_bearer_token(id, token).await
+}
+
+#[tauri::command]
+async fn get_mcp_auth_status(id: String, state: tauri::State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let connection = {
+        let config = state.config.lock().await;
+        config.mcp_servers.iter().find(|item| item.id == id).cloned().ok_or_else(|| "MCP connection not found".to_string())?
+    };
+    mcp_auth::status(&connection).await
+}
+
+/// Start the OAuth device-code sign-in; the result arrives as `mcp-oauth-status`
+#[tauri::command]
+async fn start_mcp_oauth(
+    id: String,
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<mcp_auth::DeviceCode, String> {
+    mcp_auth::start_device_flow(&state, app_handle, &id).await
+}
+
+/// Uptime and recent failures from the health history, for one connection or all of them
+#[tauri::command]
+async fn get_mcp_health(
+    id: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<mcp_health::HealthSummary>, String> {
+    let ids: Vec<String> = match id {
+        Some(id) => vec![id],
+        None => state.config.lock().await.mcp_servers.iter().map(|c| c.id.clone()).collect(),
+    };
+    let mut summaries = Vec::with_capacity(ids.len());
+    for id in ids {
+        summaries.push(mcp_health::health(&id).await?);
+    }
+    Ok(summaries)
+}
+
+/// Run `resources/list` and `prompts/list` and store the results on the connection
+#[tauri::command]
+async fn refresh_mcp_resources_and_prompts(
+    id: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<mcp_catalog::CatalogResult, String> {
+    mcp_catalog::refresh(&state, &id).await
+}
+
+#[tauri::command]
+async fn read_mcp_resource(
+    id: String,
+    uri: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    mcp_catalog::read_resource(&state, &id, &uri).await
+}
+
+/// Render an MCP prompt template with `arguments`
+#[tauri::command]
+async fn get_mcp_prompt(
+    id: String,
+    name: String,
+    arguments: Option<HashMap<String, String>>,
+    state: tauri::State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    mcp_catalog::get_prompt(&state, &id, &name, arguments.unwrap_or_default()).await
+}
+
 #[tauri::command]
 async fn call_mcp_tool(
     request: McpToolCallRequest,
@@ -5199,12 +8324,29 @@ async fn call_mcp_tool(
         });
     }
 
+    let arguments = match mcp_tool_call_arguments(connection.tools.iter().find(|tool| tool.name == tool_name), request.arguments) {
+        Ok(arguments) => arguments,
+        Err(error) => {
+            return Ok(McpToolCallResult {
+                success: false,
+                latency_ms: start_time.elapsed().as_millis() as i64,
+                message: error.clone(),
+                content: String::new(),
+                is_error: true,
+                raw_result: None,
+                error: Some(error),
+                status_code: None,
+            });
+        }
+    };
+
     let timeout_duration = Duration::from_secs(connection.timeout_seconds.max(1));
     let effective_connection = if matches!(connection.transport, McpTransport::Json) && resolve_mcp_url(&connection).is_none() {
         resolve_json_stdio_connection(&connection).unwrap_or_else(|| connection.clone())
     } else {
         connection.clone()
     };
+    let effective_connection = mcp_auth::authorized(effective_connection).await?;
     let stdio_timeout = if matches!(effective_connection.transport, McpTransport::Stdio) {
         Duration::from_secs(connection.timeout_seconds.max(1).max(45))
     } else {
@@ -5217,7 +8359,7 @@ async fn call_mcp_tool(
             "tools/call",
             serde_json::json!({
                 "name": tool_name,
-                "arguments": request.arguments
+                "arguments": arguments
             }),
             stdio_timeout,
         )
@@ -5293,7 +8435,7 @@ async fn call_mcp_tool(
     }
 
     let url = resolved_url.unwrap_or_default();
-    let client = reqwest::Client::new();
+    let client = http_pool::client_for(&url);
 
     let initialize_payload = default_mcp_initialize_payload();
     let initialize_response = match post_mcp_request(
@@ -5340,7 +8482,7 @@ async fn call_mcp_tool(
         "method": "tools/call",
         "params": {
             "name": tool_name,
-            "arguments": request.arguments
+            "arguments": arguments
         }
     });
 
@@ -5668,8 +8810,6 @@ async fn test_mcp_connection(
     id: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<McpTestResult, String> {
-    let start_time = Instant::now();
-
     let connection = {
         let config = state.config.lock().await;
         config
@@ -5691,14 +8831,42 @@ async fn test_mcp_connection(
         });
     }
 
+    let mut result = probe_mcp_connection(&connection, &state).await?;
+    mcp_health::record(&id, &result).await;
+
+    let mut config = state.config.lock().await;
+    if let Some(conn) = config.mcp_servers.iter_mut().find(|item| item.id == id) {
+        conn.last_test_at = Some(Utc::now().to_rfc3339());
+        conn.last_test_status = Some(if result.success {
+            "ok".to_string()
+        } else {
+            "error".to_string()
+        });
+        conn.last_test_message = Some(result.message.clone());
+    }
+
+    drop(config);
+    let _ = save_settings(&state).await;
+
+    if !result.success && result.message.is_empty() {
+        result.message = result.error.clone().unwrap_or_else(|| "Test failed".to_string());
+    }
+
+    Ok(result)
+}
+
+/// Start or reach the connection once, as the Test button and the health checks do
+async fn probe_mcp_connection(connection: &McpServerConfig, state: &AppState) -> Result<McpTestResult, String> {
+    let start_time = Instant::now();
     let timeout_duration = Duration::from_secs(connection.timeout_seconds.max(1));
-    let effective_connection = if matches!(connection.transport, McpTransport::Json) && resolve_mcp_url(&connection).is_none() {
-        resolve_json_stdio_connection(&connection).unwrap_or_else(|| connection.clone())
+    let effective_connection = if matches!(connection.transport, McpTransport::Json) && resolve_mcp_url(connection).is_none() {
+        resolve_json_stdio_connection(connection).unwrap_or_else(|| connection.clone())
     } else {
         connection.clone()
     };
+    let effective_connection = mcp_auth::authorized(effective_connection).await?;
 
-    let mut result = match effective_connection.transport {
+    let result = match effective_connection.transport {
         McpTransport::Stdio => {
             let mut cmd = TokioCommand::new(&effective_connection.command);
             cmd.args(&effective_connection.args);
@@ -5706,6 +8874,8 @@ async fn test_mcp_connection(
                 cmd.envs(&effective_connection.env_vars);
             }
 
+            // Reassigned by the Windows fallbacks below
+            #[cfg_attr(not(windows), allow(unused_mut))]
             let mut spawned = cmd.spawn();
 
             #[cfg(windows)]
@@ -5812,7 +8982,7 @@ async fn test_mcp_connection(
         }
 
         let url = resolved_url.unwrap_or_default();
-        let client = reqwest::Client::new();
+        let client = http_pool::client_for(&url);
         let transport = effective_connection.transport.clone();
             let init_payload = match mcp_test_payload(&effective_connection) {
                 Ok(payload) => payload,
@@ -5840,18 +9010,12 @@ async fn test_mcp_connection(
                         request = request.header("accept", "application/json, text/event-stream");
                     }
 
-                    for (key, value) in &effective_connection.headers {
-                        let header_name = key.trim();
-                        let header_value = value.trim();
-                        if header_name.is_empty() || header_value.is_empty() {
-                            continue;
-                        }
-                        request = request.header(header_name, header_value);
-                    }
-
-                    Some(request)
+                    Some(with_mcp_headers(request, &effective_connection.headers))
                 }
-                McpTransport::Sse => Some(client.get(url).header("accept", "text/event-stream")),
+                McpTransport::Sse => Some(with_mcp_headers(
+                    client.get(url).header("accept", "text/event-stream"),
+                    &effective_connection.headers,
+                )),
                 _ => None,
             };
 
@@ -5902,104 +9066,72 @@ async fn test_mcp_connection(
         }
     };
 
-    let mut config = state.config.lock().await;
-    if let Some(conn) = config.mcp_servers.iter_mut().find(|item| item.id == id) {
-        conn.last_test_at = Some(Utc::now().to_rfc3339());
-        conn.last_test_status = Some(if result.success {
-            "ok".to_string()
-        } else {
-            "error".to_string()
-        });
-        conn.last_test_message = Some(result.message.clone());
-    }
-
-    drop(config);
-    let _ = save_settings(&state).await;
-
-    if !result.success {
-        if result.message.is_empty() {
-            result.message = result.error.clone().unwrap_or_else(|| "Test failed".to_string());
-        }
-    }
-
     Ok(result)
 }
 
+/// Send one tool-enabled request and validate the returned tool calls against the tools'
+/// schemas. `model` is a process id, model path or name; empty picks any running model.
+#[tauri::command]
+async fn test_tool_call(
+    model: String,
+    tools_json: String,
+    prompt: String,
+    state: tauri::State<'_, AppState>,
+) -> Result<tool_call_test::ToolCallTestResult, String> {
+    tool_call_test::run(&model, &tools_json, &prompt, &state).await
+}
+
 #[tauri::command]
 async fn correct_mcp_json_with_active_model(
     json_input: String,
     state: tauri::State<'_, AppState>,
 ) -> Result<serde_json::Value, String> {
-    let trimmed = json_input.trim();
-    if trimmed.is_empty() {
+    if json_input.trim().is_empty() {
         return Err("JSON input is required".to_string());
     }
 
-    let running = state.running_processes.lock().await;
-    let active = running
-        .values()
-        .find(|proc| matches!(proc.status, models::ProcessStatus::Running))
-        .or_else(|| running.values().next())
-        .cloned()
-        .ok_or_else(|| "No running model found. Start a model and try again.".to_string())?;
-    drop(running);
-
-    let server_url = format!("http://{}:{}", active.host, active.port);
-    let client = reqwest::Client::new();
-
-    let prompt = format!(
-        "You are a strict JSON syntax fixer. Correct syntax only and return valid minified JSON with no explanation or markdown. Input:\n{}",
-        trimmed
-    );
-
-    let response = client
-        .post(format!("{}/v1/chat/completions", server_url))
-        .json(&serde_json::json!({
-            "messages": [
-                {"role": "system", "content": "Return only valid JSON. No prose."},
-                {"role": "user", "content": prompt}
-            ],
-            "stream": false
-        }))
-        .send()
-        .await
-        .map_err(|e| format!("Failed to contact model: {}", e))?;
-
-    if !response.status().is_success() {
-        return Err(format!(
-            "Model request failed with status {}",
-            response.status().as_u16()
-        ));
-    }
-
-    let body: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse model response: {}", e))?;
-
-    let corrected = body
-        .get("choices")
-        .and_then(|c| c.get(0))
-        .and_then(|c| c.get("message"))
-        .and_then(|m| m.get("content"))
-        .and_then(|v| v.as_str())
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .ok_or_else(|| "Model returned empty content".to_string())?;
-
-    let normalized: serde_json::Value = serde_json::from_str(corrected)
-        .map_err(|e| format!("Model output is not valid JSON: {}", e))?;
-
-    let corrected_json = serde_json::to_string_pretty(&normalized)
-        .map_err(|e| format!("Failed to format corrected JSON: {}", e))?;
+    let result = structured_fixer::repair_with_model(&state, "", &json_input, structured_fixer::Format::Json, None).await?;
+    let corrected_json = match (result.valid, result.output) {
+        (true, Some(output)) => output,
+        _ => return Err(format!("Model output is not valid JSON: {}", result.errors.join("; "))),
+    };
 
     Ok(serde_json::json!({
         "corrected_json": corrected_json,
-        "model": active.model_name,
-        "server": server_url
+        "model": result.model,
+        "fixes": result.fixes
     }))
 }
 
+/// Check a JSON, YAML or TOML document (and optionally a schema) without a model,
+/// applying the deterministic repairs
+#[tauri::command]
+async fn validate_structured_text(
+    input: String,
+    format: Option<String>,
+    schema: Option<serde_json::Value>,
+) -> Result<structured_fixer::FixResult, String> {
+    let format = structured_fixer::Format::parse(format.as_deref(), &input)?;
+    Ok(structured_fixer::check(&input, format, schema.as_ref()))
+}
+
+/// Repair a document, asking a running model (`model`, or any) for what the
+/// deterministic passes could not fix
+#[tauri::command]
+async fn repair_structured_text(
+    input: String,
+    format: Option<String>,
+    schema: Option<serde_json::Value>,
+    model: Option<String>,
+    state: tauri::State<'_, AppState>,
+) -> Result<structured_fixer::FixResult, String> {
+    if input.trim().is_empty() {
+        return Err("Input is required".to_string());
+    }
+    let format = structured_fixer::Format::parse(format.as_deref(), &input)?;
+    structured_fixer::repair_with_model(&state, model.as_deref().unwrap_or_default(), &input, format, schema.as_ref()).await
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize logging
@@ -6027,6 +9159,9 @@ tauri::Builder::default()
             };
             let state = tauri::async_runtime::block_on(initialize_app_state(app_data_dir))
                 .map_err(|e| format!("Failed to initialize app state: {}", e))?;
+            generation_metrics::set_app_handle(app.handle().clone());
+            process::set_app_handle(app.handle().clone());
+            task_manager::set_app_handle(app.handle().clone());
             
             println!("Application started, process tracking enabled with kill_on_drop");
             
@@ -6055,6 +9190,9 @@ tauri::Builder::default()
                             let rt = tokio::runtime::Runtime::new().unwrap();
                             rt.block_on(async {
                                 state.cleanup_all_processes().await;
+                                if let Err(e) = port_mapping::remove_active_mapping().await {
+                                    eprintln!("[PortMapping] Failed to remove port mapping: {}", e);
+                                }
                             });
                             app.exit(0);
                         }
@@ -6065,28 +9203,15 @@ tauri::Builder::default()
                     use tauri::tray::{TrayIconEvent, MouseButton, MouseButtonState};
                     
                     match event {
-                        TrayIconEvent::Click { button, button_state, .. } => {
-                            if button == MouseButton::Left && button_state == MouseButtonState::Up {
-                                if let Some(window) = tray.app_handle().get_webview_window("main") {
-                                    let is_visible = window.is_visible().unwrap_or(false);
-                                    let is_minimized = window.is_minimized().unwrap_or(false);
-
-                                    if is_visible && !is_minimized {
-                                        let _ = window.hide();
-                                    } else {
-                                        if is_minimized {
-                                            let _ = window.unminimize();
-                                        }
-                                        let _ = window.show();
-                                        let _ = window.set_focus();
-                                    }
-                                }
-                            }
-                        }
-                        TrayIconEvent::DoubleClick { button, .. } => {
-                            if button == MouseButton::Left {
-                                if let Some(window) = tray.app_handle().get_webview_window("main") {
-                                    if window.is_minimized().unwrap_or(false) {
+                        TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } => {
+                            if let Some(window) = tray.app_handle().get_webview_window("main") {
+                                let is_visible = window.is_visible().unwrap_or(false);
+                                let is_minimized = window.is_minimized().unwrap_or(false);
+
+                                if is_visible && !is_minimized {
+                                    let _ = window.hide();
+                                } else {
+                                    if is_minimized {
                                         let _ = window.unminimize();
                                     }
                                     let _ = window.show();
@@ -6094,6 +9219,15 @@ tauri::Builder::default()
                                 }
                             }
                         }
+                        TrayIconEvent::DoubleClick { button: MouseButton::Left, .. } => {
+                            if let Some(window) = tray.app_handle().get_webview_window("main") {
+                                if window.is_minimized().unwrap_or(false) {
+                                    let _ = window.unminimize();
+                                }
+                                let _ = window.show();
+                                let _ = window.set_focus();
+                            }
+                        }
                         _ => {}
                     }
                 })
@@ -6107,19 +9241,16 @@ tauri::Builder::default()
                 main_window.set_title(&title).ok();
                 
                 main_window.on_window_event(move |event| {
-                    match event {
-                        tauri::WindowEvent::CloseRequested { api, .. } => {
-                            println!("Main window close button clicked, hiding to tray...");
-                            
-                            // Prevent the window from closing and hide it instead
-                            api.prevent_close();
-                            
-                            // Hide the window to tray
-                            if let Some(win) = app_handle.get_webview_window("main") {
-                                win.hide().ok();
-                            }
+                    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                        println!("Main window close button clicked, hiding to tray...");
+                        
+                        // Prevent the window from closing and hide it instead
+                        api.prevent_close();
+                        
+                        // Hide the window to tray
+                        if let Some(win) = app_handle.get_webview_window("main") {
+                            win.hide().ok();
                         }
-                        _ => {}
                     }
                 });
             }
@@ -6138,7 +9269,17 @@ tauri::Builder::default()
             let startup_state = state.clone();
             app.manage(state);
 
+            tauri::async_runtime::block_on(process_registry::reattach());
             tauri::async_runtime::block_on(auto_start_network_server_always(&startup_state));
+            disk_watchdog::spawn(startup_state.clone(), app.handle().clone());
+            memory_watchdog::spawn(startup_state.clone(), app.handle().clone());
+            power::spawn(startup_state.clone(), app.handle().clone());
+            app_health::spawn(startup_state.clone(), app.handle().clone());
+            mcp_health::spawn(startup_state.clone(), app.handle().clone());
+            scheduler::spawn(startup_state.clone());
+            idle_unload::spawn(startup_state.clone(), app.handle().clone());
+            download_resume::spawn(startup_state.clone(), app.handle().clone());
+            macos::spawn_app_nap_guard(startup_state.clone());
 
             let app_handle = app.handle().clone();
             tauri::async_runtime::block_on(auto_start_discovery_if_enabled(
@@ -6148,8 +9289,13 @@ tauri::Builder::default()
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(analytics::counting_commands(tauri::generate_handler![
             get_config,
+            describe_settings_schema,
+            get_app_health,
+            get_locales,
+            set_locale,
+            get_message_catalog,
             save_config,
             scan_models_command,
             get_model_settings,
@@ -6160,13 +9306,44 @@ tauri::Builder::default()
             delete_model_preset,
             set_default_preset,
             launch_model_with_preset,
+            queue_model_launch,
+            launch_model_batch,
+            test_prompt_across_models,
+            get_launch_queue,
+            cancel_queued_launch,
+            list_model_instances,
+            kill_model_instances,
             launch_model_with_half_context,
             launch_model,
             launch_model_external,
             launch_model_with_preset_external,
             delete_model_file,
+            set_models_directory_read_only,
             delete_model,
             kill_process,
+            suspend_process,
+            resume_process,
+            get_suspended_processes,
+            get_all_processes_summary,
+            cancel_task,
+            list_background_tasks,
+            list_orphan_processes,
+            adopt_orphan_process,
+            kill_orphan_process,
+            count_tokens,
+            get_generation_params,
+            set_generation_params,
+            set_context_length,
+            set_gpu_layers,
+            get_pending_changes,
+            restart_model_with_pending_changes,
+            get_sampling_profiles,
+            save_sampling_profile,
+            delete_sampling_profile,
+            get_model_schedules,
+            save_model_schedule,
+            delete_model_schedule,
+            get_upcoming_scheduled_runs,
             get_process_output,
             browse_folder,
             pick_llamacpp_zip_file,
@@ -6181,15 +9358,45 @@ tauri::Builder::default()
             cancel_download,
             pause_download,
             resume_download,
+            get_download_queue,
+            move_queued_download,
+            clean_partial_downloads,
+            verify_model_file,
             clear_download_history,
             download_from_url,
             get_llamacpp_releases,
+            get_host_platform,
+            probe_gpu_runtime,
+            check_llamacpp_asset_compatibility,
+            get_low_ram_cpu_preset,
             get_llamacpp_commit_info,
+            save_github_settings,
+            set_github_token,
+            clear_github_token,
             download_llamacpp_asset,
             download_llamacpp_asset_to_version,
             list_llamacpp_versions,
             set_active_llamacpp_version,
+            set_backend_active_llamacpp_version,
+            set_model_backend_preference,
+            set_model_exclusive_launch,
+            set_model_restart_policy,
+            set_model_memory_settings,
+            set_model_gpu_devices,
+            set_model_idle_timeout,
+            get_low_memory_mode,
+            set_low_memory_mode,
+            get_fallback_chain,
+            set_fallback_chain,
+            get_route_policies,
+            set_route_policy,
             delete_llamacpp_version,
+            auto_select_backend,
+            get_backend_benchmarks,
+            clear_backend_benchmarks,
+            prune_llamacpp_versions,
+            save_llamacpp_retention,
+            set_model_llamacpp_version,
             install_local_llamacpp_zip,
             install_local_llamacpp_cuda_dlls_zip,
             get_session_state,
@@ -6200,6 +9407,28 @@ tauri::Builder::default()
             get_app_version,
             check_file_exists,
             get_system_stats,
+            list_gpu_devices,
+            start_gpu_telemetry,
+            stop_gpu_telemetry,
+            get_energy_report,
+            get_usage_analytics,
+            set_usage_analytics_enabled,
+            get_terminal_options,
+            set_external_terminal,
+            clear_usage_analytics,
+            get_disk_space_status,
+            get_memory_pressure,
+            get_power_status,
+            set_power_policy,
+            move_model,
+            rename_model_file,
+            reconcile_model_configs,
+            compute_model_hashes,
+            register_external_model,
+            install_model_pack,
+            estimate_vram,
+            suggest_launch_args,
+            list_event_schemas,
             scan_mmproj_files_command,
             hide_window,
             show_window,
@@ -6212,23 +9441,56 @@ initial_scan_models,
             parse_hf_url,
             fetch_hf_model_info,
             fetch_hf_model_files,
+            fetch_hf_model_preview,
             get_default_download_path,
             download_hf_file,
             get_tracker_models,
             refresh_tracker_data,
             export_tracker_json,
+            import_tracker_json,
+            export_curated_list,
+            import_curated_list,
+            get_tracker_model_files,
+            classify_tracker_models,
+            set_tracker_model_category,
+            get_tracker_db_info,
+            prune_tracker_models,
+            dedupe_tracker_models,
+            vacuum_tracker_db,
             get_tracker_live_results,
             get_tracker_stats,
             get_tracker_config,
             update_tracker_config,
 get_weekly_reports,
             generate_weekly_report,
+            get_hf_rate_metrics,
+            list_hf_collections,
+            get_hf_collection,
+            import_hf_collection_to_watchlist,
+            get_tracker_watchlist,
+            add_to_tracker_watchlist,
+            remove_from_tracker_watchlist,
             save_network_config,
             get_network_config,
             get_network_interfaces,
             activate_network_server,
             deactivate_network_server,
             get_network_server_status,
+            get_openapi_document,
+            get_network_clients,
+            set_network_client_blocked,
+            save_network_allowlist,
+            get_network_api_keys,
+            create_network_api_key,
+            update_network_api_key,
+            set_network_api_key_profile,
+            delete_network_api_key,
+            enable_port_forwarding,
+            disable_port_forwarding,
+            get_port_forwarding_status,
+            start_tunnel,
+            stop_tunnel,
+            list_tunnels,
             enable_discovery,
             disable_discovery,
             get_discovered_peers,
@@ -6239,21 +9501,49 @@ get_weekly_reports,
             purge_discovery_cache,
             get_mcp_connections,
             save_mcp_connection,
+            import_mcp_servers,
             delete_mcp_connection,
             toggle_mcp_connection,
             test_mcp_connection,
             list_mcp_tools,
             call_mcp_tool,
+            refresh_mcp_resources_and_prompts,
+            set_mcp_auth_token,
+            get_mcp_auth_status,
+            start_mcp_oauth,
+            get_mcp_health,
+            set_mcp_tool_settings,
+            read_mcp_resource,
+            get_mcp_prompt,
             call_supermemory_native_tool,
             correct_mcp_json_with_active_model,
+            validate_structured_text,
+            repair_structured_text,
+            test_tool_call,
             list_chat_logs,
             create_chat_log,
             append_chat_log_message,
+            get_chat_context_settings,
+            set_chat_context_settings,
+            detect_message_language,
+            get_chat_translation_settings,
+            set_chat_translation_settings,
+            translate_chat_message,
             rename_chat_log,
+            duplicate_chat_log,
+            get_chat_messages,
+            import_chat_markdown,
+            export_chat_log,
+            export_all_chats,
              get_chat_log,
+            get_message_logprobs,
+            repeat_chat_message,
+            simulate_context_usage,
             delete_chat_log,
              search_chat_logs,
-         ])
+            extract_code_blocks,
+            save_code_block_to_file,
+         ]))
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
     }
@@ -6284,6 +9574,11 @@ mod tests {
             tools_last_status: None,
             tools_last_message: None,
             tools_last_error: None,
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            catalog_last_refresh_at: None,
+            catalog_last_error: None,
+            auth: Default::default(),
         }
     }
 
@@ -6472,6 +9767,44 @@ mod tests {
         assert!(result.unwrap_err().contains("Invalid request"));
     }
 
+    #[test]
+    fn batch_launch_order_puts_dependencies_first() {
+        let item = |path: &str, depends_on: &[&str]| BatchLaunchItem {
+            model_path: path.to_string(),
+            preset_id: None,
+            depends_on: depends_on.iter().map(|d| d.to_string()).collect(),
+        };
+        let items = vec![
+            item("/models/main.gguf", &["draft.gguf"]),
+            item("/models/embed.gguf", &[]),
+            item("/models/draft.gguf", &[]),
+        ];
+        assert_eq!(batch_launch_order(&items).unwrap(), vec![1, 2, 0]);
+
+        let cycle = vec![item("/a.gguf", &["/b.gguf"]), item("/b.gguf", &["a.gguf"])];
+        assert!(batch_launch_order(&cycle).unwrap_err().contains("cycle"));
+        assert!(batch_launch_order(&[item("/a.gguf", &["missing.gguf"])]).is_err());
+    }
+
+    #[test]
+    fn mcp_tool_settings_survive_refresh_and_gate_calls() {
+        let response = serde_json::json!({
+            "result": {"tools": [{"name": "read_file"}, {"name": "delete_file"}]}
+        });
+        let mut previous = parse_mcp_tools_from_response(&response).unwrap();
+        previous[0].argument_presets.insert("root".to_string(), serde_json::json!("/srv"));
+        previous[1].enabled = false;
+
+        let tools = keep_mcp_tool_settings(&previous, parse_mcp_tools_from_response(&response).unwrap());
+        assert!(!tools[1].enabled);
+        assert!(mcp_tool_call_arguments(tools.get(1), serde_json::json!({})).unwrap_err().contains("disabled"));
+
+        let args = mcp_tool_call_arguments(tools.first(), serde_json::json!({"path": "a.txt", "root": "/tmp"})).unwrap();
+        assert_eq!(args, serde_json::json!({"path": "a.txt", "root": "/tmp"}));
+        let args = mcp_tool_call_arguments(tools.first(), serde_json::Value::Null).unwrap();
+        assert_eq!(args, serde_json::json!({"root": "/srv"}));
+    }
+
     #[test]
     fn append_json_chat_message_appends_to_messages_array() {
         let mut path = std::env::temp_dir();
@@ -6517,4 +9850,41 @@ mod tests {
 
         fs::remove_file(&path).ok();
     }
+
+    #[test]
+    fn split_chat_message_sections_skips_frontmatter_and_keeps_headings_in_content() {
+        let markdown = "---\nchat_id: chat-1\ntitle: Test\n---\n\n\
+## USER | t0 | model-a\n\nhello\n\n\
+## ASSISTANT | t1 | model-a\n\n## Not a message\nworld\n\n\
+## USER | t2 | model-b\n\nagain\n\n";
+
+        let sections = split_chat_message_sections(markdown);
+
+        assert_eq!(sections.len(), 3);
+        assert!(sections[0].starts_with("## USER | t0"));
+        assert!(sections[1].contains("## Not a message"));
+        assert!(sections[2].contains("again"));
+    }
+
+    #[test]
+    fn extract_fenced_code_blocks_reads_language_and_content() {
+        let body = "Here you go:\n\n```Rust\nfn main() {}\n```\n\ntext\n\n~~~\nplain\n~~~\n";
+
+        let blocks = extract_fenced_code_blocks(body);
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0]["language"], "rust");
+        assert_eq!(blocks[0]["code"], "fn main() {}");
+        assert_eq!(blocks[1]["language"], "");
+        assert_eq!(blocks[1]["code"], "plain");
+    }
+
+    #[test]
+    fn validate_code_block_target_path_rejects_relative_and_traversal() {
+        assert!(validate_code_block_target_path("snippet.py", false).is_err());
+
+        let traversal = std::env::temp_dir().join("..").join("snippet.py");
+        let result = validate_code_block_target_path(traversal.to_str().unwrap(), false);
+        assert_eq!(result.unwrap_err(), "File path must not contain '..' segments");
+    }
 }
@@ -1,7 +1,7 @@
 use reqwest::Client;
 use serde_json::{json, Value};
-use std::time::Duration;
-use crate::openai_types::ChatCompletionRequest;
+use crate::http_pool::{self, RequestClass, RetryPolicy};
+use crate::openai_types::{ChatCompletionRequest, CompletionRequest};
 
 #[derive(Clone)]
 pub struct LlamaClient {
@@ -11,12 +11,8 @@ pub struct LlamaClient {
 
 impl LlamaClient {
     pub fn new(base_url: String) -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(300)) // 5 minute timeout
-            .connect_timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
-            
+        // Pooled per origin, so keep-alive connections are shared with other callers
+        let client = http_pool::client_for(&base_url);
         Self { client, base_url }
     }
 
@@ -37,17 +33,21 @@ impl LlamaClient {
         })
     }
 
+    async fn send(&self, url: &str, body: &Value, class: RequestClass) -> Result<reqwest::Response, String> {
+        http_pool::send_with_retry(
+            || http_pool::with_timeout(self.client.post(url).json(body), class),
+            RetryPolicy::DEFAULT,
+        )
+        .await
+        .map_err(|e| format!("Failed to connect to llama.cpp: {}", e))
+    }
+
     /// Send non-streaming chat completion request
     pub async fn chat_completion(&self, request: &ChatCompletionRequest) -> Result<Value, String> {
         let url = format!("{}/v1/chat/completions", self.base_url);
         let body = self.convert_request(request);
 
-        let response = self.client
-            .post(&url)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to connect to llama.cpp: {}", e))?;
+        let response = self.send(&url, &body, RequestClass::Completion).await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -59,6 +59,35 @@ impl LlamaClient {
             .map_err(|e| format!("Failed to parse llama.cpp response: {}", e))
     }
 
+    /// Send a legacy text completion request as-is; `Ok(None)` when the server has no
+    /// `/v1/completions` endpoint and the caller should fall back to chat
+    pub async fn completion(&self, request: &CompletionRequest) -> Result<Option<reqwest::Response>, String> {
+        let url = format!("{}/v1/completions", self.base_url);
+        let body = serde_json::to_value(request)
+            .map_err(|e| format!("Failed to serialize completion request: {}", e))?;
+        let class = if request.stream.unwrap_or(false) { RequestClass::Stream } else { RequestClass::Completion };
+
+        let response = self.send(&url, &body, class).await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("llama.cpp returned error {}: {}", status, text));
+        }
+        Ok(Some(response))
+    }
+
+    /// Send `body` to `path` as-is and return the response whatever its status, for
+    /// endpoints the proxy relays without interpreting
+    pub async fn forward(&self, path: &str, body: &Value) -> Result<reqwest::Response, String> {
+        let url = format!("{}{}", self.base_url, path);
+        let stream = body.get("stream").and_then(Value::as_bool).unwrap_or(false);
+        self.send(&url, body, if stream { RequestClass::Stream } else { RequestClass::Completion }).await
+    }
+
     /// Send streaming chat completion request
     pub async fn chat_completion_stream(
         &self, 
@@ -67,19 +96,62 @@ impl LlamaClient {
         let url = format!("{}/v1/chat/completions", self.base_url);
         let body = self.convert_request(request);
 
-        let response = self.client
-            .post(&url)
-            .json(&body)
+        let response = self.send(&url, &body, RequestClass::Stream).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!("llama.cpp returned error {}: {}", status, text));
+        }
+
+        Ok(response)
+    }
+
+    /// Token ids of `text` from llama-server's `/tokenize`, with the model's own tokenizer.
+    /// `add_special` adds BOS and similar tokens as a prompt would have them.
+    pub async fn tokenize(&self, text: &str, add_special: bool) -> Result<Vec<i64>, String> {
+        let url = format!("{}/tokenize", self.base_url);
+        let body = json!({ "content": text, "add_special": add_special });
+        let value = self.control(&url, &body).await?;
+        value["tokens"]
+            .as_array()
+            .map(|tokens| tokens.iter().filter_map(Value::as_i64).collect())
+            .ok_or_else(|| "llama.cpp /tokenize response has no tokens".to_string())
+    }
+
+    /// `messages` rendered through the model's chat template by `/apply-template`, as the
+    /// prompt the server would evaluate
+    pub async fn apply_template(&self, messages: &Value) -> Result<String, String> {
+        let url = format!("{}/apply-template", self.base_url);
+        let value = self.control(&url, &json!({ "messages": messages })).await?;
+        value["prompt"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "llama.cpp /apply-template response has no prompt".to_string())
+    }
+
+    /// Context size per slot from `/props`
+    pub async fn context_length(&self) -> Result<u64, String> {
+        let url = format!("{}/props", self.base_url);
+        let response = http_pool::with_timeout(self.client.get(&url), RequestClass::Control)
             .send()
             .await
             .map_err(|e| format!("Failed to connect to llama.cpp: {}", e))?;
+        let value = response.json::<Value>().await
+            .map_err(|e| format!("Failed to parse llama.cpp response: {}", e))?;
+        value["default_generation_settings"]["n_ctx"]
+            .as_u64()
+            .ok_or_else(|| "llama.cpp /props response has no n_ctx".to_string())
+    }
 
+    async fn control(&self, url: &str, body: &Value) -> Result<Value, String> {
+        let response = self.send(url, body, RequestClass::Control).await?;
         if !response.status().is_success() {
             let status = response.status();
             let text = response.text().await.unwrap_or_default();
             return Err(format!("llama.cpp returned error {}: {}", status, text));
         }
-
-        Ok(response)
+        response.json::<Value>().await
+            .map_err(|e| format!("Failed to parse llama.cpp response: {}", e))
     }
 }
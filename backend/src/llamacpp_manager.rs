@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use std::sync::Mutex;
 use std::sync::LazyLock;
 
 // Cache for releases to avoid excessive API calls
-static RELEASES_CACHE: LazyLock<Mutex<Option<(Vec<LlamaCppReleaseFrontend>, Instant)>>> = 
+type CachedReleases = (Vec<LlamaCppReleaseFrontend>, Instant);
+static RELEASES_CACHE: LazyLock<Mutex<Option<CachedReleases>>> = 
     LazyLock::new(|| Mutex::new(None));
 
 // Cache duration - GitHub allows 60 requests per hour for unauthenticated requests
@@ -51,6 +54,9 @@ pub struct LlamaCppAssetFrontend {
     pub download_count: Option<u64>,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    /// Fit for this machine; filled when releases are served, never cached
+    #[serde(default)]
+    pub compatibility: Option<crate::gpu_runtime::AssetCompatibility>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -91,6 +97,7 @@ impl From<LlamaCppAsset> for LlamaCppAssetFrontend {
             download_count: asset.download_count,
             created_at: asset.created_at,
             updated_at: asset.updated_at,
+            compatibility: None,
         }
     }
 }
@@ -166,6 +173,64 @@ fn format_release_body(body: &Option<String>) -> Option<String> {
     })
 }
 
+/// Controls how `fetch_llamacpp_releases` uses its caches
+#[derive(Debug, Clone)]
+pub struct ReleaseFetchOptions {
+    pub github_token: Option<String>,
+    /// How long the on-disk release list is served before revalidating with GitHub
+    pub max_age: Duration,
+    pub force_refresh: bool,
+}
+
+impl Default for ReleaseFetchOptions {
+    fn default() -> Self {
+        Self {
+            github_token: None,
+            max_age: Duration::from_secs(6 * 3600),
+            force_refresh: false,
+        }
+    }
+}
+
+// ETag-keyed responses for conditional GitHub requests. A 304 does not count
+// against the rate limit, so revalidating is much cheaper than refetching.
+static GITHUB_ETAG_CACHE: LazyLock<Mutex<HashMap<String, (String, serde_json::Value)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ReleasesDiskCache {
+    etag: Option<String>,
+    fetched_at: chrono::DateTime<chrono::Utc>,
+    releases: Vec<LlamaCppReleaseFrontend>,
+}
+
+fn releases_cache_path() -> PathBuf {
+    crate::models::preferred_arandu_base_dir()
+        .join("cache")
+        .join("llamacpp_releases.json")
+}
+
+fn load_releases_disk_cache() -> Option<ReleasesDiskCache> {
+    let content = std::fs::read_to_string(releases_cache_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_releases_disk_cache(cache: &ReleasesDiskCache) {
+    let path = releases_cache_path();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string(cache) {
+        Ok(json) => {
+            let tmp_path = path.with_extension("json.tmp");
+            if std::fs::write(&tmp_path, json).is_ok() {
+                let _ = std::fs::rename(&tmp_path, &path);
+            }
+        }
+        Err(e) => eprintln!("Failed to serialize releases cache: {}", e),
+    }
+}
+
 /// Check if we have valid cached releases
 fn get_cached_releases() -> Option<Vec<LlamaCppReleaseFrontend>> {
     if let Ok(cache) = RELEASES_CACHE.lock() {
@@ -185,35 +250,28 @@ fn cache_releases(releases: Vec<LlamaCppReleaseFrontend>) {
     }
 }
 
-/// Fetch llama.cpp releases from GitHub API with proper rate limiting and caching
-pub async fn fetch_llamacpp_releases() -> Result<Vec<LlamaCppReleaseFrontend>, Box<dyn std::error::Error + Send + Sync>> {
-    // Check cache first
-    if let Some(cached_releases) = get_cached_releases() {
-        println!("Returning cached releases ({} releases)", cached_releases.len());
-        return Ok(cached_releases);
-    }
-    
-    let client = reqwest::Client::new();
-    
-    // Use the proper GitHub API endpoint with correct headers
-    let url = "https://api.github.com/repos/ggerganov/llama.cpp/releases";
-    
-    println!("Fetching llama.cpp releases from: {}", url);
-    
-    // Build request with proper GitHub API headers
-    let request = client
+fn github_request(
+    client: &reqwest::Client,
+    url: &str,
+    token: Option<&str>,
+    etag: Option<&str>,
+) -> reqwest::RequestBuilder {
+    let mut request = client
         .get(url)
         .header("User-Agent", "Arandu-Tauri/1.0")
         .header("Accept", "application/vnd.github+json")
         .header("X-GitHub-Api-Version", "2022-11-28");
-    
-    let response = request.send().await?;
-    
-    let status = response.status();
-    println!("GitHub API response status: {}", status);
-    
-    // Check rate limiting headers
-    if let Some(remaining) = response.headers().get("x-ratelimit-remaining") {
+    if let Some(token) = token.map(str::trim).filter(|t| !t.is_empty()) {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    if let Some(etag) = etag {
+        request = request.header("If-None-Match", etag);
+    }
+    request
+}
+
+fn log_rate_limit(headers: &reqwest::header::HeaderMap) {
+    if let Some(remaining) = headers.get("x-ratelimit-remaining") {
         if let Ok(remaining_str) = remaining.to_str() {
             if let Ok(remaining_int) = remaining_str.parse::<i32>() {
                 println!("GitHub API rate limit remaining: {}", remaining_int);
@@ -223,8 +281,8 @@ pub async fn fetch_llamacpp_releases() -> Result<Vec<LlamaCppReleaseFrontend>, B
             }
         }
     }
-    
-    if let Some(reset_time) = response.headers().get("x-ratelimit-reset") {
+
+    if let Some(reset_time) = headers.get("x-ratelimit-reset") {
         if let Ok(reset_str) = reset_time.to_str() {
             if let Ok(reset_timestamp) = reset_str.parse::<u64>() {
                 let reset_date = chrono::DateTime::from_timestamp(reset_timestamp as i64, 0);
@@ -234,22 +292,140 @@ pub async fn fetch_llamacpp_releases() -> Result<Vec<LlamaCppReleaseFrontend>, B
             }
         }
     }
+}
+
+fn github_error_message(status: reqwest::StatusCode, headers: &reqwest::header::HeaderMap, authenticated: bool) -> String {
+    let exhausted = headers
+        .get("x-ratelimit-remaining")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim() == "0");
+
+    match status.as_u16() {
+        401 => "GitHub rejected the configured token. Check it in Settings.".to_string(),
+        403 | 429 if exhausted && !authenticated => {
+            "Rate limit exceeded. Add a GitHub token in Settings to raise the limit, or try again later.".to_string()
+        }
+        403 | 429 => "Rate limit exceeded. Please try again later.".to_string(),
+        404 => "Repository not found or access denied.".to_string(),
+        500..=599 => "GitHub API server error. Please try again later.".to_string(),
+        _ => format!("GitHub API request failed with status: {}", status),
+    }
+}
+
+/// GET a GitHub API URL as JSON, revalidating previously seen responses with their ETag
+async fn github_get_json(
+    client: &reqwest::Client,
+    url: &str,
+    token: Option<&str>,
+) -> Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>> {
+    let cached = GITHUB_ETAG_CACHE.lock().ok().and_then(|c| c.get(url).cloned());
+
+    let response = github_request(client, url, token, cached.as_ref().map(|(etag, _)| etag.as_str()))
+        .send()
+        .await?;
+    log_rate_limit(response.headers());
+
+    if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+        if let Some((_, value)) = cached {
+            return Ok(value);
+        }
+    }
+
+    if !response.status().is_success() {
+        return Err(github_error_message(response.status(), response.headers(), token.is_some()).into());
+    }
+
+    let etag = response
+        .headers()
+        .get("etag")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    let value: serde_json::Value = response.json().await?;
+    if let Some(etag) = etag {
+        if let Ok(mut cache) = GITHUB_ETAG_CACHE.lock() {
+            cache.insert(url.to_string(), (etag, value.clone()));
+        }
+    }
+    Ok(value)
+}
+
+/// Fetch llama.cpp releases from GitHub API with proper rate limiting and caching.
+/// The release list is kept on disk and revalidated with an ETag once `max_age`
+/// has passed; if GitHub is unreachable or rate limited the stale copy is returned.
+pub async fn fetch_llamacpp_releases(
+    options: &ReleaseFetchOptions,
+) -> Result<Vec<LlamaCppReleaseFrontend>, Box<dyn std::error::Error + Send + Sync>> {
+    // Check cache first
+    if !options.force_refresh {
+        if let Some(cached_releases) = get_cached_releases() {
+            println!("Returning cached releases ({} releases)", cached_releases.len());
+            return Ok(cached_releases);
+        }
+    }
+
+    let disk_cache = load_releases_disk_cache();
+    if let Some(cache) = disk_cache.as_ref().filter(|_| !options.force_refresh) {
+        let age = chrono::Utc::now().signed_duration_since(cache.fetched_at);
+        if age.to_std().is_ok_and(|age| age < options.max_age) {
+            println!("Returning releases from disk cache ({} releases)", cache.releases.len());
+            cache_releases(cache.releases.clone());
+            return Ok(cache.releases.clone());
+        }
+    }
+
+    let client = reqwest::Client::new();
+    
+    // Use the proper GitHub API endpoint with correct headers
+    let url = "https://api.github.com/repos/ggerganov/llama.cpp/releases";
+    
+    println!("Fetching llama.cpp releases from: {}", url);
+    
+    let token = options.github_token.as_deref();
+    let etag = disk_cache.as_ref().and_then(|c| c.etag.as_deref());
+    let response = match github_request(&client, url, token, etag).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            if let Some(cache) = disk_cache {
+                eprintln!("GitHub unreachable ({}), using stale releases cache", e);
+                cache_releases(cache.releases.clone());
+                return Ok(cache.releases);
+            }
+            return Err(e.into());
+        }
+    };
+    
+    let status = response.status();
+    println!("GitHub API response status: {}", status);
+    log_rate_limit(response.headers());
+
+    if status == reqwest::StatusCode::NOT_MODIFIED {
+        if let Some(mut cache) = disk_cache {
+            println!("Releases unchanged since last fetch");
+            cache.fetched_at = chrono::Utc::now();
+            save_releases_disk_cache(&cache);
+            cache_releases(cache.releases.clone());
+            return Ok(cache.releases);
+        }
+    }
     
     // Check status before consuming the response
     if !status.is_success() {
+        let error_message = github_error_message(status, response.headers(), token.is_some());
         let response_text = response.text().await?;
         println!("GitHub API error response: {}", response_text);
-        
-        // Provide more specific error messages
-        let error_message = match status.as_u16() {
-            403 => "Rate limit exceeded. Please try again later.",
-            404 => "Repository not found or access denied.",
-            500..=599 => "GitHub API server error. Please try again later.",
-            _ => &format!("GitHub API request failed with status: {}", status),
-        };
-        
+
+        if let Some(cache) = disk_cache {
+            eprintln!("{} Using stale releases cache", error_message);
+            return Ok(cache.releases);
+        }
         return Err(error_message.into());
     }
+
+    let etag = response
+        .headers()
+        .get("etag")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
     
     // Get the response text for successful responses
     let response_text = response.text().await?;
@@ -275,31 +451,29 @@ pub async fn fetch_llamacpp_releases() -> Result<Vec<LlamaCppReleaseFrontend>, B
     
     // Cache the results
     cache_releases(frontend_releases.clone());
+    save_releases_disk_cache(&ReleasesDiskCache {
+        etag,
+        fetched_at: chrono::Utc::now(),
+        releases: frontend_releases.clone(),
+    });
     
     Ok(frontend_releases)
 }
 
 /// Fetch commit information from GitHub API
-pub async fn fetch_commit_info(tag_name: &str) -> Result<CommitInfo, Box<dyn std::error::Error + Send + Sync>> {
+pub async fn fetch_commit_info(
+    tag_name: &str,
+    github_token: Option<&str>,
+) -> Result<CommitInfo, Box<dyn std::error::Error + Send + Sync>> {
     let client = reqwest::Client::new();
     
     // Get the specific release to find the commit SHA
     let release_url = format!("https://api.github.com/repos/ggerganov/llama.cpp/releases/tags/{}", tag_name);
     println!("Fetching release info from: {}", release_url);
     
-    let release_response = client
-        .get(&release_url)
-        .header("User-Agent", "Arandu-Tauri/1.0")
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .send()
-        .await?;
-    
-    if !release_response.status().is_success() {
-        return Err(format!("Failed to fetch release info: {}", release_response.status()).into());
-    }
-    
-    let release_data: serde_json::Value = release_response.json().await?;
+    let release_data = github_get_json(&client, &release_url, github_token)
+        .await
+        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
     
     // Get the commit SHA from the release
     let commit_sha = release_data["target_commitish"].as_str()
@@ -311,19 +485,9 @@ pub async fn fetch_commit_info(tag_name: &str) -> Result<CommitInfo, Box<dyn std
     let commit_url = format!("https://api.github.com/repos/ggerganov/llama.cpp/commits/{}", commit_sha);
     println!("Fetching commit info from: {}", commit_url);
     
-    let commit_response = client
-        .get(&commit_url)
-        .header("User-Agent", "Arandu-Tauri/1.0")
-        .header("Accept", "application/vnd.github+json")
-        .header("X-GitHub-Api-Version", "2022-11-28")
-        .send()
-        .await?;
-    
-    if !commit_response.status().is_success() {
-        return Err(format!("Failed to fetch commit info: {}", commit_response.status()).into());
-    }
-    
-    let commit_data: serde_json::Value = commit_response.json().await?;
+    let commit_data = github_get_json(&client, &commit_url, github_token)
+        .await
+        .map_err(|e| format!("Failed to fetch commit info: {}", e))?;
     
     let sha = commit_data["sha"].as_str().unwrap_or("").to_string();
     let message = commit_data["commit"]["message"].as_str().unwrap_or("").to_string();
@@ -338,4 +502,292 @@ pub async fn fetch_commit_info(tag_name: &str) -> Result<CommitInfo, Box<dyn std
         date,
         html_url,
     })
-}
\ No newline at end of file
+}
+
+// ==================== Installed version metadata ====================
+
+const VERSIONS_METADATA_FILE: &str = "versions.json";
+
+// Serializes read-modify-write cycles on versions.json across concurrent installs
+static VERSIONS_METADATA_LOCK: Mutex<()> = Mutex::new(());
+
+/// What we knew about a llama.cpp build when it was installed, stored in
+/// `<executable_folder>/versions/versions.json` keyed by `<version>/<backend>`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstalledVersionMetadata {
+    #[serde(default)]
+    pub tag_name: Option<String>,
+    #[serde(default)]
+    pub commit_sha: Option<String>,
+    #[serde(default)]
+    pub backend_type: Option<String>,
+    #[serde(default)]
+    pub asset_name: Option<String>,
+    #[serde(default)]
+    pub published_at: Option<String>,
+    #[serde(default)]
+    pub release_notes: Option<String>,
+    #[serde(default)]
+    pub release_url: Option<String>,
+    /// "github" or "local_zip"
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub installed_at: Option<String>,
+}
+
+/// Key for a version folder relative to the versions root, always `/`-separated
+pub fn version_metadata_key(versions_root: &std::path::Path, version_path: &std::path::Path) -> Option<String> {
+    let relative = version_path.strip_prefix(versions_root).ok()?;
+    let key = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/");
+    if key.is_empty() { None } else { Some(key) }
+}
+
+pub fn load_versions_metadata(versions_root: &std::path::Path) -> HashMap<String, InstalledVersionMetadata> {
+    std::fs::read_to_string(versions_root.join(VERSIONS_METADATA_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Apply `update` to the entry for `key`; setting it to `None` removes the entry
+pub fn update_version_metadata<F>(versions_root: &std::path::Path, key: &str, update: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Option<InstalledVersionMetadata>),
+{
+    let _guard = VERSIONS_METADATA_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let mut metadata = load_versions_metadata(versions_root);
+    let mut entry = metadata.remove(key);
+    update(&mut entry);
+    if let Some(entry) = entry {
+        metadata.insert(key.to_string(), entry);
+    }
+
+    std::fs::create_dir_all(versions_root)
+        .map_err(|e| format!("Failed to create versions directory: {}", e))?;
+    let json = serde_json::to_string_pretty(&metadata)
+        .map_err(|e| format!("Failed to serialize version metadata: {}", e))?;
+    let path = versions_root.join(VERSIONS_METADATA_FILE);
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, json).map_err(|e| format!("Failed to write version metadata: {}", e))?;
+    std::fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to write version metadata: {}", e))
+}
+
+/// Tag every asset with how well it fits `host`. With `platform_only`, assets built
+/// for another OS or architecture are dropped.
+pub fn annotate_asset_compatibility(
+    releases: &mut [LlamaCppReleaseFrontend],
+    host: &crate::gpu_runtime::HostPlatform,
+    platform_only: bool,
+) {
+    for release in releases.iter_mut() {
+        for asset in release.assets.iter_mut() {
+            asset.compatibility = Some(crate::gpu_runtime::assess_asset(&asset.name, host));
+        }
+        let mut assessed: Vec<_> = release.assets.iter_mut().filter_map(|a| a.compatibility.as_mut()).collect();
+        crate::gpu_runtime::mark_recommended(&mut assessed, host);
+        if platform_only {
+            release
+                .assets
+                .retain(|a| a.compatibility.as_ref().map(|c| c.platform_match).unwrap_or(true));
+        }
+    }
+}
+
+/// Look up a release by tag in the memory or disk release cache without hitting GitHub
+pub fn cached_release(tag_name: &str) -> Option<LlamaCppReleaseFrontend> {
+    let tag = tag_name.trim_start_matches('v');
+    let matches = |r: &LlamaCppReleaseFrontend| r.tag_name.trim_start_matches('v') == tag;
+
+    if let Some(release) = RELEASES_CACHE
+        .lock()
+        .ok()
+        .and_then(|cache| cache.as_ref().and_then(|(releases, _)| releases.iter().find(|r| matches(r)).cloned()))
+    {
+        return Some(release);
+    }
+    load_releases_disk_cache().and_then(|cache| cache.releases.into_iter().find(|r| matches(r)))
+}
+
+// ==================== Version retention ====================
+
+/// An installed build considered by `prune_llamacpp_versions`
+#[derive(Debug, Clone, Serialize)]
+pub struct PruneCandidate {
+    pub path: String,
+    pub name: String,
+    pub backend_type: String,
+    /// Newer builds sort higher: release date, then install date, then folder creation time
+    pub sort_key: i64,
+    /// Active or pinned by a model; never removed
+    pub protected: bool,
+    pub size_bytes: u64,
+}
+
+fn normalize_version_path(p: &str) -> String {
+    let p = p.replace('\\', "/");
+    let p = p.trim_end_matches('/').to_string();
+    if cfg!(windows) { p.to_lowercase() } else { p }
+}
+
+/// Compare version folder paths the way the config stores them
+pub fn same_version_path(a: &str, b: &str) -> bool {
+    normalize_version_path(a) == normalize_version_path(b)
+}
+
+/// Same path, or one inside the other: an active `.../cuda/bin` keeps `.../cuda`
+pub fn overlapping_version_paths(a: &str, b: &str) -> bool {
+    let (a, b) = (normalize_version_path(a), normalize_version_path(b));
+    a == b || a.starts_with(&format!("{}/", b)) || b.starts_with(&format!("{}/", a))
+}
+
+/// Backend folders of a `<version>/<backend>` install: subfolders with llama-server at
+/// their root or an entry in versions.json. A flat install keeps llama-server in the
+/// version folder itself; its `bin/` and `lib/` are not backends, so it yields none.
+pub fn backend_dirs(
+    versions_root: &std::path::Path,
+    version_path: &std::path::Path,
+    metadata: &HashMap<String, InstalledVersionMetadata>,
+) -> Vec<std::path::PathBuf> {
+    let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+    let recorded = |path: &std::path::Path| version_metadata_key(versions_root, path).is_some_and(|key| metadata.contains_key(&key));
+    let Ok(entries) = std::fs::read_dir(version_path) else { return Vec::new() };
+    let children: Vec<std::path::PathBuf> = entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect();
+    if version_path.join(exe_name).is_file() && !children.iter().any(|child| recorded(child)) {
+        return Vec::new();
+    }
+    children.into_iter().filter(|child| child.join(exe_name).is_file() || recorded(child)).collect()
+}
+
+/// Sort key for a build from its recorded metadata, falling back to folder creation time
+pub fn version_sort_key(metadata: Option<&InstalledVersionMetadata>, created: Option<i64>) -> i64 {
+    let parse = |value: &Option<String>| {
+        value
+            .as_deref()
+            .and_then(|v| chrono::DateTime::parse_from_rfc3339(v).ok())
+            .map(|d| d.timestamp())
+    };
+    metadata
+        .and_then(|m| parse(&m.published_at).or_else(|| parse(&m.installed_at)))
+        .or(created)
+        .unwrap_or(0)
+}
+
+/// Total size of the files under `path`, not following symlinks
+pub fn directory_size(path: &std::path::Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else { return 0 };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => directory_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Keep the newest `keep` builds of each backend and return the rest, skipping
+/// protected ones. Protected builds still count towards `keep`.
+pub fn select_versions_to_prune(candidates: &[PruneCandidate], keep: usize) -> Vec<PruneCandidate> {
+    let mut by_backend: HashMap<&str, Vec<&PruneCandidate>> = HashMap::new();
+    for candidate in candidates {
+        by_backend.entry(candidate.backend_type.as_str()).or_default().push(candidate);
+    }
+
+    let mut to_remove = Vec::new();
+    for builds in by_backend.values_mut() {
+        builds.sort_by(|a, b| b.sort_key.cmp(&a.sort_key).then_with(|| b.name.cmp(&a.name)));
+        to_remove.extend(builds.iter().skip(keep).filter(|c| !c.protected).map(|c| (*c).clone()));
+    }
+    to_remove.sort_by(|a, b| a.path.cmp(&b.path));
+    to_remove
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_metadata_round_trips_and_removes_entries() {
+        let root = std::env::temp_dir().join(format!("arandu-versions-{}", uuid::Uuid::new_v4()));
+        let version_path = root.join("b5000").join("cuda");
+        let key = version_metadata_key(&root, &version_path).unwrap();
+        assert_eq!(key, "b5000/cuda");
+
+        update_version_metadata(&root, &key, |entry| {
+            *entry = Some(InstalledVersionMetadata {
+                tag_name: Some("b5000".to_string()),
+                backend_type: Some("cuda".to_string()),
+                ..Default::default()
+            });
+        })
+        .unwrap();
+        let loaded = load_versions_metadata(&root);
+        assert_eq!(loaded[&key].tag_name.as_deref(), Some("b5000"));
+
+        update_version_metadata(&root, &key, |entry| *entry = None).unwrap();
+        assert!(load_versions_metadata(&root).is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn select_versions_to_prune_keeps_newest_per_backend_and_protected() {
+        let candidate = |name: &str, backend: &str, sort_key: i64, protected: bool| PruneCandidate {
+            path: format!("/versions/{}/{}", name, backend),
+            name: format!("{}-{}", name, backend),
+            backend_type: backend.to_string(),
+            sort_key,
+            protected,
+            size_bytes: 0,
+        };
+        let candidates = vec![
+            candidate("b100", "cuda", 100, false),
+            candidate("b200", "cuda", 200, false),
+            candidate("b300", "cuda", 300, false),
+            candidate("b050", "cuda", 50, true),
+            candidate("b100", "vulkan", 100, false),
+        ];
+
+        let removed = select_versions_to_prune(&candidates, 2);
+        let paths: Vec<&str> = removed.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["/versions/b100/cuda"]);
+
+        assert!(select_versions_to_prune(&candidates, 0).iter().all(|c| !c.protected));
+        assert!(same_version_path("/versions/b100/cuda/", "/versions/b100/cuda"));
+        assert!(overlapping_version_paths("/versions/b100/cuda/bin", "/versions/b100/cuda"));
+        assert!(!overlapping_version_paths("/versions/b100/cuda2", "/versions/b100/cuda"));
+    }
+
+    #[test]
+    fn flat_installs_have_no_backend_folders() {
+        let root = std::env::temp_dir().join(format!("arandu-backends-{}", uuid::Uuid::new_v4()));
+        let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+        let touch = |path: std::path::PathBuf| {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, b"").unwrap();
+        };
+        // Flat: llama-server at the top, with bin/ and lib/ beside it
+        touch(root.join("b100").join(exe_name));
+        touch(root.join("b100").join("bin").join("llama-cli"));
+        touch(root.join("b100").join("lib").join("libggml.so"));
+        // Nested: one backend found by its server, one by its versions.json entry
+        touch(root.join("b200").join("cuda").join(exe_name));
+        std::fs::create_dir_all(root.join("b200").join("vulkan")).unwrap();
+        std::fs::create_dir_all(root.join("b200").join("notes")).unwrap();
+        update_version_metadata(&root, "b200/vulkan", |entry| *entry = Some(InstalledVersionMetadata::default())).unwrap();
+
+        let metadata = load_versions_metadata(&root);
+        assert!(backend_dirs(&root, &root.join("b100"), &metadata).is_empty());
+        let mut nested = backend_dirs(&root, &root.join("b200"), &metadata);
+        nested.sort();
+        assert_eq!(nested, vec![root.join("b200").join("cuda"), root.join("b200").join("vulkan")]);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
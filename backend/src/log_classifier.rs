@@ -0,0 +1,161 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+// Severity tagging for llama-server output. llama.cpp writes nearly everything to
+// stderr, so the stream says little about severity; the line content does.
+// Stored lines keep a leading tag (`[ERROR] ...`) so existing consumers still get
+// plain strings and filters can recover the severity later.
+
+/// `--log-prefix` / `--log-timestamps` format: `0.01.234.567 W message`
+static LEVEL_PREFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:\d+\.\d+\.\d+\.\d+\s+)?([DIWE])\s").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSeverity {
+    Debug,
+    Info,
+    Progress,
+    Warning,
+    Error,
+}
+
+impl LogSeverity {
+    pub fn tag(self) -> &'static str {
+        match self {
+            LogSeverity::Debug => "[DEBUG]",
+            LogSeverity::Info => "[INFO]",
+            LogSeverity::Progress => "[PROGRESS]",
+            LogSeverity::Warning => "[WARN]",
+            LogSeverity::Error => "[ERROR]",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "debug" => Some(LogSeverity::Debug),
+            "info" => Some(LogSeverity::Info),
+            "progress" => Some(LogSeverity::Progress),
+            "warn" | "warning" => Some(LogSeverity::Warning),
+            "error" => Some(LogSeverity::Error),
+            _ => None,
+        }
+    }
+}
+
+const ERROR_MARKERS: &[&str] = &[
+    "error:",
+    "error while",
+    "failed to",
+    "ggml_assert",
+    "cuda error",
+    "out of memory",
+    "segmentation fault",
+    "terminate called",
+    "exception",
+    "panic",
+    "unable to load",
+    "cannot open",
+];
+
+const WARNING_MARKERS: &[&str] = &[
+    "warning:",
+    "warn:",
+    " warn ",
+    "deprecated",
+    "not supported",
+    "falling back",
+    "fallback to",
+];
+
+const PROGRESS_MARKERS: &[&str] = &[
+    "progress",
+    "loading model",
+    "load_tensors: loading",
+    "llama_model_load: loading",
+    "warming up",
+    "prompt processing",
+];
+
+/// Severity of a raw llama.cpp output line
+pub fn classify_line(line: &str) -> LogSeverity {
+    let trimmed = line.trim();
+    if let Some(level) = LEVEL_PREFIX.captures(trimmed).and_then(|c| c.get(1)) {
+        return match level.as_str() {
+            "E" => LogSeverity::Error,
+            "W" => LogSeverity::Warning,
+            "D" => LogSeverity::Debug,
+            _ => classify_text(trimmed),
+        };
+    }
+    classify_text(trimmed)
+}
+
+fn classify_text(trimmed: &str) -> LogSeverity {
+    // Bare dot runs are the tensor-loading progress bar
+    if !trimmed.is_empty() && trimmed.chars().all(|c| c == '.') {
+        return LogSeverity::Progress;
+    }
+    let lower = trimmed.to_lowercase();
+    if ERROR_MARKERS.iter().any(|m| lower.contains(m)) {
+        LogSeverity::Error
+    } else if WARNING_MARKERS.iter().any(|m| lower.contains(m)) {
+        LogSeverity::Warning
+    } else if PROGRESS_MARKERS.iter().any(|m| lower.contains(m)) {
+        LogSeverity::Progress
+    } else {
+        LogSeverity::Info
+    }
+}
+
+/// Prefix a line for storage. Plain stdout lines keep their `[OUT]` tag.
+pub fn tag_line(line: &str, from_stderr: bool) -> String {
+    let severity = classify_line(line);
+    if !from_stderr && severity == LogSeverity::Info {
+        return format!("[OUT] {}", line);
+    }
+    format!("{} {}", severity.tag(), line)
+}
+
+/// Severity of a stored line, read from its tag or classified when untagged
+pub fn severity_of_stored(line: &str) -> LogSeverity {
+    const TAGS: &[(&str, LogSeverity)] = &[
+        ("[ERROR]", LogSeverity::Error),
+        ("[WARN]", LogSeverity::Warning),
+        ("[PROGRESS]", LogSeverity::Progress),
+        ("[DEBUG]", LogSeverity::Debug),
+        ("[INFO]", LogSeverity::Info),
+        ("[OUT]", LogSeverity::Info),
+    ];
+    TAGS.iter()
+        .find(|(tag, _)| line.starts_with(*tag))
+        .map(|(_, severity)| *severity)
+        .unwrap_or_else(|| classify_line(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_llama_cpp_output() {
+        assert_eq!(classify_line("llama_model_load: error loading model: failed to open file"), LogSeverity::Error);
+        assert_eq!(classify_line("ggml_backend_cuda_buffer_type_alloc_buffer: allocating 8192 MiB on device 0: cudaMalloc failed: out of memory"), LogSeverity::Error);
+        assert_eq!(classify_line("warning: no usable GPU found, --gpu-layers option will be ignored"), LogSeverity::Warning);
+        assert_eq!(classify_line("0.00.412.118 W flash_attn is not compatible with this model"), LogSeverity::Warning);
+        assert_eq!(classify_line("..............................................."), LogSeverity::Progress);
+        assert_eq!(classify_line("slot update_slots: id  0 | task 0 | prompt processing progress, n_past = 512"), LogSeverity::Progress);
+        assert_eq!(classify_line("main: server is listening on http://127.0.0.1:8080"), LogSeverity::Info);
+    }
+
+    #[test]
+    fn stored_lines_round_trip_their_severity() {
+        assert_eq!(tag_line("hello", false), "[OUT] hello");
+        assert_eq!(tag_line("error: boom", true), "[ERROR] error: boom");
+        assert_eq!(severity_of_stored(&tag_line("warning: slow", true)), LogSeverity::Warning);
+        assert_eq!(severity_of_stored("Process exited with code: 1"), LogSeverity::Info);
+        assert_eq!(LogSeverity::parse("Warning"), Some(LogSeverity::Warning));
+        assert!(LogSeverity::Error > LogSeverity::Warning);
+    }
+}
@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Token logprobs for assistant replies, kept next to the chat as
+// `<chat>.logprobs.json` so the markdown log stays readable. Entries use short
+// field names and rounded values because a long reply has thousands of tokens.
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenLogprob {
+    #[serde(rename = "t")]
+    pub token: String,
+    #[serde(rename = "lp")]
+    pub logprob: f32,
+    /// Most likely alternatives as `(token, logprob)`
+    #[serde(rename = "alt", default, skip_serializing_if = "Vec::is_empty")]
+    pub alternatives: Vec<(String, f32)>,
+}
+
+fn round(logprob: f64) -> f32 {
+    ((logprob * 1000.0).round() / 1000.0) as f32
+}
+
+/// llama.cpp's older `probs` entries carry a probability, not a logprob
+fn entry_logprob(entry: &Value) -> Option<f64> {
+    entry
+        .get("logprob")
+        .and_then(|v| v.as_f64())
+        .or_else(|| entry.get("prob").and_then(|v| v.as_f64()).map(|p| p.max(f64::MIN_POSITIVE).ln()))
+}
+
+fn entry_token(entry: &Value) -> Option<String> {
+    ["token", "tok_str", "content"]
+        .iter()
+        .find_map(|key| entry.get(*key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+}
+
+fn parse_entry(entry: &Value) -> Option<TokenLogprob> {
+    let token = entry_token(entry)?;
+    let alternatives: Vec<(String, f32)> = entry
+        .get("top_logprobs")
+        .or_else(|| entry.get("probs"))
+        .and_then(|v| v.as_array())
+        .map(|alts| {
+            alts.iter()
+                .filter_map(|alt| Some((entry_token(alt)?, round(entry_logprob(alt)?))))
+                .collect()
+        })
+        .unwrap_or_default();
+    // Old format: the chosen token's probability is only listed among the alternatives
+    let logprob = entry_logprob(entry)
+        .map(round)
+        .or_else(|| alternatives.iter().find(|(t, _)| *t == token).map(|(_, lp)| *lp))?;
+    Some(TokenLogprob {
+        token,
+        logprob,
+        alternatives: alternatives.into_iter().filter(|(t, _)| !t.is_empty()).collect(),
+    })
+}
+
+/// Token entries from a completion response, a concatenation of stream chunks, or the
+/// bare `logprobs.content` / `completion_probabilities` array
+pub fn extract(value: &Value) -> Vec<TokenLogprob> {
+    let entries: Vec<&Value> = match value {
+        Value::Array(items) if items.iter().any(|i| i.get("choices").is_some()) => {
+            items.iter().flat_map(|chunk| extract_entries(chunk)).collect()
+        }
+        Value::Array(items) => items.iter().collect(),
+        other => extract_entries(other),
+    };
+    entries.into_iter().filter_map(parse_entry).collect()
+}
+
+fn extract_entries(value: &Value) -> Vec<&Value> {
+    let content = value
+        .pointer("/choices/0/logprobs/content")
+        .or_else(|| value.pointer("/logprobs/content"))
+        .or_else(|| value.get("completion_probabilities"))
+        .or_else(|| value.get("content").filter(|v| v.is_array()));
+    content.and_then(|v| v.as_array()).map(|a| a.iter().collect()).unwrap_or_default()
+}
+
+fn logprobs_path(chat_path: &Path) -> PathBuf {
+    chat_path.with_extension("logprobs.json")
+}
+
+fn read_all(chat_path: &Path) -> BTreeMap<usize, Vec<TokenLogprob>> {
+    fs::read_to_string(logprobs_path(chat_path))
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn store(chat_path: &Path, message_index: usize, tokens: Vec<TokenLogprob>) -> Result<(), String> {
+    let mut all = read_all(chat_path);
+    all.insert(message_index, tokens);
+    let content = serde_json::to_string(&all).map_err(|e| format!("Failed to serialize logprobs: {}", e))?;
+    let path = logprobs_path(chat_path);
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write logprobs temp file: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| format!("Failed to rename logprobs temp file: {}", e))
+}
+
+pub fn load(chat_path: &Path, message_index: usize) -> Option<Vec<TokenLogprob>> {
+    read_all(chat_path).remove(&message_index)
+}
+
+pub fn remove_all(chat_path: &Path) {
+    let path = logprobs_path(chat_path);
+    if path.exists() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_openai_and_legacy_llama_formats() {
+        let openai = serde_json::json!({
+            "choices": [{"logprobs": {"content": [
+                {"token": "Hi", "logprob": -0.01234, "top_logprobs": [{"token": "Hi", "logprob": -0.01234}, {"token": "Hey", "logprob": -4.5}]}
+            ]}}]
+        });
+        let tokens = extract(&openai);
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].logprob, -0.012);
+        assert_eq!(tokens[0].alternatives[1], ("Hey".to_string(), -4.5));
+
+        let legacy = serde_json::json!({"completion_probabilities": [
+            {"content": "Hello", "probs": [{"tok_str": "Hello", "prob": 1.0}, {"tok_str": "Hi", "prob": 0.0}]}
+        ]});
+        let tokens = extract(&legacy);
+        assert_eq!(tokens[0].token, "Hello");
+        assert_eq!(tokens[0].logprob, 0.0);
+
+        let compact = serde_json::to_string(&tokens[0]).unwrap();
+        assert!(compact.starts_with("{\"t\":\"Hello\",\"lp\":0.0"));
+    }
+}
@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+
+// Windows paths past MAX_PATH (260 UTF-16 units, 248 for directories) fail in
+// llama-server and other programs unless written in the extended `\\?\` form, which
+// also turns off Windows' own `/` and `..` handling. Nested author/model folders with
+// CJK names reach the limit quickly. Paths Arandu hands to other programs, or opens
+// itself, go through `extended`; paths shown to the user or stored in settings go
+// through `strip_verbatim` so they stay in the usual form. Off Windows both are no-ops.
+
+/// Longest directory path Win32 accepts without the `\\?\` form
+const MAX_DIR_PATH: usize = 248;
+
+/// Whether `path` is too long for Win32 without the `\\?\` form. Counted in UTF-16 units
+/// like Windows does, not bytes: a CJK character is three bytes but one unit.
+pub fn exceeds_max_path(path: &Path) -> bool {
+    path.as_os_str().to_string_lossy().encode_utf16().count() >= MAX_DIR_PATH
+}
+
+/// The `\\?\` form of an absolute Windows path: separators become `\`, `.` and `..` are
+/// resolved, and `\\server\share` becomes `\\?\UNC\server\share`. `None` for paths
+/// already in that form and for relative ones.
+fn verbatim(path: &str) -> Option<String> {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return None;
+    }
+    let path = path.replace('/', "\\");
+    let (prefix, rest) = if let Some(unc) = path.strip_prefix(r"\\") {
+        let mut parts = unc.splitn(3, '\\');
+        let (server, share) = (parts.next()?, parts.next()?);
+        (format!(r"\\?\UNC\{}\{}", server, share), parts.next().unwrap_or("").to_string())
+    } else {
+        let bytes = path.as_bytes();
+        if bytes.len() < 3 || !bytes[0].is_ascii_alphabetic() || bytes[1] != b':' || bytes[2] != b'\\' {
+            return None;
+        }
+        (format!(r"\\?\{}", &path[..2].to_ascii_uppercase()), path[3..].to_string())
+    };
+
+    let mut parts: Vec<&str> = Vec::new();
+    for part in rest.split('\\') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    Some(format!(r"{}\{}", prefix, parts.join("\\")))
+}
+
+/// `path` in the `\\?\` form when it is too long for Win32, otherwise unchanged
+pub fn extended(path: &Path) -> PathBuf {
+    if !cfg!(windows) || !exceeds_max_path(path) {
+        return path.to_path_buf();
+    }
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match verbatim(&absolute.to_string_lossy()) {
+        Some(verbatim) => PathBuf::from(verbatim),
+        None => absolute,
+    }
+}
+
+/// `extended` for a path passed as a program argument
+pub fn extended_arg(path: &str) -> String {
+    extended(Path::new(path)).to_string_lossy().to_string()
+}
+
+/// `path` without a `\\?\` prefix, as `canonicalize` adds on Windows
+pub fn strip_verbatim(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", unc))
+    } else if let Some(disk) = text.strip_prefix(r"\\?\") {
+        PathBuf::from(disk)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_and_unicode_paths_get_the_extended_form() {
+        let deep = format!(r"C:\models\{}\model.gguf", "author-with-a-long-name\\".repeat(12));
+        assert!(exceeds_max_path(Path::new(&deep)));
+        let converted = verbatim(&deep).unwrap();
+        assert!(converted.starts_with(r"\\?\C:\models\author-with-a-long-name\") && converted.ends_with(r"\model.gguf"));
+        assert!(!converted.contains(r"\\author"));
+
+        // 100 CJK characters are 300 bytes but only 100 UTF-16 units
+        let cjk = format!(r"D:\模型\{}.gguf", "通".repeat(100));
+        assert!(cjk.len() > 300 && !exceeds_max_path(Path::new(&cjk)));
+        assert!(exceeds_max_path(Path::new(&format!(r"D:\模型\{}.gguf", "通".repeat(250)))));
+        assert_eq!(verbatim(r"d:/模型/./通义/../千问.gguf").unwrap(), r"\\?\D:\模型\千问.gguf");
+
+        assert_eq!(verbatim(r"\\nas\share\models\a.gguf").unwrap(), r"\\?\UNC\nas\share\models\a.gguf");
+        assert_eq!(verbatim(r"\\?\C:\models\a.gguf"), None);
+        assert_eq!(verbatim(r"models\a.gguf"), None);
+
+        assert_eq!(strip_verbatim(Path::new(r"\\?\C:\models\a.gguf")), PathBuf::from(r"C:\models\a.gguf"));
+        assert_eq!(strip_verbatim(Path::new(r"\\?\UNC\nas\share\a.gguf")), PathBuf::from(r"\\nas\share\a.gguf"));
+        // Short paths are left alone
+        assert_eq!(extended(Path::new(r"C:\models\a.gguf")), PathBuf::from(r"C:\models\a.gguf"));
+    }
+}
@@ -0,0 +1,97 @@
+use std::path::{Path, PathBuf};
+use crate::AppState;
+
+// macOS specifics. Data lives under `~/Library/Application Support/Arandu` like other
+// Mac apps (an existing `~/.Arandu` keeps being used so nothing is stranded), and while
+// a model is loading or serving the app holds an NSProcessInfo activity so App Nap does
+// not throttle the proxy, health probes and output readers once the window is hidden.
+// Everything here compiles on every platform; the App Nap guard is a no-op elsewhere.
+
+/// How often the guard looks at the running processes
+#[cfg(target_os = "macos")]
+const GUARD_POLL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Base folder for settings, models and llama.cpp builds on macOS
+pub fn data_base_dir(home: &Path, application_support: Option<&Path>) -> PathBuf {
+    let legacy = home.join(".Arandu");
+    match application_support {
+        Some(support) if !legacy.exists() => support.join("Arandu"),
+        _ => legacy,
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod app_nap {
+    use objc2_foundation::{NSActivityOptions, NSProcessInfo, NSString};
+    use std::sync::mpsc::{self, Sender};
+    use std::sync::Mutex;
+
+    /// Dropping the sender ends the activity on its holder thread
+    static ACTIVITY: Mutex<Option<Sender<()>>> = Mutex::new(None);
+
+    pub fn hold(active: bool) {
+        let mut activity = ACTIVITY.lock().unwrap_or_else(|p| p.into_inner());
+        if active == activity.is_some() {
+            return;
+        }
+        if !active {
+            activity.take();
+            println!("[Arandu] No models serving; App Nap allowed again");
+            return;
+        }
+        // The activity token is not Send, so one thread owns it from begin to end
+        let (tx, rx) = mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            let info = NSProcessInfo::processInfo();
+            let reason = NSString::from_str("Serving local models");
+            let token = unsafe {
+                info.beginActivityWithOptions_reason(NSActivityOptions::NSActivityUserInitiatedAllowingIdleSystemSleep, &reason)
+            };
+            let _ = rx.recv();
+            unsafe { info.endActivity(&token) };
+        });
+        *activity = Some(tx);
+        println!("[Arandu] Models serving; App Nap disabled");
+    }
+}
+
+/// Keep App Nap off while any local model is starting, running or suspended
+#[cfg(target_os = "macos")]
+pub fn spawn_app_nap_guard(state: AppState) {
+    use crate::models::ProcessStatus;
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let serving = state
+                .running_processes
+                .lock()
+                .await
+                .values()
+                .any(|p| !p.model_path.starts_with("tunnel:") && matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running | ProcessStatus::Suspended));
+            app_nap::hold(serving);
+            tokio::time::sleep(GUARD_POLL).await;
+        }
+    });
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn spawn_app_nap_guard(_state: AppState) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_application_support_unless_legacy_folder_exists() {
+        let root = std::env::temp_dir().join(format!("arandu-macos-{}", std::process::id()));
+        let home = root.join("home");
+        let support = home.join("Library").join("Application Support");
+        std::fs::create_dir_all(&home).unwrap();
+
+        assert_eq!(data_base_dir(&home, Some(&support)), support.join("Arandu"));
+        assert_eq!(data_base_dir(&home, None), home.join(".Arandu"));
+        std::fs::create_dir_all(home.join(".Arandu")).unwrap();
+        assert_eq!(data_base_dir(&home, Some(&support)), home.join(".Arandu"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
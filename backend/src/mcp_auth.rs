@@ -0,0 +1,287 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::Emitter;
+use tokio::time::Duration;
+use crate::http_pool;
+use crate::models::{McpAuthKind, McpServerConfig};
+use crate::AppState;
+
+// Authentication for hosted MCP servers. A connection either sends its static headers
+// only, a bearer token, or a token obtained through the OAuth device-code flow
+// (RFC 8628). Tokens live in the OS keyring under the connection id, never in
+// settings.json; OAuth tokens are refreshed shortly before they expire.
+
+const KEYRING_SERVICE: &str = "arandu-mcp";
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+/// Refresh this long before `expires_at`
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct StoredToken {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    token_type: Option<String>,
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceCode {
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: Option<String>,
+    pub expires_in: u64,
+}
+
+#[derive(Debug, PartialEq)]
+enum TokenPoll {
+    Token(StoredToken),
+    Pending,
+    SlowDown,
+}
+
+fn keyring_entry(id: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, id).map_err(|e| format!("Failed to open keyring: {}", e))
+}
+
+fn load(id: &str) -> Result<Option<StoredToken>, String> {
+    match keyring_entry(id)?.get_password() {
+        Ok(raw) => Ok(serde_json::from_str(&raw).ok()),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read MCP token from keyring: {}", e)),
+    }
+}
+
+fn store(id: &str, token: &StoredToken) -> Result<(), String> {
+    let raw = serde_json::to_string(token).map_err(|e| format!("Failed to serialize MCP token: {}", e))?;
+    keyring_entry(id)?
+        .set_password(&raw)
+        .map_err(|e| format!("Failed to store MCP token in keyring: {}", e))
+}
+
+pub fn forget(id: &str) -> Result<(), String> {
+    match keyring_entry(id)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove MCP token from keyring: {}", e)),
+    }
+}
+
+async fn blocking<T: Send + 'static>(f: impl FnOnce() -> Result<T, String> + Send + 'static) -> Result<T, String> {
+    tokio::task::spawn_blocking(f).await.map_err(|e| format!("Keyring task failed: {}", e))?
+}
+
+/// Store (or with an empty `token`, remove) the bearer token of a connection
+pub async fn set_bearer_token(id: String, token: String) -> Result<(), String> {
+    let token = token.trim().to_string();
+    blocking(move || {
+        if token.is_empty() {
+            return forget(&id);
+        }
+        store(&id, &StoredToken { access_token: token, refresh_token: None, token_type: None, expires_at: None })
+    })
+    .await
+}
+
+fn form_body(pairs: &[(&str, &str)]) -> String {
+    pairs
+        .iter()
+        .map(|(key, value)| format!("{}={}", urlencoding::encode(key), urlencoding::encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+async fn post_form(url: &str, pairs: &[(&str, &str)]) -> Result<Value, String> {
+    let response = http_pool::client_for(url)
+        .post(url)
+        .header("content-type", "application/x-www-form-urlencoded")
+        .header("accept", "application/json")
+        .body(form_body(pairs))
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact {}: {}", url, e))?;
+    // Token endpoints report pending / denied states as 400 with a JSON body
+    response.json().await.map_err(|e| format!("Failed to parse OAuth response: {}", e))
+}
+
+fn parse_token_response(body: &Value, now: DateTime<Utc>, previous_refresh: Option<&str>) -> Result<TokenPoll, String> {
+    if let Some(access_token) = body.get("access_token").and_then(|v| v.as_str()).filter(|t| !t.is_empty()) {
+        return Ok(TokenPoll::Token(StoredToken {
+            access_token: access_token.to_string(),
+            // Servers may keep the old refresh token without repeating it
+            refresh_token: body
+                .get("refresh_token")
+                .and_then(|v| v.as_str())
+                .or(previous_refresh)
+                .map(str::to_string),
+            token_type: body.get("token_type").and_then(|v| v.as_str()).map(str::to_string),
+            expires_at: body.get("expires_in").and_then(|v| v.as_i64()).map(|secs| now + chrono::Duration::seconds(secs)),
+        }));
+    }
+    match body.get("error").and_then(|v| v.as_str()) {
+        Some("authorization_pending") => Ok(TokenPoll::Pending),
+        Some("slow_down") => Ok(TokenPoll::SlowDown),
+        Some(error) => {
+            let description = body.get("error_description").and_then(|v| v.as_str()).unwrap_or(error);
+            Err(description.to_string())
+        }
+        None => Err("OAuth server returned no access token".to_string()),
+    }
+}
+
+fn needs_refresh(token: &StoredToken, now: DateTime<Utc>) -> bool {
+    token.expires_at.is_some_and(|at| at - chrono::Duration::seconds(REFRESH_MARGIN_SECS) <= now)
+}
+
+async fn refresh(connection: &McpServerConfig, token: StoredToken) -> Result<StoredToken, String> {
+    let Some(refresh_token) = token.refresh_token.clone() else {
+        return Err("The sign-in has expired; sign in again".to_string());
+    };
+    let auth = &connection.auth;
+    let body = post_form(
+        &auth.token_url,
+        &[("grant_type", "refresh_token"), ("refresh_token", &refresh_token), ("client_id", &auth.client_id)],
+    )
+    .await?;
+    match parse_token_response(&body, Utc::now(), Some(&refresh_token)) {
+        Ok(TokenPoll::Token(token)) => {
+            let (id, stored) = (connection.id.clone(), token.clone());
+            blocking(move || store(&id, &stored)).await?;
+            println!("[MCP] Refreshed OAuth token for {}", connection.name);
+            Ok(token)
+        }
+        Ok(_) => Err("Token refresh did not return a token".to_string()),
+        Err(e) => Err(format!("The sign-in has expired ({}); sign in again", e)),
+    }
+}
+
+/// `connection` with its `Authorization` header, refreshing an OAuth token when due
+pub async fn authorized(mut connection: McpServerConfig) -> Result<McpServerConfig, String> {
+    if connection.auth.kind == McpAuthKind::None
+        || connection.headers.keys().any(|k| k.trim().eq_ignore_ascii_case("authorization"))
+    {
+        return Ok(connection);
+    }
+    let id = connection.id.clone();
+    let token = blocking(move || load(&id)).await?.ok_or_else(|| match connection.auth.kind {
+        McpAuthKind::OauthDevice => format!("Sign in to {} first", connection.name),
+        _ => format!("No token saved for {}; add one in its settings", connection.name),
+    })?;
+    let token = if connection.auth.kind == McpAuthKind::OauthDevice && needs_refresh(&token, Utc::now()) {
+        refresh(&connection, token).await?
+    } else {
+        token
+    };
+    let scheme = token.token_type.as_deref().filter(|t| !t.eq_ignore_ascii_case("bearer")).unwrap_or("Bearer");
+    connection.headers.insert("Authorization".to_string(), format!("{} {}", scheme, token.access_token));
+    Ok(connection)
+}
+
+/// Kind of auth and whether a token is stored, for the connection list
+pub async fn status(connection: &McpServerConfig) -> Result<Value, String> {
+    let id = connection.id.clone();
+    let token = blocking(move || load(&id)).await?;
+    Ok(serde_json::json!({
+        "kind": connection.auth.kind,
+        "has_token": token.is_some(),
+        "expires_at": token.as_ref().and_then(|t| t.expires_at),
+        "can_refresh": token.as_ref().is_some_and(|t| t.refresh_token.is_some()),
+    }))
+}
+
+/// Begin the device-code flow: returns the code to show the user and keeps polling in
+/// the background, emitting `mcp-oauth-status` when it ends
+pub async fn start_device_flow(state: &AppState, app_handle: tauri::AppHandle, id: &str) -> Result<DeviceCode, String> {
+    let connection = crate::mcp_catalog::connection(state, id).await?;
+    let auth = connection.auth.clone();
+    if auth.kind != McpAuthKind::OauthDevice {
+        return Err("This connection is not set up for OAuth sign-in".to_string());
+    }
+    if auth.device_authorization_url.trim().is_empty() || auth.token_url.trim().is_empty() || auth.client_id.trim().is_empty() {
+        return Err("OAuth sign-in needs a device authorization URL, token URL and client id".to_string());
+    }
+
+    let mut pairs = vec![("client_id", auth.client_id.as_str())];
+    if !auth.scope.trim().is_empty() {
+        pairs.push(("scope", auth.scope.as_str()));
+    }
+    let body = post_form(&auth.device_authorization_url, &pairs).await?;
+    let text = |key: &str| body.get(key).and_then(|v| v.as_str()).map(str::to_string);
+    let device_code = text("device_code").ok_or_else(|| {
+        let error = text("error_description").or_else(|| text("error")).unwrap_or_else(|| "no device code returned".to_string());
+        format!("OAuth sign-in could not start: {}", error)
+    })?;
+    let code = DeviceCode {
+        user_code: text("user_code").unwrap_or_default(),
+        verification_uri: text("verification_uri").or_else(|| text("verification_url")).unwrap_or_default(),
+        verification_uri_complete: text("verification_uri_complete"),
+        expires_in: body.get("expires_in").and_then(|v| v.as_u64()).unwrap_or(900),
+    };
+    let mut interval = body.get("interval").and_then(|v| v.as_u64()).unwrap_or(5).max(1);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(code.expires_in);
+
+    let id = id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let emit = |status: &str, message: String| {
+            let _ = app_handle.emit("mcp-oauth-status", serde_json::json!({ "id": id, "status": status, "message": message }));
+        };
+        while tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+            let poll = post_form(&auth.token_url, &[("grant_type", DEVICE_CODE_GRANT), ("device_code", &device_code), ("client_id", &auth.client_id)])
+                .await
+                .and_then(|body| parse_token_response(&body, Utc::now(), None));
+            match poll {
+                Ok(TokenPoll::Pending) => {}
+                Ok(TokenPoll::SlowDown) => interval += 5,
+                Ok(TokenPoll::Token(token)) => {
+                    let stored_id = id.clone();
+                    match blocking(move || store(&stored_id, &token)).await {
+                        Ok(()) => {
+                            println!("[MCP] OAuth sign-in completed for {}", connection.name);
+                            emit("authorized", "Signed in".to_string());
+                        }
+                        Err(e) => emit("failed", e),
+                    }
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("[MCP] OAuth sign-in failed for {}: {}", connection.name, e);
+                    emit("failed", e);
+                    return;
+                }
+            }
+        }
+        emit("expired", "The sign-in code expired".to_string());
+    });
+
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_token_responses_and_refresh_deadlines() {
+        let now = Utc::now();
+        assert_eq!(parse_token_response(&serde_json::json!({"error": "authorization_pending"}), now, None), Ok(TokenPoll::Pending));
+        assert_eq!(parse_token_response(&serde_json::json!({"error": "slow_down"}), now, None), Ok(TokenPoll::SlowDown));
+        assert_eq!(
+            parse_token_response(&serde_json::json!({"error": "access_denied", "error_description": "User declined"}), now, None),
+            Err("User declined".to_string())
+        );
+
+        let body = serde_json::json!({"access_token": "abc", "token_type": "Bearer", "expires_in": 30});
+        let Ok(TokenPoll::Token(token)) = parse_token_response(&body, now, Some("old-refresh")) else { panic!("expected a token") };
+        assert_eq!(token.refresh_token.as_deref(), Some("old-refresh"));
+        assert_eq!(token.expires_at, Some(now + chrono::Duration::seconds(30)));
+        // 30 s left is inside the refresh margin
+        assert!(needs_refresh(&token, now));
+        assert!(!needs_refresh(&StoredToken { expires_at: None, ..token }, now));
+
+        assert_eq!(form_body(&[("scope", "read write"), ("client_id", "a&b")]), "scope=read%20write&client_id=a%26b");
+    }
+}
@@ -0,0 +1,230 @@
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tokio::time::Duration;
+use crate::http_pool;
+use crate::models::{McpPromptInfo, McpResourceInfo, McpServerConfig, McpTransport};
+use crate::AppState;
+
+// Resources and prompts of MCP servers, next to the tools handled in lib.rs.
+// `resources/list` and `prompts/list` results are stored on the connection; a server
+// that does not offer one of them answers "method not found", which counts as an empty
+// list rather than an error. `resources/read` and `prompts/get` are sent on demand.
+
+const METHOD_NOT_FOUND: i64 = -32601;
+/// Pages followed through `nextCursor` per list
+const MAX_PAGES: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogResult {
+    pub success: bool,
+    pub resources: Vec<McpResourceInfo>,
+    pub prompts: Vec<McpPromptInfo>,
+    pub errors: Vec<String>,
+}
+
+/// An enabled connection by id
+pub async fn connection(state: &AppState, id: &str) -> Result<McpServerConfig, String> {
+    let connection = {
+        let config = state.config.lock().await;
+        config.mcp_servers.iter().find(|item| item.id == id).cloned().ok_or_else(|| "MCP connection not found".to_string())?
+    };
+    if !connection.enabled {
+        return Err("Connection is disabled. Enable it first.".to_string());
+    }
+    Ok(connection)
+}
+
+/// Send one JSON-RPC request and return the response body
+async fn request(connection: &McpServerConfig, method: &str, params: Value) -> Result<Value, String> {
+    let timeout_duration = Duration::from_secs(connection.timeout_seconds.max(1));
+    let effective = if matches!(connection.transport, McpTransport::Json) && crate::resolve_mcp_url(connection).is_none() {
+        crate::resolve_json_stdio_connection(connection).unwrap_or_else(|| connection.clone())
+    } else {
+        connection.clone()
+    };
+    let effective = crate::mcp_auth::authorized(effective).await?;
+
+    if matches!(effective.transport, McpTransport::Stdio) {
+        // Stdio servers are started per request, which can take a while with npx / uvx
+        let stdio_timeout = Duration::from_secs(connection.timeout_seconds.max(45));
+        return crate::execute_stdio_mcp_request(&effective, method, params, stdio_timeout).await;
+    }
+
+    let url = crate::resolve_mcp_url(&effective).ok_or_else(|| "URL is required for this MCP connection".to_string())?;
+    let client = http_pool::client_for(&url);
+    let initialize = crate::post_mcp_request(&client, &effective.transport, &url, crate::default_mcp_initialize_payload(), &effective.headers, timeout_duration).await?;
+    if !initialize.status().is_success() {
+        return Err(format!("Initialize request returned HTTP {}", initialize.status()));
+    }
+    let payload = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": format!("arandu-{}", method.replace('/', "-")),
+        "method": method,
+        "params": params,
+    });
+    let response = crate::post_mcp_request(&client, &effective.transport, &url, payload, &effective.headers, timeout_duration).await?;
+    if !response.status().is_success() {
+        return Err(format!("{} request returned HTTP {}", method, response.status()));
+    }
+    crate::read_mcp_response_value(response).await
+}
+
+/// `result` of a response, `Ok(None)` when the server does not support the method
+fn result_of(body: &Value) -> Result<Option<&Value>, String> {
+    if let Some(error) = body.get("error") {
+        if error.get("code").and_then(|c| c.as_i64()) == Some(METHOD_NOT_FOUND) {
+            return Ok(None);
+        }
+        return Err(crate::parse_mcp_json_error_message(body).unwrap_or_else(|| error.to_string()));
+    }
+    Ok(Some(body.get("result").unwrap_or(&Value::Null)))
+}
+
+/// Items under `key` of one list page and the cursor of the next
+fn parse_page<T: serde::de::DeserializeOwned>(result: &Value, key: &str) -> (Vec<T>, Option<String>) {
+    let items = result
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|item| serde_json::from_value(item.clone()).ok()).collect())
+        .unwrap_or_default();
+    let cursor = result.get("nextCursor").and_then(|c| c.as_str()).filter(|c| !c.is_empty()).map(str::to_string);
+    (items, cursor)
+}
+
+async fn list<T: serde::de::DeserializeOwned>(connection: &McpServerConfig, method: &str, key: &str) -> Result<Vec<T>, String> {
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let params = match &cursor {
+            Some(cursor) => serde_json::json!({ "cursor": cursor }),
+            None => serde_json::json!({}),
+        };
+        let body = request(connection, method, params).await?;
+        let Some(result) = result_of(&body)? else { break };
+        let (page, next) = parse_page(result, key);
+        items.extend(page);
+        match next {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    Ok(items)
+}
+
+/// List resources and prompts and store them on the connection
+pub async fn refresh(state: &AppState, id: &str) -> Result<CatalogResult, String> {
+    let connection = connection(state, id).await?;
+    let mut errors = Vec::new();
+    let resources = list::<McpResourceInfo>(&connection, "resources/list", "resources")
+        .await
+        .unwrap_or_else(|e| {
+            errors.push(format!("resources/list: {}", e));
+            connection.resources.clone()
+        });
+    let prompts = list::<McpPromptInfo>(&connection, "prompts/list", "prompts")
+        .await
+        .unwrap_or_else(|e| {
+            errors.push(format!("prompts/list: {}", e));
+            connection.prompts.clone()
+        });
+
+    {
+        let mut config = state.config.lock().await;
+        if let Some(conn) = config.mcp_servers.iter_mut().find(|item| item.id == id) {
+            conn.resources = resources.clone();
+            conn.prompts = prompts.clone();
+            conn.catalog_last_refresh_at = Some(Utc::now().to_rfc3339());
+            conn.catalog_last_error = if errors.is_empty() { None } else { Some(errors.join("; ")) };
+        }
+    }
+    if let Err(e) = crate::config::save_settings(state).await {
+        eprintln!("[MCP] Failed to save resources and prompts: {}", e);
+    }
+    println!("[MCP] {}: {} resource(s), {} prompt(s)", connection.name, resources.len(), prompts.len());
+
+    Ok(CatalogResult { success: errors.is_empty(), resources, prompts, errors })
+}
+
+/// Text of resource or prompt contents, one part per text item
+fn joined_text<'a>(items: impl Iterator<Item = &'a Value>) -> String {
+    items.filter_map(|item| item.get("text").and_then(|t| t.as_str())).collect::<Vec<_>>().join("\n\n")
+}
+
+pub async fn read_resource(state: &AppState, id: &str, uri: &str) -> Result<Value, String> {
+    let connection = connection(state, id).await?;
+    let body = request(&connection, "resources/read", serde_json::json!({ "uri": uri })).await?;
+    let result = result_of(&body)?.ok_or_else(|| "This MCP server does not offer resources".to_string())?;
+    let contents = result.get("contents").and_then(|c| c.as_array()).cloned().unwrap_or_default();
+    Ok(serde_json::json!({
+        "uri": uri,
+        "text": joined_text(contents.iter()),
+        "contents": contents,
+    }))
+}
+
+/// Arguments for `prompt`, or which required ones are missing
+fn prompt_arguments(prompt: Option<&McpPromptInfo>, arguments: HashMap<String, String>) -> Result<HashMap<String, String>, String> {
+    let Some(prompt) = prompt else { return Ok(arguments) };
+    let missing: Vec<&str> = prompt
+        .arguments
+        .iter()
+        .filter(|arg| arg.required && arguments.get(&arg.name).is_none_or(|v| v.trim().is_empty()))
+        .map(|arg| arg.name.as_str())
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("Missing prompt argument(s): {}", missing.join(", ")));
+    }
+    Ok(arguments)
+}
+
+/// Render a prompt template into its messages
+pub async fn get_prompt(state: &AppState, id: &str, name: &str, arguments: HashMap<String, String>) -> Result<Value, String> {
+    let connection = connection(state, id).await?;
+    let arguments = prompt_arguments(connection.prompts.iter().find(|p| p.name == name), arguments)?;
+    let body = request(&connection, "prompts/get", serde_json::json!({ "name": name, "arguments": arguments })).await?;
+    let result = result_of(&body)?.ok_or_else(|| "This MCP server does not offer prompts".to_string())?;
+    let messages = result.get("messages").and_then(|m| m.as_array()).cloned().unwrap_or_default();
+    Ok(serde_json::json!({
+        "name": name,
+        "description": result.get("description"),
+        "text": joined_text(messages.iter().filter_map(|m| m.get("content"))),
+        "messages": messages,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::McpPromptArgument;
+
+    #[test]
+    fn parses_pages_and_treats_missing_methods_as_empty() {
+        let page = serde_json::json!({
+            "resources": [
+                {"uri": "file:///notes.md", "name": "notes", "mimeType": "text/markdown"},
+                {"name": "no uri"}
+            ],
+            "nextCursor": "2"
+        });
+        let (resources, next): (Vec<McpResourceInfo>, _) = parse_page(&page, "resources");
+        assert_eq!(resources.len(), 1);
+        assert_eq!(resources[0].mime_type.as_deref(), Some("text/markdown"));
+        assert_eq!(next.as_deref(), Some("2"));
+
+        let unsupported = serde_json::json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32601, "message": "Method not found"}});
+        assert_eq!(result_of(&unsupported), Ok(None));
+        let failed = serde_json::json!({"error": {"code": -32000, "message": "boom"}});
+        assert_eq!(result_of(&failed), Err("boom".to_string()));
+
+        let prompt = McpPromptInfo {
+            name: "review".to_string(),
+            description: None,
+            arguments: vec![McpPromptArgument { name: "code".to_string(), description: None, required: true }],
+        };
+        assert!(prompt_arguments(Some(&prompt), HashMap::new()).unwrap_err().contains("code"));
+        let args = HashMap::from([("code".to_string(), "fn main() {}".to_string())]);
+        assert!(prompt_arguments(Some(&prompt), args).is_ok());
+    }
+}
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::Emitter;
+use crate::models::{McpServerConfig, McpTestResult, McpTransport};
+use crate::AppState;
+
+// Background health checks for MCP servers. Every `mcp_health_check_interval_minutes`
+// the enabled remote connections are probed like the Test button does; each result goes
+// to the `mcp_health_checks` table and `mcp-status-changed` is emitted when a server goes
+// up or down. Stdio servers are started on use, so there is nothing to ping between
+// uses; their manual tests still land in the history.
+
+/// How often to look at the interval again while checks are switched off
+const IDLE_POLL: Duration = Duration::from_secs(60);
+const MAX_CHECKS_PER_CONNECTION: usize = 2_000;
+/// Window for `uptime_percent`
+const UPTIME_WINDOW_HOURS: i64 = 24;
+const RECENT_FAILURES: usize = 5;
+
+/// Last known state per connection id, so events fire on changes only
+static LAST_UP: LazyLock<Mutex<HashMap<String, bool>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HealthCheck {
+    pub connection_id: String,
+    pub success: bool,
+    pub latency_ms: i64,
+    pub message: String,
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HealthSummary {
+    pub connection_id: String,
+    /// `up`, `down` or `unknown` (never checked)
+    pub status: String,
+    /// Successful checks in the last 24 hours, `None` without checks in that window
+    pub uptime_percent: Option<f64>,
+    pub checks: usize,
+    pub last_checked_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// Newest first
+    pub recent_failures: Vec<HealthCheck>,
+}
+
+/// `checks` oldest first, as the repository returns them
+pub fn summarize(connection_id: &str, checks: &[HealthCheck], now: DateTime<Utc>) -> HealthSummary {
+    let window_start = now - chrono::Duration::hours(UPTIME_WINDOW_HOURS);
+    let in_window: Vec<&HealthCheck> = checks.iter().filter(|c| c.checked_at >= window_start).collect();
+    let uptime_percent = (!in_window.is_empty())
+        .then(|| in_window.iter().filter(|c| c.success).count() as f64 * 100.0 / in_window.len() as f64);
+    let status = match checks.last() {
+        Some(last) if last.success => "up",
+        Some(_) => "down",
+        None => "unknown",
+    };
+    HealthSummary {
+        connection_id: connection_id.to_string(),
+        status: status.to_string(),
+        uptime_percent,
+        checks: checks.len(),
+        last_checked_at: checks.last().map(|c| c.checked_at),
+        last_success_at: checks.iter().rev().find(|c| c.success).map(|c| c.checked_at),
+        recent_failures: checks.iter().rev().filter(|c| !c.success).take(RECENT_FAILURES).cloned().collect(),
+    }
+}
+
+/// Store the result of a probe of `connection_id`
+pub async fn record(connection_id: &str, result: &McpTestResult) {
+    let check = HealthCheck {
+        connection_id: connection_id.to_string(),
+        success: result.success,
+        latency_ms: result.latency_ms,
+        message: if result.message.is_empty() { result.error.clone().unwrap_or_default() } else { result.message.clone() },
+        checked_at: Utc::now(),
+    };
+    if let Err(e) = crate::storage::run(move |db| db.mcp_health().record(&check, MAX_CHECKS_PER_CONNECTION)).await {
+        eprintln!("[MCP] {}", e);
+    }
+}
+
+pub async fn health(connection_id: &str) -> Result<HealthSummary, String> {
+    let id = connection_id.to_string();
+    let checks = crate::storage::run(move |db| db.mcp_health().history(&id)).await?;
+    Ok(summarize(connection_id, &checks, Utc::now()))
+}
+
+/// Drop the history of a deleted connection
+pub async fn forget(connection_id: &str) {
+    LAST_UP.lock().unwrap_or_else(|p| p.into_inner()).remove(connection_id);
+    let id = connection_id.to_string();
+    if let Err(e) = crate::storage::run(move |db| db.mcp_health().forget(&id)).await {
+        eprintln!("[MCP] {}", e);
+    }
+}
+
+/// Enabled connections worth pinging: everything reached over HTTP
+fn checkable(connections: &[McpServerConfig]) -> Vec<McpServerConfig> {
+    connections
+        .iter()
+        .filter(|c| c.enabled && !matches!(c.transport, McpTransport::Stdio))
+        .filter(|c| !matches!(c.transport, McpTransport::Json) || crate::resolve_mcp_url(c).is_some())
+        .cloned()
+        .collect()
+}
+
+async fn check_once(state: &AppState, app_handle: &tauri::AppHandle) {
+    let connections = checkable(&state.config.lock().await.mcp_servers);
+    for connection in connections {
+        let result = crate::probe_mcp_connection(&connection, state).await.unwrap_or_else(|e| McpTestResult {
+            success: false,
+            latency_ms: 0,
+            message: e.clone(),
+            status_code: None,
+            exit_code: None,
+            error: Some(e),
+        });
+        record(&connection.id, &result).await;
+
+        let previous = LAST_UP.lock().unwrap_or_else(|p| p.into_inner()).insert(connection.id.clone(), result.success);
+        if previous == Some(result.success) {
+            continue;
+        }
+        println!("[MCP] {} is {}", connection.name, if result.success { "up" } else { "down" });
+        {
+            let mut config = state.config.lock().await;
+            if let Some(conn) = config.mcp_servers.iter_mut().find(|item| item.id == connection.id) {
+                conn.last_test_at = Some(Utc::now().to_rfc3339());
+                conn.last_test_status = Some(if result.success { "ok" } else { "error" }.to_string());
+                conn.last_test_message = Some(result.message.clone());
+            }
+        }
+        if let Err(e) = crate::config::save_settings(state).await {
+            eprintln!("[MCP] Failed to save connection status: {}", e);
+        }
+        let summary = health(&connection.id).await.ok();
+        let _ = app_handle.emit("mcp-status-changed", serde_json::json!({
+            "id": connection.id,
+            "name": connection.name,
+            "status": if result.success { "up" } else { "down" },
+            "previous": previous.map(|up| if up { "up" } else { "down" }),
+            "message": result.message,
+            "latency_ms": result.latency_ms,
+            "uptime_percent": summary.and_then(|s| s.uptime_percent),
+        }));
+    }
+}
+
+pub fn spawn(state: AppState, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let minutes = state.config.lock().await.mcp_health_check_interval_minutes;
+            if minutes == 0 {
+                tokio::time::sleep(IDLE_POLL).await;
+                continue;
+            }
+            check_once(&state, &app_handle).await;
+            tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_reports_uptime_over_the_window_and_recent_failures() {
+        let now = Utc::now();
+        let check = |success, hours_ago| HealthCheck {
+            connection_id: "mcp-1".to_string(),
+            success,
+            latency_ms: 20,
+            message: if success { "ok" } else { "timed out" }.to_string(),
+            checked_at: now - chrono::Duration::hours(hours_ago),
+        };
+        let checks = vec![check(false, 48), check(true, 3), check(false, 2), check(true, 1), check(true, 0)];
+
+        let summary = summarize("mcp-1", &checks, now);
+        assert_eq!(summary.status, "up");
+        assert_eq!(summary.uptime_percent, Some(75.0));
+        assert_eq!(summary.checks, 5);
+        assert_eq!(summary.recent_failures.len(), 2);
+        assert_eq!(summary.recent_failures[0].checked_at, checks[2].checked_at);
+        assert_eq!(summary.last_success_at, Some(checks[4].checked_at));
+
+        let stale = summarize("mcp-1", &checks[..1], now);
+        assert_eq!((stale.status.as_str(), stale.uptime_percent), ("down", None));
+        assert_eq!(summarize("mcp-2", &[], now).status, "unknown");
+    }
+}
@@ -0,0 +1,199 @@
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use crate::models::{McpServerConfig, McpTransport};
+use crate::structured_fixer::{self, Format};
+
+// Import of MCP connections from the `mcpServers` JSON used by Claude Desktop, Cursor
+// and most other clients (`servers` for VS Code). Local servers are
+// `{"command", "args", "env"}`; remote ones are `{"url", "headers", "type"}`. Every entry
+// is validated like a connection saved from the UI and reported on its own, so one bad
+// entry does not stop the rest. Trailing commas, comments and similar slips in the
+// pasted text are repaired before parsing.
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    Imported,
+    Updated,
+    Skipped,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportResult {
+    pub name: String,
+    pub status: ImportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub message: String,
+}
+
+/// The name -> entry map, wherever the file keeps it
+fn server_entries(root: &Value) -> Result<&Map<String, Value>, String> {
+    let root = root.as_object().ok_or_else(|| "MCP config must be a JSON object".to_string())?;
+    for key in ["mcpServers", "mcp_servers", "servers"] {
+        if let Some(entries) = root.get(key) {
+            return entries.as_object().ok_or_else(|| format!("\"{}\" must be an object of servers", key));
+        }
+    }
+    // A bare map of servers, as pasted from a single client's section
+    if !root.is_empty() && root.values().all(|v| v.get("command").is_some() || v.get("url").is_some()) {
+        return Ok(root);
+    }
+    Err("No \"mcpServers\" section found".to_string())
+}
+
+fn string_map(value: Option<&Value>, what: &str) -> Result<HashMap<String, String>, String> {
+    let Some(value) = value.filter(|v| !v.is_null()) else { return Ok(HashMap::new()) };
+    let object = value.as_object().ok_or_else(|| format!("\"{}\" must be an object", what))?;
+    object
+        .iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => Ok((key.clone(), s.clone())),
+            Value::Number(_) | Value::Bool(_) => Ok((key.clone(), value.to_string())),
+            _ => Err(format!("\"{}.{}\" must be a string", what, key)),
+        })
+        .collect()
+}
+
+fn transport_for(entry: &Value) -> Result<McpTransport, String> {
+    let declared = entry.get("type").or_else(|| entry.get("transport")).and_then(|v| v.as_str());
+    match declared.map(|t| t.to_lowercase()) {
+        None if entry.get("command").is_some() => Ok(McpTransport::Stdio),
+        None => Ok(McpTransport::Sse),
+        Some(t) => match t.as_str() {
+            "stdio" => Ok(McpTransport::Stdio),
+            "sse" => Ok(McpTransport::Sse),
+            "http" => Ok(McpTransport::Http),
+            "streamable-http" | "streamable_http" | "streamablehttp" => Ok(McpTransport::StreamableHttp),
+            other => Err(format!("Unsupported transport type '{}'", other)),
+        },
+    }
+}
+
+fn connection_from_entry(name: &str, entry: &Value, id: String) -> Result<McpServerConfig, String> {
+    if !entry.is_object() {
+        return Err("Server entry must be an object".to_string());
+    }
+    let transport = transport_for(entry)?;
+    let args = match entry.get("args") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| item.as_str().map(str::to_string).ok_or_else(|| "\"args\" must be a list of strings".to_string()))
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("\"args\" must be a list of strings".to_string()),
+    };
+    let text = |key: &str| entry.get(key).and_then(|v| v.as_str()).unwrap_or_default().trim().to_string();
+    let connection = McpServerConfig {
+        id,
+        name: name.trim().to_string(),
+        enabled: !entry.get("disabled").and_then(|v| v.as_bool()).unwrap_or(false),
+        transport,
+        url: text("url"),
+        command: text("command"),
+        json_payload: String::new(),
+        args,
+        env_vars: string_map(entry.get("env"), "env")?,
+        headers: string_map(entry.get("headers"), "headers")?,
+        timeout_seconds: entry.get("timeout").and_then(|v| v.as_u64()).filter(|t| *t > 0).unwrap_or(10),
+        last_test_at: None,
+        last_test_status: Some("never_tested".to_string()),
+        last_test_message: None,
+        tools: Vec::new(),
+        tools_last_refresh_at: None,
+        tools_last_status: None,
+        tools_last_message: None,
+        tools_last_error: None,
+        resources: Vec::new(),
+        prompts: Vec::new(),
+        catalog_last_refresh_at: None,
+        catalog_last_error: None,
+        auth: Default::default(),
+    };
+    crate::validate_mcp_connection_payload(&connection)?;
+    Ok(connection)
+}
+
+/// Merge the servers in `raw` into `existing`. A server whose name is already taken is
+/// skipped, or with `overwrite` has its launch settings replaced (keeping its id).
+pub fn import_servers(raw: &str, existing: &mut Vec<McpServerConfig>, overwrite: bool) -> Result<Vec<ImportResult>, String> {
+    let (root, _) = structured_fixer::parse_lenient(raw, Format::Json).map_err(|e| format!("Invalid MCP config JSON: {}", e))?;
+    let entries = server_entries(&root)?;
+    let base_id = Utc::now().timestamp_micros();
+
+    let mut results = Vec::new();
+    for (index, (name, entry)) in entries.iter().enumerate() {
+        let result = |status, id: Option<String>, message: String| ImportResult { name: name.clone(), status, id, message };
+        let taken = existing.iter().position(|c| c.name.trim().eq_ignore_ascii_case(name.trim()));
+        if taken.is_some() && !overwrite {
+            results.push(result(ImportStatus::Skipped, taken.map(|i| existing[i].id.clone()), "A connection with this name already exists".to_string()));
+            continue;
+        }
+        let id = taken.map(|i| existing[i].id.clone()).unwrap_or_else(|| format!("mcp-{}", base_id + index as i64));
+        match (connection_from_entry(name, entry, id), taken) {
+            (Err(e), _) => results.push(result(ImportStatus::Error, None, e)),
+            (Ok(connection), Some(i)) => {
+                let current = &mut existing[i];
+                let id = current.id.clone();
+                *current = McpServerConfig {
+                    tools: std::mem::take(&mut current.tools),
+                    resources: std::mem::take(&mut current.resources),
+                    prompts: std::mem::take(&mut current.prompts),
+                    auth: std::mem::take(&mut current.auth),
+                    ..connection
+                };
+                results.push(result(ImportStatus::Updated, Some(id), "Replaced the existing connection's settings".to_string()));
+            }
+            (Ok(connection), None) => {
+                let id = connection.id.clone();
+                existing.push(connection);
+                results.push(result(ImportStatus::Imported, Some(id), "Imported".to_string()));
+            }
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imports_claude_desktop_config_per_entry() {
+        let raw = r#"{
+            "mcpServers": {
+                "filesystem": {
+                    "command": "npx",
+                    "args": ["-y", "@modelcontextprotocol/server-filesystem", "C:/Users/me/Documents"],
+                    "env": {"DEBUG": "1", "RETRIES": 3}
+                },
+                "remote": {"type": "streamable-http", "url": "https://mcp.example.com/mcp", "headers": {"Authorization": "Bearer x"}},
+                "broken": {"args": ["x"]},
+                "existing": {"command": "uvx", "args": ["mcp-server-git"], "disabled": true}
+            }
+        }"#;
+        let mut existing = vec![connection_from_entry("Existing", &serde_json::json!({"command": "old"}), "mcp-1".to_string()).unwrap()];
+
+        let results = import_servers(raw, &mut existing, false).unwrap();
+        let status = |name: &str| results.iter().find(|r| r.name == name).map(|r| r.status.clone());
+        assert_eq!(status("filesystem"), Some(ImportStatus::Imported));
+        assert_eq!(status("remote"), Some(ImportStatus::Imported));
+        assert_eq!(status("broken"), Some(ImportStatus::Error));
+        assert_eq!(status("existing"), Some(ImportStatus::Skipped));
+        assert_eq!(existing.len(), 3);
+
+        let fs = existing.iter().find(|c| c.name == "filesystem").unwrap();
+        assert_eq!((fs.transport.clone(), fs.args.len(), fs.env_vars["RETRIES"].as_str()), (McpTransport::Stdio, 3, "3"));
+        let remote = existing.iter().find(|c| c.name == "remote").unwrap();
+        assert_eq!(remote.transport, McpTransport::StreamableHttp);
+        assert_ne!(fs.id, remote.id);
+
+        import_servers(raw, &mut existing, true).unwrap();
+        let updated = existing.iter().find(|c| c.id == "mcp-1").unwrap();
+        assert_eq!((updated.command.as_str(), updated.enabled), ("uvx", false));
+        assert!(import_servers("[]", &mut existing, false).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use crate::models::{MemorySettings, NumaMode};
+use crate::cli_args::{self, set_flag};
+
+// Memory placement of the model weights. `--mlock`, `--no-mmap` and `--numa` come
+// from the model's structured settings; low memory mode is global and trades
+// speed for headroom on 8-16GB machines, where the default context and slot
+// count of recent llama-server builds push the system into swap.
+
+const CONTEXT_FLAGS: &[&str] = &["-c", "--ctx-size"];
+const PARALLEL_FLAGS: &[&str] = &["-np", "--parallel"];
+
+/// At or below this much RAM the settings suggest turning low memory mode on
+pub const LOW_MEMORY_RECOMMENDED_GB: f64 = 16.5;
+
+/// Launch arguments with the model's memory options merged in. `low_memory_context`
+/// is the context used in low memory mode when the arguments set none, `None` when
+/// the mode is off.
+pub fn launch_args(custom_args: &str, settings: &MemorySettings, low_memory_context: Option<u32>) -> String {
+    if low_memory_context.is_none() && *settings == MemorySettings::default() {
+        return custom_args.to_string();
+    }
+    let mut args = cli_args::split(custom_args);
+    let has = |args: &[String], flags: &[&str]| {
+        args.iter().any(|arg| flags.iter().any(|f| arg == f || arg.starts_with(&format!("{}=", f))))
+    };
+
+    if let Some(context) = low_memory_context {
+        // Pinned or fully read weights cannot be paged out; mapped ones can
+        args.retain(|arg| arg != "--mlock" && arg != "--no-mmap");
+        if !has(&args, CONTEXT_FLAGS) {
+            set_flag(&mut args, CONTEXT_FLAGS, &context.to_string());
+        }
+        // Every slot keeps its own share of the KV cache
+        set_flag(&mut args, PARALLEL_FLAGS, "1");
+    } else {
+        if settings.mlock && !args.iter().any(|arg| arg == "--mlock") {
+            args.push("--mlock".to_string());
+        }
+        if settings.no_mmap && !args.iter().any(|arg| arg == "--no-mmap") {
+            args.push("--no-mmap".to_string());
+        }
+    }
+    if let Some(numa) = settings.numa {
+        set_flag(&mut args, &["--numa"], numa.as_arg());
+    }
+    cli_args::join(&args)
+}
+
+impl NumaMode {
+    pub fn as_arg(self) -> &'static str {
+        match self {
+            NumaMode::Distribute => "distribute",
+            NumaMode::Isolate => "isolate",
+            NumaMode::Numactl => "numactl",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn low_memory_mode_overrides_memory_hungry_defaults() {
+        let pinned = MemorySettings { mlock: true, no_mmap: true, numa: Some(NumaMode::Distribute) };
+        assert_eq!(launch_args("-c 8192", &pinned, None), "-c 8192 --mlock --no-mmap --numa distribute");
+        assert_eq!(launch_args("--mlock -ngl 99", &pinned, Some(4096)), "-ngl 99 -c 4096 -np 1 --numa distribute");
+
+        // An explicit context stays; parallel slots never do
+        assert_eq!(launch_args("--ctx-size=16384 --parallel 4", &MemorySettings::default(), Some(4096)), "--ctx-size=16384 --parallel 1");
+        assert_eq!(launch_args("-c  2048", &MemorySettings::default(), None), "-c  2048");
+    }
+}
@@ -0,0 +1,235 @@
+use serde::Serialize;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+// Memory pressure watchdog for running models. Once RAM is exhausted the OS starts
+// paging model weights and KV cache in and out and the whole machine stalls; instead
+// of that going unnoticed, `memory-pressure` is emitted with what to change. Linux
+// exposes the swap-in rate directly (`/proc/vmstat`); elsewhere swap growth while
+// RAM is nearly full and the commit charge (RAM + page file in use) stand in for it.
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Sustained swap-ins per second that mean the system is thrashing (~4 MB/s of 4K pages)
+const SWAP_IN_PAGES_PER_SEC: f64 = 1000.0;
+/// Swap growth per check that counts as paging out under load
+const SWAP_GROWTH_BYTES: u64 = 256 * 1024 * 1024;
+const LOW_AVAILABLE_RATIO: f64 = 0.05;
+const HIGH_COMMIT_RATIO: f64 = 0.95;
+/// Calm checks in a row before a new episode can be reported
+const CALM_CHECKS: u32 = 6;
+const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemorySample {
+    pub total: u64,
+    pub available: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    /// Cumulative pages swapped in, where the OS reports it
+    pub swap_in_pages: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryPressure {
+    pub thrashing: bool,
+    pub available_gb: f64,
+    pub total_gb: f64,
+    pub swap_used_gb: f64,
+    /// RAM plus page file in use over what both can hold
+    pub commit_ratio: f64,
+    pub swap_in_per_sec: Option<f64>,
+    pub reasons: Vec<String>,
+}
+
+struct Episode {
+    active: bool,
+    calm_checks: u32,
+}
+
+static LAST: LazyLock<Mutex<Option<(Instant, MemorySample)>>> = LazyLock::new(|| Mutex::new(None));
+static LATEST: LazyLock<Mutex<Option<MemoryPressure>>> = LazyLock::new(|| Mutex::new(None));
+static EPISODE: LazyLock<Mutex<Episode>> = LazyLock::new(|| Mutex::new(Episode { active: false, calm_checks: 0 }));
+
+fn sample() -> MemorySample {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_memory();
+    MemorySample {
+        total: sys.total_memory(),
+        available: sys.available_memory(),
+        swap_total: sys.total_swap(),
+        swap_used: sys.used_swap(),
+        swap_in_pages: swap_in_pages(),
+    }
+}
+
+fn swap_in_pages() -> Option<u64> {
+    let vmstat = std::fs::read_to_string("/proc/vmstat").ok()?;
+    vmstat
+        .lines()
+        .find_map(|line| line.strip_prefix("pswpin "))
+        .and_then(|count| count.trim().parse().ok())
+}
+
+/// Compare two samples taken `elapsed` apart
+pub fn assess(previous: &MemorySample, current: &MemorySample, elapsed: Duration) -> MemoryPressure {
+    let secs = elapsed.as_secs_f64().max(0.001);
+    let swap_in_per_sec = match (previous.swap_in_pages, current.swap_in_pages) {
+        (Some(before), Some(now)) => Some(now.saturating_sub(before) as f64 / secs),
+        _ => None,
+    };
+    let committed = current.total.saturating_sub(current.available) + current.swap_used;
+    let capacity = current.total + current.swap_total;
+    let commit_ratio = if capacity > 0 { committed as f64 / capacity as f64 } else { 0.0 };
+    let ram_nearly_full = current.total > 0 && (current.available as f64) < current.total as f64 * LOW_AVAILABLE_RATIO;
+
+    let mut reasons = Vec::new();
+    if let Some(rate) = swap_in_per_sec.filter(|rate| *rate >= SWAP_IN_PAGES_PER_SEC) {
+        reasons.push(format!("{:.0} pages/s read back from swap", rate));
+    }
+    if ram_nearly_full && current.swap_used >= previous.swap_used + SWAP_GROWTH_BYTES {
+        reasons.push(format!(
+            "RAM nearly full and swap grew by {:.1} GB",
+            (current.swap_used - previous.swap_used) as f64 / GB
+        ));
+    }
+    if commit_ratio >= HIGH_COMMIT_RATIO && current.swap_total > 0 {
+        reasons.push(format!("{:.0}% of RAM and page file committed", commit_ratio * 100.0));
+    }
+
+    MemoryPressure {
+        thrashing: !reasons.is_empty(),
+        available_gb: current.available as f64 / GB,
+        total_gb: current.total as f64 / GB,
+        swap_used_gb: current.swap_used as f64 / GB,
+        commit_ratio,
+        swap_in_per_sec,
+        reasons,
+    }
+}
+
+/// What to change on the running models, most effective first
+async fn suggestions(state: &AppState, running: &[(String, String)]) -> Vec<serde_json::Value> {
+    let (low_memory_mode, low_memory_context) = {
+        let config = state.config.lock().await;
+        (config.low_memory_mode, config.low_memory_context)
+    };
+    let model_configs = state.model_configs.lock().await;
+    let mut out = Vec::new();
+    if running.len() > 1 {
+        out.push(serde_json::json!({ "action": "stop_models", "message": format!("Stop some of the {} running models", running.len()) }));
+    }
+    for (model_path, model_name) in running {
+        let config = model_configs.get(model_path);
+        let args = config.map(|c| crate::cli_args::split(&c.custom_args)).unwrap_or_default();
+        let context = crate::cli_args::flag_value(&args, &["-c", "--ctx-size"]).and_then(|c| c.parse::<u32>().ok());
+        if context.is_none_or(|c| c == 0 || c > low_memory_context) {
+            out.push(serde_json::json!({
+                "action": "reduce_context",
+                "model_path": model_path,
+                "message": format!("Lower the context of {} to {} or less", model_name, low_memory_context),
+            }));
+        }
+        if !config.is_some_and(|c| c.memory.no_mmap) && !low_memory_mode {
+            out.push(serde_json::json!({
+                "action": "enable_no_mmap",
+                "model_path": model_path,
+                "message": format!("Enable no-mmap for {} so its weights are not read back from disk on every page fault", model_name),
+            }));
+        }
+        out.push(serde_json::json!({
+            "action": "smaller_quant",
+            "model_path": model_path,
+            "message": format!("Use a smaller quantization of {}", model_name),
+        }));
+    }
+    if !low_memory_mode {
+        out.push(serde_json::json!({ "action": "low_memory_mode", "message": "Turn on low memory mode" }));
+    }
+    out
+}
+
+/// Latest assessment, `None` before the first two checks
+pub fn latest() -> Option<MemoryPressure> {
+    LATEST.lock().unwrap_or_else(|p| p.into_inner()).clone()
+}
+
+async fn check_once(state: &AppState, app_handle: &tauri::AppHandle) {
+    let running: Vec<(String, String)> = state
+        .running_processes
+        .lock()
+        .await
+        .values()
+        .filter(|p| !p.model_path.starts_with("tunnel:"))
+        .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running))
+        .map(|p| (p.model_path.clone(), p.model_name.clone()))
+        .collect();
+
+    let now = Instant::now();
+    let current = sample();
+    let previous = LAST.lock().unwrap_or_else(|p| p.into_inner()).replace((now, current));
+    let Some((taken_at, previous)) = previous else { return };
+    let pressure = assess(&previous, &current, now - taken_at);
+    *LATEST.lock().unwrap_or_else(|p| p.into_inner()) = Some(pressure.clone());
+
+    // Only pressure while our models run is ours to report
+    let report = {
+        let mut episode = EPISODE.lock().unwrap_or_else(|p| p.into_inner());
+        if pressure.thrashing && !running.is_empty() {
+            episode.calm_checks = 0;
+            !std::mem::replace(&mut episode.active, true)
+        } else {
+            episode.calm_checks += 1;
+            if episode.calm_checks >= CALM_CHECKS {
+                episode.active = false;
+            }
+            false
+        }
+    };
+    if !report {
+        return;
+    }
+
+    eprintln!("[Memory] System is thrashing: {}", pressure.reasons.join("; "));
+    let suggestions = suggestions(state, &running).await;
+    let _ = app_handle.emit("memory-pressure", serde_json::json!({
+        "pressure": pressure,
+        "running_models": running.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+        "suggestions": suggestions,
+    }));
+}
+
+pub fn spawn(state: AppState, app_handle: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check_once(&state, &app_handle).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn thrashing_needs_swap_traffic_not_just_full_ram() {
+        let calm = MemorySample { total: 16_000 * MB, available: 4_000 * MB, swap_total: 8_000 * MB, swap_used: 500 * MB, swap_in_pages: Some(10_000) };
+        let full = MemorySample { available: 300 * MB, swap_in_pages: Some(10_050), ..calm };
+        assert!(!assess(&calm, &full, Duration::from_secs(5)).thrashing);
+
+        let paging = MemorySample { swap_in_pages: Some(30_000), ..full };
+        let pressure = assess(&full, &paging, Duration::from_secs(5));
+        assert_eq!(pressure.swap_in_per_sec, Some(3990.0));
+        assert!(pressure.thrashing);
+
+        // No swap-in counter (Windows, macOS): swap growth with RAM full
+        let before = MemorySample { swap_in_pages: None, ..full };
+        let after = MemorySample { swap_used: 1_000 * MB, ..before };
+        assert_eq!(assess(&before, &after, Duration::from_secs(5)).reasons.len(), 1);
+    }
+}
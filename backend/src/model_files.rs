@@ -0,0 +1,615 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tauri::Emitter;
+use crate::models::{BackendBenchmarkRecord, GlobalConfig, ModelConfig};
+use crate::scanner::split_shard;
+use crate::AppState;
+
+// Moving model files on disk while keeping everything that points at them valid:
+// `ModelConfig` keys (and with them HF links), `--mmproj`/`--lora` paths in custom
+// args and presets, fallback/route settings and the chats index. Used for moves
+// between directories and for renames in place; external files are imported here too.
+
+const COPY_CHUNK: usize = 8 * 1024 * 1024;
+const PROGRESS_EVENT: &str = "model-move-progress";
+
+/// Old and new absolute path of one moved file
+pub type PathChange = (String, String);
+
+/// Shards of a split model, or mmproj / LoRA files next to it
+fn is_sibling(model_file_name: &str, candidate: &str) -> bool {
+    let model = model_file_name.to_lowercase();
+    let candidate = candidate.to_lowercase();
+    if candidate == model || !candidate.ends_with(".gguf") {
+        return false;
+    }
+    if let (Some((a, _)), Some((b, _))) = (split_shard(&model), split_shard(&candidate)) {
+        if a == b {
+            return true;
+        }
+    }
+    candidate.starts_with("mmproj") || candidate.contains("lora")
+}
+
+/// The model plus its sibling files, model first
+pub fn model_with_siblings(model_path: &Path) -> Vec<PathBuf> {
+    let mut files = vec![model_path.to_path_buf()];
+    let (Some(dir), Some(name)) = (model_path.parent(), model_path.file_name().and_then(|n| n.to_str())) else {
+        return files;
+    };
+    let mut siblings: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| path.file_name().and_then(|n| n.to_str()).is_some_and(|n| is_sibling(name, n)))
+        .collect();
+    siblings.sort();
+    files.extend(siblings);
+    files
+}
+
+/// Other models left in `dir` that may still use shared mmproj/LoRA files
+fn has_other_models(dir: &Path, moving: &[PathBuf]) -> bool {
+    fs::read_dir(dir).into_iter().flatten().flatten().any(|entry| {
+        let path = entry.path();
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_lowercase();
+        name.ends_with(".gguf") && !name.starts_with("mmproj") && !name.contains("lora") && !moving.contains(&path)
+    })
+}
+
+/// Rename, or copy and delete when the target is on another disk
+fn move_file(
+    source: &Path,
+    target: &Path,
+    keep_source: bool,
+    on_progress: &dyn Fn(u64, u64),
+) -> Result<(), String> {
+    if !keep_source && fs::rename(source, target).is_ok() {
+        let size = fs::metadata(target).map(|m| m.len()).unwrap_or(0);
+        on_progress(size, size);
+        return Ok(());
+    }
+
+    let total = fs::metadata(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?.len();
+    let partial = target.with_extension("gguf.part");
+    let copy = || -> Result<(), String> {
+        let mut reader = fs::File::open(source).map_err(|e| format!("Failed to open {}: {}", source.display(), e))?;
+        let mut writer = fs::File::create(&partial).map_err(|e| format!("Failed to create {}: {}", partial.display(), e))?;
+        let mut buffer = vec![0u8; COPY_CHUNK];
+        let mut copied = 0u64;
+        let mut last_percent = None;
+        loop {
+            let read = reader.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read]).map_err(|e| format!("Failed to write {}: {}", partial.display(), e))?;
+            copied += read as u64;
+            let percent = copied * 100 / total.max(1);
+            if last_percent != Some(percent) {
+                last_percent = Some(percent);
+                on_progress(copied, total);
+            }
+        }
+        writer.sync_all().map_err(|e| format!("Failed to flush {}: {}", partial.display(), e))?;
+        fs::rename(&partial, target).map_err(|e| format!("Failed to finish {}: {}", target.display(), e))
+    };
+    if let Err(e) = copy() {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    if !keep_source {
+        fs::remove_file(source).map_err(|e| format!("Failed to remove {}: {}", source.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Put already moved files back after a later step failed
+fn undo_moves(moved: &[(PathBuf, PathBuf, bool)]) {
+    for (source, target, kept) in moved.iter().rev() {
+        let result = if *kept { fs::remove_file(target) } else { fs::rename(target, source) };
+        if let Err(e) = result {
+            eprintln!("[Models] Failed to roll back {}: {}", target.display(), e);
+        }
+    }
+}
+
+fn replace_paths(text: &str, changes: &[PathChange]) -> String {
+    changes.iter().fold(text.to_string(), |text, (old, new)| text.replace(old.as_str(), new))
+}
+
+/// Replace string values (not keys) equal to a changed path anywhere in `value`
+fn replace_in_json(value: &mut serde_json::Value, changes: &[PathChange]) -> bool {
+    match value {
+        serde_json::Value::String(s) => match changes.iter().find(|(old, _)| old == s) {
+            Some((_, new)) => {
+                *s = new.clone();
+                true
+            }
+            None => false,
+        },
+        serde_json::Value::Array(items) => items.iter_mut().fold(false, |changed, v| replace_in_json(v, changes) | changed),
+        serde_json::Value::Object(map) => map.values_mut().fold(false, |changed, v| replace_in_json(v, changes) | changed),
+        _ => false,
+    }
+}
+
+fn rewrite_args(config: &mut ModelConfig, changes: &[PathChange]) {
+    config.custom_args = replace_paths(&config.custom_args, changes);
+    for preset in &mut config.presets {
+        preset.custom_args = replace_paths(&preset.custom_args, changes);
+    }
+}
+
+/// `copies` are files duplicated rather than moved; only the first changed model
+/// switches to them, the rest keep using the originals
+fn rekey_configs(
+    configs: &mut std::collections::HashMap<String, ModelConfig>,
+    changes: &[PathChange],
+    copies: &[PathChange],
+) {
+    for config in configs.values_mut() {
+        rewrite_args(config, changes);
+    }
+    if let Some(config) = changes.first().and_then(|(old, _)| configs.get_mut(old)) {
+        rewrite_args(config, copies);
+    }
+    for (old, new) in changes {
+        if let Some(mut config) = configs.remove(old) {
+            config.model_path = new.clone();
+            configs.insert(new.clone(), config);
+        }
+    }
+}
+
+/// Point model settings, proxy settings and chats at the new paths, saving once.
+/// The model itself comes first in `changes`; when a step fails the settings are put
+/// back as they were, in memory and on disk.
+pub async fn update_references(state: &AppState, changes: &[PathChange], copies: &[PathChange]) -> Result<(), String> {
+    // Read before anything changes so an unreadable index fails the move early
+    let mut index = crate::read_chats_index()?;
+    let index_changed = index.iter_mut().fold(false, |changed, entry| replace_in_json(entry, changes) | changed);
+
+    let (previous_config, previous_configs) = {
+        let mut config = state.config.lock().await;
+        let mut configs = state.model_configs.lock().await;
+        let previous = (config.clone(), configs.clone());
+
+        rekey_configs(&mut configs, changes, copies);
+        for entry in &mut config.fallback_chain {
+            *entry = replace_paths(entry, changes);
+        }
+        config.route_policies = std::mem::take(&mut config.route_policies)
+            .into_iter()
+            .map(|(key, policy)| (replace_paths(&key, changes), policy))
+            .collect();
+        for record in config.backend_benchmarks.values_mut() {
+            record.model_path = replace_paths(&record.model_path, changes);
+        }
+        previous
+    };
+
+    if let Err(e) = crate::config::save_settings(state).await.map_err(|e| e.to_string()) {
+        restore_settings(state, previous_config, previous_configs, false).await;
+        return Err(format!("Failed to save settings: {}", e));
+    }
+    let benchmarks: Vec<BackendBenchmarkRecord> = state.config.lock().await.backend_benchmarks.values().cloned().collect();
+    let mut result = crate::storage::run(move |db| benchmarks.iter().try_for_each(|record| db.benchmarks().save(record))).await;
+    if result.is_ok() && index_changed {
+        result = crate::write_chats_index(&index);
+    }
+    if let Err(e) = result {
+        restore_settings(state, previous_config, previous_configs, true).await;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Put back the settings `update_references` changed, on disk too when they were `saved`
+async fn restore_settings(
+    state: &AppState,
+    config: GlobalConfig,
+    configs: std::collections::HashMap<String, ModelConfig>,
+    saved: bool,
+) {
+    *state.config.lock().await = config;
+    *state.model_configs.lock().await = configs;
+    if saved {
+        if let Err(e) = crate::config::save_settings(state).await.map_err(|e| e.to_string()) {
+            eprintln!("[Models] Failed to restore settings: {}", e);
+        }
+    }
+}
+
+/// `path` with `.` and `..` resolved without touching the disk, so a path that climbs
+/// out of a directory is not taken to be inside it
+fn normalized(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match result.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    result.pop();
+                }
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => result.push(Component::ParentDir),
+            },
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+/// Whether `path` is `dir` or inside it once both are normalized
+fn within(path: &Path, dir: &Path) -> bool {
+    normalized(path).starts_with(normalized(dir))
+}
+
+async fn allowed_directories(state: &AppState) -> Vec<PathBuf> {
+    let config = state.config.lock().await;
+    std::iter::once(&config.models_directory)
+        .chain(config.additional_models_directories.iter())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// The read-only models directory holding `path`, if any
+pub fn read_only_directory<'a>(read_only: &'a [String], path: &Path) -> Option<&'a str> {
+    read_only
+        .iter()
+        .map(|dir| dir.trim())
+        .find(|dir| !dir.is_empty() && within(path, Path::new(dir)))
+}
+
+/// Err when `path` is in a read-only models directory; `action` says what was refused
+pub async fn ensure_writable(state: &AppState, path: &Path, action: &str) -> Result<(), String> {
+    let read_only = state.config.lock().await.read_only_models_directories.clone();
+    match read_only_directory(&read_only, path) {
+        Some(dir) => Err(format!("Cannot {} {}: {} is a read-only models directory", action, path.display(), dir)),
+        None => Ok(()),
+    }
+}
+
+/// Refuse to touch a model a running server has open
+pub async fn ensure_not_running(state: &AppState, model_path: &str) -> Result<(), String> {
+    let running = state.running_processes.lock().await;
+    if running.values().any(|p| p.model_path == model_path) {
+        return Err("Stop the model before moving or renaming its files".to_string());
+    }
+    Ok(())
+}
+
+/// Move a model and its siblings into `target_directory`, reporting copy progress with
+/// `model-move-progress`. Shared mmproj/LoRA files are copied when other models stay behind.
+pub async fn move_model(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    model_path: &str,
+    target_directory: &str,
+) -> Result<serde_json::Value, String> {
+    let source = PathBuf::from(model_path);
+    if !source.is_file() {
+        return Err("Model file does not exist".to_string());
+    }
+    let target_dir = normalized(Path::new(target_directory));
+    let allowed = allowed_directories(state).await;
+    if !allowed.iter().any(|dir| within(&source, dir)) {
+        return Err("Cannot move files outside of models directories".to_string());
+    }
+    if !allowed.iter().any(|dir| within(&target_dir, dir)) {
+        return Err("Target must be a configured models directory".to_string());
+    }
+    if source.parent() == Some(target_dir.as_path()) {
+        return Err("Model is already in that directory".to_string());
+    }
+    ensure_writable(state, &source, "move").await?;
+    ensure_writable(state, &target_dir, "move models into").await?;
+    ensure_not_running(state, model_path).await?;
+
+    let files = model_with_siblings(&source);
+    let plan: Vec<(PathBuf, PathBuf)> = files
+        .iter()
+        .filter_map(|file| Some((file.clone(), target_dir.join(file.file_name()?))))
+        .collect();
+    if let Some((_, existing)) = plan.iter().find(|(_, target)| target.exists()) {
+        return Err(format!("{} already exists in the target directory", existing.display()));
+    }
+
+    let source_dir = source.parent().map(Path::to_path_buf).unwrap_or_default();
+    let shared_stays = has_other_models(&source_dir, &files);
+    let model_name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let handle = app_handle.clone();
+    let event_model = model_path.to_string();
+
+    let moved = tokio::task::spawn_blocking(move || -> Result<Vec<(PathBuf, PathBuf, bool)>, String> {
+        fs::create_dir_all(&target_dir).map_err(|e| format!("Failed to create target directory: {}", e))?;
+        let mut moved = Vec::new();
+        for (index, (from, to)) in plan.into_iter().enumerate() {
+            let file_name = from.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let is_shared = index > 0 && shared_stays && !is_split_shard(&model_name, &file_name);
+            let progress = |copied: u64, total: u64| {
+                let _ = handle.emit(PROGRESS_EVENT, serde_json::json!({
+                    "model_path": event_model,
+                    "file": file_name,
+                    "copied_bytes": copied,
+                    "total_bytes": total,
+                    "progress": (copied * 100).checked_div(total).map_or(100, |percent| percent as u8),
+                }));
+            };
+            if let Err(e) = move_file(&from, &to, is_shared, &progress) {
+                undo_moves(&moved);
+                return Err(e);
+            }
+            moved.push((from, to, is_shared));
+        }
+        Ok(moved)
+    })
+    .await
+    .map_err(|e| format!("Failed to move model: {}", e))??;
+
+    let as_change = |(from, to, _): &(PathBuf, PathBuf, bool)| (from.to_string_lossy().to_string(), to.to_string_lossy().to_string());
+    let changes: Vec<PathChange> = moved.iter().filter(|(_, _, kept)| !kept).map(as_change).collect();
+    let copies: Vec<PathChange> = moved.iter().filter(|(_, _, kept)| *kept).map(as_change).collect();
+    if let Err(e) = update_references(state, &changes, &copies).await {
+        undo_moves(&moved);
+        return Err(e);
+    }
+
+    let new_path = changes[0].1.clone();
+    println!("[Models] Moved {} to {} ({} file(s), {} copied)", model_path, new_path, changes.len(), copies.len());
+    let _ = app_handle.emit(PROGRESS_EVENT, serde_json::json!({ "model_path": model_path, "new_path": new_path, "done": true }));
+    Ok(serde_json::json!({
+        "success": true,
+        "new_path": new_path,
+        "moved_files": changes.iter().map(|(_, to)| to).collect::<Vec<_>>(),
+        "copied_files": copies.iter().map(|(_, to)| to).collect::<Vec<_>>(),
+    }))
+}
+
+fn is_split_shard(model_file_name: &str, candidate: &str) -> bool {
+    let lower = candidate.to_lowercase();
+    is_sibling(model_file_name, candidate) && !lower.starts_with("mmproj") && !lower.contains("lora")
+}
+
+/// New file names for `old_names` (one file, or every shard of a split model) given the
+/// name the user typed; shards keep their `-00001-of-00003` suffixes
+fn renamed_files(old_names: &[String], new_name: &str) -> Result<Vec<String>, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name.contains(['/', '\\']) || new_name.starts_with('.') {
+        return Err("Enter a file name without folders".to_string());
+    }
+    let stem = split_shard(new_name)
+        .map(|(base, _)| base.to_string())
+        .unwrap_or_else(|| new_name.strip_suffix(".gguf").or_else(|| new_name.strip_suffix(".GGUF")).unwrap_or(new_name).to_string());
+    Ok(old_names
+        .iter()
+        .map(|old| match split_shard(old) {
+            Some((_, suffix)) => format!("{}{}.gguf", stem, suffix),
+            None => format!("{}.gguf", stem),
+        })
+        .collect())
+}
+
+/// Rename a model file in place, migrating its settings and every reference to it.
+/// For split models all shards are renamed together.
+pub async fn rename_model_file(state: &AppState, old_path: &str, new_name: &str) -> Result<serde_json::Value, String> {
+    let source = PathBuf::from(old_path);
+    if !source.is_file() {
+        return Err("Model file does not exist".to_string());
+    }
+    if !allowed_directories(state).await.iter().any(|dir| within(&source, dir)) {
+        return Err("Cannot rename files outside of models directories".to_string());
+    }
+    ensure_writable(state, &source, "rename").await?;
+    ensure_not_running(state, old_path).await?;
+
+    let model_name = source.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let files: Vec<PathBuf> = model_with_siblings(&source)
+        .into_iter()
+        .filter(|file| file == &source || file.file_name().is_some_and(|n| is_split_shard(&model_name, &n.to_string_lossy())))
+        .collect();
+    let old_names: Vec<String> = files.iter().map(|f| f.file_name().unwrap_or_default().to_string_lossy().to_string()).collect();
+    let new_names = renamed_files(&old_names, new_name)?;
+    let plan: Vec<(PathBuf, PathBuf)> = files.iter().cloned().zip(new_names.iter().map(|n| source.with_file_name(n))).collect();
+    if plan.iter().all(|(from, to)| from == to) {
+        return Err("The new name is the same as the current one".to_string());
+    }
+    if let Some((_, existing)) = plan.iter().find(|(from, to)| from != to && to.exists()) {
+        return Err(format!("{} already exists", existing.display()));
+    }
+
+    let mut renamed: Vec<(PathBuf, PathBuf, bool)> = Vec::new();
+    for (from, to) in plan {
+        if let Err(e) = fs::rename(&from, &to) {
+            undo_moves(&renamed);
+            return Err(format!("Failed to rename {}: {}", from.display(), e));
+        }
+        renamed.push((from, to, false));
+    }
+
+    let changes: Vec<PathChange> = renamed
+        .iter()
+        .map(|(from, to, _)| (from.to_string_lossy().to_string(), to.to_string_lossy().to_string()))
+        .collect();
+    if let Err(e) = update_references(state, &changes, &[]).await {
+        undo_moves(&renamed);
+        return Err(e);
+    }
+
+    let new_path = changes[0].1.clone();
+    println!("[Models] Renamed {} to {}", old_path, new_path);
+    Ok(serde_json::json!({
+        "success": true,
+        "new_path": new_path,
+        "renamed_files": changes.iter().map(|(_, to)| to).collect::<Vec<_>>(),
+    }))
+}
+
+/// Link `target` to `source`: a symlink, or a hard link on Windows without symlink rights
+fn link_file(source: &Path, target: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    let linked = std::os::unix::fs::symlink(source, target);
+    #[cfg(windows)]
+    let linked = std::os::windows::fs::symlink_file(source, target).or_else(|_| fs::hard_link(source, target));
+    linked.map_err(|e| format!("Failed to link {}: {}", source.display(), e))
+}
+
+/// Bring a GGUF from outside the models directories into the library by copying it
+/// (`mode` "copy") or linking to it ("link"), together with its shards and mmproj/LoRA files
+pub async fn register_external_model(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    path: &str,
+    mode: &str,
+    target_directory: Option<String>,
+    hf_model_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let source = PathBuf::from(path);
+    if !source.is_file() || !path.to_lowercase().ends_with(".gguf") {
+        return Err("Only existing .gguf files can be imported".to_string());
+    }
+    let link = match mode {
+        "copy" => false,
+        "link" => true,
+        other => return Err(format!("Unknown import mode '{}', use copy or link", other)),
+    };
+    let metadata = crate::scanner::extract_gguf_metadata(&source).map_err(|e| format!("Not a valid GGUF file: {}", e))?;
+
+    let allowed = allowed_directories(state).await;
+    if allowed.iter().any(|dir| within(&source, dir)) {
+        return Err("This model is already in a models directory".to_string());
+    }
+    let target_dir = match target_directory {
+        Some(dir) => normalized(Path::new(&dir)),
+        None => allowed.first().cloned().ok_or_else(|| "Set a models directory first".to_string())?,
+    };
+    if !allowed.iter().any(|dir| within(&target_dir, dir)) {
+        return Err("Target must be a configured models directory".to_string());
+    }
+    ensure_writable(state, &target_dir, "import models into").await?;
+
+    let plan: Vec<(PathBuf, PathBuf)> = model_with_siblings(&source)
+        .into_iter()
+        .filter_map(|file| {
+            let target = target_dir.join(file.file_name()?);
+            Some((file, target))
+        })
+        .collect();
+    if let Some((_, existing)) = plan.iter().find(|(_, target)| target.exists()) {
+        return Err(format!("{} already exists in the target directory", existing.display()));
+    }
+
+    let handle = app_handle.clone();
+    let event_model = path.to_string();
+    let imported = tokio::task::spawn_blocking(move || -> Result<Vec<PathBuf>, String> {
+        fs::create_dir_all(&target_dir).map_err(|e| format!("Failed to create target directory: {}", e))?;
+        let mut done: Vec<(PathBuf, PathBuf, bool)> = Vec::new();
+        for (from, to) in plan {
+            let file_name = from.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+            let progress = |copied: u64, total: u64| {
+                let _ = handle.emit(PROGRESS_EVENT, serde_json::json!({
+                    "model_path": event_model,
+                    "file": file_name,
+                    "copied_bytes": copied,
+                    "total_bytes": total,
+                    "progress": (copied * 100).checked_div(total).map_or(100, |percent| percent as u8),
+                }));
+            };
+            let result = if link { link_file(&from, &to) } else { move_file(&from, &to, true, &progress) };
+            if let Err(e) = result {
+                undo_moves(&done);
+                return Err(e);
+            }
+            // Keep the source on rollback: only the new entry is removed
+            done.push((from, to, true));
+        }
+        Ok(done.into_iter().map(|(_, to, _)| to).collect())
+    })
+    .await
+    .map_err(|e| format!("Failed to import model: {}", e))??;
+
+    let model_path = imported[0].to_string_lossy().to_string();
+    {
+        let mut configs = state.model_configs.lock().await;
+        let config = configs.entry(model_path.clone()).or_insert_with(|| ModelConfig::new(model_path.clone()));
+        config.file_size_bytes = fs::metadata(&source).ok().map(|m| m.len() as i64);
+        if let Some(hf_id) = hf_model_id.map(|id| id.trim().to_string()).filter(|id| id.contains('/')) {
+            config.hf_model_id = Some(hf_id);
+            config.hf_link_source = Some("manual".to_string());
+        }
+    }
+    crate::config::save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    println!("[Models] Imported {} as {} ({})", path, model_path, mode);
+    Ok(serde_json::json!({
+        "success": true,
+        "model_path": model_path,
+        "files": imported.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>(),
+        "linked": link,
+        "metadata": metadata,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn siblings_and_references_follow_the_move() {
+        assert!(is_sibling("qwen-00001-of-00003.gguf", "qwen-00002-of-00003.gguf"));
+        assert!(is_sibling("qwen-vl-q4.gguf", "mmproj-qwen-vl-f16.gguf"));
+        assert!(is_sibling("llama.gguf", "style-LoRA.gguf"));
+        assert!(!is_sibling("llama.gguf", "mistral.gguf"));
+        assert!(!is_sibling("llama.gguf", "mmproj-notes.txt"));
+        assert!(is_split_shard("qwen-00001-of-00003.gguf", "qwen-00003-of-00003.gguf"));
+
+        let changes = vec![
+            ("/a/qwen.gguf".to_string(), "/b/qwen.gguf".to_string()),
+            ("/a/mmproj-qwen.gguf".to_string(), "/b/mmproj-qwen.gguf".to_string()),
+        ];
+        let mut configs = std::collections::HashMap::new();
+        let mut config = ModelConfig::new("/a/qwen.gguf".to_string());
+        config.custom_args = "--mmproj /a/mmproj-qwen.gguf -c 8192".to_string();
+        configs.insert("/a/qwen.gguf".to_string(), config);
+        rekey_configs(&mut configs, &changes, &[]);
+        let moved = &configs["/b/qwen.gguf"];
+        assert_eq!(moved.model_path, "/b/qwen.gguf");
+        assert_eq!(moved.custom_args, "--mmproj /b/mmproj-qwen.gguf -c 8192");
+
+        let mut chat = json!({"chat_id": "c1", "last_model": "qwen.gguf", "translation_settings": {"model_path": "/a/qwen.gguf"}});
+        assert!(replace_in_json(&mut chat, &changes));
+        assert_eq!(chat["translation_settings"]["model_path"], "/b/qwen.gguf");
+        assert_eq!(chat["last_model"], "qwen.gguf");
+    }
+
+    #[test]
+    fn renames_keep_shard_suffixes() {
+        assert_eq!(renamed_files(&["old.gguf".to_string()], "new-name").unwrap(), vec!["new-name.gguf"]);
+        assert_eq!(renamed_files(&["old.gguf".to_string()], "new.GGUF").unwrap(), vec!["new.gguf"]);
+        let shards = vec!["big-00001-of-00002.gguf".to_string(), "big-00002-of-00002.gguf".to_string()];
+        assert_eq!(
+            renamed_files(&shards, "huge-00001-of-00002.gguf").unwrap(),
+            vec!["huge-00001-of-00002.gguf", "huge-00002-of-00002.gguf"]
+        );
+        assert!(renamed_files(&shards, "../escape.gguf").is_err());
+        assert!(renamed_files(&shards, "  ").is_err());
+    }
+
+    #[test]
+    fn files_under_a_read_only_directory_are_found() {
+        let read_only = vec!["/mnt/nas/models/ ".to_string(), String::new()];
+        assert_eq!(read_only_directory(&read_only, Path::new("/mnt/nas/models/qwen/qwen.gguf")), Some("/mnt/nas/models/"));
+        assert_eq!(read_only_directory(&read_only, Path::new("/mnt/nas/models-local/qwen.gguf")), None);
+        assert_eq!(read_only_directory(&read_only, Path::new("/home/me/models/qwen.gguf")), None);
+        // Climbing out with `..` does not escape the check, nor does it count as inside
+        assert_eq!(read_only_directory(&read_only, Path::new("/home/me/../../mnt/nas/models/q.gguf")), Some("/mnt/nas/models/"));
+        assert!(!within(Path::new("/models/../elsewhere"), Path::new("/models")));
+        assert!(within(Path::new("/models/./qwen/../llama"), Path::new("/models")));
+    }
+}
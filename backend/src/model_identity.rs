@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::Emitter;
+use crate::models::ModelConfig;
+use crate::AppState;
+
+// Content identity for model files. A file's SHA-256 is computed once and cached by
+// path, size and mtime (`model_hashes.json`); `ModelConfig.content_sha256` then lets
+// settings, HF links and chat references follow the file after it is renamed or moved
+// outside the app, and lets a copy in another directory start from its twin's settings.
+
+const READ_CHUNK: usize = 8 * 1024 * 1024;
+const PROGRESS_EVENT: &str = "model-hash-progress";
+
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct CachedHash {
+    size: u64,
+    modified: i64,
+    sha256: String,
+}
+
+fn cache_path() -> Result<PathBuf, String> {
+    Ok(crate::arandu_base_dir()?.join("model_hashes.json"))
+}
+
+fn read_cache() -> HashMap<String, CachedHash> {
+    cache_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, i64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    Some((metadata.len(), modified))
+}
+
+/// Hash from the cache while the file's size and mtime are unchanged
+pub fn cached_sha256(path: &str) -> Option<String> {
+    let (size, modified) = file_stamp(Path::new(path))?;
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    read_cache()
+        .remove(path)
+        .filter(|entry| entry.size == size && entry.modified == modified)
+        .map(|entry| entry.sha256)
+}
+
+fn store_cached(path: &str, size: u64, modified: i64, sha256: &str) {
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+    let mut cache = read_cache();
+    cache.retain(|cached, _| Path::new(cached).exists());
+    cache.insert(path.to_string(), CachedHash { size, modified, sha256: sha256.to_string() });
+    let result = cache_path().and_then(|target| {
+        let content = serde_json::to_string(&cache).map_err(|e| format!("Failed to serialize model hashes: {}", e))?;
+        fs::write(target, content).map_err(|e| format!("Failed to write model hashes: {}", e))
+    });
+    if let Err(e) = result {
+        eprintln!("[Models] {}", e);
+    }
+}
+
+fn sha256_file(path: &Path, on_progress: &dyn Fn(u64, u64)) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let total = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; READ_CHUNK];
+    let mut hashed = 0u64;
+    loop {
+        let read = file.read(&mut buffer).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        hashed += read as u64;
+        on_progress(hashed, total);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Cached hash, or hash the whole file now (blocking; multi-GB files take a while)
+pub fn sha256_of(path: &str, on_progress: &dyn Fn(u64, u64)) -> Result<String, String> {
+    if let Some(hash) = cached_sha256(path) {
+        return Ok(hash);
+    }
+    let (size, modified) = file_stamp(Path::new(path)).ok_or_else(|| format!("{} does not exist", path))?;
+    let hash = sha256_file(Path::new(path), on_progress)?;
+    store_cached(path, size, modified, &hash);
+    Ok(hash)
+}
+
+/// What to do with an unconfigured file whose content matches a known model
+#[derive(Debug, Clone, PartialEq)]
+enum Attach {
+    /// The configured file is gone: move its settings to `to`
+    Relink { from: String, to: String },
+    /// The configured file is still there: `to` is a duplicate and starts from a copy
+    Inherit { from: String, to: String },
+}
+
+/// Pair unconfigured `files` with known identities. Only same-size files are hashed.
+fn match_identities(
+    configs: &HashMap<String, ModelConfig>,
+    files: &[(String, u64)],
+    hash_of: &dyn Fn(&str) -> Option<String>,
+) -> Vec<Attach> {
+    let mut attach = Vec::new();
+    let mut relinked = std::collections::HashSet::new();
+    for (path, size) in files.iter().filter(|(path, _)| !configs.contains_key(path)) {
+        let same_size: Vec<&ModelConfig> = configs
+            .values()
+            .filter(|c| c.content_sha256.is_some() && c.file_size_bytes == Some(*size as i64))
+            .collect();
+        if same_size.is_empty() {
+            continue;
+        }
+        let Some(hash) = hash_of(path) else { continue };
+        let mut twins: Vec<&&ModelConfig> = same_size.iter().filter(|c| c.content_sha256.as_deref() == Some(hash.as_str())).collect();
+        // Prefer a missing original so its settings move instead of being duplicated
+        twins.sort_by_key(|c| Path::new(&c.model_path).exists());
+        match twins.first() {
+            Some(twin) if !Path::new(&twin.model_path).exists() && relinked.insert(twin.model_path.clone()) => {
+                attach.push(Attach::Relink { from: twin.model_path.clone(), to: path.clone() });
+            }
+            Some(twin) if Path::new(&twin.model_path).exists() => {
+                attach.push(Attach::Inherit { from: twin.model_path.clone(), to: path.clone() });
+            }
+            _ => {}
+        }
+    }
+    attach
+}
+
+fn library_files(directories: &[String]) -> Vec<(String, u64)> {
+    directories
+        .iter()
+        .filter(|dir| !dir.is_empty() && Path::new(dir).is_dir())
+        .filter_map(|dir| glob::glob(&format!("{}/**/*.gguf", dir)).ok())
+        .flatten()
+        .flatten()
+        .filter_map(|path| Some((path.to_string_lossy().to_string(), fs::metadata(&path).ok()?.len())))
+        .collect()
+}
+
+/// Reattach settings to files that were renamed or moved outside the app, and give new
+/// duplicates their twin's settings. Runs after every library scan.
+pub async fn reattach(state: &AppState) -> Result<usize, String> {
+    let directories = {
+        let config = state.config.lock().await;
+        let mut dirs = vec![config.models_directory.clone()];
+        dirs.extend(config.additional_models_directories.clone());
+        dirs
+    };
+    let configs = state.model_configs.lock().await.clone();
+    if !configs.values().any(|c| c.content_sha256.is_some()) {
+        return Ok(0);
+    }
+    let attach = tokio::task::spawn_blocking(move || {
+        let files = library_files(&directories);
+        match_identities(&configs, &files, &|path| sha256_of(path, &|_, _| {}).ok())
+    })
+    .await
+    .map_err(|e| format!("Failed to match model identities: {}", e))?;
+
+    let relinks: Vec<(String, String)> = attach
+        .iter()
+        .filter_map(|a| match a {
+            Attach::Relink { from, to } => Some((from.clone(), to.clone())),
+            Attach::Inherit { .. } => None,
+        })
+        .collect();
+    if !relinks.is_empty() {
+        crate::model_files::update_references(state, &relinks, &[]).await?;
+    }
+
+    let mut inherited = 0;
+    {
+        let mut model_configs = state.model_configs.lock().await;
+        for a in &attach {
+            if let Attach::Inherit { from, to } = a {
+                let Some(mut copy) = model_configs.get(from).cloned() else { continue };
+                copy.model_path = to.clone();
+                model_configs.entry(to.clone()).or_insert(copy);
+                inherited += 1;
+            }
+        }
+    }
+    if inherited > 0 {
+        crate::config::save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    }
+    if !attach.is_empty() {
+        println!("[Models] Identity matched {} moved and {} duplicate model file(s)", relinks.len(), inherited);
+    }
+    Ok(attach.len())
+}
+
+/// Hash the given models (all configured ones when None) and store the result on their
+/// settings, emitting `model-hash-progress` while large files are read
+pub async fn compute_hashes(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    model_paths: Option<Vec<String>>,
+) -> Result<HashMap<String, String>, String> {
+    let paths: Vec<String> = match model_paths {
+        Some(paths) => paths,
+        None => state.model_configs.lock().await.keys().cloned().collect(),
+    };
+    let handle = app_handle.clone();
+    let hashes = tokio::task::spawn_blocking(move || {
+        let count = paths.len();
+        let mut hashes = HashMap::new();
+        for (index, path) in paths.into_iter().enumerate() {
+            let last_percent = std::cell::Cell::new(u64::MAX);
+            let progress = |hashed: u64, total: u64| {
+                // One event per percent keeps the frontend responsive on 100 GB files
+                let percent = hashed * 100 / total.max(1);
+                if last_percent.replace(percent) != percent {
+                    let _ = handle.emit(PROGRESS_EVENT, serde_json::json!({
+                        "model_path": path,
+                        "hashed_bytes": hashed,
+                        "total_bytes": total,
+                        "index": index,
+                        "count": count,
+                    }));
+                }
+            };
+            match sha256_of(&path, &progress) {
+                Ok(hash) => {
+                    hashes.insert(path, hash);
+                }
+                Err(e) => eprintln!("[Models] {}", e),
+            }
+        }
+        hashes
+    })
+    .await
+    .map_err(|e| format!("Failed to hash models: {}", e))?;
+
+    {
+        let mut model_configs = state.model_configs.lock().await;
+        for (path, hash) in &hashes {
+            if let Some(config) = model_configs.get_mut(path) {
+                config.content_sha256 = Some(hash.clone());
+                config.file_size_bytes = file_stamp(Path::new(path)).map(|(size, _)| size as i64);
+            }
+        }
+    }
+    crate::config::save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_files_and_matches_moved_models() {
+        let file = std::env::temp_dir().join(format!("arandu-identity-{}.gguf", uuid::Uuid::new_v4()));
+        fs::write(&file, b"abc").unwrap();
+        let hash = sha256_file(&file, &|_, _| {}).unwrap();
+        assert_eq!(hash, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        let _ = fs::remove_file(&file);
+
+        let mut configs = HashMap::new();
+        for (path, sha) in [("/gone/qwen.gguf", "aaa"), (file.to_str().unwrap(), "bbb")] {
+            let mut config = ModelConfig::new(path.to_string());
+            config.content_sha256 = Some(sha.to_string());
+            config.file_size_bytes = Some(3);
+            configs.insert(path.to_string(), config);
+        }
+        let files = vec![("/new/renamed.gguf".to_string(), 3), ("/new/other.gguf".to_string(), 4)];
+        let matched = match_identities(&configs, &files, &|path| (path == "/new/renamed.gguf").then(|| "aaa".to_string()));
+        assert_eq!(matched, vec![Attach::Relink { from: "/gone/qwen.gguf".to_string(), to: "/new/renamed.gguf".to_string() }]);
+    }
+}
@@ -0,0 +1,281 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use crate::models::{DownloadStartResult, ModelConfig, ModelPreset};
+use crate::AppState;
+
+// "Arandu pack" manifests: a shareable JSON list of Hugging Face files plus the presets to
+// create for them and system prompts to add. Installing starts one download per repo
+// and prepares the settings so each model is ready to launch when its files land.
+//
+// {
+//   "format": "arandu-pack", "version": 1, "name": "Coding starter", "author": "someone",
+//   "models": [{ "repo": "Qwen/Qwen2.5-Coder-7B-Instruct-GGUF",
+//                "files": ["qwen2.5-coder-7b-instruct-q4_k_m.gguf"],
+//                "custom_args": "-c 16384",
+//                "presets": [{ "name": "Long context", "custom_args": "-c 32768", "is_default": true }] }],
+//   "prompts": [{ "name": "Reviewer", "prompt": "You review code..." }]
+// }
+
+pub const PACK_FORMAT: &str = "arandu-pack";
+const SUPPORTED_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelPack {
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub models: Vec<PackModel>,
+    #[serde(default)]
+    pub prompts: Vec<PackPrompt>,
+}
+
+fn default_version() -> u32 {
+    SUPPORTED_VERSION
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackModel {
+    /// Hugging Face `author/model`
+    pub repo: String,
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub custom_args: Option<String>,
+    #[serde(default)]
+    pub presets: Vec<PackPreset>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackPreset {
+    pub name: String,
+    #[serde(default)]
+    pub custom_args: String,
+    #[serde(default)]
+    pub is_default: bool,
+    #[serde(default)]
+    pub env_vars: HashMap<String, String>,
+}
+
+/// Added to the system prompt library in the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackPrompt {
+    pub name: String,
+    pub prompt: String,
+}
+
+fn safe_relative(file: &str) -> bool {
+    let path = Path::new(file);
+    !file.trim().is_empty()
+        && !path.is_absolute()
+        && !file.starts_with('/')
+        && !file.starts_with('\\')
+        && path.components().all(|c| matches!(c, std::path::Component::Normal(_)))
+}
+
+/// Parse and validate manifest JSON, or read it from a `.json` file path
+pub fn load_manifest(manifest: &str) -> Result<ModelPack, String> {
+    let raw = if manifest.trim_start().starts_with('{') {
+        manifest.to_string()
+    } else {
+        std::fs::read_to_string(manifest.trim()).map_err(|e| format!("Failed to read pack manifest: {}", e))?
+    };
+    let (value, _) = crate::structured_fixer::parse_lenient(&raw, crate::structured_fixer::Format::Json)
+        .map_err(|e| format!("Failed to parse pack manifest: {}", e))?;
+    let pack: ModelPack = serde_json::from_value(value).map_err(|e| format!("Failed to parse pack manifest: {}", e))?;
+
+    if pack.format.as_deref().is_some_and(|f| f != PACK_FORMAT) {
+        return Err(format!("Not an Arandu pack (format '{}')", pack.format.unwrap_or_default()));
+    }
+    if pack.version > SUPPORTED_VERSION {
+        return Err(format!("Pack version {} needs a newer Arandu", pack.version));
+    }
+    if pack.name.trim().is_empty() {
+        return Err("Pack needs a name".to_string());
+    }
+    if pack.models.is_empty() && pack.prompts.is_empty() {
+        return Err("Pack has no models or prompts".to_string());
+    }
+    for model in &pack.models {
+        let parts: Vec<&str> = model.repo.split('/').collect();
+        if parts.len() != 2 || parts.iter().any(|p| !safe_relative(p)) {
+            return Err(format!("Invalid Hugging Face repo '{}'", model.repo));
+        }
+        if model.files.is_empty() {
+            return Err(format!("No files listed for {}", model.repo));
+        }
+        if let Some(file) = model.files.iter().find(|f| !safe_relative(f) || !f.to_lowercase().ends_with(".gguf")) {
+            return Err(format!("Invalid file '{}' in {}: only relative .gguf paths are allowed", file, model.repo));
+        }
+    }
+    Ok(pack)
+}
+
+/// What installing would do, shown for the single confirmation. Launch arguments and
+/// environment variables come from whoever wrote the pack, so each is listed verbatim and
+/// flagged for the user to read before confirming.
+pub fn summary(pack: &ModelPack) -> serde_json::Value {
+    let sets_arguments = pack.models.iter().any(|m| {
+        m.custom_args.as_ref().is_some_and(|a| !a.trim().is_empty()) || m.presets.iter().any(|p| !p.custom_args.trim().is_empty())
+    });
+    let sets_environment = pack.models.iter().any(|m| m.presets.iter().any(|p| !p.env_vars.is_empty()));
+    let models: Vec<serde_json::Value> = pack
+        .models
+        .iter()
+        .map(|m| {
+            let presets: Vec<serde_json::Value> = m
+                .presets
+                .iter()
+                .map(|p| {
+                    let mut env_vars: Vec<String> = p.env_vars.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+                    env_vars.sort();
+                    serde_json::json!({ "name": p.name, "custom_args": p.custom_args, "env_vars": env_vars, "is_default": p.is_default })
+                })
+                .collect();
+            serde_json::json!({ "repo": m.repo, "files": m.files, "custom_args": m.custom_args, "presets": presets })
+        })
+        .collect();
+    serde_json::json!({
+        "name": pack.name,
+        "description": pack.description,
+        "author": pack.author,
+        "models": models,
+        "file_count": pack.models.iter().map(|m| m.files.len()).sum::<usize>(),
+        "prompts": pack.prompts.iter().map(|p| p.name.clone()).collect::<Vec<_>>(),
+        "sets_arguments": sets_arguments,
+        "sets_environment": sets_environment,
+    })
+}
+
+fn config_for(model: &PackModel, model_path: String, existing: Option<ModelConfig>) -> ModelConfig {
+    let mut config = existing.unwrap_or_else(|| ModelConfig::new(model_path));
+    if let Some(args) = model.custom_args.as_ref().filter(|_| config.custom_args.trim().is_empty()) {
+        config.custom_args = args.clone();
+    }
+    for preset in &model.presets {
+        if config.presets.iter().any(|p| p.name.eq_ignore_ascii_case(&preset.name)) {
+            continue;
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        // A default the user already picked wins over the pack's
+        let is_default = preset.is_default && config.default_preset_id.is_none() && config.presets.iter().all(|p| !p.is_default);
+        if is_default {
+            config.default_preset_id = Some(id.clone());
+        }
+        config.presets.push(ModelPreset {
+            id,
+            name: preset.name.clone(),
+            custom_args: preset.custom_args.clone(),
+            is_default,
+            env_vars: preset.env_vars.clone(),
+        });
+    }
+    config.hf_model_id.get_or_insert_with(|| model.repo.clone());
+    config.hf_link_source.get_or_insert_with(|| "download".to_string());
+    config
+}
+
+/// `install` behind the single confirmation: unless `confirmed`, only the summary comes
+/// back for the user to read. Every way of installing a pack goes through here.
+pub async fn install_if_confirmed(state: &AppState, app_handle: tauri::AppHandle, pack: &ModelPack, confirmed: bool) -> Result<serde_json::Value, String> {
+    if !confirmed {
+        return Ok(serde_json::json!({ "confirm_required": true, "summary": summary(pack) }));
+    }
+    install(state, app_handle, pack).await
+}
+
+/// Prepare settings for every file and start the downloads. Prompts are returned for
+/// the frontend, which owns the system prompt library.
+async fn install(state: &AppState, app_handle: tauri::AppHandle, pack: &ModelPack) -> Result<serde_json::Value, String> {
+    use crate::downloader::{start_download, DownloadConfig};
+
+    let models_directory = state.config.lock().await.models_directory.clone();
+    if models_directory.trim().is_empty() {
+        return Err("Set a models directory before installing packs".to_string());
+    }
+
+    {
+        let mut configs = state.model_configs.lock().await;
+        for model in &pack.models {
+            let destination = Path::new(&models_directory).join(&model.repo);
+            // Presets go on the first file; split shards and mmproj files share them
+            if let Some(first) = model.files.first() {
+                let model_path = destination.join(first).to_string_lossy().to_string();
+                let config = config_for(model, model_path.clone(), configs.get(&model_path).cloned());
+                configs.insert(model_path, config);
+            }
+        }
+    }
+    crate::config::save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+
+    let mut downloads: Vec<DownloadStartResult> = Vec::new();
+    let mut errors = Vec::new();
+    for model in &pack.models {
+        let config = DownloadConfig {
+            base_url: format!("https://huggingface.co/{}/resolve/main", model.repo),
+            destination_folder: Path::new(&models_directory).join(&model.repo).to_string_lossy().to_string(),
+            auto_extract: false,
+            create_subfolder: None,
+            files: model.files.clone(),
+            custom_headers: Some(HashMap::from([("User-Agent".to_string(), "Arandu-Tauri/1.0".to_string())])),
+            expected_sha256: None,
+        };
+        match start_download(config, state, app_handle.clone()).await {
+            Ok(result) => downloads.push(result),
+            Err(e) => errors.push(format!("{}: {}", model.repo, e)),
+        }
+    }
+    println!("[Pack] Installing '{}': {} download(s), {} error(s)", pack.name, downloads.len(), errors.len());
+
+    Ok(serde_json::json!({
+        "success": errors.is_empty(),
+        "name": pack.name,
+        "downloads": downloads.iter().map(|d| &d.download_id).collect::<Vec<_>>(),
+        "errors": errors,
+        "prompts": pack.prompts,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifests_are_validated_and_presets_merged() {
+        let raw = r#"{
+            "format": "arandu-pack", "name": "Coding",
+            "models": [{"repo": "Qwen/Coder-GGUF", "files": ["coder-q4_k_m.gguf"], "custom_args": "-c 16384",
+                        "presets": [{"name": "Long", "custom_args": "-c 32768", "is_default": true}]}],
+            "prompts": [{"name": "Reviewer", "prompt": "Review this code."}]
+        }"#;
+        let pack = load_manifest(raw).unwrap();
+        assert_eq!(summary(&pack)["file_count"], 1);
+
+        let config = config_for(&pack.models[0], "/m/Qwen/Coder-GGUF/coder-q4_k_m.gguf".to_string(), None);
+        assert_eq!(config.custom_args, "-c 16384");
+        assert_eq!(config.presets.len(), 1);
+        assert_eq!(config.default_preset_id.as_deref(), Some(config.presets[0].id.as_str()));
+        let again = config_for(&pack.models[0], String::new(), Some(config.clone()));
+        assert_eq!(again.presets.len(), 1);
+
+        assert!(load_manifest(&raw.replace("coder-q4_k_m.gguf", "../../evil.gguf")).is_err());
+        assert!(load_manifest(&raw.replace("coder-q4_k_m.gguf", "notes.txt")).is_err());
+        assert!(load_manifest(&raw.replace("Qwen/Coder-GGUF", "Qwen")).is_err());
+        assert!(load_manifest(&raw.replace("arandu-pack", "other")).is_err());
+
+        // Everything a pack would run with is shown as written
+        let summary_of = summary(&pack);
+        assert_eq!(summary_of["models"][0]["presets"][0]["custom_args"], "-c 32768");
+        assert_eq!((summary_of["sets_arguments"].clone(), summary_of["sets_environment"].clone()), (true.into(), false.into()));
+        let with_env = load_manifest(&raw.replace(r#""is_default": true"#, r#""is_default": true, "env_vars": {"LD_PRELOAD": "/tmp/x.so"}"#)).unwrap();
+        let flagged = summary(&with_env);
+        assert_eq!(flagged["sets_environment"], true);
+        assert_eq!(flagged["models"][0]["presets"][0]["env_vars"][0], "LD_PRELOAD=/tmp/x.so");
+    }
+}
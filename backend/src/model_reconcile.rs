@@ -0,0 +1,235 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use crate::models::ModelConfig;
+use crate::AppState;
+
+// Settings left behind by model files that were deleted, renamed or moved outside the
+// app. Present files get their size, mtime and a quick fingerprint recorded so a later
+// reconcile can find them again; missing ones are reported with relink suggestions.
+
+/// Bytes hashed from each end of a file for its fingerprint
+const FINGERPRINT_CHUNK: u64 = 1024 * 1024;
+const MAX_CANDIDATES: usize = 5;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RelinkCandidate {
+    pub path: String,
+    /// `fingerprint`, `size_and_name`, `size` or `name`, best first
+    pub match_kind: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedConfig {
+    pub model_path: String,
+    pub file_name: String,
+    pub last_seen_at: Option<i64>,
+    pub last_known_size_bytes: Option<i64>,
+    pub last_modified: Option<i64>,
+    pub hf_model_id: Option<String>,
+    pub preset_count: usize,
+    pub has_custom_args: bool,
+    pub relink_candidates: Vec<RelinkCandidate>,
+}
+
+/// md5 over the size and the first and last megabyte; cheap enough for multi-GB files
+pub fn fingerprint(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let size = file.metadata().ok()?.len();
+    let mut data = size.to_le_bytes().to_vec();
+    let mut head = Vec::new();
+    file.by_ref().take(FINGERPRINT_CHUNK).read_to_end(&mut head).ok()?;
+    data.extend(head);
+    if size > FINGERPRINT_CHUNK * 2 {
+        file.seek(SeekFrom::End(-(FINGERPRINT_CHUNK as i64))).ok()?;
+        let mut tail = Vec::new();
+        file.take(FINGERPRINT_CHUNK).read_to_end(&mut tail).ok()?;
+        data.extend(tail);
+    }
+    Some(format!("{:x}", md5::compute(&data)))
+}
+
+fn file_name_of(path: &str) -> String {
+    path.replace('\\', "/").rsplit('/').next().unwrap_or_default().to_lowercase()
+}
+
+/// Unconfigured files that could be the missing model, best match first
+fn rank_candidates(
+    orphan: &ModelConfig,
+    files: &[(String, u64)],
+    fingerprint_of: impl Fn(&str) -> Option<String>,
+) -> Vec<RelinkCandidate> {
+    let name = file_name_of(&orphan.model_path);
+    let size = orphan.file_size_bytes.map(|s| s as u64);
+    let mut ranked: Vec<(u8, RelinkCandidate)> = files
+        .iter()
+        .filter_map(|(path, file_size)| {
+            let same_size = size == Some(*file_size);
+            let same_name = file_name_of(path) == name;
+            let rank = if same_size && orphan.file_fingerprint.is_some() && fingerprint_of(path) == orphan.file_fingerprint {
+                (0, "fingerprint")
+            } else if same_size && same_name {
+                (1, "size_and_name")
+            } else if same_size {
+                (2, "size")
+            } else if same_name {
+                (3, "name")
+            } else {
+                return None;
+            };
+            Some((rank.0, RelinkCandidate { path: path.clone(), match_kind: rank.1.to_string() }))
+        })
+        .collect();
+    ranked.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.path.cmp(&b.1.path)));
+    ranked.into_iter().take(MAX_CANDIDATES).map(|(_, c)| c).collect()
+}
+
+fn gguf_files(directories: &[String]) -> Vec<(String, u64)> {
+    directories
+        .iter()
+        .filter(|dir| !dir.is_empty() && Path::new(dir).is_dir())
+        .filter_map(|dir| glob::glob(&format!("{}/**/*.gguf", dir)).ok())
+        .flatten()
+        .flatten()
+        .filter_map(|path| Some((path.to_string_lossy().to_string(), fs::metadata(&path).ok()?.len())))
+        .collect()
+}
+
+/// Record size, mtime and fingerprint of a present file; true when anything changed
+fn refresh_last_seen(config: &mut ModelConfig, now: i64) -> bool {
+    let Ok(metadata) = fs::metadata(&config.model_path) else { return false };
+    let size = metadata.len() as i64;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+    let stale = config.file_size_bytes != Some(size) || config.local_file_modified != modified || config.file_fingerprint.is_none();
+    if stale {
+        config.file_size_bytes = Some(size);
+        config.local_file_modified = modified;
+        config.file_fingerprint = fingerprint(Path::new(&config.model_path));
+    }
+    let day_old = config.last_seen_at.is_none_or(|seen| now - seen > 24 * 60 * 60);
+    if day_old {
+        config.last_seen_at = Some(now);
+    }
+    stale || day_old
+}
+
+/// Apply relinks (old path -> new file) and remove confirmed orphans, then report what is
+/// still missing. With neither it only refreshes last-seen info and reports.
+pub async fn reconcile(
+    state: &AppState,
+    relink: HashMap<String, String>,
+    remove: Vec<String>,
+) -> Result<serde_json::Value, String> {
+    let directories = {
+        let config = state.config.lock().await;
+        let mut dirs = vec![config.models_directory.clone()];
+        dirs.extend(config.additional_models_directories.clone());
+        dirs
+    };
+
+    // Relinks only move settings from a missing file to an existing, unconfigured one
+    let mut relinked = Vec::new();
+    {
+        let configs = state.model_configs.lock().await;
+        for (old, new) in relink {
+            if !configs.contains_key(&old) || Path::new(&old).exists() {
+                return Err(format!("{} is not a missing model", old));
+            }
+            if !Path::new(&new).is_file() || configs.contains_key(&new) {
+                return Err(format!("{} must be an existing model without settings", new));
+            }
+            relinked.push((old, new));
+        }
+    }
+    if !relinked.is_empty() {
+        crate::model_files::update_references(state, &relinked, &[]).await?;
+        println!("[Models] Relinked {} model config(s)", relinked.len());
+    }
+
+    let (orphans, removed, changed) = {
+        let mut configs = state.model_configs.lock().await;
+        let removed: Vec<String> = remove
+            .into_iter()
+            .filter(|path| !Path::new(path).exists() && configs.remove(path).is_some())
+            .collect();
+
+        let now = chrono::Utc::now().timestamp();
+        let mut changed = !removed.is_empty();
+        for config in configs.values_mut() {
+            changed |= refresh_last_seen(config, now);
+        }
+
+        let configured: HashSet<&String> = configs.keys().collect();
+        let free_files: Vec<(String, u64)> = gguf_files(&directories)
+            .into_iter()
+            .filter(|(path, _)| !configured.contains(path))
+            .collect();
+        let mut orphans: Vec<OrphanedConfig> = configs
+            .values()
+            .filter(|c| !c.model_path.is_empty() && !Path::new(&c.model_path).exists())
+            .map(|c| OrphanedConfig {
+                model_path: c.model_path.clone(),
+                file_name: file_name_of(&c.model_path),
+                last_seen_at: c.last_seen_at,
+                last_known_size_bytes: c.file_size_bytes,
+                last_modified: c.local_file_modified,
+                hf_model_id: c.hf_metadata.as_ref().map(|m| m.model_id.clone()).or_else(|| c.hf_model_id.clone()),
+                preset_count: c.presets.len(),
+                has_custom_args: !c.custom_args.trim().is_empty(),
+                relink_candidates: rank_candidates(c, &free_files, |path| fingerprint(Path::new(path))),
+            })
+            .collect();
+        orphans.sort_by(|a, b| a.model_path.cmp(&b.model_path));
+        (orphans, removed, changed)
+    };
+
+    if changed {
+        crate::config::save_settings(state).await.map_err(|e| format!("Failed to save settings: {}", e))?;
+    }
+    if !removed.is_empty() {
+        println!("[Models] Removed {} orphaned model config(s)", removed.len());
+    }
+
+    Ok(serde_json::json!({
+        "orphans": orphans,
+        "relinked": relinked.iter().map(|(old, new)| serde_json::json!({ "from": old, "to": new })).collect::<Vec<_>>(),
+        "removed": removed,
+        "checked": state.model_configs.lock().await.len(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidates_rank_fingerprint_then_size_and_name() {
+        let mut orphan = ModelConfig::new("/old/qwen-q4.gguf".to_string());
+        orphan.file_size_bytes = Some(4000);
+        orphan.file_fingerprint = Some("abc".to_string());
+        let files = vec![
+            ("/new/other-name.gguf".to_string(), 4000),
+            ("/new/qwen-q4.gguf".to_string(), 4000),
+            ("/new/renamed.gguf".to_string(), 4000),
+            ("/b/qwen-q4.gguf".to_string(), 10),
+            ("/b/unrelated.gguf".to_string(), 99),
+        ];
+        let candidates = rank_candidates(&orphan, &files, |path| (path == "/new/renamed.gguf").then(|| "abc".to_string()));
+        let kinds: Vec<(&str, &str)> = candidates.iter().map(|c| (c.path.as_str(), c.match_kind.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("/new/renamed.gguf", "fingerprint"),
+                ("/new/qwen-q4.gguf", "size_and_name"),
+                ("/new/other-name.gguf", "size"),
+                ("/b/qwen-q4.gguf", "name"),
+            ]
+        );
+    }
+}
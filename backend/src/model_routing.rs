@@ -0,0 +1,239 @@
+use crate::models::{KeyLaunchProfile, ProcessInfo, ProcessStatus};
+use crate::AppState;
+use std::sync::LazyLock;
+
+// Several models can run side by side, each on its own llama-server. The proxy sends a
+// request to the server whose model the request's `model` field names — the file name,
+// the name without `.gguf`, or the full path, case-insensitively — and to its default
+// upstream when nothing running matches, so clients that send a placeholder such as
+// `gpt-4` keep working. With several instances of one model the lowest one answers.
+// An API key bound to a launch profile skips the lookup: its requests go to that model,
+// which is launched first when it is not running. With `proxy_load_models_on_request`
+// a request naming an installed model that is not running launches it the same way,
+// with its default preset; idle unload stops it again later.
+
+/// Held while checking for and launching a profile's model, so concurrent requests
+/// with one key start a single server
+static LAUNCHING: LazyLock<tokio::sync::Mutex<()>> = LazyLock::new(|| tokio::sync::Mutex::new(()));
+
+#[derive(Debug, Clone)]
+pub struct RoutedServer {
+    pub model_path: String,
+    pub model_name: String,
+    pub base_url: String,
+}
+
+fn normalize(value: &str) -> String {
+    value.trim().replace('\\', "/").to_lowercase()
+}
+
+/// Whether `requested` names the model `process` serves
+fn names(process: &ProcessInfo, requested: &str) -> bool {
+    let name = normalize(&process.model_name);
+    requested == name
+        || name.strip_suffix(".gguf").is_some_and(|stem| requested == stem)
+        || requested == normalize(&process.model_path)
+}
+
+fn routable(process: &ProcessInfo) -> bool {
+    matches!(process.status, ProcessStatus::Running) && !process.model_path.starts_with("tunnel:")
+}
+
+fn routed(process: &ProcessInfo) -> RoutedServer {
+    RoutedServer {
+        model_path: process.model_path.clone(),
+        model_name: process.model_name.clone(),
+        base_url: crate::suspend::local_base_url(&process.host, process.port),
+    }
+}
+
+/// The running process `requested` names, if any
+fn find<'a>(processes: impl IntoIterator<Item = &'a ProcessInfo>, requested: &str) -> Option<&'a ProcessInfo> {
+    let requested = normalize(requested);
+    if requested.is_empty() {
+        return None;
+    }
+    processes
+        .into_iter()
+        .filter(|p| routable(p) && names(p, &requested))
+        .min_by_key(|p| p.instance)
+}
+
+/// Server for a request naming `requested_model`, or `None` for the default upstream
+pub async fn resolve(state: &AppState, requested_model: &str) -> Option<RoutedServer> {
+    let running = state.running_processes.lock().await;
+    find(running.values(), requested_model).map(routed)
+}
+
+/// Whether `process` serves `profile`: same model and, when the profile names one, same preset
+fn serves(process: &ProcessInfo, profile: &KeyLaunchProfile) -> bool {
+    matches!(process.status, ProcessStatus::Running | ProcessStatus::Starting)
+        && process.model_path == profile.model_path
+        && (profile.preset_id.is_none() || process.preset_id == profile.preset_id)
+}
+
+/// Whether `path` is a model file a request can name: not a projector and not a later
+/// part of a split model
+fn launchable(path: &std::path::Path) -> bool {
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let later_part = file_name
+        .rsplit_once("-of-")
+        .and_then(|(head, _)| head.rsplit_once('-'))
+        .is_some_and(|(_, part)| part.len() == 5 && part.bytes().all(|b| b.is_ascii_digit()) && part != "00001");
+    !file_name.starts_with("mmproj") && !later_part
+}
+
+/// Installed model among `paths` (the scanner's model list) that `requested` names, by
+/// the same rules as running ones
+fn installed<'a>(paths: impl IntoIterator<Item = &'a str>, requested: &str) -> Option<String> {
+    let requested = normalize(requested);
+    if requested.is_empty() {
+        return None;
+    }
+    paths
+        .into_iter()
+        .map(std::path::Path::new)
+        .filter(|path| launchable(path))
+        .find(|path| {
+            let name = normalize(&path.file_name().unwrap_or_default().to_string_lossy());
+            requested == name
+                || name.strip_suffix(".gguf").is_some_and(|stem| requested == stem)
+                || requested == normalize(&path.to_string_lossy())
+        })
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+/// Launch the installed model `requested_model` names. `None` when the setting is off or
+/// no installed model matches.
+pub async fn load_on_request(
+    state: &AppState,
+    directories: &[String],
+    requested_model: &str,
+) -> Option<Result<RoutedServer, String>> {
+    if !state.config.lock().await.proxy_load_models_on_request {
+        return None;
+    }
+    // The last scan answers; only directories never scanned are walked, once
+    let models = match crate::scanner::cached_models(directories) {
+        Some(models) => models,
+        None => crate::scanner::scan_models(directories).await.ok()?,
+    };
+    let model_path = installed(models.iter().map(|m| m.path.as_str()), requested_model)?;
+    Some(ensure_running(state, &KeyLaunchProfile { model_path, preset_id: None }).await)
+}
+
+/// Server for `profile`, launching its model when no instance is running or starting
+pub async fn ensure_running(state: &AppState, profile: &KeyLaunchProfile) -> Result<RoutedServer, String> {
+    let server = {
+        let _launching = LAUNCHING.lock().await;
+        let existing = {
+            let running = state.running_processes.lock().await;
+            running
+                .values()
+                .filter(|p| serves(p, profile))
+                .min_by_key(|p| (!matches!(p.status, ProcessStatus::Running), p.instance))
+                .map(routed)
+        };
+        match existing {
+            Some(server) => server,
+            None => {
+                println!("[Proxy] Launching {} on request", profile.model_path);
+                let launched = crate::launch_with_preset(profile.model_path.clone(), profile.preset_id.clone(), state).await?;
+                RoutedServer {
+                    model_path: profile.model_path.clone(),
+                    model_name: launched.model_name,
+                    base_url: crate::suspend::local_base_url(&launched.server_host, launched.server_port),
+                }
+            }
+        }
+    };
+    crate::suspend::wait_until_healthy(&server.base_url).await?;
+    Ok(server)
+}
+
+/// Running server for `target`: a process id, a model path, or a model name as a
+/// request would give it
+pub async fn running_server(state: &AppState, target: &str) -> Result<RoutedServer, String> {
+    let running = state.running_processes.lock().await;
+    running
+        .get(target)
+        .filter(|p| routable(p))
+        .or_else(|| find(running.values(), target))
+        .map(routed)
+        .ok_or_else(|| format!("{} is not running; launch it first", target))
+}
+
+/// One entry per running model, for `/v1/models`
+pub async fn running_models(state: &AppState) -> Vec<RoutedServer> {
+    let running = state.running_processes.lock().await;
+    let mut processes: Vec<&ProcessInfo> = running.values().filter(|p| routable(p)).collect();
+    processes.sort_by(|a, b| a.model_name.cmp(&b.model_name).then(a.instance.cmp(&b.instance)));
+    processes.dedup_by(|a, b| a.model_path == b.model_path);
+    processes.into_iter().map(routed).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(id: &str, model_path: &str, instance: u32, status: ProcessStatus) -> ProcessInfo {
+        ProcessInfo {
+            id: id.to_string(),
+            model_path: model_path.to_string(),
+            model_name: model_path.rsplit(['/', '\\']).next().unwrap().to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            command: Vec::new(),
+            status,
+            output: Vec::new(),
+            created_at: chrono::Utc::now(),
+            last_sent_line: None,
+            preset_id: None,
+            instance,
+            restart_count: 0,
+        }
+    }
+
+    #[test]
+    fn requests_go_to_the_running_model_they_name() {
+        let processes = vec![
+            process("coder-2", "/models/Qwen-Coder-7B.Q4_K_M.gguf", 2, ProcessStatus::Running),
+            process("coder-1", "/models/Qwen-Coder-7B.Q4_K_M.gguf", 1, ProcessStatus::Running),
+            process("chat", r"C:\models\Llama-3-8B.gguf", 1, ProcessStatus::Running),
+            process("loading", "/models/Mistral-7B.gguf", 1, ProcessStatus::Starting),
+        ];
+        let id = |requested: &str| find(&processes, requested).map(|p| p.id.as_str());
+
+        assert_eq!(id("Qwen-Coder-7B.Q4_K_M.gguf"), Some("coder-1"));
+        assert_eq!(id("qwen-coder-7b.q4_k_m"), Some("coder-1"));
+        assert_eq!(id("c:/models/llama-3-8b.gguf"), Some("chat"));
+        assert_eq!(id(" Llama-3-8B "), Some("chat"));
+        // Not ready yet, unknown, or no model named: the default upstream answers
+        assert_eq!(id("Mistral-7B.gguf"), None);
+        assert_eq!(id("gpt-4"), None);
+        assert_eq!(id(""), None);
+
+        let mut chat = processes[2].clone();
+        chat.preset_id = Some("16k".to_string());
+        let profile = |preset: Option<&str>| KeyLaunchProfile {
+            model_path: r"C:\models\Llama-3-8B.gguf".to_string(),
+            preset_id: preset.map(str::to_string),
+        };
+        assert!(serves(&chat, &profile(None)) && serves(&chat, &profile(Some("16k"))));
+        assert!(!serves(&chat, &profile(Some("32k"))));
+        assert!(serves(&processes[3], &KeyLaunchProfile { model_path: "/models/Mistral-7B.gguf".to_string(), preset_id: None }));
+    }
+
+    #[test]
+    fn installed_models_are_found_by_name() {
+        let paths = ["Qwen-7B.Q4_K_M.gguf", "mmproj-Qwen-7B.gguf", "Big-00001-of-00002.gguf", "Big-00002-of-00002.gguf"]
+            .map(|name| format!("/models/Qwen/{}", name));
+        let found = |requested: &str| installed(paths.iter().map(String::as_str), requested);
+
+        assert!(found("qwen-7b.q4_k_m").unwrap().ends_with("Qwen/Qwen-7B.Q4_K_M.gguf"));
+        assert!(found("Big-00001-of-00002").is_some());
+        assert_eq!(found("Big-00002-of-00002.gguf"), None);
+        assert_eq!(found("mmproj-Qwen-7B"), None);
+        assert_eq!(found("gpt-4"), None);
+    }
+}
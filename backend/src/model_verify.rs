@@ -0,0 +1,137 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncReadExt;
+use crate::download_source::HuggingFaceSource;
+use crate::task_manager::Task;
+use crate::AppState;
+
+// Checks a model file already on disk against the SHA-256 Hugging Face publishes for it.
+// Downloads verify while streaming; this covers files that were copied in, downloaded
+// before that, or may have been damaged since. The repo is taken from where Arandu puts
+// downloads, `<models directory>/<author>/<model>/...`, and its LFS hashes are fetched
+// from the main revision. Without a repo or a published hash only the hash is reported.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileVerification {
+    pub path: String,
+    pub bytes: u64,
+    pub sha256: String,
+    /// `author/model` the file was matched to
+    pub repo: Option<String>,
+    pub expected_sha256: Option<String>,
+    /// `None` when there was no published hash to compare with
+    pub matches: Option<bool>,
+}
+
+/// `author/model` and the path inside the repo, from the file's place under one of `directories`
+fn repo_of(model_path: &Path, directories: &[String]) -> Option<(String, String)> {
+    let relative = directories
+        .iter()
+        .filter(|dir| !dir.is_empty())
+        .find_map(|dir| model_path.strip_prefix(dir).ok())?;
+    let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    match parts.as_slice() {
+        [author, model, rest @ ..] if !rest.is_empty() => Some((format!("{}/{}", author, model), rest.join("/"))),
+        _ => None,
+    }
+}
+
+/// The published hash for `path_in_repo`; downloads keep only the file name, so a
+/// unique match on the name also counts
+fn expected_for(hashes: &HashMap<String, String>, path_in_repo: &str) -> Option<String> {
+    if let Some(hash) = hashes.get(path_in_repo) {
+        return Some(hash.clone());
+    }
+    let name = Path::new(path_in_repo).file_name()?;
+    let mut found = hashes.iter().filter(|(path, _)| Path::new(path.as_str()).file_name() == Some(name));
+    match (found.next(), found.next()) {
+        (Some((_, hash)), None) => Some(hash.clone()),
+        _ => None,
+    }
+}
+
+async fn hash_file(path: &Path, task: &Task) -> Result<(String, u64), String> {
+    let mut file = tokio::fs::File::open(crate::long_path::extended(path))
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let total = file.metadata().await.map(|m| m.len()).unwrap_or(0);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    let mut read_total = 0u64;
+    loop {
+        task.check()?;
+        let read = file.read(&mut buffer).await.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        read_total += read as u64;
+        if let Some(percent) = (read_total * 100).checked_div(total) {
+            task.report(Some(percent as u8), None);
+        }
+    }
+    Ok((format!("{:x}", hasher.finalize()), read_total))
+}
+
+/// Hash `model_path` and compare it with the hash its Hugging Face repo publishes
+pub async fn verify(state: &AppState, model_path: &str, task: &Task) -> Result<FileVerification, String> {
+    let path = PathBuf::from(model_path);
+    if !path.is_file() {
+        return Err(format!("Model file not found: {}", model_path));
+    }
+    let directories: Vec<String> = {
+        let config = state.config.lock().await;
+        std::iter::once(config.models_directory.clone()).chain(config.additional_models_directories.iter().cloned()).collect()
+    };
+    let (sha256, bytes) = hash_file(&path, task).await?;
+
+    let located = repo_of(&path, &directories);
+    let mut expected_sha256 = None;
+    if let Some((repo, path_in_repo)) = &located {
+        let url = format!("https://huggingface.co/{}/resolve/main/{}", repo, path_in_repo);
+        if let Some(mut source) = HuggingFaceSource::parse(&url, &[]) {
+            source.fetch_hashes().await;
+            expected_sha256 = expected_for(&source.sha256, path_in_repo);
+        }
+    }
+    let matches = expected_sha256.as_ref().map(|expected| expected.eq_ignore_ascii_case(&sha256));
+    Ok(FileVerification {
+        path: model_path.to_string(),
+        bytes,
+        sha256,
+        repo: located.map(|(repo, _)| repo),
+        expected_sha256,
+        matches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_matched_to_their_repo_and_published_hash() {
+        let directories = vec!["/models".to_string(), "/more".to_string()];
+        assert_eq!(
+            repo_of(Path::new("/more/Qwen/Qwen3-8B-GGUF/qwen3-8b-Q4_K_M.gguf"), &directories),
+            Some(("Qwen/Qwen3-8B-GGUF".to_string(), "qwen3-8b-Q4_K_M.gguf".to_string()))
+        );
+        assert_eq!(repo_of(Path::new("/models/loose.gguf"), &directories), None);
+        assert_eq!(repo_of(Path::new("/elsewhere/a/b/c.gguf"), &directories), None);
+
+        let hashes: HashMap<String, String> = [
+            ("Q4_K_M/model-Q4_K_M.gguf", "aaa"),
+            ("Q8_0/model-Q8_0.gguf", "bbb"),
+            ("a/dup.gguf", "ccc"),
+            ("b/dup.gguf", "ddd"),
+        ]
+        .into_iter()
+        .map(|(path, hash)| (path.to_string(), hash.to_string()))
+        .collect();
+        assert_eq!(expected_for(&hashes, "Q8_0/model-Q8_0.gguf").as_deref(), Some("bbb"));
+        assert_eq!(expected_for(&hashes, "model-Q4_K_M.gguf").as_deref(), Some("aaa"));
+        assert_eq!(expected_for(&hashes, "dup.gguf"), None);
+    }
+}
@@ -10,6 +10,10 @@ pub struct GlobalConfig {
     pub models_directory: String,
     #[serde(default)]
     pub additional_models_directories: Vec<String>,
+    /// Models directories Arandu only reads from, e.g. a library shared over the network:
+    /// scanned and launched, but never deleted from, moved out of or downloaded into
+    #[serde(default)]
+    pub read_only_models_directories: Vec<String>,
     pub executable_folder: String,
     #[serde(default)]
     pub active_executable_folder: Option<String>,
@@ -28,8 +32,97 @@ pub struct GlobalConfig {
     pub network_server_host: String,
     #[serde(default = "default_network_server_port")]
     pub network_server_port: u16,
+    #[serde(default = "default_network_rate_limit_per_minute")]
+    pub network_rate_limit_per_minute: u32,
+    #[serde(default = "default_network_max_body_mb")]
+    pub network_max_body_mb: u32,
+    #[serde(default = "default_network_read_timeout_secs")]
+    pub network_read_timeout_secs: u64,
+    #[serde(default = "default_network_write_timeout_secs")]
+    pub network_write_timeout_secs: u64,
+    #[serde(default)]
+    pub network_blocked_clients: Vec<String>,
+    /// CIDRs allowed to reach the network server; empty allows everyone
+    #[serde(default = "default_network_allowed_cidrs")]
+    pub network_allowed_cidrs: Vec<String>,
+    /// Keys the network server accepts as `Authorization: Bearer`; none configured
+    /// leaves it open
+    #[serde(default)]
+    pub network_api_keys: Vec<ProxyApiKey>,
+    /// Launch an installed model that a proxied request names when it is not running
+    #[serde(default)]
+    pub proxy_load_models_on_request: bool,
     #[serde(default)]
     pub mcp_servers: Vec<McpServerConfig>,
+    /// Read only from settings written before the token moved to the OS keyring
+    /// (`github_token.rs`); never saved
+    #[serde(default, skip_serializing)]
+    pub github_token: Option<String>,
+    #[serde(default = "default_llamacpp_releases_refresh_hours")]
+    pub llamacpp_releases_refresh_hours: u32,
+    /// Installed builds kept per backend by `prune_llamacpp_versions`
+    #[serde(default = "default_llamacpp_keep_versions_per_backend")]
+    pub llamacpp_keep_versions_per_backend: u32,
+    /// Active llama.cpp build per backend type ("cuda", "vulkan", "cpu", ...), used by
+    /// models that prefer that backend. `active_executable_folder` stays the default.
+    #[serde(default)]
+    pub active_backend_versions: HashMap<String, String>,
+    /// Benchmark winners keyed by model class, see `backend_bench::model_class`. Stored in
+    /// `storage`; still read here so older settings files get imported.
+    #[serde(default, skip_serializing)]
+    pub backend_benchmarks: HashMap<String, BackendBenchmarkRecord>,
+    /// Named sampling bundles a chat request can pick with `"profile": "<id>"`
+    #[serde(default = "default_sampling_profiles")]
+    pub sampling_profiles: Vec<SamplingProfile>,
+    /// Model paths tried in order for proxied chat completions, primary first;
+    /// empty sends everything to the proxy's current server
+    #[serde(default)]
+    pub fallback_chain: Vec<String>,
+    /// Proxy limits keyed by model path or alias, `*` for the rest; see `route_policy`
+    #[serde(default)]
+    pub route_policies: HashMap<String, RoutePolicy>,
+    /// Downloads pause when a models disk has less free space than this (GB); 0 disables
+    #[serde(default = "default_disk_space_min_free_gb")]
+    pub disk_space_min_free_gb: f64,
+    /// Downloads running at once; the rest wait in the queue. 0 runs them all
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: u32,
+    /// Combined speed of all downloads in KB/s; 0 is unlimited
+    #[serde(default)]
+    pub download_bandwidth_limit_kb_per_sec: u64,
+    /// `clean_partial_downloads` removes partial downloads untouched for this many days; 0 removes them at any age
+    #[serde(default = "default_partial_download_retention_days")]
+    pub partial_download_retention_days: u32,
+    /// `clean_partial_downloads` leaves partial downloads that can still be resumed
+    #[serde(default = "default_true")]
+    pub keep_resumable_downloads: bool,
+    /// Language of backend messages; a locale with a catalog in `locales/`
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// Keep local usage statistics for `get_usage_analytics`; they never leave the machine
+    #[serde(default)]
+    pub usage_analytics_enabled: bool,
+    /// Minutes between background pings of enabled remote MCP servers; 0 disables
+    #[serde(default = "default_mcp_health_check_interval_minutes")]
+    pub mcp_health_check_interval_minutes: u64,
+    /// Terminal command for external launches (`{exe}`, `{args}`, `{script}`); empty picks an installed one
+    #[serde(default)]
+    pub external_terminal: String,
+    /// Launch every model with a small default context, one slot and mapped weights
+    #[serde(default)]
+    pub low_memory_mode: bool,
+    /// Context low memory mode uses when a model's arguments set none
+    #[serde(default = "default_low_memory_context")]
+    pub low_memory_context: u32,
+    /// Add a `-ngl` sized to the free VRAM to launches whose arguments set none
+    #[serde(default = "default_true")]
+    pub auto_gpu_layers: bool,
+    /// What to hold back on battery power, see `power`
+    #[serde(default)]
+    pub power_policy: PowerPolicy,
+    /// Models started and stopped at set times, see `scheduler`
+    #[serde(default)]
+    pub model_schedules: Vec<ModelSchedule>,
     // === NETWORK DISCOVERY CONFIGURATION ===
     #[serde(default)]
     pub discovery_enabled: bool,
@@ -43,10 +136,11 @@ pub struct GlobalConfig {
     pub discovery_instance_id: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum McpTransport {
     Stdio,
+    #[default]
     Sse,
     Http,
     Json,
@@ -54,12 +148,6 @@ pub enum McpTransport {
     StreamableHttp,
 }
 
-impl Default for McpTransport {
-    fn default() -> Self {
-        McpTransport::Sse
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct McpServerConfig {
     pub id: String,
@@ -98,6 +186,42 @@ pub struct McpServerConfig {
     pub tools_last_message: Option<String>,
     #[serde(default)]
     pub tools_last_error: Option<String>,
+    #[serde(default)]
+    pub resources: Vec<McpResourceInfo>,
+    #[serde(default)]
+    pub prompts: Vec<McpPromptInfo>,
+    /// When `resources/list` and `prompts/list` last ran
+    #[serde(default)]
+    pub catalog_last_refresh_at: Option<String>,
+    #[serde(default)]
+    pub catalog_last_error: Option<String>,
+    #[serde(default)]
+    pub auth: McpAuthConfig,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpAuthKind {
+    /// Static `headers` only
+    #[default]
+    None,
+    Bearer,
+    OauthDevice,
+}
+
+/// How a connection authenticates; tokens themselves are kept in the keyring
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct McpAuthConfig {
+    #[serde(default)]
+    pub kind: McpAuthKind,
+    #[serde(default)]
+    pub device_authorization_url: String,
+    #[serde(default)]
+    pub token_url: String,
+    #[serde(default)]
+    pub client_id: String,
+    #[serde(default)]
+    pub scope: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -109,6 +233,41 @@ pub struct McpToolInfo {
     pub input_schema: Option<Value>,
     #[serde(default, rename = "outputSchema")]
     pub output_schema: Option<Value>,
+    /// Disabled tools are not offered to models and calls to them are refused
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Argument values filled in when a call leaves them out
+    #[serde(default)]
+    pub argument_presets: serde_json::Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpResourceInfo {
+    pub uri: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, rename = "mimeType")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpPromptArgument {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpPromptInfo {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<McpPromptArgument>,
 }
 
 #[cfg(test)]
@@ -139,6 +298,29 @@ mod tests {
         assert_eq!(config.discovery_broadcast_interval, 5);
         assert!(!config.discovery_instance_name.is_empty());
         assert!(!config.discovery_instance_id.is_empty());
+        // Network server limits should have defaults
+        assert_eq!(config.network_rate_limit_per_minute, 120);
+        assert_eq!(config.network_max_body_mb, 32);
+        assert_eq!(config.network_read_timeout_secs, 30);
+        assert_eq!(config.network_write_timeout_secs, 60);
+        assert!(config.network_allowed_cidrs.contains(&"192.168.0.0/16".to_string()));
+        assert!(config.github_token.is_none());
+        assert_eq!(config.llamacpp_releases_refresh_hours, 6);
+        assert_eq!(config.llamacpp_keep_versions_per_backend, 3);
+        assert!(config.active_backend_versions.is_empty());
+        assert!(config.backend_benchmarks.is_empty());
+        assert!(config.sampling_profiles.iter().any(|p| p.id == "precise"));
+        assert_eq!(config.disk_space_min_free_gb, 10.0);
+
+        // A token from older settings is read but never written back
+        let mut with_token = config.clone();
+        with_token.github_token = Some("ghp_legacy".to_string());
+        let saved = serde_json::to_value(&with_token).unwrap();
+        assert!(saved.get("github_token").is_none());
+        let mut legacy = saved;
+        legacy["github_token"] = serde_json::json!("ghp_legacy");
+        let legacy: GlobalConfig = serde_json::from_value(legacy).unwrap();
+        assert_eq!(legacy.github_token.as_deref(), Some("ghp_legacy"));
     }
 
     #[test]
@@ -231,6 +413,21 @@ mod tests {
         assert!(tool.input_schema.is_some());
         assert!(tool.output_schema.is_some());
     }
+
+    #[test]
+    fn restart_policy_backs_off_and_gives_up() {
+        let policy: RestartPolicy =
+            serde_json::from_str(r#"{"mode": "on-failure", "max_retries": 3}"#).expect("policy should deserialize");
+        let secs = |code, restarts| policy.next_delay(code, restarts).map(|d| d.as_secs());
+
+        assert_eq!(secs(0, 0), None);
+        assert_eq!((secs(137, 0), secs(-1, 1), secs(1, 2)), (Some(5), Some(10), Some(20)));
+        assert_eq!(secs(1, 3), None);
+
+        let always = RestartPolicy { mode: RestartMode::Always, max_retries: 40, backoff_seconds: 5 };
+        assert_eq!(always.next_delay(0, 30).map(|d| d.as_secs()), Some(300));
+        assert_eq!(RestartPolicy::default().next_delay(1, 0), None);
+    }
 }
 
 impl McpServerConfig {
@@ -255,6 +452,11 @@ impl McpServerConfig {
             tools_last_status: None,
             tools_last_message: None,
             tools_last_error: None,
+            resources: Vec::new(),
+            prompts: Vec::new(),
+            catalog_last_refresh_at: None,
+            catalog_last_error: None,
+            auth: McpAuthConfig::default(),
         }
     }
 }
@@ -357,7 +559,91 @@ fn default_network_server_port() -> u16 {
     8080
 }
 
+fn default_network_rate_limit_per_minute() -> u32 {
+    120
+}
+
+fn default_network_max_body_mb() -> u32 {
+    32
+}
+
+fn default_network_read_timeout_secs() -> u64 {
+    30
+}
+
+fn default_network_write_timeout_secs() -> u64 {
+    60
+}
+
+fn default_disk_space_min_free_gb() -> f64 {
+    10.0
+}
+
+fn default_max_concurrent_downloads() -> u32 {
+    2
+}
+
+fn default_partial_download_retention_days() -> u32 {
+    7
+}
+
+fn default_mcp_health_check_interval_minutes() -> u64 {
+    5
+}
+
+fn default_low_memory_context() -> u32 {
+    4096
+}
+
+fn default_locale() -> String {
+    crate::i18n::DEFAULT_LOCALE.to_string()
+}
+
+/// Localhost plus RFC1918 / unique-local / link-local ranges
+pub(crate) fn default_network_allowed_cidrs() -> Vec<String> {
+    [
+        "127.0.0.0/8",
+        "::1/128",
+        "10.0.0.0/8",
+        "172.16.0.0/12",
+        "192.168.0.0/16",
+        "169.254.0.0/16",
+        "fc00::/7",
+        "fe80::/10",
+    ]
+    .iter()
+    .map(|cidr| cidr.to_string())
+    .collect()
+}
+
+fn default_llamacpp_releases_refresh_hours() -> u32 {
+    6
+}
+
+fn default_llamacpp_keep_versions_per_backend() -> u32 {
+    3
+}
+
 // === NETWORK DISCOVERY DEFAULT FUNCTIONS ===
+fn sampling_profile(id: &str, name: &str, temperature: f32, top_p: f32, top_k: f32, min_p: f32) -> SamplingProfile {
+    SamplingProfile {
+        id: id.to_string(),
+        name: name.to_string(),
+        temperature: Some(temperature),
+        top_p: Some(top_p),
+        top_k: Some(top_k),
+        min_p: Some(min_p),
+    }
+}
+
+pub(crate) fn default_sampling_profiles() -> Vec<SamplingProfile> {
+    vec![
+        sampling_profile("creative", "Creative", 1.0, 0.95, 64.0, 0.05),
+        sampling_profile("precise", "Precise", 0.3, 0.9, 20.0, 0.1),
+        sampling_profile("code", "Code", 0.15, 0.95, 40.0, 0.05),
+    ]
+}
+
 fn default_discovery_port() -> u16 {
     5352
 }
@@ -383,7 +669,16 @@ pub(crate) fn preferred_arandu_base_dir() -> PathBuf {
         return preferred;
     }
 
-    dirs::home_dir().unwrap_or_default().join(".Arandu")
+    default_arandu_base_dir()
+}
+
+/// `~/.Arandu`, or on macOS a folder under Application Support
+pub(crate) fn default_arandu_base_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_default();
+    if cfg!(target_os = "macos") {
+        return crate::macos::data_base_dir(&home, dirs::data_dir().as_deref());
+    }
+    home.join(".Arandu")
 }
 
 impl Default for GlobalConfig {
@@ -396,6 +691,7 @@ impl Default for GlobalConfig {
                 .unwrap_or_default()
                 .to_string(),
             additional_models_directories: Vec::new(),
+            read_only_models_directories: Vec::new(),
             executable_folder: base_dir
                 .join("llama.cpp")
                 .to_str()
@@ -410,7 +706,37 @@ impl Default for GlobalConfig {
             openai_proxy_port: 8081,
             network_server_host: "127.0.0.1".to_string(),
             network_server_port: 8080,
+            network_rate_limit_per_minute: default_network_rate_limit_per_minute(),
+            network_max_body_mb: default_network_max_body_mb(),
+            network_read_timeout_secs: default_network_read_timeout_secs(),
+            network_write_timeout_secs: default_network_write_timeout_secs(),
+            network_blocked_clients: Vec::new(),
+            network_allowed_cidrs: default_network_allowed_cidrs(),
+            network_api_keys: Vec::new(),
+            proxy_load_models_on_request: false,
             mcp_servers: Vec::new(),
+            github_token: None,
+            llamacpp_releases_refresh_hours: default_llamacpp_releases_refresh_hours(),
+            llamacpp_keep_versions_per_backend: default_llamacpp_keep_versions_per_backend(),
+            active_backend_versions: HashMap::new(),
+            backend_benchmarks: HashMap::new(),
+            sampling_profiles: default_sampling_profiles(),
+            fallback_chain: Vec::new(),
+            route_policies: HashMap::new(),
+            disk_space_min_free_gb: default_disk_space_min_free_gb(),
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            download_bandwidth_limit_kb_per_sec: 0,
+            partial_download_retention_days: default_partial_download_retention_days(),
+            keep_resumable_downloads: true,
+            locale: default_locale(),
+            usage_analytics_enabled: false,
+            mcp_health_check_interval_minutes: default_mcp_health_check_interval_minutes(),
+            external_terminal: String::new(),
+            low_memory_mode: false,
+            low_memory_context: default_low_memory_context(),
+            auto_gpu_layers: true,
+            power_policy: PowerPolicy::default(),
+            model_schedules: Vec::new(),
             // === NETWORK DISCOVERY DEFAULTS ===
             discovery_enabled: false,
             discovery_port: default_discovery_port(),
@@ -463,6 +789,129 @@ pub struct ModelConfig {
     pub update_available: bool, // Computed flag (legacy field)
     #[serde(default)]
     pub hf_metadata: Option<HfMetadata>, // New HF metadata from update_checker
+    /// llama.cpp build folder this model always launches with, overriding the active version
+    #[serde(default)]
+    pub pinned_llamacpp_version: Option<String>,
+    /// Backend type to launch with when not pinned; resolved through `active_backend_versions`
+    #[serde(default)]
+    pub preferred_backend: Option<String>,
+    /// Stop all other running models before this one launches
+    #[serde(default)]
+    pub exclusive_launch: bool,
+    /// Quick content hash (see `model_reconcile::fingerprint`) for finding the file after it moves
+    #[serde(default)]
+    pub file_fingerprint: Option<String>,
+    /// Unix timestamp of the last reconcile that found the file on disk
+    #[serde(default)]
+    pub last_seen_at: Option<i64>,
+    /// SHA-256 of the file, the identity settings follow across renames and moves
+    #[serde(default)]
+    pub content_sha256: Option<String>,
+    /// Whether the server is relaunched after it exits on its own
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    /// `--mlock`, `--no-mmap` and `--numa`, see `memory_mode`
+    #[serde(default)]
+    pub memory: MemorySettings,
+    /// GPUs the model runs on, see `multi_gpu`
+    #[serde(default)]
+    pub gpu_devices: GpuDevices,
+    /// Stop the server after this many minutes without requests; 0 keeps it running
+    #[serde(default)]
+    pub idle_timeout_minutes: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NumaMode {
+    Distribute,
+    Isolate,
+    Numactl,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemorySettings {
+    /// Pin the weights in RAM so the OS never pages them out
+    #[serde(default)]
+    pub mlock: bool,
+    /// Read the whole file into memory instead of mapping it
+    #[serde(default)]
+    pub no_mmap: bool,
+    #[serde(default)]
+    pub numa: Option<NumaMode>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GpuDevices {
+    /// llama-server device names (`CUDA0`, `Vulkan1`); empty leaves the choice to llama-server
+    #[serde(default)]
+    pub devices: Vec<String>,
+    /// Share of the model on each listed device; empty splits by free memory
+    #[serde(default)]
+    pub tensor_split: Vec<f32>,
+    /// Index into `devices` of the GPU for intermediate results and the KV cache
+    #[serde(default)]
+    pub main_gpu: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartMode {
+    #[default]
+    Never,
+    /// Only after a non-zero exit or a kill by signal (OOM killer, segfault)
+    OnFailure,
+    Always,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartPolicy {
+    #[serde(default)]
+    pub mode: RestartMode,
+    /// Restarts before giving up; the count carries over between attempts of one launch
+    #[serde(default = "default_restart_max_retries")]
+    pub max_retries: u32,
+    /// Delay before the first restart, doubled for each one after it
+    #[serde(default = "default_restart_backoff_seconds")]
+    pub backoff_seconds: u64,
+}
+
+fn default_restart_max_retries() -> u32 {
+    3
+}
+
+fn default_restart_backoff_seconds() -> u64 {
+    5
+}
+
+/// Longest wait between two restarts
+const MAX_RESTART_BACKOFF_SECS: u64 = 300;
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            max_retries: default_restart_max_retries(),
+            backoff_seconds: default_restart_backoff_seconds(),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Wait before restart number `restarts + 1` after an exit with `exit_code`,
+    /// or `None` when the policy says to leave the server stopped
+    pub fn next_delay(&self, exit_code: i32, restarts: u32) -> Option<std::time::Duration> {
+        let wanted = match self.mode {
+            RestartMode::Never => false,
+            RestartMode::OnFailure => exit_code != 0,
+            RestartMode::Always => true,
+        };
+        if !wanted || restarts >= self.max_retries {
+            return None;
+        }
+        let secs = self.backoff_seconds.saturating_mul(1u64 << restarts.min(16)).min(MAX_RESTART_BACKOFF_SECS);
+        Some(std::time::Duration::from_secs(secs))
+    }
 }
 
 impl ModelConfig {
@@ -484,6 +933,16 @@ impl ModelConfig {
             hf_file_size: None,
             update_available: false,
             hf_metadata: None,
+            pinned_llamacpp_version: None,
+            preferred_backend: None,
+            exclusive_launch: false,
+            file_fingerprint: None,
+            last_seen_at: None,
+            content_sha256: None,
+            restart_policy: RestartPolicy::default(),
+            memory: MemorySettings::default(),
+            gpu_devices: GpuDevices::default(),
+            idle_timeout_minutes: 0,
         }
     }
 }
@@ -506,6 +965,163 @@ pub struct GgufMetadata {
     pub quantization: Option<String>,
 }
 
+/// One backend's llama-bench run for `auto_select_backend`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendBenchmarkResult {
+    pub backend: String,
+    pub build_path: String,
+    /// Prompt processing speed, tokens/s
+    pub prompt_tps: Option<f64>,
+    /// Generation speed, tokens/s
+    pub gen_tps: Option<f64>,
+    pub error: Option<String>,
+    /// GPU power and clocks while llama-bench ran (NVIDIA only)
+    #[serde(default)]
+    pub gpu_power: Option<GpuPowerSummary>,
+}
+
+/// Averages of GPU power/clock samples taken while a model was generating
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GpuPowerSummary {
+    pub samples: u32,
+    pub avg_power_watts: f32,
+    pub peak_power_watts: f32,
+    pub avg_graphics_clock_mhz: u32,
+    pub avg_memory_clock_mhz: u32,
+    pub duration_ms: u64,
+    /// Average power over the measured time
+    pub energy_wh: f64,
+}
+
+/// A bundle of sampler settings. Unset fields leave the request (or the server default) alone.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SamplingProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub top_p: Option<f32>,
+    #[serde(default)]
+    pub top_k: Option<f32>,
+    #[serde(default)]
+    pub min_p: Option<f32>,
+}
+
+/// Start and/or stop a model at local times on some days of the week
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelSchedule {
+    #[serde(default)]
+    pub id: String,
+    pub model_path: String,
+    /// Preset the scheduled launch uses; the model's default preset otherwise
+    #[serde(default)]
+    pub preset_id: Option<String>,
+    /// `mon` .. `sun`; empty means every day
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// Local time as `HH:MM`
+    #[serde(default)]
+    pub start_at: Option<String>,
+    #[serde(default)]
+    pub stop_at: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// Throttling on battery power; off by default
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PowerPolicy {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Throttle on battery at or below this charge; the OS power saver always throttles
+    #[serde(default = "default_battery_threshold_percent")]
+    pub battery_threshold_percent: u8,
+    #[serde(default = "default_true")]
+    pub pause_idle_models: bool,
+    #[serde(default = "default_true")]
+    pub reduce_proxy_concurrency: bool,
+    /// Requests each model serves at once while throttled
+    #[serde(default = "default_power_proxy_max_concurrent")]
+    pub proxy_max_concurrent: u32,
+    #[serde(default = "default_true")]
+    pub refuse_launches: bool,
+}
+
+fn default_battery_threshold_percent() -> u8 {
+    30
+}
+
+fn default_power_proxy_max_concurrent() -> u32 {
+    1
+}
+
+impl Default for PowerPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            battery_threshold_percent: default_battery_threshold_percent(),
+            pause_idle_models: true,
+            reduce_proxy_concurrency: true,
+            proxy_max_concurrent: default_power_proxy_max_concurrent(),
+            refuse_launches: true,
+        }
+    }
+}
+
+/// Proxy limits for one model so a single client cannot starve the others
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RoutePolicy {
+    /// Whole-request budget, streams included
+    #[serde(default)]
+    pub request_timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub max_tokens_cap: Option<i32>,
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+}
+
+/// An API key of the network server. Only the SHA-256 of the key is stored; the key
+/// itself is shown once, when it is created.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProxyApiKey {
+    pub id: String,
+    pub name: String,
+    pub key_hash: String,
+    /// First characters of the key, to tell keys apart in lists
+    pub prefix: String,
+    /// Requests per minute with this key; 0 leaves only the per-client limit
+    #[serde(default)]
+    pub requests_per_minute: u32,
+    pub created_at: DateTime<Utc>,
+    /// Model the proxy serves to this key's client, loading it when it is not running
+    #[serde(default)]
+    pub launch_profile: Option<KeyLaunchProfile>,
+}
+
+/// Model and preset bound to an API key, e.g. the coder model with a 16k-context preset
+/// for an editor plugin's key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyLaunchProfile {
+    pub model_path: String,
+    /// Preset to launch with; the model's default preset when unset
+    #[serde(default)]
+    pub preset_id: Option<String>,
+}
+
+/// Fastest backend for a model class (architecture + size bucket), reused for
+/// models of the same class that have no backend preference
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendBenchmarkRecord {
+    pub model_class: String,
+    pub winner_backend: Option<String>,
+    pub winner_path: Option<String>,
+    pub results: Vec<BackendBenchmarkResult>,
+    /// Model the benchmark was run with
+    pub model_path: String,
+    pub measured_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HfMetadata {
     pub model_id: String,            // "author/model-name"
@@ -544,12 +1160,23 @@ pub struct ProcessInfo {
     pub output: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub last_sent_line: Option<usize>,
+    /// Preset the process was launched with, when any
+    #[serde(default)]
+    pub preset_id: Option<String>,
+    /// 1-based number telling apart instances of the same model running side by side
+    #[serde(default)]
+    pub instance: u32,
+    /// Times the restart policy relaunched this process after it exited
+    #[serde(default)]
+    pub restart_count: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ProcessStatus {
     Starting,
     Running,
+    /// Frozen by `suspend_process`; still holds its memory
+    Suspended,
     Stopped,
     Failed,
 }
@@ -562,6 +1189,30 @@ pub struct LaunchResult {
     pub server_port: u16,
     pub model_name: String,
     pub message: String,
+    #[serde(default)]
+    pub instance: u32,
+}
+
+/// One model of a `launch_model_batch` call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchLaunchItem {
+    pub model_path: String,
+    #[serde(default)]
+    pub preset_id: Option<String>,
+    /// Models of the same batch (path or file name) that must be up first
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchLaunchEntry {
+    pub model_path: String,
+    pub preset_id: Option<String>,
+    /// `launched`, `failed` or `skipped`
+    pub status: String,
+    pub message: String,
+    pub process_id: Option<String>,
+    pub server_port: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -575,6 +1226,7 @@ pub struct ProcessOutput {
 pub enum ModelStatus {
     Starting,
     Ready,
+    Suspended,
     Failed(String),
 }
 
@@ -624,23 +1276,13 @@ pub struct RemoteActiveModelsResponse {
     pub models: Vec<ActiveModel>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionState {
     pub windows: HashMap<String, WindowState>,
     pub terminals: HashMap<String, TerminalState>,
     pub desktop_state: DesktopState,
 }
 
-impl Default for SessionState {
-    fn default() -> Self {
-        Self {
-            windows: HashMap::new(),
-            terminals: HashMap::new(),
-            desktop_state: DesktopState::default(),
-        }
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowState {
     pub window_type: String,
@@ -717,6 +1359,9 @@ pub struct ModelBasic {
     pub likes: u64,
     #[serde(rename = "lastModified")]
     pub last_modified: Option<String>,
+    /// Q4_K_M at 4K context, from the parameter count in the name
+    #[serde(default)]
+    pub vram_estimate_gb: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -788,6 +1433,48 @@ pub struct TrackerModel {
     pub likes: u64,
     pub last_updated: Option<String>,
     pub created_at: String,
+    /// Use-case category from the enrichment pass or a manual override
+    #[serde(default)]
+    pub classification: Option<ModelClassification>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelClassification {
+    pub model_id: String,
+    /// `coder`, `roleplay`, `vision`, `embedding`, `reasoning` or `general`
+    pub category: String,
+    pub confidence: f64,
+    /// `model` or `manual`
+    pub source: String,
+    /// Local model that produced it
+    pub classified_by: Option<String>,
+    pub classified_at: String,
+}
+
+/// One GGUF file from a tracker model's Hugging Face file list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerFile {
+    pub file_name: String,
+    pub size_bytes: u64,
+    pub quantization: Option<String>,
+}
+
+/// A quantization a repo actually ships, split shards counted together
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerQuant {
+    pub name: String,
+    pub size_bytes: u64,
+    pub file_count: usize,
+    pub vram_estimate_gb: Option<f64>,
+}
+
+/// Cached file list of an expanded tracker model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerFileList {
+    pub model_id: String,
+    pub fetched_at: String,
+    pub files: Vec<TrackerFile>,
+    pub quantizations: Vec<TrackerQuant>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -797,6 +1484,16 @@ pub struct TrackerConfig {
     pub last_scrape: Option<String>,
     pub enabled_sources: Vec<String>,
     pub include_chinese: bool,
+    /// Classify new models with the running local model after each refresh
+    #[serde(default)]
+    pub auto_classify: bool,
+    /// Models missing from this many consecutive scrapes are pruned (watchlisted ones stay)
+    #[serde(default = "default_keep_scrapes")]
+    pub keep_scrapes: u32,
+}
+
+fn default_keep_scrapes() -> u32 {
+    3
 }
 
 impl Default for TrackerConfig {
@@ -807,10 +1504,44 @@ impl Default for TrackerConfig {
             last_scrape: None,
             enabled_sources: vec!["huggingface".to_string()],
             include_chinese: true,
+            auto_classify: false,
+            keep_scrapes: default_keep_scrapes(),
         }
     }
 }
 
+/// A curated Hugging Face collection; `items` may be truncated in owner listings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HfCollection {
+    pub slug: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub owner: String,
+    pub upvotes: u64,
+    pub last_updated: Option<String>,
+    pub items: Vec<HfCollectionItem>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HfCollectionItem {
+    pub id: String,
+    /// "model", "dataset", "space" or "paper"
+    pub item_type: String,
+    pub note: Option<String>,
+    pub downloads: Option<u64>,
+    pub likes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistEntry {
+    pub model_id: String,
+    /// "manual", "collection" or "curated"
+    pub source: String,
+    pub collection_slug: Option<String>,
+    pub note: Option<String>,
+    pub added_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrackerStats {
     pub total_models: u32,
@@ -819,6 +1550,20 @@ pub struct TrackerStats {
     pub categories: HashMap<String, u32>,
 }
 
+/// Size of the tracker database and what the last maintenance step removed
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TrackerDbInfo {
+    pub size_bytes: u64,
+    /// Space a VACUUM would give back
+    pub free_bytes: u64,
+    pub model_count: u32,
+    pub scrape_count: u32,
+    pub pruned: usize,
+    pub deduplicated: usize,
+    pub orphans_removed: usize,
+    pub vacuumed: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WeeklyReport {
     pub id: String,
@@ -833,6 +1578,132 @@ pub struct WeeklyReport {
     pub top_downloads: Vec<TrackerModel>,
 }
 
+// Chat log structures
+/// Per-message generation details stored next to a chat's markdown file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChatMessageMetadata {
+    #[serde(default)]
+    pub message_index: usize,
+    #[serde(default)]
+    pub role: String,
+    #[serde(default)]
+    pub timestamp: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub params: Option<Value>,
+    #[serde(default)]
+    pub tokens_per_second: Option<f64>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub prompt_tokens: Option<u64>,
+    #[serde(default)]
+    pub completion_tokens: Option<u64>,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    /// Sampling seed of the request, from `params.seed` when not given; needed to repeat
+    /// the message exactly
+    #[serde(default)]
+    pub seed: Option<i64>,
+    /// Set when the message was translated before sending or after receiving
+    #[serde(default)]
+    pub translation: Option<MessageTranslation>,
+    /// From `start_gpu_telemetry` / `stop_gpu_telemetry` around the generation
+    #[serde(default)]
+    pub gpu_power: Option<GpuPowerSummary>,
+}
+
+/// One message of a chat's structured log (`<chat_id>.json` next to the markdown)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatLogMessage {
+    pub role: String,
+    pub content: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// RFC 3339 time the message was logged
+    #[serde(default, alias = "created_at")]
+    pub timestamp: String,
+    /// Generation time of an assistant message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTranslation {
+    pub source_language: String,
+    pub target_language: String,
+    pub original: String,
+    pub translated: String,
+}
+
+/// Per-chat translation assist for talking to English-centric models in other languages
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranslationSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Language the model is addressed in (ISO 639-1)
+    #[serde(default = "default_translation_target")]
+    pub target_language: String,
+    /// Running model used for translation; the chat's model when unset
+    #[serde(default)]
+    pub model_path: Option<String>,
+    /// Custom instruction; `{source}` and `{target}` are replaced with language names
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+fn default_translation_target() -> String {
+    "en".to_string()
+}
+
+impl Default for TranslationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_language: default_translation_target(),
+            model_path: None,
+            prompt: None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// How a chat behaves once the conversation outgrows the context window
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContextSettings {
+    /// Launch with `--context-shift` so old tokens are dropped instead of the request failing
+    #[serde(default = "default_true")]
+    pub context_shift: bool,
+    /// Reuse the KV cache of the shared prompt prefix between turns
+    #[serde(default = "default_true")]
+    pub cache_prompt: bool,
+    /// Prompt tokens kept when the context shifts (-1 keeps the whole prompt)
+    #[serde(default)]
+    pub n_keep: Option<i32>,
+    /// Minimum chunk size for `--cache-reuse`; unset leaves KV shifting off
+    #[serde(default)]
+    pub cache_reuse: Option<u32>,
+}
+
+impl Default for ContextSettings {
+    fn default() -> Self {
+        Self {
+            context_shift: true,
+            cache_prompt: true,
+            n_keep: None,
+            cache_reuse: None,
+        }
+    }
+}
+
 // Note: DiscoveredPeer, RemoteModel, and DiscoveryStatus are defined in discovery.rs
 // Re-export them here for use in other modules
 pub use crate::discovery::{DiscoveredPeer, DiscoveryStatus};
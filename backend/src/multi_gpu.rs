@@ -0,0 +1,76 @@
+use crate::cli_args::{self, set_flag};
+use crate::models::GpuDevices;
+
+// Which GPUs a model runs on. `ModelConfig.gpu_devices` becomes `--device`,
+// `--tensor-split` and `--main-gpu` at launch, replacing any the arguments set.
+// Device names are the ones llama-server prints with `--list-devices`;
+// `system_monitor::list_gpus` offers the CUDA ones it can see.
+
+const DEVICE_FLAGS: &[&str] = &["-dev", "--device"];
+const TENSOR_SPLIT_FLAGS: &[&str] = &["-ts", "--tensor-split"];
+const MAIN_GPU_FLAGS: &[&str] = &["-mg", "--main-gpu"];
+
+pub fn validate(settings: &GpuDevices) -> Result<(), String> {
+    if settings.devices.iter().any(|d| d.trim().is_empty() || d.contains([',', ' '])) {
+        return Err("Device names must be non-empty and contain no commas or spaces".to_string());
+    }
+    if !settings.tensor_split.is_empty() {
+        if !settings.devices.is_empty() && settings.tensor_split.len() != settings.devices.len() {
+            return Err(format!(
+                "Tensor split has {} values for {} devices",
+                settings.tensor_split.len(),
+                settings.devices.len()
+            ));
+        }
+        if settings.tensor_split.iter().any(|v| !v.is_finite() || *v < 0.0) || settings.tensor_split.iter().all(|v| *v == 0.0) {
+            return Err("Tensor split values must be zero or positive, and not all zero".to_string());
+        }
+    }
+    if let (Some(main), false) = (settings.main_gpu, settings.devices.is_empty()) {
+        if main as usize >= settings.devices.len() {
+            return Err(format!("Main GPU {} is not one of the {} selected devices", main, settings.devices.len()));
+        }
+    }
+    Ok(())
+}
+
+/// Launch arguments with the model's device selection merged in
+pub fn launch_args(custom_args: &str, settings: &GpuDevices) -> String {
+    if *settings == GpuDevices::default() {
+        return custom_args.to_string();
+    }
+    let mut args = cli_args::split(custom_args);
+    if !settings.devices.is_empty() {
+        set_flag(&mut args, DEVICE_FLAGS, &settings.devices.join(","));
+    }
+    if !settings.tensor_split.is_empty() {
+        let split: Vec<String> = settings.tensor_split.iter().map(|v| v.to_string()).collect();
+        set_flag(&mut args, TENSOR_SPLIT_FLAGS, &split.join(","));
+    }
+    if let Some(main) = settings.main_gpu {
+        set_flag(&mut args, MAIN_GPU_FLAGS, &main.to_string());
+    }
+    cli_args::join(&args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_selection_becomes_llama_server_flags() {
+        let two = GpuDevices {
+            devices: vec!["CUDA0".to_string(), "CUDA1".to_string()],
+            tensor_split: vec![3.0, 1.5],
+            main_gpu: Some(1),
+        };
+        assert!(validate(&two).is_ok());
+        assert_eq!(launch_args("-c 8192 -ts 1,1", &two), "-c 8192 -ts 3,1.5 -dev CUDA0,CUDA1 -mg 1");
+        assert_eq!(launch_args("-c  8192", &GpuDevices::default()), "-c  8192");
+
+        assert!(validate(&GpuDevices { tensor_split: vec![1.0], ..two.clone() }).is_err());
+        assert!(validate(&GpuDevices { tensor_split: vec![0.0, 0.0], ..two.clone() }).is_err());
+        assert!(validate(&GpuDevices { main_gpu: Some(2), ..two.clone() }).is_err());
+        assert!(validate(&GpuDevices { devices: vec!["CUDA0,CUDA1".to_string()], tensor_split: Vec::new(), main_gpu: None }).is_err());
+    }
+}
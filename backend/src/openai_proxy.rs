@@ -1,5 +1,6 @@
 use axum::{
-    extract::State,
+    extract::{ConnectInfo, DefaultBodyLimit, State},
+    Extension,
     http::StatusCode,
     response::{IntoResponse, Json},
     routing::{get, post},
@@ -17,11 +18,14 @@ use tokio::sync::RwLock;
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 use crate::openai_types::{
-    ChatCompletionRequest, AudioTranscriptionRequest, AudioTranscriptionResponse,
+    ChatCompletionRequest, CompletionRequest, AudioTranscriptionRequest, AudioTranscriptionResponse,
     AudioSpeechRequest, ImageGenerationRequest,
     ModelInfo, ModelsResponse, OpenAIError, OpenAIErrorResponse
 };
 use crate::llama_client::LlamaClient;
+use crate::proxy_guard::{guard_request, parse_allowlist, usage_tokens, AuthorizedKey, ProxyGuard, ProxyLimits};
+use crate::route_policy::{Admission, ConcurrencyLimiter};
+use crate::generation_metrics::GenerationMeter;
 use crate::AppState;
 use crate::models::{ActiveModel, ModelStatus, ProcessStatus};
 
@@ -36,6 +40,7 @@ pub struct ProxyServer {
     proxy_port: u16,
     shutdown_tx: Option<tokio::sync::mpsc::Sender<()>>,
     models_directories: Vec<String>,
+    guard: Option<Arc<ProxyGuard>>,
 }
 
 impl ProxyServer {
@@ -45,9 +50,15 @@ impl ProxyServer {
             proxy_port,
             shutdown_tx: None,
             models_directories,
+            guard: None,
         }
     }
 
+    /// Access log and limits of the running server, if started
+    pub fn guard(&self) -> Option<Arc<ProxyGuard>> {
+        self.guard.clone()
+    }
+
     pub async fn start(&mut self, app_state: Arc<AppState>) -> Result<(), String> {
         // Configure CORS to allow all origins (needed for cross-LAN access)
         let cors = CorsLayer::new()
@@ -56,26 +67,47 @@ impl ProxyServer {
             .allow_headers(Any);
 
         let models_dirs = self.models_directories.clone();
+        let (limits, blocked_clients, allowed_cidrs, api_keys) = {
+            let config = app_state.config.lock().await;
+            (
+                ProxyLimits::from_config(&config),
+                config.network_blocked_clients.clone(),
+                config.network_allowed_cidrs.clone(),
+                config.network_api_keys.clone(),
+            )
+        };
+        let allowlist = parse_allowlist(&allowed_cidrs)?;
+        let guard = Arc::new(ProxyGuard::new(limits.clone(), &blocked_clients, allowlist, api_keys));
+        self.guard = Some(guard.clone());
 
         let app = Router::new()
             .route("/v1/models", get(list_models))
             .route("/v1/models/arandu", get(list_models_arandu))
             .route("/v1/chat/completions", post(chat_completions))
+            .route("/v1/completions", post(completions))
             .route("/v1/audio/transcriptions", post(audio_transcriptions))
             .route("/v1/audio/speech", post(audio_speech))
             .route("/v1/images/generations", post(image_generations))
+            .route("/v1/embeddings", post(embeddings))
+            .route("/v1/rerank", post(rerank))
+            .route("/infill", post(infill))
             .route("/health", get(health_check))
+            .route("/openapi.json", get(openapi_document))
 
             .route("/api/models/launch", post(launch_model))
             .route("/api/models/stop", post(stop_model))
             .route("/api/models/active", get(list_active_models))
 
+            .layer(DefaultBodyLimit::max(limits.max_body_bytes))
+            .layer(axum::middleware::from_fn_with_state(guard.clone(), guard_request))
             .layer(cors)
             .with_state(Arc::new(RwLock::new(ProxyState {
                 llama_server_url: self.llama_server_url.clone(),
                 llama_client: LlamaClient::new(self.llama_server_url.clone()),
                 models_directories: models_dirs,
                 app_state,
+                guard,
+                route_limiter: ConcurrencyLimiter::default(),
             })));
 
         let addr = SocketAddr::from(([0, 0, 0, 0], self.proxy_port));
@@ -93,7 +125,7 @@ impl ProxyServer {
         info!("OpenAI proxy server bound to {} and ready to accept connections", addr);
 
         tokio::spawn(async move {
-            axum::serve(listener, app)
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
                 .with_graceful_shutdown(async move {
                     shutdown_rx.recv().await;
                 })
@@ -113,10 +145,69 @@ impl ProxyServer {
 
 /// Shared state for proxy handlers
 pub struct ProxyState {
+    /// Default upstream, for requests naming no running model
     pub llama_server_url: String,
     pub llama_client: LlamaClient,
     pub models_directories: Vec<String>,
     pub app_state: Arc<AppState>,
+    pub guard: Arc<ProxyGuard>,
+    pub route_limiter: ConcurrencyLimiter,
+}
+
+/// Upstream for a request naming `requested_model`: the model bound to the request's API
+/// key (launched when needed, and written into `requested_model`), else the running server
+/// of the named model, else that model launched on request, else the default one
+async fn upstream_for(
+    state: &Arc<RwLock<ProxyState>>,
+    key: Option<&AuthorizedKey>,
+    requested_model: &mut String,
+) -> Result<(Arc<AppState>, String, LlamaClient), axum::response::Response> {
+    let (app_state, default_url, default_client, directories) = {
+        let state_guard = state.read().await;
+        (
+            state_guard.app_state.clone(),
+            state_guard.llama_server_url.clone(),
+            state_guard.llama_client.clone(),
+            state_guard.models_directories.clone(),
+        )
+    };
+    if let Some(AuthorizedKey(key)) = key {
+        if let Some(profile) = &key.launch_profile {
+            let server = crate::model_routing::ensure_running(&app_state, profile)
+                .await
+                .map_err(|e| model_unavailable_response(format!("The model for API key {} could not be loaded: {}", key.name, e)))?;
+            *requested_model = server.model_name;
+            let client = LlamaClient::new(server.base_url.clone());
+            return Ok((app_state, server.base_url, client));
+        }
+    }
+    let server = match crate::model_routing::resolve(&app_state, requested_model).await {
+        Some(server) => Some(server),
+        None => crate::model_routing::load_on_request(&app_state, &directories, requested_model)
+            .await
+            .transpose()
+            .map_err(|e| model_unavailable_response(format!("Model {} could not be loaded: {}", requested_model, e)))?,
+    };
+    match server {
+        Some(server) if server.base_url != default_url => {
+            let client = LlamaClient::new(server.base_url.clone());
+            Ok((app_state, server.base_url, client))
+        }
+        _ => Ok((app_state, default_url, default_client)),
+    }
+}
+
+fn model_unavailable_response(message: String) -> axum::response::Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(OpenAIErrorResponse {
+            error: OpenAIError {
+                message,
+                error_type: "model_unavailable".to_string(),
+                code: Some("503".to_string()),
+            },
+        })
+    ).into_response()
 }
 
 // ============== HANDLER FUNCTIONS ==============
@@ -125,17 +216,45 @@ async fn health_check() -> impl IntoResponse {
     Json(json!({"status": "healthy"}))
 }
 
+async fn openapi_document(headers: axum::http::HeaderMap) -> impl IntoResponse {
+    let server_url = headers
+        .get(axum::http::header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .map(|host| format!("http://{}", host));
+    Json(crate::openapi_spec::build_openapi_document(server_url.as_deref()))
+}
+
 async fn list_models(
     State(state): State<Arc<RwLock<ProxyState>>>,
 ) -> impl IntoResponse {
+    let app_state = state.read().await.app_state.clone();
+    let running = crate::model_routing::running_models(&app_state).await;
+    if !running.is_empty() {
+        let models = running
+            .into_iter()
+            .map(|server| ModelInfo {
+                id: server.model_name,
+                object: "model".to_string(),
+                created: chrono::Utc::now().timestamp(),
+                owned_by: "llama.cpp".to_string(),
+                size_gb: None,
+                quantization: None,
+                architecture: None,
+                date: None,
+                path: Some(server.model_path),
+                has_custom_launch_config: None,
+            })
+            .collect();
+        return (StatusCode::OK, Json(ModelsResponse { object: "list".to_string(), data: models })).into_response();
+    }
+
+    // Nothing Arandu launched is running; ask whatever serves the default upstream
     let state_guard = state.read().await;
     // llama.cpp uses /props endpoint to get model info, not /v1/models
     let url = format!("{}/props", state_guard.llama_server_url);
     drop(state_guard);
 
-    let client = reqwest::Client::new();
-
-    match client.get(&url).timeout(std::time::Duration::from_secs(5)).send().await {
+    match crate::http_pool::get(&url, crate::http_pool::RequestClass::Health).send().await {
         Ok(response) if response.status().is_success() => {
             // Parse llama.cpp props response to get model name
             match response.json::<serde_json::Value>().await {
@@ -274,16 +393,42 @@ async fn list_models_arandu(
 
 async fn chat_completions(
     State(state): State<Arc<RwLock<ProxyState>>>,
-    Json(request): Json<ChatCompletionRequest>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    key: Option<Extension<AuthorizedKey>>,
+    Json(mut request): Json<ChatCompletionRequest>,
 ) -> impl IntoResponse {
-    // Check if llama.cpp server is reachable
-    let health_url = format!("{}/health", state.read().await.llama_server_url);
-    let health_client = reqwest::Client::new();
-    
-    match health_client.get(&health_url).timeout(Duration::from_secs(2)).send().await {
+    let (app_state, server_url, client) = match upstream_for(&state, key.as_deref(), &mut request.model).await {
+        Ok(upstream) => upstream,
+        Err(response) => return response,
+    };
+    let fallback_chain = app_state.config.lock().await.fallback_chain.clone();
+    // A restart to apply new load parameters is short; hold the request rather than fail it
+    if !crate::parameter_controller::wait_while_restarting(&app_state, &server_url).await {
+        let mut response = (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(OpenAIErrorResponse {
+                error: OpenAIError {
+                    message: "The model is restarting to apply new parameters. Retry shortly.".to_string(),
+                    error_type: "model_restarting".to_string(),
+                    code: Some("503".to_string()),
+                },
+            })
+        ).into_response();
+        response.headers_mut().insert(axum::http::header::RETRY_AFTER, axum::http::HeaderValue::from_static("10"));
+        return response;
+    }
+
+    // Check if llama.cpp server is reachable (the fallback chain skips models that are not)
+    let health_url = format!("{}/health", server_url);
+    match crate::http_pool::get(&health_url, crate::http_pool::RequestClass::Health)
+        .timeout(Duration::from_secs(2))
+        .send()
+        .await
+    {
         Ok(resp) if resp.status().is_success() => {
             // Server is healthy, proceed
         }
+        _ if !fallback_chain.is_empty() => {}
         _ => {
             return (
                 StatusCode::SERVICE_UNAVAILABLE,
@@ -298,19 +443,57 @@ async fn chat_completions(
         }
     }
 
+    crate::context_settings::apply_for_request(&mut request);
+
+    // Request values first, then the named sampling profile, then the process defaults
+    let profiles = app_state.config.lock().await.sampling_profiles.clone();
+    if let Err(message) = crate::sampling_profiles::resolve_and_apply(&profiles, &mut request) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(OpenAIErrorResponse {
+                error: OpenAIError {
+                    message,
+                    error_type: "invalid_request_error".to_string(),
+                    code: Some("400".to_string()),
+                },
+            })
+        ).into_response();
+    }
+    crate::parameter_controller::apply_generation_defaults(&app_state, &server_url, &mut request).await;
+    crate::idle_unload::touch(&server_url);
+
+    // Per-model policy: max_tokens cap, a concurrency slot and the request timeout
+    let admission = {
+        let state_guard = state.read().await;
+        crate::route_policy::admit(&app_state, &state_guard.route_limiter, &server_url, &request.model, &mut request.max_tokens).await
+    };
+    let admission = match admission {
+        Ok(admission) => admission,
+        Err(message) => return model_busy_response(message),
+    };
+
+    if !fallback_chain.is_empty() {
+        return fallback_completion(state, &app_state, &server_url, &fallback_chain, request, addr, admission).await;
+    }
+
     // Check if streaming is requested
     let stream = request.stream.unwrap_or(false);
     
     if stream {
-        return handle_streaming_completion(state, request).await.into_response();
+        return handle_streaming_completion(state, server_url, client, request, addr, admission).await.into_response();
     }
     
     // Handle non-streaming completion
     let state_guard = state.read().await;
-    let client = &state_guard.llama_client;
     
-    match client.chat_completion(&request).await {
+    let Some(result) = crate::route_policy::within(admission.timeout, client.chat_completion(&request)).await else {
+        return request_timeout_response(admission.timeout);
+    };
+    match result {
         Ok(response) => {
+            if let Some((prompt, completion)) = usage_tokens(&response) {
+                state_guard.guard.clients.record_tokens(addr.ip(), prompt, completion);
+            }
             // llama.cpp returns OpenAI-compatible format, just pass it through
             (StatusCode::OK, Json(response)).into_response()
         }
@@ -327,6 +510,261 @@ async fn chat_completions(
     }
 }
 
+fn model_busy_response(message: String) -> axum::response::Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(OpenAIErrorResponse {
+            error: OpenAIError {
+                message,
+                error_type: "model_busy".to_string(),
+                code: Some("429".to_string()),
+            },
+        })
+    ).into_response();
+    response.headers_mut().insert(axum::http::header::RETRY_AFTER, axum::http::HeaderValue::from_static("5"));
+    response
+}
+
+fn request_timeout_response(timeout: Option<Duration>) -> axum::response::Response {
+    (
+        StatusCode::GATEWAY_TIMEOUT,
+        Json(OpenAIErrorResponse {
+            error: OpenAIError {
+                message: format!(
+                    "Request exceeded the {}s timeout set for this model",
+                    timeout.map(|t| t.as_secs()).unwrap_or(0)
+                ),
+                error_type: "request_timeout".to_string(),
+                code: Some("504".to_string()),
+            },
+        })
+    ).into_response()
+}
+
+fn answered_by_headers(response: &mut axum::response::Response, answered: &crate::fallback::Candidate, fallback_used: bool) {
+    let headers = response.headers_mut();
+    if let Ok(value) = axum::http::HeaderValue::from_str(&answered.model_name) {
+        headers.insert(axum::http::HeaderName::from_static("x-arandu-answered-by"), value);
+    }
+    headers.insert(
+        axum::http::HeaderName::from_static("x-arandu-fallback-used"),
+        axum::http::HeaderValue::from_static(if fallback_used { "true" } else { "false" }),
+    );
+}
+
+/// Chat completion on the routed server, then through `GlobalConfig.fallback_chain`; the
+/// response says which model answered
+#[allow(clippy::too_many_arguments)]
+async fn fallback_completion(
+    state: Arc<RwLock<ProxyState>>,
+    app_state: &AppState,
+    server_url: &str,
+    chain: &[String],
+    request: ChatCompletionRequest,
+    addr: SocketAddr,
+    admission: Admission,
+) -> axum::response::Response {
+    let guard = state.read().await.guard.clone();
+    let (candidates, skipped) = crate::fallback::candidates(server_url, &request.model, chain, app_state).await;
+
+    let result = if request.stream.unwrap_or(false) {
+        match crate::fallback::chat_completion_stream(&candidates, skipped, &request).await {
+            Ok((upstream, answered, attempts)) => {
+                let meter = GenerationMeter::new(&answered.model_name, request.max_tokens)
+                    .with_backend(crate::energy_report::served_backend(app_state, &answered.base_url).await)
+                    .with_power_sampling();
+                let mut response = relay_stream(Ok(upstream), guard, addr, admission, meter, None).into_response();
+                answered_by_headers(&mut response, &answered, !attempts.is_empty());
+                Ok(response)
+            }
+            Err(attempts) => Err(attempts),
+        }
+    } else {
+        let timeout = admission.timeout;
+        let Some(result) =
+            crate::route_policy::within(timeout, crate::fallback::chat_completion(&candidates, skipped, &request)).await
+        else {
+            return request_timeout_response(timeout);
+        };
+        result
+            .map(|(body, answered, attempts)| {
+                if let Some((prompt, completion)) = usage_tokens(&body) {
+                    guard.clients.record_tokens(addr.ip(), prompt, completion);
+                }
+                let mut response = (StatusCode::OK, Json(body)).into_response();
+                answered_by_headers(&mut response, &answered, !attempts.is_empty());
+                response
+            })
+    };
+
+    result.unwrap_or_else(|attempts| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(OpenAIErrorResponse {
+                error: OpenAIError {
+                    message: crate::fallback::exhausted_message(&attempts),
+                    error_type: "fallback_exhausted".to_string(),
+                    code: Some("503".to_string()),
+                },
+            })
+        ).into_response()
+    })
+}
+
+/// Legacy text completions: passed through, or answered via chat when the server has no `/v1/completions`
+async fn completions(
+    State(state): State<Arc<RwLock<ProxyState>>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    key: Option<Extension<AuthorizedKey>>,
+    Json(mut request): Json<CompletionRequest>,
+) -> impl IntoResponse {
+    let guard = state.read().await.guard.clone();
+    let (app_state, server_url, client) = match upstream_for(&state, key.as_deref(), &mut request.model).await {
+        Ok(upstream) => upstream,
+        Err(response) => return response,
+    };
+    crate::idle_unload::touch(&server_url);
+    let admission = {
+        let state_guard = state.read().await;
+        crate::route_policy::admit(&app_state, &state_guard.route_limiter, &server_url, &request.model, &mut request.max_tokens).await
+    };
+    let admission = match admission {
+        Ok(admission) => admission,
+        Err(message) => return model_busy_response(message),
+    };
+    let stream = request.stream.unwrap_or(false);
+    let upstream_error = |e: String| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(OpenAIErrorResponse {
+                error: OpenAIError { message: e, error_type: "api_error".to_string(), code: Some("500".to_string()) },
+            })
+        ).into_response()
+    };
+
+    let passthrough = match crate::route_policy::within(admission.timeout, client.completion(&request)).await {
+        Some(result) => result,
+        None => return request_timeout_response(admission.timeout),
+    };
+    match passthrough {
+        Ok(Some(response)) if stream => {
+            let meter = GenerationMeter::new(&request.model, request.max_tokens)
+                .with_backend(crate::energy_report::served_backend(&app_state, &server_url).await)
+                .with_power_sampling();
+            return relay_stream(Ok(response), guard, addr, admission, meter, None).into_response();
+        }
+        Ok(Some(response)) => {
+            return match response.json::<serde_json::Value>().await {
+                Ok(body) => {
+                    if let Some((prompt, completion)) = usage_tokens(&body) {
+                        guard.clients.record_tokens(addr.ip(), prompt, completion);
+                    }
+                    (StatusCode::OK, Json(body)).into_response()
+                }
+                Err(e) => upstream_error(format!("Failed to parse llama.cpp response: {}", e)),
+            };
+        }
+        Ok(None) => {}
+        Err(e) => return upstream_error(e),
+    }
+
+    // Chat-only server: translate the prompt to one user message
+    let chat_request = match crate::legacy_completions::to_chat_request(&request) {
+        Ok(chat_request) => chat_request,
+        Err(message) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(OpenAIErrorResponse {
+                    error: OpenAIError { message, error_type: "invalid_request_error".to_string(), code: Some("400".to_string()) },
+                })
+            ).into_response();
+        }
+    };
+    if stream {
+        let upstream = client.chat_completion_stream(&chat_request).await;
+        let meter = GenerationMeter::new(&request.model, request.max_tokens)
+            .with_backend(crate::energy_report::served_backend(&app_state, &server_url).await)
+            .with_power_sampling();
+        return relay_stream(upstream, guard, addr, admission, meter, Some(crate::legacy_completions::completion_chunk_from_chat))
+            .into_response();
+    }
+    let Some(result) = crate::route_policy::within(admission.timeout, client.chat_completion(&chat_request)).await else {
+        return request_timeout_response(admission.timeout);
+    };
+    match result {
+        Ok(body) => {
+            if let Some((prompt, completion)) = usage_tokens(&body) {
+                guard.clients.record_tokens(addr.ip(), prompt, completion);
+            }
+            (StatusCode::OK, Json(crate::legacy_completions::completion_from_chat(&body))).into_response()
+        }
+        Err(e) => upstream_error(e),
+    }
+}
+
+async fn embeddings(
+    State(state): State<Arc<RwLock<ProxyState>>>,
+    key: Option<Extension<AuthorizedKey>>,
+    Json(body): Json<serde_json::Value>,
+) -> axum::response::Response {
+    passthrough(state, key, "/v1/embeddings", body).await
+}
+
+async fn rerank(
+    State(state): State<Arc<RwLock<ProxyState>>>,
+    key: Option<Extension<AuthorizedKey>>,
+    Json(body): Json<serde_json::Value>,
+) -> axum::response::Response {
+    passthrough(state, key, "/v1/rerank", body).await
+}
+
+async fn infill(
+    State(state): State<Arc<RwLock<ProxyState>>>,
+    key: Option<Extension<AuthorizedKey>>,
+    Json(body): Json<serde_json::Value>,
+) -> axum::response::Response {
+    passthrough(state, key, "/infill", body).await
+}
+
+/// Relay a request to the same `path` on the server its `model` routes to, and the
+/// server's response back unchanged: status, content type and body, streamed
+async fn passthrough(
+    state: Arc<RwLock<ProxyState>>,
+    key: Option<Extension<AuthorizedKey>>,
+    path: &str,
+    mut body: serde_json::Value,
+) -> axum::response::Response {
+    let mut model = body.get("model").and_then(|m| m.as_str()).unwrap_or_default().to_string();
+    let (_, server_url, client) = match upstream_for(&state, key.as_deref(), &mut model).await {
+        Ok(upstream) => upstream,
+        Err(response) => return response,
+    };
+    if let Some(fields) = body.as_object_mut().filter(|_| !model.is_empty()) {
+        fields.insert("model".to_string(), json!(model));
+    }
+    crate::idle_unload::touch(&server_url);
+
+    let upstream = match client.forward(path, &body).await {
+        Ok(upstream) => upstream,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(OpenAIErrorResponse {
+                    error: OpenAIError { message: e, error_type: "api_error".to_string(), code: Some("500".to_string()) },
+                })
+            ).into_response();
+        }
+    };
+    let status = StatusCode::from_u16(upstream.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let content_type = upstream.headers().get(reqwest::header::CONTENT_TYPE).cloned();
+    let mut response = axum::response::Response::new(axum::body::Body::from_stream(upstream.bytes_stream()));
+    *response.status_mut() = status;
+    if let Some(content_type) = content_type {
+        response.headers_mut().insert(axum::http::header::CONTENT_TYPE, content_type);
+    }
+    response
+}
+
 async fn audio_transcriptions(
     State(_state): State<Arc<RwLock<ProxyState>>>,
     Json(_request): Json<AudioTranscriptionRequest>,
@@ -373,49 +811,113 @@ async fn image_generations(
 
 async fn handle_streaming_completion(
     state: Arc<RwLock<ProxyState>>,
+    server_url: String,
+    client: LlamaClient,
     request: ChatCompletionRequest,
+    addr: SocketAddr,
+    admission: Admission,
 ) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
     let state_guard = state.read().await;
-    let client = state_guard.llama_client.clone();
+    let guard = state_guard.guard.clone();
+    let backend = crate::energy_report::served_backend(&state_guard.app_state, &server_url).await;
     drop(state_guard);
-    
-    let stream = async_stream::stream! {
-        match client.chat_completion_stream(&request).await {
-            Ok(response) => {
-                let mut stream = response.bytes_stream();
+
+    let meter = GenerationMeter::new(&request.model, request.max_tokens)
+        .with_backend(backend)
+        .with_power_sampling();
+    let upstream = client.chat_completion_stream(&request).await;
+    relay_stream(upstream, guard, addr, admission, meter, None)
+}
+
+/// Forward an upstream SSE response to the client, or a single error event.
+/// The admission's concurrency slot is held until the stream ends; `meter` reports
+/// live speed; `convert` rewrites each `data:` payload (chat chunks for a legacy
+/// completion client).
+fn relay_stream(
+    upstream: Result<reqwest::Response, String>,
+    guard: Arc<ProxyGuard>,
+    addr: SocketAddr,
+    admission: Admission,
+    mut meter: GenerationMeter,
+    convert: Option<fn(&str) -> String>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let write_timeout = guard.limits.write_timeout;
+
+    // Upstream chunks go through a small bounded channel; if the client stops reading
+    // for longer than the write timeout we drop the upstream request instead of
+    // buffering the whole generation in memory.
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<Event>(16);
+
+    tokio::spawn(async move {
+        let _permit = admission.permit;
+        let relay = async {
+            match upstream {
+                Ok(response) => {
+                    let mut stream = response.bytes_stream();
                 
-                while let Some(chunk) = stream.next().await {
-                    match chunk {
-                        Ok(bytes) => {
-                            // Parse SSE data from llama.cpp
-                            let text = String::from_utf8_lossy(&bytes);
-                            for line in text.lines() {
-                                if line.starts_with("data: ") {
-                                    let data = &line[6..];
-                                    if data == "[DONE]" {
-                                        yield Ok(Event::default().data("[DONE]"));
-                                    } else {
-                                        yield Ok(Event::default().data(data));
+                    while let Some(chunk) = stream.next().await {
+                        match chunk {
+                            Ok(bytes) => {
+                                // Parse SSE data from llama.cpp
+                                let text = String::from_utf8_lossy(&bytes);
+                                for line in text.lines() {
+                                    if let Some(data) = line.strip_prefix("data: ") {
+                                        let data = match convert {
+                                            Some(convert) => convert(data),
+                                            None => data.to_string(),
+                                        };
+                                        let data = data.as_str();
+                                        meter.observe(data);
+                                        if data.contains("\"usage\"") {
+                                            if let Some((prompt, completion)) = serde_json::from_str::<serde_json::Value>(data)
+                                                .ok()
+                                                .as_ref()
+                                                .and_then(usage_tokens)
+                                            {
+                                                guard.clients.record_tokens(addr.ip(), prompt, completion);
+                                            }
+                                        }
+                                        if tx.send_timeout(Event::default().data(data), write_timeout).await.is_err() {
+                                            eprintln!("[Proxy] Client stopped reading stream, aborting completion");
+                                            return;
+                                        }
                                     }
                                 }
                             }
-                        }
-                        Err(e) => {
-                            eprintln!("Stream error: {}", e);
-                            break;
+                            Err(e) => {
+                                eprintln!("Stream error: {}", e);
+                                break;
+                            }
                         }
                     }
                 }
+                Err(e) => {
+                    let error = json!({
+                        "error": {
+                            "message": e,
+                            "type": "api_error"
+                        }
+                    });
+                    let _ = tx.send_timeout(Event::default().data(error.to_string()), write_timeout).await;
+                }
             }
-            Err(e) => {
-                let error = json!({
-                    "error": {
-                        "message": e,
-                        "type": "api_error"
-                    }
-                });
-                yield Ok(Event::default().data(error.to_string()));
-            }
+        };
+        if crate::route_policy::within(admission.timeout, relay).await.is_none() {
+            eprintln!("[Proxy] Stream exceeded the request timeout for this model, aborting completion");
+            let error = json!({
+                "error": {
+                    "message": "Request exceeded the timeout set for this model",
+                    "type": "request_timeout"
+                }
+            });
+            let _ = tx.send_timeout(Event::default().data(error.to_string()), write_timeout).await;
+        }
+        meter.finish().await;
+    });
+
+    let stream = async_stream::stream! {
+        while let Some(event) = rx.recv().await {
+            yield Ok(event);
         }
     };
     
@@ -537,7 +1039,7 @@ async fn launch_model(
 
             Json(RemoteLaunchResponse {
                 success: true,
-                message: "Model launched successfully".to_string(),
+                message: crate::i18n::t("launch.success", &[]),
                 process_id: Some(launch_result.process_id),
                 server_host: Some(launch_result.server_host),
                 server_port: Some(launch_result.server_port),
@@ -546,7 +1048,7 @@ async fn launch_model(
         Err(e) => {
             Json(RemoteLaunchResponse {
                 success: false,
-                message: format!("Failed to launch model: {}", e),
+                message: crate::i18n::t("launch.failed", &[("error", &e.to_string())]),
                 process_id: None,
                 server_host: None,
                 server_port: None,
@@ -610,6 +1112,10 @@ async fn list_active_models(
                     model.status = ModelStatus::Ready;
                     true
                 }
+                ProcessStatus::Suspended => {
+                    model.status = ModelStatus::Suspended;
+                    true
+                }
                 ProcessStatus::Stopped | ProcessStatus::Failed => false,
             }
         } else {
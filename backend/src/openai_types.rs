@@ -48,6 +48,19 @@ pub struct ChatCompletionRequest {
     pub reasoning_format: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reasoning_budget: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_prompt: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n_keep: Option<i32>,
+    /// Sampling seed; the same seed and parameters give the same output on the same server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    /// Arandu sampling profile id or name; resolved by the proxy, never sent upstream
+    #[serde(default, skip_serializing)]
+    pub profile: Option<String>,
+    /// Arandu chat log whose context settings apply; never sent upstream
+    #[serde(default, skip_serializing)]
+    pub chat_id: Option<String>,
     #[serde(default, flatten)]
     pub extra: HashMap<String, Value>,
 }
@@ -75,6 +88,27 @@ pub struct ChatCompletionChoice {
     pub finish_reason: String,
 }
 
+// ============== LEGACY COMPLETIONS ==============
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CompletionRequest {
+    #[serde(default)]
+    pub model: String,
+    /// A string, or an array of strings / token ids
+    pub prompt: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<bool>,
+    /// Number of most likely alternatives to return per token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo: Option<bool>,
+    #[serde(default, flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
 // ============== AUDIO (TTS/STT) ==============
 
 #[derive(Debug, Deserialize)]
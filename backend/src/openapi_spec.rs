@@ -0,0 +1,491 @@
+use serde_json::{json, Map, Value};
+
+// OpenAPI 3 description of the network server surface. Keep this in sync with
+// the routes registered in `openai_proxy::ProxyServer::start`; a test fails for any
+// route missing here. Schemas and paths are inserted one entry at a time, since one
+// `json!` literal for the whole document runs past the macro recursion limit. Every
+// operation gets the guard's own responses (`proxy_guard::guard_request`), and all but
+// the open paths need a bearer API key once keys are configured.
+
+const OPENAPI_VERSION: &str = "3.0.3";
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn json_body(schema: Value) -> Value {
+    json!({
+        "required": true,
+        "content": { "application/json": { "schema": schema } }
+    })
+}
+
+fn json_response(description: &str, schema: Value) -> Value {
+    json!({
+        "description": description,
+        "content": { "application/json": { "schema": schema } }
+    })
+}
+
+fn error_response(description: &str) -> Value {
+    json_response(description, schema_ref("ErrorResponse"))
+}
+
+fn number_param(description: &str) -> Value {
+    json!({ "type": "number", "description": description })
+}
+
+fn integer_param(description: &str) -> Value {
+    json!({ "type": "integer", "description": description })
+}
+
+fn chat_completion_request_schema() -> Value {
+    let mut properties = Map::new();
+    properties.insert("model".into(), json!({ "type": "string", "description": "Model id; routed to the running model of that name, else the default server" }));
+    properties.insert("messages".into(), json!({ "type": "array", "items": schema_ref("ChatMessage") }));
+    properties.insert("stream".into(), json!({ "type": "boolean", "default": false }));
+    properties.insert("stream_options".into(), json!({ "type": "object" }));
+    properties.insert("max_tokens".into(), integer_param("Maximum tokens to generate"));
+    properties.insert("temperature".into(), number_param("Sampling temperature"));
+    properties.insert("top_p".into(), number_param("Nucleus sampling probability"));
+    properties.insert("top_k".into(), number_param("Top-k sampling"));
+    properties.insert("min_p".into(), number_param("Min-p sampling"));
+    properties.insert("repeat_penalty".into(), number_param("llama.cpp repeat penalty"));
+    properties.insert("repeat_last_n".into(), integer_param("Tokens considered for repeat penalty"));
+    properties.insert("presence_penalty".into(), number_param("Presence penalty"));
+    properties.insert("frequency_penalty".into(), number_param("Frequency penalty"));
+    properties.insert("stop".into(), json!({ "type": "array", "items": { "type": "string" } }));
+    properties.insert("xtc_probability".into(), number_param("XTC sampler probability"));
+    properties.insert("xtc_threshold".into(), number_param("XTC sampler threshold"));
+    properties.insert("dry_multiplier".into(), number_param("DRY sampler multiplier"));
+    properties.insert("dry_base".into(), number_param("DRY sampler base"));
+    properties.insert("dry_allowed_length".into(), integer_param("DRY sampler allowed length"));
+    properties.insert("reasoning_format".into(), json!({ "type": "string" }));
+    properties.insert("reasoning_budget".into(), integer_param("Reasoning token budget"));
+    properties.insert("cache_prompt".into(), json!({ "type": "boolean", "description": "Reuse the KV cache of the shared prompt prefix" }));
+    properties.insert("n_keep".into(), integer_param("Prompt tokens kept when the context shifts (-1 = all)"));
+    properties.insert("seed".into(), integer_param("Sampling seed; unset uses the model's default seed, if any"));
+    properties.insert("chat_id".into(), json!({ "type": "string", "description": "Arandu chat log whose context settings apply to this request" }));
+    properties.insert("profile".into(), json!({ "type": "string", "description": "Arandu sampling profile (e.g. creative, precise, code); explicit sampler fields override it" }));
+
+    json!({
+        "type": "object",
+        "required": ["model", "messages"],
+        "properties": properties,
+        "additionalProperties": true,
+        "description": "Unknown fields are forwarded to llama-server unchanged"
+    })
+}
+
+fn components() -> Value {
+    let mut schemas = Map::new();
+    schemas.insert("ChatMessage".into(), json!({
+        "type": "object",
+        "required": ["role", "content"],
+        "properties": {
+            "role": { "type": "string", "enum": ["system", "user", "assistant", "tool"] },
+            "content": { "description": "String or array of content parts" }
+        }
+    }));
+    schemas.insert("ChatCompletionRequest".into(), chat_completion_request_schema());
+    schemas.insert("ChatCompletionResponse".into(), json!({
+        "type": "object",
+        "description": "Response as returned by llama-server",
+        "additionalProperties": true
+    }));
+    schemas.insert("CompletionRequest".into(), json!({
+        "type": "object",
+        "required": ["prompt"],
+        "properties": {
+            "model": { "type": "string" },
+            "prompt": { "description": "String, or an array of strings / token ids" },
+            "max_tokens": integer_param("Maximum tokens to generate"),
+            "stream": { "type": "boolean", "default": false },
+            "logprobs": integer_param("Most likely alternatives returned per token"),
+            "echo": { "type": "boolean" }
+        },
+        "additionalProperties": true,
+        "description": "Unknown fields are forwarded to llama-server unchanged"
+    }));
+    schemas.insert("CompletionResponse".into(), json!({
+        "type": "object",
+        "description": "`text_completion` object as returned by llama-server",
+        "additionalProperties": true
+    }));
+    schemas.insert("ModelInfo".into(), json!({
+        "type": "object",
+        "required": ["id", "object", "created", "owned_by"],
+        "properties": {
+            "id": { "type": "string" },
+            "object": { "type": "string" },
+            "created": { "type": "integer" },
+            "owned_by": { "type": "string" },
+            "size_gb": { "type": "number" },
+            "quantization": { "type": "string" },
+            "architecture": { "type": "string" },
+            "date": { "type": "integer" },
+            "path": { "type": "string" },
+            "has_custom_launch_config": { "type": "boolean" }
+        }
+    }));
+    schemas.insert("ModelsResponse".into(), json!({
+        "type": "object",
+        "properties": {
+            "object": { "type": "string" },
+            "data": { "type": "array", "items": schema_ref("ModelInfo") }
+        }
+    }));
+    schemas.insert("RemoteLaunchRequest".into(), json!({
+        "type": "object",
+        "required": ["model_path"],
+        "properties": {
+            "model_path": { "type": "string" },
+            "server_host": { "type": "string", "nullable": true },
+            "server_port": { "type": "integer", "nullable": true }
+        }
+    }));
+    schemas.insert("RemoteLaunchResponse".into(), json!({
+        "type": "object",
+        "properties": {
+            "success": { "type": "boolean" },
+            "message": { "type": "string" },
+            "process_id": { "type": "string", "nullable": true },
+            "server_host": { "type": "string", "nullable": true },
+            "server_port": { "type": "integer", "nullable": true }
+        }
+    }));
+    schemas.insert("RemoteStopRequest".into(), json!({
+        "type": "object",
+        "required": ["process_id"],
+        "properties": { "process_id": { "type": "string" } }
+    }));
+    schemas.insert("RemoteStopResponse".into(), json!({
+        "type": "object",
+        "properties": {
+            "success": { "type": "boolean" },
+            "message": { "type": "string" }
+        }
+    }));
+    schemas.insert("ActiveModel".into(), json!({
+        "type": "object",
+        "properties": {
+            "process_id": { "type": "string" },
+            "model_path": { "type": "string" },
+            "model_name": { "type": "string" },
+            "host": { "type": "string" },
+            "port": { "type": "integer" },
+            "server_host": { "type": "string" },
+            "server_port": { "type": "integer" },
+            "status": {},
+            "launched_at": { "type": "string", "format": "date-time" }
+        }
+    }));
+    schemas.insert("RemoteActiveModelsResponse".into(), json!({
+        "type": "object",
+        "properties": {
+            "success": { "type": "boolean" },
+            "models": { "type": "array", "items": schema_ref("ActiveModel") }
+        }
+    }));
+    schemas.insert("AudioTranscriptionRequest".into(), json!({
+        "type": "object",
+        "required": ["file"],
+        "properties": {
+            "file": { "type": "string", "description": "Audio to transcribe" },
+            "model": { "type": "string", "nullable": true },
+            "language": { "type": "string", "nullable": true }
+        }
+    }));
+    schemas.insert("AudioTranscriptionResponse".into(), json!({
+        "type": "object",
+        "properties": { "text": { "type": "string" } }
+    }));
+    schemas.insert("AudioSpeechRequest".into(), json!({
+        "type": "object",
+        "required": ["model", "input", "voice"],
+        "properties": {
+            "model": { "type": "string" },
+            "input": { "type": "string" },
+            "voice": { "type": "string" }
+        }
+    }));
+    schemas.insert("ImageGenerationRequest".into(), json!({
+        "type": "object",
+        "required": ["prompt"],
+        "properties": {
+            "prompt": { "type": "string" },
+            "n": { "type": "integer", "nullable": true },
+            "size": { "type": "string", "nullable": true }
+        }
+    }));
+    schemas.insert("ErrorResponse".into(), json!({
+        "type": "object",
+        "properties": {
+            "error": {
+                "type": "object",
+                "properties": {
+                    "message": { "type": "string" },
+                    "type": { "type": "string" },
+                    "code": { "type": "string", "nullable": true }
+                }
+            }
+        }
+    }));
+
+    json!({
+        "schemas": schemas,
+        "securitySchemes": {
+            "bearerAuth": {
+                "type": "http",
+                "scheme": "bearer",
+                "description": "A network server API key (`sk-arandu-...`). Required once the host configures keys, and always for clients reaching it through a tunnel or router port mapping."
+            }
+        }
+    })
+}
+
+/// Add the responses every request may get from the proxy guard, and mark the paths
+/// that need no API key
+fn add_guard_responses(paths: &mut Map<String, Value>) {
+    for (path, item) in paths.iter_mut() {
+        let open = crate::proxy_guard::OPEN_PATHS.contains(&path.as_str());
+        for operation in item.as_object_mut().into_iter().flat_map(|item| item.values_mut()) {
+            if open {
+                operation["security"] = json!([]);
+            }
+            let Some(responses) = operation["responses"].as_object_mut() else { continue };
+            let mut guard = vec![
+                ("403", "Client address not in the allowlist or revoked by the host"),
+                ("429", "Rate limit of the client or its API key exceeded"),
+            ];
+            if !open {
+                guard.push(("401", "API key missing or invalid"));
+            }
+            for (status, description) in guard {
+                responses.entry(status).or_insert_with(|| error_response(description));
+            }
+        }
+    }
+}
+
+fn paths() -> Value {
+    let mut paths = Map::new();
+    paths.insert("/health".into(), json!({
+        "get": {
+            "summary": "Proxy liveness check",
+            "tags": ["system"],
+            "responses": {
+                "200": json_response("Proxy is running", json!({
+                    "type": "object",
+                    "properties": { "status": { "type": "string" } }
+                }))
+            }
+        }
+    }));
+    paths.insert("/openapi.json".into(), json!({
+        "get": {
+            "summary": "This document",
+            "tags": ["system"],
+            "responses": { "200": json_response("OpenAPI document", json!({ "type": "object" })) }
+        }
+    }));
+    paths.insert("/v1/models".into(), json!({
+        "get": {
+            "summary": "List the running models",
+            "tags": ["openai"],
+            "responses": { "200": json_response("Loaded models", schema_ref("ModelsResponse")) }
+        }
+    }));
+    paths.insert("/v1/models/arandu".into(), json!({
+        "get": {
+            "summary": "List every model in the host's library",
+            "tags": ["arandu"],
+            "responses": { "200": json_response("Library models", schema_ref("ModelsResponse")) }
+        }
+    }));
+    paths.insert("/v1/chat/completions".into(), json!({
+        "post": {
+            "summary": "Create a chat completion",
+            "tags": ["openai"],
+            "requestBody": json_body(schema_ref("ChatCompletionRequest")),
+            "responses": {
+                "200": {
+                    "description": "Completion, or an SSE stream when `stream` is true",
+                    "content": {
+                        "application/json": { "schema": schema_ref("ChatCompletionResponse") },
+                        "text/event-stream": { "schema": { "type": "string" } }
+                    }
+                },
+                "403": error_response("Client address not in the allowlist or revoked by the host"),
+                "408": error_response("Request body was not received within the read timeout"),
+                "413": error_response("Request body exceeds the configured size limit"),
+                "429": error_response("Per-client rate limit exceeded"),
+                "500": error_response("Upstream llama-server error"),
+                "503": error_response("No model is loaded")
+            }
+        }
+    }));
+    paths.insert("/v1/completions".into(), json!({
+        "post": {
+            "summary": "Create a legacy text completion",
+            "description": "Passed through to llama-server; servers without the endpoint answer through chat completions",
+            "tags": ["openai"],
+            "requestBody": json_body(schema_ref("CompletionRequest")),
+            "responses": {
+                "200": {
+                    "description": "Completion, or an SSE stream when `stream` is true",
+                    "content": {
+                        "application/json": { "schema": schema_ref("CompletionResponse") },
+                        "text/event-stream": { "schema": { "type": "string" } }
+                    }
+                },
+                "400": error_response("Prompt cannot be translated to a chat request"),
+                "429": error_response("Rate limit or the model's concurrency limit exceeded"),
+                "500": error_response("Upstream llama-server error"),
+                "504": error_response("Request exceeded the model's timeout")
+            }
+        }
+    }));
+    paths.insert("/v1/audio/transcriptions".into(), json!({
+        "post": {
+            "summary": "Transcribe audio",
+            "description": "Not backed by a model yet; answers with placeholder text",
+            "tags": ["openai"],
+            "requestBody": json_body(schema_ref("AudioTranscriptionRequest")),
+            "responses": { "200": json_response("Transcription", schema_ref("AudioTranscriptionResponse")) }
+        }
+    }));
+    for (path, summary, request) in [
+        ("/v1/audio/speech", "Generate speech from text", "AudioSpeechRequest"),
+        ("/v1/images/generations", "Generate images from a prompt", "ImageGenerationRequest"),
+    ] {
+        paths.insert(path.into(), json!({
+            "post": {
+                "summary": summary,
+                "description": "Reserved for OpenAI compatibility; not implemented yet",
+                "tags": ["openai"],
+                "requestBody": json_body(schema_ref(request)),
+                "responses": { "501": error_response("Not implemented") }
+            }
+        }));
+    }
+    for (path, summary) in [
+        ("/v1/embeddings", "Create embeddings"),
+        ("/v1/rerank", "Rerank documents against a query"),
+        ("/infill", "Fill in code between a prefix and a suffix"),
+    ] {
+        paths.insert(path.into(), json!({
+            "post": {
+                "summary": summary,
+                "description": "Passed through unchanged to the llama-server the `model` field routes to; the server needs the matching flag (`--embedding` or `--reranking`)",
+                "tags": ["openai"],
+                "requestBody": json_body(json!({ "type": "object" })),
+                "responses": {
+                    "200": json_response("llama-server response", json!({ "type": "object" })),
+                    "500": error_response("Upstream llama-server unreachable"),
+                    "503": error_response("The routed model could not be loaded")
+                }
+            }
+        }));
+    }
+    paths.insert("/api/models/launch".into(), json!({
+        "post": {
+            "summary": "Launch a library model on the host",
+            "tags": ["admin"],
+            "requestBody": json_body(schema_ref("RemoteLaunchRequest")),
+            "responses": { "200": json_response("Launch result", schema_ref("RemoteLaunchResponse")) }
+        }
+    }));
+    paths.insert("/api/models/stop".into(), json!({
+        "post": {
+            "summary": "Stop a running model process",
+            "tags": ["admin"],
+            "requestBody": json_body(schema_ref("RemoteStopRequest")),
+            "responses": { "200": json_response("Stop result", schema_ref("RemoteStopResponse")) }
+        }
+    }));
+    paths.insert("/api/models/active".into(), json!({
+        "get": {
+            "summary": "List running model processes",
+            "tags": ["admin"],
+            "responses": { "200": json_response("Active models", schema_ref("RemoteActiveModelsResponse")) }
+        }
+    }));
+
+    add_guard_responses(&mut paths);
+    Value::Object(paths)
+}
+
+/// Build the OpenAPI document for the proxy. `server_url` is advertised in `servers`
+/// when provided so generated clients point at the right host.
+pub fn build_openapi_document(server_url: Option<&str>) -> Value {
+    let mut document = json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "Arandu Network API",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": "OpenAI-compatible proxy in front of llama-server plus Arandu model management endpoints"
+        },
+        "tags": [
+            { "name": "openai", "description": "OpenAI-compatible endpoints" },
+            { "name": "arandu", "description": "Arandu extensions" },
+            { "name": "admin", "description": "Model process management" },
+            { "name": "system", "description": "Health and metadata" }
+        ],
+        "paths": paths(),
+        "components": components(),
+        "security": [{ "bearerAuth": [] }]
+    });
+
+    if let Some(url) = server_url.map(str::trim).filter(|url| !url.is_empty()) {
+        document["servers"] = json!([{ "url": url }]);
+    }
+
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_references_only_defined_schemas() {
+        let document = build_openapi_document(Some("http://127.0.0.1:8081"));
+        let serialized = document.to_string();
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+
+        for (idx, _) in serialized.match_indices("#/components/schemas/") {
+            let rest = &serialized[idx + "#/components/schemas/".len()..];
+            let name: String = rest.chars().take_while(|c| c.is_ascii_alphanumeric()).collect();
+            assert!(schemas.contains_key(&name), "missing schema {}", name);
+        }
+
+        assert_eq!(document["servers"][0]["url"], "http://127.0.0.1:8081");
+        assert!(document["paths"]["/v1/chat/completions"]["post"].is_object());
+    }
+
+    #[test]
+    fn document_lists_every_registered_route() {
+        let document = build_openapi_document(None);
+        let source = include_str!("openai_proxy.rs");
+        let routes: Vec<&str> = source
+            .match_indices(".route(\"")
+            .filter_map(|(idx, pattern)| source[idx + pattern.len()..].split('"').next())
+            .collect();
+        assert!(routes.len() >= 15, "found only {:?}", routes);
+        for route in routes {
+            assert!(document["paths"][route].is_object(), "{} is not in the OpenAPI document", route);
+        }
+
+        assert_eq!(document["components"]["securitySchemes"]["bearerAuth"]["scheme"], "bearer");
+        assert_eq!(document["security"][0]["bearerAuth"], json!([]));
+        assert_eq!(document["paths"]["/health"]["get"]["security"], json!([]));
+        assert_eq!(document["paths"]["/openapi.json"]["get"]["security"], json!([]));
+        assert!(document["paths"]["/health"]["get"]["responses"]["401"].is_null());
+        let chat = &document["paths"]["/v1/chat/completions"]["post"];
+        assert!(chat["security"].is_null());
+        for status in ["401", "403", "429"] {
+            assert!(chat["responses"][status].is_object(), "chat completions lacks {}", status);
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use crate::cli_args::{self, flag_value, set_flag};
+use crate::http_pool::{self, RequestClass};
+use crate::models::LaunchResult;
+use crate::openai_types::ChatCompletionRequest;
+use crate::AppState;
+
+// Per-process generation and load parameters. Generation parameters take effect on
+// the next request (the proxy fills them into requests that leave them unset);
+// load parameters such as context length need a relaunch, so they are kept as
+// pending changes until the model starts again.
+
+const CONTEXT_FLAGS: &[&str] = &["-c", "--ctx-size"];
+const GPU_LAYER_FLAGS: &[&str] = &["-ngl", "--n-gpu-layers", "--gpu-layers"];
+
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long the proxy holds a request while the model behind it restarts
+const RESTART_HOLD: Duration = Duration::from_secs(180);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct GenerationParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_p: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat_penalty: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<i32>,
+    /// Fixed seed, so repeated prompts give repeated outputs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+}
+
+impl GenerationParams {
+    /// Fill fields the client left unset; explicit request values always win
+    pub fn apply_defaults(&self, request: &mut ChatCompletionRequest) {
+        request.temperature = request.temperature.or(self.temperature);
+        request.top_p = request.top_p.or(self.top_p);
+        request.top_k = request.top_k.or(self.top_k);
+        request.min_p = request.min_p.or(self.min_p);
+        request.repeat_penalty = request.repeat_penalty.or(self.repeat_penalty);
+        request.presence_penalty = request.presence_penalty.or(self.presence_penalty);
+        request.frequency_penalty = request.frequency_penalty.or(self.frequency_penalty);
+        request.max_tokens = request.max_tokens.or(self.max_tokens);
+        request.seed = request.seed.or(self.seed);
+    }
+}
+
+/// Parameters fixed when llama-server starts
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LoadParams {
+    pub context_length: Option<u32>,
+    pub gpu_layers: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingChange {
+    pub name: String,
+    pub current: Option<String>,
+    pub pending: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParameterController {
+    pub process_id: String,
+    pub model_path: String,
+    pub generation: GenerationParams,
+    /// What the running process was launched with
+    pub load: LoadParams,
+    /// Applied the next time this model launches
+    pub pending: LoadParams,
+    /// Set by a restart so the relaunch binds the port the old process had
+    pub relaunch_port: Option<u16>,
+}
+
+impl ParameterController {
+    pub fn new(process_id: String, model_path: String, launch_args: &str) -> Self {
+        let args = cli_args::split(launch_args);
+        let load = LoadParams {
+            context_length: flag_value(&args, CONTEXT_FLAGS).and_then(|v| v.parse().ok()),
+            gpu_layers: flag_value(&args, GPU_LAYER_FLAGS).and_then(|v| v.parse().ok()),
+        };
+        Self {
+            process_id,
+            model_path,
+            generation: GenerationParams::default(),
+            load,
+            pending: LoadParams::default(),
+            relaunch_port: None,
+        }
+    }
+
+    pub fn set_generation_params(&mut self, params: GenerationParams) {
+        self.generation = params;
+    }
+
+    /// Queue a context length for the next launch; setting the current value clears it
+    pub fn set_context_length(&mut self, context_length: u32) {
+        self.pending.context_length = Some(context_length).filter(|v| Some(*v) != self.load.context_length);
+    }
+
+    pub fn set_gpu_layers(&mut self, gpu_layers: i32) {
+        self.pending.gpu_layers = Some(gpu_layers).filter(|v| Some(*v) != self.load.gpu_layers);
+    }
+
+    pub fn has_pending(&self) -> bool {
+        self.pending != LoadParams::default()
+    }
+
+    pub fn pending_changes(&self) -> Vec<PendingChange> {
+        let mut changes = Vec::new();
+        if let Some(ctx) = self.pending.context_length {
+            changes.push(PendingChange {
+                name: "context_length".to_string(),
+                current: self.load.context_length.map(|v| v.to_string()),
+                pending: ctx.to_string(),
+            });
+        }
+        if let Some(ngl) = self.pending.gpu_layers {
+            changes.push(PendingChange {
+                name: "gpu_layers".to_string(),
+                current: self.load.gpu_layers.map(|v| v.to_string()),
+                pending: ngl.to_string(),
+            });
+        }
+        changes
+    }
+
+    /// `custom_args` with the pending load values merged in
+    pub fn get_model_load_args(&self, custom_args: &str) -> String {
+        let mut args = cli_args::split(custom_args);
+        if let Some(ctx) = self.pending.context_length {
+            set_flag(&mut args, CONTEXT_FLAGS, &ctx.to_string());
+        }
+        if let Some(ngl) = self.pending.gpu_layers {
+            set_flag(&mut args, GPU_LAYER_FLAGS, &ngl.to_string());
+        }
+        if let Some(port) = self.relaunch_port {
+            set_flag(&mut args, &["--port"], &port.to_string());
+        }
+        cli_args::join(&args)
+    }
+}
+
+/// Merge pending load changes queued for `model_path` into `custom_args` and clear
+/// them. Controllers of processes that have exited are dropped along the way.
+pub async fn take_pending_load_args(state: &AppState, model_path: &str, custom_args: &str) -> Option<String> {
+    let live: Vec<String> = state.running_processes.lock().await.keys().cloned().collect();
+    let mut controllers = state.parameter_controllers.lock().await;
+
+    let merged = controllers
+        .values()
+        .filter(|c| c.model_path == model_path && (c.has_pending() || c.relaunch_port.is_some()))
+        .max_by_key(|c| !live.contains(&c.process_id))
+        .map(|c| c.get_model_load_args(custom_args));
+
+    for controller in controllers.values_mut().filter(|c| c.model_path == model_path) {
+        controller.pending = LoadParams::default();
+        controller.relaunch_port = None;
+    }
+    controllers.retain(|id, _| live.contains(id));
+    merged
+}
+
+fn url_port(server_url: &str) -> Option<u16> {
+    url::Url::parse(server_url).ok().and_then(|u| u.port_or_known_default())
+}
+
+/// Fill the generation defaults of the process serving `server_url` into `request`
+pub async fn apply_generation_defaults(state: &AppState, server_url: &str, request: &mut ChatCompletionRequest) {
+    let Some(port) = url_port(server_url) else { return };
+    let process_id = state
+        .running_processes
+        .lock()
+        .await
+        .values()
+        .find(|p| p.port == port && !p.model_path.starts_with("tunnel:"))
+        .map(|p| p.id.clone());
+    let Some(process_id) = process_id else { return };
+    if let Some(controller) = state.parameter_controllers.lock().await.get(&process_id) {
+        controller.generation.apply_defaults(request);
+    }
+}
+
+pub async fn register(state: &AppState, process_id: &str, model_path: &str, launch_args: &str) {
+    let controller = ParameterController::new(process_id.to_string(), model_path.to_string(), launch_args);
+    state.parameter_controllers.lock().await.insert(process_id.to_string(), controller);
+}
+
+/// Hold a proxied request while the server behind `server_url` restarts.
+/// Returns false when the restart is still running after the hold period.
+pub async fn wait_while_restarting(state: &AppState, server_url: &str) -> bool {
+    let Some(port) = url_port(server_url) else { return true };
+    let deadline = tokio::time::Instant::now() + RESTART_HOLD;
+    while state.restarting_ports.lock().await.contains(&port) {
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    true
+}
+
+/// Whether `GET /slots` reports a slot still generating
+fn any_slot_busy(slots: &serde_json::Value) -> bool {
+    slots.as_array().is_some_and(|slots| {
+        slots.iter().any(|s| {
+            s.get("is_processing").and_then(|v| v.as_bool()).unwrap_or(false)
+                || s.get("state").and_then(|v| v.as_u64()).is_some_and(|state| state != 0)
+        })
+    })
+}
+
+/// Let in-flight generations finish before the server goes away
+async fn drain_slots(base_url: &str) {
+    let slots_url = format!("{}/slots", base_url);
+    let deadline = tokio::time::Instant::now() + DRAIN_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        let slots = match http_pool::get(&slots_url, RequestClass::Health).send().await {
+            Ok(response) if response.status().is_success() => response.json::<serde_json::Value>().await.unwrap_or_default(),
+            _ => return,
+        };
+        if !any_slot_busy(&slots) {
+            return;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    println!("[Params] Requests still running after {}s, restarting anyway", DRAIN_TIMEOUT.as_secs());
+}
+
+async fn wait_for_port_release(port: u16) {
+    let deadline = tokio::time::Instant::now() + PORT_RELEASE_TIMEOUT;
+    while !crate::process::is_port_available(port) && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestartResult {
+    pub old_process_id: String,
+    pub process_id: String,
+    pub server_host: String,
+    pub server_port: u16,
+    pub instance: u32,
+    pub applied: Vec<PendingChange>,
+}
+
+async fn stop_and_relaunch(process: &crate::models::ProcessInfo, state: &AppState) -> Result<LaunchResult, String> {
+    drain_slots(&crate::suspend::local_base_url(&process.host, process.port)).await;
+    crate::process::terminate_process(process.id.clone(), state)
+        .await
+        .map_err(|e| format!("Failed to stop process: {}", e))?;
+    wait_for_port_release(process.port).await;
+
+    let launched = crate::launch_with_preset(process.model_path.clone(), process.preset_id.clone(), state).await?;
+    if launched.server_port != process.port {
+        println!("[Params] Port {} still busy, relaunched on {}", process.port, launched.server_port);
+    }
+    crate::suspend::wait_until_healthy(&crate::suspend::local_base_url(&launched.server_host, launched.server_port)).await?;
+    Ok(launched)
+}
+
+/// Stop a process and relaunch it on the same port with its pending load changes.
+/// Proxy requests for that port are held until the new server is healthy.
+pub async fn restart_with_pending_changes(process_id: &str, state: &AppState) -> Result<RestartResult, String> {
+    let process = state
+        .running_processes
+        .lock()
+        .await
+        .get(process_id)
+        .cloned()
+        .ok_or_else(|| "Process not found".to_string())?;
+    if process.model_path.starts_with("tunnel:") {
+        return Err("Tunnels cannot be restarted".into());
+    }
+
+    let applied = {
+        let mut controllers = state.parameter_controllers.lock().await;
+        let controller = controllers
+            .get_mut(process_id)
+            .ok_or_else(|| format!("No parameter controller for process {}", process_id))?;
+        if !controller.has_pending() {
+            return Err("No pending parameter changes for this process".into());
+        }
+        controller.relaunch_port = Some(process.port);
+        controller.pending_changes()
+    };
+
+    println!("[Params] Restarting {} on port {} to apply {} change(s)", process.model_name, process.port, applied.len());
+    state.restarting_ports.lock().await.insert(process.port);
+    let result = stop_and_relaunch(&process, state).await;
+    state.restarting_ports.lock().await.remove(&process.port);
+
+    let launched = match result {
+        Ok(launched) => launched,
+        Err(e) => {
+            if let Some(controller) = state.parameter_controllers.lock().await.get_mut(process_id) {
+                controller.relaunch_port = None;
+            }
+            eprintln!("[Params] Restart of {} failed: {}", process.model_name, e);
+            return Err(e);
+        }
+    };
+
+    Ok(RestartResult {
+        old_process_id: process_id.to_string(),
+        process_id: launched.process_id,
+        server_host: launched.server_host,
+        server_port: launched.server_port,
+        instance: launched.instance,
+        applied,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pending_load_changes_are_merged_into_args() {
+        let mut controller = ParameterController::new("p1".into(), "/m.gguf".into(), "-c 4096 -ngl 99 --mmproj \"C:/My Models/mm.gguf\"");
+        assert_eq!(controller.load, LoadParams { context_length: Some(4096), gpu_layers: Some(99) });
+
+        controller.set_context_length(4096);
+        assert!(!controller.has_pending());
+
+        controller.set_context_length(16384);
+        controller.set_gpu_layers(20);
+        assert_eq!(controller.pending_changes().len(), 2);
+        assert_eq!(
+            controller.get_model_load_args("-c 4096 -ngl 99 --mmproj \"C:/My Models/mm.gguf\""),
+            "-c 16384 -ngl 20 --mmproj \"C:/My Models/mm.gguf\""
+        );
+        assert_eq!(controller.get_model_load_args("--ctx-size=2048"), "--ctx-size=16384 -ngl 20");
+
+        controller.relaunch_port = Some(8081);
+        assert_eq!(controller.get_model_load_args("--port 8080"), "--port 8081 -c 16384 -ngl 20");
+    }
+
+    #[test]
+    fn busy_slots_block_the_drain() {
+        assert!(any_slot_busy(&serde_json::json!([{"id": 0, "is_processing": false}, {"id": 1, "is_processing": true}])));
+        assert!(any_slot_busy(&serde_json::json!([{"id": 0, "state": 1}])));
+        assert!(!any_slot_busy(&serde_json::json!([{"id": 0, "is_processing": false, "state": 0}])));
+        assert!(!any_slot_busy(&serde_json::json!({"error": "slots disabled"})));
+    }
+}
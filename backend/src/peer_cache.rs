@@ -218,6 +218,7 @@ impl PeerModelCache {
 
     /// Update or insert peer data with new models
     /// Returns model delta details for UI and logging
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_peer_models(
         &self,
         instance_id: String,
@@ -573,12 +574,13 @@ mod tests {
             quantization: Some("Q4_K_M".to_string()),
             architecture: Some("llama".to_string()),
             date: Some(1234567890),
-            path: Some("/path/to/model.gguf".to_string()),
+            path: Some(format!("/path/to/{}.gguf", id)),
+            has_custom_launch_config: None,
         }
     }
 
     fn create_test_temp_dir() -> std::path::PathBuf {
-        let temp_dir = std::env::temp_dir().join(format!("arandu_test_{}", std::process::id()));
+        let temp_dir = std::env::temp_dir().join(format!("arandu_test_{}", uuid::Uuid::new_v4()));
         let _ = std::fs::create_dir_all(&temp_dir);
         temp_dir
     }
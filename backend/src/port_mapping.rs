@@ -0,0 +1,459 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::sync::Mutex;
+
+// Router port forwarding for the network server. UPnP IGD is tried first and
+// NAT-PMP is used as a fallback. Only one mapping is tracked at a time. Mappings
+// always have a finite lease that a background task renews while Arandu runs, so a
+// crash or kill leaves the port open for at most one lease; a clean exit deletes it.
+
+static ACTIVE_MAPPING: LazyLock<Mutex<Option<PortMapping>>> = LazyLock::new(|| Mutex::new(None));
+/// Mirrors `ACTIVE_MAPPING` for the proxy guard, which cannot await
+static MAPPED: AtomicBool = AtomicBool::new(false);
+static RENEWAL: LazyLock<std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>> = LazyLock::new(|| std::sync::Mutex::new(None));
+
+const SSDP_ADDR: &str = "239.255.255.250:1900";
+const SSDP_TIMEOUT: Duration = Duration::from_secs(3);
+const NAT_PMP_PORT: u16 = 5351;
+const NAT_PMP_LIFETIME_SECS: u32 = 7200;
+const UPNP_LEASE_SECS: u32 = 3600;
+const MAPPING_DESCRIPTION: &str = "Arandu network server";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingProtocol {
+    Upnp,
+    NatPmp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortMapping {
+    pub protocol: MappingProtocol,
+    pub gateway: String,
+    pub internal_ip: String,
+    pub internal_port: u16,
+    pub external_port: u16,
+    pub external_ip: Option<String>,
+    /// Lease in seconds, renewed at half time while Arandu runs
+    pub lease_secs: u32,
+    #[serde(default)]
+    pub control_url: Option<String>,
+    #[serde(default)]
+    pub service_type: Option<String>,
+}
+
+impl PortMapping {
+    pub fn external_address(&self) -> Option<String> {
+        self.external_ip
+            .as_ref()
+            .map(|ip| format!("{}:{}", ip, self.external_port))
+    }
+}
+
+/// Warnings included with every successful mapping so the UI cannot hide them
+pub fn security_warnings(mapping: &PortMapping) -> Vec<String> {
+    let mut warnings = vec![
+        "Your network server is now reachable from the internet. Anyone who finds this address can use your GPU and models.".to_string(),
+        "Clients from outside your local network must send one of the network server's API keys; the allowlist only applies to LAN clients.".to_string(),
+        "Remove the mapping (or deactivate the network server) as soon as you no longer need remote access.".to_string(),
+    ];
+    warnings.push(format!(
+        "The router drops the mapping {} minutes after Arandu stops renewing it.",
+        mapping.lease_secs / 60
+    ));
+    warnings
+}
+
+fn local_ip_towards(target: IpAddr) -> Option<Ipv4Addr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect(SocketAddr::new(target, 9)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(ip) if !ip.is_loopback() => Some(ip),
+        _ => None,
+    }
+}
+
+// ============== UPnP IGD ==============
+
+fn parse_ssdp_location(response: &str) -> Option<String> {
+    response.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        if name.trim().eq_ignore_ascii_case("location") {
+            Some(value.trim().to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// Find the WAN connection service (type, absolute control URL) in an IGD description
+fn parse_wan_service(description: &str, location: &str) -> Option<(String, String)> {
+    let service_re = Regex::new(r"(?s)<service>(.*?)</service>").ok()?;
+    let type_re = Regex::new(r"(?s)<serviceType>\s*(.*?)\s*</serviceType>").ok()?;
+    let control_re = Regex::new(r"(?s)<controlURL>\s*(.*?)\s*</controlURL>").ok()?;
+    let base = url::Url::parse(location).ok()?;
+
+    let found = service_re.captures_iter(description).find_map(|captures| {
+        let block = captures.get(1)?.as_str();
+        let service_type = type_re.captures(block)?.get(1)?.as_str().to_string();
+        if !service_type.contains("WANIPConnection") && !service_type.contains("WANPPPConnection") {
+            return None;
+        }
+        let control = control_re.captures(block)?.get(1)?.as_str();
+        let control_url = base.join(control).ok()?.to_string();
+        Some((service_type, control_url))
+    });
+    found
+}
+
+fn soap_envelope(service_type: &str, action: &str, args: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?><s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body><u:{action} xmlns:u=\"{service}\">{args}</u:{action}></s:Body></s:Envelope>",
+        action = action,
+        service = service_type,
+        args = args
+    )
+}
+
+async fn soap_call(control_url: &str, service_type: &str, action: &str, args: &str) -> Result<String, String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .post(control_url)
+        .header("Content-Type", "text/xml; charset=\"utf-8\"")
+        .header("SOAPAction", format!("\"{}#{}\"", service_type, action))
+        .body(soap_envelope(service_type, action, args))
+        .send()
+        .await
+        .map_err(|e| format!("UPnP {} request failed: {}", action, e))?;
+
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        let detail = Regex::new(r"(?s)<errorDescription>(.*?)</errorDescription>")
+            .ok()
+            .and_then(|re| re.captures(&body).and_then(|c| c.get(1)).map(|m| m.as_str().to_string()))
+            .unwrap_or_else(|| status.to_string());
+        return Err(format!("UPnP {} rejected by router: {}", action, detail));
+    }
+    Ok(body)
+}
+
+async fn discover_igd() -> Result<(String, String, String), String> {
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| format!("Failed to bind SSDP socket: {}", e))?;
+    let request = "M-SEARCH * HTTP/1.1\r\nHOST: 239.255.255.250:1900\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\nST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\r\n";
+    socket
+        .send_to(request.as_bytes(), SSDP_ADDR)
+        .await
+        .map_err(|e| format!("Failed to send SSDP discovery: {}", e))?;
+
+    let mut buf = [0u8; 2048];
+    let deadline = tokio::time::Instant::now() + SSDP_TIMEOUT;
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        let (len, _) = match tokio::time::timeout(remaining, socket.recv_from(&mut buf)).await {
+            Ok(Ok(received)) => received,
+            Ok(Err(e)) => return Err(format!("SSDP receive failed: {}", e)),
+            Err(_) => return Err("No UPnP gateway answered".to_string()),
+        };
+
+        let Some(location) = parse_ssdp_location(&String::from_utf8_lossy(&buf[..len])) else {
+            continue;
+        };
+        let description = match reqwest::Client::new()
+            .get(&location)
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+        {
+            Ok(resp) => resp.text().await.unwrap_or_default(),
+            Err(_) => continue,
+        };
+        if let Some((service_type, control_url)) = parse_wan_service(&description, &location) {
+            return Ok((location, service_type, control_url));
+        }
+    }
+}
+
+/// Add (or, for the same client, refresh) the mapping with a `UPNP_LEASE_SECS` lease
+async fn add_upnp_mapping(control_url: &str, service_type: &str, port: u16, internal_ip: &str) -> Result<(), String> {
+    let args = format!(
+        "<NewRemoteHost></NewRemoteHost><NewExternalPort>{port}</NewExternalPort><NewProtocol>TCP</NewProtocol>\
+<NewInternalPort>{port}</NewInternalPort><NewInternalClient>{ip}</NewInternalClient><NewEnabled>1</NewEnabled>\
+<NewPortMappingDescription>{desc}</NewPortMappingDescription><NewLeaseDuration>{lease}</NewLeaseDuration>",
+        port = port,
+        ip = internal_ip,
+        desc = MAPPING_DESCRIPTION,
+        lease = UPNP_LEASE_SECS
+    );
+    soap_call(control_url, service_type, "AddPortMapping", &args).await.map(|_| ())
+}
+
+async fn map_with_upnp(internal_port: u16) -> Result<PortMapping, String> {
+    let (location, service_type, control_url) = discover_igd().await?;
+    let gateway_host = url::Url::parse(&location)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let gateway_ip: IpAddr = gateway_host
+        .parse()
+        .map_err(|_| format!("Unexpected gateway host: {}", gateway_host))?;
+    let internal_ip = local_ip_towards(gateway_ip).ok_or("Could not determine local LAN address")?;
+    add_upnp_mapping(&control_url, &service_type, internal_port, &internal_ip.to_string()).await?;
+
+    let external_ip = soap_call(&control_url, &service_type, "GetExternalIPAddress", "")
+        .await
+        .ok()
+        .and_then(|body| {
+            Regex::new(r"<NewExternalIPAddress>\s*(.*?)\s*</NewExternalIPAddress>")
+                .ok()?
+                .captures(&body)?
+                .get(1)
+                .map(|m| m.as_str().to_string())
+        })
+        .filter(|ip| !ip.is_empty());
+
+    Ok(PortMapping {
+        protocol: MappingProtocol::Upnp,
+        gateway: gateway_host,
+        internal_ip: internal_ip.to_string(),
+        internal_port,
+        external_port: internal_port,
+        external_ip,
+        lease_secs: UPNP_LEASE_SECS,
+        control_url: Some(control_url),
+        service_type: Some(service_type),
+    })
+}
+
+// ============== NAT-PMP ==============
+
+/// NAT-PMP has no discovery; assume the gateway is .1 on the local /24
+fn guess_gateway() -> Option<(Ipv4Addr, Ipv4Addr)> {
+    let local = local_ip_towards(IpAddr::V4(Ipv4Addr::new(8, 8, 8, 8)))?;
+    let [a, b, c, _] = local.octets();
+    Some((Ipv4Addr::new(a, b, c, 1), local))
+}
+
+async fn nat_pmp_request(gateway: Ipv4Addr, request: &[u8], expected_len: usize) -> Result<Vec<u8>, String> {
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| format!("Failed to bind NAT-PMP socket: {}", e))?;
+    let target = SocketAddr::new(IpAddr::V4(gateway), NAT_PMP_PORT);
+    let mut buf = [0u8; 16];
+
+    for attempt in 0..3u32 {
+        socket
+            .send_to(request, target)
+            .await
+            .map_err(|e| format!("Failed to send NAT-PMP request: {}", e))?;
+        let wait = Duration::from_millis(250 * 2u64.pow(attempt));
+        if let Ok(Ok((len, from))) = tokio::time::timeout(wait, socket.recv_from(&mut buf)).await {
+            if from.ip() == IpAddr::V4(gateway) && len >= expected_len {
+                let result_code = u16::from_be_bytes([buf[2], buf[3]]);
+                if result_code != 0 {
+                    return Err(format!("NAT-PMP request rejected (result code {})", result_code));
+                }
+                return Ok(buf[..len].to_vec());
+            }
+        }
+    }
+    Err(format!("No NAT-PMP response from {}", gateway))
+}
+
+fn nat_pmp_mapping_request(internal_port: u16, external_port: u16, lifetime: u32) -> Vec<u8> {
+    let mut request = vec![0u8, 2, 0, 0];
+    request.extend_from_slice(&internal_port.to_be_bytes());
+    request.extend_from_slice(&external_port.to_be_bytes());
+    request.extend_from_slice(&lifetime.to_be_bytes());
+    request
+}
+
+async fn map_with_nat_pmp(internal_port: u16) -> Result<PortMapping, String> {
+    let (gateway, internal_ip) = guess_gateway().ok_or("Could not determine local LAN address")?;
+
+    let response = nat_pmp_request(
+        gateway,
+        &nat_pmp_mapping_request(internal_port, internal_port, NAT_PMP_LIFETIME_SECS),
+        16,
+    )
+    .await?;
+    let external_port = u16::from_be_bytes([response[10], response[11]]);
+    let lease_secs = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+
+    let external_ip = nat_pmp_request(gateway, &[0, 0], 12)
+        .await
+        .ok()
+        .map(|r| Ipv4Addr::new(r[8], r[9], r[10], r[11]).to_string());
+
+    Ok(PortMapping {
+        protocol: MappingProtocol::NatPmp,
+        gateway: gateway.to_string(),
+        internal_ip: internal_ip.to_string(),
+        internal_port,
+        external_port,
+        external_ip,
+        lease_secs,
+        control_url: None,
+        service_type: None,
+    })
+}
+
+// ============== RENEWAL ==============
+
+/// Request `mapping` again so its lease starts over
+async fn renew(mapping: &PortMapping) -> Result<(), String> {
+    match mapping.protocol {
+        MappingProtocol::Upnp => {
+            let (Some(control_url), Some(service_type)) = (&mapping.control_url, &mapping.service_type) else {
+                return Err("UPnP mapping is missing its control URL".to_string());
+            };
+            add_upnp_mapping(control_url, service_type, mapping.internal_port, &mapping.internal_ip).await
+        }
+        MappingProtocol::NatPmp => {
+            let gateway: Ipv4Addr = mapping
+                .gateway
+                .parse()
+                .map_err(|_| format!("Invalid NAT-PMP gateway: {}", mapping.gateway))?;
+            let request = nat_pmp_mapping_request(mapping.internal_port, mapping.external_port, NAT_PMP_LIFETIME_SECS);
+            nat_pmp_request(gateway, &request, 16).await.map(|_| ())
+        }
+    }
+}
+
+/// Renew the active mapping at half its lease until it is removed
+fn start_renewal(lease_secs: u32) {
+    let interval = Duration::from_secs(u64::from(lease_secs.max(120) / 2));
+    let task = tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(interval).await;
+            let Some(mapping) = active_mapping().await else {
+                return;
+            };
+            match renew(&mapping).await {
+                Ok(()) => println!("[PortMapping] Renewed external port {}", mapping.external_port),
+                Err(e) => eprintln!("[PortMapping] Failed to renew external port {}: {}", mapping.external_port, e),
+            }
+        }
+    });
+    if let Some(previous) = RENEWAL.lock().unwrap_or_else(|p| p.into_inner()).replace(task) {
+        previous.abort();
+    }
+}
+
+fn stop_renewal() {
+    if let Some(task) = RENEWAL.lock().unwrap_or_else(|p| p.into_inner()).take() {
+        task.abort();
+    }
+}
+
+// ============== PUBLIC API ==============
+
+/// Map `internal_port` on the router, replacing any mapping created earlier
+pub async fn request_port_mapping(internal_port: u16) -> Result<PortMapping, String> {
+    remove_active_mapping().await?;
+
+    let mapping = match map_with_upnp(internal_port).await {
+        Ok(mapping) => mapping,
+        Err(upnp_error) => {
+            println!("[PortMapping] UPnP failed ({}), trying NAT-PMP", upnp_error);
+            map_with_nat_pmp(internal_port)
+                .await
+                .map_err(|pmp_error| format!("UPnP: {}; NAT-PMP: {}", upnp_error, pmp_error))?
+        }
+    };
+
+    println!(
+        "[PortMapping] Mapped external port {} -> {}:{} via {:?}",
+        mapping.external_port, mapping.internal_ip, mapping.internal_port, mapping.protocol
+    );
+    *ACTIVE_MAPPING.lock().await = Some(mapping.clone());
+    MAPPED.store(true, Ordering::SeqCst);
+    start_renewal(mapping.lease_secs);
+    Ok(mapping)
+}
+
+/// Remove the mapping created by this app, if any. Returns the removed mapping.
+pub async fn remove_active_mapping() -> Result<Option<PortMapping>, String> {
+    let mut active = ACTIVE_MAPPING.lock().await;
+    let Some(mapping) = active.clone() else {
+        return Ok(None);
+    };
+    stop_renewal();
+
+    match mapping.protocol {
+        MappingProtocol::Upnp => {
+            let (Some(control_url), Some(service_type)) = (&mapping.control_url, &mapping.service_type) else {
+                return Err("UPnP mapping is missing its control URL".to_string());
+            };
+            let args = format!(
+                "<NewRemoteHost></NewRemoteHost><NewExternalPort>{}</NewExternalPort><NewProtocol>TCP</NewProtocol>",
+                mapping.external_port
+            );
+            soap_call(control_url, service_type, "DeletePortMapping", &args).await?;
+        }
+        MappingProtocol::NatPmp => {
+            let gateway: Ipv4Addr = mapping
+                .gateway
+                .parse()
+                .map_err(|_| format!("Invalid NAT-PMP gateway: {}", mapping.gateway))?;
+            nat_pmp_request(gateway, &nat_pmp_mapping_request(mapping.internal_port, 0, 0), 16).await?;
+        }
+    }
+
+    println!("[PortMapping] Removed external port {}", mapping.external_port);
+    *active = None;
+    MAPPED.store(false, Ordering::SeqCst);
+    Ok(Some(mapping))
+}
+
+/// Whether the router forwards the proxy port, so clients outside the LAN can connect
+pub fn is_mapped() -> bool {
+    MAPPED.load(Ordering::SeqCst)
+}
+
+pub async fn active_mapping() -> Option<PortMapping> {
+    ACTIVE_MAPPING.lock().await.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_wan_service_resolves_relative_control_url() {
+        let description = r#"<root><device><serviceList>
+            <service><serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType><controlURL>/l3f</controlURL></service>
+            <service>
+                <serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>
+                <controlURL>/upnp/control/WANIPConn1</controlURL>
+            </service>
+        </serviceList></device></root>"#;
+
+        let (service_type, control_url) =
+            parse_wan_service(description, "http://192.168.1.1:5000/rootDesc.xml").expect("WAN service");
+
+        assert_eq!(service_type, "urn:schemas-upnp-org:service:WANIPConnection:1");
+        assert_eq!(control_url, "http://192.168.1.1:5000/upnp/control/WANIPConn1");
+    }
+
+    #[test]
+    fn parse_ssdp_location_is_case_insensitive() {
+        let response = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\nLocation: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        assert_eq!(
+            parse_ssdp_location(response).as_deref(),
+            Some("http://192.168.1.1:5000/rootDesc.xml")
+        );
+    }
+}
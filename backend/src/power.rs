@@ -0,0 +1,281 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::Duration;
+use tauri::Emitter;
+use crate::http_pool::{self, RequestClass};
+use crate::models::{PowerPolicy, ProcessStatus};
+use crate::AppState;
+
+// Battery awareness for laptops. While the machine runs on battery below
+// `PowerPolicy.battery_threshold_percent`, or with the OS power saver on, idle models
+// are frozen (see `suspend`), the proxy serves one request per model at a time and new
+// launches are refused. Each deferred action emits `power-action-deferred` with the
+// reason; models frozen here are thawed once the machine is back on power.
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// Battery percent above the threshold before throttling ends, so it does not flap
+const RESUME_MARGIN_PERCENT: u8 = 5;
+
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct PowerStatus {
+    pub has_battery: bool,
+    pub on_battery: bool,
+    pub battery_percent: Option<u8>,
+    /// Windows battery saver, macOS Low Power Mode or the Linux `low-power` profile
+    pub power_saver: bool,
+}
+
+/// What the policy currently holds back, `None` when not throttled
+#[derive(Debug, Clone, Serialize)]
+pub struct Throttle {
+    pub reason: String,
+    pub refuse_launches: bool,
+    pub proxy_max_concurrent: Option<u32>,
+}
+
+static THROTTLE: LazyLock<Mutex<Option<Throttle>>> = LazyLock::new(|| Mutex::new(None));
+static LAST_STATUS: LazyLock<Mutex<PowerStatus>> = LazyLock::new(|| Mutex::new(PowerStatus::default()));
+/// Processes frozen by this module (user pauses are left alone)
+static PAUSED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+#[cfg(target_os = "linux")]
+pub fn read_status() -> PowerStatus {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).map(|s| s.trim().to_string()).ok();
+    let mut status = PowerStatus::default();
+    let mut mains_online = false;
+    if let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") {
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            match read(dir.join("type")).as_deref() {
+                Some("Battery") if read(dir.join("scope")).as_deref() != Some("Device") => {
+                    status.has_battery = true;
+                    status.battery_percent = read(dir.join("capacity")).and_then(|c| c.parse().ok());
+                    status.on_battery |= read(dir.join("status")).as_deref() == Some("Discharging");
+                }
+                Some("Mains") | Some("USB") => mains_online |= read(dir.join("online")).as_deref() == Some("1"),
+                _ => {}
+            }
+        }
+    }
+    status.on_battery &= !mains_online;
+    status.power_saver = read("/sys/firmware/acpi/platform_profile".into()).as_deref() == Some("low-power");
+    status
+}
+
+#[cfg(windows)]
+pub fn read_status() -> PowerStatus {
+    #[repr(C)]
+    #[derive(Default)]
+    #[allow(non_snake_case)]
+    struct SYSTEM_POWER_STATUS {
+        ACLineStatus: u8,
+        BatteryFlag: u8,
+        BatteryLifePercent: u8,
+        SystemStatusFlag: u8,
+        BatteryLifeTime: u32,
+        BatteryFullLifeTime: u32,
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(lpSystemPowerStatus: *mut SYSTEM_POWER_STATUS) -> i32;
+    }
+
+    let mut raw = SYSTEM_POWER_STATUS::default();
+    if unsafe { GetSystemPowerStatus(&mut raw) } == 0 {
+        return PowerStatus::default();
+    }
+    // BatteryFlag 128 = no system battery, 255 = unknown
+    let has_battery = raw.BatteryFlag & 128 == 0 && raw.BatteryFlag != 255;
+    PowerStatus {
+        has_battery,
+        on_battery: has_battery && raw.ACLineStatus == 0,
+        battery_percent: (raw.BatteryLifePercent <= 100).then_some(raw.BatteryLifePercent),
+        power_saver: raw.SystemStatusFlag == 1,
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn read_status() -> PowerStatus {
+    let run = |args: &[&str]| {
+        std::process::Command::new("pmset")
+            .args(args)
+            .output()
+            .ok()
+            .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+            .unwrap_or_default()
+    };
+    let batt = run(&["-g", "batt"]);
+    let battery_percent = batt
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|word| word.strip_suffix('%').and_then(|p| p.parse().ok()));
+    PowerStatus {
+        has_battery: battery_percent.is_some(),
+        on_battery: batt.contains("'Battery Power'"),
+        battery_percent,
+        power_saver: run(&["-g"]).lines().any(|l| {
+            let mut words = l.split_whitespace();
+            words.next() == Some("lowpowermode") && words.next() == Some("1")
+        }),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows, target_os = "macos")))]
+pub fn read_status() -> PowerStatus {
+    PowerStatus::default()
+}
+
+/// Why the policy throttles under `status`, or `None`. `throttled` is the previous
+/// state, which needs a few percent of charge on top of the threshold to end.
+pub fn throttle_reason(status: &PowerStatus, policy: &PowerPolicy, throttled: bool) -> Option<String> {
+    if !policy.enabled || !status.on_battery {
+        return None;
+    }
+    if status.power_saver {
+        return Some("power saver is on".to_string());
+    }
+    let threshold = policy.battery_threshold_percent.saturating_add(if throttled { RESUME_MARGIN_PERCENT } else { 0 });
+    match status.battery_percent {
+        Some(percent) if percent <= threshold => Some(format!("battery at {}% on battery power", percent)),
+        _ => None,
+    }
+}
+
+pub fn current() -> (PowerStatus, Option<Throttle>) {
+    let status = LAST_STATUS.lock().unwrap_or_else(|p| p.into_inner()).clone();
+    (status, THROTTLE.lock().unwrap_or_else(|p| p.into_inner()).clone())
+}
+
+/// Tell the UI an action was held back
+pub fn notify_deferred(action: &str, target: &str, reason: &str) {
+    println!("[Power] Deferred {} of {}: {}", action, target, reason);
+    if let Some(app_handle) = APP_HANDLE.get() {
+        let _ = app_handle.emit("power-action-deferred", serde_json::json!({
+            "action": action,
+            "target": target,
+            "reason": reason,
+        }));
+    }
+}
+
+/// Err with the reason when the policy refuses new launches right now
+pub fn check_launch(model_path: &str) -> Result<(), String> {
+    let throttle = THROTTLE.lock().unwrap_or_else(|p| p.into_inner()).clone();
+    match throttle.filter(|t| t.refuse_launches) {
+        Some(throttle) => {
+            notify_deferred("launch", model_path, &throttle.reason);
+            Err(format!("Launch deferred: {}", throttle.reason))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Concurrency cap for proxied requests while throttled
+pub fn proxy_max_concurrent() -> Option<(u32, String)> {
+    let throttle = THROTTLE.lock().unwrap_or_else(|p| p.into_inner()).clone()?;
+    Some((throttle.proxy_max_concurrent?, throttle.reason))
+}
+
+/// Whether llama-server has no slot processing a request
+async fn is_idle(host: &str, port: u16) -> bool {
+    let url = format!("{}/slots", crate::suspend::local_base_url(host, port));
+    let Ok(response) = http_pool::get(&url, RequestClass::Control).send().await else { return false };
+    let Ok(slots) = response.json::<serde_json::Value>().await else { return false };
+    slots
+        .as_array()
+        .is_some_and(|slots| slots.iter().all(|s| !s.get("is_processing").and_then(|v| v.as_bool()).unwrap_or(true)))
+}
+
+/// `notify_busy` reports models left running because they are busy, once per episode
+async fn pause_idle_models(state: &AppState, reason: &str, notify_busy: bool) {
+    let candidates: Vec<(String, String, u16, String)> = state
+        .running_processes
+        .lock()
+        .await
+        .values()
+        .filter(|p| !p.model_path.starts_with("tunnel:") && matches!(p.status, ProcessStatus::Running))
+        .map(|p| (p.id.clone(), p.host.clone(), p.port, p.model_name.clone()))
+        .collect();
+    for (process_id, host, port, model_name) in candidates {
+        if !is_idle(&host, port).await {
+            if notify_busy {
+                notify_deferred("pause", &model_name, "model is busy; paused once idle");
+            }
+            continue;
+        }
+        match crate::suspend::suspend_process(&process_id, false, state).await {
+            Ok(_) => {
+                println!("[Power] Paused idle {}: {}", model_name, reason);
+                PAUSED.lock().unwrap_or_else(|p| p.into_inner()).insert(process_id);
+            }
+            Err(e) => eprintln!("[Power] Failed to pause {}: {}", model_name, e),
+        }
+    }
+}
+
+async fn resume_paused(state: &AppState) {
+    let paused: Vec<String> = PAUSED.lock().unwrap_or_else(|p| p.into_inner()).drain().collect();
+    for process_id in paused {
+        match crate::suspend::resume_process(&process_id, state).await {
+            Ok(_) => println!("[Power] Resumed {} on power", process_id),
+            // Already resumed or stopped by the user
+            Err(e) => println!("[Power] Not resuming {}: {}", process_id, e),
+        }
+    }
+}
+
+async fn check_once(state: &AppState, app_handle: &tauri::AppHandle) {
+    let policy = state.config.lock().await.power_policy.clone();
+    let status = read_status();
+    let was_throttled = THROTTLE.lock().unwrap_or_else(|p| p.into_inner()).is_some();
+    let reason = throttle_reason(&status, &policy, was_throttled);
+    *LAST_STATUS.lock().unwrap_or_else(|p| p.into_inner()) = status.clone();
+
+    let throttle = reason.clone().map(|reason| Throttle {
+        reason,
+        refuse_launches: policy.refuse_launches,
+        proxy_max_concurrent: policy.reduce_proxy_concurrency.then_some(policy.proxy_max_concurrent.max(1)),
+    });
+    *THROTTLE.lock().unwrap_or_else(|p| p.into_inner()) = throttle.clone();
+
+    if was_throttled != reason.is_some() {
+        println!("[Power] {}", reason.as_deref().map_or("Back on power; throttling ended".to_string(), |r| format!("Throttling: {}", r)));
+        let _ = app_handle.emit("power-state-changed", serde_json::json!({ "status": status, "throttle": throttle }));
+        if reason.is_none() {
+            resume_paused(state).await;
+        }
+    }
+    // Models that were busy at the last check are paused once they go idle
+    if let Some(reason) = reason.filter(|_| policy.pause_idle_models) {
+        pause_idle_models(state, &reason, !was_throttled).await;
+    }
+}
+
+pub fn spawn(state: AppState, app_handle: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app_handle.clone());
+    tauri::async_runtime::spawn(async move {
+        loop {
+            check_once(&state, &app_handle).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttles_on_low_battery_or_power_saver_with_hysteresis() {
+        let policy = PowerPolicy { enabled: true, ..PowerPolicy::default() };
+        let battery = |percent| PowerStatus { has_battery: true, on_battery: true, battery_percent: Some(percent), power_saver: false };
+
+        assert!(throttle_reason(&battery(25), &policy, false).unwrap().contains("25%"));
+        assert!(throttle_reason(&battery(33), &policy, false).is_none());
+        assert!(throttle_reason(&battery(33), &policy, true).is_some());
+        assert!(throttle_reason(&PowerStatus { on_battery: false, ..battery(5) }, &policy, false).is_none());
+        assert_eq!(throttle_reason(&PowerStatus { power_saver: true, ..battery(90) }, &policy, false).as_deref(), Some("power saver is on"));
+        assert!(throttle_reason(&battery(5), &PowerPolicy::default(), false).is_none());
+    }
+}
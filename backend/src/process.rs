@@ -1,7 +1,6 @@
 use tokio::process::{Child, Command as TokioCommand};
 use tokio::io::{BufReader, AsyncBufReadExt};
 use std::process::Stdio;
-#[cfg(windows)]
 use uuid::Uuid;
 use chrono::Utc;
 use std::sync::Arc;
@@ -9,20 +8,144 @@ use tokio::sync::Mutex;
 use crate::models::*;
 use crate::AppState;
 use crate::config::save_settings;
+use crate::log_classifier::{self, LogSeverity};
 use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use crate::http_pool::{self, RequestClass};
+
+const READY_POLL: Duration = Duration::from_millis(500);
+/// Large models on slow disks can take minutes to load
+const READY_TIMEOUT: Duration = Duration::from_secs(900);
+/// Time a stopped server gets to exit on SIGTERM
+#[cfg(unix)]
+const GRACEFUL_STOP: Duration = Duration::from_secs(5);
+
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// What a restart carries over from the process that exited
+#[derive(Clone)]
+struct Relaunch {
+    process_id: String,
+    restart_count: u32,
+    instance: u32,
+    host: String,
+    port: u16,
+}
+
+tokio::task_local! {
+    /// Set around the launch in `restart_after_exit` so the new server takes over the
+    /// old process id, and with it the terminal window polling that id
+    static RELAUNCH: Relaunch;
+}
+
+/// Called once from setup so readiness probes can emit `model-ready`
+pub fn set_app_handle(app_handle: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
 
 fn has_arg(args: &[String], key: &str) -> bool {
     args.iter().any(|arg| arg.eq_ignore_ascii_case(key))
 }
 
+/// `custom_args` with the model's mlock/mmap/numa settings and low memory mode applied
+fn memory_launch_args(global_config: &GlobalConfig, model_config: &ModelConfig) -> String {
+    let low_memory_context = global_config.low_memory_mode.then_some(global_config.low_memory_context);
+    crate::memory_mode::launch_args(&model_config.custom_args, &model_config.memory, low_memory_context)
+}
+
+/// The model's GPU selection as arguments; CUDA then numbers devices in PCI order
+/// like `system_monitor::list_gpus`
+fn apply_gpu_devices(model_config: &mut ModelConfig) {
+    model_config.custom_args = crate::multi_gpu::launch_args(&model_config.custom_args, &model_config.gpu_devices);
+    if !model_config.gpu_devices.devices.is_empty() {
+        model_config.env_vars.entry("CUDA_DEVICE_ORDER".to_string()).or_insert_with(|| "PCI_BUS_ID".to_string());
+    }
+}
+
+pub(crate) const GPU_LAYER_FLAGS: &[&str] = &["-ngl", "--n-gpu-layers", "--gpu-layers"];
+
+/// `-ngl` sized to the free VRAM when the arguments leave it to llama-server
+async fn gpu_layer_args(global_config: &GlobalConfig, model_config: &ModelConfig) -> String {
+    let args = crate::cli_args::split(&model_config.custom_args);
+    if !global_config.auto_gpu_layers || crate::cli_args::flag_value(&args, GPU_LAYER_FLAGS).is_some() {
+        return model_config.custom_args.clone();
+    }
+    match suggest_gpu_layers(&model_config.model_path, &model_config.custom_args).await {
+        Ok(suggestion) => {
+            println!("[Launch] Using -ngl {}: {}", suggestion.gpu_layers, suggestion.reasoning.join("; "));
+            let mut args = args;
+            crate::cli_args::set_flag(&mut args, GPU_LAYER_FLAGS, &suggestion.gpu_layers.to_string());
+            crate::cli_args::join(&args)
+        }
+        Err(e) => {
+            println!("[Launch] No -ngl suggestion: {}", e);
+            model_config.custom_args.clone()
+        }
+    }
+}
+
+/// GPU layers for `model_path` launched with `custom_args`, from its GGUF header and
+/// the VRAM free right now
+pub async fn suggest_gpu_layers(model_path: &str, custom_args: &str) -> Result<crate::vram_estimate::GpuLayerSuggestion, String> {
+    let (path, args) = (model_path.to_string(), custom_args.to_string());
+    tokio::task::spawn_blocking(move || {
+        let (total, used) = crate::system_monitor::gpu_memory_gb().ok_or_else(|| "No GPU memory reading".to_string())?;
+        let free = (total - used).max(0.0) as f64;
+        crate::vram_estimate::suggest_for_file(std::path::Path::new(&path), &args, free)
+            .ok_or_else(|| format!("Cannot read model file {}", path))
+    })
+    .await
+    .map_err(|e| format!("GPU layer suggestion failed: {}", e))?
+}
+
 async fn resolve_llama_server_path_with_fallback(
     state: &AppState,
     global_config: &GlobalConfig,
+    model_config: &ModelConfig,
 ) -> std::path::PathBuf {
     use std::fs;
     use std::time::SystemTime;
 
     let exe_name = if cfg!(windows) { "llama-server.exe" } else { "llama-server" };
+
+    // A version pinned on the model wins over the globally active one
+    if let Some(pinned) = model_config.pinned_llamacpp_version.as_deref().filter(|p| !p.is_empty()) {
+        let pinned_server = std::path::Path::new(pinned).join(exe_name);
+        if pinned_server.exists() {
+            return pinned_server;
+        }
+        eprintln!("Pinned llama.cpp version not found at {}, using the active version", pinned);
+    }
+
+    // Next, the active build for the backend this model prefers (e.g. CUDA vs CPU),
+    // falling back to the benchmark winner for models of the same class
+    let preferred_backend = model_config
+        .preferred_backend
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+        .map(crate::detect_backend_type)
+        .or_else(|| {
+            if global_config.backend_benchmarks.is_empty() {
+                return None;
+            }
+            let class = crate::backend_bench::model_class(&model_config.model_path);
+            global_config
+                .backend_benchmarks
+                .get(&class)
+                .and_then(|record| record.winner_backend.clone())
+        });
+    if let Some(backend) = &preferred_backend {
+        if let Some(backend_path) = global_config.active_backend_versions.get(backend) {
+            let backend_server = std::path::Path::new(backend_path).join(exe_name);
+            if backend_server.exists() {
+                return backend_server;
+            }
+        }
+    }
     
     // First, try the preferred path using active executable folder
     if let Some(active_path) = &global_config.active_executable_folder {
@@ -34,7 +157,7 @@ async fn resolve_llama_server_path_with_fallback(
     
     // Fallback: look for the latest installed version under <exec>/versions
     let versions_dir = std::path::Path::new(&global_config.executable_folder).join("versions");
-    let mut candidates: Vec<(std::path::PathBuf, Option<SystemTime>)> = Vec::new();
+    let mut candidates: Vec<(std::path::PathBuf, Option<SystemTime>, String)> = Vec::new();
     
     if versions_dir.exists() {
         if let Ok(read_dir) = fs::read_dir(&versions_dir) {
@@ -52,7 +175,8 @@ async fn resolve_llama_server_path_with_fallback(
                                         .metadata()
                                         .ok()
                                         .and_then(|m| m.created().ok());
-                                    candidates.push((backend_path.clone(), created));
+                                    let backend_name = backend_entry.file_name().to_string_lossy().to_string();
+                                    candidates.push((backend_path.clone(), created, crate::detect_backend_type(&backend_name)));
                                 }
                             }
                         }
@@ -65,7 +189,8 @@ async fn resolve_llama_server_path_with_fallback(
                             .metadata()
                             .ok()
                             .and_then(|m| m.created().ok());
-                        candidates.push((version_path.clone(), created));
+                        let version_name = entry.file_name().to_string_lossy().to_string();
+                        candidates.push((version_path.clone(), created, crate::detect_backend_type(&version_name)));
                     }
                 }
             }
@@ -80,7 +205,16 @@ async fn resolve_llama_server_path_with_fallback(
         (None, None) => b.0.cmp(&a.0),
     });
 
-    if let Some((chosen_dir, _)) = candidates.first() {
+    // The model's backend has no active build: use its newest install without
+    // changing the global selection
+    if let Some(backend) = &preferred_backend {
+        if let Some((backend_dir, _, _)) = candidates.iter().find(|c| &c.2 == backend) {
+            return backend_dir.join(exe_name);
+        }
+        eprintln!("No installed llama.cpp build for backend '{}', using the active version", backend);
+    }
+
+    if let Some((chosen_dir, _, _)) = candidates.first() {
         // Update config to set this as active
         {
             let mut cfg = state.config.lock().await;
@@ -111,7 +245,7 @@ pub struct ProcessHandle {
 }
 
 impl ProcessHandle {
-    fn new(child: Child, process_id: String) -> Self {
+    pub(crate) fn new(child: Child, process_id: String) -> Self {
         Self {
             child: Some(child),
             process_id,
@@ -149,6 +283,22 @@ pub async fn launch_model_server(
     state: &AppState,
     host_override: Option<String>,
 ) -> Result<LaunchResult, Box<dyn std::error::Error>> {
+    // Restarts of a crashed server are not new launches
+    if RELAUNCH.try_with(|_| ()).is_err() {
+        crate::power::check_launch(&model_path)?;
+    }
+    let result = start_model_server(model_path.clone(), state, host_override).await;
+    crate::analytics::record_launch(&model_path, result.is_ok());
+    result
+}
+
+async fn start_model_server(
+    model_path: String,
+    state: &AppState,
+    host_override: Option<String>,
+) -> Result<LaunchResult, Box<dyn std::error::Error>> {
+    let relaunch = RELAUNCH.try_with(Relaunch::clone).ok();
+    let host_override = relaunch.as_ref().map(|r| r.host.clone()).or(host_override);
     let (global_config, mut model_config) = {
         let config = state.config.lock().await;
         let model_configs = state.model_configs.lock().await;
@@ -162,16 +312,30 @@ pub async fn launch_model_server(
     if let Some(host) = host_override {
         model_config.server_host = host;
     }
+
+    // Context / GPU layer changes queued from the parameter controls take effect now
+    if let Some(args) = crate::parameter_controller::take_pending_load_args(state, &model_path, &model_config.custom_args).await {
+        println!("[Params] Applying pending load parameters: {}", args);
+        model_config.custom_args = args;
+    }
+    model_config.custom_args = memory_launch_args(&global_config, &model_config);
+    apply_gpu_devices(&mut model_config);
+    model_config.custom_args = gpu_layer_args(&global_config, &model_config).await;
     
     // Resolve server path with fallback to latest installed version if needed
-    let executable_path = resolve_llama_server_path_with_fallback(state, &global_config).await;
+    let executable_path = resolve_llama_server_path_with_fallback(state, &global_config, &model_config).await;
     
     if !executable_path.exists() {
-        return Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, format!("Server executable not found at: {:?}", executable_path))));
+        return Err(Box::new(std::io::Error::other(crate::i18n::t("launch.executable_missing", &[("path", &executable_path.display().to_string())]))));
     }
     
-    let requested_port = parse_port_from_args(&model_config.custom_args, model_config.server_port);
-    let actual_port = find_available_port(requested_port);
+    // A restart asks for the port clients already know
+    let requested_port = match &relaunch {
+        Some(r) => r.port,
+        None => port_from_args(&model_config.custom_args, model_config.server_port),
+    };
+    let taken = tracked_ports(state, relaunch.as_ref().map(|r| r.process_id.as_str())).await;
+    let actual_port = find_available_port(requested_port, &taken);
     
     // If we had to change the port, update the model config for this session
     let final_port = if actual_port != requested_port {
@@ -182,10 +346,11 @@ pub async fn launch_model_server(
     };
     
     // Build command with custom args if any
-    let mut cmd = TokioCommand::new(&executable_path);
+    let mut cmd = TokioCommand::new(crate::long_path::extended(&executable_path));
     
-    // Set working directory to the executable's parent folder
-    if let Some(parent) = executable_path.parent() {
+    // Set working directory to the executable's parent folder. Windows cannot start a
+    // process in a directory past MAX_PATH; the DLLs next to the executable are found anyway.
+    if let Some(parent) = executable_path.parent().filter(|p| !(cfg!(windows) && crate::long_path::exceeds_max_path(p))) {
         cmd.current_dir(parent);
     }
 
@@ -229,7 +394,7 @@ println!("Using custom UI path: {:?}", custom_ui_path);
 
     let mut launch_args = vec![
         "-m".to_string(),
-        model_config.model_path.clone(),
+        crate::long_path::extended_arg(&model_config.model_path),
         "--host".to_string(),
         model_config.server_host.clone(),
         "--port".to_string(),
@@ -247,25 +412,11 @@ println!("Using custom UI path: {:?}", custom_ui_path);
     
     // Add custom arguments if present
     if !model_config.custom_args.trim().is_empty() {
-        let mut custom_args = parse_custom_args(&model_config.custom_args);
-        filter_port_args(&mut custom_args); // Filter out --port arguments
-        
-        // Resolve relative paths for --mmproj, -mm, --model-draft, and -md
-        let mut i = 0;
-        while i < custom_args.len() {
-            if (custom_args[i] == "--mmproj" || custom_args[i] == "-mm" || 
-                custom_args[i] == "--model-draft" || custom_args[i] == "-md") && i + 1 < custom_args.len() {
-                let path = &custom_args[i + 1];
-                if !std::path::Path::new(path).is_absolute() {
-                    let abs_path = std::path::Path::new(&global_config.models_directory).join(path);
-                    custom_args[i + 1] = abs_path.to_string_lossy().to_string();
-                }
-                i += 2;
-            } else {
-                i += 1;
-            }
-        }
-        
+        let mut custom_args = crate::cli_args::split(&model_config.custom_args);
+        // The port is chosen above
+        crate::cli_args::remove_flag(&mut custom_args, &["--port"]);
+        resolve_model_paths(&mut custom_args, &global_config.models_directory);
+
         let sanitized = sanitize_args_for_ik_backend(&executable_path, custom_args).await;
         launch_args.extend(sanitized);
     }
@@ -279,7 +430,10 @@ println!("Using custom UI path: {:?}", custom_ui_path);
         .kill_on_drop(true); // Ensure child process is killed when dropped
     
     let mut child = cmd.spawn()?;
-    let process_id = Uuid::new_v4().to_string();
+    let process_id = match &relaunch {
+        Some(r) => r.process_id.clone(),
+        None => Uuid::new_v4().to_string(),
+    };
     
     // Get stdout and stderr for output capture
     let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
@@ -291,7 +445,11 @@ println!("Using custom UI path: {:?}", custom_ui_path);
         .unwrap_or("unknown")
         .to_string();
     
-    let process_info = ProcessInfo {
+    let instance = match &relaunch {
+        Some(r) => r.instance,
+        None => next_instance(state, &model_config.model_path).await,
+    };
+    let mut process_info = ProcessInfo {
         id: process_id.clone(),
         model_path: model_config.model_path.clone(),
         model_name: model_name.clone(),
@@ -302,20 +460,43 @@ println!("Using custom UI path: {:?}", custom_ui_path);
         output: Vec::new(),
         created_at: Utc::now(),
         last_sent_line: Some(0),
+        preset_id: None,
+        instance,
+        restart_count: relaunch.as_ref().map_or(0, |r| r.restart_count),
     };
     
     // Store the process info and child
     {
         let mut processes = state.running_processes.lock().await;
+        // A restart keeps the log so the crash stays readable above the new output
+        if let Some(previous) = processes.get(&process_id).filter(|_| relaunch.is_some()) {
+            process_info.output = previous.output.clone();
+            process_info.last_sent_line = previous.last_sent_line;
+            process_info.created_at = previous.created_at;
+        }
         processes.insert(process_id.clone(), process_info);
     }
     
+    if let Some(pid) = child.id() {
+        crate::process_registry::record(crate::process_registry::RegisteredProcess {
+            process_id: process_id.clone(),
+            pid,
+            executable: executable_path.to_string_lossy().to_string(),
+            model_path: model_config.model_path.clone(),
+            model_name: model_name.clone(),
+            host: model_config.server_host.clone(),
+            port: final_port,
+            started_at: Utc::now(),
+        }).await;
+    }
+
     // Store the child process using simplified wrapper
     let process_handle = Arc::new(Mutex::new(ProcessHandle::new(child, process_id.clone())));
     {
         let mut child_processes = state.child_processes.lock().await;
         child_processes.insert(process_id.clone(), process_handle.clone());
     }
+    crate::parameter_controller::register(state, &process_id, &model_path, &model_config.custom_args).await;
     
     // Spawn task to handle output capture
     let state_clone = state.clone();
@@ -325,6 +506,10 @@ println!("Using custom UI path: {:?}", custom_ui_path);
     tokio::spawn(async move {
         handle_process_output(state_clone, process_id_clone, handle_clone, stdout, stderr).await;
     });
+
+    // Running only once the server answers /health
+    let base_url = crate::suspend::local_base_url(&model_config.server_host, final_port);
+    tokio::spawn(probe_readiness(state.clone(), process_id.clone(), base_url));
     
     Ok(LaunchResult {
         success: true,
@@ -332,7 +517,8 @@ println!("Using custom UI path: {:?}", custom_ui_path);
         server_host: model_config.server_host,
         server_port: final_port,
         model_name,
-        message: "Model server launched successfully".to_string(),
+        message: crate::i18n::t("launch.success", &[]),
+        instance,
     })
 }
 
@@ -340,7 +526,8 @@ pub async fn launch_model_external(
     model_path: String,
     state: &AppState,
 ) -> Result<LaunchResult, Box<dyn std::error::Error>> {
-    let (global_config, model_config) = {
+    crate::power::check_launch(&model_path)?;
+    let (global_config, mut model_config) = {
         let config = state.config.lock().await;
         let model_configs = state.model_configs.lock().await;
         let model_config = model_configs.get(&model_path)
@@ -348,16 +535,19 @@ pub async fn launch_model_external(
             .unwrap_or_else(|| ModelConfig::new(model_path.clone()));
         (config.clone(), model_config)
     };
+    model_config.custom_args = memory_launch_args(&global_config, &model_config);
+    apply_gpu_devices(&mut model_config);
+    model_config.custom_args = gpu_layer_args(&global_config, &model_config).await;
     
     // Resolve server path with fallback to latest installed version if needed
-    let executable_path = resolve_llama_server_path_with_fallback(state, &global_config).await;
+    let executable_path = resolve_llama_server_path_with_fallback(state, &global_config, &model_config).await;
     
     if !executable_path.exists() {
-        return Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, format!("Server executable not found at: {:?}", executable_path))));
+        return Err(Box::new(std::io::Error::other(crate::i18n::t("launch.executable_missing", &[("path", &executable_path.display().to_string())]))));
     }
     
-    let requested_port = parse_port_from_args(&model_config.custom_args, model_config.server_port);
-    let actual_port = find_available_port(requested_port);
+    let requested_port = port_from_args(&model_config.custom_args, model_config.server_port);
+    let actual_port = find_available_port(requested_port, &tracked_ports(state, None).await);
     
     // If we had to change the port, update the model config for this session
     let final_port = if actual_port != requested_port {
@@ -370,7 +560,7 @@ pub async fn launch_model_external(
 // For external launch, spawn in a new terminal window
     let mut cmd_args = vec![
         "-m".to_string(),
-        model_config.model_path.clone(),
+        crate::long_path::extended_arg(&model_config.model_path),
         "--host".to_string(),
         model_config.server_host.clone(),
         "--port".to_string(),
@@ -380,25 +570,11 @@ pub async fn launch_model_external(
     
     // Add custom arguments if present
     if !model_config.custom_args.trim().is_empty() {
-        let mut custom_args = parse_custom_args(&model_config.custom_args);
-        filter_port_args(&mut custom_args); // Filter out --port arguments
-        
-        // Resolve relative paths for --mmproj, -mm, --model-draft, and -md
-        let mut i = 0;
-        while i < custom_args.len() {
-            if (custom_args[i] == "--mmproj" || custom_args[i] == "-mm" || 
-                custom_args[i] == "--model-draft" || custom_args[i] == "-md") && i + 1 < custom_args.len() {
-                let path = &custom_args[i + 1];
-                if !std::path::Path::new(path).is_absolute() {
-                    let abs_path = std::path::Path::new(&global_config.models_directory).join(path);
-                    custom_args[i + 1] = abs_path.to_string_lossy().to_string();
-                }
-                i += 2;
-            } else {
-                i += 1;
-            }
-        }
-        
+        let mut custom_args = crate::cli_args::split(&model_config.custom_args);
+        // The port is chosen above
+        crate::cli_args::remove_flag(&mut custom_args, &["--port"]);
+        resolve_model_paths(&mut custom_args, &global_config.models_directory);
+
         let sanitized = sanitize_args_for_ik_backend(&executable_path, custom_args).await;
         cmd_args.extend(sanitized);
     }
@@ -408,53 +584,8 @@ pub async fn launch_model_external(
     }
     
     // Launch in external terminal
-    #[cfg(windows)]
-    {
-        let mut cmd = TokioCommand::new("cmd");
-        
-        // Apply environment variables
-        for (key, value) in &model_config.env_vars {
-            cmd.env(key, value);
-        }
-
-        cmd.args(["/c", "start", "cmd", "/k"])
-           .arg(executable_path.to_string_lossy().to_string())
-           .args(&cmd_args);
-        cmd.spawn()?;
-    }
-    
-    #[cfg(not(windows))]
-    {
-        let mut cmd = TokioCommand::new("x-terminal-emulator");
-        for (key, value) in &model_config.env_vars {
-            cmd.env(key, value);
-        }
-        cmd.args(["-e"])
-           .arg(executable_path.to_string_lossy().to_string())
-           .args(&cmd_args);
-        
-        // Fallback to other terminal emulators if x-terminal-emulator fails
-        if cmd.spawn().is_err() {
-            let mut cmd = TokioCommand::new("gnome-terminal");
-            for (key, value) in &model_config.env_vars {
-                cmd.env(key, value);
-            }
-            cmd.args(["--"])
-               .arg(executable_path.to_string_lossy().to_string())
-               .args(&cmd_args);
-            
-            if cmd.spawn().is_err() {
-                let mut cmd = TokioCommand::new("xterm");
-                for (key, value) in &model_config.env_vars {
-                    cmd.env(key, value);
-                }
-                cmd.args(["-e"])
-                   .arg(executable_path.to_string_lossy().to_string())
-                   .args(&cmd_args);
-                cmd.spawn()?;
-            }
-        }
-    }
+    crate::terminal::spawn(&global_config.external_terminal, &executable_path, &cmd_args, &model_config.env_vars)
+        .map_err(std::io::Error::other)?;
     
     let model_name = std::path::Path::new(&model_config.model_path)
         .file_stem()
@@ -468,10 +599,65 @@ pub async fn launch_model_external(
         server_host: model_config.server_host,
         server_port: final_port,
         model_name,
-        message: "Model launched in external terminal".to_string(),
+        message: crate::i18n::t("launch.external", &[]),
+        instance: 0,
     })
 }
 
+/// Poll `/health` until llama-server has loaded the model, then move the process from
+/// Starting to Running and emit `model-ready`. Gives up when the process leaves Starting
+/// (exited or stopped) or after `READY_TIMEOUT`.
+pub(crate) async fn probe_readiness(state: AppState, process_id: String, base_url: String) {
+    let started = Instant::now();
+    let health_url = format!("{}/health", base_url);
+    while started.elapsed() < READY_TIMEOUT {
+        tokio::time::sleep(READY_POLL).await;
+        let starting = state
+            .running_processes
+            .lock()
+            .await
+            .get(&process_id)
+            .is_some_and(|p| matches!(p.status, ProcessStatus::Starting));
+        if !starting {
+            return;
+        }
+        // 503 while the model is loading
+        let ready = http_pool::get(&health_url, RequestClass::Health)
+            .send()
+            .await
+            .is_ok_and(|response| response.status().is_success());
+        if !ready {
+            continue;
+        }
+
+        let info = {
+            let mut processes = state.running_processes.lock().await;
+            match processes.get_mut(&process_id) {
+                Some(process_info) if matches!(process_info.status, ProcessStatus::Starting) => {
+                    process_info.status = ProcessStatus::Running;
+                    process_info.clone()
+                }
+                _ => return,
+            }
+        };
+        let load_ms = started.elapsed().as_millis() as u64;
+        println!("[Launch] {} ready on {} after {:.1}s", info.model_name, base_url, load_ms as f64 / 1000.0);
+        add_output_line(&state, &process_id, format!("[Arandu] Model ready after {:.1}s", load_ms as f64 / 1000.0)).await;
+        if let Some(app_handle) = APP_HANDLE.get() {
+            crate::events::emit(app_handle, &crate::events::ModelReady {
+                process_id: process_id.to_string(),
+                model_path: info.model_path.clone(),
+                model_name: info.model_name.clone(),
+                host: info.host.clone(),
+                port: info.port,
+                load_ms,
+            });
+        }
+        return;
+    }
+    eprintln!("[Launch] {} did not answer {} within {}s; leaving it as Starting", process_id, health_url, READY_TIMEOUT.as_secs());
+}
+
 async fn handle_process_output(
     state: AppState,
     process_id: String,
@@ -485,14 +671,6 @@ async fn handle_process_output(
     let mut stdout_buf = Vec::new();
     let mut stderr_buf = Vec::new();
     
-    // Update status to running
-    {
-        let mut processes = state.running_processes.lock().await;
-        if let Some(process_info) = processes.get_mut(&process_id) {
-            process_info.status = ProcessStatus::Running;
-        }
-    }
-    
     loop {
         tokio::select! {
             read_stdout = stdout_reader.read_until(b'\n', &mut stdout_buf) => {
@@ -500,7 +678,7 @@ async fn handle_process_output(
                     Ok(0) => break, // EOF
                     Ok(_) => {
                         let line = String::from_utf8_lossy(&stdout_buf).to_string();
-                        let formatted_line = format!("[OUT] {}", line.trim_end());
+                        let formatted_line = log_classifier::tag_line(line.trim_end(), false);
                         add_output_line(&state, &process_id, formatted_line).await;
                         stdout_buf.clear();
                     },
@@ -515,7 +693,7 @@ async fn handle_process_output(
                     Ok(0) => break, // EOF
                     Ok(_) => {
                         let line = String::from_utf8_lossy(&stderr_buf).to_string();
-                        let formatted_line = format!("[INFO] {}", line.trim_end());
+                        let formatted_line = log_classifier::tag_line(line.trim_end(), true);
                         add_output_line(&state, &process_id, formatted_line).await;
                         stderr_buf.clear();
                     },
@@ -528,24 +706,34 @@ async fn handle_process_output(
         }
     }
     
-    // Wait for process to finish and get exit code
-    let exit_code = {
+    // Wait for process to finish and get exit code. No child left means
+    // terminate_process took it: stopped on purpose, never restarted.
+    let exited = {
         let mut handle_guard = process_handle.lock().await;
         if let Some(mut child_process) = handle_guard.take_child() {
             match child_process.wait().await {
-                Ok(status) => status.code().unwrap_or(-1),
-                Err(_) => -1,
+                Ok(status) => Some(status.code().unwrap_or(-1)),
+                Err(_) => Some(-1),
             }
         } else {
-            -1
+            None
         }
     };
+    let exit_code = exited.unwrap_or(-1);
+
+    let (model_path, restarts) = {
+        let processes = state.running_processes.lock().await;
+        processes.get(&process_id).map(|p| (p.model_path.clone(), p.restart_count)).unwrap_or_default()
+    };
+    let policy = state.model_configs.lock().await.get(&model_path).map(|c| c.restart_policy.clone()).unwrap_or_default();
+    let restart_delay = exited.and_then(|code| policy.next_delay(code, restarts));
     
-    // Update process status to stopped and clean up child process tracking
+    // Update process status to stopped and clean up child process tracking. A pending
+    // restart keeps it Starting so the terminal goes on polling through the backoff.
     {
         let mut processes = state.running_processes.lock().await;
         if let Some(process_info) = processes.get_mut(&process_id) {
-            process_info.status = ProcessStatus::Stopped;
+            process_info.status = if restart_delay.is_some() { ProcessStatus::Starting } else { ProcessStatus::Stopped };
             let exit_msg = format!("Process exited with code: {}", exit_code);
             process_info.output.push(exit_msg);
         }
@@ -557,9 +745,87 @@ async fn handle_process_output(
         child_processes.remove(&process_id);
         println!("Process {} exited naturally, removed from tracking", process_id);
     }
+    crate::process_registry::forget(&process_id).await;
+
+    match restart_delay {
+        Some(delay) => restart_after_exit(state, process_id, exit_code, delay).await,
+        None if exited.is_some() && policy.mode != RestartMode::Never && restarts >= policy.max_retries => {
+            let line = format!("[Arandu] Not restarting: {} restarts used up", policy.max_retries);
+            println!("[Launch] {} for {}", line, process_id);
+            add_output_line(&state, &process_id, line).await;
+        }
+        None => {}
+    }
+}
+
+/// Wait out the backoff, then relaunch under the same process id with the preset the
+/// process ran with. Returns a boxed future because the relaunch spawns another
+/// `handle_process_output`, which would otherwise make this future contain itself.
+fn restart_after_exit(state: AppState, process_id: String, exit_code: i32, delay: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    Box::pin(async move {
+        let Some(process) = state.running_processes.lock().await.get(&process_id).cloned() else { return };
+        let attempt = process.restart_count + 1;
+        let max_retries = state
+            .model_configs
+            .lock()
+            .await
+            .get(&process.model_path)
+            .map_or(0, |c| c.restart_policy.max_retries);
+        let line = format!(
+            "[Arandu] Server exited with code {}; restarting in {}s (attempt {}/{})",
+            exit_code,
+            delay.as_secs(),
+            attempt,
+            max_retries
+        );
+        println!("[Launch] {}: {}", process.model_name, line);
+        add_output_line(&state, &process_id, line).await;
+        if let Some(app_handle) = APP_HANDLE.get() {
+            crate::events::emit(app_handle, &crate::events::ModelRestarting {
+                process_id: process_id.clone(),
+                model_path: process.model_path.clone(),
+                model_name: process.model_name.clone(),
+                exit_code,
+                attempt,
+                max_retries,
+                delay_ms: delay.as_millis() as u64,
+            });
+        }
+
+        tokio::time::sleep(delay).await;
+        // Stopped by the user while waiting
+        let still_pending = state
+            .running_processes
+            .lock()
+            .await
+            .get(&process_id)
+            .is_some_and(|p| matches!(p.status, ProcessStatus::Starting));
+        if !still_pending {
+            println!("[Launch] Restart of {} cancelled", process_id);
+            return;
+        }
+
+        let relaunch = Relaunch {
+            process_id: process_id.clone(),
+            restart_count: attempt,
+            instance: process.instance,
+            host: process.host.clone(),
+            port: process.port,
+        };
+        let launched = RELAUNCH
+            .scope(relaunch, crate::launch_with_preset(process.model_path.clone(), process.preset_id.clone(), &state))
+            .await;
+        if let Err(e) = launched {
+            eprintln!("[Launch] Restart of {} failed: {}", process.model_name, e);
+            if let Some(process_info) = state.running_processes.lock().await.get_mut(&process_id) {
+                process_info.status = ProcessStatus::Failed;
+                process_info.output.push(format!("[Arandu] Restart failed: {}", e));
+            }
+        }
+    })
 }
 
-async fn add_output_line(state: &AppState, process_id: &str, line: String) {
+pub(crate) async fn add_output_line(state: &AppState, process_id: &str, line: String) {
     let mut processes = state.running_processes.lock().await;
     if let Some(process_info) = processes.get_mut(process_id) {
         process_info.output.push(line);
@@ -570,6 +836,22 @@ async fn add_output_line(state: &AppState, process_id: &str, line: String) {
     }
 }
 
+/// SIGTERM first so llama-server can release Metal/CUDA buffers and exit cleanly;
+/// SIGKILL once `grace` runs out
+#[cfg(unix)]
+async fn terminate_gracefully(child: &mut Child, grace: Duration) -> std::io::Result<()> {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+    if let Some(pid) = child.id() {
+        if kill(Pid::from_raw(pid as i32), Signal::SIGTERM).is_ok()
+            && tokio::time::timeout(grace, child.wait()).await.is_ok()
+        {
+            return Ok(());
+        }
+    }
+    child.kill().await
+}
+
 pub async fn terminate_process(
     process_id: String,
     state: &AppState,
@@ -580,10 +862,25 @@ pub async fn terminate_process(
     {
         use tokio::time::{timeout, Duration};
         let mut child_processes = state.child_processes.lock().await;
-        if let Some(handle_arc) = child_processes.remove(&process_id) {
+        let handle = child_processes.remove(&process_id);
+        let adopted = handle.is_none()
+            && state.running_processes.lock().await.get(&process_id).is_some_and(|p| {
+                !p.model_path.starts_with("tunnel:") && matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running)
+            });
+        if adopted {
+            // Adopted from a previous run; only its PID is known
+            if let Err(e) = crate::process_registry::kill(&process_id).await {
+                eprintln!("Failed to kill adopted process {}: {}", process_id, e);
+            }
+        }
+        if let Some(handle_arc) = handle {
             let mut handle_guard = handle_arc.lock().await;
             if let Some(mut child) = handle_guard.take_child() {
-                match child.kill().await {
+                #[cfg(unix)]
+                let killed = terminate_gracefully(&mut child, GRACEFUL_STOP).await;
+                #[cfg(not(unix))]
+                let killed = child.kill().await;
+                match killed {
                     Ok(_) => {
                         // Wait for the process to actually exit, with timeout
                         match timeout(Duration::from_secs(5), child.wait()).await {
@@ -629,12 +926,41 @@ pub async fn terminate_process(
         }
         processes.remove(&process_id);
     }
+    state.suspended_processes.lock().await.remove(&process_id);
+    crate::process_registry::forget(&process_id).await;
     
     Ok(())
 }
 
+/// Stop every running model except instances of `keep_model_path`, so an exclusive
+/// launch gets the GPU to itself. Tunnels are left alone. Returns the stopped ids.
+pub async fn stop_other_models(keep_model_path: &str, state: &AppState) -> Vec<String> {
+    let others: Vec<String> = {
+        let processes = state.running_processes.lock().await;
+        processes
+            .values()
+            .filter(|p| !p.model_path.starts_with("tunnel:") && p.model_path != keep_model_path)
+            .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running | ProcessStatus::Suspended))
+            .map(|p| p.id.clone())
+            .collect()
+    };
+
+    let mut stopped = Vec::new();
+    for process_id in others {
+        println!("[Exclusive] Stopping {} before launching {}", process_id, keep_model_path);
+        match terminate_process(process_id.clone(), state).await {
+            Ok(()) => stopped.push(process_id),
+            Err(e) => eprintln!("[Exclusive] Failed to stop {}: {}", process_id, e),
+        }
+    }
+    stopped
+}
+
+/// New output since the last call. With `min_severity`, lines below it are skipped
+/// (they still count as sent).
 pub async fn get_process_logs(
     process_id: String,
+    min_severity: Option<LogSeverity>,
     state: &AppState,
 ) -> Result<ProcessOutput, Box<dyn std::error::Error>> {
     let mut processes = state.running_processes.lock().await;
@@ -645,7 +971,11 @@ pub async fn get_process_logs(
         let last_sent = process_info.last_sent_line.unwrap_or(0);
         
         let new_output = if last_sent < total_lines {
-            let new_lines = process_info.output[last_sent..].to_vec();
+            let new_lines = process_info.output[last_sent..]
+                .iter()
+                .filter(|line| min_severity.is_none_or(|min| log_classifier::severity_of_stored(line) >= min))
+                .cloned()
+                .collect();
             // Update the last sent line index
             process_info.last_sent_line = Some(total_lines);
             new_lines
@@ -655,44 +985,15 @@ pub async fn get_process_logs(
         
         Ok(ProcessOutput {
             output: new_output,
-            is_running: matches!(process_info.status, ProcessStatus::Running | ProcessStatus::Starting),
+            is_running: matches!(process_info.status, ProcessStatus::Running | ProcessStatus::Starting | ProcessStatus::Suspended),
             return_code: None,
         })
     } else {
-        Err(Box::new(std::io::Error::new(std::io::ErrorKind::Other, "Process not found")))
-    }
-}
-
-fn parse_port_from_args(custom_args: &str, default_port: u16) -> u16 {
-    if let Some(port_pos) = custom_args.find("--port") {
-        let after_port = &custom_args[port_pos + 6..];
-        // Handle both --port=1234 and --port 1234 formats
-        let port_str = if after_port.starts_with('=') {
-            // Format: --port=1234
-            let after_equals = &after_port[1..];
-            if let Some(space_pos) = after_equals.find(' ') {
-                &after_equals[..space_pos]
-            } else {
-                after_equals
-            }
-        } else {
-            // Format: --port 1234
-            let trimmed = after_port.trim_start();
-            if let Some(space_pos) = trimmed.find(' ') {
-                &trimmed[..space_pos]
-            } else {
-                trimmed
-            }
-        };
-        
-        if let Ok(port) = port_str.parse::<u16>() {
-            return port;
-        }
+        Err(Box::new(std::io::Error::other("Process not found")))
     }
-    default_port
 }
 
-fn is_port_available(port: u16) -> bool {
+pub(crate) fn is_port_available(port: u16) -> bool {
     if let Ok(listener) = std::net::TcpListener::bind(format!("127.0.0.1:{}", port)) {
         // Port is available, close the listener
         drop(listener);
@@ -703,9 +1004,42 @@ fn is_port_available(port: u16) -> bool {
     }
 }
 
-fn find_available_port(start_port: u16) -> u16 {
+/// Ports of models we are already running. A server that is still loading may not
+/// have bound its port yet, so a bind check alone would hand it out twice. `except` is
+/// a process being restarted, whose old port is free again.
+async fn tracked_ports(state: &AppState, except: Option<&str>) -> Vec<u16> {
+    let processes = state.running_processes.lock().await;
+    processes
+        .values()
+        .filter(|p| !p.model_path.starts_with("tunnel:") && Some(p.id.as_str()) != except)
+        .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running | ProcessStatus::Suspended))
+        .map(|p| p.port)
+        .collect()
+}
+
+/// Lowest instance number not taken by a running copy of `model_path`
+pub(crate) async fn next_instance(state: &AppState, model_path: &str) -> u32 {
+    let processes = state.running_processes.lock().await;
+    let taken: Vec<u32> = processes
+        .values()
+        .filter(|p| p.model_path == model_path)
+        .filter(|p| matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running | ProcessStatus::Suspended))
+        .map(|p| p.instance)
+        .collect();
+    (1..).find(|n| !taken.contains(n)).unwrap_or(1)
+}
+
+/// Label for an instance in lists and window titles, e.g. `#2 Large context`
+pub fn instance_label(instance: u32, preset_name: Option<&str>) -> String {
+    match preset_name.filter(|n| !n.is_empty()) {
+        Some(name) => format!("#{} {}", instance, name),
+        None => format!("#{}", instance),
+    }
+}
+
+fn find_available_port(start_port: u16, reserved: &[u16]) -> u16 {
     let mut port = start_port;
-    while !is_port_available(port) {
+    while reserved.contains(&port) || !is_port_available(port) {
         port += 1;
         // Prevent infinite loop by setting a reasonable upper limit
         if port-start_port > 10 {
@@ -716,55 +1050,33 @@ fn find_available_port(start_port: u16) -> u16 {
     port
 }
 
-fn filter_port_args(args: &mut Vec<String>) {
+/// Make relative `--mmproj` / `--model-draft` paths absolute under the models directory
+fn resolve_model_paths(args: &mut [String], models_directory: &str) {
+    const PATH_FLAGS: &[&str] = &["--mmproj", "-mm", "--model-draft", "-md"];
+    let resolve = |path: &str| {
+        if std::path::Path::new(path).is_absolute() {
+            path.to_string()
+        } else {
+            std::path::Path::new(models_directory).join(path).to_string_lossy().to_string()
+        }
+    };
     let mut i = 0;
     while i < args.len() {
-        if args[i] == "--port" {
-            // Remove --port and the next argument (the port number)
-            if i + 1 < args.len() {
-                args.remove(i + 1);
-            }
-            args.remove(i);
-        } else if args[i].starts_with("--port=") {
-            // Remove --port=...
-            args.remove(i);
-        } else {
-            i += 1;
+        if PATH_FLAGS.contains(&args[i].as_str()) && i + 1 < args.len() {
+            args[i + 1] = resolve(&args[i + 1]);
+            i += 2;
+            continue;
         }
+        if let Some((flag, path)) = args[i].split_once('=').filter(|(flag, _)| PATH_FLAGS.contains(flag)) {
+            args[i] = format!("{}={}", flag, resolve(path));
+        }
+        i += 1;
     }
 }
 
-fn parse_custom_args(custom_args: &str) -> Vec<String> {
-    let mut args = Vec::new();
-    let mut current_arg = String::new();
-    let mut in_quotes = false;
-    let mut chars = custom_args.chars().peekable();
-    
-    while let Some(ch) = chars.next() {
-        match ch {
-            '"' | '\'' if !in_quotes => {
-                in_quotes = true;
-            },
-            '"' | '\'' if in_quotes => {
-                in_quotes = false;
-            },
-            ' ' if !in_quotes => {
-                if !current_arg.is_empty() {
-                    args.push(current_arg.clone());
-                    current_arg.clear();
-                }
-            },
-            _ => {
-                current_arg.push(ch);
-            }
-        }
-    }
-    
-    if !current_arg.is_empty() {
-        args.push(current_arg);
-    }
-    
-    args
+/// `--port` from the model's arguments, or its configured port
+fn port_from_args(custom_args: &str, default_port: u16) -> u16 {
+    crate::cli_args::port(&crate::cli_args::split(custom_args)).unwrap_or(default_port)
 }
 
 fn is_ik_backend_path(executable_path: &std::path::Path) -> bool {
@@ -783,9 +1095,7 @@ fn extract_supported_flags_from_help(help_text: &str) -> HashSet<String> {
             .trim_matches('(')
             .trim_matches(')')
             .trim();
-        if cleaned.starts_with("--") && cleaned.len() > 2 {
-            out.insert(cleaned.to_string());
-        } else if cleaned.starts_with('-') && !cleaned.starts_with("--") && cleaned.len() > 1 {
+        if cleaned.starts_with('-') && cleaned != "--" && cleaned.len() > 1 {
             out.insert(cleaned.to_string());
         }
     }
@@ -859,3 +1169,35 @@ async fn sanitize_args_for_ik_backend(executable_path: &std::path::Path, args: V
 
     sanitized
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stop_sends_sigterm_before_killing() {
+        // Exits 0 only when it got the SIGTERM; a SIGKILL leaves no exit code
+        let mut child = TokioCommand::new("sh")
+            .args(["-c", "trap 'exit 0' TERM; while :; do sleep 0.1; done"])
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        terminate_gracefully(&mut child, Duration::from_secs(5)).await.unwrap();
+        assert_eq!(child.wait().await.unwrap().code(), Some(0));
+
+        let mut stubborn = TokioCommand::new("sh").args(["-c", "trap '' TERM; sleep 30"]).kill_on_drop(true).spawn().unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        terminate_gracefully(&mut stubborn, Duration::from_millis(300)).await.unwrap();
+        assert_eq!(stubborn.wait().await.unwrap().code(), None);
+    }
+
+    #[test]
+    fn launch_args_keep_quoted_paths_with_spaces() {
+        let mut args = crate::cli_args::split(r#"--port 9000 --mmproj "vision/mm proj.gguf" -md=/abs/draft.gguf --alias "a --port b""#);
+        assert_eq!(port_from_args(r#"--alias "--port 1" --port=9000"#, 8080), 9000);
+        crate::cli_args::remove_flag(&mut args, &["--port"]);
+        resolve_model_paths(&mut args, "/my models");
+        assert_eq!(args, ["--mmproj", "/my models/vision/mm proj.gguf", "-md=/abs/draft.gguf", "--alias", "a --port b"]);
+    }
+}
@@ -0,0 +1,234 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+use crate::http_pool::{self, RequestClass};
+use crate::models::{ProcessInfo, ProcessStatus};
+use crate::AppState;
+
+// llama-server processes outlive Arandu when it crashes or is killed, and
+// `running_processes` is only in memory. Every launch is recorded in the database with
+// its PID and removed when the server exits or is stopped. On startup the entries whose
+// PID still runs the recorded executable become orphans, probed on their port; the user
+// adopts them back into the process list or kills them.
+
+/// How often an adopted server is checked for having exited
+const ADOPTED_POLL: Duration = Duration::from_secs(5);
+const KILL_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredProcess {
+    pub process_id: String,
+    pub pid: u32,
+    /// Path of the llama-server binary, to tell a reused PID apart
+    pub executable: String,
+    pub model_path: String,
+    pub model_name: String,
+    pub host: String,
+    pub port: u16,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanProcess {
+    #[serde(flatten)]
+    pub process: RegisteredProcess,
+    /// The server answered on its port when Arandu started
+    pub responding: bool,
+}
+
+static ORPHANS: LazyLock<Mutex<Vec<OrphanProcess>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+pub async fn record(process: RegisteredProcess) {
+    if let Err(e) = crate::storage::run(move |db| db.processes().record(&process)).await {
+        eprintln!("[Registry] {}", e);
+    }
+}
+
+pub async fn forget(process_id: &str) {
+    let process_id = process_id.to_string();
+    if let Err(e) = crate::storage::run(move |db| db.processes().remove(&process_id)).await {
+        eprintln!("[Registry] {}", e);
+    }
+}
+
+/// Whether a process named `name` can be the one started from `executable`
+fn runs_executable(name: &str, executable: &str) -> bool {
+    // Recorded paths may come from either platform's separators
+    let file_name = executable.rsplit(['/', '\\']).next().unwrap_or(executable);
+    let stem = std::path::Path::new(file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_name);
+    !stem.is_empty() && name.to_lowercase().contains(&stem.to_lowercase())
+}
+
+fn is_alive(sys: &mut System, process: &RegisteredProcess) -> bool {
+    let pid = Pid::from_u32(process.pid);
+    sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+    sys.process(pid)
+        .is_some_and(|p| runs_executable(&p.name().to_string_lossy(), &process.executable))
+}
+
+async fn still_running(process: &RegisteredProcess) -> bool {
+    let process = process.clone();
+    tokio::task::spawn_blocking(move || is_alive(&mut System::new(), &process))
+        .await
+        .unwrap_or(false)
+}
+
+/// SIGTERM (where there is one), then a hard kill once `KILL_GRACE` runs out
+fn kill_pid(process: &RegisteredProcess) -> Result<(), String> {
+    let mut sys = System::new();
+    if !is_alive(&mut sys, process) {
+        return Ok(());
+    }
+    let pid = Pid::from_u32(process.pid);
+    if let Some(p) = sys.process(pid) {
+        if p.kill_with(sysinfo::Signal::Term) == Some(true) {
+            let deadline = std::time::Instant::now() + KILL_GRACE;
+            while std::time::Instant::now() < deadline {
+                std::thread::sleep(Duration::from_millis(200));
+                if !is_alive(&mut sys, process) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+    match sys.process(pid) {
+        Some(p) if !p.kill() => Err(format!("Failed to kill process {}", process.pid)),
+        _ => Ok(()),
+    }
+}
+
+async fn responds(process: &RegisteredProcess) -> bool {
+    let url = format!("{}/health", crate::suspend::local_base_url(&process.host, process.port));
+    // Any answer counts; 503 means the model is still loading
+    http_pool::get(&url, RequestClass::Health).send().await.is_ok()
+}
+
+/// Sort the registry left over from the last run into orphans and dead entries.
+/// Called once at startup, before anything is launched.
+pub async fn reattach() {
+    let registered = match crate::storage::run(|db| db.processes().all()).await {
+        Ok(registered) => registered,
+        Err(e) => {
+            eprintln!("[Registry] {}", e);
+            return;
+        }
+    };
+    let mut orphans = Vec::new();
+    for process in registered {
+        if !still_running(&process).await {
+            forget(&process.process_id).await;
+            continue;
+        }
+        let responding = responds(&process).await;
+        println!(
+            "[Registry] {} (PID {}) on port {} survived the last run{}",
+            process.model_name,
+            process.pid,
+            process.port,
+            if responding { "" } else { " but does not answer" }
+        );
+        orphans.push(OrphanProcess { process, responding });
+    }
+    *ORPHANS.lock().unwrap_or_else(|p| p.into_inner()) = orphans;
+}
+
+pub fn orphans() -> Vec<OrphanProcess> {
+    ORPHANS.lock().unwrap_or_else(|p| p.into_inner()).clone()
+}
+
+fn take_orphan(process_id: &str) -> Option<OrphanProcess> {
+    let mut orphans = ORPHANS.lock().unwrap_or_else(|p| p.into_inner());
+    let index = orphans.iter().position(|o| o.process.process_id == process_id)?;
+    Some(orphans.remove(index))
+}
+
+/// Track an orphan again as a running model. Arandu has no handle on it, so its output
+/// is not captured and its exit is noticed by polling the PID.
+pub async fn adopt(process_id: &str, state: &AppState) -> Result<ProcessInfo, String> {
+    let orphan = take_orphan(process_id).ok_or_else(|| format!("No orphaned process {}", process_id))?;
+    let process = orphan.process;
+    if !still_running(&process).await {
+        forget(&process.process_id).await;
+        return Err(format!("Process {} (PID {}) has exited", process.model_name, process.pid));
+    }
+
+    let info = ProcessInfo {
+        id: process.process_id.clone(),
+        model_path: process.model_path.clone(),
+        model_name: process.model_name.clone(),
+        host: process.host.clone(),
+        port: process.port,
+        command: vec![process.executable.clone()],
+        status: ProcessStatus::Starting,
+        output: vec![format!("[Arandu] Adopted PID {} from a previous run; its earlier output is not available", process.pid)],
+        created_at: process.started_at,
+        last_sent_line: Some(0),
+        preset_id: None,
+        instance: crate::process::next_instance(state, &process.model_path).await,
+        restart_count: 0,
+    };
+    state.running_processes.lock().await.insert(info.id.clone(), info.clone());
+
+    let base_url = crate::suspend::local_base_url(&process.host, process.port);
+    tokio::spawn(crate::process::probe_readiness(state.clone(), info.id.clone(), base_url));
+    tokio::spawn(watch_adopted(state.clone(), process));
+    Ok(info)
+}
+
+async fn watch_adopted(state: AppState, process: RegisteredProcess) {
+    loop {
+        tokio::time::sleep(ADOPTED_POLL).await;
+        if !state.running_processes.lock().await.contains_key(&process.process_id) {
+            return;
+        }
+        if still_running(&process).await {
+            continue;
+        }
+        if let Some(info) = state.running_processes.lock().await.get_mut(&process.process_id) {
+            info.status = ProcessStatus::Stopped;
+            info.output.push("Process exited".to_string());
+        }
+        forget(&process.process_id).await;
+        return;
+    }
+}
+
+/// Kill an orphan, or an adopted process `terminate_process` has no child handle for
+pub async fn kill(process_id: &str) -> Result<(), String> {
+    let process = match take_orphan(process_id) {
+        Some(orphan) => orphan.process,
+        None => {
+            let id = process_id.to_string();
+            crate::storage::run(move |db| db.processes().all())
+                .await?
+                .into_iter()
+                .find(|p| p.process_id == id)
+                .ok_or_else(|| format!("No registered process {}", process_id))?
+        }
+    };
+    let target = process.clone();
+    tokio::task::spawn_blocking(move || kill_pid(&target))
+        .await
+        .map_err(|e| format!("Kill task failed: {}", e))??;
+    forget(&process.process_id).await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reused_pids_of_other_programs_are_not_orphans() {
+        assert!(runs_executable("llama-server", "/opt/llama/llama-server"));
+        assert!(runs_executable("llama-server.exe", r"C:\llama\llama-server.exe"));
+        assert!(runs_executable("LLAMA-SERVER.EXE", "llama-server"));
+        assert!(!runs_executable("bash", "/opt/llama/llama-server"));
+        assert!(!runs_executable("llama-server", ""));
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sysinfo::{Pid, ProcessesToUpdate, System};
+use crate::log_classifier;
+use crate::models::{ProcessInfo, ProcessStatus};
+use crate::AppState;
+
+// One-call snapshot of every tracked process for the processes dashboard, so the
+// frontend does not poll status, logs and memory per process.
+
+const TAIL_LINES: usize = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessSummary {
+    pub id: String,
+    pub model_path: String,
+    pub model_name: String,
+    pub preset_id: Option<String>,
+    pub instance: u32,
+    pub host: String,
+    pub port: u16,
+    pub status: ProcessStatus,
+    pub created_at: DateTime<Utc>,
+    pub uptime_secs: i64,
+    pub pid: Option<u32>,
+    /// Resident memory of the server process (not VRAM)
+    pub memory_bytes: Option<u64>,
+    /// Generation and prompt speed from the most recent request timings in the log
+    pub gen_tps: Option<f64>,
+    pub prompt_tps: Option<f64>,
+    pub warnings: usize,
+    pub errors: usize,
+    pub last_lines: Vec<String>,
+}
+
+/// `tokens per second` from llama-server's timing lines, split into
+/// `(is_prompt, tps)`. Both lines look like
+/// `prompt eval time = 120.5 ms / 64 tokens (1.88 ms per token, 531.12 tokens per second)`.
+fn parse_timing_line(line: &str) -> Option<(bool, f64)> {
+    let lower = line.to_lowercase();
+    if !lower.contains("eval time") {
+        return None;
+    }
+    let before = lower.split("tokens per second").next()?;
+    let tps = before.rsplit([',', '(']).next()?.trim().parse::<f64>().ok()?;
+    Some((lower.contains("prompt eval time"), tps))
+}
+
+fn latest_speeds(output: &[String]) -> (Option<f64>, Option<f64>) {
+    let mut gen_tps = None;
+    let mut prompt_tps = None;
+    for line in output.iter().rev() {
+        match parse_timing_line(line) {
+            Some((true, tps)) if prompt_tps.is_none() => prompt_tps = Some(tps),
+            Some((false, tps)) if gen_tps.is_none() => gen_tps = Some(tps),
+            _ => {}
+        }
+        if gen_tps.is_some() && prompt_tps.is_some() {
+            break;
+        }
+    }
+    (gen_tps, prompt_tps)
+}
+
+fn summarize(process: &ProcessInfo, pid: Option<u32>, memory_bytes: Option<u64>) -> ProcessSummary {
+    let (gen_tps, prompt_tps) = latest_speeds(&process.output);
+    let severities: Vec<_> = process.output.iter().map(|l| log_classifier::severity_of_stored(l)).collect();
+    ProcessSummary {
+        id: process.id.clone(),
+        model_path: process.model_path.clone(),
+        model_name: process.model_name.clone(),
+        preset_id: process.preset_id.clone(),
+        instance: process.instance,
+        host: process.host.clone(),
+        port: process.port,
+        status: process.status.clone(),
+        created_at: process.created_at,
+        uptime_secs: (Utc::now() - process.created_at).num_seconds().max(0),
+        pid,
+        memory_bytes,
+        gen_tps,
+        prompt_tps,
+        warnings: severities.iter().filter(|s| **s == log_classifier::LogSeverity::Warning).count(),
+        errors: severities.iter().filter(|s| **s == log_classifier::LogSeverity::Error).count(),
+        last_lines: process.output.iter().rev().take(TAIL_LINES).rev().cloned().collect(),
+    }
+}
+
+pub async fn all_processes_summary(state: &AppState) -> Vec<ProcessSummary> {
+    let processes: Vec<ProcessInfo> = state.running_processes.lock().await.values().cloned().collect();
+
+    let mut pids = Vec::new();
+    {
+        let child_processes = state.child_processes.lock().await;
+        for process in &processes {
+            let Some(handle) = child_processes.get(&process.id) else { continue };
+            let mut handle = handle.lock().await;
+            if let Some(pid) = handle.get_child_mut().and_then(|child| child.id()) {
+                pids.push((process.id.clone(), pid));
+            }
+        }
+    }
+
+    let memory = {
+        let pids = pids.clone();
+        tokio::task::spawn_blocking(move || {
+            let sys_pids: Vec<Pid> = pids.iter().map(|(_, pid)| Pid::from_u32(*pid)).collect();
+            let mut sys = System::new();
+            sys.refresh_processes(ProcessesToUpdate::Some(&sys_pids), true);
+            pids.iter()
+                .map(|(id, pid)| (id.clone(), sys.process(Pid::from_u32(*pid)).map(|p| p.memory())))
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default()
+    };
+
+    let mut summaries: Vec<ProcessSummary> = processes
+        .iter()
+        .map(|process| {
+            let pid = pids.iter().find(|(id, _)| *id == process.id).map(|(_, pid)| *pid);
+            let memory_bytes = memory.iter().find(|(id, _)| *id == process.id).and_then(|(_, m)| *m);
+            summarize(process, pid, memory_bytes)
+        })
+        .collect();
+    summaries.sort_by_key(|a| a.created_at);
+    summaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_speeds_reads_most_recent_timings() {
+        let output = vec![
+            "[INFO] prompt eval time =     120.50 ms /    64 tokens (    1.88 ms per token,   531.12 tokens per second)".to_string(),
+            "[INFO]        eval time =    1234.56 ms /    64 tokens (   19.29 ms per token,    51.84 tokens per second)".to_string(),
+            "[INFO]        eval time =     900.00 ms /    60 tokens (   15.00 ms per token,    66.67 tokens per second)".to_string(),
+            "[INFO] srv  update_slots: all slots are idle".to_string(),
+        ];
+        assert_eq!(latest_speeds(&output), (Some(66.67), Some(531.12)));
+        assert_eq!(latest_speeds(&[]), (None, None));
+    }
+}
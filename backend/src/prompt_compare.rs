@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use std::time::Instant;
+use crate::http_pool::{self, RequestClass};
+use crate::models::ProcessStatus;
+use crate::parameter_controller::GenerationParams;
+use crate::task_manager::Task;
+use crate::AppState;
+
+// Runs one prompt against several models so their answers can be compared side by side.
+// Models run one at a time so each has the GPU to itself: one that is not running is
+// launched with its default preset, prompted and stopped again; one already running is
+// prompted as is and left running. Every run gets the same seed and sampling parameters,
+// with temperature 0 unless set, so differences come from the models and their chat
+// templates rather than from sampling.
+
+const DEFAULT_SEED: u64 = 42;
+const DEFAULT_MAX_TOKENS: i32 = 512;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptRun {
+    pub model_path: String,
+    pub model_name: String,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    /// Launch until `/health` answers; `None` when the model was already running
+    pub load_ms: Option<u64>,
+    pub generation_ms: Option<u64>,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: Option<u64>,
+    pub tokens_per_second: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptComparison {
+    pub prompt: String,
+    pub seed: u64,
+    pub params: GenerationParams,
+    pub runs: Vec<PromptRun>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// `params` with the defaults every run shares filled in
+fn fixed_params(params: Option<GenerationParams>) -> GenerationParams {
+    let mut params = params.unwrap_or_default();
+    params.temperature = params.temperature.or(Some(0.0));
+    params.max_tokens = params.max_tokens.or(Some(DEFAULT_MAX_TOKENS));
+    params
+}
+
+/// The prompt as a single user message with the shared parameters
+fn request_body(prompt: &str, seed: u64, params: &GenerationParams) -> Value {
+    let mut body = serde_json::to_value(params).unwrap_or_else(|_| json!({}));
+    body["messages"] = json!([{ "role": "user", "content": prompt }]);
+    body["seed"] = json!(seed);
+    body["stream"] = json!(false);
+    body
+}
+
+/// The answer and `(prompt, completion)` token counts from one server
+async fn prompt_server(base_url: &str, body: &Value) -> Result<(String, Option<(u64, u64)>), String> {
+    let url = format!("{}/v1/chat/completions", base_url);
+    let request = http_pool::client_for(&url).post(&url).json(body);
+    let response = http_pool::with_timeout(request, RequestClass::Completion)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("llama-server returned {}: {}", status, text));
+    }
+    let value: Value = response.json().await.map_err(|e| format!("Invalid response: {}", e))?;
+    let output = value["choices"][0]["message"]["content"].as_str().unwrap_or_default().to_string();
+    Ok((output, crate::proxy_guard::usage_tokens(&value)))
+}
+
+async fn run_model(state: &AppState, model_path: &str, body: &Value, task: &Task) -> PromptRun {
+    let mut run = PromptRun {
+        model_path: model_path.to_string(),
+        model_name: Path::new(model_path).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default(),
+        output: None,
+        error: None,
+        load_ms: None,
+        generation_ms: None,
+        prompt_tokens: None,
+        completion_tokens: None,
+        tokens_per_second: None,
+    };
+    let running = state
+        .running_processes
+        .lock()
+        .await
+        .values()
+        .filter(|p| p.model_path == model_path && matches!(p.status, ProcessStatus::Running))
+        .min_by_key(|p| p.instance)
+        .map(|p| crate::suspend::local_base_url(&p.host, p.port));
+
+    let (base_url, launched) = match running {
+        Some(base_url) => (base_url, None),
+        None => {
+            let started = Instant::now();
+            let launched = match crate::launch_with_preset(model_path.to_string(), None, state).await {
+                Ok(launched) => launched,
+                Err(e) => {
+                    run.error = Some(e);
+                    return run;
+                }
+            };
+            let base_url = crate::suspend::local_base_url(&launched.server_host, launched.server_port);
+            if let Err(e) = task.run(crate::suspend::wait_until_healthy(&base_url)).await.and_then(|healthy| healthy) {
+                run.error = Some(e);
+                stop(state, launched.process_id).await;
+                return run;
+            }
+            run.load_ms = Some(started.elapsed().as_millis() as u64);
+            (base_url, Some(launched.process_id))
+        }
+    };
+
+    let started = Instant::now();
+    match task.run(prompt_server(&base_url, body)).await.and_then(|answer| answer) {
+        Ok((output, usage)) => {
+            let elapsed = started.elapsed();
+            run.output = Some(output);
+            run.generation_ms = Some(elapsed.as_millis() as u64);
+            if let Some((prompt_tokens, completion_tokens)) = usage {
+                run.prompt_tokens = Some(prompt_tokens);
+                run.completion_tokens = Some(completion_tokens);
+                run.tokens_per_second = (elapsed.as_secs_f64() > 0.0).then(|| completion_tokens as f64 / elapsed.as_secs_f64());
+            }
+        }
+        Err(e) => run.error = Some(e),
+    }
+    if let Some(process_id) = launched {
+        stop(state, process_id).await;
+    }
+    run
+}
+
+async fn stop(state: &AppState, process_id: String) {
+    if let Err(e) = crate::process::terminate_process(process_id.clone(), state).await {
+        eprintln!("[PromptTest] Failed to stop {}: {}", process_id, e);
+    }
+}
+
+/// Run `prompt` on each of `model_paths` in turn. A model that fails records its error
+/// and the rest still run; cancelling `task` skips the models not reached yet.
+pub async fn compare(
+    state: &AppState,
+    prompt: String,
+    model_paths: Vec<String>,
+    params: Option<GenerationParams>,
+    seed: Option<u64>,
+    task: &Task,
+) -> PromptComparison {
+    let seed = seed.unwrap_or(DEFAULT_SEED);
+    let params = fixed_params(params);
+    let body = request_body(&prompt, seed, &params);
+
+    let mut runs = Vec::new();
+    for (step, model_path) in model_paths.iter().enumerate() {
+        if task.is_cancelled() {
+            break;
+        }
+        task.report_steps(step, model_paths.len(), model_path.clone());
+        runs.push(run_model(state, model_path, &body, task).await);
+    }
+    PromptComparison { prompt, seed, params, runs, created_at: Utc::now() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_model_gets_the_same_seeded_request() {
+        let params = fixed_params(Some(GenerationParams { top_k: Some(20.0), max_tokens: Some(64), ..Default::default() }));
+        let body = request_body("Write a haiku", 7, &params);
+        assert_eq!(body["seed"], 7);
+        assert_eq!(body["temperature"], 0.0);
+        assert_eq!((body["top_k"].as_f64(), body["max_tokens"].as_i64()), (Some(20.0), Some(64)));
+        assert_eq!(body["messages"][0]["content"], "Write a haiku");
+        assert_eq!(body["stream"], false);
+        assert_eq!(fixed_params(None).max_tokens, Some(DEFAULT_MAX_TOKENS));
+    }
+}
@@ -0,0 +1,672 @@
+use axum::{
+    body::Body,
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use crate::models::{GlobalConfig, ProxyApiKey};
+use crate::openai_types::{OpenAIError, OpenAIErrorResponse};
+use crate::tunnels::TunnelProvider;
+
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+/// Paths answered without an API key, so monitors can tell the server is up
+pub(crate) const OPEN_PATHS: [&str; 2] = ["/health", "/openapi.json"];
+
+/// Connection-level limits applied to every proxy request
+#[derive(Debug, Clone)]
+pub struct ProxyLimits {
+    /// Requests per minute per client IP; 0 disables rate limiting
+    pub requests_per_minute: u32,
+    pub max_body_bytes: usize,
+    /// Time allowed for a client to finish sending its request body
+    pub read_timeout: Duration,
+    /// Time a streaming response may wait for a stalled client before it is dropped
+    pub write_timeout: Duration,
+}
+
+impl ProxyLimits {
+    pub fn from_config(config: &GlobalConfig) -> Self {
+        Self {
+            requests_per_minute: config.network_rate_limit_per_minute,
+            max_body_bytes: (config.network_max_body_mb.max(1) as usize) * 1024 * 1024,
+            read_timeout: Duration::from_secs(config.network_read_timeout_secs.max(1)),
+            write_timeout: Duration::from_secs(config.network_write_timeout_secs.max(1)),
+        }
+    }
+}
+
+/// Sliding-window request counter keyed by client IP, or by API key id
+#[derive(Debug)]
+pub struct RateLimiter<K = IpAddr> {
+    hits: Mutex<HashMap<K, VecDeque<Instant>>>,
+}
+
+impl<K> Default for RateLimiter<K> {
+    fn default() -> Self {
+        Self { hits: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    /// Record a request and return whether it fits in the window
+    pub fn check(&self, key: K, limit: u32, now: Instant) -> bool {
+        if limit == 0 {
+            return true;
+        }
+
+        let mut hits = lock_or_recover(&self.hits);
+
+        if hits.len() > 1024 {
+            hits.retain(|_, times| {
+                times.back().is_some_and(|last| now.duration_since(*last) < RATE_LIMIT_WINDOW)
+            });
+        }
+
+        let times = hits.entry(key).or_default();
+        while times.front().is_some_and(|first| now.duration_since(*first) >= RATE_LIMIT_WINDOW) {
+            times.pop_front();
+        }
+
+        if times.len() >= limit as usize {
+            return false;
+        }
+        times.push_back(now);
+        true
+    }
+}
+
+/// An IPv4 or IPv6 network in CIDR notation, e.g. `192.168.0.0/16`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNetwork {
+    /// Parse `addr/prefix`; a bare address is treated as a single host
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let raw = raw.trim();
+        let (addr_part, prefix_part) = match raw.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (raw, None),
+        };
+        let addr: IpAddr = addr_part
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid network address: {}", raw))?;
+        let max_prefix = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix_part {
+            Some(p) => p
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max_prefix)
+                .ok_or_else(|| format!("Invalid prefix length in {}", raw))?,
+            None => max_prefix,
+        };
+        Ok(Self { addr, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // Dual-stack listeners report IPv4 clients as ::ffff:a.b.c.d
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            other => other,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// Parse a list of CIDR strings, failing on the first invalid entry
+pub fn parse_allowlist(entries: &[String]) -> Result<Vec<IpNetwork>, String> {
+    entries
+        .iter()
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| IpNetwork::parse(entry))
+        .collect()
+}
+
+/// Loopback and the private, unique-local and link-local ranges
+static LOCAL_NETWORKS: LazyLock<Vec<IpNetwork>> = LazyLock::new(|| {
+    parse_allowlist(&crate::models::default_network_allowed_cidrs()).expect("default allowlist should parse")
+});
+
+fn is_local_network(ip: IpAddr) -> bool {
+    LOCAL_NETWORKS.iter().any(|network| network.contains(ip))
+}
+
+/// Per-client usage as seen by the proxy since it was activated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkClientStats {
+    pub ip: String,
+    /// Masked API keys the client has presented
+    pub api_keys: Vec<String>,
+    pub request_count: u64,
+    pub rejected_count: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub last_path: String,
+    pub blocked: bool,
+}
+
+impl NetworkClientStats {
+    fn new(ip: IpAddr, now: DateTime<Utc>) -> Self {
+        Self {
+            ip: ip.to_string(),
+            api_keys: Vec::new(),
+            request_count: 0,
+            rejected_count: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            first_seen: now,
+            last_seen: now,
+            last_path: String::new(),
+            blocked: false,
+        }
+    }
+}
+
+/// Access log keyed by client IP plus the set of revoked clients
+#[derive(Debug, Default)]
+pub struct ClientRegistry {
+    clients: Mutex<HashMap<IpAddr, NetworkClientStats>>,
+    blocked: Mutex<HashSet<IpAddr>>,
+}
+
+fn lock_or_recover<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+impl ClientRegistry {
+    pub fn with_blocked(blocked: &[String]) -> Self {
+        let registry = Self::default();
+        {
+            let mut set = lock_or_recover(&registry.blocked);
+            set.extend(blocked.iter().filter_map(|ip| ip.trim().parse::<IpAddr>().ok()));
+        }
+        registry
+    }
+
+    pub fn is_blocked(&self, ip: IpAddr) -> bool {
+        lock_or_recover(&self.blocked).contains(&ip)
+    }
+
+    pub fn set_blocked(&self, ip: IpAddr, blocked: bool) {
+        let mut set = lock_or_recover(&self.blocked);
+        if blocked {
+            set.insert(ip);
+        } else {
+            set.remove(&ip);
+        }
+    }
+
+    pub fn record_request(&self, ip: IpAddr, path: &str, api_key: Option<String>, rejected: bool) {
+        let now = Utc::now();
+        let mut clients = lock_or_recover(&self.clients);
+        let entry = clients.entry(ip).or_insert_with(|| NetworkClientStats::new(ip, now));
+        entry.last_seen = now;
+        entry.last_path = path.to_string();
+        if rejected {
+            entry.rejected_count += 1;
+        } else {
+            entry.request_count += 1;
+        }
+        if let Some(key) = api_key {
+            if !entry.api_keys.contains(&key) {
+                entry.api_keys.push(key);
+            }
+        }
+    }
+
+    pub fn record_tokens(&self, ip: IpAddr, prompt_tokens: u64, completion_tokens: u64) {
+        let mut clients = lock_or_recover(&self.clients);
+        if let Some(entry) = clients.get_mut(&ip) {
+            entry.prompt_tokens += prompt_tokens;
+            entry.completion_tokens += completion_tokens;
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<NetworkClientStats> {
+        let blocked = lock_or_recover(&self.blocked).clone();
+        let mut list: Vec<NetworkClientStats> = lock_or_recover(&self.clients)
+            .iter()
+            .map(|(ip, stats)| {
+                let mut stats = stats.clone();
+                stats.blocked = blocked.contains(ip);
+                stats
+            })
+            .collect();
+        list.sort_by_key(|stats| std::cmp::Reverse(stats.last_seen));
+        list
+    }
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let token = value.strip_prefix("Bearer ").unwrap_or(value).trim();
+    (!token.is_empty()).then_some(token)
+}
+
+/// Short, non-reversible label for a bearer token so it can be shown in the access log
+pub fn api_key_label(headers: &HeaderMap) -> Option<String> {
+    let token = bearer_token(headers)?;
+    let prefix: String = token.chars().take(4).collect();
+    Some(format!("{}…({} chars)", prefix, token.chars().count()))
+}
+
+/// SHA-256 of an API key, as stored in `ProxyApiKey.key_hash`
+pub fn hash_api_key(key: &str) -> String {
+    Sha256::digest(key.trim().as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// A new random key and the entry to store for it
+pub fn new_api_key(name: &str, requests_per_minute: u32) -> (String, ProxyApiKey) {
+    let key = format!("sk-arandu-{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    let entry = ProxyApiKey {
+        id: format!("key-{}", Utc::now().timestamp_micros()),
+        name: name.trim().to_string(),
+        key_hash: hash_api_key(&key),
+        prefix: key.chars().take(14).collect(),
+        requests_per_minute,
+        created_at: Utc::now(),
+        launch_profile: None,
+    };
+    (key, entry)
+}
+
+/// Read `usage` token counts from an OpenAI-style response or stream chunk
+pub fn usage_tokens(value: &serde_json::Value) -> Option<(u64, u64)> {
+    let usage = value.get("usage")?;
+    let prompt = usage.get("prompt_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+    let completion = usage.get("completion_tokens").and_then(|v| v.as_u64()).unwrap_or(0);
+    Some((prompt, completion))
+}
+
+/// The API key a request was authorized with, for handlers that act on the key's launch profile
+#[derive(Debug, Clone)]
+pub struct AuthorizedKey(pub ProxyApiKey);
+
+/// Shared state for the guard middleware
+#[derive(Debug)]
+pub struct ProxyGuard {
+    pub limits: ProxyLimits,
+    pub rate_limiter: RateLimiter,
+    pub clients: ClientRegistry,
+    allowlist: Mutex<Vec<IpNetwork>>,
+    api_keys: Mutex<Vec<ProxyApiKey>>,
+    key_limiter: RateLimiter<String>,
+}
+
+impl ProxyGuard {
+    pub fn new(limits: ProxyLimits, blocked_clients: &[String], allowlist: Vec<IpNetwork>, api_keys: Vec<ProxyApiKey>) -> Self {
+        Self {
+            limits,
+            rate_limiter: RateLimiter::default(),
+            clients: ClientRegistry::with_blocked(blocked_clients),
+            allowlist: Mutex::new(allowlist),
+            api_keys: Mutex::new(api_keys),
+            key_limiter: RateLimiter::default(),
+        }
+    }
+
+    pub fn set_api_keys(&self, api_keys: Vec<ProxyApiKey>) {
+        *lock_or_recover(&self.api_keys) = api_keys;
+    }
+
+    /// Check a request's bearer token against the configured keys and the key's own rate
+    /// limit, returning the matching key. With no keys configured every request passes,
+    /// except ones from the internet, which are refused.
+    #[allow(clippy::result_large_err)]
+    fn authorize(&self, token: Option<&str>, from_internet: bool, now: Instant) -> Result<Option<ProxyApiKey>, Response> {
+        let api_keys = lock_or_recover(&self.api_keys);
+        if api_keys.is_empty() && from_internet {
+            return Err(guard_error(
+                StatusCode::UNAUTHORIZED,
+                "invalid_api_key",
+                "Requests from the internet need an API key; none are configured".to_string(),
+            ));
+        }
+        if api_keys.is_empty() {
+            return Ok(None);
+        }
+        let hash = token.map(hash_api_key);
+        let Some(key) = api_keys.iter().find(|k| Some(&k.key_hash) == hash.as_ref()) else {
+            let mut response = guard_error(
+                StatusCode::UNAUTHORIZED,
+                "invalid_api_key",
+                if token.is_some() { "Invalid API key" } else { "An API key is required: send Authorization: Bearer <key>" }.to_string(),
+            );
+            response.headers_mut().insert(header::WWW_AUTHENTICATE, header::HeaderValue::from_static("Bearer"));
+            return Err(response);
+        };
+        if !self.key_limiter.check(key.id.clone(), key.requests_per_minute, now) {
+            return Err(guard_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limit_exceeded",
+                format!("Rate limit of {} requests per minute exceeded for API key {}", key.requests_per_minute, key.name),
+            ));
+        }
+        Ok(Some(key.clone()))
+    }
+
+    /// Check the allowlist, revocations and API keys for a request from `ip`. Requests from
+    /// the internet (through a tunnel or a router port mapping) skip the allowlist, which
+    /// is about the local network; they need a key.
+    #[allow(clippy::result_large_err)]
+    fn admit(&self, ip: IpAddr, from_internet: bool, path: &str, token: Option<&str>, now: Instant) -> Result<Option<ProxyApiKey>, Response> {
+        if !from_internet && !self.is_allowed(ip) {
+            return Err(guard_error(
+                StatusCode::FORBIDDEN,
+                "address_not_allowed",
+                "This address is not allowed to use the network server".to_string(),
+            ));
+        }
+        if self.clients.is_blocked(ip) {
+            return Err(guard_error(
+                StatusCode::FORBIDDEN,
+                "access_revoked",
+                "Access for this client has been revoked by the host".to_string(),
+            ));
+        }
+        if OPEN_PATHS.contains(&path) {
+            return Ok(None);
+        }
+        self.authorize(token, from_internet, now)
+    }
+
+    pub fn set_allowlist(&self, allowlist: Vec<IpNetwork>) {
+        *lock_or_recover(&self.allowlist) = allowlist;
+    }
+
+    /// An empty allowlist admits every address
+    pub fn is_allowed(&self, ip: IpAddr) -> bool {
+        let allowlist = lock_or_recover(&self.allowlist);
+        allowlist.is_empty() || allowlist.iter().any(|network| network.contains(ip))
+    }
+}
+
+fn guard_error(status: StatusCode, error_type: &str, message: String) -> Response {
+    let body = OpenAIErrorResponse {
+        error: OpenAIError {
+            message,
+            error_type: error_type.to_string(),
+            code: Some(status.as_u16().to_string()),
+        },
+    };
+    (status, Json(body)).into_response()
+}
+
+/// The client behind a tunnel of one of `providers`, from the header that provider
+/// sets. cloudflared names it in `CF-Connecting-IP`; tailscale appends it to
+/// `X-Forwarded-For`. A tailnet client can send its own `CF-Connecting-IP`, so while a
+/// Tailscale tunnel runs only the last `X-Forwarded-For` hop counts; Cloudflare appends
+/// the client there too.
+fn forwarded_client(headers: &HeaderMap, providers: &[TunnelProvider]) -> Option<IpAddr> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    if providers.contains(&TunnelProvider::Tailscale) {
+        header("x-forwarded-for")?.rsplit(',').next()?.trim().parse().ok()
+    } else if providers.contains(&TunnelProvider::Cloudflared) {
+        header("cf-connecting-ip")?.trim().parse().ok()
+    } else {
+        None
+    }
+}
+
+/// Enforce the allowlist, revocations and API keys, rate-limit by client IP and API key, and buffer the request body under the size and read-time limits
+pub async fn guard_request(
+    State(guard): State<Arc<ProxyGuard>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    // While a tunnel runs, loopback traffic may come from the internet through it;
+    // such requests are judged by the client they were forwarded for
+    let forwarded = addr
+        .ip()
+        .is_loopback()
+        .then(|| forwarded_client(request.headers(), &crate::tunnels::forwarding_providers()))
+        .flatten();
+    let ip = forwarded.unwrap_or(addr.ip());
+    // A router port mapping lets addresses outside the local network connect directly
+    let from_internet = forwarded.is_some() || (crate::port_mapping::is_mapped() && !is_local_network(ip));
+    let limits = &guard.limits;
+    let path = request.uri().path().to_string();
+    let api_key = api_key_label(request.headers());
+
+    match guard.admit(ip, from_internet, &path, bearer_token(request.headers()), Instant::now()) {
+        Ok(Some(key)) => {
+            request.extensions_mut().insert(AuthorizedKey(key));
+        }
+        Ok(None) => {}
+        Err(response) => {
+            guard.clients.record_request(ip, &path, api_key, true);
+            eprintln!("[Proxy] Rejected request from {} for {}: {}", ip, path, response.status());
+            return response;
+        }
+    }
+
+    // Local tools on the host machine are not throttled
+    if !ip.is_loopback() && !guard.rate_limiter.check(ip, limits.requests_per_minute, Instant::now()) {
+        guard.clients.record_request(ip, &path, api_key, true);
+        eprintln!("[Proxy] Rate limit exceeded for {}", ip);
+        return guard_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limit_exceeded",
+            format!("Rate limit of {} requests per minute exceeded", limits.requests_per_minute),
+        );
+    }
+
+    guard.clients.record_request(ip, &path, api_key, false);
+
+    let declared_len = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if declared_len.is_some_and(|len| len > limits.max_body_bytes) {
+        return guard_error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            "request_too_large",
+            format!("Request body exceeds {} bytes", limits.max_body_bytes),
+        );
+    }
+
+    let (parts, body) = request.into_parts();
+    let bytes = match tokio::time::timeout(limits.read_timeout, axum::body::to_bytes(body, limits.max_body_bytes)).await {
+        Ok(Ok(bytes)) => bytes,
+        Ok(Err(_)) => {
+            return guard_error(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "request_too_large",
+                format!("Request body exceeds {} bytes", limits.max_body_bytes),
+            );
+        }
+        Err(_) => {
+            eprintln!("[Proxy] Timed out reading request body from {}", ip);
+            return guard_error(
+                StatusCode::REQUEST_TIMEOUT,
+                "request_timeout",
+                "Timed out reading request body".to_string(),
+            );
+        }
+    };
+
+    next.run(Request::from_parts(parts, Body::from(bytes))).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limiter_blocks_after_limit_and_recovers_after_window() {
+        let limiter = RateLimiter::default();
+        let ip: IpAddr = "192.168.1.20".parse().unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.check(ip, 2, start));
+        assert!(limiter.check(ip, 2, start));
+        assert!(!limiter.check(ip, 2, start));
+
+        let other: IpAddr = "192.168.1.21".parse().unwrap();
+        assert!(limiter.check(other, 2, start));
+
+        assert!(limiter.check(ip, 2, start + RATE_LIMIT_WINDOW));
+    }
+
+    #[test]
+    fn client_registry_tracks_requests_tokens_and_blocks() {
+        let registry = ClientRegistry::with_blocked(&["10.0.0.9".to_string(), "not-an-ip".to_string()]);
+        let ip: IpAddr = "10.0.0.7".parse().unwrap();
+
+        registry.record_request(ip, "/v1/chat/completions", Some("sk-a…(20 chars)".to_string()), false);
+        registry.record_request(ip, "/v1/models", None, true);
+        registry.record_tokens(ip, 12, 30);
+
+        let snapshot = registry.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].request_count, 1);
+        assert_eq!(snapshot[0].rejected_count, 1);
+        assert_eq!(snapshot[0].completion_tokens, 30);
+        assert_eq!(snapshot[0].last_path, "/v1/models");
+
+        assert!(registry.is_blocked("10.0.0.9".parse().unwrap()));
+        registry.set_blocked(ip, true);
+        assert!(registry.snapshot()[0].blocked);
+    }
+
+    #[test]
+    fn ip_network_matches_default_private_ranges() {
+        let networks = parse_allowlist(&crate::models::default_network_allowed_cidrs()).unwrap();
+        let allowed = |ip: &str| networks.iter().any(|n| n.contains(ip.parse().unwrap()));
+
+        assert!(allowed("127.0.0.1"));
+        assert!(allowed("::1"));
+        assert!(allowed("192.168.1.50"));
+        assert!(allowed("172.31.255.1"));
+        assert!(allowed("::ffff:10.1.2.3"));
+        assert!(!allowed("172.32.0.1"));
+        assert!(!allowed("8.8.8.8"));
+        assert!(!allowed("2001:4860::8888"));
+    }
+
+    #[test]
+    fn ip_network_parse_rejects_bad_input() {
+        assert!(IpNetwork::parse("10.0.0.0/33").is_err());
+        assert!(IpNetwork::parse("not-an-ip/8").is_err());
+        assert_eq!(IpNetwork::parse("10.0.0.1").unwrap().to_string(), "10.0.0.1/32");
+        assert!(IpNetwork::parse("0.0.0.0/0").unwrap().contains("203.0.113.9".parse().unwrap()));
+    }
+
+    #[test]
+    fn api_keys_are_required_once_configured_and_limited_per_key() {
+        let limits = ProxyLimits { requests_per_minute: 0, max_body_bytes: 1024, read_timeout: Duration::from_secs(1), write_timeout: Duration::from_secs(1) };
+        let guard = ProxyGuard::new(limits, &[], Vec::new(), Vec::new());
+        let now = Instant::now();
+        assert!(guard.authorize(None, false, now).unwrap().is_none());
+        assert_eq!(guard.authorize(None, true, now).err().map(|r| r.status()), Some(StatusCode::UNAUTHORIZED));
+
+        let (key, entry) = new_api_key(" Laptop ", 2);
+        assert_eq!((entry.name.as_str(), entry.key_hash.len()), ("Laptop", 64));
+        assert!(key.starts_with(&entry.prefix) && !entry.key_hash.contains(&key));
+        guard.set_api_keys(vec![entry]);
+
+        let status = |token: Option<&str>| guard.authorize(token, false, now).err().map(|r| r.status());
+        assert_eq!(status(None), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(status(Some("sk-arandu-wrong")), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(guard.authorize(Some(&key), true, now).unwrap().map(|k| k.name), Some("Laptop".to_string()));
+        assert_eq!(status(Some(&key)), None);
+        assert_eq!(status(Some(&key)), Some(StatusCode::TOO_MANY_REQUESTS));
+        assert!(guard.authorize(Some(&key), false, now + RATE_LIMIT_WINDOW).is_ok());
+    }
+
+    #[test]
+    fn tunneled_requests_are_judged_by_the_forwarded_client() {
+        let cloudflared = [TunnelProvider::Cloudflared];
+        let tailscale = [TunnelProvider::Tailscale];
+        let mut headers = HeaderMap::new();
+        assert_eq!(forwarded_client(&headers, &cloudflared), None);
+        headers.insert("x-forwarded-for", "10.0.0.1, 203.0.113.7".parse().unwrap());
+        assert_eq!(forwarded_client(&headers, &cloudflared), None);
+        assert_eq!(forwarded_client(&headers, &tailscale), Some("203.0.113.7".parse().unwrap()));
+        headers.insert("cf-connecting-ip", "198.51.100.4".parse().unwrap());
+        assert_eq!(forwarded_client(&headers, &cloudflared), Some("198.51.100.4".parse().unwrap()));
+        assert_eq!(forwarded_client(&headers, &[]), None);
+
+        // A tailnet client claiming to be the host through Cloudflare's header
+        let mut spoofed = HeaderMap::new();
+        spoofed.insert("cf-connecting-ip", "127.0.0.1".parse().unwrap());
+        spoofed.insert("x-forwarded-for", "127.0.0.1, 100.101.102.103".parse().unwrap());
+        assert_eq!(forwarded_client(&spoofed, &tailscale), Some("100.101.102.103".parse().unwrap()));
+        spoofed.remove("x-forwarded-for");
+        assert_eq!(forwarded_client(&spoofed, &[TunnelProvider::Cloudflared, TunnelProvider::Tailscale]), None);
+    }
+
+    #[test]
+    fn internet_clients_need_a_key_instead_of_the_default_allowlist() {
+        let limits = ProxyLimits { requests_per_minute: 0, max_body_bytes: 1024, read_timeout: Duration::from_secs(1), write_timeout: Duration::from_secs(1) };
+        let allowlist = parse_allowlist(&crate::models::default_network_allowed_cidrs()).unwrap();
+        let (key, entry) = new_api_key("Phone", 0);
+        let guard = ProxyGuard::new(limits, &["203.0.113.9".to_string()], allowlist, vec![entry]);
+        let now = Instant::now();
+        let status = |ip: &str, from_internet: bool, token: Option<&str>| {
+            guard.admit(ip.parse().unwrap(), from_internet, "/v1/models", token, now).err().map(|r| r.status())
+        };
+
+        // Cloudflare clients come from public addresses, Tailscale ones from 100.64.0.0/10
+        assert_eq!(status("203.0.113.7", true, Some(&key)), None);
+        assert_eq!(status("100.101.102.103", true, Some(&key)), None);
+        assert_eq!(status("203.0.113.7", true, None), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(status("203.0.113.9", true, Some(&key)), Some(StatusCode::FORBIDDEN));
+        // Without a tunnel the allowlist still applies
+        assert_eq!(status("203.0.113.7", false, Some(&key)), Some(StatusCode::FORBIDDEN));
+        assert_eq!(status("192.168.1.5", false, Some(&key)), None);
+        assert!(is_local_network("192.168.1.5".parse().unwrap()) && !is_local_network("203.0.113.7".parse().unwrap()));
+
+        // Nobody from the internet gets in once every key is deleted
+        guard.set_api_keys(Vec::new());
+        assert_eq!(status("203.0.113.7", true, None), Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(status("192.168.1.5", false, None), None);
+    }
+
+    #[test]
+    fn rate_limiter_zero_limit_disables_checks() {
+        let limiter = RateLimiter::default();
+        let ip: IpAddr = "10.0.0.5".parse().unwrap();
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert!(limiter.check(ip, 0, now));
+        }
+    }
+}
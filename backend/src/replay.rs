@@ -0,0 +1,147 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::Path;
+use crate::http_pool::{self, RequestClass};
+use crate::models::{ChatLogMessage, ChatMessageMetadata};
+use crate::AppState;
+
+// "Repeat exactly": send an assistant message's prompt (every message before it) again
+// with the seed and parameters recorded in its metadata, to the running server of the
+// model that wrote it. llama.cpp is deterministic for a given seed, parameters and
+// build, so a different answer points at a changed model, build or server setting.
+// The repeat is returned for comparison and never added to the chat.
+
+/// Request fields that belong to one particular send, not to the sampling setup
+const PER_SEND_FIELDS: &[&str] = &["messages", "stream", "stream_options", "chat_id"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayResult {
+    pub model: String,
+    pub seed: i64,
+    pub params: Value,
+    pub original: String,
+    pub output: String,
+    pub identical: bool,
+    pub prompt_tokens: Option<u64>,
+    pub completion_tokens: Option<u64>,
+}
+
+/// `seed` out of a message's recorded request parameters
+pub fn recorded_seed(params: Option<&Value>) -> Option<i64> {
+    params?.get("seed")?.as_i64()
+}
+
+/// The request that produced assistant message `message_index`, rebuilt from the
+/// messages before it and its metadata
+fn replay_request(messages: &[ChatLogMessage], message_index: usize, meta: &ChatMessageMetadata) -> Result<Value, String> {
+    let message = messages
+        .get(message_index)
+        .ok_or_else(|| format!("Message index {} is out of range (chat has {} messages)", message_index, messages.len()))?;
+    if message.role != "assistant" {
+        return Err(format!("Message {} is not an assistant message", message_index));
+    }
+    let seed = meta
+        .seed
+        .or_else(|| recorded_seed(meta.params.as_ref()))
+        .ok_or_else(|| format!("Message {} has no recorded seed and cannot be repeated exactly", message_index))?;
+
+    let mut body = match &meta.params {
+        Some(Value::Object(params)) => params.clone(),
+        _ => serde_json::Map::new(),
+    };
+    body.retain(|key, _| !PER_SEND_FIELDS.contains(&key.as_str()));
+    let prompt: Vec<Value> = messages[..message_index]
+        .iter()
+        .map(|m| json!({ "role": m.role, "content": m.content }))
+        .collect();
+    body.insert("messages".to_string(), Value::Array(prompt));
+    body.insert("seed".to_string(), json!(seed));
+    body.insert("stream".to_string(), json!(false));
+    Ok(Value::Object(body))
+}
+
+/// Repeat assistant message `message_index` of the chat logged at `chat_path`
+pub async fn repeat(state: &AppState, chat_path: &Path, chat_id: &str, message_index: usize) -> Result<ReplayResult, String> {
+    let messages = crate::chat_store::messages(chat_path, chat_id)?;
+    let meta = crate::read_chat_message_metadata(chat_path)
+        .into_iter()
+        .find(|m| m.message_index == message_index)
+        .ok_or_else(|| format!("Message {} has no recorded parameters and cannot be repeated exactly", message_index))?;
+    let body = replay_request(&messages, message_index, &meta)?;
+
+    let model = meta
+        .model
+        .clone()
+        .or_else(|| messages[message_index].model.clone())
+        .ok_or_else(|| format!("Message {} does not record its model", message_index))?;
+    let server = crate::model_routing::resolve(state, &model)
+        .await
+        .ok_or_else(|| format!("Model {} is not running; launch it to repeat this message", model))?;
+
+    let url = format!("{}/v1/chat/completions", server.base_url);
+    let request = http_pool::client_for(&url).post(&url).json(&body);
+    let response = http_pool::with_timeout(request, RequestClass::Completion)
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("llama-server returned {}: {}", status, text));
+    }
+    let value: Value = response.json().await.map_err(|e| format!("Invalid response: {}", e))?;
+    let output = value["choices"][0]["message"]["content"].as_str().unwrap_or_default().trim().to_string();
+    let usage = crate::proxy_guard::usage_tokens(&value);
+
+    let original = messages[message_index].content.clone();
+    Ok(ReplayResult {
+        model,
+        seed: body["seed"].as_i64().unwrap_or_default(),
+        params: meta.params.unwrap_or(Value::Null),
+        identical: output == original,
+        original,
+        output,
+        prompt_tokens: usage.map(|(prompt, _)| prompt),
+        completion_tokens: usage.map(|(_, completion)| completion),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(role: &str, content: &str) -> ChatLogMessage {
+        ChatLogMessage {
+            role: role.to_string(),
+            content: content.to_string(),
+            model: None,
+            timestamp: String::new(),
+            duration_ms: None,
+            prompt_tokens: None,
+            completion_tokens: None,
+        }
+    }
+
+    #[test]
+    fn replays_use_the_prompt_before_the_message_and_its_seed() {
+        let messages = vec![message("system", "Be brief"), message("user", "Hi"), message("assistant", "Hello!")];
+        let meta = ChatMessageMetadata {
+            message_index: 2,
+            params: Some(json!({ "temperature": 0.8, "seed": 1234, "stream": true, "messages": [] })),
+            ..ChatMessageMetadata::default()
+        };
+
+        let body = replay_request(&messages, 2, &meta).unwrap();
+        assert_eq!((body["seed"].as_i64(), body["temperature"].as_f64()), (Some(1234), Some(0.8)));
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(body["messages"][1], json!({ "role": "user", "content": "Hi" }));
+
+        // An explicit seed in the metadata wins; no seed at all cannot be repeated
+        let seeded = ChatMessageMetadata { seed: Some(7), ..meta.clone() };
+        assert_eq!(replay_request(&messages, 2, &seeded).unwrap()["seed"], 7);
+        let unseeded = ChatMessageMetadata { params: Some(json!({ "temperature": 0.8 })), ..ChatMessageMetadata::default() };
+        assert!(replay_request(&messages, 2, &unseeded).is_err());
+        assert!(replay_request(&messages, 1, &meta).is_err());
+    }
+}
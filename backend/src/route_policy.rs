@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::models::RoutePolicy;
+use crate::AppState;
+
+// Per-model proxy policies from `GlobalConfig.route_policies`. Keys are a model path
+// or the alias clients send as `model`; `*` applies to everything without its own
+// policy. Concurrency is counted per key, so a greedy client only blocks its own model.
+
+/// Key used for requests that match no other policy
+pub const DEFAULT_KEY: &str = "*";
+
+/// The policy for a request: its `model` alias first, then the serving model's path
+pub fn resolve<'a>(
+    policies: &'a HashMap<String, RoutePolicy>,
+    requested_model: &str,
+    served_model_path: Option<&str>,
+) -> Option<(&'a str, &'a RoutePolicy)> {
+    let requested = requested_model.trim();
+    let by_alias = policies
+        .iter()
+        .find(|(key, _)| !requested.is_empty() && key.eq_ignore_ascii_case(requested));
+    let by_path = || {
+        let served = served_model_path?.replace('\\', "/");
+        policies.iter().find(|(key, _)| key.replace('\\', "/").eq_ignore_ascii_case(&served))
+    };
+    by_alias
+        .or_else(by_path)
+        .or_else(|| policies.get_key_value(DEFAULT_KEY))
+        .map(|(key, policy)| (key.as_str(), policy))
+}
+
+/// Clamp `max_tokens` to the cap, filling it in when the client asked for "unlimited"
+pub fn cap_max_tokens(policy: &RoutePolicy, max_tokens: &mut Option<i32>) {
+    if let Some(cap) = policy.max_tokens_cap.filter(|c| *c > 0) {
+        *max_tokens = Some(max_tokens.filter(|t| *t > 0).map_or(cap, |t| t.min(cap)));
+    }
+}
+
+pub fn request_timeout(policy: &RoutePolicy) -> Option<Duration> {
+    policy.request_timeout_secs.filter(|s| *s > 0).map(Duration::from_secs)
+}
+
+/// What a request was admitted with, carried until its response is finished
+#[derive(Debug, Default)]
+pub struct Admission {
+    pub permit: Option<RoutePermit>,
+    pub timeout: Option<Duration>,
+}
+
+/// Run `future` under the admitted timeout; None when it ran out
+pub async fn within<F: Future>(timeout: Option<Duration>, future: F) -> Option<F::Output> {
+    match timeout {
+        Some(limit) => tokio::time::timeout(limit, future).await.ok(),
+        None => Some(future.await),
+    }
+}
+
+async fn served_model_path(state: &AppState, server_url: &str) -> Option<String> {
+    let port = url::Url::parse(server_url).ok()?.port_or_known_default()?;
+    state
+        .running_processes
+        .lock()
+        .await
+        .values()
+        .find(|p| p.port == port && !p.model_path.starts_with("tunnel:"))
+        .map(|p| p.model_path.clone())
+}
+
+/// Apply the matching policy to a request for `requested_model` and take a concurrency
+/// slot for it. Err carries the message for a request turned away because the model is busy.
+pub async fn admit(
+    state: &AppState,
+    limiter: &ConcurrencyLimiter,
+    server_url: &str,
+    requested_model: &str,
+    max_tokens: &mut Option<i32>,
+) -> Result<Admission, String> {
+    let policies = state.config.lock().await.route_policies.clone();
+    // On battery the power policy caps every model, with or without a route policy
+    let power_cap = crate::power::proxy_max_concurrent();
+    if policies.is_empty() && power_cap.is_none() {
+        return Ok(Admission::default());
+    }
+    let served = served_model_path(state, server_url).await;
+    let (key, policy) = match resolve(&policies, requested_model, served.as_deref()) {
+        Some((key, policy)) => (key.to_string(), policy.clone()),
+        None if power_cap.is_some() => (served.unwrap_or_else(|| DEFAULT_KEY.to_string()), RoutePolicy::default()),
+        None => return Ok(Admission::default()),
+    };
+
+    cap_max_tokens(&policy, max_tokens);
+    let max_concurrent = match &power_cap {
+        Some((cap, _)) => Some(policy.max_concurrent.filter(|m| *m > 0).map_or(*cap, |m| m.min(*cap))),
+        None => policy.max_concurrent,
+    };
+    let permit = limiter.try_acquire(&key, max_concurrent).ok_or_else(|| {
+        eprintln!("[Proxy] Concurrency limit reached for {}", key);
+        if let Some((cap, reason)) = &power_cap {
+            crate::power::notify_deferred("proxy_request", &key, reason);
+            return format!("Model '{}' serves {} request(s) at a time while {}; retry shortly", key, cap, reason);
+        }
+        format!(
+            "Model '{}' is already serving {} request(s); retry shortly",
+            key,
+            max_concurrent.unwrap_or(0)
+        )
+    })?;
+    Ok(Admission { permit: Some(permit), timeout: request_timeout(&policy) })
+}
+
+/// In-flight request counts per policy key
+#[derive(Debug, Default)]
+pub struct ConcurrencyLimiter {
+    in_flight: Arc<Mutex<HashMap<String, u32>>>,
+}
+
+/// Held for the life of a request (including a stream); releases its slot on drop
+#[derive(Debug)]
+pub struct RoutePermit {
+    key: String,
+    in_flight: Arc<Mutex<HashMap<String, u32>>>,
+}
+
+impl ConcurrencyLimiter {
+    /// A slot under `key`, or None when `max` requests are already running
+    pub fn try_acquire(&self, key: &str, max: Option<u32>) -> Option<RoutePermit> {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|p| p.into_inner());
+        let count = in_flight.entry(key.to_string()).or_insert(0);
+        if max.is_some_and(|m| m > 0 && *count >= m) {
+            return None;
+        }
+        *count += 1;
+        Some(RoutePermit { key: key.to_string(), in_flight: self.in_flight.clone() })
+    }
+}
+
+impl Drop for RoutePermit {
+    fn drop(&mut self) {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(count) = in_flight.get_mut(&self.key) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                in_flight.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_resolve_by_alias_then_path_then_default() {
+        let mut policies = HashMap::new();
+        policies.insert("qwen-coder".to_string(), RoutePolicy { max_concurrent: Some(1), ..RoutePolicy::default() });
+        policies.insert("C:\\models\\llama.gguf".to_string(), RoutePolicy { max_tokens_cap: Some(2048), ..RoutePolicy::default() });
+        policies.insert(DEFAULT_KEY.to_string(), RoutePolicy { request_timeout_secs: Some(60), ..RoutePolicy::default() });
+
+        assert_eq!(resolve(&policies, "Qwen-Coder", None).unwrap().0, "qwen-coder");
+        assert_eq!(resolve(&policies, "gpt-4", Some("C:/models/llama.gguf")).unwrap().0, "C:\\models\\llama.gguf");
+        assert_eq!(resolve(&policies, "", Some("/other.gguf")).unwrap().0, DEFAULT_KEY);
+
+        let limiter = ConcurrencyLimiter::default();
+        let first = limiter.try_acquire("qwen-coder", Some(1));
+        assert!(first.is_some());
+        assert!(limiter.try_acquire("qwen-coder", Some(1)).is_none());
+        drop(first);
+        assert!(limiter.try_acquire("qwen-coder", Some(1)).is_some());
+    }
+}
@@ -0,0 +1,80 @@
+use chrono::Utc;
+use crate::models::SamplingProfile;
+use crate::openai_types::ChatCompletionRequest;
+
+// Sampling profiles let a chat request say `"profile": "precise"` instead of
+// spelling out temperature / top_p / top_k / min_p. Numbers given explicitly in
+// the request still win over the profile.
+
+/// Profile by id, or by name ignoring case
+pub fn find<'a>(profiles: &'a [SamplingProfile], key: &str) -> Option<&'a SamplingProfile> {
+    let key = key.trim();
+    profiles
+        .iter()
+        .find(|p| p.id == key)
+        .or_else(|| profiles.iter().find(|p| p.name.eq_ignore_ascii_case(key)))
+}
+
+/// Fill the request's unset sampler fields from the profile
+pub fn apply(profile: &SamplingProfile, request: &mut ChatCompletionRequest) {
+    request.temperature = request.temperature.or(profile.temperature);
+    request.top_p = request.top_p.or(profile.top_p);
+    request.top_k = request.top_k.or(profile.top_k);
+    request.min_p = request.min_p.or(profile.min_p);
+}
+
+/// Check a profile before it is stored, assigning an id to new ones
+pub fn normalize(mut profile: SamplingProfile) -> Result<SamplingProfile, String> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err("Profile name is required".to_string());
+    }
+    if profile.temperature.is_some_and(|t| !(0.0..=5.0).contains(&t)) {
+        return Err("Temperature must be between 0 and 5".to_string());
+    }
+    if profile.top_p.is_some_and(|p| !(0.0..=1.0).contains(&p)) || profile.min_p.is_some_and(|p| !(0.0..=1.0).contains(&p)) {
+        return Err("top_p and min_p must be between 0 and 1".to_string());
+    }
+    if profile.top_k.is_some_and(|k| k < 0.0) {
+        return Err("top_k cannot be negative".to_string());
+    }
+    if profile.id.trim().is_empty() {
+        profile.id = format!("profile-{}", Utc::now().timestamp_micros());
+    }
+    Ok(profile)
+}
+
+/// Resolve the request's `profile` against the configured profiles and apply it
+pub fn resolve_and_apply(profiles: &[SamplingProfile], request: &mut ChatCompletionRequest) -> Result<(), String> {
+    let Some(key) = request.profile.take() else { return Ok(()) };
+    let profile = find(profiles, &key).ok_or_else(|| format!("Unknown sampling profile '{}'", key))?;
+    apply(profile, request);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::default_sampling_profiles;
+
+    #[test]
+    fn profile_fills_only_unset_sampler_fields() {
+        let profiles = default_sampling_profiles();
+        let mut request: ChatCompletionRequest = serde_json::from_value(serde_json::json!({
+            "model": "any",
+            "messages": [{"role": "user", "content": "hi"}],
+            "temperature": 0.7,
+            "profile": "Precise"
+        }))
+        .unwrap();
+
+        resolve_and_apply(&profiles, &mut request).unwrap();
+        assert_eq!(request.temperature, Some(0.7));
+        assert_eq!(request.top_k, Some(20.0));
+        assert!(request.profile.is_none());
+        assert!(serde_json::to_value(&request).unwrap().get("profile").is_none());
+
+        request.profile = Some("nope".into());
+        assert!(resolve_and_apply(&profiles, &mut request).is_err());
+    }
+}
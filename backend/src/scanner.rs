@@ -1,11 +1,40 @@
 use std::fs;
-use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
 use glob::glob;
 use regex::Regex;
+use std::sync::LazyLock;
+use tokio_util::sync::CancellationToken;
 use crate::models::*;
 
+static SPLIT_SHARD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(.+?)(-\d{5}-of-\d{5})\.gguf$").unwrap());
+
+/// Models each directory held at its last scan, so lookups by name need not walk the disk
+static LAST_SCAN: LazyLock<std::sync::Mutex<std::collections::HashMap<String, Vec<ModelInfo>>>> =
+    LazyLock::new(Default::default);
+
+/// Models of `directories` as of their last scan; `None` when one was never scanned
+pub fn cached_models(directories: &[String]) -> Option<Vec<ModelInfo>> {
+    let cache = LAST_SCAN.lock().unwrap_or_else(|p| p.into_inner());
+    let mut models = Vec::new();
+    for directory in directories.iter().filter(|d| !d.is_empty()) {
+        models.extend(cache.get(directory)?.iter().cloned());
+    }
+    Some(models)
+}
+
+/// Base name and `-00001-of-00003` suffix of a split model shard's file name (or path)
+pub fn split_shard(file_name: &str) -> Option<(&str, &str)> {
+    let captures = SPLIT_SHARD.captures(file_name)?;
+    Some((captures.get(1)?.as_str(), captures.get(2)?.as_str()))
+}
+
 pub async fn scan_models(directories: &[String]) -> Result<Vec<ModelInfo>, Box<dyn std::error::Error>> {
+    scan_models_until_cancelled(directories, &CancellationToken::new()).await
+}
+
+/// `scan_models` that stops between model files once `cancel` fires
+pub async fn scan_models_until_cancelled(directories: &[String], cancel: &CancellationToken) -> Result<Vec<ModelInfo>, Box<dyn std::error::Error>> {
     let mut all_models = Vec::new();
     let mut seen_paths = std::collections::HashSet::new();
     
@@ -14,7 +43,8 @@ pub async fn scan_models(directories: &[String]) -> Result<Vec<ModelInfo>, Box<d
             continue;
         }
         
-        let pattern = format!("{}/**/*.gguf", directory);
+        // Folder names may hold glob syntax such as `[`
+        let pattern = format!("{}/**/*.gguf", glob::Pattern::escape(directory));
         let files: Result<Vec<_>, _> = glob(&pattern)?.collect();
         let files = files?;
         
@@ -36,20 +66,25 @@ pub async fn scan_models(directories: &[String]) -> Result<Vec<ModelInfo>, Box<d
                 .to_string();
             
             // Check if this is a split file (e.g., model-00001-of-00005.gguf)
-            let re = Regex::new(r"(.+?)-\d{5}-of-\d{5}\.gguf$")?;
-            if let Some(captures) = re.captures(&file_name) {
-                let base_name = captures.get(1).unwrap().as_str().to_string();
+            if let Some((base_name, _)) = split_shard(&file_name) {
+                let base_name = base_name.to_string();
                 model_groups.entry(base_name).or_insert_with(Vec::new).push(path_str);
             } else {
                 model_groups.entry(path_str.clone()).or_insert_with(Vec::new).push(path_str);
             }
         }
         
+        let mut directory_models = Vec::new();
         for (base_name, file_list) in model_groups {
+            if cancel.is_cancelled() {
+                return Err("Model scan was cancelled".into());
+            }
             if let Ok(model_info) = process_model_group(&base_name, &file_list).await {
-                all_models.push(model_info);
+                directory_models.push(model_info);
             }
         }
+        LAST_SCAN.lock().unwrap_or_else(|p| p.into_inner()).insert(directory.clone(), directory_models.clone());
+        all_models.extend(directory_models);
     }
     
     // Sort by name
@@ -65,13 +100,13 @@ async fn process_model_group(base_name: &str, file_list: &[String]) -> Result<Mo
     // Calculate total size
     let mut total_size = 0u64;
     for file_path in file_list {
-        if let Ok(metadata) = fs::metadata(file_path) {
+        if let Ok(metadata) = fs::metadata(crate::long_path::extended(Path::new(file_path))) {
             total_size += metadata.len();
         }
     }
     
     // Get file metadata
-    let metadata = fs::metadata(first_path)?;
+    let metadata = fs::metadata(crate::long_path::extended(first_path))?;
     let modified_time = metadata.modified()?
         .duration_since(std::time::UNIX_EPOCH)?
         .as_secs() as i64;
@@ -105,101 +140,24 @@ async fn process_model_group(base_name: &str, file_list: &[String]) -> Result<Mo
 }
 
 pub fn extract_gguf_metadata(file_path: &Path) -> Result<GgufMetadata, Box<dyn std::error::Error>> {
-    let mut file = fs::File::open(file_path)?;
-    
-    // Read magic bytes
-    let mut magic = [0u8; 4];
-    file.read_exact(&mut magic)?;
-    
-    if &magic != b"GGUF" {
-        return Ok(GgufMetadata {
-            architecture: "Unknown".to_string(),
-            name: file_path.file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown")
-                .to_string(),
-            quantization: None,
-        });
-    }
-    
-    // Skip version and tensor count
-    file.seek(SeekFrom::Current(12))?;
-    
-    // Read metadata key-value count
-    let mut kv_count_bytes = [0u8; 8];
-    file.read_exact(&mut kv_count_bytes)?;
-    let kv_count = u64::from_le_bytes(kv_count_bytes);
-    
-    let mut architecture = "Unknown".to_string();
-    let mut name = file_path.file_name()
+    let file = fs::File::open(crate::long_path::extended(file_path))?;
+    let header = crate::gguf_parser::read_header(&mut std::io::BufReader::new(file));
+
+    let file_name = file_path.file_name()
         .and_then(|n| n.to_str())
-        .unwrap_or("Unknown")
-        .to_string();
-    
-    // Read key-value pairs
-    for _ in 0..kv_count {
-        // Read key length
-        let mut key_len_bytes = [0u8; 8];
-        if file.read_exact(&mut key_len_bytes).is_err() {
-            break;
-        }
-        let key_len = u64::from_le_bytes(key_len_bytes);
-        
-        // Read key
-        let mut key_bytes = vec![0u8; key_len as usize];
-        if file.read_exact(&mut key_bytes).is_err() {
-            break;
-        }
-        let key = String::from_utf8_lossy(&key_bytes);
-        
-        // Read value type
-        let mut value_type_bytes = [0u8; 4];
-        if file.read_exact(&mut value_type_bytes).is_err() {
-            break;
-        }
-        let value_type = u32::from_le_bytes(value_type_bytes);
-        
-        // Handle string values (type 8)
-        if value_type == 8 {
-            // Read value length
-            let mut value_len_bytes = [0u8; 8];
-            if file.read_exact(&mut value_len_bytes).is_err() {
-                break;
-            }
-            let value_len = u64::from_le_bytes(value_len_bytes);
-            
-            // Read value
-            let mut value_bytes = vec![0u8; value_len as usize];
-            if file.read_exact(&mut value_bytes).is_err() {
-                break;
-            }
-            let value = String::from_utf8_lossy(&value_bytes);
-            
-            match key.as_ref() {
-                "general.architecture" => {
-                    architecture = value.to_string();
-                }
-                "general.name" => {
-                    name = value.to_string();
-                }
-                _ => {}
-            }
-        } else {
-            // Skip non-string values
-            break;
-        }
-    }
-    
+        .unwrap_or("Unknown");
+    let header = header.as_ref();
     Ok(GgufMetadata {
-        architecture,
-        name,
+        architecture: header.and_then(|h| h.architecture()).unwrap_or("Unknown").to_string(),
+        name: header.and_then(|h| h.text("general.name")).unwrap_or(file_name).to_string(),
         quantization: None,
     })
 }
 
 pub fn get_quantization_from_filename(filename: &str) -> String {
-    // Find .gguf extension first, then search backwards for the first dash or dot
-    let filename_lower = filename.to_lowercase();
+    // Find .gguf extension first, then search backwards for the first dash or dot.
+    // ASCII lowercasing keeps byte offsets valid for the slice below with any script.
+    let filename_lower = filename.to_ascii_lowercase();
     
     if let Some(gguf_pos) = filename_lower.find(".gguf") {
         let base_part = &filename[..gguf_pos];
@@ -289,27 +247,26 @@ pub async fn scan_mmproj_files(directories: &[String]) -> Result<Vec<String>, Bo
         }
         
         let base_path = Path::new(directory);
-        let pattern = format!("{}/**/*.gguf", directory);
+        // Folder names may hold glob syntax such as `[`
+        let pattern = format!("{}/**/*.gguf", glob::Pattern::escape(directory));
         let entries = glob(&pattern)?;
         
-        for entry in entries {
-            if let Ok(path) = entry {
-                let path_str = path.to_string_lossy().to_string();
-                
-                // Skip if we've already seen this exact path
-                if seen_paths.contains(&path_str) {
-                    continue;
-                }
-                seen_paths.insert(path_str.clone());
-                
-                // Check if this GGUF file has CLIP architecture
-                if let Ok(metadata) = extract_gguf_metadata(&path) {
-                    if metadata.architecture.to_lowercase() == "clip" {
-                        if let Ok(rel_path) = path.strip_prefix(base_path) {
-                            all_files.push(rel_path.to_string_lossy().to_string());
-                        } else {
-                            all_files.push(path_str);
-                        }
+        for path in entries.flatten() {
+            let path_str = path.to_string_lossy().to_string();
+            
+            // Skip if we've already seen this exact path
+            if seen_paths.contains(&path_str) {
+                continue;
+            }
+            seen_paths.insert(path_str.clone());
+            
+            // Check if this GGUF file has CLIP architecture
+            if let Ok(metadata) = extract_gguf_metadata(&path) {
+                if metadata.architecture.to_lowercase() == "clip" {
+                    if let Ok(rel_path) = path.strip_prefix(base_path) {
+                        all_files.push(rel_path.to_string_lossy().to_string());
+                    } else {
+                        all_files.push(path_str);
                     }
                 }
             }
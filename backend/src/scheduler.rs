@@ -0,0 +1,237 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeZone, Utc, Weekday};
+use serde::Serialize;
+use std::time::Duration;
+use crate::models::{ModelSchedule, ProcessStatus};
+use crate::AppState;
+
+// Models started and stopped at set times, e.g. a coding model at 09:00 on weekdays
+// and stopped at 19:00. Schedules live in `GlobalConfig.model_schedules` and follow the
+// machine's local time. Every `TICK` the loop runs the start and stop times passed since
+// the previous tick; times missed by more than `MISSED_GRACE_MINUTES` (the machine was
+// asleep) are skipped rather than run late.
+
+const TICK: Duration = Duration::from_secs(30);
+const MISSED_GRACE_MINUTES: i64 = 10;
+const UPCOMING_DAYS: i64 = 8;
+
+const DAY_NAMES: [(Weekday, &str); 7] = [
+    (Weekday::Mon, "mon"),
+    (Weekday::Tue, "tue"),
+    (Weekday::Wed, "wed"),
+    (Weekday::Thu, "thu"),
+    (Weekday::Fri, "fri"),
+    (Weekday::Sat, "sat"),
+    (Weekday::Sun, "sun"),
+];
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleAction {
+    Start,
+    Stop,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledRun {
+    pub schedule_id: String,
+    pub model_path: String,
+    pub action: ScheduleAction,
+    pub at: DateTime<Local>,
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| format!("'{}' is not a time like 09:00", value))
+}
+
+/// Check a schedule before it is stored: times become `HH:MM`, days their short names
+/// in week order (`weekdays` and `weekends` expand), and new schedules get an id
+pub fn normalize(mut schedule: ModelSchedule) -> Result<ModelSchedule, String> {
+    schedule.model_path = schedule.model_path.trim().to_string();
+    if schedule.model_path.is_empty() {
+        return Err("Model path is required".to_string());
+    }
+    if schedule.start_at.is_none() && schedule.stop_at.is_none() {
+        return Err("A schedule needs a start or a stop time".to_string());
+    }
+    for time in [&mut schedule.start_at, &mut schedule.stop_at].into_iter().flatten() {
+        *time = parse_time(time)?.format("%H:%M").to_string();
+    }
+
+    let mut days = Vec::new();
+    for day in &schedule.days {
+        let day = day.trim().to_lowercase();
+        match day.as_str() {
+            "weekdays" => days.extend([Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]),
+            "weekends" => days.extend([Weekday::Sat, Weekday::Sun]),
+            _ => days.push(day.parse::<Weekday>().map_err(|_| format!("'{}' is not a day of the week", day))?),
+        }
+    }
+    schedule.days = DAY_NAMES
+        .iter()
+        .filter(|(weekday, _)| days.contains(weekday))
+        .map(|(_, name)| name.to_string())
+        .collect();
+
+    if schedule.id.trim().is_empty() {
+        schedule.id = format!("schedule-{}", Utc::now().timestamp_micros());
+    }
+    Ok(schedule)
+}
+
+/// Start and stop times of `schedule` in `(after, until]`, in order
+fn occurrences<Tz: TimeZone>(schedule: &ModelSchedule, after: &DateTime<Tz>, until: &DateTime<Tz>) -> Vec<(ScheduleAction, DateTime<Tz>)> {
+    let days: Vec<Weekday> = schedule.days.iter().filter_map(|d| d.parse().ok()).collect();
+    let times: Vec<(ScheduleAction, NaiveTime)> = [(ScheduleAction::Start, &schedule.start_at), (ScheduleAction::Stop, &schedule.stop_at)]
+        .into_iter()
+        .filter_map(|(action, time)| Some((action, parse_time(time.as_deref()?).ok()?)))
+        .collect();
+
+    let timezone = after.timezone();
+    let mut found = Vec::new();
+    let mut date = after.date_naive();
+    while date <= until.date_naive() {
+        if days.is_empty() || days.contains(&date.weekday()) {
+            for (action, time) in &times {
+                // A time a DST change skips does not run that day
+                let Some(at) = timezone.from_local_datetime(&date.and_time(*time)).earliest() else { continue };
+                if at > *after && at <= *until {
+                    found.push((*action, at));
+                }
+            }
+        }
+        let Some(next) = date.succ_opt() else { break };
+        date = next;
+    }
+    found.sort_by(|a, b| a.1.cmp(&b.1));
+    found
+}
+
+/// The next `limit` runs of the enabled schedules
+pub fn upcoming(schedules: &[ModelSchedule], now: DateTime<Local>, limit: usize) -> Vec<ScheduledRun> {
+    let until = now + chrono::Duration::days(UPCOMING_DAYS);
+    let mut runs: Vec<ScheduledRun> = schedules
+        .iter()
+        .filter(|s| s.enabled)
+        .flat_map(|s| {
+            occurrences(s, &now, &until).into_iter().map(|(action, at)| ScheduledRun {
+                schedule_id: s.id.clone(),
+                model_path: s.model_path.clone(),
+                action,
+                at,
+            })
+        })
+        .collect();
+    runs.sort_by_key(|a| a.at);
+    runs.truncate(limit);
+    runs
+}
+
+async fn start(schedule: &ModelSchedule, state: &AppState) {
+    let running = state
+        .running_processes
+        .lock()
+        .await
+        .values()
+        .any(|p| p.model_path == schedule.model_path && matches!(p.status, ProcessStatus::Starting | ProcessStatus::Running | ProcessStatus::Suspended));
+    if running {
+        println!("[Scheduler] {} is already running", schedule.model_path);
+        return;
+    }
+    match crate::launch_with_preset(schedule.model_path.clone(), schedule.preset_id.clone(), state).await {
+        Ok(result) => println!("[Scheduler] Started {} on port {}", schedule.model_path, result.server_port),
+        Err(e) => eprintln!("[Scheduler] Failed to start {}: {}", schedule.model_path, e),
+    }
+}
+
+async fn stop(schedule: &ModelSchedule, state: &AppState) {
+    let process_ids: Vec<String> = state
+        .running_processes
+        .lock()
+        .await
+        .values()
+        .filter(|p| p.model_path == schedule.model_path && !matches!(p.status, ProcessStatus::Stopped | ProcessStatus::Failed))
+        .map(|p| p.id.clone())
+        .collect();
+    for process_id in process_ids {
+        match crate::process::terminate_process(process_id.clone(), state).await {
+            Ok(()) => println!("[Scheduler] Stopped {} ({})", schedule.model_path, process_id),
+            Err(e) => eprintln!("[Scheduler] Failed to stop {}: {}", process_id, e),
+        }
+    }
+}
+
+async fn tick(state: &AppState, after: DateTime<Local>, now: DateTime<Local>) {
+    let schedules = state.config.lock().await.model_schedules.clone();
+    for schedule in schedules.iter().filter(|s| s.enabled) {
+        for (action, at) in occurrences(schedule, &after, &now) {
+            if (now - at).num_minutes() > MISSED_GRACE_MINUTES {
+                println!("[Scheduler] Skipping the {:?} of {} missed at {}", action, schedule.model_path, at.format("%H:%M"));
+                continue;
+            }
+            match action {
+                ScheduleAction::Start => start(schedule, state).await,
+                ScheduleAction::Stop => stop(schedule, state).await,
+            }
+        }
+    }
+}
+
+pub fn spawn(state: AppState) {
+    tauri::async_runtime::spawn(async move {
+        let mut last = Local::now();
+        loop {
+            tokio::time::sleep(TICK).await;
+            let now = Local::now();
+            tick(&state, last, now).await;
+            last = now;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(days: &[&str], start_at: Option<&str>, stop_at: Option<&str>) -> ModelSchedule {
+        ModelSchedule {
+            id: String::new(),
+            model_path: " /models/coder.gguf ".to_string(),
+            preset_id: None,
+            days: days.iter().map(|d| d.to_string()).collect(),
+            start_at: start_at.map(str::to_string),
+            stop_at: stop_at.map(str::to_string),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn weekday_schedule_starts_and_stops_on_weekdays_only() {
+        let coding = normalize(schedule(&["Weekdays", "friday"], Some("9:00"), Some("19:00"))).unwrap();
+        assert_eq!(coding.days, vec!["mon", "tue", "wed", "thu", "fri"]);
+        assert_eq!((coding.start_at.as_deref(), coding.model_path.as_str()), (Some("09:00"), "/models/coder.gguf"));
+        assert!(!coding.id.is_empty());
+
+        // Friday 2026-10-16 08:00 through Monday 09:30
+        let after = Utc.with_ymd_and_hms(2026, 10, 16, 8, 0, 0).unwrap();
+        let until = Utc.with_ymd_and_hms(2026, 10, 19, 9, 30, 0).unwrap();
+        let runs: Vec<(ScheduleAction, String)> = occurrences(&coding, &after, &until)
+            .into_iter()
+            .map(|(action, at)| (action, at.format("%a %H:%M").to_string()))
+            .collect();
+        assert_eq!(
+            runs,
+            vec![
+                (ScheduleAction::Start, "Fri 09:00".to_string()),
+                (ScheduleAction::Stop, "Fri 19:00".to_string()),
+                (ScheduleAction::Start, "Mon 09:00".to_string()),
+            ]
+        );
+        // A time exactly at the previous tick has already run
+        let at_nine = Utc.with_ymd_and_hms(2026, 10, 16, 9, 0, 0).unwrap();
+        assert!(occurrences(&coding, &at_nine, &at_nine).is_empty());
+
+        assert!(normalize(schedule(&[], None, None)).is_err());
+        assert!(normalize(schedule(&["someday"], Some("09:00"), None)).is_err());
+        assert!(normalize(schedule(&[], Some("25:00"), None)).is_err());
+    }
+}
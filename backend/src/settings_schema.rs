@@ -0,0 +1,217 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use crate::models::{GlobalConfig, ModelConfig};
+
+// Machine-readable description of `GlobalConfig` and `ModelConfig` for the settings UI
+// and external tools. Keys, types, defaults and which keys are required or nullable are
+// read off the structs themselves (their serialized defaults and what serde accepts);
+// the `hint` tables only add help text, ranges and choices, and a test keeps them in
+// step with the fields.
+
+#[derive(Debug, Clone, Copy)]
+struct Hint {
+    help: &'static str,
+    min: Option<f64>,
+    max: Option<f64>,
+    options: &'static [&'static str],
+    /// Type of a field whose default is `null`
+    value_type: Option<&'static str>,
+    /// Bookkeeping the app maintains itself; shown read-only
+    internal: bool,
+    /// Never echo the value back
+    secret: bool,
+}
+
+const NONE: Hint = Hint { help: "", min: None, max: None, options: &[], value_type: None, internal: false, secret: false };
+
+const fn help(text: &'static str) -> Hint {
+    Hint { help: text, ..NONE }
+}
+
+const fn range(text: &'static str, min: f64, max: f64) -> Hint {
+    Hint { help: text, min: Some(min), max: Some(max), ..NONE }
+}
+
+const fn internal(text: &'static str, value_type: Option<&'static str>) -> Hint {
+    Hint { help: text, value_type, internal: true, ..NONE }
+}
+
+const PORT: (f64, f64) = (1.0, 65535.0);
+
+fn global_hint(key: &str) -> Option<Hint> {
+    let hint = match key {
+        "models_directory" => help("Main folder scanned for GGUF models"),
+        "additional_models_directories" => help("Extra folders scanned for models"),
+        "read_only_models_directories" => help("Models directories that are scanned and launched but never written to"),
+        "executable_folder" => help("Folder holding installed llama.cpp builds"),
+        "active_executable_folder" => Hint { value_type: Some("string"), ..help("llama.cpp build used when a model has no pin or backend preference") },
+        "active_executable_version" => internal("Version label of the active build", Some("string")),
+        "theme_color" => help("Desktop theme"),
+        "background_color" => help("Desktop background"),
+        "theme_is_synced" => help("Use the theme color for the background too"),
+        "openai_proxy_enabled" => help("Serve the OpenAI-compatible proxy"),
+        "openai_proxy_port" => range("Port of the OpenAI-compatible proxy", PORT.0, PORT.1),
+        "network_server_host" => help("Address the network server binds; 0.0.0.0 exposes it to the LAN"),
+        "network_server_port" => range("Port of the network server", PORT.0, PORT.1),
+        "network_rate_limit_per_minute" => range("Requests per minute per client; 0 disables the limit", 0.0, 100_000.0),
+        "network_max_body_mb" => range("Largest request body accepted, in MB", 1.0, 1024.0),
+        "network_read_timeout_secs" => range("Seconds to wait for a client request", 1.0, 3600.0),
+        "network_write_timeout_secs" => range("Seconds allowed to send a response", 1.0, 3600.0),
+        "network_blocked_clients" => help("Client addresses refused by the network server"),
+        "network_allowed_cidrs" => help("CIDRs allowed to reach the network server; empty allows everyone"),
+        "network_api_keys" => Hint { secret: true, ..help("Hashed API keys the network server requires as Bearer tokens; none leaves it open") },
+        "proxy_load_models_on_request" => help("Launch an installed model a proxied request names when it is not running"),
+        "mcp_servers" => help("MCP server connections"),
+        "mcp_health_check_interval_minutes" => range("Minutes between background MCP server health checks; 0 disables", 0.0, 1440.0),
+        "llamacpp_releases_refresh_hours" => range("Hours between llama.cpp release list refreshes", 1.0, 168.0),
+        "llamacpp_keep_versions_per_backend" => range("Installed builds kept per backend when pruning", 1.0, 50.0),
+        "active_backend_versions" => help("Active llama.cpp build per backend type"),
+        "sampling_profiles" => help("Named sampler bundles a request can pick with \"profile\""),
+        "fallback_chain" => help("Model paths tried in order when the model a proxied chat completion went to fails"),
+        "route_policies" => help("Proxy limits keyed by model path or alias, * for the rest"),
+        "disk_space_min_free_gb" => range("Downloads pause below this much free disk space (GB); 0 disables", 0.0, 10_000.0),
+        "max_concurrent_downloads" => range("Downloads running at once; the rest wait in the queue. 0 runs them all", 0.0, 32.0),
+        "download_bandwidth_limit_kb_per_sec" => range("Combined speed of all downloads in KB/s; 0 is unlimited", 0.0, 10_000_000.0),
+        "partial_download_retention_days" => range("Partial downloads cleanup removes once untouched this many days; 0 removes them at any age", 0.0, 3650.0),
+        "keep_resumable_downloads" => help("Partial downloads cleanup leaves files that can still be resumed"),
+        "external_terminal" => help("Terminal command for external launches with {exe} and {args} (or {script}); empty detects one"),
+        "low_memory_mode" => help("Small default context, one parallel slot and no --mlock/--no-mmap for 8-16GB RAM machines"),
+        "low_memory_context" => range("Context low memory mode launches with when a model sets none", 512.0, 131_072.0),
+        "auto_gpu_layers" => help("Launches without -ngl offload as many layers as the free VRAM holds"),
+        "power_policy" => help("On battery below battery_threshold_percent or in power saver: pause idle models, cap proxy requests per model, refuse launches"),
+        "model_schedules" => help("Launch and stop models at set times: model_path, days (mon..sun, empty for every day), start_at and stop_at as HH:MM"),
+        "usage_analytics_enabled" => help("Keep local usage statistics (feature use, launch success, download speed); never sent anywhere"),
+        "locale" => Hint { options: &["en", "pt-BR", "es"], ..help("Language of download, launch and error messages") },
+        "discovery_enabled" => help("Announce this instance and find others on the LAN"),
+        "discovery_port" => range("UDP port for discovery broadcasts", PORT.0, PORT.1),
+        "discovery_broadcast_interval" => range("Seconds between discovery broadcasts", 1.0, 300.0),
+        "discovery_instance_name" => help("Name other instances see"),
+        "discovery_instance_id" => internal("Stable id of this instance", None),
+        _ => return None,
+    };
+    Some(hint)
+}
+
+fn model_hint(key: &str) -> Option<Hint> {
+    let hint = match key {
+        "custom_args" => help("Extra llama-server arguments"),
+        "server_host" => help("Address the model's server binds"),
+        "server_port" => range("Port of the model's server", PORT.0, PORT.1),
+        "model_path" => internal("Absolute path of the model file", None),
+        "presets" => help("Named argument sets for this model"),
+        "default_preset_id" => Hint { value_type: Some("string"), ..help("Preset used when launching without picking one") },
+        "env_vars" => help("Environment variables for the server process"),
+        "hf_model_id" => Hint { value_type: Some("string"), ..help("Hugging Face repo the model came from (author/model)") },
+        "hf_link_source" => Hint { value_type: Some("string"), options: &["download", "guess", "manual"], ..help("How the Hugging Face link was made") },
+        "local_file_modified" => internal("File modification time at the last update check", Some("integer")),
+        "file_size_bytes" => internal("File size at the last update check", Some("integer")),
+        "last_hf_check" => internal("When Hugging Face was last asked for updates", Some("integer")),
+        "hf_file_modified" => internal("Upstream file time", Some("integer")),
+        "hf_file_size" => internal("Upstream file size", Some("integer")),
+        "update_available" => internal("A newer upstream file exists", None),
+        "hf_metadata" => internal("Upstream metadata from the update checker", Some("object")),
+        "pinned_llamacpp_version" => Hint { value_type: Some("string"), ..help("llama.cpp build this model always launches with") },
+        "preferred_backend" => Hint { value_type: Some("string"), options: &["cuda", "rocm", "vulkan", "opencl", "sycl", "metal", "cpu"], ..help("Backend type to launch with when not pinned") },
+        "exclusive_launch" => help("Stop all other running models before this one launches"),
+        "file_fingerprint" => internal("Quick content hash used to find the file after it moves", Some("string")),
+        "last_seen_at" => internal("Last reconcile that found the file", Some("integer")),
+        "restart_policy" => help("Relaunch after exits: mode never, on-failure or always, with max_retries and backoff_seconds (doubles per retry)"),
+        "memory" => help("mlock pins the weights in RAM, no_mmap reads them in full, numa is distribute, isolate or numactl"),
+        "gpu_devices" => help("devices (CUDA0, Vulkan1, ...) the model runs on, tensor_split shares per device, main_gpu index into devices"),
+        "idle_timeout_minutes" => range("Stop the server after this many minutes without requests; 0 never", 0.0, 10_080.0),
+        "content_sha256" => internal("SHA-256 the settings follow across renames", Some("string")),
+        _ => return None,
+    };
+    Some(hint)
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Whether `T` still deserializes from `base` with `key` removed (`None`) or replaced
+fn accepts<T: DeserializeOwned>(base: &Map<String, Value>, key: &str, value: Option<Value>) -> bool {
+    let mut probe = base.clone();
+    match value {
+        Some(value) => probe.insert(key.to_string(), value),
+        None => probe.remove(key),
+    };
+    serde_json::from_value::<T>(Value::Object(probe)).is_ok()
+}
+
+fn describe<T: Serialize + DeserializeOwned>(defaults: &T, hints: fn(&str) -> Option<Hint>) -> Result<Vec<Value>, String> {
+    let Value::Object(base) = serde_json::to_value(defaults).map_err(|e| format!("Failed to serialize defaults: {}", e))? else {
+        return Err("Settings must serialize to an object".to_string());
+    };
+    let fields = base
+        .iter()
+        .map(|(key, default)| {
+            let hint = hints(key).unwrap_or(NONE);
+            let value_type = match default {
+                Value::Null => hint.value_type.unwrap_or("string"),
+                other => json_type(other),
+            };
+            serde_json::json!({
+                "key": key,
+                "type": value_type,
+                "default": if hint.secret { Value::Null } else { default.clone() },
+                "required": !accepts::<T>(&base, key, None),
+                "nullable": accepts::<T>(&base, key, Some(Value::Null)),
+                "min": hint.min,
+                "max": hint.max,
+                "options": hint.options,
+                "help": if hint.help.is_empty() { None } else { Some(hint.help) },
+                "internal": hint.internal,
+                "secret": hint.secret,
+            })
+        })
+        .collect();
+    Ok(fields)
+}
+
+pub fn settings_schema() -> Result<Value, String> {
+    Ok(serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "global": describe(&GlobalConfig::default(), global_hint)?,
+        "model": describe(&ModelConfig::new(String::new()), model_hint)?,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_follows_the_structs() {
+        let schema = settings_schema().unwrap();
+        let field = |section: &str, key: &str| schema[section].as_array().unwrap().iter().find(|f| f["key"] == key).cloned();
+
+        let port = field("global", "network_server_port").unwrap();
+        assert_eq!(port["type"], "integer");
+        assert_eq!(port["default"], 8080);
+        assert_eq!(port["max"], 65535.0);
+        assert_eq!(field("global", "models_directory").unwrap()["required"], true);
+        assert_eq!(field("global", "discovery_port").unwrap()["required"], false);
+        assert_eq!(field("global", "disk_space_min_free_gb").unwrap()["type"], "number");
+        // Kept in the OS keyring, not in settings
+        assert!(field("global", "github_token").is_none());
+        assert!(field("global", "backend_benchmarks").is_none());
+        assert_eq!(field("model", "model_path").unwrap()["internal"], true);
+
+        // New fields need a hint before they show up in the settings UI
+        for (section, hints) in [("global", global_hint as fn(&str) -> Option<Hint>), ("model", model_hint)] {
+            for f in schema[section].as_array().unwrap() {
+                assert!(hints(f["key"].as_str().unwrap()).is_some(), "no hint for {}", f["key"]);
+            }
+        }
+    }
+}
@@ -0,0 +1,834 @@
+use rusqlite::{params, Connection};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use crate::analytics::{AnalyticsEvent, RecordedEvent};
+use crate::downloader::{DownloadConfig, DownloadStatus};
+use crate::energy_report::EnergySample;
+use crate::mcp_health::HealthCheck;
+use crate::models::BackendBenchmarkRecord;
+use crate::process_registry::RegisteredProcess;
+
+// One SQLite database (`~/.Arandu/arandu.db`) for everything Arandu persists beyond
+// settings. The schema is versioned: `schema_version` records each applied migration and
+// `connect` runs the pending ones, so features add a migration instead of a new file.
+//
+// Repositories are thin typed wrappers over a connection. The tracker keeps its own
+// connection on its database thread (`TrackerDb`, which is the tracker repository);
+// everything else shares the connection behind `with` / `run`. Older JSON files are
+// imported the first time their repository takes over.
+
+struct Migration {
+    version: u32,
+    name: &'static str,
+    sql: &'static str,
+}
+
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "tracker",
+        sql: "
+            CREATE TABLE IF NOT EXISTS models (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                author TEXT,
+                description TEXT,
+                source TEXT DEFAULT 'huggingface',
+                category TEXT,
+                is_chinese INTEGER DEFAULT 0,
+                is_gguf INTEGER DEFAULT 0,
+                quantizations TEXT,
+                backends TEXT,
+                estimated_size_gb REAL,
+                vram_requirement_gb REAL,
+                context_length INTEGER,
+                downloads INTEGER DEFAULT 0,
+                likes INTEGER DEFAULT 0,
+                last_updated TEXT,
+                created_at TEXT
+            );
+            CREATE TABLE IF NOT EXISTS tracker_config (key TEXT PRIMARY KEY, value TEXT);
+            CREATE TABLE IF NOT EXISTS weekly_reports (
+                id TEXT PRIMARY KEY,
+                generated_at TEXT,
+                period_start TEXT,
+                period_end TEXT,
+                total_models INTEGER,
+                new_models INTEGER,
+                chinese_models INTEGER,
+                gguf_models INTEGER,
+                categories TEXT,
+                top_downloads TEXT
+            );
+            CREATE TABLE IF NOT EXISTS watchlist (
+                model_id TEXT PRIMARY KEY,
+                source TEXT DEFAULT 'manual',
+                collection_slug TEXT,
+                note TEXT,
+                added_at TEXT
+            );
+            -- Real GGUF file lists of expanded models; kept across refreshes, which only
+            -- rewrite the models table
+            CREATE TABLE IF NOT EXISTS model_files (
+                model_id TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                size_bytes INTEGER DEFAULT 0,
+                quantization TEXT,
+                fetched_at TEXT,
+                PRIMARY KEY (model_id, file_name)
+            );
+            -- One row per refresh; sightings remember the last refresh that returned each
+            -- model so stale ones can be pruned
+            CREATE TABLE IF NOT EXISTS scrape_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                scraped_at TEXT,
+                model_count INTEGER
+            );
+            CREATE TABLE IF NOT EXISTS model_sightings (
+                model_id TEXT PRIMARY KEY,
+                last_scrape INTEGER NOT NULL,
+                first_seen TEXT,
+                last_seen TEXT
+            );
+            CREATE TABLE IF NOT EXISTS model_classifications (
+                model_id TEXT PRIMARY KEY,
+                category TEXT NOT NULL,
+                confidence REAL DEFAULT 0,
+                source TEXT DEFAULT 'model',
+                classified_by TEXT,
+                classified_at TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_category ON models(category);
+            CREATE INDEX IF NOT EXISTS idx_chinese ON models(is_chinese);
+            CREATE INDEX IF NOT EXISTS idx_gguf ON models(is_gguf);
+        ",
+    },
+    Migration {
+        version: 2,
+        name: "usage_samples",
+        sql: "
+            CREATE TABLE usage_samples (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                model TEXT NOT NULL,
+                backend TEXT,
+                source TEXT NOT NULL,
+                tokens INTEGER NOT NULL,
+                energy_wh REAL NOT NULL,
+                avg_power_watts REAL NOT NULL,
+                recorded_at TEXT NOT NULL
+            );
+            CREATE INDEX idx_usage_recorded ON usage_samples(recorded_at);
+        ",
+    },
+    Migration {
+        version: 3,
+        name: "backend_benchmarks",
+        sql: "CREATE TABLE backend_benchmarks (model_class TEXT PRIMARY KEY, record TEXT NOT NULL, measured_at TEXT NOT NULL);",
+    },
+    Migration {
+        version: 4,
+        name: "download_history",
+        sql: "CREATE TABLE download_history (id TEXT PRIMARY KEY, status TEXT NOT NULL, finished_at TEXT NOT NULL);",
+    },
+    Migration {
+        version: 5,
+        name: "chats",
+        sql: "CREATE TABLE chats (chat_id TEXT PRIMARY KEY, entry TEXT NOT NULL, last_used_at TEXT);",
+    },
+    Migration {
+        version: 6,
+        name: "analytics_events",
+        sql: "
+            CREATE TABLE analytics_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                success INTEGER,
+                bytes INTEGER,
+                seconds REAL,
+                recorded_at TEXT NOT NULL
+            );
+        ",
+    },
+    Migration {
+        version: 7,
+        name: "mcp_health_checks",
+        sql: "
+            CREATE TABLE mcp_health_checks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                connection_id TEXT NOT NULL,
+                success INTEGER NOT NULL,
+                latency_ms INTEGER NOT NULL,
+                message TEXT NOT NULL,
+                checked_at TEXT NOT NULL
+            );
+            CREATE INDEX mcp_health_checks_connection ON mcp_health_checks (connection_id, id);
+        ",
+    },
+    Migration {
+        version: 8,
+        name: "process_registry",
+        sql: "CREATE TABLE process_registry (process_id TEXT PRIMARY KEY, entry TEXT NOT NULL, started_at TEXT NOT NULL);",
+    },
+    Migration {
+        version: 9,
+        name: "pending_downloads",
+        sql: "CREATE TABLE pending_downloads (id TEXT PRIMARY KEY, config TEXT NOT NULL, started_at TEXT NOT NULL);",
+    },
+];
+
+/// Tables the legacy `tracker/tracker.db` can hold
+const TRACKER_TABLES: [&str; 8] = [
+    "models",
+    "tracker_config",
+    "weekly_reports",
+    "watchlist",
+    "model_files",
+    "scrape_runs",
+    "model_sightings",
+    "model_classifications",
+];
+
+pub fn database_path() -> Result<PathBuf, String> {
+    Ok(crate::arandu_base_dir()?.join("arandu.db"))
+}
+
+/// Apply every migration newer than the recorded schema version, each in its own transaction
+fn migrate(conn: &mut Connection) -> Result<u32, String> {
+    conn.execute_batch("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER PRIMARY KEY, name TEXT NOT NULL, applied_at TEXT NOT NULL)")
+        .map_err(|e| format!("Failed to create schema_version table: {}", e))?;
+    let recorded: u32 = conn
+        .query_row("SELECT COALESCE(MAX(version), 0) FROM schema_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read schema version: {}", e))?;
+
+    let latest = MIGRATIONS.last().map(|m| m.version).unwrap_or(0);
+    if recorded > latest {
+        eprintln!("[Storage] Database schema {} is newer than this build knows ({}); opening anyway", recorded, latest);
+    }
+
+    let mut current = recorded;
+    for migration in MIGRATIONS.iter().filter(|m| m.version > recorded) {
+        let tx = conn.transaction().map_err(|e| format!("Failed to start migration: {}", e))?;
+        tx.execute_batch(migration.sql)
+            .map_err(|e| format!("Migration {} ({}) failed: {}", migration.version, migration.name, e))?;
+        tx.execute(
+            "INSERT INTO schema_version (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            params![migration.version, migration.name, chrono::Utc::now().to_rfc3339()],
+        ).map_err(|e| format!("Failed to record migration {}: {}", migration.version, e))?;
+        tx.commit().map_err(|e| format!("Failed to commit migration {}: {}", migration.version, e))?;
+        println!("[Storage] Applied migration {} ({})", migration.version, migration.name);
+        current = migration.version;
+    }
+    Ok(current)
+}
+
+static REBUILT: Mutex<Option<String>> = Mutex::new(None);
+
+fn quick_check(conn: &Connection) -> Result<(), String> {
+    let result: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if result == "ok" {
+        Ok(())
+    } else {
+        Err(result)
+    }
+}
+
+/// Open `path`, moving it aside and starting empty when SQLite finds it corrupt
+fn open_checked(path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|e| format!("Failed to open database: {}", e))?;
+    let Err(problem) = quick_check(&conn) else { return Ok(conn) };
+    drop(conn);
+
+    let backup = path.with_extension(format!("db.corrupt-{}", chrono::Utc::now().format("%Y%m%d%H%M%S")));
+    std::fs::rename(path, &backup).map_err(|e| format!("Database is corrupt ({}) and could not be moved aside: {}", problem, e))?;
+    for suffix in ["-wal", "-shm"] {
+        let side = PathBuf::from(format!("{}{}", path.display(), suffix));
+        if side.exists() {
+            let _ = std::fs::rename(&side, format!("{}{}", backup.display(), suffix));
+        }
+    }
+    let notice = format!("The database was corrupt ({}) and has been rebuilt; the old file was kept as {}", problem, backup.display());
+    eprintln!("[Storage] {}", notice);
+    *REBUILT.lock().unwrap_or_else(|p| p.into_inner()) = Some(notice);
+    Connection::open(path).map_err(|e| format!("Failed to open database: {}", e))
+}
+
+/// Set when startup found the database corrupt and replaced it
+pub fn rebuilt_notice() -> Option<String> {
+    REBUILT.lock().unwrap_or_else(|p| p.into_inner()).clone()
+}
+
+/// `PRAGMA quick_check` on the shared connection
+pub fn check_integrity() -> Result<(), String> {
+    with(|db| quick_check(db.conn))
+}
+
+/// Open the database at `path` and bring its schema up to date
+pub fn connect(path: &Path) -> Result<Connection, String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create storage directory: {}", e))?;
+    }
+    let mut conn = open_checked(path)?;
+    // WAL lets the tracker thread and the shared connection read while the other writes
+    conn.execute_batch("PRAGMA journal_mode = WAL;")
+        .map_err(|e| format!("Failed to configure database: {}", e))?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(|e| format!("Failed to configure database: {}", e))?;
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+fn table_columns(conn: &Connection, schema: &str, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA {}.table_info({})", schema, table))
+        .map_err(|e| format!("Failed to read columns of {}: {}", table, e))?;
+    let columns = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|e| format!("Failed to read columns of {}: {}", table, e))?
+        .filter_map(|c| c.ok())
+        .collect();
+    Ok(columns)
+}
+
+/// Copy a tracker database from before the unified store into `conn`, then rename it so
+/// this happens once. Rows already present win.
+pub fn import_legacy_tracker(conn: &Connection, legacy: &Path) -> Result<usize, String> {
+    if !legacy.exists() {
+        return Ok(0);
+    }
+    conn.execute("ATTACH DATABASE ?1 AS legacy", params![legacy.to_string_lossy()])
+        .map_err(|e| format!("Failed to open legacy tracker database: {}", e))?;
+
+    let copy = || -> Result<usize, String> {
+        conn.execute_batch("BEGIN").map_err(|e| format!("Failed to start import: {}", e))?;
+        let mut copied = 0;
+        for table in TRACKER_TABLES {
+            let legacy_columns = table_columns(conn, "legacy", table)?;
+            let columns: Vec<String> = table_columns(conn, "main", table)?
+                .into_iter()
+                .filter(|c| legacy_columns.contains(c))
+                .collect();
+            if columns.is_empty() {
+                continue;
+            }
+            let list = columns.join(", ");
+            copied += conn
+                .execute(&format!("INSERT OR IGNORE INTO main.{table} ({list}) SELECT {list} FROM legacy.{table}"), [])
+                .map_err(|e| format!("Failed to import {}: {}", table, e))?;
+        }
+        conn.execute_batch("COMMIT").map_err(|e| format!("Failed to finish import: {}", e))?;
+        Ok(copied)
+    };
+    let result = copy();
+    if result.is_err() {
+        let _ = conn.execute_batch("ROLLBACK");
+    }
+    let _ = conn.execute_batch("DETACH DATABASE legacy");
+    let copied = result?;
+
+    let imported = legacy.with_extension("db.imported");
+    if let Err(e) = std::fs::rename(legacy, &imported) {
+        eprintln!("[Storage] Imported the legacy tracker database but could not rename it: {}", e);
+    }
+    println!("[Storage] Imported {} row(s) from {}", copied, legacy.display());
+    Ok(copied)
+}
+
+static SHARED: Mutex<Option<Connection>> = Mutex::new(None);
+
+/// The shared connection, opened on first use
+pub fn with<T>(f: impl FnOnce(&Db) -> Result<T, String>) -> Result<T, String> {
+    let mut shared = SHARED.lock().unwrap_or_else(|p| p.into_inner());
+    if shared.is_none() {
+        *shared = Some(connect(&database_path()?)?);
+    }
+    let conn = shared.as_ref().ok_or_else(|| "Storage is not available".to_string())?;
+    f(&Db { conn })
+}
+
+/// `with` for async callers, off the runtime threads
+pub async fn run<T, F>(f: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&Db) -> Result<T, String> + Send + 'static,
+{
+    tokio::task::spawn_blocking(move || with(f))
+        .await
+        .map_err(|e| format!("Storage task failed: {}", e))?
+}
+
+pub struct Db<'c> {
+    conn: &'c Connection,
+}
+
+impl<'c> Db<'c> {
+    pub fn usage(&self) -> UsageRepo<'c> {
+        UsageRepo { conn: self.conn }
+    }
+
+    pub fn benchmarks(&self) -> BenchmarkRepo<'c> {
+        BenchmarkRepo { conn: self.conn }
+    }
+
+    pub fn downloads(&self) -> DownloadRepo<'c> {
+        DownloadRepo { conn: self.conn }
+    }
+
+    pub fn chats(&self) -> ChatRepo<'c> {
+        ChatRepo { conn: self.conn }
+    }
+
+    pub fn analytics(&self) -> AnalyticsRepo<'c> {
+        AnalyticsRepo { conn: self.conn }
+    }
+
+    pub fn mcp_health(&self) -> McpHealthRepo<'c> {
+        McpHealthRepo { conn: self.conn }
+    }
+
+    pub fn processes(&self) -> ProcessRegistryRepo<'c> {
+        ProcessRegistryRepo { conn: self.conn }
+    }
+}
+
+fn parse_json_column<T: serde::de::DeserializeOwned>(raw: String, what: &str) -> Option<T> {
+    match serde_json::from_str(&raw) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("[Storage] Skipping unreadable {}: {}", what, e);
+            None
+        }
+    }
+}
+
+/// Energy and token samples per generation, see `energy_report`
+pub struct UsageRepo<'c> {
+    conn: &'c Connection,
+}
+
+impl UsageRepo<'_> {
+    /// Insert samples, keeping the newest `keep`
+    pub fn record(&self, samples: &[EnergySample], keep: usize) -> Result<(), String> {
+        for sample in samples {
+            self.conn.execute(
+                "INSERT INTO usage_samples (model, backend, source, tokens, energy_wh, avg_power_watts, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    sample.model,
+                    sample.backend,
+                    sample.source,
+                    sample.tokens as i64,
+                    sample.energy_wh,
+                    sample.avg_power_watts as f64,
+                    sample.recorded_at.to_rfc3339(),
+                ],
+            ).map_err(|e| format!("Failed to record usage sample: {}", e))?;
+        }
+        self.conn.execute(
+            "DELETE FROM usage_samples WHERE id NOT IN (SELECT id FROM usage_samples ORDER BY id DESC LIMIT ?1)",
+            params![keep as i64],
+        ).map_err(|e| format!("Failed to trim usage samples: {}", e))?;
+        Ok(())
+    }
+
+    pub fn samples(&self) -> Result<Vec<EnergySample>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT model, backend, source, tokens, energy_wh, avg_power_watts, recorded_at FROM usage_samples ORDER BY id")
+            .map_err(|e| format!("Failed to read usage samples: {}", e))?;
+        let samples = stmt
+            .query_map([], |row| {
+                let recorded_at: String = row.get(6)?;
+                Ok(EnergySample {
+                    model: row.get(0)?,
+                    backend: row.get(1)?,
+                    source: row.get(2)?,
+                    tokens: row.get::<_, i64>(3)?.max(0) as u64,
+                    energy_wh: row.get(4)?,
+                    avg_power_watts: row.get::<_, f64>(5)? as f32,
+                    recorded_at: chrono::DateTime::parse_from_rfc3339(&recorded_at)
+                        .map(|t| t.with_timezone(&chrono::Utc))
+                        .unwrap_or_default(),
+                })
+            })
+            .map_err(|e| format!("Failed to read usage samples: {}", e))?
+            .filter_map(|s| s.ok())
+            .collect();
+        Ok(samples)
+    }
+}
+
+/// Backend benchmark winners keyed by model class
+pub struct BenchmarkRepo<'c> {
+    conn: &'c Connection,
+}
+
+impl BenchmarkRepo<'_> {
+    pub fn save(&self, record: &BackendBenchmarkRecord) -> Result<(), String> {
+        let raw = serde_json::to_string(record).map_err(|e| format!("Failed to serialize benchmark: {}", e))?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO backend_benchmarks (model_class, record, measured_at) VALUES (?1, ?2, ?3)",
+            params![record.model_class, raw, record.measured_at.to_rfc3339()],
+        ).map_err(|e| format!("Failed to save benchmark: {}", e))?;
+        Ok(())
+    }
+
+    pub fn all(&self) -> Result<Vec<BackendBenchmarkRecord>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT record FROM backend_benchmarks ORDER BY model_class")
+            .map_err(|e| format!("Failed to read benchmarks: {}", e))?;
+        let records = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Failed to read benchmarks: {}", e))?
+            .filter_map(|raw| raw.ok())
+            .filter_map(|raw| parse_json_column(raw, "benchmark"))
+            .collect();
+        Ok(records)
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        self.conn.execute("DELETE FROM backend_benchmarks", [])
+            .map_err(|e| format!("Failed to clear benchmarks: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Finished downloads from earlier sessions, and running ones to restart after an exit
+pub struct DownloadRepo<'c> {
+    conn: &'c Connection,
+}
+
+impl DownloadRepo<'_> {
+    pub fn record(&self, status: &DownloadStatus) -> Result<(), String> {
+        let raw = serde_json::to_string(status).map_err(|e| format!("Failed to serialize download: {}", e))?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO download_history (id, status, finished_at) VALUES (?1, ?2, ?3)",
+            params![status.id, raw, chrono::Utc::now().to_rfc3339()],
+        ).map_err(|e| format!("Failed to record download: {}", e))?;
+        Ok(())
+    }
+
+    /// Newest first
+    pub fn history(&self, limit: usize) -> Result<Vec<DownloadStatus>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT status FROM download_history ORDER BY finished_at DESC LIMIT ?1")
+            .map_err(|e| format!("Failed to read download history: {}", e))?;
+        let history = stmt
+            .query_map(params![limit as i64], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Failed to read download history: {}", e))?
+            .filter_map(|raw| raw.ok())
+            .filter_map(|raw| parse_json_column(raw, "download record"))
+            .collect();
+        Ok(history)
+    }
+
+    /// Remember a started download until it finishes, see `download_resume`
+    pub fn track_pending(&self, id: &str, config: &DownloadConfig) -> Result<(), String> {
+        let raw = serde_json::to_string(config).map_err(|e| format!("Failed to serialize download: {}", e))?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO pending_downloads (id, config, started_at) VALUES (?1, ?2, ?3)",
+            params![id, raw, chrono::Utc::now().to_rfc3339()],
+        ).map_err(|e| format!("Failed to record download: {}", e))?;
+        Ok(())
+    }
+
+    pub fn finish_pending(&self, id: &str) -> Result<(), String> {
+        self.conn.execute("DELETE FROM pending_downloads WHERE id = ?1", params![id])
+            .map_err(|e| format!("Failed to forget download: {}", e))?;
+        Ok(())
+    }
+
+    /// Oldest first
+    pub fn pending(&self) -> Result<Vec<(String, DownloadConfig)>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, config FROM pending_downloads ORDER BY started_at")
+            .map_err(|e| format!("Failed to read pending downloads: {}", e))?;
+        let pending = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| format!("Failed to read pending downloads: {}", e))?
+            .filter_map(|row| row.ok())
+            .filter_map(|(id, raw)| Some((id, parse_json_column(raw, "pending download")?)))
+            .collect();
+        Ok(pending)
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        self.conn.execute("DELETE FROM download_history", [])
+            .map_err(|e| format!("Failed to clear download history: {}", e))?;
+        Ok(())
+    }
+}
+
+/// llama-server processes Arandu started, see `process_registry`
+pub struct ProcessRegistryRepo<'c> {
+    conn: &'c Connection,
+}
+
+impl ProcessRegistryRepo<'_> {
+    pub fn record(&self, process: &RegisteredProcess) -> Result<(), String> {
+        let raw = serde_json::to_string(process).map_err(|e| format!("Failed to serialize process: {}", e))?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO process_registry (process_id, entry, started_at) VALUES (?1, ?2, ?3)",
+            params![process.process_id, raw, process.started_at.to_rfc3339()],
+        ).map_err(|e| format!("Failed to record process: {}", e))?;
+        Ok(())
+    }
+
+    pub fn remove(&self, process_id: &str) -> Result<(), String> {
+        self.conn.execute("DELETE FROM process_registry WHERE process_id = ?1", params![process_id])
+            .map_err(|e| format!("Failed to forget process: {}", e))?;
+        Ok(())
+    }
+
+    /// Oldest first
+    pub fn all(&self) -> Result<Vec<RegisteredProcess>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT entry FROM process_registry ORDER BY started_at")
+            .map_err(|e| format!("Failed to read process registry: {}", e))?;
+        let processes = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Failed to read process registry: {}", e))?
+            .filter_map(|raw| raw.ok())
+            .filter_map(|raw| parse_json_column(raw, "process entry"))
+            .collect();
+        Ok(processes)
+    }
+}
+
+/// Mirror of the chats index (`chats/index.json`); the markdown logs stay files
+pub struct ChatRepo<'c> {
+    conn: &'c Connection,
+}
+
+impl ChatRepo<'_> {
+    /// Replace the mirror with `index`
+    pub fn replace_index(&self, index: &[serde_json::Value]) -> Result<(), String> {
+        self.conn.execute_batch("BEGIN").map_err(|e| format!("Failed to start chats update: {}", e))?;
+        let write = || -> Result<(), String> {
+            self.conn.execute("DELETE FROM chats", []).map_err(|e| format!("Failed to update chats: {}", e))?;
+            for entry in index {
+                let Some(chat_id) = entry.get("chat_id").and_then(|v| v.as_str()) else { continue };
+                self.conn.execute(
+                    "INSERT OR REPLACE INTO chats (chat_id, entry, last_used_at) VALUES (?1, ?2, ?3)",
+                    params![chat_id, entry.to_string(), entry.get("last_used_at").and_then(|v| v.as_str())],
+                ).map_err(|e| format!("Failed to update chats: {}", e))?;
+            }
+            Ok(())
+        };
+        match write() {
+            Ok(()) => self.conn.execute_batch("COMMIT").map_err(|e| format!("Failed to update chats: {}", e)),
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    /// Most recently used first
+    pub fn index(&self) -> Result<Vec<serde_json::Value>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT entry FROM chats ORDER BY last_used_at DESC")
+            .map_err(|e| format!("Failed to read chats: {}", e))?;
+        let entries = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| format!("Failed to read chats: {}", e))?
+            .filter_map(|raw| raw.ok())
+            .filter_map(|raw| parse_json_column(raw, "chat entry"))
+            .collect();
+        Ok(entries)
+    }
+}
+
+/// Opt-in local usage events, see `analytics`
+pub struct AnalyticsRepo<'c> {
+    conn: &'c Connection,
+}
+
+impl AnalyticsRepo<'_> {
+    /// Insert an event, keeping the newest `keep`
+    pub fn record(&self, event: &AnalyticsEvent, keep: usize) -> Result<(), String> {
+        let (kind, name, success, bytes, seconds) = match event {
+            AnalyticsEvent::Feature { name } => ("feature", name.as_str(), None, None, None),
+            AnalyticsEvent::Launch { model, success } => ("launch", model.as_str(), Some(*success), None, None),
+            AnalyticsEvent::Download { bytes, seconds } => ("download", "", None, Some(*bytes as i64), Some(*seconds)),
+        };
+        self.conn.execute(
+            "INSERT INTO analytics_events (kind, name, success, bytes, seconds, recorded_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![kind, name, success, bytes, seconds, chrono::Utc::now().to_rfc3339()],
+        ).map_err(|e| format!("Failed to record usage event: {}", e))?;
+        self.conn.execute(
+            "DELETE FROM analytics_events WHERE id NOT IN (SELECT id FROM analytics_events ORDER BY id DESC LIMIT ?1)",
+            params![keep as i64],
+        ).map_err(|e| format!("Failed to trim usage events: {}", e))?;
+        Ok(())
+    }
+
+    /// Oldest first
+    pub fn events(&self) -> Result<Vec<RecordedEvent>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT kind, name, success, bytes, seconds, recorded_at FROM analytics_events ORDER BY id")
+            .map_err(|e| format!("Failed to read usage events: {}", e))?;
+        let events = stmt
+            .query_map([], |row| {
+                let kind: String = row.get(0)?;
+                let name: String = row.get(1)?;
+                let event = match kind.as_str() {
+                    "launch" => AnalyticsEvent::Launch { model: name, success: row.get::<_, Option<bool>>(2)?.unwrap_or(false) },
+                    "download" => AnalyticsEvent::Download {
+                        bytes: row.get::<_, Option<i64>>(3)?.unwrap_or(0).max(0) as u64,
+                        seconds: row.get::<_, Option<f64>>(4)?.unwrap_or(0.0),
+                    },
+                    _ => AnalyticsEvent::Feature { name },
+                };
+                let recorded_at: String = row.get(5)?;
+                Ok(RecordedEvent {
+                    event,
+                    recorded_at: chrono::DateTime::parse_from_rfc3339(&recorded_at)
+                        .map(|t| t.with_timezone(&chrono::Utc))
+                        .unwrap_or_default(),
+                })
+            })
+            .map_err(|e| format!("Failed to read usage events: {}", e))?
+            .filter_map(|e| e.ok())
+            .collect();
+        Ok(events)
+    }
+
+    pub fn clear(&self) -> Result<(), String> {
+        self.conn.execute("DELETE FROM analytics_events", [])
+            .map_err(|e| format!("Failed to clear usage events: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Results of MCP health checks, see `mcp_health`
+pub struct McpHealthRepo<'c> {
+    conn: &'c Connection,
+}
+
+impl McpHealthRepo<'_> {
+    /// Insert a check, keeping the newest `keep` of its connection
+    pub fn record(&self, check: &HealthCheck, keep: usize) -> Result<(), String> {
+        self.conn.execute(
+            "INSERT INTO mcp_health_checks (connection_id, success, latency_ms, message, checked_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![check.connection_id, check.success, check.latency_ms, check.message, check.checked_at.to_rfc3339()],
+        ).map_err(|e| format!("Failed to record MCP health check: {}", e))?;
+        self.conn.execute(
+            "DELETE FROM mcp_health_checks WHERE connection_id = ?1 AND id NOT IN
+                (SELECT id FROM mcp_health_checks WHERE connection_id = ?1 ORDER BY id DESC LIMIT ?2)",
+            params![check.connection_id, keep as i64],
+        ).map_err(|e| format!("Failed to trim MCP health checks: {}", e))?;
+        Ok(())
+    }
+
+    /// Oldest first
+    pub fn history(&self, connection_id: &str) -> Result<Vec<HealthCheck>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT success, latency_ms, message, checked_at FROM mcp_health_checks WHERE connection_id = ?1 ORDER BY id")
+            .map_err(|e| format!("Failed to read MCP health checks: {}", e))?;
+        let checks = stmt
+            .query_map(params![connection_id], |row| {
+                let checked_at: String = row.get(3)?;
+                Ok(HealthCheck {
+                    connection_id: connection_id.to_string(),
+                    success: row.get(0)?,
+                    latency_ms: row.get(1)?,
+                    message: row.get(2)?,
+                    checked_at: chrono::DateTime::parse_from_rfc3339(&checked_at)
+                        .map(|t| t.with_timezone(&chrono::Utc))
+                        .unwrap_or_default(),
+                })
+            })
+            .map_err(|e| format!("Failed to read MCP health checks: {}", e))?
+            .filter_map(|c| c.ok())
+            .collect();
+        Ok(checks)
+    }
+
+    pub fn forget(&self, connection_id: &str) -> Result<(), String> {
+        self.conn.execute("DELETE FROM mcp_health_checks WHERE connection_id = ?1", params![connection_id])
+            .map_err(|e| format!("Failed to clear MCP health checks: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn migrations_apply_once_and_repositories_round_trip() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn).unwrap(), 9);
+        assert_eq!(migrate(&mut conn).unwrap(), 9);
+        let applied: i64 = conn.query_row("SELECT COUNT(*) FROM schema_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(applied, MIGRATIONS.len() as i64);
+
+        let db = Db { conn: &conn };
+        let sample = |tokens| EnergySample {
+            model: "qwen".to_string(),
+            backend: Some("cuda".to_string()),
+            source: "proxy".to_string(),
+            tokens,
+            energy_wh: 1.5,
+            avg_power_watts: 210.0,
+            recorded_at: chrono::Utc::now(),
+        };
+        db.usage().record(&[sample(10), sample(20), sample(30)], 2).unwrap();
+        let tokens: Vec<u64> = db.usage().samples().unwrap().iter().map(|s| s.tokens).collect();
+        assert_eq!(tokens, vec![20, 30]);
+
+        let index = vec![
+            serde_json::json!({"chat_id": "a", "title": "Old", "last_used_at": "2026-01-01T00:00:00Z"}),
+            serde_json::json!({"chat_id": "b", "title": "New", "last_used_at": "2026-02-01T00:00:00Z"}),
+            serde_json::json!({"title": "no id"}),
+        ];
+        db.chats().replace_index(&index).unwrap();
+        let mirrored = db.chats().index().unwrap();
+        assert_eq!(mirrored.len(), 2);
+        assert_eq!(mirrored[0]["chat_id"], "b");
+        db.chats().replace_index(&index[..1]).unwrap();
+        assert_eq!(db.chats().index().unwrap().len(), 1);
+
+        assert!(db.benchmarks().all().unwrap().is_empty());
+        assert!(db.downloads().history(10).unwrap().is_empty());
+        let config = DownloadConfig {
+            base_url: "https://huggingface.co/org/model/resolve/main/model.gguf".to_string(),
+            destination_folder: "/models".to_string(),
+            auto_extract: false,
+            create_subfolder: None,
+            files: Vec::new(),
+            custom_headers: None,
+            expected_sha256: None,
+        };
+        db.downloads().track_pending("d1", &config).unwrap();
+        assert_eq!(db.downloads().pending().unwrap()[0].1.base_url, config.base_url);
+        db.downloads().finish_pending("d1").unwrap();
+        assert!(db.downloads().pending().unwrap().is_empty());
+
+        let process = RegisteredProcess {
+            process_id: "p1".to_string(),
+            pid: 4242,
+            executable: "llama-server".to_string(),
+            model_path: "/models/qwen.gguf".to_string(),
+            model_name: "qwen".to_string(),
+            host: "127.0.0.1".to_string(),
+            port: 8081,
+            started_at: chrono::Utc::now(),
+        };
+        db.processes().record(&process).unwrap();
+        db.processes().record(&RegisteredProcess { pid: 4243, ..process.clone() }).unwrap();
+        assert_eq!(db.processes().all().unwrap().iter().map(|p| p.pid).collect::<Vec<_>>(), vec![4243]);
+        db.processes().remove("p1").unwrap();
+        assert!(db.processes().all().unwrap().is_empty());
+    }
+}
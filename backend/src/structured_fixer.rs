@@ -0,0 +1,387 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use crate::http_pool::{self, RequestClass};
+use crate::AppState;
+
+// Validation and repair of JSON, YAML and TOML documents, for anything users paste
+// or install: MCP configs, pack manifests, tool arguments. Cheap deterministic passes
+// run first (Markdown fences, typographic quotes, comments, trailing commas, single
+// quotes, bare keys, unclosed brackets); only what they cannot fix goes to a running
+// model. Schemas use the subset `tool_call_test::validate` understands.
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    /// `json`, `yaml`/`yml` or `toml`; guessed from the text when empty or `auto`
+    pub fn parse(name: Option<&str>, raw: &str) -> Result<Format, String> {
+        match name.map(|n| n.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("auto") => Ok(Self::detect(raw)),
+            Some("json") => Ok(Format::Json),
+            Some("yaml") | Some("yml") => Ok(Format::Yaml),
+            Some("toml") => Ok(Format::Toml),
+            Some(other) => Err(format!("Unsupported format '{}' (use json, yaml or toml)", other)),
+        }
+    }
+
+    fn detect(raw: &str) -> Format {
+        let text = fenced_body(raw).unwrap_or(raw).trim_start_matches('\u{feff}').trim();
+        let first_line = text.lines().next().unwrap_or_default().trim();
+        let toml_table = first_line.starts_with('[') && first_line.ends_with(']') && !first_line.contains([',', '{', '"']);
+        if !toml_table && text.starts_with(['{', '[']) {
+            return Format::Json;
+        }
+        let assignment = |line: &str| {
+            let line = line.trim();
+            line.split_once('=').is_some_and(|(key, _)| {
+                !key.is_empty() && key.trim().chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '"'))
+            }) && !line.contains(": ")
+        };
+        if toml_table || text.lines().any(assignment) {
+            Format::Toml
+        } else {
+            Format::Yaml
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Format::Json => "JSON",
+            Format::Yaml => "YAML",
+            Format::Toml => "TOML",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FixResult {
+    /// Parses, and matches the schema when one was given
+    pub valid: bool,
+    pub format: Format,
+    /// The document in `format`, `None` when it still does not parse
+    pub output: Option<String>,
+    pub value: Option<Value>,
+    /// What was changed, in order
+    pub fixes: Vec<String>,
+    /// Parse error or schema violations left
+    pub errors: Vec<String>,
+    /// Model that rewrote the document, when one was needed
+    pub model: Option<String>,
+}
+
+fn note(fixes: &mut Vec<String>, fix: &str) {
+    if !fixes.iter().any(|f| f == fix) {
+        fixes.push(fix.to_string());
+    }
+}
+
+/// Body of the first Markdown code fence
+fn fenced_body(raw: &str) -> Option<&str> {
+    let after = &raw[raw.find("```")? + 3..];
+    let body = &after[after.find('\n')? + 1..];
+    Some(&body[..body.find("```").unwrap_or(body.len())])
+}
+
+/// Drop a comma at the end of `out`, ignoring whitespace after it
+fn drop_trailing_comma(out: &mut String, fixes: &mut Vec<String>) {
+    let end = out.trim_end().len();
+    if out[..end].ends_with(',') {
+        out.truncate(end - 1);
+        note(fixes, "Removed trailing commas");
+    }
+}
+
+/// Lexical JSON repairs; the result is not guaranteed to parse
+fn repair_json(text: &str, fixes: &mut Vec<String>) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut closers: Vec<char> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' | '\'' => {
+                if c == '\'' {
+                    note(fixes, "Replaced single quotes with double quotes");
+                }
+                out.push('"');
+                i += 1;
+                let mut closed = false;
+                while i < chars.len() {
+                    match chars[i] {
+                        '\\' if i + 1 < chars.len() => {
+                            match chars[i + 1] {
+                                '\'' => out.push('\''),
+                                next => {
+                                    out.push('\\');
+                                    out.push(next);
+                                }
+                            }
+                            i += 2;
+                            continue;
+                        }
+                        ch if ch == c => {
+                            closed = true;
+                            i += 1;
+                            break;
+                        }
+                        // Only reachable inside single-quoted strings
+                        '"' => out.push_str("\\\""),
+                        '\n' | '\r' | '\t' => {
+                            out.push_str(match chars[i] {
+                                '\n' => "\\n",
+                                '\r' => "\\r",
+                                _ => "\\t",
+                            });
+                            note(fixes, "Escaped control characters inside strings");
+                        }
+                        ch => out.push(ch),
+                    }
+                    i += 1;
+                }
+                if !closed {
+                    note(fixes, "Closed an unterminated string");
+                }
+                out.push('"');
+            }
+            '/' if matches!(chars.get(i + 1), Some('/') | Some('*')) => {
+                let block = chars[i + 1] == '*';
+                i += 2;
+                while i < chars.len() {
+                    if !block && chars[i] == '\n' {
+                        break;
+                    }
+                    if block && chars[i] == '*' && chars.get(i + 1) == Some(&'/') {
+                        i += 2;
+                        break;
+                    }
+                    i += 1;
+                }
+                note(fixes, "Removed comments");
+            }
+            '{' | '[' => {
+                closers.push(if c == '{' { '}' } else { ']' });
+                out.push(c);
+                i += 1;
+            }
+            '}' | ']' => {
+                drop_trailing_comma(&mut out, fixes);
+                if closers.last() == Some(&c) {
+                    closers.pop();
+                }
+                out.push(c);
+                i += 1;
+            }
+            c if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | '$' | '-')) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                let next = chars[i..].iter().find(|ch| !ch.is_whitespace());
+                if next == Some(&':') {
+                    out.push('"');
+                    out.push_str(&word);
+                    out.push('"');
+                    note(fixes, "Quoted object keys");
+                } else {
+                    let literal = match word.as_str() {
+                        "True" => "true",
+                        "False" => "false",
+                        "None" => "null",
+                        _ => {
+                            out.push_str(&word);
+                            continue;
+                        }
+                    };
+                    out.push_str(literal);
+                    note(fixes, "Converted Python literals");
+                }
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    if !closers.is_empty() {
+        drop_trailing_comma(&mut out, fixes);
+        out.extend(closers.iter().rev());
+        note(fixes, "Closed unterminated brackets");
+    }
+    out
+}
+
+/// Deterministic repairs for `format`
+fn repair(raw: &str, format: Format, fixes: &mut Vec<String>) -> String {
+    let mut text = raw.trim().trim_start_matches('\u{feff}').to_string();
+    if let Some(body) = fenced_body(&text) {
+        text = body.trim().to_string();
+        note(fixes, "Removed the Markdown code fence");
+    }
+    if text.contains(['\u{201c}', '\u{201d}', '\u{2018}', '\u{2019}']) {
+        text = text.replace(['\u{201c}', '\u{201d}'], "\"").replace(['\u{2018}', '\u{2019}'], "'");
+        note(fixes, "Replaced typographic quotes");
+    }
+    match format {
+        Format::Json => {
+            if !text.starts_with(['{', '[']) {
+                if let (Some(start), Some(end)) = (text.find(['{', '[']), text.rfind(['}', ']'])) {
+                    if start < end {
+                        text = text[start..=end].to_string();
+                        note(fixes, "Dropped text around the JSON");
+                    }
+                }
+            }
+            repair_json(&text, fixes)
+        }
+        Format::Yaml if text.lines().any(|line| line.starts_with('\t')) => {
+            note(fixes, "Replaced tab indentation with spaces");
+            text.lines()
+                .map(|line| {
+                    let tabs = line.len() - line.trim_start_matches('\t').len();
+                    format!("{}{}", "  ".repeat(tabs), &line[tabs..])
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        _ => text,
+    }
+}
+
+fn parse(text: &str, format: Format) -> Result<Value, String> {
+    match format {
+        Format::Json => serde_json::from_str(text).map_err(|e| format!("Invalid JSON: {}", e)),
+        Format::Yaml => serde_yaml::from_str(text).map_err(|e| format!("Invalid YAML: {}", e)),
+        Format::Toml => toml::from_str(text).map_err(|e| format!("Invalid TOML: {}", e)),
+    }
+}
+
+fn render(value: &Value, format: Format) -> Result<String, String> {
+    match format {
+        Format::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+        Format::Yaml => serde_yaml::to_string(value).map_err(|e| e.to_string()),
+        Format::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+    }
+}
+
+/// Parse `raw`, repairing it if needed; errors report the original text
+pub fn parse_lenient(raw: &str, format: Format) -> Result<(Value, Vec<String>), String> {
+    let mut fixes = Vec::new();
+    match parse(raw.trim(), format) {
+        Ok(value) => Ok((value, fixes)),
+        Err(error) => parse(&repair(raw, format, &mut fixes), format).map(|value| (value, fixes)).map_err(|_| error),
+    }
+}
+
+/// Deterministic validation and repair, without a model
+pub fn check(raw: &str, format: Format, schema: Option<&Value>) -> FixResult {
+    let (value, fixes) = match parse_lenient(raw, format) {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            return FixResult { valid: false, format, output: None, value: None, fixes: Vec::new(), errors: vec![error], model: None };
+        }
+    };
+    let mut errors = Vec::new();
+    if let Some(schema) = schema {
+        crate::tool_call_test::validate(schema, &value, "$", &mut errors);
+    }
+    // YAML and TOML keep their comments and layout when nothing needed fixing
+    let output = if fixes.is_empty() && format != Format::Json {
+        Some(raw.trim().to_string())
+    } else {
+        render(&value, format).map_err(|e| errors.push(format!("Failed to format {}: {}", format.label(), e))).ok()
+    };
+    FixResult { valid: errors.is_empty(), format, output, value: Some(value), fixes, errors, model: None }
+}
+
+/// `check`, then let a running model (`model` as in the tool-call console) rewrite
+/// what is still broken
+pub async fn repair_with_model(
+    state: &AppState,
+    model: &str,
+    raw: &str,
+    format: Format,
+    schema: Option<&Value>,
+) -> Result<FixResult, String> {
+    let first = check(raw, format, schema);
+    if first.valid {
+        return Ok(first);
+    }
+    let (server_url, model_name) = crate::tool_call_test::resolve_server(model, state).await?;
+
+    let label = format.label();
+    let mut prompt = format!(
+        "You are a strict {} fixer. Correct the input so it is valid {}{}, keep its content, and return only the corrected {} with no explanation or markdown.\n\nProblems:\n- {}\n",
+        label,
+        label,
+        if schema.is_some() { " matching the JSON schema below" } else { "" },
+        label,
+        first.errors.join("\n- ")
+    );
+    if let Some(schema) = schema {
+        prompt.push_str(&format!("\nSchema:\n{}\n", schema));
+    }
+    prompt.push_str(&format!("\nInput:\n{}", raw.trim()));
+
+    let request = http_pool::client_for(&server_url)
+        .post(format!("{}/v1/chat/completions", server_url))
+        .json(&json!({
+            "messages": [
+                {"role": "system", "content": format!("Return only valid {}. No prose.", label)},
+                {"role": "user", "content": prompt}
+            ],
+            "stream": false
+        }));
+    let response = http_pool::with_timeout(request, RequestClass::Completion)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact model: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Model request failed with status {}", response.status().as_u16()));
+    }
+    let body: Value = response.json().await.map_err(|e| format!("Failed to parse model response: {}", e))?;
+    let corrected = body
+        .pointer("/choices/0/message/content")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Model returned empty content".to_string())?;
+
+    let mut result = check(corrected, format, schema);
+    result.fixes.insert(0, format!("Rewritten by {}", model_name));
+    result.model = Some(model_name);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_passes_fix_common_mistakes() {
+        let pasted = "Here is the config:\n```json\n{\n  // local server\n  mcpServers: {'fs': {\"command\": \"npx\", \"args\": [\"-y\",],},},\n  \"debug\": True,\n```";
+        assert_eq!(Format::parse(None, pasted), Ok(Format::Json));
+        let result = check(pasted, Format::Json, None);
+        assert!(result.valid, "{:?}", result.errors);
+        assert_eq!(result.value.unwrap(), json!({"mcpServers": {"fs": {"command": "npx", "args": ["-y"]}}, "debug": true}));
+        for fix in ["Removed comments", "Quoted object keys", "Removed trailing commas", "Closed unterminated brackets"] {
+            assert!(result.fixes.iter().any(|f| f == fix), "missing {}", fix);
+        }
+
+        let schema = json!({"type": "object", "required": ["name"], "properties": {"port": {"type": "integer"}}});
+        let yaml = check("port: \"8080\"\n", Format::Yaml, Some(&schema));
+        assert!(!yaml.valid);
+        assert_eq!(yaml.errors.len(), 2);
+        assert_eq!(yaml.output.as_deref(), Some("port: \"8080\""));
+
+        assert_eq!(Format::parse(None, "[server]\nport = 8080"), Ok(Format::Toml));
+        assert_eq!(check("[server]\nport = 8080", Format::Toml, None).value.unwrap()["server"]["port"], 8080);
+        assert!(!check("{\"a\": }", Format::Json, None).valid);
+    }
+}
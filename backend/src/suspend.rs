@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use crate::http_pool::{self, RequestClass};
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+// Pausing a model so the GPU can be used for something else. `Paused` freezes the
+// process at the OS level: compute stops but weights stay resident, so resuming is
+// instant. `Unloaded` saves the KV-cache slots, stops the server and relaunches it
+// with the same preset on resume, which actually frees VRAM.
+
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(300);
+const HEALTH_POLL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuspendMode {
+    Paused,
+    Unloaded,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspendedProcess {
+    pub process_id: String,
+    pub model_path: String,
+    pub model_name: String,
+    pub preset_id: Option<String>,
+    pub mode: SuspendMode,
+    /// KV-cache files saved through `/slots/{id}?action=save`, as `(slot id, filename)`
+    pub saved_slots: Vec<(u64, String)>,
+    pub suspended_at: DateTime<Utc>,
+}
+
+/// Freeze or thaw a process with the OS scheduler
+#[cfg(windows)]
+fn set_os_suspended(pid: u32, suspend: bool) -> Result<(), String> {
+    use std::ffi::c_void;
+    const PROCESS_SUSPEND_RESUME: u32 = 0x0800;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(dwDesiredAccess: u32, bInheritHandle: i32, dwProcessId: u32) -> *mut c_void;
+        fn CloseHandle(hObject: *mut c_void) -> i32;
+    }
+    #[link(name = "ntdll")]
+    extern "system" {
+        fn NtSuspendProcess(ProcessHandle: *mut c_void) -> i32;
+        fn NtResumeProcess(ProcessHandle: *mut c_void) -> i32;
+    }
+
+    let status = unsafe {
+        let handle = OpenProcess(PROCESS_SUSPEND_RESUME, 0, pid);
+        if handle.is_null() {
+            return Err(format!("Failed to open process {}: {}", pid, std::io::Error::last_os_error()));
+        }
+        let status = if suspend { NtSuspendProcess(handle) } else { NtResumeProcess(handle) };
+        CloseHandle(handle);
+        status
+    };
+    if status < 0 {
+        return Err(format!("Failed to {} process {}: NTSTATUS 0x{:08X}", if suspend { "suspend" } else { "resume" }, pid, status as u32));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_os_suspended(pid: u32, suspend: bool) -> Result<(), String> {
+    let signal = if suspend { "-STOP" } else { "-CONT" };
+    let status = std::process::Command::new("kill")
+        .args([signal, &pid.to_string()])
+        .status()
+        .map_err(|e| format!("Failed to signal process {}: {}", pid, e))?;
+    if !status.success() {
+        return Err(format!("kill {} {} exited with {}", signal, pid, status));
+    }
+    Ok(())
+}
+
+async fn os_pid(process_id: &str, state: &AppState) -> Result<u32, String> {
+    let handle = state
+        .child_processes
+        .lock()
+        .await
+        .get(process_id)
+        .cloned()
+        .ok_or_else(|| "Process not found".to_string())?;
+    let mut handle = handle.lock().await;
+    handle
+        .get_child_mut()
+        .and_then(|child| child.id())
+        .ok_or_else(|| "Process has already exited".to_string())
+}
+
+async fn set_status(process_id: &str, status: ProcessStatus, state: &AppState) {
+    if let Some(process) = state.running_processes.lock().await.get_mut(process_id) {
+        process.status = status;
+    }
+}
+
+pub(crate) fn local_base_url(host: &str, port: u16) -> String {
+    let host = if host == "0.0.0.0" || host.is_empty() { "127.0.0.1" } else { host };
+    format!("http://{}:{}", host, port)
+}
+
+/// Slot ids from llama-server's `GET /slots`
+fn slot_ids(slots: &serde_json::Value) -> Vec<u64> {
+    slots
+        .as_array()
+        .map(|slots| slots.iter().filter_map(|s| s.get("id").and_then(|id| id.as_u64())).collect())
+        .unwrap_or_default()
+}
+
+fn slot_filename(process_id: &str, slot_id: u64) -> String {
+    let safe_id: String = process_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect();
+    format!("arandu-{}-slot{}.bin", safe_id, slot_id)
+}
+
+/// Save every slot's KV cache. Needs the server to run with `--slot-save-path`;
+/// without it the save fails and the model simply reloads with an empty cache.
+async fn save_slots(base_url: &str, process_id: &str) -> Vec<(u64, String)> {
+    let client = http_pool::client_for(base_url);
+    let slots = match http_pool::get(&format!("{}/slots", base_url), RequestClass::Control).send().await {
+        Ok(response) if response.status().is_success() => response.json::<serde_json::Value>().await.unwrap_or_default(),
+        Ok(response) => {
+            println!("[Suspend] Slots endpoint unavailable ({}), unloading without KV cache", response.status());
+            return Vec::new();
+        }
+        Err(e) => {
+            eprintln!("[Suspend] Failed to list slots: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut saved = Vec::new();
+    for slot_id in slot_ids(&slots) {
+        let filename = slot_filename(process_id, slot_id);
+        let request = client
+            .post(format!("{}/slots/{}?action=save", base_url, slot_id))
+            .json(&serde_json::json!({ "filename": filename }));
+        let result = http_pool::with_timeout(request, RequestClass::Control).send().await;
+        match result {
+            Ok(response) if response.status().is_success() => saved.push((slot_id, filename)),
+            Ok(response) => println!("[Suspend] Slot {} not saved: HTTP {}", slot_id, response.status()),
+            Err(e) => eprintln!("[Suspend] Slot {} not saved: {}", slot_id, e),
+        }
+    }
+    saved
+}
+
+pub(crate) async fn wait_until_healthy(base_url: &str) -> Result<(), String> {
+    let health_url = format!("{}/health", base_url);
+    let deadline = tokio::time::Instant::now() + HEALTH_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        if let Ok(response) = http_pool::get(&health_url, RequestClass::Health).send().await {
+            if response.status().is_success() {
+                return Ok(());
+            }
+        }
+        tokio::time::sleep(HEALTH_POLL).await;
+    }
+    Err(format!("Server did not become ready within {}s", HEALTH_TIMEOUT.as_secs()))
+}
+
+async fn restore_slots(base_url: &str, saved_slots: &[(u64, String)]) {
+    let client = http_pool::client_for(base_url);
+    for (slot_id, filename) in saved_slots {
+        let request = client
+            .post(format!("{}/slots/{}?action=restore", base_url, slot_id))
+            .json(&serde_json::json!({ "filename": filename }));
+        let result = http_pool::with_timeout(request, RequestClass::Control).send().await;
+        match result {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => println!("[Suspend] Slot {} not restored: HTTP {}", slot_id, response.status()),
+            Err(e) => eprintln!("[Suspend] Slot {} not restored: {}", slot_id, e),
+        }
+    }
+}
+
+pub async fn suspend_process(process_id: &str, unload: bool, state: &AppState) -> Result<SuspendedProcess, String> {
+    let process = state
+        .running_processes
+        .lock()
+        .await
+        .get(process_id)
+        .cloned()
+        .ok_or_else(|| "Process not found".to_string())?;
+    if process.model_path.starts_with("tunnel:") {
+        return Err("Tunnels cannot be suspended".into());
+    }
+    if matches!(process.status, ProcessStatus::Suspended) {
+        return Err("Process is already suspended".into());
+    }
+
+    let mut suspended = SuspendedProcess {
+        process_id: process_id.to_string(),
+        model_path: process.model_path.clone(),
+        model_name: process.model_name.clone(),
+        preset_id: process.preset_id.clone(),
+        mode: if unload { SuspendMode::Unloaded } else { SuspendMode::Paused },
+        saved_slots: Vec::new(),
+        suspended_at: Utc::now(),
+    };
+
+    if unload {
+        suspended.saved_slots = save_slots(&local_base_url(&process.host, process.port), process_id).await;
+        crate::process::terminate_process(process_id.to_string(), state)
+            .await
+            .map_err(|e| format!("Failed to stop process: {}", e))?;
+        println!("[Suspend] Unloaded {} ({} slot(s) saved)", process.model_name, suspended.saved_slots.len());
+    } else {
+        let pid = os_pid(process_id, state).await?;
+        set_os_suspended(pid, true)?;
+        set_status(process_id, ProcessStatus::Suspended, state).await;
+        println!("[Suspend] Paused {} (PID {})", process.model_name, pid);
+    }
+
+    state
+        .suspended_processes
+        .lock()
+        .await
+        .insert(process_id.to_string(), suspended.clone());
+    Ok(suspended)
+}
+
+/// Resume a suspended process. Unloaded models come back as a new process, whose id is returned.
+pub async fn resume_process(process_id: &str, state: &AppState) -> Result<String, String> {
+    let suspended = state
+        .suspended_processes
+        .lock()
+        .await
+        .get(process_id)
+        .cloned()
+        .ok_or_else(|| "Process is not suspended".to_string())?;
+
+    let resumed_id = match suspended.mode {
+        SuspendMode::Paused => {
+            let pid = os_pid(process_id, state).await?;
+            set_os_suspended(pid, false)?;
+            set_status(process_id, ProcessStatus::Running, state).await;
+            println!("[Suspend] Resumed {} (PID {})", suspended.model_name, pid);
+            process_id.to_string()
+        }
+        SuspendMode::Unloaded => {
+            let result = crate::launch_with_preset(suspended.model_path.clone(), suspended.preset_id.clone(), state).await?;
+            if !suspended.saved_slots.is_empty() {
+                let base_url = local_base_url(&result.server_host, result.server_port);
+                wait_until_healthy(&base_url).await?;
+                restore_slots(&base_url, &suspended.saved_slots).await;
+            }
+            println!("[Suspend] Reloaded {} as {}", suspended.model_name, result.process_id);
+            result.process_id
+        }
+    };
+
+    state.suspended_processes.lock().await.remove(process_id);
+    Ok(resumed_id)
+}
+
+pub async fn list_suspended(state: &AppState) -> Vec<SuspendedProcess> {
+    let suspended = state.suspended_processes.lock().await;
+    let mut list: Vec<SuspendedProcess> = suspended.values().cloned().collect();
+    list.sort_by_key(|a| a.suspended_at);
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_ids_and_filenames_from_slots_response() {
+        let slots = serde_json::json!([{"id": 0, "is_processing": false}, {"id": 1}, {"n_ctx": 4096}]);
+        assert_eq!(slot_ids(&slots), vec![0, 1]);
+        assert!(slot_ids(&serde_json::json!({"error": "slots disabled"})).is_empty());
+        assert_eq!(slot_filename("proc/1:a", 2), "arandu-proc_1_a-slot2.bin");
+        assert_eq!(local_base_url("0.0.0.0", 8080), "http://127.0.0.1:8080");
+    }
+}
@@ -1,8 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::time::SystemTime;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use sysinfo::{System};
 use std::path::Path;
 use std::fs;
+use crate::models::GpuPowerSummary;
 #[cfg(target_os = "windows")]
 use std::process::Command;
 
@@ -57,6 +61,12 @@ pub async fn get_system_stats(state: tauri::State<'_, crate::AppState>) -> Resul
     })
 }
 
+/// `(total, used)` VRAM in GB of the primary GPU, `None` when no GPU memory is reported
+pub(crate) fn gpu_memory_gb() -> Option<(f32, f32)> {
+    let (_, _, total, used) = get_gpu_info();
+    if total > 0.0 { Some((total, used)) } else { None }
+}
+
 fn get_gpu_info() -> (String, f32, f32, f32) {
     if let Some(nvidia_info) = get_nvidia_gpu_info() {
         return nvidia_info;
@@ -97,8 +107,171 @@ fn get_nvidia_gpu_info() -> Option<(String, f32, f32, f32)> {
     Some((name, gpu_usage, gpu_memory_total_gb, gpu_memory_used_gb))
 }
 
+/// One GPU as the machine reports it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GpuDevice {
+    pub index: u32,
+    pub name: String,
+    pub memory_total_gb: f32,
+    pub memory_used_gb: f32,
+    /// Name to pass to `--device`, when it is known which llama.cpp backend sees it how
+    pub llama_device: Option<String>,
+}
+
+/// Every GPU, NVIDIA ones with live memory use. CUDA numbers devices the way NVML
+/// does only with `CUDA_DEVICE_ORDER=PCI_BUS_ID`, which launches with a device
+/// selection set.
+pub fn list_gpus() -> Vec<GpuDevice> {
+    if let Some(devices) = list_nvidia_gpus().filter(|d| !d.is_empty()) {
+        return devices;
+    }
+
+    #[cfg(target_os = "windows")]
+    if let Some(devices) = list_windows_gpus() {
+        return devices;
+    }
+
+    Vec::new()
+}
+
+fn list_nvidia_gpus() -> Option<Vec<GpuDevice>> {
+    let nvml = nvml_wrapper::Nvml::init().ok()?;
+    let count = nvml.device_count().ok()?;
+    let gib = 1024.0 * 1024.0 * 1024.0;
+    Some(
+        (0..count)
+            .filter_map(|index| {
+                let device = nvml.device_by_index(index).ok()?;
+                let (total, used) = device
+                    .memory_info()
+                    .map(|m| (m.total as f32 / gib, m.used as f32 / gib))
+                    .unwrap_or((0.0, 0.0));
+                Some(GpuDevice {
+                    index,
+                    name: device.name().unwrap_or_else(|_| "NVIDIA GPU".to_string()),
+                    memory_total_gb: total,
+                    memory_used_gb: used,
+                    llama_device: Some(format!("CUDA{}", index)),
+                })
+            })
+            .collect(),
+    )
+}
+
+#[tauri::command]
+pub async fn list_gpu_devices() -> Result<Vec<GpuDevice>, String> {
+    tokio::task::spawn_blocking(list_gpus)
+        .await
+        .map_err(|e| format!("Failed to list GPUs: {}", e))
+}
+
+const POWER_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Telemetry sessions opened by the frontend around a chat generation
+static POWER_SESSIONS: LazyLock<Mutex<HashMap<String, PowerSampler>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Power draw and clocks of the primary GPU at one instant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuPowerSample {
+    pub power_watts: f32,
+    pub graphics_clock_mhz: u32,
+    pub memory_clock_mhz: u32,
+}
+
+fn sample_nvidia_power(device: &nvml_wrapper::Device) -> Option<GpuPowerSample> {
+    use nvml_wrapper::enum_wrappers::device::Clock;
+    let power_mw = device.power_usage().ok()?;
+    Some(GpuPowerSample {
+        power_watts: power_mw as f32 / 1000.0,
+        graphics_clock_mhz: device.clock_info(Clock::Graphics).unwrap_or(0),
+        memory_clock_mhz: device.clock_info(Clock::Memory).unwrap_or(0),
+    })
+}
+
+pub fn summarize_power(samples: &[GpuPowerSample], duration: Duration) -> Option<GpuPowerSummary> {
+    if samples.is_empty() {
+        return None;
+    }
+    let n = samples.len() as f32;
+    let avg_power_watts = samples.iter().map(|s| s.power_watts).sum::<f32>() / n;
+    Some(GpuPowerSummary {
+        samples: samples.len() as u32,
+        avg_power_watts,
+        peak_power_watts: samples.iter().map(|s| s.power_watts).fold(0.0, f32::max),
+        avg_graphics_clock_mhz: (samples.iter().map(|s| s.graphics_clock_mhz as f32).sum::<f32>() / n).round() as u32,
+        avg_memory_clock_mhz: (samples.iter().map(|s| s.memory_clock_mhz as f32).sum::<f32>() / n).round() as u32,
+        duration_ms: duration.as_millis() as u64,
+        energy_wh: avg_power_watts as f64 * duration.as_secs_f64() / 3600.0,
+    })
+}
+
+/// Samples GPU power on a background thread until `finish` (or drop). Only NVIDIA
+/// GPUs (through NVML) report power; elsewhere the summary is None.
+#[derive(Debug)]
+pub struct PowerSampler {
+    stop: Arc<AtomicBool>,
+    started: Instant,
+    handle: Option<std::thread::JoinHandle<Vec<GpuPowerSample>>>,
+}
+
+impl PowerSampler {
+    pub fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        let handle = std::thread::spawn(move || {
+            let mut samples = Vec::new();
+            let Ok(nvml) = nvml_wrapper::Nvml::init() else { return samples };
+            let Ok(device) = nvml.device_by_index(0) else { return samples };
+            while !thread_stop.load(Ordering::Relaxed) {
+                samples.extend(sample_nvidia_power(&device));
+                std::thread::sleep(POWER_SAMPLE_INTERVAL);
+            }
+            samples
+        });
+        Self { stop, started: Instant::now(), handle: Some(handle) }
+    }
+
+    pub async fn finish(mut self) -> Option<GpuPowerSummary> {
+        let duration = self.started.elapsed();
+        self.stop.store(true, Ordering::Relaxed);
+        let handle = self.handle.take()?;
+        let samples = tokio::task::spawn_blocking(move || handle.join().unwrap_or_default())
+            .await
+            .unwrap_or_default();
+        summarize_power(&samples, duration)
+    }
+}
+
+impl Drop for PowerSampler {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Start sampling GPU power for a generation; pass the id to `stop_gpu_telemetry`
+#[tauri::command]
+pub async fn start_gpu_telemetry() -> Result<String, String> {
+    let session_id = uuid::Uuid::new_v4().to_string();
+    POWER_SESSIONS
+        .lock()
+        .map_err(|e| format!("Failed to lock telemetry sessions: {}", e))?
+        .insert(session_id.clone(), PowerSampler::start());
+    Ok(session_id)
+}
+
+/// Averages for the session, to store in the message metadata as `gpu_power`
+#[tauri::command]
+pub async fn stop_gpu_telemetry(session_id: String) -> Result<Option<GpuPowerSummary>, String> {
+    let sampler = POWER_SESSIONS
+        .lock()
+        .map_err(|e| format!("Failed to lock telemetry sessions: {}", e))?
+        .remove(&session_id)
+        .ok_or_else(|| "Telemetry session not found".to_string())?;
+    Ok(sampler.finish().await)
+}
+
 #[cfg(target_os = "windows")]
-fn get_windows_gpu_fallback_info() -> Option<(String, f32, f32, f32)> {
+fn list_windows_gpus() -> Option<Vec<GpuDevice>> {
     let output = Command::new("powershell")
         .args([
             "-NoProfile",
@@ -115,8 +288,7 @@ fn get_windows_gpu_fallback_info() -> Option<(String, f32, f32, f32)> {
     let stdout = String::from_utf8(output.stdout).ok()?;
     let parsed: serde_json::Value = serde_json::from_str(stdout.trim()).ok()?;
 
-    let mut best_name = String::new();
-    let mut best_vram_bytes: u64 = 0;
+    let mut devices = Vec::new();
 
     let entries: Vec<&serde_json::Value> = match &parsed {
         serde_json::Value::Array(items) => items.iter().collect(),
@@ -150,18 +322,25 @@ fn get_windows_gpu_fallback_info() -> Option<(String, f32, f32, f32)> {
             })
             .unwrap_or(0);
 
-        if best_name.is_empty() || adapter_ram_bytes > best_vram_bytes {
-            best_name = name.to_string();
-            best_vram_bytes = adapter_ram_bytes;
-        }
+        devices.push(GpuDevice {
+            index: devices.len() as u32,
+            name: name.to_string(),
+            memory_total_gb: adapter_ram_bytes as f32 / (1024.0 * 1024.0 * 1024.0),
+            memory_used_gb: 0.0,
+            // WMI order is not the Vulkan or CUDA device order
+            llama_device: None,
+        });
     }
 
-    if best_name.is_empty() {
-        return None;
-    }
+    (!devices.is_empty()).then_some(devices)
+}
 
-    let total_gb = best_vram_bytes as f32 / (1024.0 * 1024.0 * 1024.0);
-    Some((best_name, 0.0, total_gb.max(0.0), 0.0))
+#[cfg(target_os = "windows")]
+fn get_windows_gpu_fallback_info() -> Option<(String, f32, f32, f32)> {
+    let best = list_windows_gpus()?
+        .into_iter()
+        .reduce(|best, device| if device.memory_total_gb > best.memory_total_gb { device } else { best })?;
+    Some((best.name, 0.0, best.memory_total_gb.max(0.0), 0.0))
 }
 
 async fn get_models_stats(state: &crate::AppState) -> (f32, u32) {
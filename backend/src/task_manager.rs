@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+use crate::events::TaskProgress;
+
+// Every long-running backend job: downloads, model scans, backend benchmarks, batch
+// launches and MCP tool discovery. Each run registers a `Task` under an id (the download
+// id, or one the frontend passes in so it can cancel before the command returns), reports
+// progress through it and checks its cancellation token. `list_background_tasks` lists
+// the running ones, `cancel_task` cancels any of them by id, and all progress goes out
+// on the one `task-progress` event. The registration ends when the `Task` is dropped.
+
+/// Progress events per task at most this often; the last one always goes out
+const EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackgroundTask {
+    pub task_id: String,
+    pub kind: &'static str,
+    pub label: String,
+    /// Percent done, when the task knows its total
+    pub progress: Option<u8>,
+    pub message: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub cancelled: bool,
+}
+
+struct Registered {
+    serial: u64,
+    token: CancellationToken,
+    info: BackgroundTask,
+    last_emit: Option<Instant>,
+}
+
+static TASKS: LazyLock<Mutex<HashMap<String, Registered>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static SERIAL: AtomicU64 = AtomicU64::new(0);
+static APP_HANDLE: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+/// Called once from setup so tasks can emit `task-progress`
+pub fn set_app_handle(app_handle: tauri::AppHandle) {
+    let _ = APP_HANDLE.set(app_handle);
+}
+
+fn emit(task: &BackgroundTask, done: bool) {
+    if let Some(app_handle) = APP_HANDLE.get() {
+        crate::events::emit(app_handle, &TaskProgress {
+            task_id: task.task_id.clone(),
+            kind: task.kind.to_string(),
+            label: task.label.clone(),
+            progress: task.progress,
+            message: task.message.clone(),
+            cancelled: task.cancelled,
+            done,
+        });
+    }
+}
+
+pub struct Task {
+    id: String,
+    kind: &'static str,
+    serial: u64,
+    token: CancellationToken,
+}
+
+impl Task {
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    pub fn cancelled_error(&self) -> String {
+        format!("{} {} was cancelled", self.kind, self.id)
+    }
+
+    /// `Err` once the task was cancelled, for checks between steps
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(self.cancelled_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Drive `future` until it finishes or the task is cancelled, dropping it then
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, String> {
+        self.token.run_until_cancelled(future).await.ok_or_else(|| self.cancelled_error())
+    }
+
+    /// Record progress and tell the frontend, throttled to `EMIT_INTERVAL`
+    pub fn report(&self, progress: Option<u8>, message: Option<String>) {
+        let mut tasks = TASKS.lock().unwrap_or_else(|p| p.into_inner());
+        let Some(registered) = tasks.get_mut(&self.id).filter(|t| t.serial == self.serial) else { return };
+        registered.info.progress = progress.map(|p| p.min(100));
+        registered.info.message = message;
+        if registered.last_emit.is_some_and(|at| at.elapsed() < EMIT_INTERVAL) {
+            return;
+        }
+        registered.last_emit = Some(Instant::now());
+        emit(&registered.info, false);
+    }
+
+    /// `report` with the percent of `done` out of `total`
+    pub fn report_steps(&self, done: usize, total: usize, message: impl Into<String>) {
+        let progress = (total > 0).then(|| (done * 100 / total) as u8);
+        self.report(progress, Some(message.into()));
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        let mut tasks = TASKS.lock().unwrap_or_else(|p| p.into_inner());
+        if tasks.get(&self.id).is_some_and(|t| t.serial == self.serial) {
+            if let Some(mut registered) = tasks.remove(&self.id) {
+                registered.info.cancelled = self.token.is_cancelled();
+                emit(&registered.info, true);
+            }
+        }
+    }
+}
+
+/// Register a run of `kind` under `task_id`, or a fresh id. A run already registered
+/// under the same id stays cancellable only through its own `Task`.
+pub fn register(kind: &'static str, task_id: Option<String>, label: impl Into<String>) -> Task {
+    let id = task_id.filter(|id| !id.trim().is_empty()).unwrap_or_else(|| Uuid::new_v4().to_string());
+    let serial = SERIAL.fetch_add(1, Ordering::Relaxed);
+    let token = CancellationToken::new();
+    let info = BackgroundTask {
+        task_id: id.clone(),
+        kind,
+        label: label.into(),
+        progress: None,
+        message: None,
+        started_at: Utc::now(),
+        cancelled: false,
+    };
+    emit(&info, false);
+    TASKS
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .insert(id.clone(), Registered { serial, token: token.clone(), info, last_emit: None });
+    Task { id, kind, serial, token }
+}
+
+/// Cancel the running task `task_id`; returns its kind
+pub fn cancel(task_id: &str) -> Result<&'static str, String> {
+    let mut tasks = TASKS.lock().unwrap_or_else(|p| p.into_inner());
+    let task = tasks.get_mut(task_id).ok_or_else(|| format!("No running task {}", task_id))?;
+    task.token.cancel();
+    task.info.cancelled = true;
+    println!("[Tasks] Cancelled {} {}", task.info.kind, task_id);
+    Ok(task.info.kind)
+}
+
+/// Running tasks, oldest first
+pub fn list() -> Vec<BackgroundTask> {
+    let mut tasks: Vec<BackgroundTask> = TASKS
+        .lock()
+        .unwrap_or_else(|p| p.into_inner())
+        .values()
+        .map(|t| t.info.clone())
+        .collect();
+    tasks.sort_by_key(|a| a.started_at);
+    tasks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn tasks_are_listed_with_progress_and_cancelled_until_dropped() {
+        let task = register("scan", Some("scan-1".to_string()), "Model scan");
+        assert!(task.check().is_ok());
+        task.report_steps(3, 4, "3 of 4 files");
+        let listed = list().into_iter().find(|t| t.task_id == "scan-1").unwrap();
+        assert_eq!((listed.progress, listed.message.as_deref()), (Some(75), Some("3 of 4 files")));
+
+        assert_eq!(cancel("scan-1").unwrap(), "scan");
+        assert!(task.check().is_err());
+        assert!(task.run(std::future::pending::<()>()).await.is_err());
+        assert!(list().iter().any(|t| t.task_id == "scan-1" && t.cancelled));
+
+        // A later run under the same id is not unregistered by the earlier one
+        let again = register("scan", Some("scan-1".to_string()), "Model scan");
+        drop(task);
+        assert!(!again.is_cancelled());
+        assert!(cancel("scan-1").is_ok());
+        drop(again);
+        assert!(cancel("scan-1").is_err());
+        assert!(list().iter().all(|t| t.task_id != "scan-1"));
+        assert_ne!(register("scan", None, "").id, register("scan", Some(" ".to_string()), "").id);
+    }
+}
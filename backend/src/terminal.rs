@@ -0,0 +1,204 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command as TokioCommand;
+
+// Terminal windows for external launches. `GlobalConfig.external_terminal` is a command
+// template: `{exe}` becomes the llama-server path, `{args}` its arguments and `{script}`
+// a generated script that sets the environment and runs both, for terminals that only
+// open files (macOS `open -a Terminal`). An empty template tries the installed
+// terminals of the platform in the order below.
+
+#[cfg(windows)]
+const KNOWN_TERMINALS: &[(&str, &str, &str)] = &[
+    ("Windows Terminal", "wt.exe", "wt.exe new-tab cmd /k {exe} {args}"),
+    ("Command Prompt", "cmd.exe", "cmd /c start cmd /k {exe} {args}"),
+];
+
+#[cfg(target_os = "macos")]
+const KNOWN_TERMINALS: &[(&str, &str, &str)] = &[
+    ("Terminal", "/System/Applications/Utilities/Terminal.app", "open -a Terminal {script}"),
+    ("iTerm", "/Applications/iTerm.app", "open -a iTerm {script}"),
+    ("WezTerm", "wezterm", "wezterm start -- {exe} {args}"),
+    ("Alacritty", "alacritty", "alacritty -e {exe} {args}"),
+    ("kitty", "kitty", "kitty {exe} {args}"),
+];
+
+#[cfg(all(unix, not(target_os = "macos")))]
+const KNOWN_TERMINALS: &[(&str, &str, &str)] = &[
+    ("Default terminal", "x-terminal-emulator", "x-terminal-emulator -e {exe} {args}"),
+    ("GNOME Terminal", "gnome-terminal", "gnome-terminal -- {exe} {args}"),
+    ("Konsole", "konsole", "konsole -e {exe} {args}"),
+    ("Xfce Terminal", "xfce4-terminal", "xfce4-terminal -x {exe} {args}"),
+    ("Alacritty", "alacritty", "alacritty -e {exe} {args}"),
+    ("kitty", "kitty", "kitty {exe} {args}"),
+    ("WezTerm", "wezterm", "wezterm start -- {exe} {args}"),
+    ("xterm", "xterm", "xterm -e {exe} {args}"),
+];
+
+/// How long a generated launch script is kept for its terminal to open it
+const SCRIPT_LIFETIME: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalOption {
+    pub name: String,
+    pub template: String,
+    pub installed: bool,
+}
+
+/// `program` is an absolute path that exists or an executable on PATH
+fn is_installed(program: &str) -> bool {
+    let path = Path::new(program);
+    if path.is_absolute() {
+        return path.exists();
+    }
+    let Some(paths) = std::env::var_os("PATH") else { return false };
+    std::env::split_paths(&paths).any(|dir| {
+        let candidate = dir.join(program);
+        candidate.is_file() || (cfg!(windows) && candidate.extension().is_none() && candidate.with_extension("exe").is_file())
+    })
+}
+
+/// Known terminals of this platform and whether each is installed
+pub fn options() -> Vec<TerminalOption> {
+    KNOWN_TERMINALS
+        .iter()
+        .map(|(name, program, template)| TerminalOption {
+            name: name.to_string(),
+            template: template.to_string(),
+            installed: is_installed(program),
+        })
+        .collect()
+}
+
+/// Program and arguments for `template`; `{exe} {args}` is appended when the template
+/// names neither the executable nor a script
+pub fn expand(template: &str, exe: &str, args: &[String], script: Option<&Path>) -> Result<Vec<String>, String> {
+    let mut tokens = crate::cli_args::split(template);
+    if tokens.is_empty() {
+        return Err("Terminal command is empty".to_string());
+    }
+    if !tokens.iter().any(|t| t.contains("{exe}") || t.contains("{script}")) {
+        tokens.extend(["{exe}".to_string(), "{args}".to_string()]);
+    }
+
+    let mut command = Vec::with_capacity(tokens.len() + args.len());
+    for token in tokens {
+        if token == "{args}" {
+            command.extend(args.iter().cloned());
+            continue;
+        }
+        let mut token = token.replace("{exe}", exe).replace("{args}", &args.join(" "));
+        if token.contains("{script}") {
+            let script = script.ok_or_else(|| "No launch script was written for {script}".to_string())?;
+            token = token.replace("{script}", &script.to_string_lossy());
+        }
+        command.push(token);
+    }
+    Ok(command)
+}
+
+#[cfg(windows)]
+fn script_contents(exe: &str, args: &[String], env: &HashMap<String, String>) -> String {
+    let quote = |s: &str| if s.contains(' ') { format!("\"{}\"", s) } else { s.to_string() };
+    let mut lines = vec!["@echo off".to_string()];
+    lines.extend(env.iter().map(|(k, v)| format!("set \"{}={}\"", k, v)));
+    lines.push(std::iter::once(exe).chain(args.iter().map(String::as_str)).map(quote).collect::<Vec<_>>().join(" "));
+    lines.join("\r\n")
+}
+
+#[cfg(not(windows))]
+fn script_contents(exe: &str, args: &[String], env: &HashMap<String, String>) -> String {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "'\\''"));
+    let mut lines = vec!["#!/bin/sh".to_string()];
+    lines.extend(env.iter().map(|(k, v)| format!("export {}={}", k, quote(v))));
+    lines.push(format!("exec {}", std::iter::once(exe).chain(args.iter().map(String::as_str)).map(quote).collect::<Vec<_>>().join(" ")));
+    lines.join("\n") + "\n"
+}
+
+/// Remove launch scripts older than `SCRIPT_LIFETIME`; by then their terminal has read them
+fn remove_old_scripts(dir: &Path) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let is_script = entry.file_name().to_string_lossy().starts_with("launch-");
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age >= SCRIPT_LIFETIME);
+        if is_script && expired {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Write the launch script for `{script}` templates under `~/.Arandu/terminal`. It holds the
+/// model's environment variables, so only the owner can read it and old ones are removed.
+fn write_script(exe: &str, args: &[String], env: &HashMap<String, String>) -> Result<PathBuf, String> {
+    let dir = crate::arandu_base_dir()?.join("terminal");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create launch script folder: {}", e))?;
+    remove_old_scripts(&dir);
+    let extension = if cfg!(windows) { "cmd" } else { "command" };
+    let path = dir.join(format!("launch-{}.{}", chrono::Utc::now().timestamp_millis(), extension));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o700);
+    }
+    options
+        .open(&path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, script_contents(exe, args, env).as_bytes()))
+        .map_err(|e| format!("Failed to write launch script: {}", e))?;
+    Ok(path)
+}
+
+/// Open `exe args` in a terminal window: the configured template, or else the first
+/// installed terminal that starts
+pub fn spawn(template: &str, exe: &Path, args: &[String], env: &HashMap<String, String>) -> Result<(), String> {
+    let templates: Vec<String> = if template.trim().is_empty() {
+        options().into_iter().filter(|o| o.installed).map(|o| o.template).collect()
+    } else {
+        vec![template.to_string()]
+    };
+    if templates.is_empty() {
+        return Err("No terminal emulator found. Set one under Settings > External terminal.".to_string());
+    }
+
+    let exe = exe.to_string_lossy();
+    let mut last_error = String::new();
+    for template in templates {
+        let script = if template.contains("{script}") { Some(write_script(&exe, args, env)?) } else { None };
+        let command = expand(&template, &exe, args, script.as_deref())?;
+        let mut cmd = TokioCommand::new(&command[0]);
+        cmd.args(&command[1..]).envs(env);
+        match cmd.spawn() {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = format!("Failed to start terminal '{}': {}", command[0], e),
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_expand_placeholders() {
+        let args = vec!["-m".to_string(), "/models/qwen 7b.gguf".to_string()];
+        assert_eq!(
+            expand("konsole -e {exe} {args}", "/bin/llama-server", &args, None).unwrap(),
+            vec!["konsole", "-e", "/bin/llama-server", "-m", "/models/qwen 7b.gguf"]
+        );
+        assert_eq!(expand("alacritty -e", "srv", &args, None).unwrap(), vec!["alacritty", "-e", "srv", "-m", "/models/qwen 7b.gguf"]);
+        assert_eq!(
+            expand("open -a Terminal {script}", "srv", &args, Some(Path::new("/tmp/launch.command"))).unwrap(),
+            vec!["open", "-a", "Terminal", "/tmp/launch.command"]
+        );
+        assert!(expand("open -a Terminal {script}", "srv", &args, None).is_err());
+        assert!(expand("  ", "srv", &args, None).is_err());
+    }
+}
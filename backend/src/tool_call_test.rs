@@ -0,0 +1,285 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Instant;
+use crate::http_pool::{self, RequestClass};
+use crate::models::ProcessStatus;
+use crate::AppState;
+
+// Function-calling test console: send one tool-enabled request to a running model
+// and check the tool calls it returns against the tools' JSON schemas. The schema
+// check covers the subset tool definitions use in practice (type, properties,
+// required, enum, items, additionalProperties), not the full JSON Schema spec.
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCallCheck {
+    pub name: String,
+    pub arguments_raw: String,
+    pub arguments: Option<Value>,
+    pub known_tool: bool,
+    pub errors: Vec<String>,
+    pub valid: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolCallTestResult {
+    pub model: String,
+    pub latency_ms: u128,
+    pub finish_reason: Option<String>,
+    pub content: Option<String>,
+    pub tool_calls: Vec<ToolCallCheck>,
+    /// The assistant message exactly as the server returned it
+    pub raw_message: Value,
+    /// At least one tool call and every call valid
+    pub passed: bool,
+}
+
+/// Accepts an OpenAI `tools` array, a bare list of `{name, parameters}` functions,
+/// or an object with a `tools` key; returns OpenAI-format tools
+pub fn parse_tools(tools_json: &str) -> Result<Vec<Value>, String> {
+    let value: Value = serde_json::from_str(tools_json).map_err(|e| format!("Tools JSON is invalid: {}", e))?;
+    let items = match value {
+        Value::Array(items) => items,
+        Value::Object(ref map) if map.get("tools").is_some_and(|t| t.is_array()) => map["tools"].as_array().cloned().unwrap_or_default(),
+        Value::Object(_) => vec![value],
+        _ => return Err("Tools JSON must be an array of tool definitions".to_string()),
+    };
+
+    let mut tools = Vec::new();
+    for item in items {
+        let function = item.get("function").cloned().unwrap_or(item);
+        let name = function
+            .get("name")
+            .and_then(|n| n.as_str())
+            .filter(|n| !n.trim().is_empty())
+            .ok_or_else(|| "Every tool needs a name".to_string())?;
+        let parameters = function
+            .get("parameters")
+            .or_else(|| function.get("input_schema"))
+            .or_else(|| function.get("inputSchema"))
+            .cloned()
+            .unwrap_or_else(|| json!({ "type": "object", "properties": {} }));
+        tools.push(json!({
+            "type": "function",
+            "function": {
+                "name": name,
+                "description": function.get("description").cloned().unwrap_or(Value::String(String::new())),
+                "parameters": parameters,
+            }
+        }));
+    }
+    if tools.is_empty() {
+        return Err("At least one tool is required".to_string());
+    }
+    Ok(tools)
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Schema violations of `value`, each prefixed with its JSON path
+pub fn validate(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(|t| t.as_str()).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|t| type_matches(t, value)) {
+        errors.push(format!("{}: expected {}, got {}", path, types.join(" | "), value_kind(value)));
+        return;
+    }
+    if let Some(options) = schema.get("enum").and_then(|e| e.as_array()) {
+        if !options.contains(value) {
+            errors.push(format!("{}: {} is not one of the allowed values", path, value));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        for key in schema.get("required").and_then(|r| r.as_array()).into_iter().flatten().filter_map(|k| k.as_str()) {
+            if !object.contains_key(key) {
+                errors.push(format!("{}: missing required property '{}'", path, key));
+            }
+        }
+        for (key, child) in object {
+            let child_path = format!("{}.{}", path, key);
+            match properties.and_then(|p| p.get(key)) {
+                Some(child_schema) => validate(child_schema, child, &child_path, errors),
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    errors.push(format!("{}: unexpected property", child_path));
+                }
+                None => {}
+            }
+        }
+    }
+
+    if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            validate(item_schema, item, &format!("{}[{}]", path, i), errors);
+        }
+    }
+}
+
+fn value_kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn check_call(call: &Value, tools: &[Value]) -> ToolCallCheck {
+    let name = call.pointer("/function/name").and_then(|v| v.as_str()).unwrap_or("").to_string();
+    // Servers return arguments as a JSON string; tolerate an object too
+    let arguments_value = call.pointer("/function/arguments").cloned().unwrap_or(Value::Null);
+    let arguments_raw = match &arguments_value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    let mut errors = Vec::new();
+    let arguments = match &arguments_value {
+        Value::String(s) => match serde_json::from_str::<Value>(s) {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                errors.push(format!("arguments are not valid JSON: {}", e));
+                None
+            }
+        },
+        Value::Object(_) => Some(arguments_value.clone()),
+        _ => {
+            errors.push("arguments are missing".to_string());
+            None
+        }
+    };
+
+    let schema = tools
+        .iter()
+        .find(|t| t.pointer("/function/name").and_then(|n| n.as_str()) == Some(name.as_str()))
+        .and_then(|t| t.pointer("/function/parameters"));
+    if schema.is_none() {
+        errors.push(format!("'{}' is not one of the provided tools", name));
+    }
+    if let (Some(schema), Some(arguments)) = (schema, &arguments) {
+        validate(schema, arguments, "$", &mut errors);
+    }
+
+    ToolCallCheck {
+        name,
+        arguments_raw,
+        arguments,
+        known_tool: schema.is_some(),
+        valid: errors.is_empty(),
+        errors,
+    }
+}
+
+/// Running model matched by process id, model path or name; any running model when empty
+pub async fn resolve_server(model: &str, state: &AppState) -> Result<(String, String), String> {
+    let running = state.running_processes.lock().await;
+    let key = model.trim();
+    let process = running
+        .values()
+        .filter(|p| matches!(p.status, ProcessStatus::Running) && !p.model_path.starts_with("tunnel:"))
+        .find(|p| key.is_empty() || p.id == key || p.model_path == key || p.model_name.eq_ignore_ascii_case(key))
+        .ok_or_else(|| {
+            if key.is_empty() {
+                "No running model found. Start a model and try again.".to_string()
+            } else {
+                format!("Model '{}' is not running", key)
+            }
+        })?;
+    Ok((crate::suspend::local_base_url(&process.host, process.port), process.model_name.clone()))
+}
+
+pub async fn run(model: &str, tools_json: &str, prompt: &str, state: &AppState) -> Result<ToolCallTestResult, String> {
+    if prompt.trim().is_empty() {
+        return Err("Prompt is required".to_string());
+    }
+    let tools = parse_tools(tools_json)?;
+    let (server_url, model_name) = resolve_server(model, state).await?;
+
+    let started = Instant::now();
+    let request = http_pool::client_for(&server_url)
+        .post(format!("{}/v1/chat/completions", server_url))
+        .json(&json!({
+            "messages": [{"role": "user", "content": prompt}],
+            "tools": tools,
+            "tool_choice": "auto",
+            "stream": false
+        }));
+    let response = http_pool::with_timeout(request, RequestClass::Completion)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact model: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        let hint = if text.to_lowercase().contains("jinja") || text.contains("tools param") {
+            " (llama-server needs --jinja for tool calls)"
+        } else {
+            ""
+        };
+        return Err(format!("Model request failed with status {}: {}{}", status.as_u16(), text, hint));
+    }
+    let body: Value = response.json().await.map_err(|e| format!("Failed to parse model response: {}", e))?;
+    let latency_ms = started.elapsed().as_millis();
+
+    let message = body.pointer("/choices/0/message").cloned().unwrap_or(Value::Null);
+    let tool_calls: Vec<ToolCallCheck> = message
+        .get("tool_calls")
+        .and_then(|c| c.as_array())
+        .map(|calls| calls.iter().map(|call| check_call(call, &tools)).collect())
+        .unwrap_or_default();
+    let passed = !tool_calls.is_empty() && tool_calls.iter().all(|c| c.valid);
+
+    Ok(ToolCallTestResult {
+        model: model_name,
+        latency_ms,
+        finish_reason: body.pointer("/choices/0/finish_reason").and_then(|v| v.as_str()).map(str::to_string),
+        content: message.get("content").and_then(|v| v.as_str()).map(str::to_string),
+        tool_calls,
+        raw_message: message,
+        passed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_calls_are_checked_against_their_schema() {
+        let tools = parse_tools(r#"[{"name": "get_weather", "parameters": {
+            "type": "object",
+            "properties": {"city": {"type": "string"}, "unit": {"type": "string", "enum": ["c", "f"]}},
+            "required": ["city"],
+            "additionalProperties": false
+        }}]"#)
+        .unwrap();
+        assert_eq!(tools[0]["type"], "function");
+
+        let good = json!({"function": {"name": "get_weather", "arguments": "{\"city\": \"Lisbon\", \"unit\": \"c\"}"}});
+        assert!(check_call(&good, &tools).valid);
+
+        let bad = json!({"function": {"name": "get_weather", "arguments": "{\"unit\": \"k\", \"extra\": 1}"}});
+        let check = check_call(&bad, &tools);
+        assert_eq!(check.errors.len(), 3);
+
+        let unknown = json!({"function": {"name": "send_email", "arguments": "{}"}});
+        assert!(!check_call(&unknown, &tools).known_tool);
+        assert!(parse_tools("{}").is_err());
+    }
+}
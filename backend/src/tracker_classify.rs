@@ -0,0 +1,168 @@
+use crate::http_pool::{self, RequestClass};
+use crate::models::{ModelClassification, ProcessStatus, TrackerModel};
+use crate::tracker_manager::TrackerDb;
+use crate::AppState;
+
+// Optional enrichment pass for the tracker: a running local model reads each scraped
+// model's name, pipeline description and backends and picks one of a few use-case
+// categories. Results are stored with a confidence score; manual overrides always win
+// and are never re-classified.
+
+pub const CATEGORIES: [&str; 6] = ["coder", "roleplay", "vision", "embedding", "reasoning", "general"];
+const BATCH_SIZE: usize = 8;
+
+const SYSTEM_PROMPT: &str = "You classify AI models by their main use. Categories: coder (programming), roleplay (characters, creative chat), vision (images or video as input), embedding (embeddings, rerankers, retrieval), reasoning (thinking / math / step-by-step models), general (anything else). Reply with a JSON array only, one object per model: {\"id\": \"<model id>\", \"category\": \"<category>\", \"confidence\": <0.0-1.0>}.";
+
+/// Map what a model answers (or a user types) onto a category
+pub fn normalize_category(raw: &str) -> Option<&'static str> {
+    let raw = raw.trim().to_lowercase();
+    let category = match raw.as_str() {
+        "coder" | "code" | "coding" | "programming" => "coder",
+        "roleplay" | "rp" | "role-play" | "creative" => "roleplay",
+        "vision" | "multimodal" | "vlm" | "image" => "vision",
+        "embedding" | "embeddings" | "reranker" | "retrieval" => "embedding",
+        "reasoning" | "thinking" | "math" => "reasoning",
+        "general" | "chat" | "other" => "general",
+        _ => return None,
+    };
+    Some(category)
+}
+
+fn prompt_for(models: &[TrackerModel]) -> String {
+    models
+        .iter()
+        .map(|m| {
+            format!(
+                "- id: {}\n  description: {}\n  tracker category: {}\n  backends: {}",
+                m.id,
+                m.description,
+                m.category,
+                if m.backends.is_empty() { "unknown".to_string() } else { m.backends.join(", ") }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Classifications for the batch's ids; unknown ids and categories are dropped
+fn parse_response(content: &str, ids: &[String], classified_by: &str) -> Vec<ModelClassification> {
+    let (Some(start), Some(end)) = (content.find('['), content.rfind(']')) else { return Vec::new() };
+    if end < start {
+        return Vec::new();
+    }
+    let Ok(items) = serde_json::from_str::<Vec<serde_json::Value>>(&content[start..=end]) else {
+        return Vec::new();
+    };
+    let classified_at = chrono::Utc::now().to_rfc3339();
+    let mut results: Vec<ModelClassification> = Vec::new();
+    for item in items {
+        let Some(id) = item.get("id").and_then(|v| v.as_str()).filter(|id| ids.iter().any(|known| known == id)) else { continue };
+        let Some(category) = item.get("category").and_then(|v| v.as_str()).and_then(normalize_category) else { continue };
+        if results.iter().any(|r| r.model_id == id) {
+            continue;
+        }
+        results.push(ModelClassification {
+            model_id: id.to_string(),
+            category: category.to_string(),
+            confidence: item.get("confidence").and_then(|v| v.as_f64()).unwrap_or(0.5).clamp(0.0, 1.0),
+            source: "model".to_string(),
+            classified_by: Some(classified_by.to_string()),
+            classified_at: classified_at.clone(),
+        });
+    }
+    results
+}
+
+/// The requested running model, else the first one running
+async fn classifier_server(state: &AppState, model_path: Option<&str>) -> Result<(String, String), String> {
+    let running = state.running_processes.lock().await;
+    let mut candidates = running
+        .values()
+        .filter(|p| matches!(p.status, ProcessStatus::Running) && !p.model_path.starts_with("tunnel:"));
+    let process = match model_path {
+        Some(path) => candidates.find(|p| p.model_path == path).ok_or_else(|| "That model is not running".to_string())?,
+        None => candidates.next().ok_or_else(|| "No running model found. Start a model to classify with.".to_string())?,
+    };
+    Ok((crate::suspend::local_base_url(&process.host, process.port), process.model_name.clone()))
+}
+
+async fn ask(server_url: &str, batch: &[TrackerModel]) -> Result<String, String> {
+    let request = http_pool::client_for(server_url)
+        .post(format!("{}/v1/chat/completions", server_url))
+        .json(&serde_json::json!({
+            "messages": [
+                {"role": "system", "content": SYSTEM_PROMPT},
+                {"role": "user", "content": prompt_for(batch)}
+            ],
+            "temperature": 0.0,
+            "stream": false
+        }));
+    let response = http_pool::with_timeout(request, RequestClass::Completion)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact classifier model: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Classification request failed with status {}", response.status().as_u16()));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse classifier response: {}", e))?;
+    Ok(body.pointer("/choices/0/message/content").and_then(|v| v.as_str()).unwrap_or_default().to_string())
+}
+
+/// Classify up to `limit` models that have no category yet, most downloaded first
+pub async fn classify(state: &AppState, model_path: Option<&str>, limit: usize) -> Result<serde_json::Value, String> {
+    let (server_url, model_name) = classifier_server(state, model_path).await?;
+    let tracker = TrackerDb::from_state(state).await?;
+    let pending: Vec<TrackerModel> = tracker
+        .run(|manager| manager.get_models(None, None, false, false, None, None, None, "downloads", true))
+        .await?
+        .into_iter()
+        .filter(|m| m.classification.is_none())
+        .take(limit)
+        .collect();
+
+    let mut classified = 0;
+    let mut errors = Vec::new();
+    for batch in pending.chunks(BATCH_SIZE) {
+        let ids: Vec<String> = batch.iter().map(|m| m.id.clone()).collect();
+        match ask(&server_url, batch).await {
+            Ok(content) => {
+                let results = parse_response(&content, &ids, &model_name);
+                classified += tracker.run(move |manager| manager.save_classifications(&results)).await?;
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+    println!("[Tracker] Classified {} of {} model(s) with {}", classified, pending.len(), model_name);
+
+    Ok(serde_json::json!({
+        "classified": classified,
+        "pending": pending.len(),
+        "model": model_name,
+        "errors": errors,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_batch_answers_and_drops_unknowns() {
+        let ids = vec!["Qwen/Qwen2.5-Coder-7B".to_string(), "BAAI/bge-m3".to_string()];
+        let content = r#"Sure! ```json
+        [{"id": "Qwen/Qwen2.5-Coder-7B", "category": "Coding", "confidence": 0.92},
+         {"id": "BAAI/bge-m3", "category": "embeddings"},
+         {"id": "someone/else", "category": "coder", "confidence": 1},
+         {"id": "BAAI/bge-m3", "category": "general", "confidence": 0.1}]
+        ```"#;
+        let results = parse_response(content, &ids, "qwen3-8b");
+        let summary: Vec<(&str, &str, f64)> = results.iter().map(|r| (r.model_id.as_str(), r.category.as_str(), r.confidence)).collect();
+        assert_eq!(summary, vec![("Qwen/Qwen2.5-Coder-7B", "coder", 0.92), ("BAAI/bge-m3", "embedding", 0.5)]);
+        assert!(parse_response("I cannot help with that", &ids, "x").is_empty());
+        assert_eq!(normalize_category("RP"), Some("roleplay"));
+        assert_eq!(normalize_category("finance"), None);
+    }
+}
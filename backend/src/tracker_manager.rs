@@ -1,8 +1,12 @@
-use crate::models::{TrackerConfig, TrackerModel, TrackerStats, WeeklyReport};
+use crate::models::{
+    ModelClassification, TrackerConfig, TrackerDbInfo, TrackerFile, TrackerFileList, TrackerModel, TrackerQuant, TrackerStats, WatchlistEntry, WeeklyReport,
+};
 use rusqlite::{params, Connection};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use crate::AppState;
 
 pub struct TrackerManager {
     conn: Mutex<Connection>,
@@ -17,97 +21,255 @@ impl std::fmt::Debug for TrackerManager {
     }
 }
 
-impl TrackerManager {
-    pub fn new(app_data_dir: PathBuf) -> Result<Self, String> {
-        std::fs::create_dir_all(&app_data_dir)
-            .map_err(|e| format!("Failed to create tracker directory: {}", e))?;
+type Job = Box<dyn FnOnce(&TrackerManager) + Send>;
+
+/// Async handle to the tracker database. Every query runs on one dedicated thread, so
+/// large scans never stall the async runtime. Dropping a pending call (a cancelled
+/// command) skips it if it has not started yet and interrupts it if it is running;
+/// an interrupted write rolls back with its transaction.
+#[derive(Clone)]
+pub struct TrackerDb {
+    jobs: std::sync::mpsc::Sender<Job>,
+    /// Id of the call executing right now
+    running: Arc<Mutex<Option<u64>>>,
+    next_id: Arc<AtomicU64>,
+    interrupt: Arc<rusqlite::InterruptHandle>,
+}
 
-        let db_path = app_data_dir.join("tracker.db");
-        let conn = Connection::open(&db_path)
-            .map_err(|e| format!("Failed to open database: {}", e))?;
+impl std::fmt::Debug for TrackerDb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TrackerDb").field("running", &self.running).finish()
+    }
+}
 
-        let manager = Self {
-            conn: Mutex::new(conn),
+impl TrackerDb {
+    pub fn open(legacy_dir: PathBuf) -> Result<Self, String> {
+        let manager = TrackerManager::new(legacy_dir)?;
+        let interrupt = Arc::new(manager.interrupt_handle()?);
+        let (jobs, receiver) = std::sync::mpsc::channel::<Job>();
+        std::thread::Builder::new()
+            .name("tracker-db".to_string())
+            .spawn(move || {
+                for job in receiver {
+                    job(&manager);
+                }
+            })
+            .map_err(|e| format!("Failed to start tracker database thread: {}", e))?;
+
+        Ok(Self {
+            jobs,
+            running: Arc::new(Mutex::new(None)),
+            next_id: Arc::new(AtomicU64::new(1)),
+            interrupt,
+        })
+    }
+
+    pub async fn from_state(state: &AppState) -> Result<Self, String> {
+        state.tracker_manager.lock().await.clone().ok_or_else(|| crate::i18n::t("tracker.not_initialized", &[]))
+    }
+
+    /// Run `query` on the database thread and wait for its result
+    pub async fn run<T, F>(&self, query: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&TrackerManager) -> Result<T, String> + Send + 'static,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = tokio::sync::oneshot::channel();
+
+        let running = self.running.clone();
+        let job_cancelled = cancelled.clone();
+        let job: Job = Box::new(move |manager| {
+            {
+                let mut current = running.lock().unwrap_or_else(|p| p.into_inner());
+                if job_cancelled.load(Ordering::SeqCst) {
+                    return;
+                }
+                *current = Some(id);
+            }
+            // A panicking query must not take the database thread down with it
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| query(manager)))
+                .unwrap_or_else(|_| Err("Tracker database query panicked".to_string()));
+            *running.lock().unwrap_or_else(|p| p.into_inner()) = None;
+            let _ = sender.send(result);
+        });
+
+        let mut guard = CancelOnDrop {
+            id,
+            cancelled,
+            running: self.running.clone(),
+            interrupt: self.interrupt.clone(),
+            armed: true,
         };
+        self.jobs.send(job).map_err(|_| "Tracker database thread stopped".to_string())?;
+        let result = receiver.await.map_err(|_| "Tracker database call was cancelled".to_string());
+        guard.armed = false;
+        result?
+    }
+}
 
-        manager.init_db()?;
-        
-        Ok(manager)
+/// Cancels the call it belongs to when the awaiting future is dropped early
+struct CancelOnDrop {
+    id: u64,
+    cancelled: Arc<AtomicBool>,
+    running: Arc<Mutex<Option<u64>>>,
+    interrupt: Arc<rusqlite::InterruptHandle>,
+    armed: bool,
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        // Same lock the job takes before starting, so it either sees the flag or is
+        // already the running call
+        let current = self.running.lock().unwrap_or_else(|p| p.into_inner());
+        self.cancelled.store(true, Ordering::SeqCst);
+        if *current == Some(self.id) {
+            self.interrupt.interrupt();
+        }
     }
+}
 
-    fn init_db(&self) -> Result<(), String> {
-        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+impl TrackerManager {
+    /// Tracker tables live in the shared store; `legacy_dir` is where older versions kept
+    /// their own `tracker.db`, imported once
+    pub fn new(legacy_dir: PathBuf) -> Result<Self, String> {
+        let conn = crate::storage::connect(&crate::storage::database_path()?)?;
+        if let Err(e) = crate::storage::import_legacy_tracker(&conn, &legacy_dir.join("tracker.db")) {
+            eprintln!("[Tracker] Could not import the previous tracker database: {}", e);
+        }
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS models (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                author TEXT,
-                description TEXT,
-                source TEXT DEFAULT 'huggingface',
-                category TEXT,
-                is_chinese INTEGER DEFAULT 0,
-                is_gguf INTEGER DEFAULT 0,
-                quantizations TEXT,
-                backends TEXT,
-                estimated_size_gb REAL,
-                vram_requirement_gb REAL,
-                context_length INTEGER,
-                downloads INTEGER DEFAULT 0,
-                likes INTEGER DEFAULT 0,
-                last_updated TEXT,
-                created_at TEXT
-            )",
-            [],
-        ).map_err(|e| format!("Failed to create models table: {}", e))?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS tracker_config (
-                key TEXT PRIMARY KEY,
-                value TEXT
-            )",
-            [],
-        ).map_err(|e| format!("Failed to create config table: {}", e))?;
+    fn interrupt_handle(&self) -> Result<rusqlite::InterruptHandle, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        Ok(conn.get_interrupt_handle())
+    }
 
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS weekly_reports (
-                id TEXT PRIMARY KEY,
-                generated_at TEXT,
-                period_start TEXT,
-                period_end TEXT,
-                total_models INTEGER,
-                new_models INTEGER,
-                chinese_models INTEGER,
-                gguf_models INTEGER,
-                categories TEXT,
-                top_downloads TEXT
-            )",
-            [],
-        ).map_err(|e| format!("Failed to create weekly_reports table: {}", e))?;
+    /// Record a refresh and mark every returned model as seen in it
+    pub fn record_scrape(&self, models: &[TrackerModel]) -> Result<i64, String> {
+        let mut conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let now = chrono::Utc::now().to_rfc3339();
+        let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        tx.execute(
+            "INSERT INTO scrape_runs (scraped_at, model_count) VALUES (?1, ?2)",
+            params![now, models.len() as i64],
+        ).map_err(|e| format!("Failed to record scrape: {}", e))?;
+        let scrape_id = tx.last_insert_rowid();
+        for model in models {
+            tx.execute(
+                "INSERT INTO model_sightings (model_id, last_scrape, first_seen, last_seen) VALUES (?1, ?2, ?3, ?3)
+                ON CONFLICT(model_id) DO UPDATE SET last_scrape = excluded.last_scrape, last_seen = excluded.last_seen",
+                params![model.id, scrape_id, now],
+            ).map_err(|e| format!("Failed to record sighting: {}", e))?;
+        }
+        tx.commit().map_err(|e| format!("Failed to record scrape: {}", e))?;
+        Ok(scrape_id)
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_category ON models(category)",
-            [],
-        ).map_err(|e| format!("Failed to create index: {}", e))?;
+    /// Delete models not returned by any of the last `keep_scrapes` refreshes. Watchlisted
+    /// models stay; so does everything until the first refresh has been recorded.
+    pub fn prune_unseen(&self, keep_scrapes: u32) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let latest: Option<i64> = conn
+            .query_row("SELECT MAX(id) FROM scrape_runs", [], |row| row.get(0))
+            .map_err(|e| format!("Query error: {}", e))?;
+        let Some(latest) = latest else { return Ok(0) };
+        let oldest_kept = latest - keep_scrapes.max(1) as i64 + 1;
+
+        let pruned = conn.execute(
+            "DELETE FROM models
+             WHERE id NOT IN (SELECT model_id FROM watchlist)
+               AND id NOT IN (SELECT model_id FROM model_sightings WHERE last_scrape >= ?1)",
+            params![oldest_kept],
+        ).map_err(|e| format!("Failed to prune models: {}", e))?;
+        conn.execute("DELETE FROM scrape_runs WHERE id < ?1", params![oldest_kept])
+            .map_err(|e| format!("Failed to prune scrape history: {}", e))?;
+        Ok(pruned)
+    }
 
+    /// Collapse rows whose ids differ only in case (the same repo reached through
+    /// different sources), keeping the most downloaded one
+    pub fn dedupe(&self) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
         conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_chinese ON models(is_chinese)",
+            "DELETE FROM models WHERE rowid NOT IN (
+                SELECT rowid FROM (
+                    SELECT rowid, ROW_NUMBER() OVER (
+                        PARTITION BY lower(id) ORDER BY downloads DESC, last_updated DESC, rowid
+                    ) AS row_rank FROM models
+                ) WHERE row_rank = 1
+            )",
             [],
-        ).map_err(|e| format!("Failed to create index: {}", e))?;
+        ).map_err(|e| format!("Failed to deduplicate models: {}", e))
+    }
 
-        conn.execute(
-            "CREATE INDEX IF NOT EXISTS idx_gguf ON models(is_gguf)",
-            [],
-        ).map_err(|e| format!("Failed to create index: {}", e))?;
+    /// Drop cached files, sightings and model-made classifications of deleted models.
+    /// Manual categories are kept in case the model comes back.
+    pub fn remove_orphans(&self) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let mut removed = 0;
+        for sql in [
+            "DELETE FROM model_files WHERE model_id NOT IN (SELECT id FROM models)",
+            "DELETE FROM model_sightings WHERE model_id NOT IN (SELECT id FROM models)",
+            "DELETE FROM model_classifications WHERE source != 'manual' AND model_id NOT IN (SELECT id FROM models)",
+        ] {
+            removed += conn.execute(sql, []).map_err(|e| format!("Failed to remove orphaned rows: {}", e))?;
+        }
+        Ok(removed)
+    }
 
-        Ok(())
+    pub fn vacuum(&self) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        conn.execute_batch("VACUUM").map_err(|e| format!("Failed to vacuum tracker database: {}", e))
     }
 
-    pub fn clear_models(&self) -> Result<(), String> {
+    pub fn db_info(&self) -> Result<TrackerDbInfo, String> {
         let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
-        conn.execute("DELETE FROM models", [])
-            .map_err(|e| format!("Failed to clear models: {}", e))?;
-        Ok(())
+        let pragma = |name: &str| -> Result<u64, String> {
+            conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0))
+                .map(|v| v.max(0) as u64)
+                .map_err(|e| format!("Query error: {}", e))
+        };
+        let page_size = pragma("page_size")?;
+        let count = |table: &str| -> Result<u32, String> {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .map_err(|e| format!("Query error: {}", e))
+        };
+        Ok(TrackerDbInfo {
+            size_bytes: pragma("page_count")? * page_size,
+            free_bytes: pragma("freelist_count")? * page_size,
+            model_count: count("models")?,
+            scrape_count: count("scrape_runs")?,
+            ..Default::default()
+        })
+    }
+
+    /// Prune, dedupe and clean up after a refresh; VACUUM only once a quarter of the
+    /// file is free pages, or when `force_vacuum` is set
+    pub fn maintain(&self, keep_scrapes: u32, force_vacuum: bool) -> Result<TrackerDbInfo, String> {
+        let pruned = self.prune_unseen(keep_scrapes)?;
+        let deduplicated = self.dedupe()?;
+        let orphans_removed = self.remove_orphans()?;
+        let before = self.db_info()?;
+        let vacuumed = force_vacuum || (before.size_bytes > 0 && before.free_bytes * 4 >= before.size_bytes);
+        if vacuumed {
+            self.vacuum()?;
+        }
+        let info = TrackerDbInfo { pruned, deduplicated, orphans_removed, vacuumed, ..self.db_info()? };
+        if pruned + deduplicated + orphans_removed > 0 || vacuumed {
+            println!(
+                "[Tracker] Maintenance: pruned {}, deduplicated {}, {} orphaned row(s), vacuumed: {} ({} KB)",
+                pruned, deduplicated, orphans_removed, vacuumed, info.size_bytes / 1024
+            );
+        }
+        Ok(info)
     }
 
     pub fn save_models(&self, models: &[TrackerModel]) -> Result<(), String> {
@@ -150,6 +312,7 @@ impl TrackerManager {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn get_models(
         &self,
         vram_limit: Option<f64>,
@@ -199,8 +362,12 @@ impl TrackerManager {
                 let placeholders: Vec<String> = cats.iter()
                     .map(|_| "?".to_string())
                     .collect();
-                sql.push_str(&format!(" AND category IN ({})", placeholders.join(",")));
-                for cat in cats {
+                // Scraped categories and classified use cases share the filter
+                sql.push_str(&format!(
+                    " AND (category IN ({0}) OR id IN (SELECT model_id FROM model_classifications WHERE category IN ({0})))",
+                    placeholders.join(",")
+                ));
+                for cat in cats.iter().chain(cats.iter()) {
                     params_vec.push(Box::new(cat.clone()));
                 }
             }
@@ -249,9 +416,12 @@ impl TrackerManager {
                 likes: row.get::<_, i64>(14)? as u64,
                 last_updated: row.get(15)?,
                 created_at: row.get(16)?,
+                classification: None,
             })
         }).map_err(|e| format!("Query error: {}", e))?;
 
+        let file_quants = Self::cached_quantizations(&conn)?;
+        let mut classifications = Self::classifications(&conn)?;
         let mut models = Vec::new();
         for model_result in models_iter {
             match model_result {
@@ -277,10 +447,16 @@ impl TrackerManager {
                         }
                     }
 
+                    let mut model = model;
+                    if let Some(exact) = file_quants.get(&model.id) {
+                        model.quantizations = exact.clone();
+                    }
+                    model.classification = classifications.remove(&model.id);
+
                     if let Some(ref quants) = quantizations {
                         if !quants.is_empty() {
                             let model_has_quant = model.quantizations.iter()
-                                .any(|q| quants.iter().any(|uq| uq.to_lowercase() == q.to_lowercase()));
+                                .any(|q| quants.iter().any(|uq| quant_matches(uq, q)));
                             if !model_has_quant {
                                 continue;
                             }
@@ -303,6 +479,138 @@ impl TrackerManager {
         Ok(models)
     }
 
+    /// Exact quantizations per model with a cached file list
+    fn cached_quantizations(conn: &Connection) -> Result<HashMap<String, Vec<String>>, String> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT DISTINCT model_id, quantization FROM model_files
+                 WHERE quantization IS NOT NULL AND lower(file_name) NOT LIKE '%mmproj%'
+                 ORDER BY quantization",
+            )
+            .map_err(|e| format!("Query error: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| format!("Query error: {}", e))?;
+        let mut quants: HashMap<String, Vec<String>> = HashMap::new();
+        for (model_id, quant) in rows.flatten() {
+            quants.entry(model_id).or_default().push(quant);
+        }
+        Ok(quants)
+    }
+
+    fn classifications(conn: &Connection) -> Result<HashMap<String, ModelClassification>, String> {
+        let mut stmt = conn
+            .prepare("SELECT model_id, category, confidence, source, classified_by, classified_at FROM model_classifications")
+            .map_err(|e| format!("Query error: {}", e))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ModelClassification {
+                    model_id: row.get(0)?,
+                    category: row.get(1)?,
+                    confidence: row.get::<_, Option<f64>>(2)?.unwrap_or(0.0),
+                    source: row.get::<_, Option<String>>(3)?.unwrap_or_else(|| "model".to_string()),
+                    classified_by: row.get(4)?,
+                    classified_at: row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+                })
+            })
+            .map_err(|e| format!("Query error: {}", e))?;
+        Ok(rows.flatten().map(|c| (c.model_id.clone(), c)).collect())
+    }
+
+    /// Store model-made classifications; manual overrides are left alone. Returns how
+    /// many rows were written.
+    pub fn save_classifications(&self, classifications: &[ModelClassification]) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let mut saved = 0;
+        for c in classifications {
+            saved += conn.execute(
+                "INSERT INTO model_classifications (model_id, category, confidence, source, classified_by, classified_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                ON CONFLICT(model_id) DO UPDATE SET
+                    category = excluded.category, confidence = excluded.confidence, source = excluded.source,
+                    classified_by = excluded.classified_by, classified_at = excluded.classified_at
+                WHERE model_classifications.source != 'manual'",
+                params![c.model_id, c.category, c.confidence, c.source, c.classified_by, c.classified_at],
+            ).map_err(|e| format!("Failed to save classification: {}", e))?;
+        }
+        Ok(saved)
+    }
+
+    /// Pin a model's category, or with None drop it so the next pass classifies again
+    pub fn set_manual_category(&self, model_id: &str, category: Option<&str>) -> Result<(), String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        match category {
+            Some(category) => conn.execute(
+                "INSERT OR REPLACE INTO model_classifications (model_id, category, confidence, source, classified_by, classified_at)
+                VALUES (?1, ?2, 1.0, 'manual', NULL, ?3)",
+                params![model_id, category, chrono::Utc::now().to_rfc3339()],
+            ),
+            None => conn.execute("DELETE FROM model_classifications WHERE model_id = ?1", params![model_id]),
+        }
+        .map_err(|e| format!("Failed to set category: {}", e))?;
+        Ok(())
+    }
+
+    /// Replace a model's cached file list and refresh its quantizations and size
+    pub fn save_model_files(&self, model_id: &str, files: &[TrackerFile]) -> Result<TrackerFileList, String> {
+        let mut conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let fetched_at = chrono::Utc::now().to_rfc3339();
+        let list = file_list(model_id, &fetched_at, files.to_vec());
+
+        let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        tx.execute("DELETE FROM model_files WHERE model_id = ?1", params![model_id])
+            .map_err(|e| format!("Failed to clear model files: {}", e))?;
+        for file in files {
+            tx.execute(
+                "INSERT OR REPLACE INTO model_files (model_id, file_name, size_bytes, quantization, fetched_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![model_id, file.file_name, file.size_bytes as i64, file.quantization, fetched_at],
+            ).map_err(|e| format!("Failed to save model files: {}", e))?;
+        }
+        let names: Vec<&String> = list.quantizations.iter().map(|q| &q.name).collect();
+        let size_gb = files.iter().map(|f| f.size_bytes as f64).sum::<f64>() / 1_000_000_000.0;
+        tx.execute(
+            "UPDATE models SET quantizations = ?1, estimated_size_gb = ?2, is_gguf = ?3 WHERE id = ?4",
+            params![
+                serde_json::to_string(&names).unwrap_or_else(|_| "[]".to_string()),
+                size_gb,
+                (!files.is_empty()) as i32,
+                model_id,
+            ],
+        ).map_err(|e| format!("Failed to update model: {}", e))?;
+        tx.commit().map_err(|e| format!("Failed to save model files: {}", e))?;
+
+        Ok(list)
+    }
+
+    /// Cached file list, if the model was expanded before
+    pub fn get_model_files(&self, model_id: &str) -> Result<Option<TrackerFileList>, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let mut stmt = conn.prepare(
+            "SELECT file_name, size_bytes, quantization, fetched_at FROM model_files
+             WHERE model_id = ?1 ORDER BY file_name"
+        ).map_err(|e| format!("Query error: {}", e))?;
+
+        let mut fetched_at = None;
+        let rows = stmt.query_map(params![model_id], |row| {
+            Ok((
+                TrackerFile {
+                    file_name: row.get(0)?,
+                    size_bytes: row.get::<_, i64>(1)?.max(0) as u64,
+                    quantization: row.get(2)?,
+                },
+                row.get::<_, Option<String>>(3)?,
+            ))
+        }).map_err(|e| format!("Query error: {}", e))?;
+        let mut files = Vec::new();
+        for (file, at) in rows.flatten() {
+            fetched_at = fetched_at.or(at);
+            files.push(file);
+        }
+
+        Ok(fetched_at.map(|at| file_list(model_id, &at, files)))
+    }
+
     pub fn get_stats(&self) -> Result<TrackerStats, String> {
         let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
 
@@ -333,10 +641,8 @@ impl TrackerManager {
         }).map_err(|e| format!("Query error: {}", e))?;
 
         let mut categories = HashMap::new();
-        for cat_result in categories_iter {
-            if let Ok((cat, count)) = cat_result {
-                categories.insert(cat, count);
-            }
+        for (cat, count) in categories_iter.flatten() {
+            categories.insert(cat, count);
         }
 
         Ok(TrackerStats {
@@ -387,6 +693,65 @@ impl TrackerManager {
             .map_err(|e| format!("Export error: {}", e))
     }
 
+    /// Load models from `export_json` output; existing ids are updated in place
+    pub fn import_json(&self, json: &str) -> Result<usize, String> {
+        let models: Vec<TrackerModel> = serde_json::from_str(json)
+            .map_err(|e| format!("Import error: {}", e))?;
+        self.save_models(&models)?;
+        Ok(models.len())
+    }
+
+    /// Insert watchlist entries; models already being watched keep their original entry.
+    /// Returns how many entries were newly added.
+    pub fn add_to_watchlist(&self, entries: &[WatchlistEntry]) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+        let mut added = 0;
+        for entry in entries {
+            added += conn.execute(
+                "INSERT OR IGNORE INTO watchlist (model_id, source, collection_slug, note, added_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    entry.model_id,
+                    entry.source,
+                    entry.collection_slug,
+                    entry.note,
+                    entry.added_at,
+                ],
+            ).map_err(|e| format!("Failed to add to watchlist: {}", e))?;
+        }
+
+        Ok(added)
+    }
+
+    pub fn get_watchlist(&self) -> Result<Vec<WatchlistEntry>, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT model_id, source, collection_slug, note, added_at
+             FROM watchlist ORDER BY added_at DESC"
+        ).map_err(|e| format!("Query error: {}", e))?;
+
+        let entries = stmt.query_map([], |row| {
+            Ok(WatchlistEntry {
+                model_id: row.get(0)?,
+                source: row.get::<_, Option<String>>(1)?.unwrap_or_else(|| "manual".to_string()),
+                collection_slug: row.get(2)?,
+                note: row.get(3)?,
+                added_at: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+            })
+        }).map_err(|e| format!("Query error: {}", e))?;
+
+        Ok(entries.flatten().collect())
+    }
+
+    pub fn remove_from_watchlist(&self, model_id: &str) -> Result<bool, String> {
+        let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
+        let removed = conn.execute("DELETE FROM watchlist WHERE model_id = ?1", params![model_id])
+            .map_err(|e| format!("Failed to remove from watchlist: {}", e))?;
+        Ok(removed > 0)
+    }
+
     pub fn get_weekly_reports(&self, limit: u32) -> Result<Vec<WeeklyReport>, String> {
         let conn = self.conn.lock().map_err(|e| format!("Lock error: {}", e))?;
 
@@ -415,10 +780,8 @@ impl TrackerManager {
         }).map_err(|e| format!("Query error: {}", e))?;
 
         let mut result = Vec::new();
-        for report in reports {
-            if let Ok(r) = report {
-                result.push(r);
-            }
+        for r in reports.flatten() {
+            result.push(r);
         }
 
         Ok(result)
@@ -453,10 +816,8 @@ impl TrackerManager {
         }).map_err(|e| format!("Query error: {}", e))?;
 
         let mut categories = HashMap::new();
-        for cat_result in categories_iter {
-            if let Ok((cat, count)) = cat_result {
-                categories.insert(cat, count);
-            }
+        for (cat, count) in categories_iter.flatten() {
+            categories.insert(cat, count);
         }
 
         // Get top downloads
@@ -489,14 +850,13 @@ impl TrackerManager {
                 likes: row.get::<_, i64>(14)? as u64,
                 last_updated: row.get(15)?,
                 created_at: row.get(16)?,
+                classification: None,
             })
         }).map_err(|e| format!("Query error: {}", e))?;
 
         let mut top_downloads = Vec::new();
-        for model in top_iter {
-            if let Ok(m) = model {
-                top_downloads.push(m);
-            }
+        for m in top_iter.flatten() {
+            top_downloads.push(m);
         }
 
         let report = WeeklyReport {
@@ -547,3 +907,39 @@ impl TrackerManager {
         Ok(report)
     }
 }
+
+/// A filter matches its exact quant, and a coarse filter (`IQ4`) matches every variant
+fn quant_matches(filter: &str, quant: &str) -> bool {
+    let filter = filter.trim().to_uppercase().replace('-', "_");
+    let quant = quant.to_uppercase();
+    quant == filter || quant.starts_with(&format!("{}_", filter))
+}
+
+/// Group files by quantization, counting split shards together
+fn file_list(model_id: &str, fetched_at: &str, files: Vec<TrackerFile>) -> TrackerFileList {
+    use crate::vram_estimate::{estimate_remote, EstimateOptions};
+
+    let mut quantizations: Vec<TrackerQuant> = Vec::new();
+    for file in files.iter().filter(|f| !f.file_name.to_lowercase().contains("mmproj")) {
+        let Some(name) = file.quantization.clone() else { continue };
+        match quantizations.iter_mut().find(|q| q.name == name) {
+            Some(quant) => {
+                quant.size_bytes += file.size_bytes;
+                quant.file_count += 1;
+            }
+            None => quantizations.push(TrackerQuant { name, size_bytes: file.size_bytes, file_count: 1, vram_estimate_gb: None }),
+        }
+    }
+    for quant in &mut quantizations {
+        quant.vram_estimate_gb = estimate_remote(model_id, None, Some(&quant.name), Some(quant.size_bytes), &EstimateOptions::default())
+            .map(|estimate| estimate.total_gb);
+    }
+    quantizations.sort_by_key(|q| q.size_bytes);
+
+    TrackerFileList {
+        model_id: model_id.to_string(),
+        fetched_at: fetched_at.to_string(),
+        files,
+        quantizations,
+    }
+}
@@ -1,11 +1,9 @@
-use crate::models::TrackerModel;
+use crate::models::{HfCollection, HfCollectionItem, TrackerFile, TrackerModel};
 use chrono::Utc;
-use reqwest::Client;
+use crate::hf_client;
 use serde::Deserialize;
 
-pub struct TrackerScraper {
-    client: Client,
-}
+pub struct TrackerScraper;
 
 #[derive(Debug, Deserialize)]
 struct HFSearchResponse {
@@ -48,12 +46,91 @@ struct HFTreeItem {
     file_type: String,
 }
 
-impl TrackerScraper {
-    pub fn new() -> Self {
+#[derive(Debug, Deserialize)]
+struct HFCollectionOwner {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HFCollectionNote {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct HFCollectionItemResponse {
+    #[serde(default)]
+    id: String,
+    #[serde(default, rename = "type")]
+    item_type: String,
+    #[serde(default)]
+    note: Option<HFCollectionNote>,
+    #[serde(default)]
+    downloads: Option<u64>,
+    #[serde(default)]
+    likes: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HFCollectionResponse {
+    slug: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    owner: Option<HFCollectionOwner>,
+    #[serde(default)]
+    upvotes: u64,
+    #[serde(default, rename = "lastUpdated")]
+    last_updated: Option<String>,
+    #[serde(default)]
+    items: Vec<HFCollectionItemResponse>,
+}
+
+impl From<HFCollectionResponse> for HfCollection {
+    fn from(collection: HFCollectionResponse) -> Self {
+        let owner = collection
+            .owner
+            .map(|o| o.name)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| collection.slug.split('/').next().unwrap_or_default().to_string());
+
         Self {
-            client: Client::new(),
+            slug: collection.slug,
+            title: collection.title,
+            description: collection.description.filter(|d| !d.is_empty()),
+            owner,
+            upvotes: collection.upvotes,
+            last_updated: collection.last_updated,
+            items: collection
+                .items
+                .into_iter()
+                .filter(|item| !item.id.is_empty())
+                .map(|item| HfCollectionItem {
+                    id: item.id,
+                    item_type: item.item_type,
+                    note: item.note.map(|n| n.text).filter(|t| !t.is_empty()),
+                    downloads: item.downloads,
+                    likes: item.likes,
+                })
+                .collect(),
         }
     }
+}
+
+// Owners and slugs end up in the URL path, so keep them to the characters HF allows
+fn is_valid_hf_path_segment(value: &str) -> bool {
+    !value.is_empty()
+        && !value.contains("..")
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+}
+
+impl TrackerScraper {
+    pub fn new() -> Self {
+        Self
+    }
 
     pub async fn fetch_trending_models(&self, limit: u32) -> Result<Vec<TrackerModel>, String> {
         let url = format!(
@@ -61,16 +138,12 @@ impl TrackerScraper {
             limit
         );
 
-        let response = self.client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+        let response = hf_client::get(&url)
             .await
             .map_err(|e| format!("Failed to fetch models: {}", e))?;
 
         let models: Vec<HFSearchResponse> = response
             .json()
-            .await
             .map_err(|e| format!("Failed to parse models: {}", e))?;
 
         let mut tracker_models = Vec::new();
@@ -84,15 +157,15 @@ impl TrackerScraper {
 
             let details = self.fetch_model_details(&model_id).await;
             
-            let (quantizations, backends, is_gguf, size_gb) = if let Ok(d) = &details {
+            let (quantizations, backends, is_gguf, size_gb, vram_gb) = if let Ok(d) = &details {
                 let files = self.fetch_model_files(&model_id).await.unwrap_or_default();
                 let quants = Self::detect_quantizations(&files);
                 let backs = Self::detect_backends(&d.tags);
                 let gguf = !quants.is_empty();
                 let size = files.iter().map(|f| f.size as f64).sum::<f64>() / 1_000_000_000.0;
-                (quants, backs, gguf, size)
+                (quants, backs, gguf, size, Self::vram_requirement(&model_id, &files))
             } else {
-                (Vec::new(), Vec::new(), false, 0.0)
+                (Vec::new(), Vec::new(), false, 0.0, None)
             };
 
             let category = Self::categorize_model(
@@ -119,12 +192,13 @@ impl TrackerScraper {
                 quantizations,
                 backends,
                 estimated_size_gb: size_gb,
-                vram_requirement_gb: None,
+                vram_requirement_gb: vram_gb,
                 context_length: None,
                 downloads: model.downloads,
                 likes: model.likes,
                 last_updated: Some(model.last_modified),
                 created_at: Utc::now().to_rfc3339(),
+                classification: None,
             });
         }
 
@@ -167,16 +241,12 @@ impl TrackerScraper {
             }
         }
 
-        let response = self.client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+        let response = hf_client::get(&url)
             .await
             .map_err(|e| format!("Failed to fetch live results: {}", e))?;
 
         let models: Vec<HFSearchResponse> = response
             .json()
-            .await
             .map_err(|e| format!("Failed to parse live results: {}", e))?;
 
         let mut tracker_models = Vec::new();
@@ -228,49 +298,97 @@ impl TrackerScraper {
                     .map(|d| Self::detect_backends(&d.tags))
                     .unwrap_or_default(),
                 estimated_size_gb: size_gb,
-                vram_requirement_gb: None,
+                vram_requirement_gb: Self::vram_requirement(&model_id, &files),
                 context_length: None,
                 downloads: model.downloads,
                 likes: model.likes,
                 last_updated: Some(model.last_modified),
                 created_at: chrono::Utc::now().to_rfc3339(),
+                classification: None,
             });
         }
 
         Ok(tracker_models)
     }
 
+    /// List the public collections of a user or organisation, most upvoted first
+    pub async fn fetch_collections(&self, owner: &str) -> Result<Vec<HfCollection>, String> {
+        let owner = owner.trim();
+        if !is_valid_hf_path_segment(owner) || owner.contains('/') {
+            return Err(format!("Invalid collection owner: {}", owner));
+        }
+
+        let url = format!(
+            "https://huggingface.co/api/collections?owner={}&sort=upvotes&limit=100",
+            urlencoding::encode(owner)
+        );
+
+        let response = hf_client::get(&url)
+            .await
+            .map_err(|e| format!("Failed to fetch collections: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch collections (HTTP {})", response.status()));
+        }
+
+        let collections: Vec<HFCollectionResponse> = response
+            .json()
+            .map_err(|e| format!("Failed to parse collections: {}", e))?;
+
+        Ok(collections.into_iter().map(HfCollection::from).collect())
+    }
+
+    /// Fetch a single collection with its complete item list
+    pub async fn fetch_collection(&self, slug: &str) -> Result<HfCollection, String> {
+        let slug = slug.trim().trim_matches('/');
+        if !is_valid_hf_path_segment(slug) {
+            return Err(format!("Invalid collection slug: {}", slug));
+        }
+
+        let url = format!("https://huggingface.co/api/collections/{}", slug);
+
+        let response = hf_client::get(&url)
+            .await
+            .map_err(|e| format!("Failed to fetch collection: {}", e))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err("Collection not found".to_string());
+        }
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch collection (HTTP {})", response.status()));
+        }
+
+        let collection: HFCollectionResponse = response
+            .json()
+            .map_err(|e| format!("Failed to parse collection: {}", e))?;
+
+        Ok(collection.into())
+    }
+
     async fn fetch_model_details(&self, model_id: &str) -> Result<HFModelDetails, String> {
         let url = format!("https://huggingface.co/api/models/{}", model_id);
 
-        let response = self.client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+        let response = hf_client::get(&url)
             .await
             .map_err(|e| format!("Failed to fetch model details: {}", e))?;
 
         let details: HFModelDetails = response
             .json()
-            .await
             .map_err(|e| format!("Failed to parse model details: {}", e))?;
 
         Ok(details)
     }
 
     async fn fetch_model_files(&self, model_id: &str) -> Result<Vec<HFFile>, String> {
-        let url = format!("https://huggingface.co/api/models/{}/tree/main", model_id);
+        // Recursive, so quants kept in per-quant folders are listed too
+        let url = format!("https://huggingface.co/api/models/{}/tree/main?recursive=true", model_id);
 
-        let response = self.client
-            .get(&url)
-            .header("Accept", "application/json")
-            .send()
+        let response = hf_client::get(&url)
             .await
             .map_err(|e| format!("Failed to fetch model files: {}", e))?;
 
         let tree: Vec<HFTreeItem> = response
             .json()
-            .await
             .map_err(|e| format!("Failed to parse model files: {}", e))?;
 
         let files: Vec<HFFile> = tree
@@ -285,6 +403,28 @@ impl TrackerScraper {
         Ok(files)
     }
 
+    /// Every GGUF in the repo with its size and exact quantization (`IQ4_XS`, not `IQ4`)
+    pub async fn fetch_file_list(&self, model_id: &str) -> Result<Vec<TrackerFile>, String> {
+        let files = self.fetch_model_files(model_id).await?;
+        Ok(files
+            .into_iter()
+            .map(|f| TrackerFile {
+                quantization: crate::vram_estimate::quant_from_name(&f.rfilename),
+                file_name: f.rfilename,
+                size_bytes: f.size.max(0) as u64,
+            })
+            .collect())
+    }
+
+    /// VRAM to run the repo's Q4_K_M (or smallest) GGUF at the default 4K context
+    fn vram_requirement(model_id: &str, files: &[HFFile]) -> Option<f64> {
+        use crate::vram_estimate::{estimate_remote, pick_remote_file, quant_from_name, EstimateOptions};
+        let listed: Vec<(String, u64)> = files.iter().map(|f| (f.rfilename.clone(), f.size.max(0) as u64)).collect();
+        let (file, bytes) = pick_remote_file(&listed)?;
+        let quant = quant_from_name(&file);
+        estimate_remote(model_id, None, quant.as_deref(), Some(bytes), &EstimateOptions::default()).map(|estimate| estimate.total_gb)
+    }
+
     fn detect_quantizations(files: &[HFFile]) -> Vec<String> {
         let mut quants = Vec::new();
         
@@ -0,0 +1,229 @@
+use serde::Serialize;
+use crate::http_pool::{self, RequestClass};
+use crate::models::{ProcessStatus, TranslationSettings};
+use crate::AppState;
+
+// Translation assist: detect the language a user writes in and translate through a
+// running model, so people can talk to English-centric models in their own language.
+// Detection is a lightweight script + stopword heuristic; it only has to tell
+// "already in the target language" apart from "needs translating".
+
+const DEFAULT_PROMPT: &str = "Translate the following text from {source} to {target}. Reply with the translation only. Keep formatting, code, numbers and names unchanged.";
+
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("de", "German"),
+    ("it", "Italian"),
+    ("pt", "Portuguese"),
+    ("nl", "Dutch"),
+    ("pl", "Polish"),
+    ("tr", "Turkish"),
+    ("ru", "Russian"),
+    ("uk", "Ukrainian"),
+    ("el", "Greek"),
+    ("ar", "Arabic"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("th", "Thai"),
+    ("zh", "Chinese"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+];
+
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "is", "are", "you", "what", "how", "this", "that", "with", "for", "of", "to", "can", "it"]),
+    ("es", &["el", "la", "los", "las", "que", "de", "es", "y", "en", "por", "para", "una", "como", "qué", "cómo"]),
+    ("fr", &["le", "la", "les", "est", "et", "que", "une", "des", "pour", "dans", "vous", "je", "pas", "avec", "ce"]),
+    ("de", &["der", "die", "das", "und", "ist", "nicht", "ich", "mit", "für", "ein", "eine", "wie", "sie", "zu", "auf"]),
+    ("it", &["il", "che", "di", "è", "non", "per", "una", "sono", "come", "gli", "della", "con", "lo", "mi", "questo"]),
+    ("pt", &["o", "que", "não", "é", "uma", "para", "com", "os", "do", "da", "em", "você", "como", "mas", "isso"]),
+    ("nl", &["de", "het", "een", "en", "is", "niet", "ik", "van", "dat", "op", "met", "voor", "je", "zijn", "wat"]),
+    ("pl", &["nie", "jest", "się", "to", "że", "na", "jak", "czy", "co", "mam", "dla", "ale", "tak", "jestem", "w"]),
+    ("tr", &["bir", "ve", "bu", "ne", "için", "ile", "mi", "değil", "nasıl", "ben", "sen", "çok", "da", "de", "var"]),
+];
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 code, `und` when nothing matched
+    pub code: String,
+    pub name: String,
+    pub confidence: f32,
+}
+
+pub fn language_name(code: &str) -> String {
+    LANGUAGE_NAMES
+        .iter()
+        .find(|(c, _)| c.eq_ignore_ascii_case(code))
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| code.to_string())
+}
+
+fn detected(code: &str, confidence: f32) -> DetectedLanguage {
+    DetectedLanguage {
+        code: code.to_string(),
+        name: if code == "und" { "Unknown".to_string() } else { language_name(code) },
+        confidence: confidence.clamp(0.0, 1.0),
+    }
+}
+
+/// Script of a non-Latin character, as the language it most likely implies
+fn script_language(c: char) -> Option<&'static str> {
+    match c as u32 {
+        0x3040..=0x30FF => Some("ja"),
+        0xAC00..=0xD7AF | 0x1100..=0x11FF => Some("ko"),
+        0x4E00..=0x9FFF => Some("zh"),
+        0x0400..=0x04FF => Some("ru"),
+        0x0370..=0x03FF => Some("el"),
+        0x0600..=0x06FF => Some("ar"),
+        0x0590..=0x05FF => Some("he"),
+        0x0900..=0x097F => Some("hi"),
+        0x0E00..=0x0E7F => Some("th"),
+        _ => None,
+    }
+}
+
+pub fn detect_language(text: &str) -> DetectedLanguage {
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.is_empty() {
+        return detected("und", 0.0);
+    }
+
+    let mut scripts: Vec<(&str, usize)> = Vec::new();
+    for lang in letters.iter().filter_map(|c| script_language(*c)) {
+        match scripts.iter_mut().find(|(l, _)| *l == lang) {
+            Some((_, count)) => *count += 1,
+            None => scripts.push((lang, 1)),
+        }
+    }
+    let non_latin: usize = scripts.iter().map(|(_, n)| n).sum();
+    if non_latin * 2 > letters.len() {
+        // Kana anywhere means Japanese even when kanji dominate
+        let has_kana = scripts.iter().any(|(l, _)| *l == "ja");
+        let cyrillic_uk = scripts.iter().any(|(l, _)| *l == "ru") && text.chars().any(|c| "іїєґІЇЄҐ".contains(c));
+        let (lang, count) = scripts.iter().max_by_key(|(_, n)| *n).copied().unwrap_or(("und", 0));
+        let lang = if has_kana && lang == "zh" { "ja" } else if cyrillic_uk { "uk" } else { lang };
+        let share = if has_kana && lang == "ja" { non_latin } else { count };
+        return detected(lang, share as f32 / letters.len() as f32);
+    }
+
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+    let scores: Vec<(&str, usize)> = STOPWORDS
+        .iter()
+        .map(|(lang, stopwords)| (*lang, words.iter().filter(|w| stopwords.contains(*w)).count()))
+        .collect();
+    let total: usize = scores.iter().map(|(_, n)| n).sum();
+    let Some((lang, best)) = scores.iter().max_by_key(|(_, n)| *n).copied().filter(|(_, n)| *n > 0) else {
+        return detected("und", 0.0);
+    };
+    // Short texts give few stopword hits, so scale confidence down for them
+    let coverage = (words.len() as f32 / 6.0).min(1.0);
+    detected(lang, best as f32 / total as f32 * coverage)
+}
+
+/// Base URL of the model used for translating: the configured one, else any running model
+async fn translation_server(state: &AppState, model_path: Option<&str>) -> Result<(String, String), String> {
+    let running = state.running_processes.lock().await;
+    let mut candidates = running
+        .values()
+        .filter(|p| matches!(p.status, ProcessStatus::Running) && !p.model_path.starts_with("tunnel:"));
+    let process = match model_path {
+        Some(path) => candidates
+            .find(|p| p.model_path == path)
+            .ok_or_else(|| "The translation model is not running. Start it and try again.".to_string())?,
+        None => candidates
+            .next()
+            .ok_or_else(|| "No running model found. Start a model and try again.".to_string())?,
+    };
+    Ok((crate::suspend::local_base_url(&process.host, process.port), process.model_name.clone()))
+}
+
+fn instruction(settings: &TranslationSettings, source: &str, target: &str) -> String {
+    settings
+        .prompt
+        .as_deref()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or(DEFAULT_PROMPT)
+        .replace("{source}", &language_name(source))
+        .replace("{target}", &language_name(target))
+}
+
+pub async fn translate(
+    state: &AppState,
+    settings: &TranslationSettings,
+    text: &str,
+    source: &str,
+    target: &str,
+) -> Result<String, String> {
+    let (server_url, model_name) = translation_server(state, settings.model_path.as_deref()).await?;
+    let request = http_pool::client_for(&server_url)
+        .post(format!("{}/v1/chat/completions", server_url))
+        .json(&serde_json::json!({
+            "messages": [
+                {"role": "system", "content": instruction(settings, source, target)},
+                {"role": "user", "content": text}
+            ],
+            "temperature": 0.1,
+            "stream": false
+        }));
+    let response = http_pool::with_timeout(request, RequestClass::Completion)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to contact translation model: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Translation request failed with status {}", response.status().as_u16()));
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse translation response: {}", e))?;
+    let translated = body
+        .pointer("/choices/0/message/content")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .ok_or_else(|| "Translation model returned empty content".to_string())?;
+    println!("[Translate] {} -> {} via {} ({} chars)", source, target, model_name, text.len());
+    Ok(translated.to_string())
+}
+
+pub fn for_chat(chat_id: &str) -> Result<TranslationSettings, String> {
+    crate::read_chat_setting(chat_id, "translation_settings")
+}
+
+pub fn save_for_chat(chat_id: &str, settings: &TranslationSettings) -> Result<(), String> {
+    if settings.target_language.trim().is_empty() {
+        return Err("Target language is required".to_string());
+    }
+    crate::write_chat_setting(chat_id, "translation_settings", settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_common_languages() {
+        assert_eq!(detect_language("What is the best way to learn Rust for this project?").code, "en");
+        assert_eq!(detect_language("¿Cómo puedo instalar el modelo en mi ordenador para que funcione?").code, "es");
+        assert_eq!(detect_language("Ich habe eine Frage und ich weiß nicht wie das geht").code, "de");
+        assert_eq!(detect_language("Привет, как дела?").code, "ru");
+        assert_eq!(detect_language("これは日本語の文章です").code, "ja");
+        assert_eq!(detect_language("这是一个中文句子").code, "zh");
+        assert_eq!(detect_language("12345 !!!").code, "und");
+    }
+
+    #[test]
+    fn instruction_uses_language_names() {
+        let settings = TranslationSettings::default();
+        assert!(instruction(&settings, "pt", "en").contains("from Portuguese to English"));
+        let custom = TranslationSettings { prompt: Some("{source}=>{target}".into()), ..TranslationSettings::default() };
+        assert_eq!(instruction(&custom, "de", "en"), "German=>English");
+    }
+}
@@ -0,0 +1,352 @@
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command as TokioCommand;
+use tokio::sync::Mutex;
+use crate::models::{ProcessInfo, ProcessStatus};
+use crate::process::{add_output_line, ProcessHandle};
+use crate::AppState;
+
+// Tunnels expose the proxy through cloudflared or tailscale without opening router
+// ports. Each tunnel runs as a managed child process, so it shows up in the process
+// list and is cleaned up with the other children on exit. Tunnel traffic reaches the
+// proxy from loopback, so while one runs `proxy_guard` takes the client from the
+// forwarding header of that provider, and a tunnel only starts once API keys are
+// configured. Tunneled clients are not held to the network allowlist; their key is.
+
+/// Tunnels whose process is still running, by provider
+static LIVE: LazyLock<std::sync::Mutex<HashMap<String, TunnelProvider>>> = LazyLock::new(Default::default);
+
+fn live() -> std::sync::MutexGuard<'static, HashMap<String, TunnelProvider>> {
+    LIVE.lock().unwrap_or_else(|p| p.into_inner())
+}
+
+/// Providers of the tunnels that may be forwarding requests to the proxy
+pub fn forwarding_providers() -> Vec<TunnelProvider> {
+    let mut providers: Vec<TunnelProvider> = live().values().cloned().collect();
+    providers.dedup();
+    providers
+}
+
+static TUNNEL_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"https://[A-Za-z0-9.-]+\.(?:trycloudflare\.com|ts\.net)\S*")
+        .expect("tunnel URL regex should compile")
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TunnelProvider {
+    Cloudflared,
+    Tailscale,
+}
+
+impl TunnelProvider {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_lowercase().as_str() {
+            "cloudflared" | "cloudflare" => Ok(Self::Cloudflared),
+            "tailscale" => Ok(Self::Tailscale),
+            other => Err(format!("Unsupported tunnel provider: {}", other)),
+        }
+    }
+
+    fn default_executable(&self) -> &'static str {
+        match self {
+            Self::Cloudflared => "cloudflared",
+            Self::Tailscale => "tailscale",
+        }
+    }
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            Self::Cloudflared => "Cloudflare Tunnel",
+            Self::Tailscale => "Tailscale Serve",
+        }
+    }
+
+    fn args(&self, port: u16) -> Vec<String> {
+        match self {
+            Self::Cloudflared => vec![
+                "tunnel".to_string(),
+                "--no-autoupdate".to_string(),
+                "--url".to_string(),
+                format!("http://127.0.0.1:{}", port),
+            ],
+            Self::Tailscale => vec!["serve".to_string(), port.to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TunnelStatus {
+    Starting,
+    Running,
+    Stopped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TunnelInfo {
+    /// Same id as the entry in `running_processes`
+    pub id: String,
+    pub provider: TunnelProvider,
+    pub executable: String,
+    pub target_port: u16,
+    pub public_url: Option<String>,
+    pub status: TunnelStatus,
+    pub started_at: DateTime<Utc>,
+    pub error: Option<String>,
+}
+
+pub fn extract_tunnel_url(line: &str) -> Option<String> {
+    TUNNEL_URL_RE
+        .find(line)
+        .map(|m| m.as_str().trim_end_matches(['|', ',', ')']).to_string())
+}
+
+pub async fn start_tunnel(
+    provider: TunnelProvider,
+    executable: Option<String>,
+    target_port: u16,
+    state: &AppState,
+) -> Result<TunnelInfo, String> {
+    {
+        let tunnels = state.tunnels.lock().await;
+        if tunnels
+            .values()
+            .any(|t| t.provider == provider && matches!(t.status, TunnelStatus::Starting | TunnelStatus::Running))
+        {
+            return Err(format!("A {} is already running", provider.display_name()));
+        }
+    }
+    if state.config.lock().await.network_api_keys.is_empty() {
+        return Err("Create an API key for the network server before starting a tunnel; without one anyone who finds the tunnel URL can use it".to_string());
+    }
+
+    let executable = executable
+        .map(|e| e.trim().to_string())
+        .filter(|e| !e.is_empty())
+        .unwrap_or_else(|| provider.default_executable().to_string());
+    let args = provider.args(target_port);
+
+    let mut cmd = TokioCommand::new(&executable);
+    cmd.args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {} ({}): {}", provider.display_name(), executable, e))?;
+    let stdout = child.stdout.take().ok_or("Failed to get stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to get stderr")?;
+
+    let tunnel_id = format!("tunnel-{}", uuid::Uuid::new_v4());
+    let mut command = vec![executable.clone()];
+    command.extend(args);
+
+    let info = TunnelInfo {
+        id: tunnel_id.clone(),
+        provider: provider.clone(),
+        executable,
+        target_port,
+        public_url: None,
+        status: TunnelStatus::Starting,
+        started_at: Utc::now(),
+        error: None,
+    };
+
+    {
+        let mut processes = state.running_processes.lock().await;
+        processes.insert(
+            tunnel_id.clone(),
+            ProcessInfo {
+                id: tunnel_id.clone(),
+                model_path: format!("tunnel:{}", provider.default_executable()),
+                model_name: provider.display_name().to_string(),
+                host: "127.0.0.1".to_string(),
+                port: target_port,
+                command,
+                status: ProcessStatus::Starting,
+                output: Vec::new(),
+                created_at: Utc::now(),
+                last_sent_line: Some(0),
+                preset_id: None,
+                instance: 0,
+                restart_count: 0,
+            },
+        );
+    }
+    live().insert(tunnel_id.clone(), provider.clone());
+    let handle = Arc::new(Mutex::new(ProcessHandle::new(child, tunnel_id.clone())));
+    state
+        .child_processes
+        .lock()
+        .await
+        .insert(tunnel_id.clone(), handle.clone());
+    state.tunnels.lock().await.insert(tunnel_id.clone(), info.clone());
+
+    let state_clone = state.clone();
+    tokio::spawn(async move {
+        supervise_tunnel(state_clone, tunnel_id, handle, stdout, stderr).await;
+    });
+
+    Ok(info)
+}
+
+async fn record_tunnel_line(state: &AppState, tunnel_id: &str, line: String) {
+    if let Some(url) = extract_tunnel_url(&line) {
+        let mut tunnels = state.tunnels.lock().await;
+        if let Some(tunnel) = tunnels.get_mut(tunnel_id) {
+            if tunnel.public_url.is_none() {
+                println!("[Tunnel] {} available at {}", tunnel.provider.display_name(), url);
+                tunnel.public_url = Some(url);
+                tunnel.status = TunnelStatus::Running;
+            }
+        }
+    }
+    add_output_line(state, tunnel_id, line).await;
+}
+
+async fn supervise_tunnel(
+    state: AppState,
+    tunnel_id: String,
+    handle: Arc<Mutex<ProcessHandle>>,
+    stdout: tokio::process::ChildStdout,
+    stderr: tokio::process::ChildStderr,
+) {
+    {
+        let mut processes = state.running_processes.lock().await;
+        if let Some(process) = processes.get_mut(&tunnel_id) {
+            process.status = ProcessStatus::Running;
+        }
+    }
+
+    let mut stdout_lines = BufReader::new(stdout).lines();
+    let mut stderr_lines = BufReader::new(stderr).lines();
+    let mut stdout_open = true;
+    let mut stderr_open = true;
+
+    while stdout_open || stderr_open {
+        tokio::select! {
+            line = stdout_lines.next_line(), if stdout_open => match line {
+                Ok(Some(line)) => record_tunnel_line(&state, &tunnel_id, format!("[OUT] {}", line)).await,
+                _ => stdout_open = false,
+            },
+            line = stderr_lines.next_line(), if stderr_open => match line {
+                Ok(Some(line)) => record_tunnel_line(&state, &tunnel_id, format!("[INFO] {}", line)).await,
+                _ => stderr_open = false,
+            },
+        }
+    }
+
+    let exit_code = {
+        let mut guard = handle.lock().await;
+        match guard.take_child() {
+            Some(mut child) => child.wait().await.ok().and_then(|s| s.code()).unwrap_or(-1),
+            None => -1,
+        }
+    };
+    live().remove(&tunnel_id);
+
+    {
+        let mut tunnels = state.tunnels.lock().await;
+        if let Some(tunnel) = tunnels.get_mut(&tunnel_id) {
+            if tunnel.status != TunnelStatus::Stopped {
+                if exit_code == 0 {
+                    tunnel.status = TunnelStatus::Stopped;
+                } else {
+                    tunnel.status = TunnelStatus::Failed;
+                    tunnel.error = Some(format!("Tunnel exited with code: {}", exit_code));
+                }
+            }
+        }
+    }
+    {
+        let mut processes = state.running_processes.lock().await;
+        if let Some(process) = processes.get_mut(&tunnel_id) {
+            process.status = ProcessStatus::Stopped;
+            process.output.push(format!("Process exited with code: {}", exit_code));
+        }
+    }
+    state.child_processes.lock().await.remove(&tunnel_id);
+    println!("[Tunnel] {} exited with code {}", tunnel_id, exit_code);
+}
+
+pub async fn stop_tunnel(tunnel_id: &str, state: &AppState) -> Result<TunnelInfo, String> {
+    let tunnel = {
+        let mut tunnels = state.tunnels.lock().await;
+        let tunnel = tunnels
+            .get_mut(tunnel_id)
+            .ok_or_else(|| "Tunnel not found".to_string())?;
+        tunnel.status = TunnelStatus::Stopped;
+        tunnel.clone()
+    };
+
+    crate::process::terminate_process(tunnel_id.to_string(), state)
+        .await
+        .map_err(|e| format!("Failed to stop tunnel: {}", e))?;
+
+    // Killing the foreground `tailscale serve` can leave the serve config behind
+    if tunnel.provider == TunnelProvider::Tailscale {
+        let _ = TokioCommand::new(&tunnel.executable)
+            .args(["serve", "reset"])
+            .output()
+            .await;
+    }
+
+    Ok(tunnel)
+}
+
+pub async fn stop_all_tunnels(state: &AppState) {
+    let ids: Vec<String> = {
+        let tunnels = state.tunnels.lock().await;
+        tunnels
+            .values()
+            .filter(|t| matches!(t.status, TunnelStatus::Starting | TunnelStatus::Running))
+            .map(|t| t.id.clone())
+            .collect()
+    };
+    for id in ids {
+        if let Err(e) = stop_tunnel(&id, state).await {
+            eprintln!("[Tunnel] Failed to stop {}: {}", id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_tunnel_url_finds_cloudflare_and_tailscale_urls() {
+        let cloudflare = "2024-01-01T00:00:00Z INF |  https://quiet-river-1234.trycloudflare.com                      |";
+        assert_eq!(
+            extract_tunnel_url(cloudflare).as_deref(),
+            Some("https://quiet-river-1234.trycloudflare.com")
+        );
+
+        let tailscale = "Available within your tailnet: https://desktop.tail1234.ts.net/";
+        assert_eq!(
+            extract_tunnel_url(tailscale).as_deref(),
+            Some("https://desktop.tail1234.ts.net/")
+        );
+
+        assert!(extract_tunnel_url("INF Requesting new quick Tunnel on trycloudflare.com...").is_none());
+    }
+
+    #[test]
+    fn tunnel_provider_parse_accepts_aliases() {
+        assert_eq!(TunnelProvider::parse("Cloudflare").unwrap(), TunnelProvider::Cloudflared);
+        assert_eq!(TunnelProvider::parse("tailscale").unwrap(), TunnelProvider::Tailscale);
+        assert!(TunnelProvider::parse("ngrok").is_err());
+    }
+}
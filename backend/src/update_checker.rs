@@ -66,8 +66,7 @@ pub async fn check_huggingface_updates(
         model_id
     );
     
-    let client = reqwest::Client::new();
-    let response = match client.get(&api_url).send().await {
+    let response = match crate::hf_client::get(&api_url).await {
         Ok(resp) => resp,
         Err(e) => {
             return UpdateCheckResult {
@@ -88,7 +87,7 @@ pub async fn check_huggingface_updates(
         };
     }
     
-    let files: serde_json::Value = match response.json().await {
+    let files: serde_json::Value = match response.json() {
         Ok(data) => data,
         Err(e) => {
             return UpdateCheckResult {
@@ -218,6 +217,6 @@ mod tests {
         let metadata = result.unwrap();
         assert_eq!(metadata.model_id, "THUDM/glm-4-9b-chat");
         assert_eq!(metadata.filename, "model-Q4_K_M.gguf");
-        assert!(metadata.linked_at.len() > 0);
+        assert!(!metadata.linked_at.is_empty());
     }
 }
@@ -0,0 +1,523 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::scanner::split_shard;
+
+// VRAM estimates shared by the tracker, Hugging Face search results and launch planning.
+// The total is weights + KV cache + compute buffers + a fixed runtime allowance. Local
+// files use their real size and the layer/head shape from the GGUF header; remote models
+// fall back to the parameter count in their name, a quant's bits per weight and a
+// typical shape for that size. Every guess is listed in `assumptions`.
+
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+/// CUDA/Vulkan context, scratch allocations and driver bookkeeping
+const RUNTIME_OVERHEAD_GB: f64 = 0.35;
+/// llama.cpp's default micro-batch; compute buffers scale with it
+const UBATCH: f64 = 512.0;
+/// VRAM left free when suggesting GPU layers, for other apps and estimate error
+const VRAM_HEADROOM_GB: f64 = 0.8;
+pub const DEFAULT_CONTEXT: u32 = 4096;
+pub const DEFAULT_QUANT: &str = "Q4_K_M";
+const DEFAULT_VOCAB: u64 = 32_000;
+
+/// Attention shape the KV cache and compute buffers depend on
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ModelShape {
+    pub block_count: u64,
+    pub embedding_length: u64,
+    pub head_count: u64,
+    pub head_count_kv: u64,
+    /// Per-head K/V width when the model declares one (otherwise embedding / heads)
+    pub key_length: Option<u64>,
+    pub value_length: Option<u64>,
+    pub vocab_size: u64,
+    pub context_length: Option<u64>,
+}
+
+impl ModelShape {
+    fn kv_widths(&self) -> (f64, f64) {
+        let head_dim = self.embedding_length / self.head_count.max(1);
+        let heads = self.head_count_kv.max(1) as f64;
+        (
+            heads * self.key_length.unwrap_or(head_dim) as f64,
+            heads * self.value_length.unwrap_or(head_dim) as f64,
+        )
+    }
+}
+
+/// What the launch (or the question) asks for
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstimateOptions {
+    /// 0 means the model's trained context
+    pub context_length: u32,
+    pub cache_type_k: String,
+    pub cache_type_v: String,
+    /// Layers offloaded to the GPU; None offloads all of them
+    pub gpu_layers: Option<u64>,
+    pub flash_attention: bool,
+}
+
+impl Default for EstimateOptions {
+    fn default() -> Self {
+        Self {
+            context_length: DEFAULT_CONTEXT,
+            cache_type_k: "f16".to_string(),
+            cache_type_v: "f16".to_string(),
+            gpu_layers: None,
+            flash_attention: false,
+        }
+    }
+}
+
+impl EstimateOptions {
+    /// Read the options that change VRAM use from llama-server arguments
+    pub fn from_args(custom_args: &str) -> Self {
+        let mut options = Self::default();
+        let tokens: Vec<&str> = custom_args.split_whitespace().collect();
+        let mut index = 0;
+        while index < tokens.len() {
+            let (flag, inline) = match tokens[index].split_once('=') {
+                Some((flag, value)) if flag.starts_with('-') => (flag, Some(value)),
+                _ => (tokens[index], None),
+            };
+            let value = inline.or_else(|| tokens.get(index + 1).copied().filter(|v| !v.starts_with('-')));
+            let mut consumed = inline.is_none() && value.is_some();
+            match flag {
+                "-c" | "--ctx-size" => {
+                    if let Some(ctx) = value.and_then(|v| v.parse().ok()) {
+                        options.context_length = ctx;
+                    }
+                }
+                "-ctk" | "--cache-type-k" => {
+                    if let Some(kind) = value {
+                        options.cache_type_k = kind.to_lowercase();
+                    }
+                }
+                "-ctv" | "--cache-type-v" => {
+                    if let Some(kind) = value {
+                        options.cache_type_v = kind.to_lowercase();
+                    }
+                }
+                "-ngl" | "--n-gpu-layers" | "--gpu-layers" => {
+                    options.gpu_layers = value.and_then(|v| v.parse().ok());
+                }
+                "-fa" | "--flash-attn" => {
+                    // A bare switch in older builds, `on|off|auto` in newer ones
+                    let setting = value.filter(|v| matches!(*v, "on" | "off" | "auto" | "1" | "0" | "true" | "false"));
+                    consumed &= setting.is_some();
+                    options.flash_attention = !matches!(setting, Some("off" | "0" | "false"));
+                }
+                _ => {}
+            }
+            index += if consumed { 2 } else { 1 };
+        }
+        options
+    }
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct VramEstimate {
+    pub weights_gb: f64,
+    pub kv_cache_gb: f64,
+    pub compute_buffer_gb: f64,
+    pub overhead_gb: f64,
+    pub total_gb: f64,
+    pub context_length: u32,
+    pub offloaded_layers: Option<u64>,
+    /// Guesses that went into the numbers, shown next to the estimate
+    pub assumptions: Vec<String>,
+}
+
+/// Average bits per weight of llama.cpp quant types, including their block scales.
+/// Tracker-style coarse names (`Q4`, `IQ4`) map to their most common variant.
+pub fn bits_per_weight(quant: &str) -> Option<f64> {
+    let bits = match quant.trim().to_uppercase().replace('-', "_").as_str() {
+        "F32" | "FP32" => 32.0,
+        "F16" | "FP16" | "BF16" => 16.0,
+        "Q8_0" | "Q8" => 8.5,
+        "Q6_K" | "Q6" => 6.56,
+        "Q5_K_M" | "Q5_K" | "Q5" => 5.69,
+        "Q5_K_S" => 5.54,
+        "Q5_1" => 6.0,
+        "Q5_0" => 5.5,
+        "Q4_K_M" | "Q4_K" | "Q4" => 4.85,
+        "Q4_K_S" => 4.58,
+        "Q4_1" => 5.0,
+        "Q4_0" => 4.5,
+        "IQ4_NL" => 4.5,
+        "IQ4_XS" | "IQ4" => 4.25,
+        "Q3_K_L" => 4.27,
+        "Q3_K_M" | "Q3_K" | "Q3" => 3.91,
+        "Q3_K_S" => 3.5,
+        "IQ3_M" => 3.66,
+        "IQ3_S" => 3.44,
+        "IQ3_XS" | "IQ3" => 3.3,
+        "IQ3_XXS" => 3.06,
+        "Q2_K" | "Q2" => 3.35,
+        "Q2_K_S" => 2.97,
+        "IQ2_M" => 2.7,
+        "IQ2_S" => 2.5,
+        "IQ2_XS" | "IQ2" => 2.31,
+        "IQ2_XXS" => 2.06,
+        "IQ1_M" => 1.75,
+        "IQ1_S" | "IQ1" => 1.56,
+        "TQ1_0" => 1.69,
+        "TQ2_0" => 2.06,
+        _ => return None,
+    };
+    Some(bits)
+}
+
+/// Bytes per cached K or V element for `--cache-type-k/v`
+pub fn kv_bytes_per_element(cache_type: &str) -> Option<f64> {
+    let bytes = match cache_type.trim().to_lowercase().as_str() {
+        "f32" => 4.0,
+        "f16" | "bf16" => 2.0,
+        "q8_0" => 34.0 / 32.0,
+        "q5_1" => 24.0 / 32.0,
+        "q5_0" => 22.0 / 32.0,
+        "q4_1" => 20.0 / 32.0,
+        "q4_0" | "iq4_nl" => 18.0 / 32.0,
+        _ => return None,
+    };
+    Some(bytes)
+}
+
+/// Quant type named in a GGUF file name or folder, e.g. `Q4_K_M` from `model-q4_k_m.gguf`
+pub fn quant_from_name(file_name: &str) -> Option<String> {
+    let pattern = regex::Regex::new(r"(?i)(?:^|[-_./])((?:IQ|TQ|Q)\d_[A-Z0-9]+(?:_[A-Z]+)?|Q\d|BF16|F16|F32)(?:[-_./]|$)")
+        .expect("valid quant pattern");
+    pattern
+        .captures(file_name)
+        .map(|c| c[1].to_uppercase())
+        .filter(|quant| bits_per_weight(quant).is_some())
+}
+
+/// Parameter count (billions) from a name like `Llama-3.1-8B` or `Mixtral-8x7B`, plus the
+/// per-expert size MoE models share their attention shape with
+pub fn params_from_name(name: &str) -> Option<(f64, f64)> {
+    let pattern = regex::Regex::new(r"(?i)(?:^|[^a-z0-9.])(?:(\d+)x)?(\d+(?:\.\d+)?)([bm])(?:[^a-z0-9]|$)")
+        .expect("valid size pattern");
+    let captures = pattern.captures(name)?;
+    let mut size: f64 = captures[2].parse().ok()?;
+    if captures[3].eq_ignore_ascii_case("m") {
+        size /= 1000.0;
+    }
+    let experts: f64 = captures.get(1).and_then(|e| e.as_str().parse().ok()).unwrap_or(1.0);
+    (size > 0.0).then_some((size * experts, size))
+}
+
+/// Typical Llama-style shape for a model of this size (billions), used when no header is
+/// available. GQA with 8 KV heads is the norm for current models.
+pub fn typical_shape(parameters_b: f64) -> ModelShape {
+    const SHAPES: [(f64, u64, u64, u64, u64); 8] = [
+        (0.6, 28, 1024, 16, 8),
+        (1.5, 28, 1536, 12, 2),
+        (3.5, 28, 3072, 24, 8),
+        (9.0, 32, 4096, 32, 8),
+        (15.0, 48, 5120, 40, 8),
+        (34.0, 64, 5120, 40, 8),
+        (80.0, 80, 8192, 64, 8),
+        (f64::MAX, 126, 16384, 128, 8),
+    ];
+    let (_, block_count, embedding_length, head_count, head_count_kv) = SHAPES
+        .iter()
+        .copied()
+        .find(|(max, ..)| parameters_b <= *max)
+        .unwrap_or(SHAPES[SHAPES.len() - 1]);
+    ModelShape {
+        block_count,
+        embedding_length,
+        head_count,
+        head_count_kv,
+        vocab_size: 128_000,
+        ..Default::default()
+    }
+}
+
+/// Layer and head counts from a GGUF header; None for non-GGUF or unreadable files
+pub fn read_shape(path: &Path) -> Option<ModelShape> {
+    let header = crate::gguf_parser::read_file_header(path)?;
+    if !header.complete {
+        return None;
+    }
+    let get = |suffix: &str| header.arch_int(suffix);
+    let head_count = get("attention.head_count")?;
+    Some(ModelShape {
+        block_count: get("block_count")?,
+        embedding_length: get("embedding_length")?,
+        head_count,
+        head_count_kv: get("attention.head_count_kv").unwrap_or(head_count),
+        key_length: get("attention.key_length"),
+        value_length: get("attention.value_length"),
+        vocab_size: get("vocab_size")
+            .or_else(|| header.array_len("tokenizer.ggml.tokens"))
+            .unwrap_or(DEFAULT_VOCAB),
+        context_length: get("context_length"),
+    })
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// Combine weights and shape into an estimate for the requested context and cache types
+pub fn estimate(weights_bytes: f64, shape: &ModelShape, options: &EstimateOptions, mut assumptions: Vec<String>) -> VramEstimate {
+    let mut context = options.context_length as u64;
+    if context == 0 {
+        context = shape.context_length.unwrap_or(DEFAULT_CONTEXT as u64);
+    }
+
+    // Partial offload keeps that share of layers (and their KV cache) on the CPU
+    let layers = shape.block_count.max(1);
+    let offloaded = options.gpu_layers.map(|n| n.min(layers));
+    let gpu_share = offloaded.map(|n| n as f64 / layers as f64).unwrap_or(1.0);
+
+    let bytes_k = kv_bytes_per_element(&options.cache_type_k).unwrap_or_else(|| {
+        assumptions.push(format!("Unknown K cache type '{}', counted as f16", options.cache_type_k));
+        2.0
+    });
+    let bytes_v = kv_bytes_per_element(&options.cache_type_v).unwrap_or_else(|| {
+        assumptions.push(format!("Unknown V cache type '{}', counted as f16", options.cache_type_v));
+        2.0
+    });
+    let (width_k, width_v) = shape.kv_widths();
+    let kv_bytes = layers as f64 * gpu_share * context as f64 * (width_k * bytes_k + width_v * bytes_v);
+
+    // Activations and logits for one micro-batch; without flash attention the score
+    // matrix for the whole context is materialized too
+    let ubatch = UBATCH.min(context as f64);
+    let mut compute_bytes = ubatch * (shape.embedding_length as f64 * 16.0 + shape.vocab_size as f64 * 4.0);
+    if !options.flash_attention {
+        compute_bytes += ubatch * context as f64 * shape.head_count as f64 * 4.0;
+    }
+    let cpu_only = offloaded == Some(0);
+    if cpu_only {
+        compute_bytes = 0.0;
+    }
+
+    let weights_gb = weights_bytes * gpu_share / GIB;
+    let kv_cache_gb = kv_bytes / GIB;
+    let compute_buffer_gb = compute_bytes / GIB;
+    let overhead_gb = if cpu_only { 0.0 } else { RUNTIME_OVERHEAD_GB };
+    VramEstimate {
+        weights_gb: round2(weights_gb),
+        kv_cache_gb: round2(kv_cache_gb),
+        compute_buffer_gb: round2(compute_buffer_gb),
+        overhead_gb,
+        total_gb: round2(weights_gb + kv_cache_gb + compute_buffer_gb + overhead_gb),
+        context_length: context as u32,
+        offloaded_layers: offloaded,
+        assumptions,
+    }
+}
+
+/// Weights size of a local model with every split shard, and its shape from the GGUF header
+fn local_model(model_path: &Path) -> Option<(f64, ModelShape, Vec<String>)> {
+    let name = model_path.file_name()?.to_str()?.to_lowercase();
+    let shards: Vec<std::path::PathBuf> = match (split_shard(&name), model_path.parent()) {
+        (Some((base, _)), Some(dir)) => fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let other = path.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_lowercase();
+                split_shard(&other).is_some_and(|(other_base, _)| other_base == base)
+            })
+            .collect(),
+        _ => vec![model_path.to_path_buf()],
+    };
+    let weights_bytes: u64 = shards.iter().filter_map(|path| fs::metadata(path).ok()).map(|m| m.len()).sum();
+    if weights_bytes == 0 {
+        return None;
+    }
+
+    let mut assumptions = Vec::new();
+    let shape = read_shape(model_path).unwrap_or_else(|| {
+        let parameters = params_from_name(&name).map(|(_, shape)| shape).unwrap_or_else(|| {
+            // Q4_K_M-sized guess from the file size alone
+            weights_bytes as f64 * 8.0 / bits_per_weight(DEFAULT_QUANT).unwrap_or(4.85) / 1e9
+        });
+        assumptions.push(format!("GGUF header unreadable; layer shape assumed for a {:.1}B model", parameters));
+        typical_shape(parameters)
+    });
+    Some((weights_bytes as f64, shape, assumptions))
+}
+
+/// Estimate for a local model, counting every split shard and reading its GGUF header
+pub fn estimate_file(model_path: &Path, options: &EstimateOptions) -> Option<VramEstimate> {
+    let (weights_bytes, shape, assumptions) = local_model(model_path)?;
+    Some(estimate(weights_bytes, &shape, options, assumptions))
+}
+
+/// Recommended `--n-gpu-layers` for the free VRAM, with the numbers behind it
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GpuLayerSuggestion {
+    pub gpu_layers: u64,
+    /// Repeating layers of the model; one more also offloads the output layer
+    pub total_layers: u64,
+    pub free_vram_gb: f64,
+    pub estimate: VramEstimate,
+    pub reasoning: Vec<String>,
+}
+
+/// Most layers whose estimate leaves `VRAM_HEADROOM_GB` of `free_vram_gb` unused
+pub fn suggest_gpu_layers(weights_bytes: f64, shape: &ModelShape, options: &EstimateOptions, free_vram_gb: f64, assumptions: Vec<String>) -> GpuLayerSuggestion {
+    let layers = shape.block_count.max(1);
+    let budget = free_vram_gb - VRAM_HEADROOM_GB;
+    let at = |n: u64| estimate(weights_bytes, shape, &EstimateOptions { gpu_layers: Some(n), ..options.clone() }, assumptions.clone());
+    let mut reasoning = vec![format!(
+        "{:.1} GB VRAM free, {:.1} GB kept as headroom; {} context",
+        free_vram_gb,
+        VRAM_HEADROOM_GB,
+        at(0).context_length
+    )];
+
+    let full = at(layers);
+    if full.total_gb <= budget {
+        reasoning.push(format!("All {} layers and the output layer fit in ~{:.1} GB", layers, full.total_gb));
+        return GpuLayerSuggestion { gpu_layers: layers + 1, total_layers: layers, free_vram_gb, estimate: full, reasoning };
+    }
+    // The estimate grows with every offloaded layer
+    let (mut low, mut high) = (0, layers);
+    while low < high {
+        let mid = (low + high).div_ceil(2);
+        if at(mid).total_gb <= budget {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    let chosen = at(low);
+    reasoning.push(format!("All {} layers need ~{:.1} GB", layers, full.total_gb));
+    if low == 0 {
+        reasoning.push("Not even one layer fits; run on the CPU or lower the context".to_string());
+    } else {
+        reasoning.push(format!("{} of {} layers fit in ~{:.1} GB; the rest run on the CPU", low, layers, chosen.total_gb));
+    }
+    GpuLayerSuggestion { gpu_layers: low, total_layers: layers, free_vram_gb, estimate: chosen, reasoning }
+}
+
+/// `suggest_gpu_layers` for a local model launched with `custom_args`
+pub fn suggest_for_file(model_path: &Path, custom_args: &str, free_vram_gb: f64) -> Option<GpuLayerSuggestion> {
+    let (weights_bytes, shape, assumptions) = local_model(model_path)?;
+    let mut suggestion = suggest_gpu_layers(weights_bytes, &shape, &EstimateOptions::from_args(custom_args), free_vram_gb, assumptions);
+    suggestion.reasoning.extend(suggestion.estimate.assumptions.iter().cloned());
+    Some(suggestion)
+}
+
+/// Estimate for a model that is not downloaded: weights from the file size when known,
+/// otherwise parameters x bits per weight of `quant`. The parameter count comes from the
+/// argument, the name, or failing both the file size.
+pub fn estimate_remote(
+    name: &str,
+    parameters_b: Option<f64>,
+    quant: Option<&str>,
+    file_bytes: Option<u64>,
+    options: &EstimateOptions,
+) -> Option<VramEstimate> {
+    let mut assumptions = Vec::new();
+    let quant = quant.unwrap_or(DEFAULT_QUANT);
+    let bits = bits_per_weight(quant).unwrap_or_else(|| {
+        assumptions.push(format!("Unknown quantization '{}', sized as {}", quant, DEFAULT_QUANT));
+        bits_per_weight(DEFAULT_QUANT).unwrap_or(4.85)
+    });
+    let file_bytes = file_bytes.filter(|bytes| *bytes > 0);
+    let (total_b, shape_b) = match (parameters_b, params_from_name(name), file_bytes) {
+        (Some(parameters), _, _) => (parameters, parameters),
+        (None, Some(from_name), _) => from_name,
+        (None, None, Some(bytes)) => {
+            let parameters = bytes as f64 * 8.0 / bits / 1e9;
+            (parameters, parameters)
+        }
+        (None, None, None) => return None,
+    };
+    let weights_bytes = match file_bytes {
+        Some(bytes) => bytes as f64,
+        None => {
+            assumptions.push(format!("Weights from {:.1}B parameters at {} ({} bits/weight)", total_b, quant, bits));
+            total_b * 1e9 * bits / 8.0
+        }
+    };
+    assumptions.push(format!("Layer shape assumed for a typical {:.1}B model", shape_b));
+    Some(estimate(weights_bytes, &typical_shape(shape_b), options, assumptions))
+}
+
+/// The GGUF a repo would typically be run with (Q4_K_M, else the smallest model file) and
+/// its size with all split shards
+pub fn pick_remote_file(files: &[(String, u64)]) -> Option<(String, u64)> {
+    let mut totals: HashMap<String, u64> = HashMap::new();
+    for (file, size) in files {
+        let lower = file.to_lowercase();
+        let name = lower.rsplit('/').next().unwrap_or_default();
+        if !name.ends_with(".gguf") || name.starts_with("mmproj") || name.contains("lora") {
+            continue;
+        }
+        let key = split_shard(&lower).map(|(base, _)| base.to_string()).unwrap_or_else(|| lower.clone());
+        *totals.entry(key).or_insert(0) += size;
+    }
+    let mut candidates: Vec<(String, u64)> = totals.into_iter().collect();
+    candidates.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    candidates
+        .iter()
+        .find(|(name, _)| quant_from_name(name).as_deref() == Some(DEFAULT_QUANT))
+        .or_else(|| candidates.first())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_follow_quant_context_and_cache_type() {
+        assert_eq!(params_from_name("meta-llama/Llama-3.1-8B-Instruct-GGUF"), Some((8.0, 8.0)));
+        assert_eq!(params_from_name("Mixtral-8x7B-v0.1"), Some((56.0, 7.0)));
+        assert_eq!(params_from_name("Qwen3-30B-A3B-GGUF").map(|p| p.0), Some(30.0));
+        assert_eq!(params_from_name("SmolLM2-360M").map(|p| p.0), Some(0.36));
+        assert_eq!(quant_from_name("qwen2.5-7b-instruct-q4_k_m.gguf").as_deref(), Some("Q4_K_M"));
+        assert_eq!(quant_from_name("model-IQ4_XS-00001-of-00002.gguf").as_deref(), Some("IQ4_XS"));
+
+        // Llama 3 8B at Q4_K_M, 8K context: ~4.5 GB weights + 1 GB f16 KV cache
+        let llama = typical_shape(8.0);
+        let options = EstimateOptions::from_args("-c 8192 -ngl 99");
+        let base = estimate_remote("Llama-3-8B", None, Some("Q4_K_M"), None, &options).unwrap();
+        assert!((base.weights_gb - 4.52).abs() < 0.05, "{:?}", base);
+        assert!((base.kv_cache_gb - 1.0).abs() < 0.01, "{:?}", base);
+        assert_eq!(base.offloaded_layers, Some(llama.block_count));
+
+        let quantized_cache = estimate(4.85e9, &llama, &EstimateOptions::from_args("-c 8192 -ctk q8_0 -ctv q8_0 -fa on"), Vec::new());
+        assert!((quantized_cache.kv_cache_gb - 0.53).abs() < 0.01);
+        assert!(quantized_cache.compute_buffer_gb < base.compute_buffer_gb);
+
+        let half = estimate(4.85e9, &llama, &EstimateOptions::from_args("--n-gpu-layers=16"), Vec::new());
+        assert!((half.weights_gb - 4.85e9 / 2.0 / GIB).abs() < 0.01);
+
+        let files = vec![
+            ("m-Q8_0.gguf".to_string(), 8_000),
+            ("m-Q2_K.gguf".to_string(), 3_000),
+            ("mmproj-f16.gguf".to_string(), 600),
+            ("Q4_K_M/m-Q4_K_M-00001-of-00002.gguf".to_string(), 2_000),
+            ("Q4_K_M/m-Q4_K_M-00002-of-00002.gguf".to_string(), 2_500),
+        ];
+        assert_eq!(pick_remote_file(&files), Some(("q4_k_m/m-q4_k_m".to_string(), 4_500)));
+    }
+
+    #[test]
+    fn gpu_layer_suggestion_fits_the_free_vram() {
+        let llama = typical_shape(8.0);
+        let options = EstimateOptions::from_args("-c 8192");
+        let roomy = suggest_gpu_layers(4.85e9, &llama, &options, 24.0, Vec::new());
+        assert_eq!(roomy.gpu_layers, llama.block_count + 1);
+
+        let tight = suggest_gpu_layers(4.85e9, &llama, &options, 4.0, Vec::new());
+        assert!(tight.gpu_layers > 0 && tight.gpu_layers < llama.block_count, "{:?}", tight);
+        assert!(tight.estimate.total_gb <= 4.0 - VRAM_HEADROOM_GB);
+        let one_more = estimate(4.85e9, &llama, &EstimateOptions { gpu_layers: Some(tight.gpu_layers + 1), ..options.clone() }, Vec::new());
+        assert!(one_more.total_gb > 4.0 - VRAM_HEADROOM_GB);
+
+        assert_eq!(suggest_gpu_layers(4.85e9, &llama, &options, 0.5, Vec::new()).gpu_layers, 0);
+    }
+}
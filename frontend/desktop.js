@@ -105,6 +105,11 @@ class DesktopManager {
         this.initMcpManager();
         this.initDiscovery();
         this.initDiscoveryDebugButton();
+        this.initLaunchQueueListener();
+        this.initMcpOauthListener();
+        this.initMcpStatusListener();
+        this.initModelReadyListener();
+        this.initExternalModelDrop();
 
         // Wait for DOM to be fully loaded before showing content
         if (document.readyState === 'loading') {
@@ -324,6 +329,28 @@ class DesktopManager {
         setTimeout(() => {
             this.updateCustomArgsIndicators();
         }, 500);
+
+        // Surface degraded states from the startup self-test
+        setTimeout(() => this.checkAppHealth(), 3000);
+    }
+
+    async checkAppHealth(retries = 3) {
+        try {
+            const health = await invoke('get_app_health');
+            if (health.checking && retries > 0) {
+                setTimeout(() => this.checkAppHealth(retries - 1), 3000);
+                return;
+            }
+            this.appHealth = health;
+            const problems = (health.checks || []).filter((check) => check.status !== 'ok');
+            if (problems.length === 0) return;
+            problems.forEach((check) => console.warn(`[Health] ${check.id}: ${check.message}`));
+            const worst = problems.find((check) => check.status === 'error') || problems[0];
+            const more = problems.length > 1 ? ` (+${problems.length - 1} more)` : '';
+            this.showNotification(`${worst.message}${more}`, worst.status === 'error' ? 'error' : 'info');
+        } catch (error) {
+            console.error('Failed to read app health:', error);
+        }
     }
 
     initViewToggle() {
@@ -723,6 +750,11 @@ class DesktopManager {
         if (execFolder && config.executable_folder) {
             execFolder.value = config.executable_folder;
         }
+        const externalTerminal = document.getElementById('external-terminal');
+        if (externalTerminal) {
+            externalTerminal.value = config.external_terminal || '';
+            this.loadTerminalOptions();
+        }
         if (themeColor && config.theme_color) {
             themeColor.value = config.theme_color;
         }
@@ -2753,7 +2785,7 @@ async ensureTerminalManager() {
         }
 
         // Check if there's already a terminal for this model
-        const existingTerminal = terminalManager.getExistingTerminal ? terminalManager.getExistingTerminal(modelPath) : null;
+        const existingTerminal = terminalManager.getExistingTerminal ? terminalManager.getExistingTerminal(modelPath, null) : null;
 
         if (existingTerminal) {
             const [windowId] = existingTerminal;
@@ -2781,6 +2813,7 @@ async ensureTerminalManager() {
 
             if (result.success) {
                 this.setLastUsedModel({ modelPath, modelName, launchMode: 'default', presetId: null });
+                this.notifyStoppedForExclusive(result);
                 console.log('Opening server terminal...');
                 console.log('Terminal manager methods:', Object.getOwnPropertyNames(terminalManager.__proto__));
 
@@ -2814,7 +2847,9 @@ async ensureTerminalManager() {
                     result.server_host,
                     result.server_port,
                     modelPath,
-                    activeVersion
+                    activeVersion,
+                    null,
+                    result.instance
                 );
 
                 console.log('Terminal created:', terminal ? 'success' : 'failed');
@@ -3023,6 +3058,10 @@ async ensureTerminalManager() {
                     <div class="mcp-section" id="mcp-section">
                         <div class="mcp-header">
                             <span class="mcp-title">MCP Connections</span>
+                            <button class="network-action-btn mini-btn" id="mcp-import-btn" type="button">
+                                <span class="material-icons">upload_file</span>
+                                Import JSON
+                            </button>
                             <button class="network-action-btn mini-btn" id="mcp-refresh-btn" type="button">
                                 <span class="material-icons">refresh</span>
                                 Refresh
@@ -3093,6 +3132,24 @@ async ensureTerminalManager() {
                                 <button class="network-action-btn activate-btn" id="mcp-add-header-btn" type="button">Add Header</button>
                             </div>
 
+                            <div class="network-config-section">
+                                <div class="network-config-label">Authentication</div>
+                                <select class="network-config-input" id="mcp-auth-kind">
+                                    <option value="none">Headers only</option>
+                                    <option value="bearer">Bearer token</option>
+                                    <option value="oauth_device">OAuth (device code)</option>
+                                </select>
+                                <div id="mcp-auth-bearer-field" style="margin-top: 6px;">
+                                    <input type="password" class="network-config-input" id="mcp-auth-token" placeholder="Token, kept in the system keyring (blank keeps the saved one)">
+                                </div>
+                                <div id="mcp-auth-oauth-field" style="margin-top: 6px; display: flex; flex-direction: column; gap: 6px;">
+                                    <input type="text" class="network-config-input" id="mcp-auth-device-url" placeholder="Device authorization URL">
+                                    <input type="text" class="network-config-input" id="mcp-auth-token-url" placeholder="Token URL">
+                                    <input type="text" class="network-config-input" id="mcp-auth-client-id" placeholder="Client ID">
+                                    <input type="text" class="network-config-input" id="mcp-auth-scope" placeholder="Scope (optional)">
+                                </div>
+                            </div>
+
                             <div class="network-config-section">
                                 <div class="network-config-label">Timeout (seconds)</div>
                                 <input type="number" class="network-config-input" id="mcp-timeout" value="10" min="1" max="3600">
@@ -3192,7 +3249,7 @@ async ensureTerminalManager() {
         }
 
         // Check if there's already a terminal for this model
-        const existingTerminal = terminalManager.getExistingTerminal ? terminalManager.getExistingTerminal(modelPath) : null;
+        const existingTerminal = terminalManager.getExistingTerminal ? terminalManager.getExistingTerminal(modelPath, presetId) : null;
         if (existingTerminal) {
             const [windowId] = existingTerminal;
             const window = this.windows.get(windowId);
@@ -3218,6 +3275,7 @@ async ensureTerminalManager() {
 
             if (result.success) {
                 this.setLastUsedModel({ modelPath, modelName, launchMode: 'preset', presetId });
+                this.notifyStoppedForExclusive(result);
                 console.log('Opening server terminal...');
 
                 // Get active llama.cpp version
@@ -3250,7 +3308,8 @@ async ensureTerminalManager() {
                     result.server_port,
                     modelPath,
                     activeVersion,
-                    presetArgs  // Pass the preset arguments
+                    presetArgs,  // Pass the preset arguments
+                    result.instance
                 );
 
                 if (!terminal) {
@@ -4521,6 +4580,23 @@ async ensureTerminalManager() {
         }
     }
 
+    async loadTerminalOptions() {
+        const list = document.getElementById('external-terminal-options');
+        if (!list) return;
+        try {
+            const result = await invoke('get_terminal_options');
+            list.innerHTML = '';
+            (result.options || []).forEach(option => {
+                const item = document.createElement('option');
+                item.value = option.template;
+                item.label = option.installed ? option.name : `${option.name} (not found)`;
+                list.appendChild(item);
+            });
+        } catch (error) {
+            console.error('Error loading terminal options:', error);
+        }
+    }
+
     async saveConfiguration(refreshModels = false) {
         const modelsDir = document.getElementById('models-directory').value;
         const execFolder = document.getElementById('executable-folder').value;
@@ -4551,6 +4627,11 @@ async ensureTerminalManager() {
                 themeIsSynced: themeIsSynced
             });
 
+            const externalTerminal = document.getElementById('external-terminal');
+            if (result.success && externalTerminal) {
+                await invoke('set_external_terminal', { template: externalTerminal.value });
+            }
+
             if (result.success) {
                 this.showNotification('Configuration saved!', 'success');
                 this.applyTheme(themeColor, backgroundColor);
@@ -5696,6 +5777,190 @@ async enableDiscovery(port, apiPort, name, chatPort) {
         }
     }
 
+    notifyStoppedForExclusive(result) {
+        const stopped = result.stopped_processes || [];
+        if (stopped.length > 0) {
+            this.showNotification(`Exclusive launch: stopped ${stopped.length} other model${stopped.length === 1 ? '' : 's'}`, 'info');
+        }
+    }
+
+    // Tell the user why a queued launch is waiting and when it finally starts
+    initLaunchQueueListener() {
+        if (this.launchQueueListenerInitialized || !window.__TAURI__ || !window.__TAURI__.event) {
+            return;
+        }
+        this.launchQueueStatuses = new Map();
+        window.__TAURI__.event.listen('launch-queue-updated', (event) => {
+            const { entries = [], positions = [] } = event.payload || {};
+            entries.forEach((entry) => {
+                const previous = this.launchQueueStatuses.get(entry.id);
+                if (previous === entry.status) return;
+                this.launchQueueStatuses.set(entry.id, entry.status);
+
+                const modelName = (entry.model_path || '').split(/[\\/]/).pop();
+                if (entry.status === 'queued' && !previous) {
+                    const position = positions.indexOf(entry.id) + 1;
+                    this.showNotification(`${modelName} queued (#${position}): ${entry.reason}`, 'info');
+                } else if (entry.status === 'launched') {
+                    this.showNotification(`Queued model ${modelName} started`, 'success');
+                } else if (entry.status === 'failed') {
+                    this.showNotification(`Queued launch of ${modelName} failed: ${entry.error || 'unknown error'}`, 'error');
+                }
+            });
+        });
+        this.launchQueueListenerInitialized = true;
+    }
+
+    // Device-code sign-ins finish in the background; report how they ended
+    initMcpOauthListener() {
+        if (this.mcpOauthListenerInitialized || !window.__TAURI__ || !window.__TAURI__.event) {
+            return;
+        }
+        window.__TAURI__.event.listen('mcp-oauth-status', (event) => {
+            const { status, message } = event.payload || {};
+            if (status === 'authorized') {
+                this.showNotification('MCP sign-in complete', 'success');
+                this.loadMcpConnections();
+            } else {
+                this.showNotification(`MCP sign-in ${status || 'failed'}: ${message || 'unknown error'}`, 'error');
+            }
+        });
+        this.mcpOauthListenerInitialized = true;
+    }
+
+    // Background health checks report servers going up or down
+    initMcpStatusListener() {
+        if (this.mcpStatusListenerInitialized || !window.__TAURI__ || !window.__TAURI__.event) {
+            return;
+        }
+        window.__TAURI__.event.listen('mcp-status-changed', (event) => {
+            const { id, name, status, previous, message, uptime_percent } = event.payload || {};
+            const connection = (this.mcpConnections || []).find((item) => item.id === id);
+            if (connection) {
+                connection.last_test_status = status === 'up' ? 'ok' : 'error';
+                connection.last_test_message = message;
+                if (!this.mcpHealth) this.mcpHealth = new Map();
+                const health = this.mcpHealth.get(id) || { connection_id: id };
+                this.mcpHealth.set(id, { ...health, status, uptime_percent });
+                this.renderMcpConnections();
+            }
+            if (previous) {
+                this.showNotification(`MCP server ${name} is ${status === 'up' ? 'back up' : `down: ${message}`}`, status === 'up' ? 'success' : 'warning');
+            }
+        });
+        this.mcpStatusListenerInitialized = true;
+    }
+
+    // The backend marks a launch Running once /health answers; mirror that in its terminal
+    initModelReadyListener() {
+        if (this.modelReadyListenerInitialized || !window.__TAURI__ || !window.__TAURI__.event) {
+            return;
+        }
+        window.__TAURI__.event.listen('model-ready', (event) => {
+            const { process_id, model_name, load_ms } = event.payload || {};
+            if (typeof terminalManager !== 'undefined' && terminalManager) {
+                terminalManager.terminals.forEach((terminalInfo, windowId) => {
+                    if (terminalInfo.processId === process_id && terminalInfo.status === 'starting') {
+                        terminalManager.updateServerStatus(windowId, 'running');
+                        terminalManager.refreshChatIframe(windowId);
+                    }
+                });
+            }
+            this.showNotification(`${model_name} is ready (${((load_ms || 0) / 1000).toFixed(1)}s)`, 'success');
+        });
+        window.__TAURI__.event.listen('model-restarting', (event) => {
+            const { process_id, model_name, exit_code, attempt, max_retries, delay_ms } = event.payload || {};
+            if (typeof terminalManager !== 'undefined' && terminalManager) {
+                terminalManager.terminals.forEach((terminalInfo, windowId) => {
+                    if (terminalInfo.processId === process_id) {
+                        terminalManager.updateServerStatus(windowId, 'starting');
+                    }
+                });
+            }
+            this.showNotification(`${model_name} exited (code ${exit_code}); restarting in ${Math.round((delay_ms || 0) / 1000)}s (${attempt}/${max_retries})`, 'warning');
+        });
+        this.modelReadyListenerInitialized = true;
+    }
+
+    // GGUF files dropped onto the window from the OS are added to the library;
+    // dropped .json files are installed as Arandu packs
+    initExternalModelDrop() {
+        if (this.externalModelDropInitialized || !window.__TAURI__ || !window.__TAURI__.event) {
+            return;
+        }
+        window.__TAURI__.event.listen('tauri://drag-drop', async (event) => {
+            const dropped = (event.payload && event.payload.paths) || [];
+            for (const manifest of dropped.filter((p) => p.toLowerCase().endsWith('.json'))) {
+                await this.installModelPack(manifest);
+            }
+            const paths = dropped.filter((p) => p.toLowerCase().endsWith('.gguf'));
+            if (paths.length === 0) return;
+
+            if (!window.confirm(`Import ${paths.length} model file${paths.length === 1 ? '' : 's'} into your models directory?`)) {
+                return;
+            }
+            const copy = window.confirm('Copy the files into the models directory?\n\nOK copies them. Cancel links to them where they are.');
+            for (const path of paths) {
+                const fileName = path.split(/[\\/]/).pop();
+                try {
+                    await invoke('register_external_model', { path, copyOrLink: copy ? 'copy' : 'link' });
+                    this.showNotification(`Imported ${fileName}`, 'success');
+                } catch (error) {
+                    this.showNotification(`Failed to import ${fileName}: ${error}`, 'error');
+                }
+            }
+            await this.loadModels(false);
+        });
+        this.externalModelDropInitialized = true;
+    }
+
+    // One confirmation, then downloads start and the pack's prompts join the prompt library
+    async installModelPack(manifest) {
+        try {
+            const preview = await invoke('install_model_pack', { manifest });
+            const summary = preview.summary || {};
+            const models = (summary.models || []).map((m) => `  - ${m.repo} (${m.files.length} file${m.files.length === 1 ? '' : 's'})`);
+            // Arguments and environment come from the pack author; show them exactly as written
+            const launchSettings = (summary.models || []).flatMap((m) => [
+                m.custom_args ? `  - ${m.repo}: ${m.custom_args}` : '',
+                ...(m.presets || []).map((p) => [
+                    p.custom_args ? `  - ${m.repo} / ${p.name}: ${p.custom_args}` : '',
+                    ...(p.env_vars || []).map((env) => `  - ${m.repo} / ${p.name} env: ${env}`)
+                ].filter(Boolean).join('\n'))
+            ]).filter(Boolean);
+            const lines = [
+                `Install pack "${summary.name}"${summary.author ? ` by ${summary.author}` : ''}?`,
+                summary.description || '',
+                models.length ? `Downloads:\n${models.join('\n')}` : '',
+                launchSettings.length ? `${summary.sets_environment ? 'WARNING: this pack sets environment variables. ' : ''}Launch settings:\n${launchSettings.join('\n')}` : '',
+                (summary.prompts || []).length ? `Prompts: ${summary.prompts.join(', ')}` : ''
+            ].filter(Boolean);
+            if (!window.confirm(lines.join('\n\n'))) return;
+
+            const result = await invoke('install_model_pack', { manifest, confirmed: true });
+            const now = new Date().toISOString();
+            (result.prompts || []).forEach((prompt) => {
+                if (this.systemPromptOverrides.some((entry) => entry.name.toLowerCase() === prompt.name.toLowerCase())) return;
+                this.systemPromptOverrides.push({
+                    id: `prompt_${Date.now()}_${Math.random().toString(36).slice(2, 8)}`,
+                    name: prompt.name,
+                    prompt: prompt.prompt,
+                    createdAt: now,
+                    updatedAt: now
+                });
+            });
+            this.persistSystemPromptOverrideState();
+
+            if (result.errors && result.errors.length > 0) {
+                this.showNotification(`Pack "${result.name}" partly installed: ${result.errors.join('; ')}`, 'warning');
+            } else {
+                this.showNotification(`Installing pack "${result.name}": ${result.downloads.length} download(s) started`, 'success');
+            }
+        } catch (error) {
+            this.showNotification(`Failed to install pack: ${error}`, 'error');
+        }
+    }
+
     buildDiscoveredPeersSignature(peers) {
         const stablePeers = (peers || []).map((peer) => {
             const modelIds = (peer.models || []).map((m) => `${m.id || ''}|${m.path || ''}|${m.date || ''}`).sort();
@@ -7534,10 +7799,117 @@ async enableDiscovery(port, apiPort, name, chatPort) {
         addEnvBtn.addEventListener('click', () => this.addMcpPairRow('mcp-env-vars-list'));
         addHeaderBtn.addEventListener('click', () => this.addMcpPairRow('mcp-headers-list'));
         correctJsonBtn.addEventListener('click', () => this.correctMcpJsonWithCurrentAi());
+        this.getMcpElement('mcp-import-btn', root)?.addEventListener('click', () => this.importMcpServersFromJson());
 
         transportSelect.addEventListener('change', () => {
             this.refreshMcpTransportInputs();
         });
+        this.getMcpElement('mcp-auth-kind', root)?.addEventListener('change', () => this.refreshMcpAuthInputs());
+    }
+
+    async showMcpResourcesAndPrompts(id) {
+        let catalog;
+        try {
+            catalog = await invoke('refresh_mcp_resources_and_prompts', { id });
+        } catch (error) {
+            const message = (error && error.message) ? error.message : String(error || 'Unknown error');
+            await ModalDialog.showInfo({ title: 'MCP Resources Failed', message });
+            return;
+        }
+        await this.loadMcpConnections();
+
+        const resources = catalog.resources || [];
+        const prompts = catalog.prompts || [];
+        const muted = 'color: var(--theme-text-muted);';
+        const resourceRows = resources.map((resource, index) => `
+            <li style="margin-bottom: 4px;">
+                <button class="network-action-btn mini-btn mcp-resource-read" data-index="${index}" type="button">Read</button>
+                ${this.escapeHtml(resource.name || resource.uri)} <span style="${muted}">${this.escapeHtml(resource.uri)}</span>
+            </li>
+        `).join('') || `<li style="${muted}">None offered</li>`;
+        const promptRows = prompts.map((prompt) => {
+            const args = (prompt.arguments || []).map((arg) => this.escapeHtml(arg.name) + (arg.required ? '*' : '')).join(', ');
+            return `<li><strong>${this.escapeHtml(prompt.name)}</strong>${prompt.description ? ` - ${this.escapeHtml(prompt.description)}` : ''}${args ? ` <span style="${muted}">(${args})</span>` : ''}</li>`;
+        }).join('') || `<li style="${muted}">None offered</li>`;
+        const errors = (catalog.errors || []).map((error) => `<p style="margin-top: 0; ${muted}">${this.escapeHtml(error)}</p>`).join('');
+
+        const dialog = ModalDialog.showCustom({
+            title: 'MCP Resources & Prompts',
+            content: `
+                ${errors}
+                <h4 style="margin: 0 0 6px;">Resources</h4>
+                <ul style="margin-top: 0;">${resourceRows}</ul>
+                <h4 style="margin: 0 0 6px;">Prompts</h4>
+                <ul style="margin-top: 0;">${promptRows}</ul>
+                <pre id="mcp-resource-preview" style="display: none; max-height: 240px; overflow: auto; white-space: pre-wrap;"></pre>
+            `,
+            buttons: [{ text: 'Close', className: 'btn-secondary', action: () => 'close' }]
+        });
+
+        const preview = document.getElementById('mcp-resource-preview');
+        document.querySelectorAll('.mcp-resource-read').forEach((button) => {
+            button.addEventListener('click', async () => {
+                const resource = resources[Number(button.dataset.index)];
+                preview.style.display = 'block';
+                preview.textContent = 'Reading...';
+                try {
+                    const result = await invoke('read_mcp_resource', { id, uri: resource.uri });
+                    preview.textContent = result.text || JSON.stringify(result.contents, null, 2);
+                } catch (error) {
+                    preview.textContent = String(error);
+                }
+            });
+        });
+        await dialog;
+    }
+
+    async importMcpServersFromJson() {
+        const dialog = ModalDialog.showCustom({
+            title: 'Import MCP Servers',
+            content: `
+                <p style="margin-top: 0; color: var(--theme-text-muted);">Paste an <code>mcpServers</code> config from Claude Desktop, Cursor or VS Code.</p>
+                <textarea class="network-config-input mcp-json-textarea" id="mcp-import-json" rows="12" style="width: 100%;" placeholder='{"mcpServers": {"filesystem": {"command": "npx", "args": ["-y", "@modelcontextprotocol/server-filesystem"]}}}'></textarea>
+                <label style="display: flex; align-items: center; gap: 6px; margin-top: 8px;">
+                    <input type="checkbox" id="mcp-import-overwrite"> Replace connections with the same name
+                </label>
+            `,
+            buttons: [
+                { text: 'Cancel', className: 'btn-secondary', action: () => 'cancel' },
+                { text: 'Import', className: 'btn-primary', action: () => 'import' }
+            ]
+        });
+        // The dialog is detached on close, so keep the inputs now
+        const jsonInput = document.getElementById('mcp-import-json');
+        const overwriteInput = document.getElementById('mcp-import-overwrite');
+        if (await dialog !== 'import') {
+            return;
+        }
+
+        const json = (jsonInput?.value || '').trim();
+        if (!json) {
+            this.showNotification('Paste an MCP config first', 'info');
+            return;
+        }
+
+        try {
+            const results = await invoke('import_mcp_servers', { json, overwrite: !!overwriteInput?.checked });
+            const count = (status) => results.filter((r) => r.status === status).length;
+            const failed = results.filter((r) => r.status === 'error');
+            await this.loadMcpConnections();
+            this.showNotification(
+                `MCP import: ${count('imported')} added, ${count('updated')} updated, ${count('skipped')} skipped, ${failed.length} failed`,
+                failed.length ? 'warning' : 'success'
+            );
+            if (failed.length) {
+                await ModalDialog.showInfo({
+                    title: 'Some MCP servers were not imported',
+                    message: failed.map((r) => `${r.name}: ${r.message}`).join('\n')
+                });
+            }
+        } catch (error) {
+            const message = (error && error.message) ? error.message : String(error || 'Unknown error');
+            await ModalDialog.showInfo({ title: 'MCP Import Failed', message });
+        }
     }
 
     async correctMcpJsonWithCurrentAi() {
@@ -7575,10 +7947,14 @@ async enableDiscovery(port, apiPort, name, chatPort) {
             }
 
             const escapedJson = this.escapeHtml(correctedJson);
+            const fixes = Array.isArray(result.fixes) && result.fixes.length
+                ? `<p style="color: var(--theme-text-muted);">Changes: ${this.escapeHtml(result.fixes.join(', '))}</p>`
+                : '';
             const decision = await ModalDialog.showCustom({
                 title: 'AI JSON Correction Preview',
                 content: `
                     <p style="margin-top: 0; color: var(--theme-text-muted);">Review the corrected JSON and apply if it looks right.</p>
+                    ${fixes}
                     <textarea class="network-config-input mcp-json-textarea" rows="12" readonly style="width: 100%;">${escapedJson}</textarea>
                 `,
                 buttons: [
@@ -7620,10 +7996,69 @@ async enableDiscovery(port, apiPort, name, chatPort) {
         jsonField.style.display = isJson ? 'flex' : 'none';
     }
 
+    refreshMcpAuthInputs() {
+        const root = this.getMcpManagerScope();
+        const kind = this.getMcpElement('mcp-auth-kind', root)?.value || 'none';
+        const bearerField = this.getMcpElement('mcp-auth-bearer-field', root);
+        const oauthField = this.getMcpElement('mcp-auth-oauth-field', root);
+        if (bearerField) bearerField.style.display = kind === 'bearer' ? 'block' : 'none';
+        if (oauthField) oauthField.style.display = kind === 'oauth_device' ? 'flex' : 'none';
+    }
+
+    setMcpAuthInputs(auth = {}) {
+        const root = this.getMcpManagerScope();
+        const set = (id, value) => {
+            const input = this.getMcpElement(id, root);
+            if (input) input.value = value || '';
+        };
+        set('mcp-auth-kind', auth.kind || 'none');
+        set('mcp-auth-token', '');
+        set('mcp-auth-device-url', auth.device_authorization_url);
+        set('mcp-auth-token-url', auth.token_url);
+        set('mcp-auth-client-id', auth.client_id);
+        set('mcp-auth-scope', auth.scope);
+        this.refreshMcpAuthInputs();
+    }
+
+    collectMcpAuthInputs() {
+        const root = this.getMcpManagerScope();
+        const value = (id) => (this.getMcpElement(id, root)?.value || '').trim();
+        return {
+            kind: value('mcp-auth-kind') || 'none',
+            device_authorization_url: value('mcp-auth-device-url'),
+            token_url: value('mcp-auth-token-url'),
+            client_id: value('mcp-auth-client-id'),
+            scope: value('mcp-auth-scope')
+        };
+    }
+
+    async signInMcpConnection(id) {
+        try {
+            const code = await invoke('start_mcp_oauth', { id });
+            const link = code.verification_uri_complete || code.verification_uri;
+            if (link) {
+                window.open(link, '_blank');
+            }
+            await ModalDialog.showInfo({
+                title: 'Sign in to MCP server',
+                message: `Open ${this.escapeHtml(code.verification_uri)} and enter the code ${this.escapeHtml(code.user_code)}. Arandu finishes the sign-in once you approve it.`
+            });
+        } catch (error) {
+            const message = (error && error.message) ? error.message : String(error || 'Unknown error');
+            await ModalDialog.showInfo({ title: 'MCP Sign-in Failed', message });
+        }
+    }
+
     async loadMcpConnections() {
         try {
             const connections = await invoke('get_mcp_connections');
             this.mcpConnections = Array.isArray(connections) ? connections : [];
+            try {
+                const health = await invoke('get_mcp_health', { id: null });
+                this.mcpHealth = new Map((health || []).map((summary) => [summary.connection_id, summary]));
+            } catch (error) {
+                console.warn('Failed to load MCP health history:', error);
+            }
             this.renderMcpConnections();
         } catch (error) {
             console.error('Failed to load MCP connections:', error);
@@ -7679,6 +8114,15 @@ async enableDiscovery(port, apiPort, name, chatPort) {
                         <span class="material-icons">list</span>
                         MCP Tools
                     </button>
+                    ${connection.auth && connection.auth.kind === 'oauth_device' ? `
+                    <button class="network-action-btn activate-btn mcp-sign-in-btn" data-id="${connection.id}" type="button">
+                        <span class="material-icons">login</span>
+                        Sign in
+                    </button>` : ''}
+                    <button class="network-action-btn activate-btn mcp-catalog-btn" data-id="${connection.id}" type="button">
+                        <span class="material-icons">library_books</span>
+                        Resources
+                    </button>
                     <button class="network-action-btn activate-btn mcp-edit-btn" data-id="${connection.id}" type="button">
                         <span class="material-icons">edit</span>
                         Edit
@@ -7714,6 +8158,22 @@ async enableDiscovery(port, apiPort, name, chatPort) {
                 });
             }
 
+            const signInButton = entry.querySelector('.mcp-sign-in-btn');
+            if (signInButton) {
+                signInButton.addEventListener('click', (event) => {
+                    const target = event.currentTarget;
+                    this.signInMcpConnection(target.dataset.id);
+                });
+            }
+
+            const catalogButton = entry.querySelector('.mcp-catalog-btn');
+            if (catalogButton) {
+                catalogButton.addEventListener('click', (event) => {
+                    const target = event.currentTarget;
+                    this.showMcpResourcesAndPrompts(target.dataset.id);
+                });
+            }
+
             const editButton = entry.querySelector('.mcp-edit-btn');
             if (editButton) {
                 editButton.addEventListener('click', (event) => {
@@ -7753,6 +8213,15 @@ async enableDiscovery(port, apiPort, name, chatPort) {
     }
 
     getMcpStatusLabel(connection) {
+        const label = this.getMcpTestStatusLabel(connection);
+        const health = this.mcpHealth && this.mcpHealth.get(connection.id);
+        if (connection.enabled && health && typeof health.uptime_percent === 'number') {
+            label.text += ` • ${health.uptime_percent.toFixed(1)}% up (24h)`;
+        }
+        return label;
+    }
+
+    getMcpTestStatusLabel(connection) {
         if (!connection.enabled) {
             return {
                 text: 'Disabled',
@@ -8019,6 +8488,7 @@ async enableDiscovery(port, apiPort, name, chatPort) {
             this.setMcpArgsRowsFromList('mcp-args-list', []);
             this.setMcpObjectRowsFromMap('mcp-env-vars-list', {});
             this.setMcpObjectRowsFromMap('mcp-headers-list', {});
+            this.setMcpAuthInputs();
 
             this.refreshMcpTransportInputs();
             return;
@@ -8034,6 +8504,7 @@ async enableDiscovery(port, apiPort, name, chatPort) {
         this.setMcpArgsRowsFromList('mcp-args-list', connection.args || []);
         this.setMcpObjectRowsFromMap('mcp-env-vars-list', connection.env_vars || {});
         this.setMcpObjectRowsFromMap('mcp-headers-list', connection.headers || {});
+        this.setMcpAuthInputs(connection.auth || {});
         timeoutInput.value = String(connection.timeout_seconds || 10);
         enabledInput.checked = !!connection.enabled;
 
@@ -8260,6 +8731,7 @@ async enableDiscovery(port, apiPort, name, chatPort) {
             args,
             env_vars,
             headers,
+            auth: this.collectMcpAuthInputs(),
             timeout_seconds: timeoutSeconds,
             last_test_at: null,
             last_test_status: null,
@@ -8283,7 +8755,11 @@ async enableDiscovery(port, apiPort, name, chatPort) {
                 this.showNotification('Applied mcpServers JSON autofill and saved as Stdio connection', 'info');
             }
 
-            await invoke('save_mcp_connection', { connection });
+            const saved = await invoke('save_mcp_connection', { connection });
+            const token = (this.getMcpElement('mcp-auth-token', this.getMcpManagerScope())?.value || '').trim();
+            if (token && connection.auth.kind === 'bearer') {
+                await invoke('set_mcp_auth_token', { id: saved.id, token });
+            }
             this.showNotification('MCP connection saved', 'success');
             this.resetMcpForm();
             await this.loadMcpConnections();
@@ -8404,6 +8880,7 @@ async enableDiscovery(port, apiPort, name, chatPort) {
         toolsWindow.style.width = '760px';
         toolsWindow.style.height = '620px';
         toolsWindow.style.maxHeight = '85vh';
+        this.bindMcpToolSettings(toolsWindow, id);
 
         const refreshButton = toolsWindow.querySelector('.mcp-tools-refresh-btn');
         if (refreshButton) {
@@ -8500,10 +8977,19 @@ async enableDiscovery(port, apiPort, name, chatPort) {
         const outputSummary = this.getMcpSchemaSummary(outputSchema);
         const inputRaw = JSON.stringify(inputSchema || {});
         const outputRaw = JSON.stringify(outputSchema || {});
+        const enabled = !tool || tool.enabled !== false;
+        const presets = tool && tool.argument_presets && Object.keys(tool.argument_presets).length
+            ? JSON.stringify(tool.argument_presets, null, 2)
+            : '';
 
         return `
-            <div class="mcp-tool-item">
-                <div class="mcp-tool-item-name">${name}</div>
+            <div class="mcp-tool-item${enabled ? '' : ' disabled'}" data-tool="${name}">
+                <div class="mcp-tool-item-name">
+                    <label class="mcp-tool-item-toggle" title="Offer this tool to models">
+                        <input type="checkbox" class="mcp-tool-enabled-toggle" ${enabled ? 'checked' : ''}>
+                        ${name}
+                    </label>
+                </div>
                 <div class="mcp-tool-item-description">${description}</div>
                 <div class="mcp-tool-item-meta">
                     <div>Input: ${this.escapeHtml(inputSummary)}</div>
@@ -8513,10 +8999,68 @@ async enableDiscovery(port, apiPort, name, chatPort) {
                     <summary>Show schema</summary>
                     <pre>Input: ${this.escapeHtml(inputRaw)}\nOutput: ${this.escapeHtml(outputRaw)}</pre>
                 </details>
+                <details class="mcp-tool-item-details"${presets ? ' open' : ''}>
+                    <summary>Argument presets</summary>
+                    <textarea class="network-config-input mcp-tool-presets" rows="3" placeholder='{"path": "C:/Users/me/Documents"}'>${this.escapeHtml(presets)}</textarea>
+                    <button class="network-action-btn activate-btn mcp-tool-presets-save-btn" type="button">Save presets</button>
+                </details>
             </div>
         `;
     }
 
+    // Tool toggles and presets are saved as soon as they change
+    bindMcpToolSettings(toolsWindow, id) {
+        const list = toolsWindow.querySelector(`#mcp-tools-list-${id}`);
+        if (!list) return;
+
+        const save = async (item, argumentPresets) => {
+            const toolName = item.dataset.tool;
+            const enabled = item.querySelector('.mcp-tool-enabled-toggle').checked;
+            try {
+                const tool = await invoke('set_mcp_tool_settings', {
+                    id,
+                    toolName,
+                    enabled,
+                    argumentPresets: argumentPresets === undefined ? null : argumentPresets
+                });
+                const connection = this.mcpConnections.find((entry) => entry.id === id);
+                const index = connection && Array.isArray(connection.tools)
+                    ? connection.tools.findIndex((entry) => entry.name === tool.name)
+                    : -1;
+                if (index >= 0) connection.tools[index] = tool;
+                item.classList.toggle('disabled', !tool.enabled);
+                this.showNotification(`${tool.name} ${argumentPresets === undefined ? (tool.enabled ? 'enabled' : 'disabled') : 'presets saved'}`, 'success');
+            } catch (error) {
+                this.showNotification(`Failed to save tool settings: ${error.message || error}`, 'error');
+            }
+        };
+
+        list.addEventListener('change', (event) => {
+            const item = event.target.closest('.mcp-tool-item');
+            if (item && event.target.classList.contains('mcp-tool-enabled-toggle')) {
+                save(item);
+            }
+        });
+        list.addEventListener('click', (event) => {
+            const button = event.target.closest('.mcp-tool-presets-save-btn');
+            const item = button && button.closest('.mcp-tool-item');
+            if (!item) return;
+            const raw = item.querySelector('.mcp-tool-presets').value.trim();
+            let presets = {};
+            try {
+                presets = raw ? JSON.parse(raw) : {};
+            } catch (error) {
+                this.showNotification(`Presets must be valid JSON: ${error.message}`, 'error');
+                return;
+            }
+            if (!presets || typeof presets !== 'object' || Array.isArray(presets)) {
+                this.showNotification('Presets must be a JSON object of argument values', 'error');
+                return;
+            }
+            save(item, presets);
+        });
+    }
+
     async deleteMcpConnection(id) {
         if (!window.confirm('Delete this MCP connection?')) {
             return;
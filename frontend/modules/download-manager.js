@@ -95,6 +95,24 @@ class DownloadManager {
                    this.updateDownloadManagerIcon();
                });
                
+               // Downloads paused by the backend disk watchdog
+               window.__TAURI__.event.listen('disk-space-low', (event) => {
+                   const { mount_point, free_gb = 0, paused_downloads = [], candidates = [] } = event.payload || {};
+                   let message = `Low disk space on ${mount_point}: ${free_gb.toFixed(1)} GB free`;
+                   if (paused_downloads.length > 0) {
+                       message += `, ${paused_downloads.length} download(s) paused until space frees up`;
+                   }
+                   if (candidates.length > 0) {
+                       const names = candidates.slice(0, 3).map((c) => `${c.name} (${c.size_gb.toFixed(1)} GB, ${c.reason})`);
+                       message += `. Consider deleting: ${names.join(', ')}`;
+                   }
+                   console.warn('Disk space low:', event.payload);
+                   if (this.desktop) {
+                       this.desktop.showNotification(message, 'warning');
+                   }
+                   this.updateDownloadManagerIcon();
+               });
+               
                window.__TAURI__.event.listen('open-download-manager', () => {
                    console.log('Received open-download-manager event');
                    this.showDownloadManager();
@@ -364,6 +382,7 @@ class DownloadManager {
 
        const downloadsHTML = this.downloads.map(download => {
            const statusIcon = {
+               'Queued': '<span class="material-icons">schedule</span>',
                'Starting': '<span class="material-icons">hourglass_top</span>',
                 'Downloading': '<span class="material-icons">download</span>',
                 'Paused': '<span class="material-icons">pause</span>',
@@ -373,7 +392,7 @@ class DownloadManager {
                 'Cancelled': '<span class="material-icons">cancel</span>',
             }[download.status] || '<span class="material-icons">help</span>';
 
-            const isActiveDownload = download.status === 'Downloading' || download.status === 'Starting' || download.status === 'Paused' || download.status === 'Extracting';
+            const isActiveDownload = download.status === 'Queued' || download.status === 'Downloading' || download.status === 'Starting' || download.status === 'Paused' || download.status === 'Extracting';
 
             const progressBar = (isActiveDownload && download.status !== 'Failed') ? `
                 <div class="download-progress">
@@ -456,6 +475,12 @@ class DownloadManager {
                                                 <span class="material-icons">close</span>
                                             </button>
                                         `;
+                                    } else if (download.status === 'Queued') {
+                                        return `
+                                            <button class="download-cancel" onclick="downloadManager.cancelDownload('${download.id}')" title="Cancel download">
+                                                <span class="material-icons">close</span>
+                                            </button>
+                                        `;
                                     } else if (download.status === 'Paused') {
                                         return `
                                             <button class="download-resume" onclick="downloadManager.resumeDownload('${download.id}')" title="Resume download">
@@ -770,7 +770,7 @@ class HuggingFaceApp {
 
             // Display results
             this.displayModelInfo(modelInfo);
-            this.displayFilesList(files);
+            this.displayFilesList(files, modelId);
 
             // Set default destination
             await this.setDefaultDestination(modelId);
@@ -842,7 +842,7 @@ class HuggingFaceApp {
         container.style.display = 'block';
     }
 
-    displayFilesList(files) {
+    displayFilesList(files, modelId) {
         const window = this.desktop.windows.get(this.windowId);
         if (!window) return;
 
@@ -872,12 +872,56 @@ class HuggingFaceApp {
             </div>
         `).join('');
 
-        container.innerHTML = filesHtml;
+        // Reading the header costs a 2 MB range request, so it waits for a click
+        container.innerHTML = `
+            <div class="model-preview" id="hf-model-preview">
+                <button type="button" class="select-all-btn" id="hf-model-preview-btn">
+                    <span class="material-icons">memory</span> Show model details
+                </button>
+            </div>
+        ` + filesHtml;
+        container.querySelector('#hf-model-preview-btn')?.addEventListener('click', () => this.loadModelPreview(modelId));
 
         // Setup file selection listeners
         this.setupFileSelectionListeners();
     }
 
+    async loadModelPreview(modelId) {
+        const window = this.desktop.windows.get(this.windowId);
+        const slot = window?.querySelector('#hf-model-preview');
+        if (!slot) return;
+
+        slot.innerHTML = '<span class="material-icons spinning">refresh</span> Reading model details...';
+        try {
+            const preview = await this.getInvoke()('fetch_hf_model_preview', { modelId });
+            slot.outerHTML = this.renderModelPreview(preview) || '<div class="model-preview">No model details found</div>';
+        } catch (error) {
+            slot.textContent = `Failed to read model details: ${error}`;
+        }
+    }
+
+    renderModelPreview(preview) {
+        if (!preview) return '';
+
+        const facts = [];
+        if (preview.architecture) facts.push(`Architecture: ${this.escapeHtml(preview.architecture)}`);
+        if (preview.parameter_count) {
+            facts.push(`Parameters: ${(preview.parameter_count / 1e9).toFixed(2)}B`);
+        } else if (preview.size_label) {
+            facts.push(`Parameters: ${this.escapeHtml(preview.size_label)}`);
+        }
+        if (preview.layer_count) facts.push(`Layers: ${preview.layer_count}`);
+        if (preview.context_length) facts.push(`Context: ${this.formatNumber(preview.context_length)} tokens`);
+        if (facts.length === 0) return '';
+
+        return `
+            <div class="model-preview">
+                <span class="material-icons">memory</span>
+                ${facts.map(fact => `<span class="model-preview-fact">${fact}</span>`).join('')}
+            </div>
+        `;
+    }
+
     setupFileSelectionListeners() {
         const window = this.desktop.windows.get(this.windowId);
         if (!window) return;
@@ -1411,6 +1455,7 @@ ${models.map((model, index) => {
                                         <span class="stat-downloads" title="${this.formatNumber(model.downloads)} downloads">⬇ ${this.formatNumber(model.downloads)}</span>
                                         <span class="stat-likes" title="${this.formatNumber(model.likes)} likes">❤ ${this.formatNumber(model.likes)}</span>
                                         <span class="stat-updated" title="Last updated: ${model.lastModified || 'Unknown'}">${updatedText}</span>
+                                        ${model.vram_estimate_gb ? `<span class="stat-vram" title="Estimated VRAM for Q4_K_M at 4K context">~${model.vram_estimate_gb.toFixed(1)} GB VRAM</span>` : ''}
                                     </div>
                                     ${compareBadge}
                                 </div>
@@ -8,7 +8,7 @@ class LlamaCppReleasesManager {
         this.initTauriAPI();
 
         // UI state
-        this.hideOtherPlatforms = true; // default ON: emphasize Windows assets
+        this.hideOtherPlatforms = true; // default ON: emphasize assets built for this machine
         this.lastReleases = null; // cache latest fetched releases for re-rendering
         this.lastIkCudaInstallPath = null;
     }
@@ -52,6 +52,22 @@ class LlamaCppReleasesManager {
         return normalized.toLowerCase();
     }
 
+    renderVersionMetadata(metadata) {
+        if (!metadata) return '';
+
+        const facts = [];
+        if (metadata.tag_name) facts.push(this.escapeHtml(metadata.tag_name));
+        if (metadata.commit_sha) facts.push(`commit ${this.escapeHtml(metadata.commit_sha.slice(0, 7))}`);
+        if (metadata.published_at) facts.push(`released ${this.escapeHtml(metadata.published_at.slice(0, 10))}`);
+        if (metadata.installed_at) facts.push(`installed ${this.escapeHtml(metadata.installed_at.slice(0, 10))}`);
+
+        const notes = metadata.release_notes
+            ? `<details class="backend-changelog"><summary>Changelog</summary><pre>${this.escapeHtml(metadata.release_notes)}</pre></details>`
+            : '';
+
+        return `<div class="backend-meta">${facts.join(' &middot; ')}</div>${notes}`;
+    }
+
     escapeHtml(str) {
         if (str == null) return '';
         return String(str)
@@ -232,9 +248,12 @@ class LlamaCppReleasesManager {
                                             <span class="backend-type">${backendDisplay}</span>
                                         </div>
                                         <div class="backend-path">${v.path}</div>
+                                        ${this.renderVersionMetadata(v.metadata)}
                                     </div>
                                     <div class="backend-status">
                                         ${isActive ? '' : (v.has_server ? '' : '<span class="badge warn">Missing server</span>')}
+                                        ${v.is_backend_active ? `<span class="badge backend-active" title="Used by models that prefer ${backendDisplay}">${backendDisplay} active</span>` :
+                                            (isReady ? `<span class="badge activate" onclick="llamacppReleasesManager.setBackendActiveVersion('${backendType}', '${escapedPath}')" title="Use this build for models that prefer ${backendDisplay}">Use for ${backendDisplay}</span>` : '')}
                                     </div>
                                     <div class="backend-activate">
                                         ${isActive ? '<span class="badge active">Active</span>' : `
@@ -274,7 +293,8 @@ class LlamaCppReleasesManager {
             'rocm': 'ROCm',
             'vulkan': 'Vulkan',
             'opencl': 'OpenCL',
-            'metal': 'Metal'
+            'metal': 'Metal',
+            'sycl': 'SYCL'
         };
         return displayNames[backendType] || backendType.toUpperCase();
     }
@@ -291,6 +311,17 @@ class LlamaCppReleasesManager {
         }
     }
 
+    async setBackendActiveVersion(backendType, path) {
+        try {
+            const invoke = this.getInvoke();
+            if (!invoke) throw new Error('Tauri API not available');
+            await invoke('set_backend_active_llamacpp_version', { backendType, path });
+            this.loadInstalledVersions();
+        } catch (e) {
+            alert(`Failed to set backend version: ${e.message || e}`);
+        }
+    }
+
     async deleteVersion(path) {
         let confirmed = false;
         try {
@@ -317,6 +348,44 @@ class LlamaCppReleasesManager {
         }
     }
 
+    async pruneOldVersions() {
+        try {
+            const invoke = this.getInvoke();
+            if (!invoke) throw new Error('Tauri API not available');
+            const preview = await invoke('prune_llamacpp_versions', { dryRun: true, keepPerBackend: null });
+            const candidates = preview.removed || [];
+            if (candidates.length === 0) {
+                alert(`Nothing to clean up. Keeping the newest ${preview.keep_per_backend} build(s) per backend.`);
+                return;
+            }
+
+            const freedMb = (preview.freed_bytes / (1024 * 1024)).toFixed(0);
+            const names = candidates.map(c => c.name).join(', ');
+            let confirmed = false;
+            try {
+                confirmed = await ModalDialog.showConfirmation({
+                    title: 'Clean Up Old Builds',
+                    message: `Remove ${candidates.length} build(s) (${freedMb} MB): ${names}? The active build and builds pinned by a model are kept.`,
+                    confirmText: 'Remove',
+                    cancelText: 'Cancel',
+                    type: 'danger'
+                });
+            } catch (e) {
+                confirmed = confirm(`Remove ${candidates.length} old build(s) (${freedMb} MB)?`);
+            }
+            if (!confirmed) return;
+
+            const result = await invoke('prune_llamacpp_versions', { dryRun: false, keepPerBackend: preview.keep_per_backend });
+            if (result.errors && result.errors.length > 0) {
+                alert(`Some builds could not be removed:\n${result.errors.join('\n')}`);
+            }
+            this.loadInstalledVersions();
+            this.updateLatestInstalledBuildDisplay();
+        } catch (e) {
+            alert(`Failed to clean up versions: ${e.message || e}`);
+        }
+    }
+
     async installIkLlamaCppMainZip() {
         try {
             const invoke = this.getInvoke();
@@ -518,6 +587,31 @@ class LlamaCppReleasesManager {
         }
     }
 
+    // Warn before downloading a build the runtime probe says this machine cannot run
+    async confirmAssetCompatibility(invoke, name) {
+        let compat = null;
+        try {
+            compat = await invoke('check_llamacpp_asset_compatibility', { assetName: name });
+        } catch (e) {
+            console.warn('Compatibility check failed, continuing with download:', e);
+            return true;
+        }
+        if (!compat || compat.compatible) return true;
+
+        const reasons = (compat.warnings || []).join('\n') || 'This build does not match the detected hardware.';
+        try {
+            return await ModalDialog.showConfirmation({
+                title: 'Incompatible Build',
+                message: `${name} may not run on this machine:\n${reasons}\n\nDownload anyway?`,
+                confirmText: 'Download',
+                cancelText: 'Cancel',
+                type: 'warning'
+            });
+        } catch (e) {
+            return confirm(`${name} may not run on this machine:\n${reasons}\n\nDownload anyway?`);
+        }
+    }
+
     async handleAssetDownload(assetId, name, downloadUrl, size, tagName) {
         const asset = {
             id: assetId, // Pass the assetId
@@ -542,7 +636,8 @@ class LlamaCppReleasesManager {
         try {
             const invoke = this.getInvoke();
             if (!invoke) throw new Error('Tauri API not available');
-            await invoke('download_llamacpp_asset_to_version', { asset, versionFolder: versionFolder });
+            if (!(await this.confirmAssetCompatibility(invoke, name))) return;
+            await invoke('download_llamacpp_asset_to_version', { asset, versionFolder: versionFolder, tagName: tagName || null });
             console.log(`Started download of ${name} (${backendType}) to ${versionFolder}`);
             // Auto-switch to Installed tab only when the downloaded version becomes Ready
             this.autoSwitchWhenVersionReady(versionFolder);
@@ -757,8 +852,11 @@ class LlamaCppReleasesManager {
                         <button class="llamacpp-refresh" onclick="llamacppReleasesManager.refreshLlamaCppReleases()" title="Refresh Releases">
                             <span class="material-icons">refresh</span> Refresh Releases
                         </button>
+                        <button class="llamacpp-refresh" onclick="llamacppReleasesManager.pruneOldVersions()" title="Remove old builds, keeping the newest per backend">
+                            <span class="material-icons">cleaning_services</span> Clean Up
+                        </button>
                         <button class="llamacpp-refresh platform-toggle" id="llamacpp-platform-toggle-ctrl" style="display: none;" onclick="llamacppReleasesManager.togglePlatformFilter()" title="Toggle platform visibility">
-                            <span class="material-icons">layers</span> Windows only
+                            <span class="material-icons">layers</span> This platform
                         </button>
                     </div>
                 </div>
@@ -862,11 +960,11 @@ class LlamaCppReleasesManager {
         };
         const isMacAsset = (name) => /mac|darwin|osx|apple|macos/i.test(name);
         const isLinuxAsset = (name) => /linux|ubuntu|debian|arch|fedora/i.test(name);
-        const shouldShowAsset = (name) => {
-            if (!this.hideOtherPlatforms) return true;
-            // Default to Windows filter for this app
-            return isWindowsAsset(name);
-        };
+        // Backend-provided compatibility wins; the name heuristic covers older responses
+        const isPlatformAsset = (asset) => asset.compatibility
+            ? asset.compatibility.platform_match
+            : isWindowsAsset(asset.name || '');
+        const assetBackend = (asset) => asset.compatibility?.backend || 'other';
 
         const releasesHTML = releases.map(release => {
             const releaseDate = new Date(release.published_at).toLocaleDateString();
@@ -877,26 +975,47 @@ class LlamaCppReleasesManager {
             const installedBadge = isInstalled ? '<span class="badge installed">Installed</span>' : '';
 
             // Preserve expansion state by not altering release-item class outside
-            // Sort assets: preferred platform (Windows) first when filter is on; keep stable otherwise
+            // Sort assets: this platform first when filter is on, then grouped by backend
             const assetsSorted = [...release.assets].sort((a, b) => {
-                const aWin = isWindowsAsset(a.name || '');
-                const bWin = isWindowsAsset(b.name || '');
-                if (this.hideOtherPlatforms && aWin !== bWin) return aWin ? -1 : 1;
+                const aMatch = isPlatformAsset(a);
+                const bMatch = isPlatformAsset(b);
+                if (this.hideOtherPlatforms && aMatch !== bMatch) return aMatch ? -1 : 1;
+                const byBackend = assetBackend(a).localeCompare(assetBackend(b));
+                if (byBackend !== 0) return byBackend;
                 return String(a.name || '').localeCompare(String(b.name || ''));
             });
 
+            let lastGroup = null;
             const assetsHTML = assetsSorted
                 .map(asset => {
                     const name = asset.name || '';
                     const warnCuda = /cudart/i.test(name);
                     const warningHTML = warnCuda ? '<span class="asset-note" style="margin-left: 8px; color: rgba(255,255,255,0.6);">Required for CUDA</span>' : '';
-                    const isWin = isWindowsAsset(name);
-                    const grayClass = this.hideOtherPlatforms && !isWin ? ' dim-asset' : '';
+                    const compat = asset.compatibility;
+                    const isMatch = isPlatformAsset(asset);
+                    const incompatible = compat && compat.platform_match && !compat.compatible;
+                    const compatHTML = incompatible
+                        ? `<span class="asset-note asset-incompatible" title="${(compat.warnings || []).join('; ')}"><span class="material-icons">warning</span> ${(compat.warnings || [])[0] || 'Not supported on this machine'}</span>`
+                        : '';
+                    const recommendedHTML = compat && compat.recommended
+                        ? '<span class="asset-note asset-recommended"><span class="material-icons">verified</span> Recommended</span>'
+                        : '';
+                    const grayClass = this.hideOtherPlatforms && !isMatch ? ' dim-asset' : '';
+
+                    // Group header when the backend changes (only within this platform's assets)
+                    const group = `${isMatch}|${assetBackend(asset)}`;
+                    const groupHeader = compat && group !== lastGroup
+                        ? `<div class="asset-backend-group${grayClass}">${this.getBackendDisplayName(assetBackend(asset))}</div>`
+                        : '';
+                    lastGroup = group;
                     return `
-                        <div class="release-asset${grayClass}">
+                        ${groupHeader}
+                        <div class="release-asset${grayClass}${incompatible ? ' incompatible-asset' : ''}">
                             <div class="asset-info">
                                 <span class="asset-name">${name}</span>
                                 ${warningHTML}
+                                ${recommendedHTML}
+                                ${compatHTML}
                             </div>
                             <button class="asset-download" onclick="llamacppReleasesManager.handleAssetDownload(${asset.id}, '${name}', '${asset.download_url}', ${asset.size}, '${release.tag_name}')" title="Download ${name} (${this.formatFileSize(asset.size)})">
                                 <span class="material-icons">download</span> Download (${this.formatFileSize(asset.size)})
@@ -961,7 +1080,7 @@ class LlamaCppReleasesManager {
             <div class="releases-header">
                 <p>Found ${releases.length} llama.cpp releases</p>
                 <button class="platform-badge" id="llamacpp-platform-badge" onclick="llamacppReleasesManager.togglePlatformFilter()" title="Toggle platform visibility">
-                    ${this.hideOtherPlatforms ? 'Windows only' : 'All platforms'}
+                    ${this.hideOtherPlatforms ? 'This platform' : 'All platforms'}
                 </button>
             </div>
             ${releasesHTML}
@@ -989,11 +1108,11 @@ class LlamaCppReleasesManager {
         this.hideOtherPlatforms = !this.hideOtherPlatforms;
         const btn = document.getElementById('llamacpp-platform-toggle-ctrl');
         if (btn) {
-            btn.innerHTML = `<span class="material-icons">layers</span> ${this.hideOtherPlatforms ? 'Windows only' : 'All platforms'}`;
+            btn.innerHTML = `<span class="material-icons">layers</span> ${this.hideOtherPlatforms ? 'This platform' : 'All platforms'}`;
         }
         const badge = document.getElementById('llamacpp-platform-badge');
         if (badge) {
-            badge.textContent = this.hideOtherPlatforms ? 'Windows only' : 'All platforms';
+            badge.textContent = this.hideOtherPlatforms ? 'This platform' : 'All platforms';
         }
         if (this.lastReleases) {
             // Re-render with cached releases and newly fetched installed versions
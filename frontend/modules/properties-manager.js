@@ -169,6 +169,9 @@ class PropertiesManager {
                     <div class="properties-sidebar">
                         <div class="presets-header">
                             <h4>Presets</h4>
+                            <button class="preset-add-btn" onclick="propertiesManager.createLowRamPreset()" title="Add a CPU-only preset for low-RAM ARM boards (Raspberry Pi and similar)">
+                                <span class="material-icons">memory</span>
+                            </button>
                             <button class="preset-add-btn" onclick="propertiesManager.createNewPreset()" title="Create new preset">
                                 <span class="material-icons">add</span>
                             </button>
@@ -478,7 +481,7 @@ class PropertiesManager {
         }
     }
 
-    async createNewPreset() {
+    async createNewPreset(name = 'New Preset', customArgs = '', editName = true) {
         const activeWindow = document.querySelector('.properties-window:not(.hidden)');
         if (!activeWindow) return;
 
@@ -486,8 +489,6 @@ class PropertiesManager {
         if (!propertyGroup) return;
 
         const modelPath = atob(propertyGroup.dataset.modelPath);
-        // New presets should start empty
-        const customArgs = '';
 
         try {
             // Initialize working presets if not exists
@@ -499,7 +500,7 @@ class PropertiesManager {
 
             const preset = {
                 id: 'preset_' + Date.now(),
-                name: 'New Preset',
+                name: name,
                 custom_args: customArgs,
                 is_default: false
             };
@@ -530,7 +531,7 @@ class PropertiesManager {
 
                 // Start inline editing immediately
                 const presetNameElement = newPresetItem.querySelector('.preset-name');
-                if (presetNameElement) {
+                if (presetNameElement && editName) {
                     this.startInlineEdit(presetNameElement, preset.id, modelPath);
                 }
             }
@@ -541,6 +542,22 @@ class PropertiesManager {
         }
     }
 
+    // Threads and context come from the backend's look at this machine
+    async createLowRamPreset() {
+        const invoke = this.getInvoke();
+        if (!invoke) return;
+        try {
+            const profile = await invoke('get_low_ram_cpu_preset');
+            await this.createNewPreset(profile.name, profile.custom_args, false);
+            if (!profile.recommended) {
+                this.desktop.showNotification('Low-RAM CPU preset added; it is tuned for small ARM boards and leaves the GPU unused', 'info');
+            }
+        } catch (error) {
+            console.error('Error creating low-RAM preset:', error);
+            this.desktop.showNotification('Failed to create low-RAM preset: ' + error, 'error');
+        }
+    }
+
     async showPresetMenu(event, presetId) {
         event.stopPropagation();
 
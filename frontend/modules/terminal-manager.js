@@ -876,8 +876,9 @@ class TerminalManager {
 
             const safeConnections = [];
             for (const connection of enabledConnections) {
+                // Tools switched off in the MCP manager are never offered to the model
                 let tools = Array.isArray(connection.tools)
-                    ? connection.tools.map((tool) => ({
+                    ? connection.tools.filter((tool) => tool && tool.enabled !== false).map((tool) => ({
                         name: tool && tool.name ? String(tool.name) : '',
                         description: tool && tool.description ? String(tool.description) : '',
                         inputSchema: tool && tool.inputSchema ? tool.inputSchema : null,
@@ -893,7 +894,7 @@ class TerminalManager {
                     if (connectionId) {
                         const refreshResult = await invoke('list_mcp_tools', { id: connectionId });
                         if (refreshResult && Array.isArray(refreshResult.tools)) {
-                            tools = refreshResult.tools.map((tool) => ({
+                            tools = refreshResult.tools.filter((tool) => tool && tool.enabled !== false).map((tool) => ({
                                 name: tool && tool.name ? String(tool.name) : '',
                                 description: tool && tool.description ? String(tool.description) : '',
                                 inputSchema: tool && tool.inputSchema ? tool.inputSchema : null,
@@ -1372,7 +1373,7 @@ class TerminalManager {
         return this.invoke;
     }
 
-    async openServerTerminal(processId, modelName, host, port, modelPath, activeVersion, launchArgs = null) {
+    async openServerTerminal(processId, modelName, host, port, modelPath, activeVersion, launchArgs = null, instance = null) {
         let resolvedLaunchArgs = launchArgs;
 
         if (resolvedLaunchArgs === null || resolvedLaunchArgs === undefined) {
@@ -1419,6 +1420,13 @@ const content = `
                             <span class="server-details">${modelName} - <span class="clickable" style="cursor: pointer; text-decoration: underline;" onclick="terminalManager.openUrl('http://${host}:${port}')">${host}:${port}</span><button class="copy-link-btn" style="background: none; border: none; cursor: pointer; margin-left: 5px; padding: 0; font-size: 14px; vertical-align: middle;" onclick="terminalManager.copyToClipboard('http://${host}:${port}', this)" title="Copy link"><span class="material-icons" style="font-size: 14px; color: var(--theme-text-muted);">content_copy</span></button></span>
                             <div class="server-controls">
                                 <button class="server-btn auto-switch-btn ${this.autoSwitchEnabled ? 'active' : ''}" id="auto-switch-btn-${windowId}" onclick="terminalManager.toggleAutoSwitch('${windowId}')" title="${this.autoSwitchEnabled ? 'Auto-switch to chat: ON' : 'Auto-switch to chat: OFF'}"><span class="material-icons">${this.autoSwitchEnabled ? 'toggle_on' : 'toggle_off'}</span></button>
+                                <select class="server-log-filter" id="log-filter-${windowId}" onchange="terminalManager.setLogFilter('${windowId}', this.value)" title="Minimum log severity">
+                                    <option value="all">All logs</option>
+                                    <option value="warning">Warnings</option>
+                                    <option value="error">Errors</option>
+                                </select>
+                                <button class="server-btn apply-params-btn" id="apply-params-btn-${windowId}" style="display: none;" onclick="terminalManager.restartWithPendingChanges('${windowId}')" title="Restart to apply pending changes"><span class="material-icons">published_with_changes</span></button>
+                                <button class="server-btn suspend-btn" id="suspend-btn-${windowId}" onclick="terminalManager.toggleSuspend('${windowId}')" title="Pause model"><span class="material-icons">pause</span></button>
                                 <button class="server-btn stop-btn" id="stop-btn-${windowId}"><span class="material-icons">stop</span> Stop</button>
                             </div>
                         </div>
@@ -1438,7 +1446,9 @@ const content = `
         `;
 
         console.log('Calling desktop.createWindow...');
-        const window = this.desktop.createWindow(windowId, `Server - ${modelName} (Build: ${activeVersion})`, 'server-terminal-window', content);
+        // Only label instances once the same model runs more than once
+        const instanceSuffix = instance && (instance.instance > 1 || instance.preset_name) ? ` ${instance.label}` : '';
+        const window = this.desktop.createWindow(windowId, `Server - ${modelName}${instanceSuffix} (Build: ${activeVersion})`, 'server-terminal-window', content);
         console.log('Desktop.createWindow returned:', window);
 
         // Ensure the window is visible and not hidden
@@ -1528,7 +1538,9 @@ const content = `
             launchFailed: false,
             output: [], // Store terminal output lines
             activeVersion: activeVersion,
-            launchArgs: resolvedLaunchArgs // Store the actual arguments used for launch
+            launchArgs: resolvedLaunchArgs, // Store the actual arguments used for launch
+            presetId: instance ? instance.preset_id || null : null,
+            instance: instance ? instance.instance : 1
         });
         this.updateTerminalModelPresentation(windowId, modelName, modelPath);
 
@@ -1597,7 +1609,7 @@ const content = `
                 outputBuffer.forEach(line => {
                     if (line !== null && line !== undefined) {
                         const lineDiv = document.createElement('div');
-                        lineDiv.className = 'server-line';
+                        lineDiv.className = `server-line ${this.severityClassForLine(line.toString())}`;
                         // Handle special characters and escape sequences
                         lineDiv.textContent = line.toString();
                         fragment.appendChild(lineDiv);
@@ -1811,6 +1823,7 @@ const content = `
                     return;
                 }
                 console.log(`[TerminalManager] Server ${modelName} is healthy and responding.`);
+                this.refreshPendingChanges(windowId);
                 const lineDiv = document.createElement('div');
                 lineDiv.className = 'server-line server-success';
                 lineDiv.textContent = `Server is responding! Ready for chat.`;
@@ -2074,6 +2087,117 @@ const content = `
         }
     }
 
+    // Lines arrive tagged by the backend classifier ([ERROR], [WARN], [PROGRESS], ...)
+    severityClassForLine(line) {
+        if (line.startsWith('[ERROR]')) return 'sev-error server-error';
+        if (line.startsWith('[WARN]')) return 'sev-warning server-warning';
+        if (line.startsWith('[PROGRESS]')) return 'sev-progress server-progress';
+        return 'sev-info';
+    }
+
+    setLogFilter(windowId, level) {
+        const outputDiv = document.getElementById(`server-output-${windowId}`);
+        if (!outputDiv) return;
+        outputDiv.classList.remove('min-warning', 'min-error');
+        if (level === 'warning' || level === 'error') {
+            outputDiv.classList.add(`min-${level}`);
+        }
+    }
+
+    // Freeze or thaw the server process; the model stays loaded so resuming is instant
+    async toggleSuspend(windowId) {
+        const terminalInfo = this.terminals.get(windowId);
+        const invoke = this.getInvoke();
+        if (!terminalInfo || !invoke) return;
+
+        const button = document.getElementById(`suspend-btn-${windowId}`);
+        try {
+            if (terminalInfo.suspended) {
+                await invoke('resume_process', { processId: terminalInfo.processId });
+                terminalInfo.suspended = false;
+            } else {
+                await invoke('suspend_process', { processId: terminalInfo.processId, unload: false });
+                terminalInfo.suspended = true;
+            }
+            this.terminals.set(windowId, terminalInfo);
+            if (button) {
+                button.innerHTML = `<span class="material-icons">${terminalInfo.suspended ? 'play_arrow' : 'pause'}</span>`;
+                button.title = terminalInfo.suspended ? 'Resume model' : 'Pause model';
+                button.classList.toggle('active', terminalInfo.suspended);
+            }
+        } catch (error) {
+            console.error('Error toggling suspend:', error);
+            this.desktop.showNotification(`Failed to ${terminalInfo.suspended ? 'resume' : 'pause'} model: ${error}`, 'error');
+        }
+    }
+
+    // Show the apply button while context / GPU-layer changes wait for a restart
+    async refreshPendingChanges(windowId) {
+        const terminalInfo = this.terminals.get(windowId);
+        const invoke = this.getInvoke();
+        const button = document.getElementById(`apply-params-btn-${windowId}`);
+        if (!terminalInfo || !invoke || !button) return;
+
+        try {
+            const changes = await invoke('get_pending_changes', { processId: terminalInfo.processId });
+            button.style.display = changes.length > 0 ? '' : 'none';
+            button.title = changes.length > 0
+                ? `Restart to apply: ${changes.map(c => `${c.name} ${c.current ?? 'default'} → ${c.pending}`).join(', ')}`
+                : 'Restart to apply pending changes';
+        } catch (error) {
+            button.style.display = 'none';
+        }
+    }
+
+    async restartWithPendingChanges(windowId) {
+        const terminalInfo = this.terminals.get(windowId);
+        const invoke = this.getInvoke();
+        if (!terminalInfo || !invoke) return;
+
+        const button = document.getElementById(`apply-params-btn-${windowId}`);
+        if (button) button.disabled = true;
+        this.updateServerStatus(windowId, 'starting');
+
+        try {
+            const result = await invoke('restart_model_with_pending_changes', { processId: terminalInfo.processId });
+            terminalInfo.processId = result.process_id;
+            terminalInfo.host = result.server_host;
+            terminalInfo.port = result.server_port;
+            terminalInfo.status = 'running';
+            this.terminals.set(windowId, terminalInfo);
+
+            // The stop handler was bound to the old process id
+            const stopBtn = document.getElementById(`stop-btn-${windowId}`);
+            if (stopBtn) {
+                const newStopBtn = stopBtn.cloneNode(true);
+                stopBtn.parentNode.replaceChild(newStopBtn, stopBtn);
+                newStopBtn.addEventListener('click', () => {
+                    this.updateServerStatus(windowId, 'terminating');
+                    this.stopServer(result.process_id, windowId, terminalInfo.modelPath, terminalInfo.modelName);
+                });
+            }
+
+            const outputDiv = document.getElementById(`server-output-${windowId}`);
+            if (outputDiv) {
+                const separator = document.createElement('div');
+                separator.className = 'server-line server-separator';
+                separator.textContent = `--- Restarted with ${result.applied.map(c => `${c.name}=${c.pending}`).join(', ')} ---`;
+                outputDiv.appendChild(separator);
+                outputDiv.scrollTop = outputDiv.scrollHeight;
+            }
+
+            this.startServerOutputPolling(result.process_id, windowId);
+            this.checkServerHealth(windowId, result.server_host, result.server_port, terminalInfo.modelName);
+            this.desktop.showNotification(`${terminalInfo.modelName} restarted with new parameters`, 'success');
+        } catch (error) {
+            console.error('Error applying pending changes:', error);
+            this.desktop.showNotification(`Failed to apply changes: ${error}`, 'error');
+        } finally {
+            if (button) button.disabled = false;
+            this.refreshPendingChanges(windowId);
+        }
+    }
+
     async startServer(windowId, modelPath, modelName) {
         const terminalInfo = this.terminals.get(windowId);
         if (!terminalInfo) return;
@@ -2597,10 +2721,12 @@ async stopRemoteModel(host, port, processId) {
         }
     }
 
-    // Check for existing terminal for a model
-    getExistingTerminal(modelPath) {
+    // Check for existing terminal for a model. Passing a presetId (null for the model's
+    // own settings) only matches that instance, so other presets can run side by side.
+    getExistingTerminal(modelPath, presetId = undefined) {
         return Array.from(this.terminals.entries()).find(([windowId, terminalInfo]) =>
             terminalInfo.modelPath === modelPath
+            && (presetId === undefined || (terminalInfo.presetId || null) === presetId)
         );
     }
 
@@ -77,6 +77,9 @@ class TrackerApp {
                         <button class="tracker-btn" id="tracker-export">
                             <span class="material-icons">download</span> Export
                         </button>
+                        <button class="tracker-btn" id="tracker-classify" title="Sort models into use cases with the running local model">
+                            <span class="material-icons">category</span> Classify
+                        </button>
                     </div>
                 </div>
                 <div class="tracker-loading" id="tracker-loading">
@@ -121,6 +124,11 @@ class TrackerApp {
                                 <option value="audio">Audio</option>
                                 <option value="coding">Coding</option>
                                 <option value="multimodal">Multimodal</option>
+                                <option value="coder">Coder (classified)</option>
+                                <option value="roleplay">Roleplay (classified)</option>
+                                <option value="vision">Vision (classified)</option>
+                                <option value="embedding">Embedding (classified)</option>
+                                <option value="reasoning">Reasoning (classified)</option>
                             </select>
                         </div>
                         <div class="filter-group">
@@ -149,7 +157,7 @@ class TrackerApp {
                         </div>
                         <div class="filter-group">
                             <label>Quantization</label>
-                            <input type="text" id="filter-quant" placeholder="e.g., Q4, Q8, IQ2" class="filter-input">
+                            <input type="text" id="filter-quant" placeholder="e.g., Q4, Q4_K_M, IQ4_XS" class="filter-input" title="Exact for models whose files were listed; Q4 matches every Q4 variant">
                         </div>
                         <div class="filter-group">
                             <label>Sort By</label>
@@ -215,6 +223,11 @@ class TrackerApp {
             exportBtn.addEventListener('click', () => this.exportJson());
         }
 
+        const classifyBtn = document.getElementById('tracker-classify');
+        if (classifyBtn) {
+            classifyBtn.addEventListener('click', () => this.classifyModels());
+        }
+
         // Filter inputs
         const searchInput = document.getElementById('filter-search');
         if (searchInput) {
@@ -583,6 +596,29 @@ class TrackerApp {
         }
     }
 
+    async classifyModels() {
+        this.desktop.showNotification('Classifying tracker models with the running model...', 'info');
+        try {
+            const result = await window.__TAURI__.core.invoke('classify_tracker_models', { limit: 50 });
+            const failed = result.errors && result.errors.length ? `, ${result.errors.length} batch(es) failed` : '';
+            this.desktop.showNotification(`Classified ${result.classified} of ${result.pending} models with ${result.model}${failed}`, failed ? 'warning' : 'success');
+            this.applyFilters();
+        } catch (error) {
+            this.desktop.showNotification(`Classification failed: ${error}`, 'error');
+        }
+    }
+
+    async setCategory(modelId) {
+        const category = prompt('Category (coder, roleplay, vision, embedding, reasoning, general). Leave empty to clear:', '');
+        if (category === null) return;
+        try {
+            await window.__TAURI__.core.invoke('set_tracker_model_category', { modelId, category: category.trim() || null });
+            this.applyFilters();
+        } catch (error) {
+            this.desktop.showNotification(`${error}`, 'error');
+        }
+    }
+
     async exportJson() {
         try {
             const json = await window.__TAURI__.core.invoke('export_tracker_json');
@@ -683,19 +719,61 @@ class TrackerApp {
                     ${quantBadges}
                     ${backendBadges}
                     ${model.is_chinese ? '<span class="badge chinese">🇨🇳</span>' : ''}
+                    ${model.classification ? `<span class="badge category" title="${model.classification.source === 'manual' ? 'Set manually' : `${Math.round(model.classification.confidence * 100)}% confidence`}" onclick="trackerApp.setCategory('${this.escapeHtml(model.id)}')">${this.escapeHtml(model.classification.category)}</span>` : ''}
                 </div>
                 <div class="model-card-stats">
                     <span>⬇️ ${downloadsStr}</span>
                     <span>❤️ ${likesStr}</span>
                     <span>📅 ${model.last_updated ? new Date(model.last_updated).toLocaleDateString() : ''}</span>
                 </div>
+                <div class="model-card-files" style="display: none;"></div>
                 <div class="model-card-actions">
+                    <button class="btn-small" onclick="trackerApp.toggleFiles(this, '${this.escapeHtml(model.id)}')">Files</button>
                     <button class="btn-small" onclick="trackerApp.viewOnHF('${this.escapeHtml(model.id)}')">View on HF</button>
                 </div>
             </div>
         `;
     }
 
+    // Expanding a card fetches the repo's real GGUF list; the backend caches it so the
+    // quantization filter can match exact quants like IQ4_XS afterwards
+    async toggleFiles(button, modelId) {
+        const card = button.closest('.model-card');
+        const panel = card ? card.querySelector('.model-card-files') : null;
+        if (!panel) return;
+        if (panel.style.display !== 'none') {
+            panel.style.display = 'none';
+            return;
+        }
+
+        panel.style.display = 'block';
+        panel.innerHTML = '<div class="loading-placeholder">Loading files...</div>';
+        try {
+            const list = await window.__TAURI__.core.invoke('get_tracker_model_files', { modelId, refresh: false });
+            if (!list.quantizations.length) {
+                panel.innerHTML = '<div class="no-models">No GGUF files in this repo</div>';
+                return;
+            }
+            panel.innerHTML = list.quantizations.map(q => `
+                <div class="model-file-row">
+                    <span class="badge quant">${this.escapeHtml(q.name)}</span>
+                    <span>${(q.size_bytes / 1e9).toFixed(1)} GB${q.file_count > 1 ? ` (${q.file_count} parts)` : ''}</span>
+                    ${q.vram_estimate_gb ? `<span>~${q.vram_estimate_gb.toFixed(1)} GB VRAM</span>` : ''}
+                </div>
+            `).join('');
+
+            const badges = card.querySelector('.model-card-badges');
+            if (badges) {
+                badges.querySelectorAll('.badge.quant').forEach(b => b.remove());
+                badges.insertAdjacentHTML('afterbegin', list.quantizations.slice(0, 3)
+                    .map(q => `<span class="badge quant">${this.escapeHtml(q.name)}</span>`).join(''));
+            }
+        } catch (error) {
+            console.error('Error loading model files:', error);
+            panel.innerHTML = `<div class="no-models">Error: ${this.escapeHtml(String(error))}</div>`;
+        }
+    }
+
     async viewOnHF(modelId) {
         try {
             const url = `https://huggingface.co/${modelId}`;